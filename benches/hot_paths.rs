@@ -0,0 +1,61 @@
+//! Throughput benchmarks for the pipeline's hottest scanning code.
+//!
+//! The corpus repeats a section holding a table and footnoted prose, the mix
+//! where footnote reference detection and table separator recognition
+//! dominate profiles. Run with `cargo bench --bench hot_paths`; the budget
+//! these numbers are held to is described in `docs/developers-guide.md`.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use mdtablefix::{Options, convert_footnotes, process_stream_opts};
+
+/// Sections in the generated corpus.
+const SECTIONS: usize = 400;
+
+fn corpus() -> Vec<String> {
+    let section = [
+        "## Results",
+        "",
+        "| Metric | Before | After |",
+        "| :--- | ---: | :---: |",
+        "| latency | 12 | 9 |",
+        "| throughput | 140 | 310 |",
+        "",
+        "The change halves the median latency.1 It also removes a lock,2 which",
+        "matters under load: 3: the old path serialized writers. See the",
+        "appendix,4 and the raw data 5 for details; nothing else moved.",
+        "",
+    ];
+    let mut lines: Vec<String> = (0..SECTIONS)
+        .flat_map(|_| section.iter().map(ToString::to_string))
+        .collect();
+    lines.extend(
+        ["## Footnotes", ""]
+            .into_iter()
+            .map(str::to_string)
+            .chain((1..=5).map(|n| format!("{n}. Source number {n}."))),
+    );
+    lines
+}
+
+fn bench_hot_paths(c: &mut Criterion) {
+    let lines = corpus();
+    let bytes = lines.iter().map(|line| line.len() + 1).sum::<usize>();
+    let mut group = c.benchmark_group("hot_paths");
+    group.throughput(Throughput::Bytes(bytes as u64));
+    group.bench_function("convert_footnotes", |b| {
+        b.iter(|| convert_footnotes(black_box(&lines)));
+    });
+    let opts = Options {
+        footnotes: true,
+        ..Options::default()
+    };
+    group.bench_function("process_stream_opts", |b| {
+        b.iter(|| process_stream_opts(black_box(&lines), opts.clone()));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_hot_paths);
+criterion_main!(benches);
@@ -0,0 +1,204 @@
+//! Abbreviate table headers to keep wide tables within a width budget.
+//!
+//! Heavily constrained README tables often spend most of their width on long
+//! header labels. [`abbreviate_headers`] swaps header cells for caller-supplied
+//! abbreviations, such as `Description` → `Desc.`, but only for tables whose
+//! widest rendered line exceeds the budget. The table is then reflowed so the
+//! columns shrink to the new labels.
+//!
+//! Every abbreviated table is preceded by an
+//! `<!-- mdtablefix-headers: ... -->` comment holding the original header row,
+//! so the change can be reverted by copying that row back. Tables already
+//! preceded by such a comment are left alone, which keeps repeated runs stable.
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    fences::FenceState,
+    table::{is_separator_line, reflow_table, split_cells},
+};
+
+/// Comment prefix recording the original header row of an abbreviated table.
+const HEADERS_DIRECTIVE: &str = "<!-- mdtablefix-headers:";
+
+/// Replace header cells of over-wide tables using `abbreviations`.
+///
+/// Each pair maps a full header label to its abbreviation and matches whole,
+/// trimmed cells exactly. Tables whose widest line fits within `max_width`
+/// display columns, tables inside fenced code, and tables whose headers have
+/// no matching label are returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::abbreviations::abbreviate_headers;
+///
+/// let lines: Vec<String> = [
+///     "| Name | Description |",
+///     "| ---- | ----------- |",
+///     "| a    | b           |",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// let map = [("Description".to_string(), "Desc.".to_string())];
+/// assert_eq!(
+///     abbreviate_headers(&lines, &map, 20),
+///     vec![
+///         "<!-- mdtablefix-headers: | Name | Description | -->",
+///         "| Name | Desc. |",
+///         "| ---- | ----- |",
+///         "| a    | b     |",
+///     ]
+/// );
+/// assert_eq!(abbreviate_headers(&lines, &map, 80), lines);
+/// ```
+#[must_use]
+pub fn abbreviate_headers(
+    lines: &[String],
+    abbreviations: &[(String, String)],
+    max_width: usize,
+) -> Vec<String> {
+    if abbreviations.is_empty() {
+        return lines.to_vec();
+    }
+    let mut fences = FenceState::default();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut table: Vec<String> = Vec::new();
+    for line in lines {
+        let fence = fences.observe_source_line(line);
+        if !fence.is_fence_marker && !fence.is_in_fence && is_table_line(line) {
+            table.push(line.clone());
+            continue;
+        }
+        flush_table(&mut out, &mut table, abbreviations, max_width);
+        out.push(line.clone());
+    }
+    flush_table(&mut out, &mut table, abbreviations, max_width);
+    out
+}
+
+fn is_table_line(line: &str) -> bool { line.trim_start().starts_with('|') }
+
+fn flush_table(
+    out: &mut Vec<String>,
+    table: &mut Vec<String>,
+    abbreviations: &[(String, String)],
+    max_width: usize,
+) {
+    if table.is_empty() {
+        return;
+    }
+    let lines = std::mem::take(table);
+    let already_abbreviated = out
+        .last()
+        .is_some_and(|prev| prev.trim_start().starts_with(HEADERS_DIRECTIVE));
+    match (!already_abbreviated)
+        .then(|| abbreviate_table(&lines, abbreviations, max_width))
+        .flatten()
+    {
+        Some(rewritten) => out.extend(rewritten),
+        None => out.extend(lines),
+    }
+}
+
+/// Returns the directive comment followed by the abbreviated table, or `None`
+/// when the table fits or no header matches.
+fn abbreviate_table(
+    lines: &[String],
+    abbreviations: &[(String, String)],
+    max_width: usize,
+) -> Option<Vec<String>> {
+    let [header, separator, ..] = lines else {
+        return None;
+    };
+    if !is_separator_line(separator.trim()) {
+        return None;
+    }
+    let widest = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+    if widest <= max_width {
+        return None;
+    }
+    let original = split_cells(header);
+    let abbreviated: Vec<String> = original
+        .iter()
+        .map(|cell| {
+            abbreviations
+                .iter()
+                .find(|(full, _)| full == cell)
+                .map_or_else(|| cell.clone(), |(_, short)| short.clone())
+        })
+        .collect();
+    if abbreviated == original {
+        return None;
+    }
+    let indent = &header[..header.len() - header.trim_start().len()];
+    let mut rewritten = lines.to_vec();
+    rewritten[0] = format!("{indent}{}", header_row(&abbreviated));
+    let mut result = vec![format!(
+        "{indent}{HEADERS_DIRECTIVE} {} -->",
+        header_row(&original)
+    )];
+    result.extend(reflow_table(&rewritten));
+    Some(result)
+}
+
+/// Renders unpadded cells as a table row, re-escaping literal pipes.
+fn header_row(cells: &[String]) -> String {
+    let escaped: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+    format!("| {} |", escaped.join(" | "))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for header abbreviation.
+
+    use super::*;
+    use crate::test_support::lines;
+
+    fn map() -> Vec<(String, String)> {
+        vec![
+            ("Description".into(), "Desc.".into()),
+            ("Identifier".into(), "ID".into()),
+        ]
+    }
+
+    #[test]
+    fn abbreviates_only_over_wide_tables() {
+        let input =
+            lines("| Identifier | Description | Notes |\n| --- | --- | --- |\n| 1 | a \\| b | c |");
+        let out = abbreviate_headers(&input, &map(), 30);
+        assert_eq!(
+            out,
+            lines(
+                "<!-- mdtablefix-headers: | Identifier | Description | Notes | -->\n\
+                 | ID  | Desc.  | Notes |\n\
+                 | --- | ------ | ----- |\n\
+                 | 1   | a \\| b | c     |"
+            )
+        );
+        assert_eq!(abbreviate_headers(&out, &map(), 30), out);
+        assert_eq!(abbreviate_headers(&input, &map(), 80), input);
+    }
+
+    #[test]
+    fn keeps_indentation_and_skips_fences() {
+        let input = lines(
+            "- item\n\n  | Description |\n  | --- |\n  | x |\n\n```\n| Description |\n| --- |\n```",
+        );
+        let out = abbreviate_headers(&input, &map(), 5);
+        assert_eq!(
+            out,
+            lines(
+                "- item\n\n  <!-- mdtablefix-headers: | Description | -->\n  | Desc. |\n  | ----- \
+                 |\n  | x     |\n\n```\n| Description |\n| --- |\n```"
+            )
+        );
+    }
+
+    #[test]
+    fn ignores_tables_without_matching_headers() {
+        let input = lines("| Name | Value |\n| --- | --- |\n| a | b |");
+        assert_eq!(abbreviate_headers(&input, &map(), 5), input);
+    }
+}
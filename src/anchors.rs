@@ -0,0 +1,199 @@
+//! Link targets defined by a Markdown document.
+//!
+//! [`collect_anchors`] lists every fragment a link such as `guide.md#setup`
+//! can point at: the slug of each heading, generated by [`heading_slug`] the
+//! way GitHub renders them, custom heading IDs written as `{#id}`, and the
+//! `id` or `name` attributes of inline HTML such as `<a id="top"></a>`.
+//! Headings and HTML inside code blocks and code spans are ignored.
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use regex::Regex;
+
+use crate::{
+    extract::{TextBlockKind, extract_text},
+    fences::FenceState,
+    frontmatter::split_leading_yaml_frontmatter,
+    wrap::{Token, markdown_tokens},
+};
+
+static CUSTOM_ID_RE: LazyLock<Regex> = lazy_regex!(
+    r"^(.*?)\s*\{#([^\s{}]+)\}$",
+    "custom heading id pattern should compile",
+);
+
+static HTML_ANCHOR_RE: LazyLock<Regex> = lazy_regex!(
+    r#"<[A-Za-z][A-Za-z0-9-]*\s[^>]*?\b(?:id|name)\s*=\s*(?:"([^"]*)"|'([^']*)')"#,
+    "HTML anchor attribute pattern should compile",
+);
+
+/// Where an [`Anchor`] comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnchorKind {
+    /// A heading, identified by its slug or its `{#id}` attribute.
+    Heading,
+    /// An `id` or `name` attribute on inline HTML.
+    Html,
+}
+
+/// A fragment identifier defined by a document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Anchor {
+    /// The fragment, without the leading `#`.
+    pub id: String,
+    /// What defines the fragment.
+    pub kind: AnchorKind,
+    /// One-based line number of the definition.
+    pub line: usize,
+}
+
+/// Returns the GitHub-style slug for heading text.
+///
+/// The text is lowercased, characters other than letters, digits, spaces,
+/// hyphens, and underscores are dropped, and each space becomes a hyphen.
+/// Pass plain text, as [`crate::extract::plain_text`] produces.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::anchors::heading_slug;
+///
+/// assert_eq!(heading_slug("Getting Started!"), "getting-started");
+/// assert_eq!(heading_slug("C++ & Rust: a_b"), "c--rust-a_b");
+/// ```
+#[must_use]
+pub fn heading_slug(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|ch| match ch {
+            ' ' => Some('-'),
+            '-' | '_' => Some(ch),
+            _ if ch.is_alphanumeric() => Some(ch),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Lists the anchors defined by a document, in line order.
+///
+/// Repeated heading slugs gain `-1`, `-2`, and so on, as on GitHub.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::anchors::{AnchorKind, collect_anchors};
+///
+/// let lines: Vec<String> = [
+///     "# Intro",
+///     "<a id=\"top\"></a>",
+///     "## Intro",
+///     "## Setup {#install}",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// let ids: Vec<_> = collect_anchors(&lines).into_iter().map(|a| a.id).collect();
+/// assert_eq!(ids, ["intro", "top", "intro-1", "install"]);
+/// ```
+#[must_use]
+pub fn collect_anchors(lines: &[String]) -> Vec<Anchor> {
+    let mut anchors = heading_anchors(lines);
+    anchors.extend(html_anchors(lines));
+    anchors.sort_by_key(|anchor| anchor.line);
+    anchors
+}
+
+fn heading_anchors(lines: &[String]) -> Vec<Anchor> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    extract_text(lines)
+        .into_iter()
+        .filter(|block| matches!(block.kind, TextBlockKind::Heading(_)))
+        .map(|block| {
+            let id = if let Some(caps) = CUSTOM_ID_RE.captures(&block.text) {
+                caps[2].to_string()
+            } else {
+                let slug = heading_slug(&block.text);
+                let count = seen.entry(slug.clone()).or_default();
+                *count += 1;
+                if *count == 1 {
+                    slug
+                } else {
+                    format!("{slug}-{}", *count - 1)
+                }
+            };
+            Anchor {
+                id,
+                kind: AnchorKind::Heading,
+                line: block.line,
+            }
+        })
+        .collect()
+}
+
+fn html_anchors(lines: &[String]) -> Vec<Anchor> {
+    let (frontmatter, body) = split_leading_yaml_frontmatter(lines);
+    let mut fences = FenceState::default();
+    let mut anchors = Vec::new();
+    for (index, line) in body.iter().enumerate() {
+        if fences.observe_source_line(line).is_code() || !line.contains('<') {
+            continue;
+        }
+        for token in markdown_tokens(line) {
+            let Token::Text(text) = token else {
+                continue;
+            };
+            anchors.extend(HTML_ANCHOR_RE.captures_iter(text).filter_map(|caps| {
+                let id = caps.get(1).or_else(|| caps.get(2))?.as_str();
+                (!id.is_empty()).then(|| Anchor {
+                    id: id.to_string(),
+                    kind: AnchorKind::Html,
+                    line: frontmatter.len() + index + 1,
+                })
+            }));
+        }
+    }
+    anchors
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for anchor collection.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::punctuation("What's new?", "whats-new")]
+    #[case::unicode("Überblick Ä", "überblick-ä")]
+    #[case::double_space("a  b", "a--b")]
+    #[case::underscores("snake_case-name", "snake_case-name")]
+    fn slugs_follow_github(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(heading_slug(text), expected);
+    }
+
+    #[test]
+    fn collects_headings_and_html_anchors() {
+        let doc = lines(
+            "---\ntitle: x\n---\nSetext `code` *title*\n===\n\n<span id='s'>x</span> <a \
+             name=\"n\"></a>\n\n```\n# Not a heading <a id=\"no\">\n```\n`<a id=\"no\">`\n## \
+             Heading <a id=\"h\"></a>",
+        );
+        let anchors: Vec<(String, AnchorKind, usize)> = collect_anchors(&doc)
+            .into_iter()
+            .map(|a| (a.id, a.kind, a.line))
+            .collect();
+        assert_eq!(
+            anchors,
+            [
+                ("setext-code-title".into(), AnchorKind::Heading, 4),
+                ("s".into(), AnchorKind::Html, 7),
+                ("n".into(), AnchorKind::Html, 7),
+                ("heading".into(), AnchorKind::Heading, 13),
+                ("h".into(), AnchorKind::Html, 13),
+            ]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Limits on runs of blank lines.
+//!
+//! markdownlint's MD012 rule flags consecutive blank lines, and MD041 wants
+//! the document to open with its content. [`limit_blank_lines`] collapses
+//! longer runs to a maximum and drops blank lines at the start and end of the
+//! document, while [`collapse_blank_lines`] only collapses, for callers that
+//! format part of a document. MD022 wants blank lines around headings, which
+//! [`space_headings`] sets to a fixed count. Fenced code is left untouched, as
+//! its blank lines are content.
+
+use std::iter;
+
+use regex::Regex;
+
+use crate::{fences::FenceState, frontmatter::split_leading_yaml_frontmatter};
+
+static HEADING_RE: std::sync::LazyLock<Regex> = lazy_regex!(
+    r"^[ ]{0,3}#{1,6}(?:\s|$)",
+    "ATX heading prefix pattern should compile",
+);
+
+/// How many blank lines [`space_headings`] puts around each heading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeadingSpacing {
+    /// Blank lines between a heading and the content above it.
+    pub before: usize,
+    /// Blank lines between a heading and the content below it.
+    pub after: usize,
+}
+
+impl Default for HeadingSpacing {
+    fn default() -> Self {
+        Self {
+            before: 1,
+            after: 1,
+        }
+    }
+}
+
+/// Shortens every run of more than `max` blank lines outside fenced code to
+/// `max` lines.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::blank_lines::collapse_blank_lines;
+///
+/// let lines: Vec<String> = ["", "a", "", "", "", "b"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(collapse_blank_lines(&lines, 1), ["", "a", "", "b"]);
+/// ```
+#[must_use]
+pub fn collapse_blank_lines(lines: &[String], max: usize) -> Vec<String> {
+    let mut fences = FenceState::default();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut run = 0;
+    for line in lines {
+        let in_code = fences.observe_source_line(line).is_code();
+        if in_code || !line.trim().is_empty() {
+            run = 0;
+            out.push(line.clone());
+            continue;
+        }
+        run += 1;
+        if run <= max {
+            out.push(line.clone());
+        }
+    }
+    out
+}
+
+/// Collapses runs of blank lines like [`collapse_blank_lines`] and removes
+/// the blank lines at the start and end of the document.
+///
+/// Leading YAML frontmatter is left as written. The blank lines between it
+/// and the body count as an ordinary run, so one of them is kept when `max`
+/// allows.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::blank_lines::limit_blank_lines;
+///
+/// let lines: Vec<String> = ["", "", "# Title", "", "", "Text.", ""]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(limit_blank_lines(&lines, 1), ["# Title", "", "Text."]);
+/// ```
+#[must_use]
+pub fn limit_blank_lines(lines: &[String], max: usize) -> Vec<String> {
+    let (frontmatter, body) = split_leading_yaml_frontmatter(lines);
+    let mut body = collapse_blank_lines(body, max);
+    while body.last().is_some_and(|line| line.trim().is_empty()) {
+        body.pop();
+    }
+    if frontmatter.is_empty() {
+        let leading = body
+            .iter()
+            .take_while(|line| line.trim().is_empty())
+            .count();
+        body.drain(..leading);
+    }
+    let mut out = frontmatter.to_vec();
+    out.extend(body);
+    out
+}
+
+/// Sets the blank lines around every ATX heading outside fenced code to the
+/// counts in `spacing`, inserting missing ones and removing extra ones.
+///
+/// Between two headings the larger of `after` and `before` applies. No blank
+/// lines are added before a heading that opens the lines or after one that
+/// ends them, and blank lines at either end are left as they are.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::blank_lines::{HeadingSpacing, space_headings};
+///
+/// let lines: Vec<String> = ["# Title", "Text.", "", "", "## Part", "More."]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     space_headings(&lines, HeadingSpacing::default()),
+///     ["# Title", "", "Text.", "", "## Part", "", "More."]
+/// );
+/// ```
+#[must_use]
+pub fn space_headings(lines: &[String], spacing: HeadingSpacing) -> Vec<String> {
+    let mut fences = FenceState::default();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut blanks = Vec::new();
+    // `None` until the first non-blank line, so leading blanks are kept.
+    let mut after_heading: Option<bool> = None;
+    for line in lines {
+        let in_code = fences.observe_source_line(line).is_code();
+        if !in_code && line.trim().is_empty() {
+            blanks.push(line.clone());
+            continue;
+        }
+        let heading = !in_code && HEADING_RE.is_match(line);
+        let wanted = after_heading.and_then(|previous| {
+            previous
+                .then_some(spacing.after)
+                .max(heading.then_some(spacing.before))
+        });
+        match wanted {
+            Some(count) => {
+                blanks.clear();
+                out.extend(iter::repeat_n(String::new(), count));
+            }
+            None => out.append(&mut blanks),
+        }
+        out.push(line.clone());
+        after_heading = Some(heading);
+    }
+    out.append(&mut blanks);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for blank line limits.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::collapses_to_max("a\n\n\n\nb", 2, "a\n\n\nb")]
+    #[case::zero_removes_all("a\n\nb", 0, "a\nb")]
+    #[case::keeps_fenced_blanks("```\n\n\n\n```\n\n\nb", 1, "```\n\n\n\n```\n\nb")]
+    fn collapses_runs(#[case] input: &str, #[case] max: usize, #[case] expected: &str) {
+        assert_eq!(collapse_blank_lines(&lines(input), max), lines(expected));
+    }
+
+    #[rstest]
+    #[case::heading_pair("# A\n## B\ntext", HeadingSpacing::default(), "# A\n\n## B\n\ntext")]
+    #[case::counts(
+        "text\n# A\n\n\n\ntext",
+        HeadingSpacing { before: 2, after: 0 },
+        "text\n\n\n# A\ntext"
+    )]
+    #[case::keeps_ends("\n# A\n", HeadingSpacing::default(), "\n# A\n")]
+    #[case::skips_fences(
+        "```\n# A\ntext\n```",
+        HeadingSpacing::default(),
+        "```\n# A\ntext\n```"
+    )]
+    #[case::not_a_heading(
+        "#tag\ntext\n    # code",
+        HeadingSpacing::default(),
+        "#tag\ntext\n    # code"
+    )]
+    fn spaces_headings(
+        #[case] input: &str,
+        #[case] spacing: HeadingSpacing,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(space_headings(&lines(input), spacing), lines(expected));
+    }
+
+    #[rstest]
+    #[case::trims_ends("\n\na\n\n", 1, "a")]
+    #[case::keeps_gap_after_frontmatter("---\nx: 1\n---\n\n\n# T\n", 1, "---\nx: 1\n---\n\n# T")]
+    #[case::only_blanks("\n\n", 1, "")]
+    fn limits_documents(#[case] input: &str, #[case] max: usize, #[case] expected: &str) {
+        let expected = if expected.is_empty() {
+            Vec::new()
+        } else {
+            lines(expected)
+        };
+        assert_eq!(limit_blank_lines(&lines(input), max), expected);
+    }
+}
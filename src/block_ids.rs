@@ -0,0 +1,247 @@
+//! Document-order IDs for tables, fenced code blocks, and headings.
+//!
+//! [`number_blocks`] gives each construct an ordinal within its kind, counted
+//! from the top of the document, so `table #3` names the third pipe table
+//! whatever line it starts on. Diagnostics and `--list-blocks` use these IDs
+//! so reports can point at a construct without quoting fragile line numbers.
+//! Tables and headings inside fenced code blocks are not counted, and YAML
+//! frontmatter is skipped.
+
+use std::fmt;
+
+use crate::{
+    extract::{TextBlockKind, extract_text},
+    fences::FenceState,
+    frontmatter::split_leading_yaml_frontmatter,
+    wrap::leading_indent,
+};
+
+/// The kinds of construct that receive an ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Construct {
+    /// A fenced code block, from its opening to its closing delimiter.
+    Fence,
+    /// An ATX or setext heading.
+    Heading,
+    /// A run of pipe-table lines, as the table reflow stage detects them.
+    Table,
+}
+
+impl Construct {
+    /// Returns the lowercase name used in diagnostics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::block_ids::Construct;
+    ///
+    /// assert_eq!(Construct::Fence.name(), "fence");
+    /// ```
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Fence => "fence",
+            Self::Heading => "heading",
+            Self::Table => "table",
+        }
+    }
+}
+
+/// A construct's kind and its one-based ordinal among constructs of that
+/// kind. It displays as `table #3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BlockId {
+    /// What the construct is.
+    pub kind: Construct,
+    /// One-based position among the document's constructs of `kind`.
+    pub ordinal: usize,
+}
+
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} #{}", self.kind.name(), self.ordinal)
+    }
+}
+
+/// A numbered construct and the lines it occupies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberedBlock {
+    /// The construct's ID.
+    pub id: BlockId,
+    /// One-based line number of the construct's first line.
+    pub start: usize,
+    /// One-based line number of the construct's last line.
+    pub end: usize,
+}
+
+/// Lists the tables, fences, and headings of a document in line order,
+/// numbering each kind separately.
+///
+/// A fence left open at the end of the document runs to its last line.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::block_ids::number_blocks;
+///
+/// let lines: Vec<String> = ["# Title", "", "| a |", "| 1 |", "", "```", "| b |", "```"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let ids: Vec<String> = number_blocks(&lines)
+///     .iter()
+///     .map(|block| format!("{}:{}-{}", block.id, block.start, block.end))
+///     .collect();
+/// assert_eq!(ids, ["heading #1:1-1", "table #1:3-4", "fence #1:6-8"]);
+/// ```
+#[must_use]
+pub fn number_blocks(lines: &[String]) -> Vec<NumberedBlock> {
+    let mut spans = heading_spans(lines);
+    spans.extend(code_and_table_spans(lines));
+    spans.sort_by_key(|&(_, start, _)| start);
+    let mut counts = [0; 3];
+    spans
+        .into_iter()
+        .map(|(kind, start, end)| {
+            let count = &mut counts[kind as usize];
+            *count += 1;
+            NumberedBlock {
+                id: BlockId {
+                    kind,
+                    ordinal: *count,
+                },
+                start,
+                end,
+            }
+        })
+        .collect()
+}
+
+/// Returns the ID of the construct covering one-based `line`, if any.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::block_ids::{block_at, number_blocks};
+///
+/// let lines: Vec<String> = ["```", "x", "```"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let blocks = number_blocks(&lines);
+/// assert_eq!(block_at(&blocks, 2).unwrap().to_string(), "fence #1");
+/// assert!(block_at(&blocks, 4).is_none());
+/// ```
+#[must_use]
+pub fn block_at(blocks: &[NumberedBlock], line: usize) -> Option<BlockId> {
+    blocks
+        .iter()
+        .find(|block| (block.start..=block.end).contains(&line))
+        .map(|block| block.id)
+}
+
+fn heading_spans(lines: &[String]) -> Vec<(Construct, usize, usize)> {
+    extract_text(lines)
+        .into_iter()
+        .filter(|block| matches!(block.kind, TextBlockKind::Heading(_)))
+        .map(|block| {
+            let underlined = lines
+                .get(block.line)
+                .is_some_and(|next| is_setext_underline(next));
+            (
+                Construct::Heading,
+                block.line,
+                block.line + usize::from(underlined),
+            )
+        })
+        .collect()
+}
+
+fn is_setext_underline(line: &str) -> bool {
+    let trimmed = line.trim();
+    leading_indent(line).0 < 4
+        && !trimmed.is_empty()
+        && (trimmed.bytes().all(|b| b == b'=') || trimmed.bytes().all(|b| b == b'-'))
+}
+
+fn is_table_line(line: &str) -> bool {
+    leading_indent(line).0 < 4 && line.trim_start().starts_with('|')
+}
+
+fn code_and_table_spans(lines: &[String]) -> Vec<(Construct, usize, usize)> {
+    let (frontmatter, body) = split_leading_yaml_frontmatter(lines);
+    let offset = frontmatter.len() + 1;
+    let mut fences = FenceState::default();
+    let mut spans = Vec::new();
+    let mut open: Option<(Construct, usize)> = None;
+    for (index, line) in body.iter().enumerate() {
+        let observed = fences.observe_source_line(line);
+        let line_no = offset + index;
+        if let Some((Construct::Table, start)) = open
+            && (observed.is_code() || !is_table_line(line))
+        {
+            spans.push((Construct::Table, start, line_no - 1));
+            open = None;
+        }
+        if observed.is_fence_marker && !observed.was_in_fence && observed.is_in_fence {
+            open = Some((Construct::Fence, line_no));
+        } else if observed.is_fence_marker && observed.was_in_fence && !observed.is_in_fence {
+            if let Some((kind, start)) = open.take() {
+                spans.push((kind, start, line_no));
+            }
+        } else if open.is_none() && !observed.is_code() && is_table_line(line) {
+            open = Some((Construct::Table, line_no));
+        }
+    }
+    if let Some((kind, start)) = open {
+        spans.push((kind, start, offset + body.len() - 1));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for block numbering.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    fn ids(text: &str) -> Vec<String> {
+        number_blocks(&lines(text))
+            .iter()
+            .map(|block| format!("{} {}-{}", block.id, block.start, block.end))
+            .collect()
+    }
+
+    #[test]
+    fn numbers_each_kind_separately() {
+        assert_eq!(
+            ids(
+                "Intro\n=====\n\n| a |\n|---|\n\n~~~\n| no |\n# no\n~~~\n\n## Next\n| b \
+                 |\n\n```\n| c |"
+            ),
+            [
+                "heading #1 1-2",
+                "table #1 4-5",
+                "fence #1 7-10",
+                "heading #2 12-12",
+                "table #2 13-13",
+                "fence #2 15-16",
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_lines_after_frontmatter() {
+        assert_eq!(ids("---\n| x |\n---\n| a |"), ["table #1 4-4"]);
+    }
+
+    #[rstest]
+    #[case::indented_code("    | a |")]
+    #[case::prose("a | b")]
+    fn ignores_non_tables(#[case] text: &str) {
+        assert!(ids(text).is_empty());
+    }
+}
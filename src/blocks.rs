@@ -0,0 +1,351 @@
+//! Per-transform exclusion of Markdown block types.
+//!
+//! Style guides often require some blocks to stay verbatim, such as quoted
+//! citations in footnote definitions. [`line_blocks`] assigns each line to
+//! the outermost block that contains it, using the same prefix classifier as
+//! wrapping, and [`BlockExclusions`] lists, per [`Transform`], the
+//! [`BlockType`]s whose lines that transform must leave alone.
+//!
+//! Exclusions apply to the transforms that rewrite lines in place without
+//! adding or removing any; see [`supports_exclusion`].
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    changes::Transform,
+    fences::FenceState,
+    wrap::{BlockKind, LinkReferenceMatcher, classify_block, leading_indent},
+};
+
+/// A Markdown block that transforms can be told to skip.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum BlockType {
+    /// A `>` blockquote, including lazy continuation lines.
+    Blockquote,
+    /// A `[^label]:` footnote definition and its continuation lines.
+    FootnoteDefinition,
+    /// An ATX heading.
+    Heading,
+    /// A bullet or ordered list item and its continuation lines.
+    ListItem,
+}
+
+impl BlockType {
+    const ALL: [Self; 4] = [
+        Self::Blockquote,
+        Self::FootnoteDefinition,
+        Self::Heading,
+        Self::ListItem,
+    ];
+
+    /// Returns the kebab-case name used in configuration files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::blocks::BlockType;
+    ///
+    /// assert_eq!(BlockType::FootnoteDefinition.name(), "footnote-definition");
+    /// ```
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Blockquote => "blockquote",
+            Self::FootnoteDefinition => "footnote-definition",
+            Self::Heading => "heading",
+            Self::ListItem => "list-item",
+        }
+    }
+
+    const fn bit(self) -> u8 { 1 << self as u8 }
+}
+
+impl fmt::Display for BlockType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.name()) }
+}
+
+impl FromStr for BlockType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|block| block.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown block type `{name}`; expected `blockquote`, `footnote-definition`, \
+                     `heading`, or `list-item`"
+                )
+            })
+    }
+}
+
+/// A set of [`BlockType`]s.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockTypes(u8);
+
+impl BlockTypes {
+    /// Returns `true` when `block` is in the set.
+    #[must_use]
+    pub const fn contains(self, block: BlockType) -> bool { self.0 & block.bit() != 0 }
+
+    /// Returns `true` when the set is empty.
+    #[must_use]
+    pub const fn is_empty(self) -> bool { self.0 == 0 }
+}
+
+impl FromIterator<BlockType> for BlockTypes {
+    fn from_iter<I: IntoIterator<Item = BlockType>>(blocks: I) -> Self {
+        Self(blocks.into_iter().fold(0, |bits, block| bits | block.bit()))
+    }
+}
+
+/// Returns `true` when [`BlockExclusions`] can restrict `transform`.
+///
+/// Only transforms that rewrite lines in place are supported: code spans,
+/// code emphasis, hard breaks, sentence spacing, and ellipsis replacement.
+#[must_use]
+pub const fn supports_exclusion(transform: Transform) -> bool {
+    matches!(
+        transform,
+        Transform::CodeSpans
+            | Transform::CodeEmphasis
+            | Transform::HardBreaks
+            | Transform::SentenceSpacing
+            | Transform::Ellipsis
+    )
+}
+
+/// The block types each transform must leave untouched.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{
+///     blocks::{BlockExclusions, BlockType},
+///     changes::Transform,
+///     process::{Options, process_stream_opts},
+/// };
+///
+/// let exclusions =
+///     BlockExclusions::default().with(Transform::Ellipsis, [BlockType::FootnoteDefinition]);
+/// let opts = Options {
+///     ellipsis: true,
+///     block_exclusions: exclusions,
+///     ..Options::default()
+/// };
+/// let lines = vec!["Wait...".to_string(), "[^1]: Quoted...".to_string()];
+/// assert_eq!(
+///     process_stream_opts(&lines, opts),
+///     ["Wait…", "[^1]: Quoted..."]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockExclusions([BlockTypes; Transform::ALL.len()]);
+
+impl BlockExclusions {
+    /// Adds `blocks` to the types `transform` skips.
+    ///
+    /// Exclusions for transforms rejected by [`supports_exclusion`] are kept
+    /// but have no effect.
+    #[must_use]
+    pub fn with(
+        mut self,
+        transform: Transform,
+        blocks: impl IntoIterator<Item = BlockType>,
+    ) -> Self {
+        let slot = &mut self.0[transform as usize];
+        *slot = BlockTypes(slot.0 | blocks.into_iter().collect::<BlockTypes>().0);
+        self
+    }
+
+    /// Returns the block types `transform` skips.
+    #[must_use]
+    pub const fn get(self, transform: Transform) -> BlockTypes { self.0[transform as usize] }
+
+    /// Returns `true` when no transform skips any block.
+    #[must_use]
+    pub fn is_empty(self) -> bool { self.0.iter().all(|blocks| blocks.is_empty()) }
+
+    /// Puts back the lines of excluded blocks that `transform` rewrote.
+    pub(crate) fn restore(
+        self,
+        transform: Transform,
+        before: &[String],
+        mut after: Vec<String>,
+    ) -> Vec<String> {
+        let excluded = self.get(transform);
+        if excluded.is_empty() || !supports_exclusion(transform) || before.len() != after.len() {
+            return after;
+        }
+        for (index, block) in line_blocks(before).into_iter().enumerate() {
+            if block.is_some_and(|block| excluded.contains(block)) {
+                after[index].clone_from(&before[index]);
+            }
+        }
+        after
+    }
+}
+
+/// Serializes as a map from each transform's name to the block types it
+/// skips, such as `{"ellipsis": ["blockquote"]}`, leaving out transforms that
+/// skip nothing.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BlockExclusions {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(Transform::ALL.into_iter().filter_map(|transform| {
+            let blocks = self.get(transform);
+            (!blocks.is_empty()).then(|| {
+                let listed: Vec<BlockType> = BlockType::ALL
+                    .into_iter()
+                    .filter(|block| blocks.contains(*block))
+                    .collect();
+                (transform, listed)
+            })
+        }))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BlockExclusions {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map =
+            std::collections::HashMap::<Transform, Vec<BlockType>>::deserialize(deserializer)?;
+        Ok(map
+            .into_iter()
+            .fold(Self::default(), |exclusions, (transform, blocks)| {
+                exclusions.with(transform, blocks)
+            }))
+    }
+}
+
+/// Returns the outermost block containing each line.
+///
+/// Blockquotes, footnote definitions, and list items extend over their lazy
+/// continuation lines and, for the latter two, over blank-separated lines
+/// indented by at least two columns. Headings cover their own line. Blocks
+/// nested inside an open footnote definition or list item belong to it.
+/// Lines of a fenced code block belong to the block the fence is indented
+/// under, if any.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::blocks::{BlockType, line_blocks};
+///
+/// let lines: Vec<String> = ["[^1]: Smith, 2020", "  p. 4", "", "Text"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     line_blocks(&lines),
+///     [
+///         Some(BlockType::FootnoteDefinition),
+///         Some(BlockType::FootnoteDefinition),
+///         Some(BlockType::FootnoteDefinition),
+///         None,
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn line_blocks(lines: &[String]) -> Vec<Option<BlockType>> {
+    let matcher = LinkReferenceMatcher::production();
+    let mut fences = FenceState::default();
+    let mut open: Option<BlockType> = None;
+    let mut after_blank = false;
+    lines
+        .iter()
+        .map(|line| {
+            let indent = leading_indent(line).0;
+            if fences.observe_source_line(line).is_code() {
+                if indent == 0 {
+                    open = None;
+                }
+                after_blank = false;
+                return open;
+            }
+            if line.trim().is_empty() {
+                after_blank = true;
+                return open;
+            }
+            let nested = indent >= 2
+                && matches!(
+                    open,
+                    Some(BlockType::FootnoteDefinition | BlockType::ListItem)
+                );
+            let started = match classify_block(line, matcher) {
+                Some(BlockKind::Heading) => Some(BlockType::Heading),
+                Some(BlockKind::Bullet) => Some(BlockType::ListItem),
+                Some(BlockKind::Blockquote) => Some(BlockType::Blockquote),
+                Some(BlockKind::FootnoteDefinition) => Some(BlockType::FootnoteDefinition),
+                _ => None,
+            };
+            open = match started {
+                _ if nested => open,
+                Some(block) => Some(block),
+                None if after_blank => None,
+                None => open.filter(|block| *block != BlockType::Heading),
+            };
+            after_blank = false;
+            open
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for block assignment and exclusion.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::lazy_blockquote("> a\nb\n\nc", &[Some(BlockType::Blockquote), Some(BlockType::Blockquote), Some(BlockType::Blockquote), None])]
+    #[case::heading_ends("# A\nb", &[Some(BlockType::Heading), None])]
+    #[case::nested_list("[^1]: a\n\n  - b\nc", &[Some(BlockType::FootnoteDefinition), Some(BlockType::FootnoteDefinition), Some(BlockType::FootnoteDefinition), Some(BlockType::FootnoteDefinition)])]
+    #[case::fence_closes("- a\n```\n# x\n```\nb", &[Some(BlockType::ListItem), None, None, None, None])]
+    #[case::indented_fence("- a\n\n  ```\n  # x\n  ```\n  b", &[Some(BlockType::ListItem); 6])]
+    fn assigns_lines_to_blocks(#[case] input: &str, #[case] expected: &[Option<BlockType>]) {
+        assert_eq!(line_blocks(&lines(input)), expected);
+    }
+
+    #[test]
+    fn restores_only_excluded_lines() {
+        let exclusions =
+            BlockExclusions::default().with(Transform::Ellipsis, [BlockType::Blockquote]);
+        let before = lines("> a...\nb...");
+        let after = lines("> a…\nb…");
+        assert_eq!(
+            exclusions.restore(Transform::Ellipsis, &before, after.clone()),
+            lines("> a...\nb...")
+        );
+        assert_eq!(
+            exclusions.restore(Transform::SentenceSpacing, &before, after.clone()),
+            after
+        );
+    }
+
+    #[test]
+    fn ignores_transforms_that_change_line_counts() {
+        let exclusions = BlockExclusions::default().with(Transform::Wrap, [BlockType::Heading]);
+        let before = lines("# a b");
+        let after = lines("# a…");
+        assert_eq!(
+            exclusions.restore(Transform::Wrap, &before, after.clone()),
+            after
+        );
+    }
+
+    #[test]
+    fn parses_block_names() {
+        assert_eq!("list-item".parse(), Ok(BlockType::ListItem));
+        assert!("table".parse::<BlockType>().is_err());
+    }
+}
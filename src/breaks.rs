@@ -4,7 +4,7 @@ use std::borrow::Cow;
 
 use regex::Regex;
 
-use crate::wrap::FenceTracker;
+use crate::fences::FenceState;
 
 pub const THEMATIC_BREAK_LEN: usize = 70;
 
@@ -45,7 +45,7 @@ static THEMATIC_BREAK_LINE: std::sync::LazyLock<String> =
 pub fn format_breaks(lines: &[String]) -> Vec<Cow<'_, str>> {
     let mut out = Vec::with_capacity(lines.len());
     // Track fenced code blocks consistently while formatting breaks.
-    let mut fences = FenceTracker::default();
+    let mut fences = FenceState::default();
 
     for line in lines {
         let fence = fences.observe_source_line(line);
@@ -0,0 +1,330 @@
+//! Change metadata recorded while the processing pipeline runs.
+//!
+//! Each pipeline stage hands its input and output to a [`ChangeLog`], which
+//! diffs the two line vectors and keeps one [`Change`] per contiguous hunk.
+//! Callers such as the CLI report writer use the log to explain which
+//! transform touched which lines without re-running the pipeline, and
+//! [`ChangeLog::edits`] restates the run as [`Edit`]s to the original
+//! document.
+
+mod edits;
+mod transform;
+
+use std::ops::Range;
+
+pub use edits::{Edit, apply_edits};
+use edits::{Origins, touches};
+use similar::{Algorithm, DiffTag, capture_diff_slices};
+pub use transform::Transform;
+
+/// One contiguous hunk rewritten by a single transform.
+///
+/// Line ranges are zero-based and half-open. `before_lines` indexes the
+/// document as the transform received it and `after_lines` indexes the
+/// document the transform produced, so ranges of later transforms refer to
+/// the output of earlier ones. `source_lines` locates the hunk in the first
+/// document the log recorded instead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The transform that rewrote the hunk.
+    pub transform: Transform,
+    /// Lines replaced in the transform's input.
+    pub before_lines: Range<usize>,
+    /// Lines emitted in the transform's output.
+    pub after_lines: Range<usize>,
+    /// Lines of the first document the log recorded that the replaced lines
+    /// derive from, or an empty range at the insertion point.
+    pub source_lines: Range<usize>,
+    /// Text of the replaced lines.
+    pub before: Vec<String>,
+    /// Text of the emitted lines.
+    pub after: Vec<String>,
+}
+
+/// Accumulates [`Change`] records for one document.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::changes::{ChangeLog, Transform};
+///
+/// let before = vec!["a".to_string(), "b".to_string()];
+/// let after = vec!["a".to_string(), "B".to_string()];
+/// let mut log = ChangeLog::default();
+/// log.record(Transform::Wrap, &before, &after);
+/// let change = &log.changes()[0];
+/// assert_eq!(change.before_lines, 1..2);
+/// assert_eq!(change.after, vec!["B".to_string()]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ChangeLog {
+    changes: Vec<Change>,
+    line_offset: usize,
+    origins: Origins,
+}
+
+impl ChangeLog {
+    /// Creates a log whose recorded ranges are shifted by `offset` lines.
+    ///
+    /// Use this when the pipeline runs on a suffix of the document, such as
+    /// the body that follows preserved YAML frontmatter.
+    #[must_use]
+    pub fn with_line_offset(offset: usize) -> Self {
+        Self {
+            changes: Vec::new(),
+            line_offset: offset,
+            origins: Origins::default(),
+        }
+    }
+
+    /// Returns the number of lines recorded ranges are shifted by.
+    #[must_use]
+    pub const fn line_offset(&self) -> usize { self.line_offset }
+
+    /// Diffs `before` against `after` and records each differing hunk.
+    pub fn record(&mut self, transform: Transform, before: &[String], after: &[String]) {
+        if before == after {
+            return;
+        }
+        let offset = self.line_offset;
+        let hunks = diff_hunks(before, after);
+        let spans = self.origins.advance(before.len(), after.len(), &hunks);
+        self.changes.extend(
+            hunks
+                .into_iter()
+                .zip(spans)
+                .map(|((old, new), span)| Change {
+                    transform,
+                    before: before[old.clone()].to_vec(),
+                    after: after[new.clone()].to_vec(),
+                    before_lines: old.start + offset..old.end + offset,
+                    after_lines: new.start + offset..new.end + offset,
+                    source_lines: span.start + offset..span.end + offset,
+                }),
+        );
+    }
+
+    /// Describes the recorded run as edits that turn `original`, the first
+    /// document recorded, into `output`, the last one produced.
+    ///
+    /// Each edit is one hunk of the difference between the two, shifted by
+    /// the log's line offset, and names every transform whose changes touch
+    /// it. Applying the edits with [`apply_edits`] reproduces `output`.
+    #[must_use]
+    pub fn edits(&self, original: &[String], output: &[String]) -> Vec<Edit> {
+        let offset = self.line_offset;
+        diff_hunks(original, output)
+            .into_iter()
+            .map(|(old, new)| {
+                let lines = old.start + offset..old.end + offset;
+                let mut transforms = Vec::new();
+                for change in &self.changes {
+                    if touches(&change.source_lines, &lines)
+                        && !transforms.contains(&change.transform)
+                    {
+                        transforms.push(change.transform);
+                    }
+                }
+                Edit {
+                    lines,
+                    replacement: output[new].to_vec(),
+                    transforms,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the recorded changes in pipeline order.
+    #[must_use]
+    pub fn changes(&self) -> &[Change] { &self.changes }
+
+    /// Totals the recorded changes; see [`ChangeSummary`].
+    #[must_use]
+    pub fn summary(&self) -> ChangeSummary { ChangeSummary::from_changes(&self.changes) }
+
+    /// Consumes the log and returns the recorded changes.
+    #[must_use]
+    pub fn into_changes(self) -> Vec<Change> { self.changes }
+}
+
+impl Extend<Change> for ChangeLog {
+    /// Appends changes recorded by another log, such as one covering a
+    /// section of the document with its own line offset.
+    fn extend<I: IntoIterator<Item = Change>>(&mut self, changes: I) {
+        self.changes.extend(changes);
+    }
+}
+
+/// Headline counts derived from a list of [`Change`] records.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::changes::{ChangeLog, ChangeSummary, Transform};
+///
+/// let lines = |items: &[&str]| items.iter().map(ToString::to_string).collect::<Vec<_>>();
+/// let mut log = ChangeLog::default();
+/// log.record(
+///     Transform::Tables,
+///     &lines(&["|a|", "", "|b|"]),
+///     &lines(&["| a |", "", "| b |"]),
+/// );
+/// log.record(Transform::Wrap, &lines(&["x y z"]), &lines(&["x y", "z"]));
+/// assert_eq!(
+///     log.summary(),
+///     ChangeSummary {
+///         changes: 3,
+///         tables_reflowed: 2,
+///         lines_wrapped: 1,
+///         paragraphs_wrapped: 1,
+///         ..ChangeSummary::default()
+///     }
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChangeSummary {
+    /// Number of hunks rewritten by any transform.
+    pub changes: usize,
+    /// Number of hunks rewritten by table reflow, which is one per table
+    /// unless tables are adjacent.
+    pub tables_reflowed: usize,
+    /// Number of input lines replaced by paragraph wrapping.
+    pub lines_wrapped: usize,
+    /// Number of hunks rewritten by paragraph wrapping, which is one per
+    /// paragraph unless paragraphs are adjacent.
+    pub paragraphs_wrapped: usize,
+    /// Number of hunks rewritten by HTML table conversion.
+    pub html_tables_converted: usize,
+    /// Number of hunks rewritten by ordered list renumbering.
+    pub lists_renumbered: usize,
+    /// Number of footnote definitions written or rewritten by footnote
+    /// conversion.
+    pub footnotes_rewritten: usize,
+}
+
+impl ChangeSummary {
+    /// Totals `changes`, such as those returned by [`ChangeLog::changes`].
+    #[must_use]
+    pub fn from_changes(changes: &[Change]) -> Self {
+        changes.iter().fold(Self::default(), |mut summary, change| {
+            summary.changes += 1;
+            match change.transform {
+                Transform::Tables => summary.tables_reflowed += 1,
+                Transform::Wrap => {
+                    summary.lines_wrapped += change.before.len();
+                    summary.paragraphs_wrapped += 1;
+                }
+                Transform::HtmlTables => summary.html_tables_converted += 1,
+                Transform::Renumber => summary.lists_renumbered += 1,
+                Transform::Footnotes => {
+                    summary.footnotes_rewritten += change
+                        .after
+                        .iter()
+                        .filter(|line| is_footnote_definition(line))
+                        .count();
+                }
+                _ => {}
+            }
+            summary
+        })
+    }
+}
+
+/// Returns `true` when `line` starts a `[^label]:` footnote definition.
+fn is_footnote_definition(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix("[^")
+        .and_then(|rest| rest.split_once(']'))
+        .is_some_and(|(label, rest)| !label.is_empty() && rest.starts_with(':'))
+}
+
+/// Computes the differing hunks between two line slices.
+///
+/// Adjacent insertions, deletions, and replacements are merged so each hunk
+/// corresponds to one contiguous edited region.
+fn diff_hunks(before: &[String], after: &[String]) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut hunks: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    for op in capture_diff_slices(Algorithm::Myers, before, after) {
+        let (tag, old, new) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        match hunks.last_mut() {
+            Some((prev_old, prev_new))
+                if prev_old.end == old.start && prev_new.end == new.start =>
+            {
+                prev_old.end = old.end;
+                prev_new.end = new.end;
+            }
+            _ => hunks.push((old, new)),
+        }
+    }
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for change recording.
+
+    use super::*;
+
+    fn lines(items: &[&str]) -> Vec<String> { items.iter().map(ToString::to_string).collect() }
+
+    #[test]
+    fn identical_input_records_nothing() {
+        let mut log = ChangeLog::default();
+        let doc = lines(&["a", "b"]);
+        log.record(Transform::Tables, &doc, &doc);
+        assert!(log.changes().is_empty());
+    }
+
+    #[test]
+    fn replacement_and_insertion_merge_into_one_hunk() {
+        let mut log = ChangeLog::default();
+        log.record(
+            Transform::Wrap,
+            &lines(&["keep", "long line", "tail"]),
+            &lines(&["keep", "long", "line", "tail"]),
+        );
+        assert_eq!(
+            log.changes(),
+            &[Change {
+                transform: Transform::Wrap,
+                before_lines: 1..2,
+                after_lines: 1..3,
+                source_lines: 1..2,
+                before: lines(&["long line"]),
+                after: lines(&["long", "line"]),
+            }]
+        );
+    }
+
+    #[test]
+    fn separate_edits_produce_separate_hunks() {
+        let mut log = ChangeLog::with_line_offset(3);
+        log.record(
+            Transform::Ellipsis,
+            &lines(&["a...", "same", "b..."]),
+            &lines(&["a…", "same", "b…"]),
+        );
+        let ranges: Vec<_> = log
+            .changes()
+            .iter()
+            .map(|c| c.before_lines.clone())
+            .collect();
+        assert_eq!(ranges, vec![3..4, 5..6]);
+    }
+
+    #[test]
+    fn pure_deletion_has_empty_after_range() {
+        let mut log = ChangeLog::default();
+        log.record(
+            Transform::Headings,
+            &lines(&["Title", "=====", "text"]),
+            &lines(&["Title", "text"]),
+        );
+        let change = &log.changes()[0];
+        assert_eq!(change.before_lines, 1..2);
+        assert!(change.after_lines.is_empty());
+    }
+}
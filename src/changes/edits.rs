@@ -0,0 +1,215 @@
+//! Edits expressed against the document the pipeline started from.
+//!
+//! A [`Change`](super::Change) locates its hunk in the document its
+//! transform received, which later transforms may have shifted. [`Origins`]
+//! follows every line back to the input lines it came from as the log
+//! records each stage, so [`ChangeLog::edits`](super::ChangeLog::edits) can
+//! describe the whole run as [`Edit`]s an editor applies to the buffer it
+//! holds.
+
+use std::ops::Range;
+
+use super::Transform;
+
+/// One rewrite of the original document.
+///
+/// `lines` is zero-based and half-open. An empty range inserts
+/// `replacement` before the line it starts at, and an empty `replacement`
+/// deletes the lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edit {
+    /// Lines of the original document the edit replaces.
+    pub lines: Range<usize>,
+    /// Text that replaces them.
+    pub replacement: Vec<String>,
+    /// Transforms that rewrote any of these lines, in pipeline order.
+    pub transforms: Vec<Transform>,
+}
+
+/// Applies `edits`, sorted and not overlapping as
+/// [`ChangeLog::edits`](super::ChangeLog::edits) returns them, to `lines`.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{
+///     changes::{Edit, Transform, apply_edits},
+///     process::{Options, process_stream_opts_with_edits},
+/// };
+///
+/// let lines: Vec<String> = ["# Notes", "", "Wait..."]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let opts = Options {
+///     ellipsis: true,
+///     ..Options::default()
+/// };
+/// let (out, edits) = process_stream_opts_with_edits(&lines, opts);
+/// assert_eq!(
+///     edits,
+///     [Edit {
+///         lines: 2..3,
+///         replacement: vec!["Wait…".to_string()],
+///         transforms: vec![Transform::Ellipsis],
+///     }]
+/// );
+/// assert_eq!(apply_edits(&lines, &edits), out);
+/// ```
+#[must_use]
+pub fn apply_edits(lines: &[String], edits: &[Edit]) -> Vec<String> {
+    let mut out = Vec::with_capacity(lines.len());
+    let mut copied = 0;
+    for edit in edits {
+        out.extend_from_slice(&lines[copied..edit.lines.start]);
+        out.extend(edit.replacement.iter().cloned());
+        copied = edit.lines.end;
+    }
+    out.extend_from_slice(&lines[copied..]);
+    out
+}
+
+/// The lines of the first recorded document that each line of the latest
+/// one derives from.
+///
+/// A line no transform touched maps to itself, and every line a hunk emits
+/// maps to all the lines the hunk replaced, or to an empty range at the
+/// insertion point when it replaced none.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Origins(Vec<Range<usize>>);
+
+impl Origins {
+    /// Moves past a stage that turned a document of `before_len` lines into
+    /// one of `after_len` by rewriting `hunks`, returning the original span
+    /// of each hunk.
+    ///
+    /// A document whose length does not match the latest one starts the
+    /// mapping again, treating it as the original.
+    pub(super) fn advance(
+        &mut self,
+        before_len: usize,
+        after_len: usize,
+        hunks: &[(Range<usize>, Range<usize>)],
+    ) -> Vec<Range<usize>> {
+        if self.0.len() != before_len {
+            self.0 = (0..before_len).map(|line| line..line + 1).collect();
+        }
+        let mut next = Vec::with_capacity(after_len);
+        let mut spans = Vec::with_capacity(hunks.len());
+        let mut copied = 0;
+        for (old, new) in hunks {
+            next.extend_from_slice(&self.0[copied..old.start]);
+            let span = if old.is_empty() {
+                let point = self.0.get(old.start).map_or_else(
+                    || self.0.last().map_or(0, |origin| origin.end),
+                    |origin| origin.start,
+                );
+                point..point
+            } else {
+                self.0[old.start].start..self.0[old.end - 1].end
+            };
+            next.extend(std::iter::repeat_n(span.clone(), new.len()));
+            spans.push(span);
+            copied = old.end;
+        }
+        next.extend_from_slice(&self.0[copied..]);
+        debug_assert_eq!(next.len(), after_len);
+        self.0 = next;
+        spans
+    }
+}
+
+/// Returns `true` when two line ranges share a line, or when an empty range
+/// sits inside or at either end of the other.
+pub(super) fn touches(a: &Range<usize>, b: &Range<usize>) -> bool {
+    if a.is_empty() || b.is_empty() {
+        a.start <= b.end && b.start <= a.end
+    } else {
+        a.start < b.end && b.start < a.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for original-document edits.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::changes::ChangeLog;
+
+    fn lines(items: &[&str]) -> Vec<String> { items.iter().map(ToString::to_string).collect() }
+
+    #[test]
+    fn later_stages_map_back_through_earlier_shifts() {
+        let original = lines(&["Title", "=====", "", "a..."]);
+        let headed = lines(&["# Title", "", "a..."]);
+        let output = lines(&["# Title", "", "a…"]);
+        let mut log = ChangeLog::default();
+        log.record(Transform::Headings, &original, &headed);
+        log.record(Transform::Ellipsis, &headed, &output);
+        assert_eq!(log.changes()[1].before_lines, 2..3);
+        assert_eq!(log.changes()[1].source_lines, 3..4);
+        assert_eq!(
+            log.edits(&original, &output),
+            [
+                Edit {
+                    lines: 0..2,
+                    replacement: lines(&["# Title"]),
+                    transforms: vec![Transform::Headings],
+                },
+                Edit {
+                    lines: 3..4,
+                    replacement: lines(&["a…"]),
+                    transforms: vec![Transform::Ellipsis],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn overlapping_stages_share_an_edit() {
+        let original = lines(&["|a|b|", "x..."]);
+        let tables = lines(&["| a | b |", "x..."]);
+        let wrapped = lines(&["| a | b |", "x…"]);
+        let mut log = ChangeLog::with_line_offset(2);
+        log.record(Transform::Tables, &original, &tables);
+        log.record(Transform::Ellipsis, &tables, &wrapped);
+        let edits = log.edits(&original, &wrapped);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].lines, 2..4);
+        assert_eq!(
+            edits[0].transforms,
+            [Transform::Tables, Transform::Ellipsis]
+        );
+    }
+
+    #[rstest]
+    #[case::insertion(&["a", "b"], &["a", "", "b"])]
+    #[case::deletion(&["a", "", "", "b"], &["a", "", "b"])]
+    #[case::append(&["a"], &["a", "b"])]
+    #[case::rewrite_all(&["a", "b"], &["c"])]
+    fn edits_reproduce_the_output(#[case] original: &[&str], #[case] output: &[&str]) {
+        let (original, output) = (lines(original), lines(output));
+        let mut log = ChangeLog::default();
+        log.record(Transform::BlankLines, &original, &output);
+        let edits = log.edits(&original, &output);
+        assert!(
+            edits
+                .iter()
+                .all(|edit| edit.transforms == [Transform::BlankLines])
+        );
+        assert_eq!(apply_edits(&original, &edits), output);
+    }
+
+    #[rstest]
+    #[case::overlap(0..2, 1..3, true)]
+    #[case::adjacent(0..2, 2..3, false)]
+    #[case::point_inside(1..1, 0..2, true)]
+    #[case::point_at_end(2..2, 0..2, true)]
+    #[case::point_apart(3..3, 0..2, false)]
+    fn ranges_touch(#[case] a: Range<usize>, #[case] b: Range<usize>, #[case] expected: bool) {
+        assert_eq!(touches(&a, &b), expected);
+        assert_eq!(touches(&b, &a), expected);
+    }
+}
@@ -0,0 +1,214 @@
+//! Names of the pipeline stages recorded in a change log.
+//!
+//! Reports, `--enable`, config tables, and `--plugin` stages all refer to
+//! transforms by the stable names [`Transform::name`] returns.
+
+/// Identifies the pipeline stage that produced a [`Change`](super::Change).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Transform {
+    /// Expansion of `<!-- include: path -->` pragmas.
+    Includes,
+    /// Rewriting of a first heading that differs from the front matter
+    /// title.
+    Titles,
+    /// Fence compression and orphan specifier attachment.
+    Fences,
+    /// HTML `<table>` conversion.
+    HtmlTables,
+    /// Jira and Confluence wiki-markup table conversion.
+    WikiTables,
+    /// reStructuredText grid and simple table conversion.
+    RstTables,
+    /// Inline code spacing and delimiter normalization.
+    CodeSpans,
+    /// Markdown table reflow.
+    Tables,
+    /// Setext to ATX heading conversion.
+    Headings,
+    /// Bold-only paragraph to ATX heading conversion.
+    EmphasisHeadings,
+    /// Emphasis repair around inline code.
+    CodeEmphasis,
+    /// Hard line break normalization.
+    HardBreaks,
+    /// Sentence spacing normalization.
+    SentenceSpacing,
+    /// Rejoining of lines hard-wrapped mid-sentence.
+    Rejoin,
+    /// Paragraph wrapping.
+    Wrap,
+    /// Ellipsis replacement outside tables.
+    Ellipsis,
+    /// Footnote conversion and renumbering.
+    Footnotes,
+    /// Removal of repeated footnote references.
+    FootnoteRefs,
+    /// Header abbreviation for tables wider than the width budget.
+    AbbreviateHeaders,
+    /// Ordered list renumbering.
+    Renumber,
+    /// List marker spacing normalization.
+    ListMarkers,
+    /// Thematic break normalization.
+    Breaks,
+    /// Blank line counts around headings.
+    HeadingSpacing,
+    /// Collapsing runs of blank lines and trimming them from the document's
+    /// ends.
+    BlankLines,
+    /// Rewrites made by a caller-supplied pipeline hook, such as a WASM
+    /// plugin.
+    Plugin,
+}
+
+impl Transform {
+    /// Returns the stable, kebab-case name used in reports.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::changes::Transform;
+    ///
+    /// assert_eq!(Transform::CodeEmphasis.name(), "code-emphasis");
+    /// ```
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Includes => "includes",
+            Self::Titles => "titles",
+            Self::Fences => "fences",
+            Self::HtmlTables => "html-tables",
+            Self::WikiTables => "wiki-tables",
+            Self::RstTables => "rst-tables",
+            Self::CodeSpans => "code-spans",
+            Self::Tables => "tables",
+            Self::Headings => "headings",
+            Self::EmphasisHeadings => "emphasis-headings",
+            Self::CodeEmphasis => "code-emphasis",
+            Self::HardBreaks => "hard-breaks",
+            Self::SentenceSpacing => "sentence-spacing",
+            Self::Rejoin => "rejoin",
+            Self::Wrap => "wrap",
+            Self::Ellipsis => "ellipsis",
+            Self::Footnotes => "footnotes",
+            Self::FootnoteRefs => "footnote-refs",
+            Self::AbbreviateHeaders => "abbreviate-headers",
+            Self::Renumber => "renumber",
+            Self::ListMarkers => "list-markers",
+            Self::Breaks => "breaks",
+            Self::HeadingSpacing => "heading-spacing",
+            Self::BlankLines => "blank-lines",
+            Self::Plugin => "plugin",
+        }
+    }
+
+    /// Returns a one-line, human-readable summary of what the transform does,
+    /// as listed by `mdtablefix --help-markdown`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::changes::Transform;
+    ///
+    /// assert_eq!(Transform::Wrap.description(), "Paragraph wrapping.");
+    /// ```
+    #[must_use]
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Includes => "Expansion of `<!-- include: path -->` pragmas.",
+            Self::Titles => {
+                "Rewriting of a first heading that differs from the front matter title."
+            }
+            Self::Fences => "Fence compression and orphan specifier attachment.",
+            Self::HtmlTables => "HTML `<table>` conversion.",
+            Self::WikiTables => "Jira and Confluence wiki-markup table conversion.",
+            Self::RstTables => "reStructuredText grid and simple table conversion.",
+            Self::CodeSpans => "Inline code spacing and delimiter normalization.",
+            Self::Tables => "Markdown table reflow.",
+            Self::Headings => "Setext to ATX heading conversion.",
+            Self::EmphasisHeadings => "Bold-only paragraph to ATX heading conversion.",
+            Self::CodeEmphasis => "Emphasis repair around inline code.",
+            Self::HardBreaks => "Hard line break normalization.",
+            Self::SentenceSpacing => "Sentence spacing normalization.",
+            Self::Rejoin => "Rejoining of lines hard-wrapped mid-sentence.",
+            Self::Wrap => "Paragraph wrapping.",
+            Self::Ellipsis => "Ellipsis replacement outside tables.",
+            Self::Footnotes => "Footnote conversion and renumbering.",
+            Self::FootnoteRefs => "Removal of repeated footnote references.",
+            Self::AbbreviateHeaders => {
+                "Header abbreviation for tables wider than the width budget."
+            }
+            Self::Renumber => "Ordered list renumbering.",
+            Self::ListMarkers => "List marker spacing normalization.",
+            Self::Breaks => "Thematic break normalization.",
+            Self::HeadingSpacing => "Blank line counts around headings.",
+            Self::BlankLines => {
+                "Collapsing runs of blank lines and trimming them from the document's ends."
+            }
+            Self::Plugin => {
+                "Rewrites made by a caller-supplied pipeline hook, such as a WASM plugin."
+            }
+        }
+    }
+
+    /// Returns every transform, in the order the pipeline runs them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::changes::Transform;
+    ///
+    /// assert_eq!(Transform::all().next(), Some(Transform::Includes));
+    /// ```
+    pub fn all() -> impl Iterator<Item = Self> { Self::ALL.into_iter() }
+
+    /// Looks up a transform by the name returned from [`Transform::name`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::changes::Transform;
+    ///
+    /// assert_eq!(Transform::from_name("wrap"), Some(Transform::Wrap));
+    /// assert_eq!(Transform::from_name("Wrap"), None);
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|transform| transform.name() == name)
+    }
+
+    pub(crate) const ALL: [Self; 25] = [
+        Self::Includes,
+        Self::Titles,
+        Self::Fences,
+        Self::HtmlTables,
+        Self::WikiTables,
+        Self::RstTables,
+        Self::CodeSpans,
+        Self::Tables,
+        Self::Headings,
+        Self::EmphasisHeadings,
+        Self::CodeEmphasis,
+        Self::HardBreaks,
+        Self::SentenceSpacing,
+        Self::Rejoin,
+        Self::Wrap,
+        Self::Ellipsis,
+        Self::Footnotes,
+        Self::FootnoteRefs,
+        Self::AbbreviateHeaders,
+        Self::Renumber,
+        Self::ListMarkers,
+        Self::Breaks,
+        Self::HeadingSpacing,
+        Self::BlankLines,
+        Self::Plugin,
+    ];
+}
@@ -0,0 +1,56 @@
+//! JSON map of the link anchors defined by each processed file.
+//!
+//! `--emit-anchors PATH` writes one artefact listing every heading slug and
+//! explicit anchor per file, taken from the formatted text, so link checkers
+//! can validate cross-file fragment links without parsing Markdown again.
+
+use std::{fs, path::Path};
+
+use anyhow::Context;
+use mdtablefix::anchors::{Anchor, AnchorKind};
+use serde_json::{Value, json};
+
+/// Version of the anchor map schema; bump when fields change incompatibly.
+const ANCHORS_VERSION: u64 = 1;
+
+/// Anchors of one file, as recorded in the map.
+pub(crate) struct FileAnchors<'a> {
+    /// Path exactly as supplied on the command line.
+    pub(crate) path: &'a Path,
+    /// Anchors defined by the formatted file, or the error that stopped
+    /// processing.
+    pub(crate) outcome: Result<&'a [Anchor], String>,
+}
+
+/// Serializes `files` as JSON and writes the anchor map to `path`.
+///
+/// # Errors
+/// Returns an error if the map cannot be written.
+pub(crate) fn write_anchors(path: &Path, files: &[FileAnchors<'_>]) -> anyhow::Result<()> {
+    let map = json!({
+        "version": ANCHORS_VERSION,
+        "files": files.iter().map(file_json).collect::<Vec<_>>(),
+    });
+    let mut text = serde_json::to_string_pretty(&map).context("serializing anchor map")?;
+    text.push('\n');
+    fs::write(path, text).with_context(|| format!("writing anchors {}", path.display()))
+}
+
+fn file_json(file: &FileAnchors<'_>) -> Value {
+    let path = file.path.display().to_string();
+    match file.outcome {
+        Ok(anchors) => json!({
+            "path": path,
+            "anchors": anchors.iter().map(anchor_json).collect::<Vec<_>>(),
+        }),
+        Err(ref error) => json!({ "path": path, "error": error }),
+    }
+}
+
+fn anchor_json(anchor: &Anchor) -> Value {
+    let kind = match anchor.kind {
+        AnchorKind::Heading => "heading",
+        AnchorKind::Html => "html",
+    };
+    json!({ "id": anchor.id, "kind": kind, "line": anchor.line })
+}
@@ -0,0 +1,321 @@
+//! Command-line arguments of the `mdtablefix` binary.
+//!
+//! [`FmtArgs`] holds every formatting flag. The bare invocation flattens it
+//! into the top-level parser, and the `fmt`, `check`, and `lint` subcommands
+//! each take their own copy, so `mdtablefix FILE` and `mdtablefix fmt FILE`
+//! accept exactly the same options.
+
+use std::{num::NonZeroUsize, path::PathBuf};
+
+use clap::{ArgAction, Args, Subcommand, builder::NonEmptyStringValueParser};
+
+use crate::{
+    diff::ColourChoice,
+    discover::{Exclude, expand_paths, parse_exclude, read_file_list},
+    exit_status::UsageError,
+    format_opts::FormatOpts,
+    format_values::{EncodingArg, FinalNewlineArg, LineEndingArg},
+    git::{GitSelection, git_files},
+    suggest_width::SuggestWidthArgs,
+    summary::OutputFormat,
+    table::TableArgs,
+};
+
+/// Flags and paths shared by the bare invocation, `fmt`, `check`, and `lint`.
+#[derive(Args)]
+#[command(group = clap::ArgGroup::new("inputs").args(["files", "files_from", "changed", "staged"]).multiple(true))]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "CLI exposes independent flags via separate switches"
+)]
+pub(crate) struct FmtArgs {
+    /// Rewrite files in place, exiting with status 1 when any file changed
+    #[arg(long = "in-place", requires = "inputs")]
+    pub(crate) in_place: bool,
+    /// With `--in-place`, copy each file that will change to its name plus
+    /// SUFFIX (`.bak` by default) before rewriting it
+    #[arg(
+        long = "backup",
+        value_name = "SUFFIX",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ".bak",
+        value_parser = NonEmptyStringValueParser::new(),
+        requires = "in_place"
+    )]
+    pub(crate) backup: Option<String>,
+    /// With `--in-place`, leave files that are already formatted untouched
+    /// so their modification time is kept
+    #[arg(long = "keep-mtime", requires = "in_place")]
+    pub(crate) keep_mtime: bool,
+    /// Write each formatted file to the same relative path below DIR instead
+    /// of printing it, leaving the inputs untouched
+    #[arg(
+        long = "output-dir",
+        value_name = "DIR",
+        requires = "inputs",
+        conflicts_with_all = ["in_place", "check", "list_changed", "diff"]
+    )]
+    pub(crate) output_dir: Option<PathBuf>,
+    /// How to read files that are not valid UTF-8: `strict` refuses them,
+    /// `lossy` replaces invalid bytes and writes UTF-8, and `detect` reads
+    /// UTF-16 with a byte order mark and Latin-1 and writes them back in the
+    /// same encoding
+    #[arg(long = "encoding", value_enum, value_name = "POLICY", default_value_t)]
+    pub(crate) encoding: EncodingArg,
+    /// Line ending to write: `auto` keeps the one most of each file's lines
+    /// use, while `lf` and `crlf` convert every file
+    #[arg(
+        long = "line-ending",
+        value_enum,
+        value_name = "STYLE",
+        default_value_t
+    )]
+    pub(crate) line_ending: LineEndingArg,
+    /// Whether output ends with a line ending: `keep` matches each input,
+    /// while `always` and `never` add or remove it
+    #[arg(
+        long = "final-newline",
+        value_enum,
+        value_name = "POLICY",
+        default_value_t
+    )]
+    pub(crate) final_newline: FinalNewlineArg,
+    /// Skip files a quick scan shows no enabled transform would change,
+    /// without reading them through the pipeline, and buffer output; for
+    /// large, mostly formatted trees
+    #[arg(long = "bulk", requires = "inputs")]
+    pub(crate) bulk: bool,
+    /// Report files, or standard input, that would be reformatted without
+    /// changing them, exiting with status 1 when any would be
+    #[arg(long = "check", conflicts_with = "in_place")]
+    pub(crate) check: bool,
+    /// Print the path of each file that would be reformatted, one per line,
+    /// instead of the fixed text
+    #[arg(
+        long = "list-changed",
+        requires = "inputs",
+        conflicts_with_all = ["in_place", "diff", "format"]
+    )]
+    pub(crate) list_changed: bool,
+    /// Print a unified diff of the changes instead of the fixed text
+    #[arg(long = "diff", conflicts_with = "in_place")]
+    pub(crate) diff: bool,
+    /// Colour `--diff` output: `auto` (when stdout is a terminal and
+    /// `NO_COLOR` is unset), `always`, or `never`
+    #[arg(
+        long = "colour",
+        visible_alias = "color",
+        value_enum,
+        value_name = "WHEN",
+        default_value_t
+    )]
+    pub(crate) colour: ColourChoice,
+    /// Print the formatted text (`text`) or a JSON summary of each file
+    /// (`json`)
+    #[arg(
+        long = "format",
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t,
+        conflicts_with = "diff"
+    )]
+    pub(crate) format: OutputFormat,
+    /// Print how many tables, paragraphs, lists, and footnotes were rewritten
+    /// in each file to stderr
+    #[arg(long = "stats")]
+    pub(crate) stats: bool,
+    /// Write a JSON report of the transforms applied to each file
+    #[arg(long = "report-file", value_name = "PATH")]
+    pub(crate) report_file: Option<PathBuf>,
+    /// Write a JSON map of the heading slugs and explicit anchors defined by
+    /// each formatted file
+    #[arg(long = "emit-anchors", value_name = "PATH")]
+    pub(crate) emit_anchors: Option<PathBuf>,
+    /// Serve formatting requests on a Unix domain socket instead of
+    /// processing files
+    #[arg(
+        long = "daemon",
+        value_name = "SOCKET",
+        conflicts_with_all = [
+            "inputs", "in_place", "output_dir", "check", "list_changed", "diff", "format", "stats",
+            "report_file", "emit_anchors", "stdin_filepath"
+        ]
+    )]
+    pub(crate) daemon: Option<PathBuf>,
+    /// Enable a bundle of flags: `readme`, `docs-site`, `changelog`, or a
+    /// preset defined in the config file
+    #[arg(long = "preset", visible_alias = "profile", value_name = "NAME")]
+    pub(crate) preset: Option<String>,
+    /// Config file defining presets and per-transform settings for every input
+    /// (defaults to the `.mdtablefix.toml` nearest to each input, when present)
+    #[arg(long = "config", value_name = "PATH")]
+    pub(crate) config: Option<PathBuf>,
+    /// Number of files to process in parallel (defaults to the available CPUs)
+    #[arg(short = 'j', long = "jobs", env = "MDTABLEFIX_JOBS", value_name = "N")]
+    pub(crate) jobs: Option<NonZeroUsize>,
+    /// Read more file names from PATH, or from standard input when PATH is
+    /// `-`, one per line or separated by NUL bytes
+    #[arg(long = "files-from", value_name = "PATH")]
+    pub(crate) files_from: Option<PathBuf>,
+    /// Walk directories without skipping the paths listed in `.gitignore` and
+    /// `.mdtablefixignore` files
+    #[arg(long = "no-ignore")]
+    pub(crate) no_ignore: bool,
+    /// Process only the Markdown files git reports as modified, staged, or
+    /// untracked, below the current directory or the FILE arguments
+    #[arg(long = "changed", conflicts_with_all = ["files_from", "staged"])]
+    pub(crate) changed: bool,
+    /// Process only the Markdown files staged in the git index, below the
+    /// current directory or the FILE arguments
+    #[arg(long = "staged", conflicts_with = "files_from")]
+    pub(crate) staged: bool,
+    /// Skip files and directories matching PATTERN, whether named on the
+    /// command line or found in a directory; may be repeated
+    #[arg(long = "exclude", value_name = "PATTERN", value_parser = parse_exclude)]
+    pub(crate) exclude: Vec<Exclude>,
+    /// Treat standard input as the contents of PATH: report it under that
+    /// name and leave it unchanged when PATH is excluded
+    #[arg(
+        long = "stdin-filepath",
+        value_name = "PATH",
+        conflicts_with = "inputs"
+    )]
+    pub(crate) stdin_filepath: Option<PathBuf>,
+    /// Hide the progress bar and every log message except errors
+    #[arg(short = 'q', long = "quiet")]
+    pub(crate) quiet: bool,
+    /// Log each file as it is processed; repeat to log every transform
+    /// (`-vv`) and the library's trace events (`-vvv`)
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count, conflicts_with = "quiet")]
+    pub(crate) verbose: u8,
+    /// Compile fenced Rust examples with rustc and report errors instead of
+    /// formatting
+    #[cfg(feature = "check-rust-fences")]
+    #[arg(
+        long = "check-rust-fences",
+        conflicts_with_all = ["in_place", "output_dir", "check", "list_changed", "diff", "report_file", "daemon"]
+    )]
+    pub(crate) check_rust_fences: bool,
+    /// Print the ID and line range of every table, fence, and heading instead
+    /// of formatting
+    #[arg(
+        long = "list-blocks",
+        conflicts_with_all = ["in_place", "output_dir", "check", "list_changed", "diff", "format", "report_file", "daemon"]
+    )]
+    pub(crate) list_blocks: bool,
+    /// Report files whose first `#` heading differs from their front matter
+    /// title, or from their file name without one, instead of formatting
+    #[arg(
+        long = "check-titles",
+        conflicts_with_all = ["in_place", "output_dir", "check", "list_changed", "diff", "format", "report_file", "daemon", "list_blocks"]
+    )]
+    pub(crate) check_titles: bool,
+    #[command(flatten)]
+    pub(crate) opts: FormatOpts,
+    /// Markdown files to fix; directories are searched recursively for
+    /// `*.md` and `*.markdown` files, and quoted glob patterns are expanded
+    pub(crate) files: Vec<PathBuf>,
+    /// Set by the `lint` subcommand: report each pending change as a
+    /// diagnostic instead of printing the text.
+    #[arg(skip)]
+    pub(crate) lint: bool,
+}
+
+/// Subcommands; without one the tool formats as `fmt` does.
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Format Markdown files, or standard input, as the bare invocation does
+    Fmt(Box<FmtArgs>),
+    /// Report files that would be reformatted, exiting with status 1 when
+    /// any would be; shorthand for `fmt --check`
+    Check(Box<FmtArgs>),
+    /// Print a `PATH:LINE: TRANSFORM` diagnostic for each change formatting
+    /// would make, exiting with status 1 when there are any
+    Lint(Box<FmtArgs>),
+    /// Recommend `--wrap-width` and `--max-table-width` values from the
+    /// widths of the prose lines and tables in FILE...
+    SuggestWidth(SuggestWidthArgs),
+    /// Reflow the single table, or CSV with `--from csv`, read from standard
+    /// input, without running the rest of the pipeline
+    Table(TableArgs),
+}
+
+impl FmtArgs {
+    /// Returns the git selection requested by `--changed` or `--staged`.
+    pub(crate) fn git_selection(&self) -> Option<GitSelection> {
+        if self.changed {
+            Some(GitSelection::Changed)
+        } else {
+            self.staged.then_some(GitSelection::Staged)
+        }
+    }
+
+    /// Resolves the files to process from git, or from the arguments and
+    /// `--files-from`.
+    pub(crate) fn input_files(&self) -> anyhow::Result<Vec<PathBuf>> {
+        if let Some(selection) = self.git_selection() {
+            return git_files(selection, &self.files, &self.exclude);
+        }
+        let mut args = self.files.clone();
+        if let Some(list) = &self.files_from {
+            args.extend(read_file_list(list)?);
+        }
+        expand_paths(&args, &self.exclude, !self.no_ignore)
+    }
+
+    /// Turns the arguments of the `check` subcommand into the equivalent
+    /// `--check` run.
+    ///
+    /// # Errors
+    /// Returns a [`UsageError`] when an option that writes files or serves
+    /// requests is given.
+    pub(crate) fn into_check(self: Box<Self>) -> Result<Self, UsageError> {
+        let mut args = *self;
+        reject(
+            "check",
+            &[
+                ("--in-place", args.in_place),
+                ("--output-dir", args.output_dir.is_some()),
+                ("--daemon", args.daemon.is_some()),
+            ],
+        )?;
+        args.check = true;
+        Ok(args)
+    }
+
+    /// Turns the arguments of the `lint` subcommand into a run that reports
+    /// each pending change as a diagnostic.
+    ///
+    /// # Errors
+    /// Returns a [`UsageError`] when an option that writes files or chooses
+    /// another output is given.
+    pub(crate) fn into_lint(self: Box<Self>) -> Result<Self, UsageError> {
+        let mut args = *self;
+        reject(
+            "lint",
+            &[
+                ("--in-place", args.in_place),
+                ("--output-dir", args.output_dir.is_some()),
+                ("--daemon", args.daemon.is_some()),
+                ("--diff", args.diff),
+                ("--list-changed", args.list_changed),
+                ("--list-blocks", args.list_blocks),
+                ("--check-titles", args.check_titles),
+                ("--format", args.format != OutputFormat::Text),
+            ],
+        )?;
+        args.lint = true;
+        Ok(args)
+    }
+}
+
+/// Fails with a [`UsageError`] naming the first of `flags` that is set.
+fn reject(subcommand: &str, flags: &[(&str, bool)]) -> Result<(), UsageError> {
+    match flags.iter().find(|(_, set)| *set) {
+        Some((flag, _)) => Err(UsageError(format!(
+            "the argument '{flag}' cannot be used with the `{subcommand}` subcommand"
+        ))),
+        None => Ok(()),
+    }
+}
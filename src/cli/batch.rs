@@ -0,0 +1,362 @@
+//! Processing of individual files in a batch run.
+//!
+//! [`handle_file`] reads, formats, and writes back, copies, or renders one
+//! file, and the `write_file_*` helpers turn the collected outcomes into the
+//! `--report-file` and `--emit-anchors` artefacts. [`run`] drives the whole
+//! batch.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use anyhow::Context;
+use mdtablefix::{
+    anchors::{Anchor, collect_anchors},
+    changes::{Change, ChangeLog},
+    document::Document,
+    io::{DecodePolicy, Decoded, Encoding, read_text, write_atomic},
+};
+use rayon::prelude::*;
+use tracing::{info, warn};
+
+use crate::{
+    anchor_map::{FileAnchors, write_anchors},
+    args::FmtArgs,
+    batch_output::BatchOutput,
+    bulk::Bulk,
+    config::ConfigResolver,
+    diff::{paint, unified_diff},
+    exit_status::Status,
+    format_opts::FormatOpts,
+    frontmatter::split_leading_yaml_frontmatter,
+    jobs::{build_pool, resolve_jobs},
+    layout::{Layout, render},
+    lint::diagnostics,
+    output_dir::write_copy,
+    panic_isolation::catch_file_panic,
+    pipeline::{process_lines, warn_unconverted_tables},
+    progress::Progress,
+    report::{FileReport, write_report},
+    summary::{FileSummary, OutputFormat, print_summary, stats_line},
+};
+
+/// What to do with each file once it has been processed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FileMode<'a> {
+    /// Print the fixed text to stdout.
+    Print,
+    /// Print a unified diff from the original to the fixed text.
+    Diff,
+    /// Rewrite the file with the fixed text, first copying a file that will
+    /// change to its path with `backup` appended when a suffix is given. With
+    /// `keep_mtime`, files whose text would not change are not written, so
+    /// their modification time stays as it was.
+    InPlace {
+        backup: Option<&'a str>,
+        keep_mtime: bool,
+    },
+    /// Write the fixed text to the file's mirror below the `--output-dir`
+    /// directory.
+    OutputDir(&'a Path),
+    /// Only report whether the file would change.
+    Check,
+}
+
+/// Result of processing one file: the text or diff to print (unless rewritten
+/// in place or checked), whether the file differs from its fixed form, the
+/// changes recorded when a report was requested, and the anchors of the fixed
+/// text when an anchor map was requested.
+pub(crate) struct FileOutcome {
+    pub(crate) output: Option<String>,
+    pub(crate) changed: bool,
+    pub(crate) changes: Vec<Change>,
+    pub(crate) anchors: Vec<Anchor>,
+}
+
+/// Reads, formats, and disposes of one file according to `mode`, writing it
+/// with the line ending and final newline `layout` chooses.
+pub(crate) fn handle_file(
+    path: &Path,
+    mode: FileMode<'_>,
+    opts: &FormatOpts,
+    encoding: DecodePolicy,
+    layout: Layout,
+    track_changes: bool,
+    emit_anchors: bool,
+) -> anyhow::Result<FileOutcome> {
+    info!(path = %path.display(), "processing file");
+    let started = Instant::now();
+    let Decoded {
+        text: content,
+        encoding,
+        replaced,
+    } = read_text(path, encoding).with_context(|| format!("reading {}", path.display()))?;
+    if replaced {
+        warn!(path = %path.display(), "replaced invalid UTF-8");
+    } else if encoding != Encoding::Utf8 {
+        info!(path = %path.display(), encoding = encoding.name(), "decoded file");
+    }
+    let mut source = Document::parse(&content);
+    layout.apply(&mut source);
+    let mut log = track_changes.then(|| {
+        ChangeLog::with_line_offset(split_leading_yaml_frontmatter(&source.lines).0.len())
+    });
+    warn_unconverted_tables(path, &source.lines, opts);
+    let fixed = process_lines(&source.lines, Some(path), opts, log.as_mut())?;
+    let changes = log.map(ChangeLog::into_changes).unwrap_or_default();
+    let anchors = if emit_anchors {
+        collect_anchors(&fixed)
+    } else {
+        Vec::new()
+    };
+    let rewritten = render(&source, &fixed);
+    let differs = rewritten != content;
+    let output = match mode {
+        FileMode::Print => Some(rewritten.clone()),
+        FileMode::Diff => unified_diff(path, &content, &rewritten),
+        FileMode::InPlace { backup, keep_mtime } => {
+            if let Some(suffix) = backup
+                && differs
+            {
+                write_backup(path, suffix)?;
+            }
+            if differs || !keep_mtime {
+                write_atomic(path, encode(path, encoding, &rewritten)?)
+                    .with_context(|| format!("writing {}", path.display()))?;
+            }
+            None
+        }
+        FileMode::OutputDir(dir) => {
+            write_copy(dir, path, &encode(path, encoding, &rewritten)?)?;
+            None
+        }
+        FileMode::Check => None,
+    };
+    log_processed(path, differs, started);
+    Ok(FileOutcome {
+        output,
+        changed: differs,
+        changes,
+        anchors,
+    })
+}
+
+/// Encodes the formatted text of `path` in the encoding it was read in.
+fn encode(path: &Path, encoding: Encoding, text: &str) -> anyhow::Result<Vec<u8>> {
+    encoding
+        .encode(text)
+        .with_context(|| format!("encoding {} as {}", path.display(), encoding.name()))
+}
+
+/// Copies `path` to the same path with `suffix` appended, replacing any
+/// earlier backup, so an unwanted rewrite can be undone.
+fn write_backup(path: &Path, suffix: &str) -> anyhow::Result<()> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(suffix);
+    let backup = PathBuf::from(backup);
+    fs::copy(path, &backup).with_context(|| format!("writing {}", backup.display()))?;
+    Ok(())
+}
+
+/// Formats every file in `files` in parallel, then prints, diffs, checks, or
+/// rewrites each one as `cli` asks and writes the requested reports. Each
+/// file is formatted with the options `configs` resolves for it.
+///
+/// # Errors
+/// Returns the first file's error once the whole batch has been handled, or
+/// an error when a report cannot be written.
+pub(crate) fn run(
+    cli: &FmtArgs,
+    configs: &ConfigResolver<'_>,
+    files: &[PathBuf],
+) -> anyhow::Result<Status> {
+    let json = cli.format == OutputFormat::Json;
+    let mode = if cli.diff {
+        FileMode::Diff
+    } else if let Some(dir) = &cli.output_dir {
+        FileMode::OutputDir(dir)
+    } else if cli.check || cli.list_changed || cli.lint || (json && !cli.in_place) {
+        FileMode::Check
+    } else if cli.in_place {
+        FileMode::InPlace {
+            backup: cli.backup.as_deref(),
+            keep_mtime: cli.keep_mtime,
+        }
+    } else {
+        FileMode::Print
+    };
+    let track_changes = cli.report_file.is_some() || json || cli.stats || cli.lint;
+    let emit_anchors = cli.emit_anchors.is_some();
+    let encoding = cli.encoding.into();
+    let layout = Layout::new(cli);
+    let colour = cli.diff && cli.colour.enabled();
+    let pool = build_pool(resolve_jobs(cli.jobs))?;
+    let bulk = cli.bulk.then(Bulk::default);
+    let progress = Progress::new(files.len(), cli.quiet);
+    let results: Vec<anyhow::Result<FileOutcome>> = pool.install(|| {
+        files
+            .par_iter()
+            .map(|p| {
+                progress.start(p);
+                let result = catch_file_panic(p, || {
+                    let opts = configs.for_file(p)?;
+                    // Anchor maps need every file's headings, so nothing is
+                    // skipped when one is requested.
+                    if let Some(bulk) = bulk.as_ref().filter(|_| !emit_anchors)
+                        && let Some(outcome) = bulk.skip_untouched(p, mode, &opts, layout)?
+                    {
+                        return Ok(outcome);
+                    }
+                    handle_file(
+                        p,
+                        mode,
+                        &opts,
+                        encoding,
+                        layout,
+                        track_changes,
+                        emit_anchors,
+                    )
+                });
+                progress.advance();
+                result
+            })
+            .collect()
+    });
+    progress.finish();
+    if let Some(report_path) = &cli.report_file {
+        write_file_report(report_path, files, &results)?;
+    }
+    if let Some(anchors_path) = &cli.emit_anchors {
+        write_file_anchors(anchors_path, files, &results)?;
+    }
+    if json {
+        print_file_summary(files, &results)?;
+    }
+    let mut unformatted = false;
+    let mut stdout = BatchOutput::new(cli.bulk);
+    let outcomes = files
+        .iter()
+        .zip(results)
+        .map(|(path, result)| result.map(|outcome| (path, outcome)))
+        .collect();
+    report_results(outcomes, |(path, outcome)| {
+        match (mode, outcome.output) {
+            (FileMode::Diff, Some(diff)) if colour => stdout.print(&paint(&diff)),
+            (_, Some(out)) => stdout.print(&out),
+            (_, None) => {}
+        }
+        if cli.stats {
+            eprintln!("{}", stats_line(path, &outcome.changes));
+        }
+        if cli.lint {
+            stdout.print(&diagnostics(path, &outcome.changes, outcome.changed));
+        }
+        if cli.list_changed && outcome.changed {
+            stdout.print(&format!("{}\n", path.display()));
+        } else if cli.check && outcome.changed {
+            eprintln!("{}: would be reformatted", path.display());
+        }
+        unformatted |= (cli.check || cli.lint || cli.in_place) && outcome.changed;
+    })?;
+    Ok(Status::findings_if(unformatted))
+}
+
+/// Emits the `-v` event recording how long a file took and whether it
+/// changed.
+pub(crate) fn log_processed(path: &Path, changed: bool, started: Instant) {
+    info!(
+        path = %path.display(),
+        changed,
+        elapsed_ms = started.elapsed().as_millis(),
+        "file processed"
+    );
+}
+
+/// Writes the `--report-file` report for a batch of files.
+pub(crate) fn write_file_report(
+    report_path: &Path,
+    files: &[PathBuf],
+    results: &[anyhow::Result<FileOutcome>],
+) -> anyhow::Result<()> {
+    let entries: Vec<FileReport<'_>> = files
+        .iter()
+        .zip(results)
+        .map(|(path, result)| FileReport {
+            path,
+            outcome: result
+                .as_ref()
+                .map(|outcome| outcome.changes.as_slice())
+                .map_err(|err| format!("{err:#}")),
+        })
+        .collect();
+    write_report(report_path, &entries)
+}
+
+/// Writes the `--emit-anchors` map for a batch of files.
+pub(crate) fn write_file_anchors(
+    anchors_path: &Path,
+    files: &[PathBuf],
+    results: &[anyhow::Result<FileOutcome>],
+) -> anyhow::Result<()> {
+    let entries: Vec<FileAnchors<'_>> = files
+        .iter()
+        .zip(results)
+        .map(|(path, result)| FileAnchors {
+            path,
+            outcome: result
+                .as_ref()
+                .map(|outcome| outcome.anchors.as_slice())
+                .map_err(|err| format!("{err:#}")),
+        })
+        .collect();
+    write_anchors(anchors_path, &entries)
+}
+
+/// Prints the `--format json` summary for a batch of files.
+pub(crate) fn print_file_summary(
+    files: &[PathBuf],
+    results: &[anyhow::Result<FileOutcome>],
+) -> anyhow::Result<()> {
+    let entries: Vec<FileSummary<'_>> = files
+        .iter()
+        .zip(results)
+        .map(|(path, result)| FileSummary {
+            path,
+            outcome: result
+                .as_ref()
+                .map(|outcome| (outcome.changed, outcome.changes.as_slice()))
+                .map_err(|err| format!("{err:#}")),
+        })
+        .collect();
+    print_summary(&entries)
+}
+
+/// Passes each successful result to `on_ok`, printing every error, and
+/// returns the first error once all results are handled.
+pub(crate) fn report_results<T, F>(
+    results: Vec<anyhow::Result<T>>,
+    mut on_ok: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(T),
+{
+    let mut first_err: Option<anyhow::Error> = None;
+    for res in results {
+        match res {
+            Ok(val) => on_ok(val),
+            Err(e) => {
+                eprintln!("{e}");
+                if first_err.is_none() {
+                    first_err = Some(e);
+                }
+            }
+        }
+    }
+    if let Some(err) = first_err {
+        Err(err)
+    } else {
+        Ok(())
+    }
+}
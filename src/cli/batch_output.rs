@@ -0,0 +1,42 @@
+//! Standard output for a batch run.
+
+/// Gathered output is written once it reaches this many bytes.
+const FLUSH_BYTES: usize = 1 << 20;
+
+/// Standard output for a batch: printed as it comes, or with `--bulk`
+/// gathered and written in large blocks, which the terminal or pipe reading
+/// it handles far faster than a write per line.
+pub(crate) struct BatchOutput {
+    pending: Option<String>,
+}
+
+impl BatchOutput {
+    pub(crate) fn new(bulk: bool) -> Self {
+        Self {
+            pending: bulk.then(String::new),
+        }
+    }
+
+    pub(crate) fn print(&mut self, text: &str) {
+        match &mut self.pending {
+            Some(pending) => {
+                pending.push_str(text);
+                if pending.len() >= FLUSH_BYTES {
+                    self.flush();
+                }
+            }
+            None => print!("{text}"),
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(pending) = &mut self.pending {
+            print!("{pending}");
+            pending.clear();
+        }
+    }
+}
+
+impl Drop for BatchOutput {
+    fn drop(&mut self) { self.flush(); }
+}
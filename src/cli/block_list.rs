@@ -0,0 +1,47 @@
+//! `--list-blocks` mode: print the IDs of the constructs in each document.
+//!
+//! Each table, fence, and heading is printed on stdout as
+//! `PATH:START-END: ID`, for example `README.md:12-15: table #2`, so the ID
+//! quoted in a bug report or script can be matched to its lines. Files are
+//! listed as read, before any formatting, and are left unchanged.
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use mdtablefix::block_ids::number_blocks;
+
+/// Lists the constructs of every file in `files`, or of stdin under
+/// `stdin_label` when one is given.
+///
+/// # Errors
+/// Returns an error when a file or stdin cannot be read.
+pub(crate) fn run(files: &[PathBuf], stdin_label: Option<&Path>) -> anyhow::Result<()> {
+    if let Some(label) = stdin_label {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        print_blocks(label, &input);
+    }
+    for path in files {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        print_blocks(path, &content);
+    }
+    Ok(())
+}
+
+fn print_blocks(label: &Path, content: &str) {
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+    for block in number_blocks(&lines) {
+        println!(
+            "{}:{}-{}: {}",
+            label.display(),
+            block.start,
+            block.end,
+            block.id
+        );
+    }
+}
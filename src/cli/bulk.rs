@@ -0,0 +1,371 @@
+//! The `--bulk` fast path for formatting large, mostly clean trees.
+//!
+//! Most files in a formatted repository need no work, yet the normal path
+//! still decodes, splits, and runs every stage over each of them. With
+//! `--bulk`, [`Bulk::skip_untouched`] memory-maps each file and runs a [`Prescan`]
+//! over the raw bytes first. A file with no pipe, no `<table>`, no line the
+//! wrap stage would touch, and none of the constructs the other enabled
+//! transforms look for cannot change, so it is reported as formatted without
+//! being processed or written. Any other file takes the normal path.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+    time::Instant,
+};
+
+use anyhow::Context;
+use mdtablefix::{Options, document::LineEnding};
+use memmap2::Mmap;
+use regex::bytes::Regex;
+use tracing::debug;
+
+use crate::{
+    batch::{FileMode, FileOutcome, log_processed},
+    format_opts::FormatOpts,
+    layout::Layout,
+    output_dir::write_copy,
+};
+
+/// The triggers the enabled transforms look for, from which a [`Prescan`]
+/// is compiled.
+#[derive(PartialEq, Eq, Hash)]
+struct Triggers {
+    /// An alternation matching any trigger.
+    pattern: String,
+    /// The width beyond which the wrap stage rewraps a line.
+    wrap_width: Option<usize>,
+}
+
+impl Triggers {
+    /// Collects the triggers for the transforms `opts` enables, or returns
+    /// `None` when one of them has no cheap test, such as footnote
+    /// conversion, which looks at every number.
+    fn new(opts: &FormatOpts) -> Option<Self> {
+        let options = Options::from(opts);
+        if options.rejoin
+            || options.footnotes
+            || options.rst_tables
+            || options.hard_breaks.is_some()
+            || options.sentence_spacing.is_some()
+            || opts.includes.is_some()
+            || opts.fix_titles
+            || opts.renumber
+            || opts.list_marker_spacing.is_some()
+            || opts.heading_spacing.is_some()
+            || opts.max_consecutive_blanks.is_some()
+            || has_plugins(opts)
+        {
+            return None;
+        }
+        // Table reflow and HTML table conversion always run.
+        let mut triggers = vec![r"\|".to_string(), "(?i:<table)".to_string()];
+        let mut needles: Vec<&str> = Vec::new();
+        if options.ellipsis {
+            needles.push("...");
+        }
+        if options.fences {
+            needles.extend(["```", "~~~"]);
+        }
+        if options.code_spans || options.code_emphasis {
+            needles.push("`");
+        }
+        if options.emphasis_headings.is_some() {
+            needles.extend(["**", "__"]);
+        }
+        if options.footnote_refs.is_some() {
+            needles.push("[^");
+        }
+        if opts.strip_renumber_comments {
+            needles.push("<!--");
+        }
+        triggers.extend(needles.into_iter().map(regex::escape));
+        let mut line_starts = String::new();
+        if options.headings {
+            line_starts.push_str("=-");
+        }
+        if opts.breaks {
+            line_starts.push_str("*_-");
+        }
+        if !line_starts.is_empty() {
+            triggers.push(format!("(?m:^[ \\t]*[{}])", regex::escape(&line_starts)));
+        }
+        if options.wrap {
+            // Wrapping joins the lines of a paragraph, trims trailing
+            // whitespace, and measures tabs by their expansion.
+            triggers.push(r"(?m:^[ \t]*[^ \t\r\n][^\n]*\n[ \t]*[^ \t\r\n])".to_string());
+            triggers.push(r"[ \t]\r?\n|\t".to_string());
+        }
+        Some(Self {
+            pattern: triggers.join("|"),
+            wrap_width: options.wrap.then_some(options.wrap_width),
+        })
+    }
+}
+
+/// A byte-level test for text that an enabled transform might change.
+///
+/// The test is conservative: it may flag a file that formats to itself,
+/// but never passes one that would change.
+struct Prescan {
+    /// Matches a trigger in a file whose lines end with `\n`.
+    lf: Regex,
+    /// Matches a trigger in a file whose lines end with `\r\n`.
+    crlf: Regex,
+    /// The width beyond which the wrap stage rewraps a line.
+    wrap_width: Option<usize>,
+}
+
+impl Prescan {
+    fn compile(triggers: &Triggers) -> Self {
+        let compile = |line_ending: &str| {
+            Regex::new(&format!("(?-u:{}|{line_ending})", triggers.pattern))
+                .expect("pre-scan pattern should compile")
+        };
+        Self {
+            // A line ending unlike the first one would be rewritten.
+            lf: compile(r"\r\n"),
+            crlf: compile(r"(?:^|[^\r])\n"),
+            wrap_width: triggers.wrap_width,
+        }
+    }
+
+    /// Returns `true` unless formatting `bytes` and writing them with the
+    /// line ending and final newline `layout` chooses is certain to leave
+    /// them unchanged.
+    fn may_change(&self, bytes: &[u8], layout: Layout) -> bool {
+        let terminated = bytes.ends_with(b"\n");
+        if !bytes.is_empty() && layout.final_newline.resolve(terminated) != terminated {
+            return true;
+        }
+        let first_line = bytes
+            .split(|&byte| byte == b'\n')
+            .next()
+            .unwrap_or_default();
+        let crlf = layout
+            .line_ending
+            .map_or(first_line.ends_with(b"\r"), |ending| {
+                ending == LineEnding::CrLf
+            });
+        let triggers = if crlf { &self.crlf } else { &self.lf };
+        triggers.is_match(bytes)
+            || self.wrap_width.is_some_and(|width| {
+                // A character is never wider than its UTF-8 encoding is long,
+                // so a line within `width` bytes fits.
+                bytes
+                    .split(|&byte| byte == b'\n')
+                    .any(|line| line.strip_suffix(b"\r").unwrap_or(line).len() > width)
+            })
+    }
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn has_plugins(opts: &FormatOpts) -> bool { !opts.plugins.is_empty() }
+
+#[cfg(not(feature = "wasm-plugins"))]
+fn has_plugins(_opts: &FormatOpts) -> bool { false }
+
+/// The pre-scans a `--bulk` run has compiled, shared by its workers.
+///
+/// Files usually share a handful of option sets, so each scan is compiled
+/// once rather than per file.
+#[derive(Default)]
+pub(crate) struct Bulk {
+    scans: Mutex<HashMap<Triggers, Arc<Prescan>>>,
+}
+
+impl Bulk {
+    fn prescan(&self, opts: &FormatOpts) -> Option<Arc<Prescan>> {
+        let triggers = Triggers::new(opts)?;
+        let mut scans = self.scans.lock().unwrap_or_else(PoisonError::into_inner);
+        let scan = scans
+            .entry(triggers)
+            .or_insert_with_key(|triggers| Arc::new(Prescan::compile(triggers)));
+        Some(Arc::clone(scan))
+    }
+
+    /// Reports `path` as formatted without processing it when the pre-scan
+    /// shows no enabled transform can change it, printing or copying its
+    /// text as `mode` requires. Returns `None` when the file needs the normal
+    /// path, including files that are not valid UTF-8, which that path
+    /// decodes or rejects according to `--encoding`.
+    ///
+    /// # Errors
+    /// Returns an error when the file cannot be mapped or its copy cannot be
+    /// written.
+    pub(crate) fn skip_untouched(
+        &self,
+        path: &Path,
+        mode: FileMode<'_>,
+        opts: &FormatOpts,
+        layout: Layout,
+    ) -> anyhow::Result<Option<FileOutcome>> {
+        let Some(prescan) = self.prescan(opts) else {
+            return Ok(None);
+        };
+        let started = Instant::now();
+        let file = File::open(path).with_context(|| format!("reading {}", path.display()))?;
+        // SAFETY: the map is only read while this function runs. A file
+        // changed by another process meanwhile can at worst yield a stale
+        // answer, as a read racing the same write would.
+        let map =
+            unsafe { Mmap::map(&file) }.with_context(|| format!("reading {}", path.display()))?;
+        let Ok(text) = std::str::from_utf8(&map) else {
+            return Ok(None);
+        };
+        if prescan.may_change(text.as_bytes(), layout) {
+            return Ok(None);
+        }
+        debug!(path = %path.display(), "skipped by pre-scan");
+        let output = match mode {
+            FileMode::Print => Some(text.to_string()),
+            FileMode::OutputDir(dir) => {
+                write_copy(dir, path, text.as_bytes())?;
+                None
+            }
+            FileMode::Diff | FileMode::InPlace { .. } | FileMode::Check => None,
+        };
+        log_processed(path, false, started);
+        Ok(Some(FileOutcome {
+            output,
+            changed: false,
+            changes: Vec::new(),
+            anchors: Vec::new(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for the `--bulk` pre-scan.
+
+    use std::{fs, path::Path};
+
+    use clap::Parser;
+    use mdtablefix::document::Document;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{format_values::FinalNewlineArg, layout::render, pipeline::process_lines};
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Flags {
+        #[command(flatten)]
+        opts: FormatOpts,
+    }
+
+    fn opts(args: &[&str]) -> FormatOpts {
+        let mut opts = Flags::try_parse_from(args).expect("flags parse").opts;
+        opts.apply_toggles().expect("toggles apply");
+        opts
+    }
+
+    fn prescan(opts: &FormatOpts) -> Option<Prescan> {
+        Triggers::new(opts).as_ref().map(Prescan::compile)
+    }
+
+    const KEEP: Layout = Layout {
+        line_ending: None,
+        final_newline: FinalNewlineArg::Keep,
+    };
+
+    fn formats_to_itself(text: &str, opts: &FormatOpts) -> bool {
+        let source = Document::parse(text);
+        let fixed =
+            process_lines(&source.lines, Some(Path::new("doc.md")), opts, None).expect("formats");
+        render(&source, &fixed) == text
+    }
+
+    #[rstest]
+    #[case::plain(&[], "# Title\n\nSome text.\n", false)]
+    #[case::pipe(&[], "a | b\n", true)]
+    #[case::html_table(&[], "<TABLE><tr><td>a</td></tr></TABLE>\n", true)]
+    #[case::unterminated(&[], "text", false)]
+    #[case::empty(&[], "", false)]
+    #[case::mixed_endings(&[], "a\r\n\nb\n", true)]
+    #[case::crlf(&[], "a\r\n\r\nb\r\n", false)]
+    #[case::ellipsis(&["--ellipsis"], "Wait...\n", true)]
+    #[case::dots_without_ellipsis(&[], "Wait...\n", false)]
+    #[case::setext(&["--headings"], "Title\n=====\n", true)]
+    #[case::short_paragraph(&["--wrap"], "One line.\n\nAnother.\n", false)]
+    #[case::joined_paragraph(&["--wrap"], "One\nline.\n", true)]
+    #[case::long_line(&["--wrap", "--wrap-width", "10"], "eleven char\n", true)]
+    #[case::trailing_space(&["--wrap"], "text \n", true)]
+    fn flags_files_that_may_change(
+        #[case] args: &[&str],
+        #[case] text: &str,
+        #[case] expected: bool,
+    ) {
+        let prescan = prescan(&opts(args)).expect("scan is supported");
+        assert_eq!(prescan.may_change(text.as_bytes(), KEEP), expected);
+    }
+
+    #[rstest]
+    #[case::lf_kept("a\n", LineEnding::Lf, false)]
+    #[case::lf_converted("a\n", LineEnding::CrLf, true)]
+    #[case::crlf_kept("a\r\n", LineEnding::CrLf, false)]
+    #[case::crlf_converted("a\r\n", LineEnding::Lf, true)]
+    fn flags_files_a_forced_line_ending_would_convert(
+        #[case] text: &str,
+        #[case] ending: LineEnding,
+        #[case] expected: bool,
+    ) {
+        let prescan = prescan(&opts(&[])).expect("scan is supported");
+        let layout = Layout {
+            line_ending: Some(ending),
+            ..KEEP
+        };
+        assert_eq!(prescan.may_change(text.as_bytes(), layout), expected);
+    }
+
+    #[rstest]
+    #[case::always_kept("a\n", FinalNewlineArg::Always, false)]
+    #[case::always_added("a", FinalNewlineArg::Always, true)]
+    #[case::always_empty("", FinalNewlineArg::Always, false)]
+    #[case::never_kept("a", FinalNewlineArg::Never, false)]
+    #[case::never_removed("a\n", FinalNewlineArg::Never, true)]
+    fn flags_files_a_final_newline_policy_would_change(
+        #[case] text: &str,
+        #[case] final_newline: FinalNewlineArg,
+        #[case] expected: bool,
+    ) {
+        let prescan = prescan(&opts(&[])).expect("scan is supported");
+        let layout = Layout {
+            final_newline,
+            ..KEEP
+        };
+        assert_eq!(prescan.may_change(text.as_bytes(), layout), expected);
+    }
+
+    #[rstest]
+    #[case::footnotes(&["--footnotes"])]
+    #[case::renumber(&["--renumber"])]
+    #[case::all(&["--all"])]
+    fn transforms_without_a_cheap_test_disable_the_scan(#[case] args: &[&str]) {
+        assert!(prescan(&opts(args)).is_none());
+    }
+
+    #[rstest]
+    #[case::defaults(&[])]
+    #[case::wrap(&["--wrap"])]
+    #[case::narrow_wrap(&["--wrap", "--wrap-width", "40"])]
+    #[case::inline(&["--ellipsis", "--fences", "--code-spans", "--code-emphasis"])]
+    #[case::headings(&["--headings", "--emphasis-headings", "2", "--breaks"])]
+    fn never_skips_a_fixture_that_would_change(#[case] args: &[&str]) {
+        let opts = opts(args);
+        let prescan = prescan(&opts).expect("scan is supported");
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data");
+        for entry in fs::read_dir(dir).expect("fixtures are listed") {
+            let path = entry.expect("fixture entry").path();
+            let Ok(text) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if !prescan.may_change(text.as_bytes(), KEEP) {
+                assert!(formats_to_itself(&text, &opts), "{}", path.display());
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! The TOML config file read by the command-line tool.
+//!
+//! Each input uses the `.mdtablefix.toml` nearest to it, found by searching
+//! its directory and then each parent in turn, so different trees of a
+//! monorepo can carry different settings. Standard input and the daemon
+//! search from the working directory, or from the `--stdin-filepath`
+//! directory. `--config PATH` names one file for every input instead. The file
+//! can define `[defaults]` and `[presets.NAME]` or `[profile.NAME]` tables (see
+//! [`crate::presets`]) and one table per transform, named as in
+//! `--report-file` reports, listing the block types that transform must
+//! leave untouched:
+//!
+//! ```toml
+//! [ellipsis]
+//! exclude = ["footnote-definition", "blockquote"]
+//! ```
+//!
+//! A top-level `opaque-tokens` array lists regular expressions for template
+//! placeholders that wrapping and table parsing must keep whole.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use anyhow::{Context, anyhow, bail};
+use mdtablefix::{
+    Options,
+    OptionsWarning,
+    blocks::{BlockExclusions, BlockType, supports_exclusion},
+    changes::Transform,
+    opaque::OpaqueTokens,
+};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::{
+    format_opts::FormatOpts,
+    presets::{Preset, apply_preset},
+};
+
+/// Config file searched for when `--config` is absent.
+const DEFAULT_CONFIG: &str = ".mdtablefix.toml";
+
+/// The parsed config file.
+#[derive(Default, Deserialize)]
+pub(crate) struct Config {
+    /// Flags enabled for every run, unless the selected preset sets them.
+    #[serde(default)]
+    pub(crate) defaults: Preset,
+    /// Preset definitions and overrides, by name, from `[presets.NAME]` or
+    /// `[profile.NAME]` tables.
+    #[serde(default, alias = "profile")]
+    pub(crate) presets: BTreeMap<String, Preset>,
+    /// Regular expressions matching text to keep whole.
+    #[serde(default, rename = "opaque-tokens")]
+    opaque_tokens: Vec<String>,
+    /// Per-transform settings, keyed by transform name.
+    #[serde(flatten)]
+    transforms: BTreeMap<String, TransformConfig>,
+}
+
+/// Settings for one transform.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransformConfig {
+    /// Names of the block types the transform skips.
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+impl Config {
+    /// Resolves the `exclude` lists of every transform table.
+    ///
+    /// # Errors
+    /// Returns an error naming the offending table when a transform or block
+    /// type is unknown, or when the transform cannot skip blocks.
+    pub(crate) fn block_exclusions(&self) -> anyhow::Result<BlockExclusions> {
+        self.transforms.iter().try_fold(
+            BlockExclusions::default(),
+            |exclusions, (name, settings)| {
+                let transform = Transform::from_name(name)
+                    .ok_or_else(|| anyhow!("unknown config table `[{name}]`"))?;
+                if !settings.exclude.is_empty() && !supports_exclusion(transform) {
+                    bail!(
+                        "`[{name}]` cannot exclude blocks; only `code-spans`, `code-emphasis`, \
+                         `hard-breaks`, `sentence-spacing`, and `ellipsis` can"
+                    );
+                }
+                let blocks = settings
+                    .exclude
+                    .iter()
+                    .map(|block| block.parse::<BlockType>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| anyhow!("`[{name}]` exclude: {err}"))?;
+                Ok(exclusions.with(transform, blocks))
+            },
+        )
+    }
+
+    /// Compiles the `opaque-tokens` patterns.
+    ///
+    /// # Errors
+    /// Returns an error naming the first pattern that fails to compile.
+    pub(crate) fn opaque_tokens(&self) -> anyhow::Result<OpaqueTokens> {
+        OpaqueTokens::new(&self.opaque_tokens).context("`opaque-tokens`")
+    }
+}
+
+/// Reads and parses the config file at `path`.
+///
+/// # Errors
+/// Returns an error when the file cannot be read or parsed.
+fn read_config(path: &Path) -> anyhow::Result<Config> {
+    let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+}
+
+/// Applies `config`, read from `path` if any, with its defaults and the preset
+/// named `preset`, to a copy of the command-line options `base`, then
+/// resolves `--all` and `--disable`. Option combinations that
+/// [`Options::validate`] reports are logged as warnings, or at `-v` when
+/// they are only notes. `--renumber` also writes renumber comments, for list
+/// items, so [`OptionsWarning::RenumberCommentsWithoutFootnotes`] is dropped
+/// with it.
+fn configure(
+    base: &FormatOpts,
+    preset: Option<&str>,
+    config: &Config,
+    path: Option<&Path>,
+) -> anyhow::Result<FormatOpts> {
+    let mut opts = base.clone();
+    apply_preset(preset, config, &mut opts)?;
+    opts.block_exclusions = config.block_exclusions()?;
+    opts.opaque_tokens = config.opaque_tokens()?;
+    opts.apply_toggles()?;
+    let warnings = Options::from(&opts)
+        .validate()
+        .into_iter()
+        .filter(|warning| {
+            !(opts.renumber && *warning == OptionsWarning::RenumberCommentsWithoutFootnotes)
+        });
+    for warning in warnings {
+        match (path, warning.is_note()) {
+            (Some(path), true) => info!(config = %path.display(), "{warning}"),
+            (Some(path), false) => warn!(config = %path.display(), "{warning}"),
+            (None, true) => info!("{warning}"),
+            (None, false) => warn!("{warning}"),
+        }
+    }
+    Ok(opts)
+}
+
+/// Resolves the options for each input from the config file nearest to it.
+///
+/// The nearest config of every directory searched, and the options built
+/// from every config read, are cached, so a batch reads each config file
+/// once however many inputs share it.
+pub(crate) struct ConfigResolver<'a> {
+    base: &'a FormatOpts,
+    preset: Option<&'a str>,
+    /// The options built from `--config`, which apply to every input.
+    explicit: Option<Arc<FormatOpts>>,
+    cache: Mutex<ConfigCache>,
+}
+
+#[derive(Default)]
+struct ConfigCache {
+    /// The nearest config file of each directory searched, if any.
+    nearest: HashMap<PathBuf, Option<PathBuf>>,
+    /// The options built from each config file, or from none.
+    opts: HashMap<Option<PathBuf>, Arc<FormatOpts>>,
+}
+
+impl ConfigCache {
+    fn nearest(&mut self, dir: &Path) -> Option<PathBuf> {
+        if let Some(found) = self.nearest.get(dir) {
+            return found.clone();
+        }
+        let candidate = dir.join(DEFAULT_CONFIG);
+        let found = if candidate.is_file() {
+            Some(candidate)
+        } else {
+            dir.parent().and_then(|parent| self.nearest(parent))
+        };
+        self.nearest.insert(dir.to_path_buf(), found.clone());
+        found
+    }
+}
+
+impl<'a> ConfigResolver<'a> {
+    /// Builds a resolver for the command-line options `base`, reading the
+    /// `--config` file named by `explicit` at once when one is given.
+    ///
+    /// # Errors
+    /// Returns an error when the `--config` file cannot be read or parsed,
+    /// or its settings or `preset` are invalid.
+    pub(crate) fn new(
+        base: &'a FormatOpts,
+        preset: Option<&'a str>,
+        explicit: Option<&Path>,
+    ) -> anyhow::Result<Self> {
+        let explicit = explicit
+            .map(|path| configure(base, preset, &read_config(path)?, Some(path)).map(Arc::new))
+            .transpose()?;
+        Ok(Self {
+            base,
+            preset,
+            explicit,
+            cache: Mutex::default(),
+        })
+    }
+
+    /// Returns the options for the file at `path`.
+    ///
+    /// # Errors
+    /// Returns an error when the nearest config file cannot be read or
+    /// parsed, or its settings or the preset are invalid.
+    pub(crate) fn for_file(&self, path: &Path) -> anyhow::Result<Arc<FormatOpts>> {
+        let path =
+            std::path::absolute(path).with_context(|| format!("resolving {}", path.display()))?;
+        self.for_dir(path.parent().unwrap_or(&path))
+    }
+
+    /// Returns the options for input read in the working directory.
+    ///
+    /// # Errors
+    /// As for [`Self::for_file`], or when the working directory is
+    /// unavailable.
+    pub(crate) fn for_working_dir(&self) -> anyhow::Result<Arc<FormatOpts>> {
+        self.for_dir(&env::current_dir().context("reading the working directory")?)
+    }
+
+    fn for_dir(&self, dir: &Path) -> anyhow::Result<Arc<FormatOpts>> {
+        if let Some(opts) = &self.explicit {
+            return Ok(Arc::clone(opts));
+        }
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        let path = cache.nearest(dir);
+        if let Some(opts) = cache.opts.get(&path) {
+            return Ok(Arc::clone(opts));
+        }
+        let config = path
+            .as_deref()
+            .map(read_config)
+            .transpose()?
+            .unwrap_or_default();
+        let opts = Arc::new(configure(self.base, self.preset, &config, path.as_deref())?);
+        cache.opts.insert(path, Arc::clone(&opts));
+        Ok(opts)
+    }
+}
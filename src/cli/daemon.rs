@@ -0,0 +1,381 @@
+//! Long-running formatting daemon for editor integrations.
+//!
+//! Editors that format on every save pay the process start-up cost each
+//! time they shell out to `mdtablefix`. With `--daemon SOCKET` the binary
+//! instead listens on a Unix domain socket and answers formatting requests
+//! until it is terminated.
+//!
+//! Every message in either direction is a frame: a four-byte big-endian
+//! length followed by that many bytes of UTF-8 JSON. A connection may carry
+//! any number of request/response pairs. A request is an object with
+//!
+//! - `content`: Markdown text to format, or
+//! - `path`: a file to read when `content` is absent, and
+//! - `args`: optional formatting flags such as `["--wrap", "--renumber"]`. When omitted, the flags
+//!   given alongside `--daemon` apply.
+//!
+//! Each request is formatted with the config file nearest to its `path`, or
+//! to the daemon's working directory when it has none, as a batch run
+//! resolves one per file.
+//!
+//! The response is `{"ok": true, "output": "..."}` on success or
+//! `{"ok": false, "error": "..."}` when the request cannot be served.
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use anyhow::{Context, anyhow, bail};
+use clap::Parser;
+use mdtablefix::document::Document;
+use serde_json::{Value, json};
+
+use crate::{
+    config::ConfigResolver,
+    format_opts::FormatOpts,
+    layout::render,
+    panic_isolation::catch_file_panic,
+    pipeline::process_lines,
+};
+
+/// Upper bound for a single frame, protecting the daemon from runaway
+/// allocations caused by corrupt length prefixes.
+const MAX_FRAME_LEN: u32 = 64 * 1024 * 1024;
+
+/// Formatting flags accepted in a request's `args` array.
+#[derive(Parser)]
+#[command(no_binary_name = true)]
+struct RequestArgs {
+    #[command(flatten)]
+    opts: FormatOpts,
+}
+
+/// Reads one length-prefixed frame, returning `None` on a clean end of
+/// stream before the length prefix.
+///
+/// # Errors
+/// Returns an error for truncated frames or frames above [`MAX_FRAME_LEN`].
+pub(crate) fn read_frame(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut len_bytes = [0_u8; 4];
+    match reader.read_exact(&mut len_bytes) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = u32::from_be_bytes(len_bytes);
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {len} bytes exceeds the {MAX_FRAME_LEN} byte limit"),
+        ));
+    }
+    let mut payload = vec![0_u8; len as usize];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+/// Writes `payload` as one length-prefixed frame.
+///
+/// # Errors
+/// Returns an error if the payload is too large or the write fails.
+pub(crate) fn write_frame(writer: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(payload.len())
+        .ok()
+        .filter(|len| *len <= MAX_FRAME_LEN)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "response frame too large"))?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Answers one request payload, never failing: errors become `ok: false`
+/// responses so a bad request cannot take the daemon down.
+pub(crate) fn respond(payload: &[u8], configs: &ConfigResolver<'_>) -> Value {
+    match format_request(payload, configs) {
+        Ok(output) => json!({ "ok": true, "output": output }),
+        Err(err) => json!({ "ok": false, "error": format!("{err:#}") }),
+    }
+}
+
+fn format_request(payload: &[u8], configs: &ConfigResolver<'_>) -> anyhow::Result<String> {
+    let request: Value = serde_json::from_slice(payload).context("parsing request JSON")?;
+    let origin = request.get("path").and_then(Value::as_str).map(Path::new);
+    let defaults = match origin {
+        Some(path) => configs.for_file(path)?,
+        None => configs.for_working_dir()?,
+    };
+    let opts = request_opts(&request, &defaults)?;
+    let (content, label) = request_content(&request)?;
+    catch_file_panic(Path::new(&label), || {
+        let source = Document::parse(&content);
+        let fixed = process_lines(&source.lines, origin, &opts, None)?;
+        Ok(render(&source, &fixed))
+    })
+}
+
+fn request_opts(request: &Value, defaults: &FormatOpts) -> anyhow::Result<FormatOpts> {
+    let args = match request.get("args") {
+        None | Some(Value::Null) => return Ok(defaults.clone()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow!("`args` must contain only strings"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        Some(_) => bail!("`args` must be an array of strings"),
+    };
+    let mut parsed = RequestArgs::try_parse_from(args).context("parsing request `args`")?;
+    parsed.opts.apply_toggles()?;
+    // Config-file settings have no flags, so requests inherit them.
+    parsed.opts.block_exclusions = defaults.block_exclusions;
+    parsed.opts.opaque_tokens = defaults.opaque_tokens.clone();
+    Ok(parsed.opts)
+}
+
+fn request_content(request: &Value) -> anyhow::Result<(String, String)> {
+    if let Some(content) = request.get("content").and_then(Value::as_str) {
+        let label = request
+            .get("path")
+            .and_then(Value::as_str)
+            .unwrap_or("<request>");
+        return Ok((content.to_string(), label.to_string()));
+    }
+    let path = request
+        .get("path")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("request needs a `content` or `path` string"))?;
+    let content = fs::read_to_string(path).with_context(|| format!("reading {path}"))?;
+    Ok((content, path.to_string()))
+}
+
+/// Serves one connection until the client closes it.
+///
+/// # Errors
+/// Returns an error when framing fails or the client disconnects mid-frame.
+pub(crate) fn serve_connection(
+    mut stream: impl Read + Write,
+    configs: &ConfigResolver<'_>,
+) -> io::Result<()> {
+    while let Some(payload) = read_frame(&mut stream)? {
+        let response = respond(&payload, configs).to_string();
+        write_frame(&mut stream, response.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Listens on `socket` and serves connections until the process is killed,
+/// formatting with the options `configs` resolves for each request unless it
+/// supplies its own `args`.
+///
+/// A leftover socket file from a previous daemon is replaced, but a socket
+/// that still accepts connections is left alone so two daemons never fight
+/// over one path.
+///
+/// # Errors
+/// Returns an error if the socket cannot be bound.
+#[cfg(unix)]
+pub(crate) fn run(socket: &Path, configs: &ConfigResolver<'_>) -> anyhow::Result<()> {
+    use std::os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    };
+
+    if fs::symlink_metadata(socket).is_ok_and(|meta| meta.file_type().is_socket()) {
+        if UnixStream::connect(socket).is_ok() {
+            bail!("{} is already served by another daemon", socket.display());
+        }
+        fs::remove_file(socket)
+            .with_context(|| format!("removing stale socket {}", socket.display()))?;
+    }
+    let listener =
+        UnixListener::bind(socket).with_context(|| format!("binding {}", socket.display()))?;
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    scope.spawn(move || {
+                        if let Err(err) = serve_connection(stream, configs) {
+                            eprintln!("daemon connection closed: {err}");
+                        }
+                    });
+                }
+                Err(err) => eprintln!("failed to accept daemon connection: {err}"),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Reports that daemon mode needs Unix domain sockets.
+///
+/// # Errors
+/// Always returns an error on platforms without Unix domain sockets.
+#[cfg(not(unix))]
+pub(crate) fn run(_socket: &Path, _configs: &ConfigResolver<'_>) -> anyhow::Result<()> {
+    bail!("--daemon requires Unix domain sockets, which this platform does not provide")
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for daemon framing and request handling.
+
+    use std::io::Cursor;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn flags(args: &[&str]) -> FormatOpts {
+        RequestArgs::try_parse_from(args)
+            .expect("flags should parse")
+            .opts
+    }
+
+    fn respond_with(payload: &[u8], defaults: &FormatOpts) -> Value {
+        let configs = ConfigResolver::new(defaults, None, None).expect("no config to read");
+        respond(payload, &configs)
+    }
+
+    fn respond_plain(payload: &[u8]) -> Value { respond_with(payload, &flags(&[])) }
+
+    /// An in-memory connection: requests are read from `input` and responses
+    /// written to `output`.
+    struct Connection {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for Connection {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.input.read(buf) }
+    }
+
+    impl Write for Connection {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.output.write(buf) }
+
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    fn frame(payload: &str) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, payload.as_bytes()).expect("frame should encode");
+        buf
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let mut reader = Cursor::new(frame("hello"));
+        assert_eq!(
+            read_frame(&mut reader).expect("frame should decode"),
+            Some(b"hello".to_vec())
+        );
+        assert_eq!(read_frame(&mut reader).expect("clean eof"), None);
+    }
+
+    #[test]
+    fn truncated_frame_is_an_error() {
+        let mut bytes = frame("hello");
+        bytes.truncate(6);
+        assert!(read_frame(&mut Cursor::new(bytes)).is_err());
+    }
+
+    #[test]
+    fn oversized_frame_is_rejected() {
+        let bytes = (MAX_FRAME_LEN + 1).to_be_bytes().to_vec();
+        let err = read_frame(&mut Cursor::new(bytes)).expect_err("limit should apply");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn formats_content_with_args() {
+        let response =
+            respond_plain(br#"{"content": "Title\n=====\n|a|b|\n", "args": ["--headings"]}"#);
+        assert_eq!(response["ok"], true);
+        assert_eq!(response["output"], "# Title\n| a | b |\n");
+    }
+
+    #[test]
+    fn reports_bad_requests_without_failing() {
+        for payload in [
+            &b"not json"[..],
+            br#"{"args": ["--wrap"]}"#,
+            br#"{"content": "x", "args": ["--no-such-flag"]}"#,
+            br#"{"content": "x", "args": "--wrap"}"#,
+        ] {
+            let response = respond_plain(payload);
+            assert_eq!(response["ok"], false, "payload {payload:?}");
+            assert!(response["error"].is_string());
+        }
+    }
+
+    #[test]
+    fn missing_args_fall_back_to_daemon_flags() {
+        let defaults = flags(&["--renumber"]);
+        let payload = br#"{"content": "1. a\n3. b"}"#;
+        assert_eq!(respond_with(payload, &defaults)["output"], "1. a\n2. b");
+        let explicit = br#"{"content": "1. a\n3. b", "args": []}"#;
+        assert_eq!(respond_with(explicit, &defaults)["output"], "1. a\n3. b");
+    }
+
+    #[test]
+    fn requests_use_the_config_nearest_their_path() {
+        let dir = tempdir().expect("temporary directory");
+        let docs = dir.path().join("docs");
+        fs::create_dir(&docs).expect("docs directory");
+        fs::write(
+            docs.join(".mdtablefix.toml"),
+            "[defaults]\nrenumber = true\n\n[ellipsis]\nexclude = [\"blockquote\"]\n",
+        )
+        .expect("config file");
+        let base = flags(&[]);
+        let configs = ConfigResolver::new(&base, None, None).expect("no config to read");
+        let request = |path: &Path, content: &str, args: &[&str]| {
+            let payload = json!({ "path": path, "content": content, "args": args });
+            respond(payload.to_string().as_bytes(), &configs)
+        };
+        let without_args = json!({ "path": docs.join("a.md"), "content": "1. a\n3. b" });
+        assert_eq!(
+            respond(without_args.to_string().as_bytes(), &configs)["output"],
+            "1. a\n2. b"
+        );
+        assert_eq!(
+            request(
+                &docs.join("b.md"),
+                "Wait...\n\n> Quoted...",
+                &["--ellipsis"]
+            )["output"],
+            "Wait…\n\n> Quoted..."
+        );
+        assert_eq!(
+            request(&dir.path().join("c.md"), "> Quoted...", &["--ellipsis"])["output"],
+            "> Quoted…"
+        );
+    }
+
+    #[test]
+    fn serves_multiple_requests_per_connection() {
+        let mut input = frame(r#"{"content": "|a|b|"}"#);
+        input.extend(frame(
+            r#"{"content": "1. a\n3. b", "args": ["--renumber"]}"#,
+        ));
+        let mut connection = Connection {
+            input: Cursor::new(input),
+            output: Vec::new(),
+        };
+        let base = flags(&[]);
+        let configs = ConfigResolver::new(&base, None, None).expect("no config to read");
+        serve_connection(&mut connection, &configs).expect("connection should close cleanly");
+        let mut reader = Cursor::new(connection.output);
+        let first: Value =
+            serde_json::from_slice(&read_frame(&mut reader).expect("io").expect("frame"))
+                .expect("json");
+        let second: Value =
+            serde_json::from_slice(&read_frame(&mut reader).expect("io").expect("frame"))
+                .expect("json");
+        assert_eq!(first["output"], "| a | b |");
+        assert_eq!(second["output"], "1. a\n2. b");
+    }
+}
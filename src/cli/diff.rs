@@ -0,0 +1,129 @@
+//! Unified diffs for `--diff`.
+//!
+//! Both sides of the header name the same path, so the output applies with
+//! `patch -p0` from the directory `mdtablefix` ran in. [`paint`] colours a
+//! rendered diff for a terminal as `--colour` asks.
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+    path::Path,
+};
+
+use similar::TextDiff;
+
+/// Renders the changes from `before` to `after` as a unified diff with three
+/// lines of context, or `None` when the texts are identical.
+pub(crate) fn unified_diff(path: &Path, before: &str, after: &str) -> Option<String> {
+    if before == after {
+        return None;
+    }
+    let label = path.display().to_string();
+    Some(
+        TextDiff::from_lines(before, after)
+            .unified_diff()
+            .context_radius(3)
+            .header(&label, &label)
+            .to_string(),
+    )
+}
+
+/// When `--diff` output is coloured, as chosen by `--colour`.
+#[derive(clap::ValueEnum, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ColourChoice {
+    /// Colour when stdout is a terminal and `NO_COLOR` is unset or empty
+    #[default]
+    Auto,
+    /// Always colour
+    Always,
+    /// Never colour
+    Never,
+}
+
+impl ColourChoice {
+    /// Returns whether output written to stdout should be coloured.
+    pub(crate) fn enabled(self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                io::stdout().is_terminal()
+                    && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+            }
+        }
+    }
+}
+
+const BOLD: &str = "\x1b[1m";
+const CYAN: &str = "\x1b[36m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Wraps each line of the unified diff `diff` in ANSI colours: the two file
+/// header lines in bold, hunk headers in cyan, additions in green, and
+/// removals in red. Context lines are left as they are.
+///
+/// Only the first two lines are taken as headers, so a removed line that
+/// itself starts with `--` is still shown as a removal.
+pub(crate) fn paint(diff: &str) -> String {
+    let mut out = String::with_capacity(diff.len() * 2);
+    for (index, line) in diff.split_inclusive('\n').enumerate() {
+        let (text, newline) = line
+            .strip_suffix('\n')
+            .map_or((line, ""), |text| (text, "\n"));
+        let style = if index < 2 {
+            Some(BOLD)
+        } else if text.starts_with("@@") {
+            Some(CYAN)
+        } else if text.starts_with('+') {
+            Some(GREEN)
+        } else if text.starts_with('-') {
+            Some(RED)
+        } else {
+            None
+        };
+        match style {
+            Some(style) => out.extend([style, text, RESET, newline]),
+            None => out.push_str(line),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for unified diff rendering.
+
+    use super::*;
+
+    #[test]
+    fn identical_texts_have_no_diff() {
+        assert_eq!(unified_diff(Path::new("a.md"), "x\n", "x\n"), None);
+    }
+
+    #[test]
+    fn renders_header_and_hunk() {
+        let diff = unified_diff(Path::new("a.md"), "keep\n|a|\n", "keep\n| a |\n");
+        assert_eq!(
+            diff.as_deref(),
+            Some("--- a.md\n+++ a.md\n@@ -1,2 +1,2 @@\n keep\n-|a|\n+| a |\n")
+        );
+    }
+
+    #[test]
+    fn marks_a_missing_final_newline() {
+        let diff = unified_diff(Path::new("a.md"), "text", "text\n").unwrap_or_default();
+        assert!(diff.contains("\\ No newline at end of file"), "{diff}");
+    }
+
+    #[test]
+    fn paints_headers_hunks_and_changed_lines() {
+        let diff = "--- a.md\n+++ a.md\n@@ -1,2 +1,2 @@\n keep\n---\n+***\n";
+        assert_eq!(
+            paint(diff),
+            "\x1b[1m--- a.md\x1b[0m\n\x1b[1m+++ a.md\x1b[0m\n\x1b[36m@@ -1,2 +1,2 @@\x1b[0m\n \
+             keep\n\x1b[31m---\x1b[0m\n\x1b[32m+***\x1b[0m\n"
+        );
+    }
+}
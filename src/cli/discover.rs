@@ -0,0 +1,362 @@
+//! Expansion of directory and glob arguments into Markdown files.
+//!
+//! Each path given on the command line is used as written when it names a
+//! file. A directory is walked recursively for `*.md` and `*.markdown` files,
+//! skipping hidden entries such as `.git` and, unless `--no-ignore` is
+//! given, paths matched by a `.gitignore` or [`IGNORE_FILE`] in the directory
+//! or one of its parents. An argument that does not exist but contains glob
+//! metacharacters is expanded as a pattern, so quoted patterns such as
+//! `'docs/**/*.md'` work on every shell. Results are sorted within each
+//! argument and de-duplicated across arguments, so a file is never rewritten
+//! twice by parallel workers.
+//!
+//! `--files-from` adds names read from a file or standard input, separated by
+//! NUL bytes when the list contains any (as `git ls-files -z` writes) and by
+//! newlines otherwise. They are expanded like command-line arguments.
+//!
+//! `--exclude` patterns drop files from the result, whether they were named
+//! explicitly or discovered. A pattern without a `/` matches any single path
+//! component, so `CHANGELOG.md` or `vendor` excludes that name anywhere. A
+//! pattern with a `/` matches the path, or one of its parent directories, as
+//! written on the command line. Excluded directories are not walked at all.
+
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fs,
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use glob::{MatchOptions, Pattern};
+use ignore::WalkBuilder;
+
+/// File extensions picked up when walking a directory.
+const MARKDOWN_EXTENSIONS: [&str; 2] = ["md", "markdown"];
+
+/// Name of the gitignore-syntax file listing paths only `mdtablefix` skips.
+pub(crate) const IGNORE_FILE: &str = ".mdtablefixignore";
+
+/// Glob options for `--exclude`: `*` stays within one path component, while
+/// `**` crosses directories.
+const EXCLUDE_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A parsed `--exclude` pattern.
+#[derive(Clone, Debug)]
+pub(crate) struct Exclude {
+    pattern: Pattern,
+    /// Match single path components rather than the whole path.
+    by_name: bool,
+}
+
+impl Exclude {
+    /// Returns `true` when `path` or one of its parent directories matches.
+    fn matches(&self, path: &Path) -> bool {
+        if self.by_name {
+            return path.components().any(|part| match part {
+                Component::Normal(name) => self
+                    .pattern
+                    .matches_with(&name.to_string_lossy(), EXCLUDE_OPTIONS),
+                _ => false,
+            });
+        }
+        let relative: PathBuf = path
+            .components()
+            .filter(|part| !matches!(part, Component::CurDir))
+            .collect();
+        relative
+            .ancestors()
+            .any(|dir| self.pattern.matches_path_with(dir, EXCLUDE_OPTIONS))
+    }
+}
+
+/// Parses an `--exclude` value for clap.
+pub(crate) fn parse_exclude(value: &str) -> Result<Exclude, String> {
+    let trimmed = value.trim_start_matches("./").trim_end_matches('/');
+    let pattern = Pattern::new(trimmed).map_err(|err| format!("invalid pattern: {err}"))?;
+    Ok(Exclude {
+        pattern,
+        by_name: !trimmed.contains('/'),
+    })
+}
+
+/// Reads the file names listed in `source`, or on standard input when
+/// `source` is `-`.
+///
+/// # Errors
+/// Returns an error when the list cannot be read.
+pub(crate) fn read_file_list(source: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let bytes = if source == Path::new("-") {
+        let mut bytes = Vec::new();
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .context("reading file list from standard input")?;
+        bytes
+    } else {
+        fs::read(source).with_context(|| format!("reading file list {}", source.display()))?
+    };
+    Ok(parse_file_list(&bytes))
+}
+
+/// Splits a file list on NUL bytes, or on newlines when it has none,
+/// skipping empty entries.
+pub(crate) fn parse_file_list(bytes: &[u8]) -> Vec<PathBuf> {
+    let separator = if bytes.contains(&0) { 0 } else { b'\n' };
+    bytes
+        .split(|&byte| byte == separator)
+        .map(|name| {
+            if separator == b'\n' {
+                name.strip_suffix(b"\r").unwrap_or(name)
+            } else {
+                name
+            }
+        })
+        .filter(|name| !name.is_empty())
+        .map(|name| PathBuf::from(os_string(name)))
+        .collect()
+}
+
+#[cfg(unix)]
+fn os_string(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStrExt;
+    std::ffi::OsStr::from_bytes(bytes).to_os_string()
+}
+
+#[cfg(not(unix))]
+fn os_string(bytes: &[u8]) -> OsString { String::from_utf8_lossy(bytes).into_owned().into() }
+
+/// Returns `true` when any of `excludes` matches `path`.
+pub(crate) fn is_excluded(path: &Path, excludes: &[Exclude]) -> bool {
+    excludes.iter().any(|exclude| exclude.matches(path))
+}
+
+/// Expands `args` into the list of files to process, leaving out any path
+/// matched by `excludes`. Directories are walked with the ignore files
+/// honoured when `ignore_files` is set.
+///
+/// # Errors
+/// Returns an error when a directory cannot be read, a glob pattern is
+/// malformed, or a pattern matches nothing.
+pub(crate) fn expand_paths(
+    args: &[PathBuf],
+    excludes: &[Exclude],
+    ignore_files: bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    for arg in args {
+        for path in expand_arg(arg, excludes, ignore_files)? {
+            if !is_excluded(&path, excludes) && seen.insert(path.clone()) {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn expand_arg(
+    arg: &Path,
+    excludes: &[Exclude],
+    ignore_files: bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    if arg.is_dir() {
+        return walk_markdown(arg, excludes, ignore_files);
+    }
+    let pattern = arg.to_string_lossy();
+    if arg.exists() || !pattern.contains(['*', '?', '[']) {
+        return Ok(vec![arg.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    for entry in glob::glob(&pattern).with_context(|| format!("invalid pattern `{pattern}`"))? {
+        let path = entry.with_context(|| format!("expanding `{pattern}`"))?;
+        if path.is_dir() {
+            files.extend(walk_markdown(&path, excludes, ignore_files)?);
+        } else {
+            files.push(path);
+        }
+    }
+    if files.is_empty() {
+        bail!("no files match `{pattern}`");
+    }
+    Ok(files)
+}
+
+/// Lists the Markdown files below `dir` in sorted order, without descending
+/// into hidden or excluded directories, or into ignored ones when
+/// `ignore_files` is set.
+fn walk_markdown(
+    dir: &Path,
+    excludes: &[Exclude],
+    ignore_files: bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let excludes = excludes.to_vec();
+    let mut builder = WalkBuilder::new(dir);
+    builder
+        .standard_filters(ignore_files)
+        .hidden(true)
+        .ignore(false)
+        .require_git(false)
+        .sort_by_file_name(Ord::cmp)
+        .filter_entry(move |entry| entry.depth() == 0 || !is_excluded(entry.path(), &excludes));
+    if ignore_files {
+        builder.add_custom_ignore_filename(IGNORE_FILE);
+    }
+    let mut files = Vec::new();
+    for entry in builder.build() {
+        let entry = entry.with_context(|| format!("walking {}", dir.display()))?;
+        if entry.file_type().is_some_and(|kind| kind.is_file()) && is_markdown(entry.path()) {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+/// Returns `true` when `path` has one of the [`MARKDOWN_EXTENSIONS`].
+pub(crate) fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| MARKDOWN_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for path expansion.
+
+    use std::fs;
+
+    use rstest::rstest;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn walks_directories_for_markdown() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("b/.hidden"))?;
+        for name in [
+            "z.md",
+            "a.markdown",
+            "notes.txt",
+            "b/c.MD",
+            "b/.hidden/d.md",
+        ] {
+            fs::write(root.join(name), "text\n")?;
+        }
+        let files = expand_paths(&[root.to_path_buf()], &[], true)?;
+        assert_eq!(
+            files,
+            [
+                root.join("a.markdown"),
+                root.join("b/c.MD"),
+                root.join("z.md")
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn expands_globs_and_deduplicates() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::write(root.join("one.md"), "")?;
+        fs::write(root.join("two.md"), "")?;
+        let pattern = root.join("*.md");
+        let files = expand_paths(&[root.join("two.md"), pattern], &[], true)?;
+        assert_eq!(files, [root.join("two.md"), root.join("one.md")]);
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_plain_paths_and_rejects_empty_globs() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let missing = dir.path().join("missing.md");
+        assert_eq!(
+            expand_paths(std::slice::from_ref(&missing), &[], true)?,
+            [missing]
+        );
+        let err = expand_paths(&[dir.path().join("*.md")], &[], true)
+            .err()
+            .map(|err| err.to_string())
+            .unwrap_or_default();
+        assert!(err.starts_with("no files match"), "{err}");
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::newlines(b"a.md\nb c.md\r\n\n", &["a.md", "b c.md"])]
+    #[case::nul(b"a.md\0with\nnewline.md\0", &["a.md", "with\nnewline.md"])]
+    #[case::empty(b"", &[])]
+    fn parses_file_lists(#[case] bytes: &[u8], #[case] expected: &[&str]) {
+        let expected: Vec<PathBuf> = expected.iter().map(PathBuf::from).collect();
+        assert_eq!(parse_file_list(bytes), expected);
+    }
+
+    #[rstest]
+    #[case::name_anywhere("CHANGELOG.md", "docs/CHANGELOG.md", true)]
+    #[case::directory_name("vendor", "vendor/lib/README.md", true)]
+    #[case::name_glob("*.generated.md", "docs/api.generated.md", true)]
+    #[case::path("docs/generated", "./docs/generated/a.md", true)]
+    #[case::path_glob("docs/**/draft-*.md", "docs/a/b/draft-1.md", true)]
+    #[case::star_stays_in_component("docs/*.md", "docs/sub/a.md", false)]
+    #[case::other_name("CHANGELOG.md", "docs/index.md", false)]
+    fn exclude_patterns_match_paths(
+        #[case] pattern: &str,
+        #[case] path: &str,
+        #[case] expected: bool,
+    ) -> Result<(), String> {
+        assert_eq!(parse_exclude(pattern)?.matches(Path::new(path)), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn excludes_walked_and_explicit_paths() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("vendor"))?;
+        for name in ["a.md", "CHANGELOG.md", "vendor/b.md"] {
+            fs::write(root.join(name), "")?;
+        }
+        let excludes = ["CHANGELOG.md", "vendor"]
+            .map(|pattern| parse_exclude(pattern).map_err(anyhow::Error::msg));
+        let excludes = excludes.into_iter().collect::<anyhow::Result<Vec<_>>>()?;
+        let files = expand_paths(
+            &[root.to_path_buf(), root.join("CHANGELOG.md")],
+            &excludes,
+            true,
+        )?;
+        assert_eq!(files, [root.join("a.md")]);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::honoured(true, &["docs/keep.md"])]
+    #[case::disabled(false, &["docs/generated/api.md", "docs/keep.md", "node_modules/pkg/README.md"])]
+    fn walks_honour_ignore_files(
+        #[case] ignore_files: bool,
+        #[case] expected: &[&str],
+    ) -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("docs/generated"))?;
+        fs::create_dir_all(root.join("node_modules/pkg"))?;
+        fs::write(root.join(".gitignore"), "node_modules/\n")?;
+        fs::write(root.join("docs").join(IGNORE_FILE), "generated/\n")?;
+        for name in [
+            "docs/keep.md",
+            "docs/generated/api.md",
+            "node_modules/pkg/README.md",
+        ] {
+            fs::write(root.join(name), "")?;
+        }
+        let files = expand_paths(&[root.to_path_buf()], &[], ignore_files)?;
+        let expected: Vec<PathBuf> = expected.iter().map(|name| root.join(name)).collect();
+        assert_eq!(files, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+//! The exit statuses `mdtablefix` reports to scripts.
+//!
+//! CI jobs rely on these staying stable:
+//!
+//! - `0`: every file is already formatted.
+//! - `1`: `--check` or `check` found files that would be reformatted, `--in-place` reformatted
+//!   files, `lint` reported changes, or `--check-rust-fences` found examples that fail to compile.
+//! - `2`: the command line is invalid. Clap reports its own parse errors with this status too.
+//! - `3`: a file, the config file, or git could not be read, written, or parsed.
+
+use std::{error::Error, fmt, process::ExitCode};
+
+/// How a run ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum Status {
+    /// Nothing needs attention.
+    Clean = 0,
+    /// A check found work to do.
+    Findings = 1,
+    /// The arguments were invalid.
+    Usage = 2,
+    /// Reading, writing, or parsing an input failed.
+    Failure = 3,
+}
+
+impl Status {
+    /// Returns [`Status::Findings`] when `found` is set and
+    /// [`Status::Clean`] otherwise.
+    pub(crate) const fn findings_if(found: bool) -> Self {
+        if found { Self::Findings } else { Self::Clean }
+    }
+
+    /// Classifies an error that ended the run.
+    pub(crate) fn of_error(err: &anyhow::Error) -> Self {
+        if err.is::<UsageError>() {
+            Self::Usage
+        } else {
+            Self::Failure
+        }
+    }
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self { Self::from(status as u8) }
+}
+
+/// An error in the arguments that clap cannot detect itself, such as an
+/// unknown preset name.
+#[derive(Debug)]
+pub(crate) struct UsageError(pub(crate) String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.0) }
+}
+
+impl Error for UsageError {}
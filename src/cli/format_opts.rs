@@ -0,0 +1,400 @@
+//! Formatting flags shared by file, stdin, and daemon processing.
+//!
+//! [`FormatOpts`] is flattened into the top-level CLI and reparsed for each
+//! daemon request, then converted into library [`Options`] for the pipeline.
+
+use mdtablefix::{
+    Options,
+    blank_lines::HeadingSpacing,
+    blocks::BlockExclusions,
+    changes::Transform,
+    footnotes::FootnotePlacement,
+    opaque::OpaqueTokens,
+    process::{TransformRegistry, WRAP_COLS},
+    protect::BlockProtection,
+    table::Alignment,
+};
+
+use crate::{
+    exit_status::UsageError,
+    format_values::{
+        CjkWrapPolicy,
+        FootnoteRefArg,
+        HardBreakArg,
+        ImportFormat,
+        IncludeArg,
+        SentenceSpacingArg,
+        TableOverflowArg,
+        TableStyleArg,
+        parse_alignment,
+        parse_heading_spacing,
+    },
+};
+
+#[derive(clap::Args, Clone)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "CLI exposes independent flags via separate switches"
+)]
+pub(crate) struct FormatOpts {
+    /// Wrap paragraphs and list items to 80 columns, or --wrap-width
+    #[arg(long = "wrap")]
+    pub(crate) wrap: bool,
+    /// Column width for --wrap
+    #[arg(
+        long = "wrap-width",
+        value_name = "N",
+        default_value_t = WRAP_COLS,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=u64::from(u16::MAX))
+    )]
+    pub(crate) wrap_width: usize,
+    /// Rejoin lines broken mid-sentence or mid-word by earlier hard wrapping
+    #[arg(long = "rejoin")]
+    pub(crate) rejoin: bool,
+    /// Replace each `<!-- include: PATH -->` line with the file it names
+    /// before formatting; `refresh` keeps the pragma and rewrites the
+    /// region it expanded to on every run
+    #[arg(
+        long = "expand-includes",
+        value_enum,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "inline"
+    )]
+    pub(crate) includes: Option<IncludeArg>,
+    /// Rewrite the first `#` heading to the front matter `title` when they
+    /// differ
+    #[arg(long = "fix-titles")]
+    pub(crate) fix_titles: bool,
+    /// Only format the lines under HEADING, such as "## API Reference", up to
+    /// the next heading of the same or a higher level
+    #[arg(long = "only-section", value_name = "HEADING")]
+    pub(crate) only_section: Option<String>,
+    /// Renumber ordered list items
+    #[arg(long = "renumber")]
+    pub(crate) renumber: bool,
+    /// With --renumber, keep counting across paragraphs and other blocks
+    /// that separate ordered lists at the same indent
+    #[arg(long = "continue-numbering", requires = "renumber")]
+    pub(crate) continue_numbering: bool,
+    /// Append "<!-- was N -->" to list items and footnote definitions whose
+    /// number renumbering changes
+    #[arg(long = "renumber-comments")]
+    pub(crate) renumber_comments: bool,
+    /// Remove the "<!-- was N -->" comments left by --renumber-comments
+    #[arg(long = "strip-renumber-comments", conflicts_with = "renumber_comments")]
+    pub(crate) strip_renumber_comments: bool,
+    /// Put exactly N spaces (1 to 4, default 1) after each list marker,
+    /// adding the space missing from items such as "1.item"
+    #[arg(
+        long = "list-marker-spacing",
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=4)
+    )]
+    pub(crate) list_marker_spacing: Option<usize>,
+    /// Reformat thematic breaks as underscores
+    #[arg(long = "breaks")]
+    pub(crate) breaks: bool,
+    /// Put BEFORE blank lines above each heading and AFTER below it
+    /// (default 1,1); a single count sets both
+    #[arg(
+        long = "heading-spacing",
+        value_name = "BEFORE[,AFTER]",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        value_parser = parse_heading_spacing
+    )]
+    pub(crate) heading_spacing: Option<HeadingSpacing>,
+    /// Collapse runs of more than N blank lines outside code fences, and
+    /// remove blank lines at the start and end of the document
+    #[arg(long = "max-consecutive-blanks", value_name = "N")]
+    pub(crate) max_consecutive_blanks: Option<usize>,
+    /// Replace "..." with the ellipsis character
+    #[arg(long = "ellipsis")]
+    pub(crate) ellipsis: bool,
+    /// Normalise fence delimiters to three backticks
+    #[arg(long = "fences")]
+    pub(crate) fences: bool,
+    /// Convert bare numeric references and the final numbered list to
+    /// Markdown footnote links
+    #[arg(long = "footnotes")]
+    pub(crate) footnotes: bool,
+    /// Implies --footnotes; number footnotes separately in each level-one or
+    /// level-two section and move definitions to the end of their section
+    #[arg(long = "footnotes-per-section")]
+    pub(crate) footnotes_per_section: bool,
+    /// Remove footnote references that repeat the one before them, as in
+    /// `[^1][^1]`; `punctuated` also merges references separated only by
+    /// commas, semicolons, and spaces
+    #[arg(
+        long = "compact-footnote-refs",
+        value_enum,
+        value_name = "POLICY",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "repeated"
+    )]
+    pub(crate) footnote_refs: Option<FootnoteRefArg>,
+    /// Fix emphasis markers adjacent to inline code
+    #[arg(long = "code-emphasis")]
+    pub(crate) code_emphasis: bool,
+    /// Trim spaces inside inline code and use the fewest backticks needed
+    #[arg(long = "code-spans")]
+    pub(crate) code_spans: bool,
+    /// Convert Setext-style headings to hash-prefixed headings
+    #[arg(long = "headings")]
+    pub(crate) headings: bool,
+    /// Convert bold-only paragraphs into headings of the given level (MD036)
+    #[arg(
+        long = "emphasis-headings",
+        value_name = "LEVEL",
+        value_parser = clap::value_parser!(u8).range(1..=6)
+    )]
+    pub(crate) emphasis_headings: Option<u8>,
+    /// Convert Jira/Confluence wiki-markup tables to Markdown tables
+    #[arg(long = "wiki-tables")]
+    pub(crate) wiki_tables: bool,
+    /// Convert tables written in another markup language (repeatable)
+    #[arg(long = "import", value_enum, value_name = "FORMAT")]
+    pub(crate) import: Vec<ImportFormat>,
+    /// Abbreviate a header label in tables wider than --max-table-width, or
+    /// --wrap-width when that is unset (repeatable)
+    #[arg(
+        long = "abbreviate-header",
+        value_name = "FULL=SHORT",
+        value_parser = parse_abbreviation
+    )]
+    pub(crate) abbreviations: Vec<(String, String)>,
+    /// Leave tables whose columns are already aligned untouched
+    #[arg(long = "keep-clean-tables")]
+    pub(crate) keep_clean_tables: bool,
+    /// How to lay out reflowed tables
+    #[arg(
+        long = "table-style",
+        value_enum,
+        value_name = "STYLE",
+        default_value = "padded"
+    )]
+    pub(crate) table_style: TableStyleArg,
+    /// Keep padded tables within N columns where their cells allow; wider
+    /// tables go to --table-overflow
+    #[arg(
+        long = "max-table-width",
+        value_name = "N",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=u64::from(u16::MAX))
+    )]
+    pub(crate) max_table_width: Option<usize>,
+    /// What to do with a table still wider than --max-table-width: keep it,
+    /// move it into a fenced block, or wrap it in a scrolling <div>
+    #[arg(
+        long = "table-overflow",
+        value_enum,
+        value_name = "POLICY",
+        default_value = "keep",
+        requires = "max_table_width"
+    )]
+    pub(crate) table_overflow: TableOverflowArg,
+    /// Set the alignment of each table column from SPEC, a comma-separated
+    /// list of `l`, `c`, `r`, or `-` for none, such as `l,c,r`; an empty
+    /// entry keeps that column's alignment
+    #[arg(
+        long = "align",
+        value_name = "SPEC",
+        value_delimiter = ',',
+        allow_hyphen_values = true,
+        value_parser = parse_alignment
+    )]
+    pub(crate) align: Vec<Option<Alignment>>,
+    /// How to wrap paragraphs written mostly in Chinese, Japanese, or Korean
+    #[arg(
+        long = "cjk-wrap",
+        value_enum,
+        value_name = "POLICY",
+        default_value = "cells"
+    )]
+    pub(crate) cjk_wrap: CjkWrapPolicy,
+    /// Rewrite hard line breaks inside paragraphs to one style
+    #[arg(long = "normalize-hard-breaks", value_enum, value_name = "STYLE")]
+    pub(crate) hard_breaks: Option<HardBreakArg>,
+    /// Use one or two spaces after sentence-ending punctuation
+    #[arg(long = "sentence-spacing", value_enum, value_name = "SPACING")]
+    pub(crate) sentence_spacing: Option<SentenceSpacingArg>,
+    /// Run a WASM plugin after STAGE: `start`, `end` (the default), or a
+    /// transform name such as `tables` (repeatable)
+    #[cfg(feature = "wasm-plugins")]
+    #[arg(
+        long = "plugin",
+        value_name = "PATH[@STAGE]",
+        value_parser = crate::plugins::parse_plugin
+    )]
+    pub(crate) plugins: Vec<crate::plugins::PluginSpec>,
+    /// Run every transform that has an on/off flag; use --disable to leave
+    /// some out
+    #[arg(long = "all")]
+    pub(crate) all: bool,
+    /// Run the transforms in NAMES, a comma-separated list such as
+    /// `footnotes,headings`, as if their flags were given (repeatable)
+    #[arg(
+        long = "enable",
+        value_name = "NAMES",
+        value_delimiter = ',',
+        value_parser = parse_transform
+    )]
+    pub(crate) enable: Vec<Transform>,
+    /// Skip the transforms in NAMES, overriding their flags, --enable, and
+    /// any preset (repeatable)
+    #[arg(
+        long = "disable",
+        value_name = "NAMES",
+        value_delimiter = ',',
+        value_parser = parse_transform
+    )]
+    pub(crate) disable: Vec<Transform>,
+    /// Block types each transform skips, read from the config file
+    #[arg(skip)]
+    pub(crate) block_exclusions: BlockExclusions,
+    /// Patterns wrapping and table parsing keep whole, read from the config
+    /// file
+    #[arg(skip)]
+    pub(crate) opaque_tokens: OpaqueTokens,
+}
+
+/// Parses a transform name for `--enable` and `--disable`.
+fn parse_transform(name: &str) -> Result<Transform, String> {
+    Transform::from_name(name.trim()).ok_or_else(|| format!("unknown transform `{name}`"))
+}
+
+/// The transforms `--all` enables: every one with an on/off flag.
+const ALL_SWITCHES: [Transform; 12] = [
+    Transform::Fences,
+    Transform::WikiTables,
+    Transform::RstTables,
+    Transform::CodeSpans,
+    Transform::Headings,
+    Transform::CodeEmphasis,
+    Transform::Rejoin,
+    Transform::Wrap,
+    Transform::Ellipsis,
+    Transform::Footnotes,
+    Transform::Renumber,
+    Transform::Breaks,
+];
+
+impl FormatOpts {
+    /// Applies `--all`, `--enable`, and then `--disable` to the switches the
+    /// binary owns, and checks the library can switch the rest, which
+    /// [`Options::from`] then does.
+    ///
+    /// # Errors
+    /// Returns a [`UsageError`] for a transform that cannot be switched that
+    /// way, such as table reflow, or a transform that needs a value to run.
+    pub(crate) fn apply_toggles(&mut self) -> Result<(), UsageError> {
+        if self.all {
+            self.enable.splice(0..0, ALL_SWITCHES);
+        }
+        let toggles = self.enable.iter().map(|&transform| (transform, true));
+        let toggles: Vec<_> = toggles
+            .chain(self.disable.iter().map(|&transform| (transform, false)))
+            .collect();
+        for (transform, enabled) in toggles {
+            let name = transform.name();
+            match (transform, enabled) {
+                (Transform::Renumber, _) => self.renumber = enabled,
+                (Transform::Breaks, _) => self.breaks = enabled,
+                (Transform::ListMarkers, true) => {
+                    self.list_marker_spacing.get_or_insert(1);
+                }
+                (Transform::ListMarkers, false) => self.list_marker_spacing = None,
+                (Transform::Includes, true) => {
+                    self.includes.get_or_insert(IncludeArg::Inline);
+                }
+                (Transform::Includes, false) => self.includes = None,
+                (Transform::Titles, _) => self.fix_titles = enabled,
+                (Transform::FootnoteRefs, true) => {
+                    self.footnote_refs.get_or_insert(FootnoteRefArg::Repeated);
+                }
+                (Transform::FootnoteRefs, false) => self.footnote_refs = None,
+                (Transform::HeadingSpacing, true) => {
+                    self.heading_spacing.get_or_insert_default();
+                }
+                (Transform::HeadingSpacing, false) => self.heading_spacing = None,
+                (Transform::AbbreviateHeaders, false) => self.abbreviations.clear(),
+                (Transform::BlankLines, false) => self.max_consecutive_blanks = None,
+                (Transform::Tables | Transform::HtmlTables, true) => {}
+                (Transform::Plugin, _) => {
+                    return Err(UsageError("plugins are chosen with --plugin".to_string()));
+                }
+                _ if Options::default().set_enabled(transform, enabled) => {}
+                (_, true) => {
+                    return Err(UsageError(format!(
+                        "cannot enable `{name}`: it takes a value, so use its own flag"
+                    )));
+                }
+                (_, false) => {
+                    return Err(UsageError(format!(
+                        "cannot disable `{name}`: it always runs"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses a `FULL=SHORT` header abbreviation.
+fn parse_abbreviation(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((full, short)) if !full.trim().is_empty() && !short.trim().is_empty() => {
+            Ok((full.trim().to_string(), short.trim().to_string()))
+        }
+        _ => Err(format!("expected FULL=SHORT, got `{value}`")),
+    }
+}
+
+impl From<&FormatOpts> for Options {
+    fn from(opts: &FormatOpts) -> Self {
+        let mut options = Self {
+            wrap: opts.wrap,
+            wrap_width: opts.wrap_width,
+            rejoin: opts.rejoin,
+            ellipsis: opts.ellipsis,
+            fences: opts.fences,
+            footnotes: opts.footnotes || opts.footnotes_per_section,
+            footnote_placement: if opts.footnotes_per_section {
+                FootnotePlacement::Section
+            } else {
+                FootnotePlacement::Document
+            },
+            footnote_refs: opts.footnote_refs.map(Into::into),
+            renumber_comments: opts.renumber_comments,
+            code_emphasis: opts.code_emphasis,
+            code_spans: opts.code_spans,
+            headings: opts.headings,
+            emphasis_headings: opts.emphasis_headings,
+            wiki_tables: opts.wiki_tables,
+            rst_tables: opts.import.contains(&ImportFormat::RstTables),
+            keep_clean_tables: opts.keep_clean_tables,
+            table_style: opts.table_style.into(),
+            max_table_width: opts.max_table_width,
+            table_overflow: opts.table_overflow.into(),
+            table_alignment: opts.align.clone(),
+            cjk_wrap: opts.cjk_wrap.into(),
+            hard_breaks: opts.hard_breaks.map(Into::into),
+            sentence_spacing: opts.sentence_spacing.map(Into::into),
+            block_exclusions: opts.block_exclusions,
+            opaque_tokens: opts.opaque_tokens.clone(),
+            protect: BlockProtection::default(),
+            transforms: TransformRegistry::default(),
+        };
+        for &transform in &opts.enable {
+            options.set_enabled(transform, true);
+        }
+        for &transform in &opts.disable {
+            options.set_enabled(transform, false);
+        }
+        options
+    }
+}
@@ -0,0 +1,253 @@
+//! Values accepted by formatting flags that choose between policies.
+//!
+//! Each `clap` value enum mirrors a library type and converts into it, so
+//! the library does not depend on `clap`. Values with more structure than a
+//! name are parsed by a function instead.
+
+use mdtablefix::{
+    blank_lines::HeadingSpacing,
+    document::LineEnding,
+    footnotes::FootnoteRefCompaction,
+    hard_breaks::HardBreakStyle,
+    includes::IncludeMode,
+    io::DecodePolicy,
+    sentence_spacing::SentenceSpacing,
+    table::{Alignment, TableOverflow, TableStyle},
+    wrap::CjkWrap,
+};
+
+/// Foreign table syntaxes accepted by `--import`.
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImportFormat {
+    /// reStructuredText grid and simple tables
+    RstTables,
+}
+
+/// Table layouts accepted by `--table-style`.
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub(crate) enum TableStyleArg {
+    /// Pad every cell so all pipes line up
+    Padded,
+    /// Align only the leading and trailing pipes
+    EdgesOnly,
+    /// Pad nothing, with one space around each pipe
+    Compact,
+}
+
+impl From<TableStyleArg> for TableStyle {
+    fn from(style: TableStyleArg) -> Self {
+        match style {
+            TableStyleArg::Padded => Self::Padded,
+            TableStyleArg::EdgesOnly => Self::EdgesOnly,
+            TableStyleArg::Compact => Self::Compact,
+        }
+    }
+}
+
+/// Overflow policies accepted by `--table-overflow`.
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub(crate) enum TableOverflowArg {
+    /// Emit the over-wide table as it is
+    Keep,
+    /// Move the table into a fenced code block
+    Fence,
+    /// Wrap the table in a horizontally scrolling <div>
+    Scroll,
+}
+
+impl From<TableOverflowArg> for TableOverflow {
+    fn from(overflow: TableOverflowArg) -> Self {
+        match overflow {
+            TableOverflowArg::Keep => Self::Keep,
+            TableOverflowArg::Fence => Self::Fence,
+            TableOverflowArg::Scroll => Self::Scroll,
+        }
+    }
+}
+
+/// Wrapping policies accepted by `--cjk-wrap`.
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub(crate) enum CjkWrapPolicy {
+    /// Leave CJK paragraphs unwrapped
+    Never,
+    /// Measure CJK paragraphs in display cells
+    Cells,
+    /// Measure CJK paragraphs in characters
+    Chars,
+}
+
+impl From<CjkWrapPolicy> for CjkWrap {
+    fn from(policy: CjkWrapPolicy) -> Self {
+        match policy {
+            CjkWrapPolicy::Never => Self::Never,
+            CjkWrapPolicy::Cells => Self::Cells,
+            CjkWrapPolicy::Chars => Self::Chars,
+        }
+    }
+}
+
+/// Expansion modes accepted by `--expand-includes`.
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub(crate) enum IncludeArg {
+    /// Replace each pragma with the file it names
+    Inline,
+    /// Keep each pragma and rewrite the region it expanded to
+    Refresh,
+}
+
+impl From<IncludeArg> for IncludeMode {
+    fn from(mode: IncludeArg) -> Self {
+        match mode {
+            IncludeArg::Inline => Self::Inline,
+            IncludeArg::Refresh => Self::Refresh,
+        }
+    }
+}
+
+/// Compaction policies accepted by `--compact-footnote-refs`.
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub(crate) enum FootnoteRefArg {
+    /// Drop references repeated back to back
+    Repeated,
+    /// Also merge references separated by commas, semicolons, and spaces
+    Punctuated,
+}
+
+impl From<FootnoteRefArg> for FootnoteRefCompaction {
+    fn from(policy: FootnoteRefArg) -> Self {
+        match policy {
+            FootnoteRefArg::Repeated => Self::Repeated,
+            FootnoteRefArg::Punctuated => Self::Punctuated,
+        }
+    }
+}
+
+/// Hard break styles accepted by `--normalize-hard-breaks`.
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub(crate) enum HardBreakArg {
+    /// Two trailing spaces
+    Spaces,
+    /// A trailing backslash
+    Backslash,
+    /// An HTML `<br>` tag
+    Html,
+}
+
+impl From<HardBreakArg> for HardBreakStyle {
+    fn from(style: HardBreakArg) -> Self {
+        match style {
+            HardBreakArg::Spaces => Self::Spaces,
+            HardBreakArg::Backslash => Self::Backslash,
+            HardBreakArg::Html => Self::Html,
+        }
+    }
+}
+
+/// Sentence gaps accepted by `--sentence-spacing`.
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub(crate) enum SentenceSpacingArg {
+    /// One space between sentences
+    Single,
+    /// Two spaces between sentences
+    Double,
+}
+
+impl From<SentenceSpacingArg> for SentenceSpacing {
+    fn from(spacing: SentenceSpacingArg) -> Self {
+        match spacing {
+            SentenceSpacingArg::Single => Self::Single,
+            SentenceSpacingArg::Double => Self::Double,
+        }
+    }
+}
+
+/// Decoding policies accepted by `--encoding`.
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+pub(crate) enum EncodingArg {
+    /// Refuse files that are not valid UTF-8
+    #[default]
+    Strict,
+    /// Replace invalid UTF-8 with U+FFFD and write UTF-8
+    Lossy,
+    /// Read UTF-16 with a byte order mark and Latin-1, and write them back
+    Detect,
+}
+
+impl From<EncodingArg> for DecodePolicy {
+    fn from(policy: EncodingArg) -> Self {
+        match policy {
+            EncodingArg::Strict => Self::Strict,
+            EncodingArg::Lossy => Self::Lossy,
+            EncodingArg::Detect => Self::Detect,
+        }
+    }
+}
+
+/// Line endings accepted by `--line-ending`.
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+pub(crate) enum LineEndingArg {
+    /// Keep the ending most of each file's lines use
+    #[default]
+    Auto,
+    /// Write Unix-style `\n`
+    Lf,
+    /// Write Windows-style `\r\n`
+    Crlf,
+}
+
+impl From<LineEndingArg> for Option<LineEnding> {
+    fn from(ending: LineEndingArg) -> Self {
+        match ending {
+            LineEndingArg::Auto => None,
+            LineEndingArg::Lf => Some(LineEnding::Lf),
+            LineEndingArg::Crlf => Some(LineEnding::CrLf),
+        }
+    }
+}
+
+/// Policies accepted by `--final-newline`.
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+pub(crate) enum FinalNewlineArg {
+    /// End the output with a line ending exactly when the input did
+    #[default]
+    Keep,
+    /// Always end non-empty output with a line ending
+    Always,
+    /// Never end the output with a line ending
+    Never,
+}
+
+impl FinalNewlineArg {
+    /// Returns whether output ends with a line ending, given whether the
+    /// input did.
+    pub(crate) fn resolve(self, trailing_newline: bool) -> bool {
+        match self {
+            Self::Keep => trailing_newline,
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+/// Parses one column of `--align`, an empty entry keeping the column's
+/// alignment.
+pub(crate) fn parse_alignment(name: &str) -> Result<Option<Alignment>, String> {
+    if name.trim().is_empty() {
+        return Ok(None);
+    }
+    name.parse().map(Some)
+}
+
+/// Parses `--heading-spacing BEFORE[,AFTER]`, where one count sets both.
+pub(crate) fn parse_heading_spacing(value: &str) -> Result<HeadingSpacing, String> {
+    let count = |text: &str| {
+        text.trim()
+            .parse()
+            .map_err(|_| format!("`{text}` is not a number of blank lines"))
+    };
+    let (before, after) = match value.split_once(',') {
+        Some((before, after)) => (count(before)?, count(after)?),
+        None => (count(value)?, count(value)?),
+    };
+    Ok(HeadingSpacing { before, after })
+}
@@ -0,0 +1,85 @@
+//! `--changed` and `--staged`: ask git which Markdown files to process.
+//!
+//! `--staged` selects the files added or modified in the index, which is
+//! what a pre-commit hook checks. `--changed` also selects files modified in
+//! the working tree and untracked files that are not ignored. Both run git in
+//! the current directory and report paths relative to it, limited to the
+//! current directory, or to the file arguments when given, which are passed
+//! to git as pathspecs. Deleted files, non-Markdown files, and paths matching
+//! `--exclude` are dropped.
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, bail};
+
+use crate::discover::{Exclude, is_excluded, is_markdown, parse_file_list};
+
+/// Which files git should report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GitSelection {
+    /// Files modified in the index or the working tree, or untracked.
+    Changed,
+    /// Files modified in the index.
+    Staged,
+}
+
+/// Lists the Markdown files selected by `selection` below `pathspecs`, in
+/// sorted order.
+///
+/// # Errors
+/// Returns an error when git cannot be run or reports a failure, for example
+/// outside a repository.
+pub(crate) fn git_files(
+    selection: GitSelection,
+    pathspecs: &[PathBuf],
+    excludes: &[Exclude],
+) -> anyhow::Result<Vec<PathBuf>> {
+    let diff = ["diff", "--name-only", "-z", "--diff-filter=d", "--relative"];
+    let mut files = BTreeSet::new();
+    files.extend(git_names(&[&diff[..], &["--cached"]].concat(), pathspecs)?);
+    if selection == GitSelection::Changed {
+        files.extend(git_names(&diff, pathspecs)?);
+        files.extend(git_names(
+            &["ls-files", "--others", "--exclude-standard", "-z"],
+            pathspecs,
+        )?);
+    }
+    Ok(files
+        .into_iter()
+        .filter(|path| is_markdown(path) && path.is_file() && !is_excluded(path, excludes))
+        .collect())
+}
+
+/// Runs git with `args` and the pathspecs, returning the NUL-separated names
+/// it prints.
+fn git_names(args: &[&str], pathspecs: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .args(["-c", "core.quotePath=off"])
+        .args(args)
+        .arg("--")
+        .args(
+            pathspecs
+                .iter()
+                .map(PathBuf::as_path)
+                .chain(default_pathspec(pathspecs)),
+        )
+        .output()
+        .context("running git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_file_list(&output.stdout))
+}
+
+/// Limits git to the current directory when no pathspecs are given.
+fn default_pathspec(pathspecs: &[PathBuf]) -> Option<&'static Path> {
+    pathspecs.is_empty().then_some(Path::new("."))
+}
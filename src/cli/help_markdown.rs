@@ -0,0 +1,206 @@
+//! `--help-markdown`: the command-line and config file reference as Markdown.
+//!
+//! The reference is generated from the clap definitions, the
+//! [`Transform`] registry, and the preset and config file keys, so it cannot
+//! drift from the tool it documents. It is formatted with the library before
+//! printing, which makes the output a fixed point of `mdtablefix` itself.
+
+use std::fmt::Write as _;
+
+use clap::{Arg, ArgAction, Command};
+use mdtablefix::{Options, blocks::supports_exclusion, changes::Transform, process_stream_opts};
+
+use crate::presets::{BUILTIN, KEYS};
+
+/// Block types accepted in a transform table's `exclude` list.
+const BLOCK_TYPES: [&str; 4] = ["blockquote", "footnote-definition", "heading", "list-item"];
+
+/// Returns the reference for `command` and its subcommands, formatted.
+pub(crate) fn render(command: &Command) -> String {
+    let mut out = String::new();
+    let name = command.get_name();
+    let _ = writeln!(out, "# {name}\n");
+    if let Some(about) = command.get_about() {
+        let _ = writeln!(out, "{}\n", sentence(&about.to_string()));
+    }
+    if let Some(version) = command.get_version() {
+        let _ = writeln!(out, "This reference describes version {version}.\n");
+    }
+    let mut command = command.clone();
+    let _ = writeln!(
+        out,
+        "## Usage\n\n```text\n{}\n```\n",
+        command.render_usage().to_string().trim()
+    );
+    out.push_str("## Options\n\n");
+    write_options(&mut out, &command);
+    out.push_str("## Subcommands\n\n");
+    // Exclusive flags such as `--help-markdown` itself belong to the bare
+    // invocation only.
+    let top_ids: Vec<&str> = visible_args(&command)
+        .filter(|arg| !arg.is_exclusive_set())
+        .map(|arg| arg.get_id().as_str())
+        .collect();
+    let subcommands = command
+        .get_subcommands()
+        .filter(|sub| !sub.is_hide_set() && sub.get_name() != "help");
+    for sub in subcommands {
+        let _ = writeln!(out, "### `{name} {}`\n", sub.get_name());
+        if let Some(about) = sub.get_about() {
+            let _ = writeln!(out, "{}\n", sentence(&about.to_string()));
+        }
+        let ids: Vec<&str> = visible_args(sub).map(|arg| arg.get_id().as_str()).collect();
+        if ids == top_ids {
+            let _ = writeln!(out, "Accepts the same options as `{name}`.\n");
+        } else {
+            write_options(&mut out, sub);
+        }
+    }
+    write_transforms(&mut out);
+    write_config(&mut out);
+    let lines: Vec<String> = out.lines().map(str::to_string).collect();
+    let opts = Options {
+        wrap: true,
+        ..Options::default()
+    };
+    process_stream_opts(&lines, opts).join("\n") + "\n"
+}
+
+/// Returns the arguments of `command` shown in `--help`.
+fn visible_args(command: &Command) -> impl Iterator<Item = &Arg> {
+    command
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set() && arg.get_id() != "help" && arg.get_id() != "version")
+}
+
+/// Writes one bullet per visible argument of `command`.
+fn write_options(out: &mut String, command: &Command) {
+    for arg in visible_args(command) {
+        let _ = write!(out, "- {}", arg_syntax(arg));
+        let aliases: Vec<String> = arg
+            .get_visible_aliases()
+            .into_iter()
+            .flatten()
+            .map(|alias| format!("`--{alias}`"))
+            .collect();
+        if !aliases.is_empty() {
+            let _ = write!(out, " (alias {})", aliases.join(", "));
+        }
+        let help = arg.get_help().map(ToString::to_string).unwrap_or_default();
+        let _ = write!(out, ": {}", sentence(&help));
+        let values: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| format!("`{}`", value.get_name()))
+            .collect();
+        if !values.is_empty() && takes_value(arg) {
+            let _ = write!(out, " One of {}.", values.join(", "));
+        }
+        let defaults: Vec<String> = arg
+            .get_default_values()
+            .iter()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect();
+        if !defaults.is_empty() && takes_value(arg) {
+            let _ = write!(out, " Defaults to `{}`.", defaults.join(","));
+        }
+        if let Some(env) = arg.get_env() {
+            let _ = write!(out, " Read from `{}` when unset.", env.to_string_lossy());
+        }
+        out.push('\n');
+    }
+    out.push('\n');
+}
+
+/// Joins the lines of `text` and ends it with a full stop.
+fn sentence(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() || text.ends_with('.') {
+        text
+    } else {
+        text + "."
+    }
+}
+
+fn takes_value(arg: &Arg) -> bool { matches!(arg.get_action(), ArgAction::Set | ArgAction::Append) }
+
+/// Returns how `arg` is written on the command line, in backticks.
+fn arg_syntax(arg: &Arg) -> String {
+    let mut syntax = match (arg.get_long(), arg.get_short()) {
+        (Some(long), Some(short)) => format!("`-{short}`, `--{long}"),
+        (Some(long), None) => format!("`--{long}"),
+        (None, Some(short)) => format!("`-{short}"),
+        (None, None) => {
+            let id = arg.get_id().as_str().to_uppercase();
+            return if arg.is_required_set() {
+                format!("`{id}`")
+            } else {
+                format!("`[{id}]`")
+            };
+        }
+    };
+    if takes_value(arg) {
+        let name = arg
+            .get_value_names()
+            .and_then(|names| names.first())
+            .map_or_else(|| arg.get_id().as_str().to_uppercase(), ToString::to_string);
+        let optional = arg
+            .get_num_args()
+            .is_some_and(|range| range.min_values() == 0);
+        if optional {
+            let _ = write!(syntax, "[={name}]");
+        } else {
+            let _ = write!(syntax, " {name}");
+        }
+    }
+    syntax.push('`');
+    syntax
+}
+
+/// Writes the table of transforms, in pipeline order.
+fn write_transforms(out: &mut String) {
+    out.push_str(
+        "## Transforms\n\nEach transform can be switched on with `--enable NAME` and off with \
+         `--disable NAME`. They run in this order.\n\n| Transform | Description |\n| --- | --- |\n",
+    );
+    for transform in Transform::all() {
+        let _ = writeln!(
+            out,
+            "| `{}` | {} |",
+            transform.name(),
+            transform.description()
+        );
+    }
+    out.push('\n');
+}
+
+/// Writes the reference for `.mdtablefix.toml`.
+fn write_config(out: &mut String) {
+    let code = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let excludable: Vec<&str> = Transform::all()
+        .filter(|transform| supports_exclusion(*transform))
+        .map(Transform::name)
+        .collect();
+    let _ = write!(
+        out,
+        "## Config file\n\nSettings are read from the nearest `.mdtablefix.toml` above each file, \
+         or from the file named by `--config`. Command-line flags override them.\n\n- \
+         `[defaults]`: switches applied to every run. Keys: {keys}.\n- `[presets.NAME]` or \
+         `[profile.NAME]`: a named set of the same keys, selected with `--preset NAME`. Tables \
+         named after a built-in preset ({builtin}) override its keys.\n- `[TRANSFORM]`: an \
+         `exclude` array of block types the transform leaves alone. Block types: {blocks}. \
+         Transforms: {excludable}.\n- `opaque-tokens`: an array of regular expressions whose \
+         matches no transform rewrites.\n",
+        keys = code(&KEYS),
+        builtin = code(&BUILTIN),
+        blocks = code(&BLOCK_TYPES),
+        excludable = code(&excludable),
+    );
+}
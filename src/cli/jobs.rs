@@ -0,0 +1,61 @@
+//! Sizing of the worker pool used for parallel file processing.
+//!
+//! CI containers often run several jobs side by side, so letting every
+//! process spawn one thread per host CPU oversubscribes the machine. The pool
+//! size comes from `--jobs` (or `MDTABLEFIX_JOBS`) and otherwise from
+//! [`std::thread::available_parallelism`], which honours cgroup CPU quotas
+//! and affinity masks on Linux.
+
+use std::{num::NonZeroUsize, thread::available_parallelism};
+
+use anyhow::Context;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+/// Returns the number of worker threads to use.
+///
+/// An explicit request wins; otherwise the quota-aware parallelism reported
+/// by the standard library is used, falling back to a single thread when it
+/// cannot be determined.
+pub(crate) fn resolve_jobs(requested: Option<NonZeroUsize>) -> NonZeroUsize {
+    requested
+        .or_else(|| available_parallelism().ok())
+        .unwrap_or(NonZeroUsize::MIN)
+}
+
+/// Builds a dedicated pool with `jobs` worker threads.
+///
+/// # Errors
+/// Returns an error if the operating system refuses to spawn the threads.
+pub(crate) fn build_pool(jobs: NonZeroUsize) -> anyhow::Result<ThreadPool> {
+    ThreadPoolBuilder::new()
+        .num_threads(jobs.get())
+        .thread_name(|index| format!("mdtablefix-worker-{index}"))
+        .build()
+        .with_context(|| format!("starting {jobs} worker threads"))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for worker pool sizing.
+
+    use super::*;
+
+    #[test]
+    fn explicit_request_wins() {
+        let four = NonZeroUsize::new(4).expect("four is non-zero");
+        assert_eq!(resolve_jobs(Some(four)), four);
+    }
+
+    #[test]
+    fn default_matches_available_parallelism() {
+        let expected = available_parallelism().unwrap_or(NonZeroUsize::MIN);
+        assert_eq!(resolve_jobs(None), expected);
+    }
+
+    #[test]
+    fn built_pool_has_requested_size() {
+        let two = NonZeroUsize::new(2).expect("two is non-zero");
+        let pool = build_pool(two).expect("pool should build");
+        assert_eq!(pool.current_num_threads(), 2);
+    }
+}
@@ -0,0 +1,50 @@
+//! The line ending and final newline of written text.
+//!
+//! [`render`] joins processed lines with the ending and final newline a
+//! [`Document`] records, and [`Layout`] overrides those with the
+//! `--line-ending` and `--final-newline` choices before rendering.
+
+use mdtablefix::document::{Document, LineEnding};
+
+use crate::{args::FmtArgs, format_values::FinalNewlineArg};
+
+/// Renders processed lines as file contents, with the line ending and final
+/// newline recorded in `source`.
+///
+/// `--check` and `--diff` compare against the same text, so they report
+/// exactly what `--in-place` would change, and the text printed to stdout is
+/// the text `--in-place` would write.
+pub(crate) fn render(source: &Document, fixed: &[String]) -> String {
+    let ending = source.line_ending.as_str();
+    let mut out = fixed.join(ending);
+    if source.trailing_newline && !fixed.is_empty() {
+        out.push_str(ending);
+    }
+    out
+}
+
+/// The `--line-ending` and `--final-newline` choices for written text.
+#[derive(Clone, Copy)]
+pub(crate) struct Layout {
+    /// The ending to write, or `None` to keep each file's own.
+    pub(crate) line_ending: Option<LineEnding>,
+    pub(crate) final_newline: FinalNewlineArg,
+}
+
+impl Layout {
+    pub(crate) fn new(cli: &FmtArgs) -> Self {
+        Self {
+            line_ending: cli.line_ending.into(),
+            final_newline: cli.final_newline,
+        }
+    }
+
+    /// Overrides the layout `source` was parsed with, so it renders as
+    /// requested.
+    pub(crate) fn apply(self, source: &mut Document) {
+        if let Some(ending) = self.line_ending {
+            source.line_ending = ending;
+        }
+        source.trailing_newline = self.final_newline.resolve(source.trailing_newline);
+    }
+}
@@ -0,0 +1,84 @@
+//! Diagnostics printed by the `lint` subcommand.
+//!
+//! `lint` formats each input without writing it and prints one
+//! `PATH:LINE: TRANSFORM ...` line per change the pipeline would make, in the
+//! style compilers use, so editors and CI annotators can jump to the text
+//! that is not yet formatted.
+
+use std::{fmt::Write as _, ops::Range, path::Path};
+
+use mdtablefix::changes::Change;
+
+/// Renders one diagnostic line for each of `changes` to `path`. A file that
+/// differs only in ways no transform records, such as a missing final
+/// newline, gets a single line without a line number.
+pub(crate) fn diagnostics(path: &Path, changes: &[Change], differs: bool) -> String {
+    let mut out = String::new();
+    for change in changes {
+        let _ = writeln!(
+            out,
+            "{}:{}: {}: {}",
+            path.display(),
+            change.before_lines.start + 1,
+            change.transform.name(),
+            describe(&change.before_lines, &change.after_lines)
+        );
+    }
+    if out.is_empty() && differs {
+        let _ = writeln!(out, "{}: would be reformatted", path.display());
+    }
+    out
+}
+
+fn describe(before: &Range<usize>, after: &Range<usize>) -> String {
+    let (verb, count) = match (before.len(), after.len()) {
+        (0, inserted) => ("insert", inserted),
+        (removed, 0) => ("remove", removed),
+        (rewritten, _) => ("rewrite", rewritten),
+    };
+    let noun = if count == 1 { "line" } else { "lines" };
+    format!("would {verb} {count} {noun}")
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for lint diagnostics.
+
+    use mdtablefix::changes::Transform;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn change(before: Range<usize>, after: Range<usize>) -> Change {
+        Change {
+            transform: Transform::Tables,
+            before_lines: before.clone(),
+            after_lines: after,
+            source_lines: before,
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+
+    #[rstest]
+    #[case::rewrite(2..4, 2..5, "a.md:3: tables: would rewrite 2 lines\n")]
+    #[case::insert(1..1, 1..2, "a.md:2: tables: would insert 1 line\n")]
+    #[case::remove(0..3, 0..0, "a.md:1: tables: would remove 3 lines\n")]
+    fn describes_each_change(
+        #[case] before: Range<usize>,
+        #[case] after: Range<usize>,
+        #[case] expected: &str,
+    ) {
+        let changes = [change(before, after)];
+        assert_eq!(diagnostics(Path::new("a.md"), &changes, true), expected);
+    }
+
+    #[test]
+    fn reports_unrecorded_differences() {
+        assert_eq!(
+            diagnostics(Path::new("a.md"), &[], true),
+            "a.md: would be reformatted\n"
+        );
+        assert_eq!(diagnostics(Path::new("a.md"), &[], false), "");
+    }
+}
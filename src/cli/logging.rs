@@ -0,0 +1,39 @@
+//! Diagnostic logging for the command-line tool.
+//!
+//! The library emits `tracing` events but never installs a subscriber, so
+//! [`init`] installs one that writes to stderr at the level chosen with `-v`
+//! and `-q`. `RUST_LOG`, when set, takes precedence, so a single module can
+//! be traced with, for example, `RUST_LOG=mdtablefix::wrap=trace`.
+
+use std::io::{self, IsTerminal};
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+/// Maps the `-v` count and `-q` flag to the most verbose level shown.
+fn level(verbose: u8, quiet: bool) -> LevelFilter {
+    match (quiet, verbose) {
+        (true, _) => LevelFilter::ERROR,
+        (false, 0) => LevelFilter::WARN,
+        (false, 1) => LevelFilter::INFO,
+        (false, 2) => LevelFilter::DEBUG,
+        (false, _) => LevelFilter::TRACE,
+    }
+}
+
+/// Installs the stderr subscriber for this run.
+///
+/// At `-v` each file is logged as it is processed, with its timing; `-vv`
+/// adds every transform that ran, whether it changed the document, and the
+/// library's debug events; `-vvv` adds its branch-level trace events.
+pub(crate) fn init(verbose: u8, quiet: bool) {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::default().add_directive(level(verbose, quiet).into()));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .with_target(false)
+        .without_time()
+        .init();
+}
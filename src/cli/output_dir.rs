@@ -0,0 +1,92 @@
+//! `--output-dir`: write formatted copies under another directory.
+//!
+//! Build pipelines that must not touch the source tree pass
+//! `--output-dir DIR`, and each input is written to the same relative path
+//! below `DIR`. Paths are mirrored relative to the current directory; an
+//! input outside it keeps its whole absolute path below `DIR`.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use mdtablefix::io::write_atomic;
+
+/// Resolves `.` and `..` in `path`, made absolute against the current
+/// directory, without touching the file system.
+fn normalize(path: &Path) -> anyhow::Result<PathBuf> {
+    let absolute =
+        std::path::absolute(path).with_context(|| format!("resolving {}", path.display()))?;
+    let mut out = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    Ok(out)
+}
+
+/// Returns where the formatted copy of `path` goes below `dir`.
+fn mirrored_path(dir: &Path, path: &Path, cwd: &Path) -> PathBuf {
+    let relative = path.strip_prefix(cwd).unwrap_or(path);
+    let mirrored: PathBuf = relative
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect();
+    dir.join(mirrored)
+}
+
+/// Writes `contents`, the encoded formatted form of `path`, to its mirror
+/// below `dir`, creating the directories it needs.
+///
+/// # Errors
+/// Returns an error when the mirror would be `path` itself, or when the copy
+/// cannot be written.
+pub(crate) fn write_copy(dir: &Path, path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    let cwd = normalize(Path::new("."))?;
+    let source = normalize(path)?;
+    let target = mirrored_path(&normalize(dir)?, &source, &cwd);
+    if target == source {
+        bail!(
+            "{}: --output-dir would overwrite the input; use --in-place instead",
+            path.display()
+        );
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    write_atomic(&target, contents).with_context(|| format!("writing {}", target.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for mirroring input paths.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::below_cwd("/work/docs/a.md", "/out/docs/a.md")]
+    #[case::outside_cwd("/elsewhere/a.md", "/out/elsewhere/a.md")]
+    fn mirrors_below_the_output_dir(#[case] path: &str, #[case] expected: &str) {
+        assert_eq!(
+            mirrored_path(Path::new("/out"), Path::new(path), Path::new("/work")),
+            Path::new(expected)
+        );
+    }
+
+    #[test]
+    fn normalizes_parent_components() {
+        let cwd = normalize(Path::new(".")).unwrap();
+        assert_eq!(
+            normalize(Path::new("a/../b/./c.md")).unwrap(),
+            cwd.join("b/c.md")
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Per-file panic isolation for parallel batch runs.
+//!
+//! A panic while formatting one pathological document must not abort the
+//! whole batch. Each file is processed inside [`catch_file_panic`], which
+//! turns an unwinding panic into an ordinary per-file error naming the path,
+//! so the remaining files are still processed and the run exits non-zero.
+
+use std::{
+    any::Any,
+    panic::{AssertUnwindSafe, catch_unwind},
+    path::Path,
+};
+
+use anyhow::anyhow;
+
+/// Runs `f`, converting a panic into an error that names `path`.
+///
+/// `AssertUnwindSafe` is sound here because a panicking closure's state is
+/// discarded: only the converted error escapes, and no shared data is
+/// mutated by per-file processing.
+pub(crate) fn catch_file_panic<T, F>(path: &Path, f: F) -> anyhow::Result<T>
+where
+    F: FnOnce() -> anyhow::Result<T>,
+{
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(anyhow!(
+            "internal error while processing {}: {}",
+            path.display(),
+            panic_message(payload.as_ref())
+        ))
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for per-file panic isolation.
+
+    use super::*;
+
+    #[test]
+    fn passes_through_success() {
+        let result = catch_file_panic(Path::new("ok.md"), || Ok(7));
+        assert_eq!(result.expect("closure succeeded"), 7);
+    }
+
+    #[test]
+    fn converts_panic_into_error_naming_path() {
+        let result: anyhow::Result<()> =
+            catch_file_panic(Path::new("docs/bad.md"), || panic!("tokenizer exploded"));
+        let message = result
+            .expect_err("panic should become an error")
+            .to_string();
+        assert!(message.contains("docs/bad.md"));
+        assert!(message.contains("tokenizer exploded"));
+    }
+
+    #[test]
+    fn formats_owned_panic_payloads() {
+        let detail = String::from("owned detail");
+        let result: anyhow::Result<()> = catch_file_panic(Path::new("x.md"), || panic!("{detail}"));
+        assert!(
+            result
+                .expect_err("panic should become an error")
+                .to_string()
+                .contains("owned detail")
+        );
+    }
+}
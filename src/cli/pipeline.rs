@@ -0,0 +1,281 @@
+//! The formatting pipeline run for each document.
+//!
+//! Wraps the library pipeline with the steps only the binary performs:
+//! expanding include pragmas, retitling the first heading, preserving YAML frontmatter, restricting
+//! work to an `--only-section`, header abbreviation, list renumbering and its comments, list marker
+//! spacing, thematic breaks, heading spacing, blank line limits, and `--plugin` stages. The
+//! binary-only steps leave HTML blocks alone, as the library stages do.
+
+use std::{borrow::Cow, path::Path, time::Instant};
+
+use mdtablefix::{
+    abbreviate_headers,
+    blank_lines::{collapse_blank_lines, limit_blank_lines, space_headings},
+    changes::{ChangeLog, Transform},
+    format_breaks,
+    html_blocks::{HtmlMask, outside_html_blocks},
+    includes::expand_includes,
+    list_markers::{space_list_markers, space_renumbered_list_markers},
+    lists::{ListContinuation, renumber_lists_with, renumber_lists_with_comments},
+    process::process_stream_inner_with_hook,
+    renumber_comments::strip_renumber_comments,
+    rst::unconvertible_rst_tables,
+    sections::section_range,
+    titles::fix_title,
+};
+use tracing::debug;
+
+use crate::{
+    format_opts::FormatOpts,
+    format_values::ImportFormat,
+    frontmatter::split_leading_yaml_frontmatter,
+    plugins::{PluginStage, run_plugins},
+};
+
+/// Runs every enabled transform over `lines`, the text of the file at
+/// `origin`, recording edits in `changes`. `origin` is `None` for standard
+/// input or daemon content without a name, whose includes resolve against
+/// the current directory.
+pub(crate) fn process_lines(
+    lines: &[String],
+    origin: Option<&Path>,
+    opts: &FormatOpts,
+    mut changes: Option<&mut ChangeLog>,
+) -> anyhow::Result<Vec<String>> {
+    // Split off leading YAML frontmatter to preserve it from all transforms
+    let (frontmatter_prefix, body) = split_leading_yaml_frontmatter(lines);
+    let mut result = frontmatter_prefix.to_vec();
+    let expanded;
+    let body = match opts.includes {
+        Some(mode) => {
+            let started = Instant::now();
+            expanded = expand_includes(body, origin.unwrap_or(Path::new("")), mode.into())?;
+            record(&mut changes, Transform::Includes, body, &expanded, started);
+            expanded.as_slice()
+        }
+        None => body,
+    };
+    let titled;
+    let body = if opts.fix_titles {
+        let started = Instant::now();
+        // Retitling keeps every line in place, so the body starts where it
+        // did.
+        let document = [frontmatter_prefix, body].concat();
+        titled = fix_title(&document, origin).split_off(frontmatter_prefix.len());
+        record(&mut changes, Transform::Titles, body, &titled, started);
+        titled.as_slice()
+    } else {
+        body
+    };
+    let Some(heading) = &opts.only_section else {
+        result.extend(run_pipeline(body, Some(frontmatter_prefix), opts, changes)?);
+        return Ok(result);
+    };
+
+    // Outside the selected section every line passes through untouched, and
+    // a document without the section is left as it is.
+    let Some(section) = section_range(body, heading) else {
+        result.extend_from_slice(body);
+        return Ok(result);
+    };
+    let mut section_log = changes
+        .as_deref()
+        .map(|log| ChangeLog::with_line_offset(log.line_offset() + section.start));
+    let fixed = run_pipeline(&body[section.clone()], None, opts, section_log.as_mut())?;
+    if let (Some(log), Some(section_log)) = (changes, section_log) {
+        log.extend(section_log.into_changes());
+    }
+    result.extend_from_slice(&body[..section.start]);
+    result.extend(fixed);
+    result.extend_from_slice(&body[section.end..]);
+    Ok(result)
+}
+
+/// Runs the library pipeline and the binary-only steps over a document body,
+/// or over one section of it when `frontmatter` is `None`.
+fn run_pipeline(
+    body: &[String],
+    frontmatter: Option<&[String]>,
+    opts: &FormatOpts,
+    mut changes: Option<&mut ChangeLog>,
+) -> anyhow::Result<Vec<String>> {
+    let mut out = body.to_vec();
+    if opts.strip_renumber_comments {
+        let started = Instant::now();
+        let stripped = outside_html_blocks(&out, strip_renumber_comments);
+        record(&mut changes, Transform::Renumber, &out, &stripped, started);
+        out = stripped;
+    }
+    plugin_stage(opts, PluginStage::Start, &mut out, &mut changes)?;
+    let after = PluginStage::After;
+    let hook = |stage, doc: &[String]| run_plugins(opts, after(stage), doc);
+    out = process_stream_inner_with_hook(&out, opts.into(), changes.as_deref_mut(), hook)?;
+    if !opts.abbreviations.is_empty() {
+        let started = Instant::now();
+        let width = opts.max_table_width.unwrap_or(opts.wrap_width);
+        let abbreviated = outside_html_blocks(&out, |doc| {
+            abbreviate_headers(doc, &opts.abbreviations, width)
+        });
+        record(
+            &mut changes,
+            Transform::AbbreviateHeaders,
+            &out,
+            &abbreviated,
+            started,
+        );
+        out = abbreviated;
+    }
+    plugin_stage(
+        opts,
+        after(Transform::AbbreviateHeaders),
+        &mut out,
+        &mut changes,
+    )?;
+    list_stages(opts, &mut out, &mut changes)?;
+    if opts.breaks {
+        let started = Instant::now();
+        let formatted = outside_html_blocks(&out, |doc| {
+            format_breaks(doc)
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect()
+        });
+        record(&mut changes, Transform::Breaks, &out, &formatted, started);
+        out = formatted;
+    }
+    plugin_stage(opts, after(Transform::Breaks), &mut out, &mut changes)?;
+    if let Some(spacing) = opts.heading_spacing {
+        let started = Instant::now();
+        let spaced = outside_html_blocks(&out, |doc| space_headings(doc, spacing));
+        record(
+            &mut changes,
+            Transform::HeadingSpacing,
+            &out,
+            &spaced,
+            started,
+        );
+        out = spaced;
+    }
+    plugin_stage(
+        opts,
+        after(Transform::HeadingSpacing),
+        &mut out,
+        &mut changes,
+    )?;
+    if let Some(max) = opts.max_consecutive_blanks {
+        let started = Instant::now();
+        // Only a whole body has document ends to trim, and the blank lines
+        // after frontmatter are limited but kept.
+        let limited = match frontmatter {
+            Some(prefix) => {
+                let document = [prefix, &out].concat();
+                let mut limited = outside_html_blocks(&document, |doc| limit_blank_lines(doc, max));
+                limited.split_off(prefix.len())
+            }
+            None => outside_html_blocks(&out, |doc| collapse_blank_lines(doc, max)),
+        };
+        record(&mut changes, Transform::BlankLines, &out, &limited, started);
+        out = limited;
+    }
+    plugin_stage(opts, after(Transform::BlankLines), &mut out, &mut changes)?;
+    plugin_stage(opts, PluginStage::End, &mut out, &mut changes)?;
+    Ok(out)
+}
+
+/// Renumbers ordered lists and then respaces list markers, so the spacing
+/// step can move item lines by however much renumbering widened or narrowed
+/// each marker.
+fn list_stages(
+    opts: &FormatOpts,
+    out: &mut Vec<String>,
+    changes: &mut Option<&mut ChangeLog>,
+) -> anyhow::Result<()> {
+    let unnumbered = (opts.renumber && opts.list_marker_spacing.is_some()).then(|| out.clone());
+    if opts.renumber {
+        let started = Instant::now();
+        let continuation = if opts.continue_numbering {
+            ListContinuation::Continue
+        } else {
+            ListContinuation::Restart
+        };
+        let renumber = if opts.renumber_comments {
+            renumber_lists_with_comments
+        } else {
+            renumber_lists_with
+        };
+        let renumbered = outside_html_blocks(out, |doc| renumber(doc, continuation));
+        record(changes, Transform::Renumber, out, &renumbered, started);
+        *out = renumbered;
+    }
+    plugin_stage(opts, PluginStage::After(Transform::Renumber), out, changes)?;
+    if let Some(spacing) = opts.list_marker_spacing {
+        let started = Instant::now();
+        let original = HtmlMask::new(unnumbered.as_deref().unwrap_or(out.as_slice()));
+        let spaced = outside_html_blocks(out, |doc| {
+            if original.lines().len() == doc.len() {
+                space_renumbered_list_markers(original.lines(), doc, spacing)
+            } else {
+                space_list_markers(doc, spacing)
+            }
+        });
+        record(changes, Transform::ListMarkers, out, &spaced, started);
+        *out = spaced;
+    }
+    plugin_stage(
+        opts,
+        PluginStage::After(Transform::ListMarkers),
+        out,
+        changes,
+    )
+}
+
+/// Runs the `--plugin` modules registered at `stage`, recording their edits.
+fn plugin_stage(
+    opts: &FormatOpts,
+    stage: PluginStage,
+    out: &mut Vec<String>,
+    changes: &mut Option<&mut ChangeLog>,
+) -> anyhow::Result<()> {
+    let started = Instant::now();
+    if let Some(rewritten) = run_plugins(opts, stage, out)? {
+        record(changes, Transform::Plugin, out, &rewritten, started);
+        *out = rewritten;
+    }
+    Ok(())
+}
+
+/// Warns about RST tables that `--import rst-tables` had to leave unchanged.
+pub(crate) fn warn_unconverted_tables(label: &Path, lines: &[String], opts: &FormatOpts) {
+    if !opts.import.contains(&ImportFormat::RstTables) {
+        return;
+    }
+    let (frontmatter_prefix, body) = split_leading_yaml_frontmatter(lines);
+    for table in unconvertible_rst_tables(body) {
+        eprintln!(
+            "{}:{}: RST table left unchanged because pipe tables cannot express {}",
+            label.display(),
+            frontmatter_prefix.len() + table.line,
+            table.span
+        );
+    }
+}
+
+/// Logs a binary-only step at `-vv` and records its edits when a log is
+/// present.
+fn record(
+    changes: &mut Option<&mut ChangeLog>,
+    transform: Transform,
+    before: &[String],
+    after: &[String],
+    started: Instant,
+) {
+    debug!(
+        transform = transform.name(),
+        changed = before != after,
+        elapsed_us = started.elapsed().as_micros(),
+        "stage applied"
+    );
+    if let Some(log) = changes.as_deref_mut() {
+        log.record(transform, before, after);
+    }
+}
@@ -0,0 +1,102 @@
+//! Placement and execution of `--plugin` WASM modules.
+//!
+//! Each `--plugin PATH[@STAGE]` is compiled while the arguments are parsed,
+//! so a bad module is reported before any file is touched. The plugin then
+//! runs before the first transform (`start`), straight after the named
+//! transform, or after every transform (`end`, the default). Plugins sharing
+//! a stage run in command-line order.
+
+#[cfg(feature = "wasm-plugins")]
+use std::path::Path;
+
+#[cfg(feature = "wasm-plugins")]
+use anyhow::Context;
+use mdtablefix::changes::Transform;
+#[cfg(feature = "wasm-plugins")]
+use mdtablefix::plugins::WasmPlugin;
+
+use crate::format_opts::FormatOpts;
+
+/// Position of a plugin in the pipeline.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PluginStage {
+    /// Before any built-in transform.
+    Start,
+    /// Immediately after the given transform, whether or not it is enabled.
+    After(Transform),
+    /// After every built-in transform.
+    End,
+}
+
+/// A plugin loaded from `--plugin`.
+#[cfg(feature = "wasm-plugins")]
+#[derive(Clone)]
+pub(crate) struct PluginSpec {
+    path: String,
+    stage: PluginStage,
+    plugin: WasmPlugin,
+}
+
+/// Parses `PATH[@STAGE]` and compiles the module at `PATH`.
+#[cfg(feature = "wasm-plugins")]
+pub(crate) fn parse_plugin(value: &str) -> Result<PluginSpec, String> {
+    let (path, stage) = match value.rsplit_once('@') {
+        Some((path, stage)) => (path, parse_stage(stage)?),
+        None => (value, PluginStage::End),
+    };
+    let plugin = WasmPlugin::load(Path::new(path)).map_err(|err| format!("{path}: {err}"))?;
+    Ok(PluginSpec {
+        path: path.to_string(),
+        stage,
+        plugin,
+    })
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn parse_stage(name: &str) -> Result<PluginStage, String> {
+    match name {
+        "start" => Ok(PluginStage::Start),
+        "end" => Ok(PluginStage::End),
+        _ => Transform::from_name(name)
+            .filter(|transform| *transform != Transform::Plugin)
+            .map(PluginStage::After)
+            .ok_or_else(|| {
+                format!("unknown stage `{name}`; expected `start`, `end`, or a transform name")
+            }),
+    }
+}
+
+/// Runs the plugins registered at `stage` over `lines`, returning `None`
+/// when no plugin is registered there.
+#[cfg(feature = "wasm-plugins")]
+pub(crate) fn run_plugins(
+    opts: &FormatOpts,
+    stage: PluginStage,
+    lines: &[String],
+) -> anyhow::Result<Option<Vec<String>>> {
+    let mut out: Option<Vec<String>> = None;
+    for spec in opts.plugins.iter().filter(|spec| spec.stage == stage) {
+        let input = out.as_deref().unwrap_or(lines);
+        let rewritten = spec
+            .plugin
+            .transform(input)
+            .with_context(|| format!("plugin {}", spec.path))?;
+        out = Some(rewritten);
+    }
+    Ok(out)
+}
+
+/// Runs the plugins registered at `stage`; without the `wasm-plugins`
+/// feature there are none.
+#[cfg(not(feature = "wasm-plugins"))]
+#[expect(
+    clippy::unnecessary_wraps,
+    reason = "matches the signature used when plugins are enabled"
+)]
+pub(crate) fn run_plugins(
+    _opts: &FormatOpts,
+    _stage: PluginStage,
+    _lines: &[String],
+) -> anyhow::Result<Option<Vec<String>>> {
+    Ok(None)
+}
@@ -0,0 +1,210 @@
+//! Named option bundles selected with `--preset`, or its alias `--profile`.
+//!
+//! The built-in presets cover common kinds of document. A TOML config file
+//! can adjust any of them, or add new ones, with `[presets.NAME]` or
+//! `[profile.NAME]` tables whose keys are the long flag names, and can set
+//! flags for every run in a `[defaults]` table:
+//!
+//! ```toml
+//! [defaults]
+//! ellipsis = true
+//!
+//! [presets.changelog]
+//! wrap = true
+//!
+//! [profile.api-docs]
+//! wiki-tables = true
+//! ellipsis = false
+//! ```
+//!
+//! The selected preset's keys replace those of `[defaults]`, so a profile can
+//! switch a default off. Flags given on the command line are then enabled on
+//! top, so a preset can be extended but never silently overrides an explicit
+//! flag, and `--disable` wins over all of them.
+
+use serde::Deserialize;
+
+use crate::{config::Config, exit_status::UsageError, format_opts::FormatOpts};
+
+/// Names of the built-in presets.
+pub(crate) const BUILTIN: [&str; 3] = ["readme", "docs-site", "changelog"];
+
+/// Keys accepted in `[defaults]` and preset tables, one per field of
+/// [`Preset`].
+pub(crate) const KEYS: [&str; 14] = [
+    "wrap",
+    "rejoin",
+    "renumber",
+    "continue-numbering",
+    "breaks",
+    "ellipsis",
+    "fences",
+    "footnotes",
+    "footnotes-per-section",
+    "code-emphasis",
+    "code-spans",
+    "headings",
+    "wiki-tables",
+    "keep-clean-tables",
+];
+
+/// Flags set by a preset. `None` leaves a built-in preset's value alone when
+/// a config file overrides it.
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Preset {
+    wrap: Option<bool>,
+    rejoin: Option<bool>,
+    renumber: Option<bool>,
+    continue_numbering: Option<bool>,
+    breaks: Option<bool>,
+    ellipsis: Option<bool>,
+    fences: Option<bool>,
+    footnotes: Option<bool>,
+    footnotes_per_section: Option<bool>,
+    code_emphasis: Option<bool>,
+    code_spans: Option<bool>,
+    headings: Option<bool>,
+    wiki_tables: Option<bool>,
+    keep_clean_tables: Option<bool>,
+}
+
+impl Preset {
+    /// Replaces every flag that `other` sets.
+    fn merge(self, other: Self) -> Self {
+        Self {
+            wrap: other.wrap.or(self.wrap),
+            rejoin: other.rejoin.or(self.rejoin),
+            renumber: other.renumber.or(self.renumber),
+            continue_numbering: other.continue_numbering.or(self.continue_numbering),
+            breaks: other.breaks.or(self.breaks),
+            ellipsis: other.ellipsis.or(self.ellipsis),
+            fences: other.fences.or(self.fences),
+            footnotes: other.footnotes.or(self.footnotes),
+            footnotes_per_section: other.footnotes_per_section.or(self.footnotes_per_section),
+            code_emphasis: other.code_emphasis.or(self.code_emphasis),
+            code_spans: other.code_spans.or(self.code_spans),
+            headings: other.headings.or(self.headings),
+            wiki_tables: other.wiki_tables.or(self.wiki_tables),
+            keep_clean_tables: other.keep_clean_tables.or(self.keep_clean_tables),
+        }
+    }
+
+    /// Enables the preset's flags in `opts`.
+    fn apply(self, opts: &mut FormatOpts) {
+        let on = |flag: Option<bool>| flag.unwrap_or(false);
+        opts.wrap |= on(self.wrap);
+        opts.rejoin |= on(self.rejoin);
+        opts.renumber |= on(self.renumber);
+        opts.continue_numbering |= on(self.continue_numbering);
+        opts.breaks |= on(self.breaks);
+        opts.ellipsis |= on(self.ellipsis);
+        opts.fences |= on(self.fences);
+        opts.footnotes |= on(self.footnotes);
+        opts.footnotes_per_section |= on(self.footnotes_per_section);
+        opts.code_emphasis |= on(self.code_emphasis);
+        opts.code_spans |= on(self.code_spans);
+        opts.headings |= on(self.headings);
+        opts.wiki_tables |= on(self.wiki_tables);
+        opts.keep_clean_tables |= on(self.keep_clean_tables);
+    }
+}
+
+/// The presets shipped with `mdtablefix`.
+fn builtin(name: &str) -> Option<Preset> {
+    let (on, off) = (Some(true), Some(false));
+    let preset = match name {
+        // Project front pages: wrapped prose and tidy code, no footnote rewriting.
+        "readme" => Preset {
+            wrap: on,
+            renumber: on,
+            fences: on,
+            footnotes: off,
+            code_spans: on,
+            headings: on,
+            ..Preset::default()
+        },
+        // Generated documentation sites, where every normalisation helps.
+        "docs-site" => Preset {
+            wrap: on,
+            renumber: on,
+            ellipsis: on,
+            fences: on,
+            footnotes: on,
+            code_emphasis: on,
+            code_spans: on,
+            headings: on,
+            ..Preset::default()
+        },
+        // Changelogs keep their hand-made line breaks and bare issue numbers.
+        "changelog" => Preset {
+            wrap: off,
+            renumber: on,
+            fences: on,
+            footnotes: off,
+            code_spans: on,
+            headings: on,
+            ..Preset::default()
+        },
+        _ => return None,
+    };
+    Some(preset)
+}
+
+/// Enables the flags of the config's `[defaults]` table in `opts`, with the
+/// keys of preset `name`, when one is selected, replacing the defaults.
+///
+/// # Errors
+/// Returns an error when neither the built-in presets nor the config define
+/// `name`.
+pub(crate) fn apply_preset(
+    name: Option<&str>,
+    config: &Config,
+    opts: &mut FormatOpts,
+) -> anyhow::Result<()> {
+    let preset = name
+        .map(|name| resolve(name, config))
+        .transpose()?
+        .unwrap_or_default();
+    config.defaults.merge(preset).apply(opts);
+    Ok(())
+}
+
+/// Returns preset `name` with any overrides from the config file applied.
+fn resolve(name: &str, config: &Config) -> anyhow::Result<Preset> {
+    let preset = match (builtin(name), config.presets.get(name)) {
+        (Some(base), Some(overrides)) => base.merge(*overrides),
+        (Some(base), None) => base,
+        (None, Some(custom)) => *custom,
+        (None, None) => {
+            return Err(UsageError(format!(
+                "unknown preset `{name}`; expected `readme`, `docs-site`, `changelog`, or a \
+                 preset defined in the config file"
+            ))
+            .into());
+        }
+    };
+    Ok(preset)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for the preset registry.
+
+    use super::*;
+
+    #[test]
+    fn every_key_is_a_preset_field() {
+        for key in KEYS {
+            let parsed: Result<Preset, _> = toml::from_str(&format!("{key} = true"));
+            assert!(parsed.is_ok(), "`{key}` is not a preset key");
+        }
+    }
+
+    #[test]
+    fn every_builtin_name_resolves() {
+        for name in BUILTIN {
+            assert!(builtin(name).is_some(), "`{name}` is not a built-in preset");
+        }
+    }
+}
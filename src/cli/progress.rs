@@ -0,0 +1,78 @@
+//! Progress bar for batch runs over many files.
+//!
+//! With the `progress` feature, [`Progress`] draws an `indicatif` bar on
+//! stderr showing the files completed, the file most recently started, and
+//! the estimated time remaining. The bar is shared by the rayon workers, so
+//! it only uses `indicatif`'s thread-safe updates. It stays hidden for a
+//! single file, with `--quiet`, and whenever stdout or stderr is not a
+//! terminal, so piped output and CI logs are unaffected. Without the feature
+//! every method is a no-op.
+
+use std::path::Path;
+
+#[cfg(feature = "progress")]
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Template for the bar: elapsed time, bar, count, ETA, and current file.
+#[cfg(feature = "progress")]
+const TEMPLATE: &str = "{elapsed_precise} [{wide_bar}] {pos}/{len} (ETA {eta}) {msg}";
+
+/// Progress reporting shared by the file-processing workers.
+pub(crate) struct Progress {
+    #[cfg(feature = "progress")]
+    bar: Option<ProgressBar>,
+}
+
+#[cfg_attr(
+    not(feature = "progress"),
+    expect(
+        unused_variables,
+        clippy::unused_self,
+        reason = "no bar is drawn without the feature"
+    )
+)]
+impl Progress {
+    /// Creates the progress bar for `total` files, hidden when `quiet` is set
+    /// or the output is not interactive.
+    pub(crate) fn new(total: usize, quiet: bool) -> Self {
+        #[cfg(feature = "progress")]
+        {
+            use std::io::{IsTerminal, stderr, stdout};
+
+            let visible = !quiet && total > 1 && stdout().is_terminal() && stderr().is_terminal();
+            let bar = visible.then(|| {
+                let style = ProgressStyle::with_template(TEMPLATE)
+                    .unwrap_or_else(|_| ProgressStyle::default_bar())
+                    .progress_chars("=> ");
+                ProgressBar::new(total as u64).with_style(style)
+            });
+            Self { bar }
+        }
+        #[cfg(not(feature = "progress"))]
+        Self {}
+    }
+
+    /// Records that a worker has started on `path`.
+    pub(crate) fn start(&self, path: &Path) {
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.bar {
+            bar.set_message(path.display().to_string());
+        }
+    }
+
+    /// Records that a worker has finished one file.
+    pub(crate) fn advance(&self) {
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.bar {
+            bar.inc(1);
+        }
+    }
+
+    /// Removes the bar before results are printed.
+    pub(crate) fn finish(&self) {
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! JSON audit report describing the transforms applied to each file.
+//!
+//! The report is written once all files have been processed so CI jobs can
+//! archive a single artefact explaining every rewrite. Snippets are bounded
+//! so a pathological rewrite cannot balloon the report.
+
+use std::{fs, path::Path};
+
+use anyhow::Context;
+use mdtablefix::changes::Change;
+use serde_json::{Value, json};
+
+/// Version of the report schema; bump when fields change incompatibly.
+const REPORT_VERSION: u64 = 1;
+/// Maximum number of lines kept for each before/after snippet.
+const MAX_SNIPPET_LINES: usize = 8;
+/// Maximum number of characters kept for each snippet line.
+const MAX_SNIPPET_CHARS: usize = 200;
+
+/// Processing outcome of one file, as recorded in the report.
+pub(crate) struct FileReport<'a> {
+    /// Path exactly as supplied on the command line.
+    pub(crate) path: &'a Path,
+    /// Changes applied to the file, or the error that stopped processing.
+    pub(crate) outcome: Result<&'a [Change], String>,
+}
+
+/// Serializes `files` as JSON and writes the report to `path`.
+///
+/// # Errors
+/// Returns an error if the report cannot be written.
+pub(crate) fn write_report(path: &Path, files: &[FileReport<'_>]) -> anyhow::Result<()> {
+    let mut text =
+        serde_json::to_string_pretty(&render_report(files)).context("serializing change report")?;
+    text.push('\n');
+    fs::write(path, text).with_context(|| format!("writing report {}", path.display()))
+}
+
+fn render_report(files: &[FileReport<'_>]) -> Value {
+    json!({
+        "version": REPORT_VERSION,
+        "files": files.iter().map(file_json).collect::<Vec<_>>(),
+    })
+}
+
+fn file_json(file: &FileReport<'_>) -> Value {
+    let path = file.path.display().to_string();
+    match file.outcome {
+        Ok(changes) => json!({
+            "path": path,
+            "changed": !changes.is_empty(),
+            "changes": changes.iter().map(change_json).collect::<Vec<_>>(),
+        }),
+        Err(ref error) => json!({ "path": path, "error": error }),
+    }
+}
+
+fn change_json(change: &Change) -> Value {
+    json!({
+        "transform": change.transform.name(),
+        "before": side_json(change.before_lines.start, &change.before),
+        "after": side_json(change.after_lines.start, &change.after),
+    })
+}
+
+/// Describes one side of a hunk using one-based line numbers.
+fn side_json(start: usize, lines: &[String]) -> Value {
+    let (snippet, truncated) = bounded_snippet(lines);
+    json!({
+        "start": start + 1,
+        "count": lines.len(),
+        "lines": snippet,
+        "truncated": truncated,
+    })
+}
+
+fn bounded_snippet(lines: &[String]) -> (Vec<String>, bool) {
+    let mut truncated = lines.len() > MAX_SNIPPET_LINES;
+    let snippet = lines
+        .iter()
+        .take(MAX_SNIPPET_LINES)
+        .map(|line| {
+            if line.chars().count() > MAX_SNIPPET_CHARS {
+                truncated = true;
+                line.chars().take(MAX_SNIPPET_CHARS).collect()
+            } else {
+                line.clone()
+            }
+        })
+        .collect();
+    (snippet, truncated)
+}
@@ -0,0 +1,64 @@
+//! `--check-rust-fences` mode: compile the Rust examples in Markdown files.
+//!
+//! Reports each compiler error as `PATH:LINE: fence #N: MESSAGE` on stderr,
+//! naming the fence by its document-order ID, and exits with status 1 when
+//! any example does not compile. Files are left unchanged.
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use mdtablefix::{
+    block_ids::{block_at, number_blocks},
+    rust_fences::{check_rust_fence, rust_fences},
+};
+
+use crate::exit_status::Status;
+
+/// Checks every Rust fence in `files`, or in stdin when `read_stdin` is set.
+///
+/// # Errors
+/// Returns an error when a file cannot be read or `rustc` cannot be run.
+pub(crate) fn run(files: &[PathBuf], read_stdin: bool) -> anyhow::Result<Status> {
+    let mut failures = 0;
+    if read_stdin {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        failures += check_source(Path::new("<stdin>"), &input)?;
+    }
+    for path in files {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        failures += check_source(path, &content)?;
+    }
+    if failures > 0 {
+        eprintln!("{failures} Rust example(s) failed to compile");
+    }
+    Ok(Status::findings_if(failures > 0))
+}
+
+/// Prints the errors for each failing fence in `content` and returns how many
+/// fences failed.
+fn check_source(label: &Path, content: &str) -> anyhow::Result<usize> {
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let blocks = number_blocks(&lines);
+    let mut failures = 0;
+    for fence in rust_fences(&lines) {
+        let errors = check_rust_fence(&fence).context("running rustc")?;
+        let id = block_at(&blocks, fence.line).map(|id| format!("{id}: "));
+        for error in &errors {
+            eprintln!(
+                "{}:{}: {}{}",
+                label.display(),
+                error.line,
+                id.as_deref().unwrap_or_default(),
+                error.message
+            );
+        }
+        failures += usize::from(!errors.is_empty());
+    }
+    Ok(failures)
+}
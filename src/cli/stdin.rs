@@ -0,0 +1,121 @@
+//! Formatting of standard input.
+//!
+//! Without file arguments the tool formats standard input and prints the
+//! result, treating the document as path `-` in reports, or as the
+//! `--stdin-filepath` path when one is given. With `--check` the result is
+//! compared with the input instead, exactly as for a file. Input is decoded
+//! according to `--encoding`, and the result is always printed as UTF-8.
+
+use std::{
+    io::{self, Read},
+    path::Path,
+    time::Instant,
+};
+
+use anyhow::Context;
+use mdtablefix::{anchors::collect_anchors, changes::ChangeLog, document::Document, io::decode};
+use tracing::info;
+
+use crate::{
+    anchor_map::{FileAnchors, write_anchors},
+    args::FmtArgs,
+    batch::log_processed,
+    diff::{paint, unified_diff},
+    discover::is_excluded,
+    exit_status::Status,
+    format_opts::FormatOpts,
+    frontmatter::split_leading_yaml_frontmatter,
+    layout::{Layout, render},
+    lint::diagnostics,
+    pipeline::{process_lines, warn_unconverted_tables},
+    report::{FileReport, write_report},
+    summary::{FileSummary, OutputFormat, print_summary, stats_line},
+};
+
+/// Formats standard input with `opts` and prints the result, writing any requested
+/// report or anchor map for the document as path `-`, or as the
+/// `--stdin-filepath` path.
+///
+/// With `--check` nothing but the diff, when `--diff` is also given, is
+/// printed, and with `lint` only the diagnostics are. Both return
+/// [`Status::Findings`] when the input would change.
+pub(crate) fn run(cli: &FmtArgs, opts: &FormatOpts) -> anyhow::Result<Status> {
+    let mut bytes = Vec::new();
+    io::stdin().read_to_end(&mut bytes)?;
+    let input = decode(bytes, cli.encoding.into())
+        .context("reading standard input")?
+        .text;
+    let mut source = Document::parse(&input);
+    Layout::new(cli).apply(&mut source);
+    let (label, path) = match &cli.stdin_filepath {
+        Some(path) => (path.as_path(), path.as_path()),
+        None => (Path::new("<stdin>"), Path::new("-")),
+    };
+    info!(path = %label.display(), "processing file");
+    let started = Instant::now();
+    let json = cli.format == OutputFormat::Json;
+    let mut log = (cli.report_file.is_some() || json || cli.stats || cli.lint).then(|| {
+        ChangeLog::with_line_offset(split_leading_yaml_frontmatter(&source.lines).0.len())
+    });
+    let fixed = if cli
+        .stdin_filepath
+        .as_deref()
+        .is_some_and(|path| is_excluded(path, &cli.exclude))
+    {
+        source.lines.clone()
+    } else {
+        warn_unconverted_tables(label, &source.lines, opts);
+        process_lines(
+            &source.lines,
+            cli.stdin_filepath.as_deref(),
+            opts,
+            log.as_mut(),
+        )?
+    };
+    let changes = log.map(ChangeLog::into_changes).unwrap_or_default();
+    let rewritten = render(&source, &fixed);
+    let differs = rewritten != input;
+    log_processed(label, differs, started);
+    if let Some(report_path) = &cli.report_file {
+        write_report(
+            report_path,
+            &[FileReport {
+                path,
+                outcome: Ok(&changes),
+            }],
+        )?;
+    }
+    if let Some(anchors_path) = &cli.emit_anchors {
+        write_anchors(
+            anchors_path,
+            &[FileAnchors {
+                path,
+                outcome: Ok(&collect_anchors(&fixed)),
+            }],
+        )?;
+    }
+    if json {
+        print_summary(&[FileSummary {
+            path,
+            outcome: Ok((differs, &changes)),
+        }])?;
+    } else if cli.diff {
+        let diff = unified_diff(label, &input, &rewritten).unwrap_or_default();
+        if cli.colour.enabled() {
+            print!("{}", paint(&diff));
+        } else {
+            print!("{diff}");
+        }
+    } else if cli.lint {
+        print!("{}", diagnostics(label, &changes, differs));
+    } else if !cli.check {
+        print!("{rewritten}");
+    }
+    if cli.stats {
+        eprintln!("{}", stats_line(label, &changes));
+    }
+    if cli.check && differs {
+        eprintln!("{}: would be reformatted", label.display());
+    }
+    Ok(Status::findings_if((cli.check || cli.lint) && differs))
+}
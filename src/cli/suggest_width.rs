@@ -0,0 +1,192 @@
+//! `suggest-width` subcommand: recommend width settings for a set of documents.
+//!
+//! Prose lines are measured as written and tables as the reflow stage would
+//! print them, both in display columns, the accounting `--wrap` and
+//! `--max-table-width` use. The share of each that fits at common widths is
+//! printed with a suggested `--wrap-width` and `--max-table-width`, the
+//! narrowest common width that at least nine in ten lines or tables already
+//! fit, so adopting it rewrites as little as possible. Files are only read.
+
+use std::{fs, ops::Range, path::PathBuf};
+
+use anyhow::Context;
+use clap::Args;
+use mdtablefix::{
+    block_ids::{Construct, number_blocks},
+    document::Document,
+    html_blocks::html_block_ranges,
+    reflow_table,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    discover::expand_paths,
+    exit_status::Status,
+    frontmatter::split_leading_yaml_frontmatter,
+};
+
+/// Widths teams commonly settle on, narrowest first.
+const COMMON_WIDTHS: [usize; 5] = [72, 80, 88, 100, 120];
+
+/// The share of lines or tables, in percent, a suggested width must fit.
+const TARGET_PERCENT: usize = 90;
+
+/// Arguments of `mdtablefix suggest-width`.
+#[derive(Args)]
+pub(crate) struct SuggestWidthArgs {
+    /// Markdown files to measure; directories are searched recursively
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+}
+
+/// Display widths collected from one or more documents.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Widths {
+    /// The width of every prose line.
+    prose: Vec<usize>,
+    /// The width of every table once reflowed.
+    tables: Vec<usize>,
+}
+
+fn display_width(line: &str) -> usize { UnicodeWidthStr::width(line.trim_end()) }
+
+/// Adds the prose line and table widths of `lines` to `widths`.
+///
+/// Prose is every non-blank line outside frontmatter, headings, tables,
+/// fenced code, and HTML blocks.
+fn measure(lines: &[String], widths: &mut Widths) {
+    let body_start = split_leading_yaml_frontmatter(lines).0.len();
+    let mut skipped: Vec<Range<usize>> = html_block_ranges(lines);
+    skipped.push(0..body_start);
+    for block in number_blocks(lines) {
+        let range = block.start - 1..block.end;
+        if block.id.kind == Construct::Table {
+            let width = reflow_table(&lines[range.clone()])
+                .iter()
+                .map(|line| display_width(line))
+                .max();
+            widths.tables.extend(width);
+        }
+        skipped.push(range);
+    }
+    widths.prose.extend(
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(index, line)| {
+                !line.trim().is_empty() && !skipped.iter().any(|range| range.contains(index))
+            })
+            .map(|(_, line)| display_width(line)),
+    );
+}
+
+/// Returns the percentage of `widths` no wider than `limit`, rounded down.
+fn percent_within(widths: &[usize], limit: usize) -> usize {
+    widths.iter().filter(|&&width| width <= limit).count() * 100 / widths.len().max(1)
+}
+
+/// Returns the narrowest common width that fits [`TARGET_PERCENT`] of
+/// `widths`, or the width that does when every common width is too narrow.
+fn suggest(widths: &[usize]) -> Option<usize> {
+    if widths.is_empty() {
+        return None;
+    }
+    let mut sorted = widths.to_vec();
+    sorted.sort_unstable();
+    let needed = (sorted.len() * TARGET_PERCENT).div_ceil(100).max(1);
+    let fitting = sorted[needed - 1];
+    Some(
+        COMMON_WIDTHS
+            .into_iter()
+            .find(|&width| width >= fitting)
+            .unwrap_or(fitting),
+    )
+}
+
+fn print_distribution(label: &str, widths: &[usize]) {
+    let Some(widest) = widths.iter().max() else {
+        println!("{label}: none");
+        return;
+    };
+    println!("{label}: {}, widest {widest} columns", widths.len());
+    for width in COMMON_WIDTHS {
+        println!(
+            "  {width:>3} columns: {:>3}%",
+            percent_within(widths, width)
+        );
+    }
+}
+
+/// Measures every file in `args` and prints the width distributions and the
+/// suggested settings.
+///
+/// # Errors
+/// Returns an error when a path cannot be expanded or a file cannot be read.
+pub(crate) fn run(args: &SuggestWidthArgs) -> anyhow::Result<Status> {
+    let mut widths = Widths::default();
+    for path in expand_paths(&args.files, &[], true)? {
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        measure(&Document::parse(&content).lines, &mut widths);
+    }
+    print_distribution("Prose lines", &widths.prose);
+    print_distribution("Tables", &widths.tables);
+    let mut flags = Vec::new();
+    if let Some(width) = suggest(&widths.prose) {
+        println!(
+            "{}% of prose lines fit at {width} columns.",
+            percent_within(&widths.prose, width)
+        );
+        flags.push(format!("--wrap-width {width}"));
+    }
+    if let Some(width) = suggest(&widths.tables) {
+        println!(
+            "{}% of tables fit at {width} columns.",
+            percent_within(&widths.tables, width)
+        );
+        flags.push(format!("--max-table-width {width}"));
+    }
+    if flags.is_empty() {
+        println!("No prose or tables to measure.");
+    } else {
+        println!("Suggested: {}", flags.join(" "));
+    }
+    Ok(Status::Clean)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for width measurement and suggestions.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[test]
+    fn measures_prose_and_reflowed_tables() {
+        let mut widths = Widths::default();
+        measure(
+            &lines(
+                "---\ntitle: A long frontmatter value\n---\n# Heading\n\nShort line.\nA second \
+                 line.\n\n|a|bb|\n|-|-|\n\n```\nlonger code inside a fence\n```\n\n<div>\n</div>",
+            ),
+            &mut widths,
+        );
+        assert_eq!(
+            widths,
+            Widths {
+                prose: vec![11, 14],
+                tables: vec![13],
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::none(&[], None)]
+    #[case::rounds_up_to_common_width(&[60, 70, 78, 79, 79, 79, 79, 79, 79, 130], Some(80))]
+    #[case::beyond_common_widths(&[150, 150, 160], Some(160))]
+    fn suggests_widths(#[case] widths: &[usize], #[case] expected: Option<usize>) {
+        assert_eq!(suggest(widths), expected);
+    }
+}
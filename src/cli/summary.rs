@@ -0,0 +1,103 @@
+//! Summaries of a run: the machine-readable one printed by `--format json`
+//! and the per-file statistics printed by `--stats`.
+//!
+//! Instead of the formatted text, the JSON summary lists each file with
+//! whether it changed and headline counts from its [`ChangeSummary`], so
+//! editors and CI jobs can act on the result without scraping diagnostics
+//! from stderr. `--stats` prints the same counts as one line per file on
+//! stderr, alongside the normal output.
+
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use anyhow::Context;
+use mdtablefix::changes::{Change, ChangeSummary};
+use serde_json::{Value, json};
+
+/// Version of the summary schema; bump when fields change incompatibly.
+const SUMMARY_VERSION: u64 = 1;
+
+/// Output formats accepted by `--format`.
+#[derive(clap::ValueEnum, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    /// The formatted text, diff, or check diagnostics
+    #[default]
+    Text,
+    /// A JSON summary of every file on stdout
+    Json,
+}
+
+/// Processing outcome of one file, as recorded in the summary.
+pub(crate) struct FileSummary<'a> {
+    /// Path exactly as supplied on the command line.
+    pub(crate) path: &'a Path,
+    /// Whether the file differs from its fixed form and the changes applied
+    /// to it, or the error that stopped processing.
+    pub(crate) outcome: Result<(bool, &'a [Change]), String>,
+}
+
+/// Prints `files` to stdout as a single JSON document.
+///
+/// # Errors
+/// Returns an error if stdout cannot be written.
+pub(crate) fn print_summary(files: &[FileSummary<'_>]) -> anyhow::Result<()> {
+    let summary = json!({
+        "version": SUMMARY_VERSION,
+        "files": files.iter().map(file_json).collect::<Vec<_>>(),
+    });
+    let text = serde_json::to_string_pretty(&summary).context("serializing summary")?;
+    writeln!(io::stdout().lock(), "{text}").context("writing summary")
+}
+
+fn file_json(file: &FileSummary<'_>) -> Value {
+    let path = file.path.display().to_string();
+    match file.outcome {
+        Ok((differs, changes)) => {
+            let summary = ChangeSummary::from_changes(changes);
+            json!({
+                "path": path,
+                "changed": differs,
+                "changes": summary.changes,
+                "tables_reflowed": summary.tables_reflowed,
+                "lines_wrapped": summary.lines_wrapped,
+                "paragraphs_wrapped": summary.paragraphs_wrapped,
+                "html_tables_converted": summary.html_tables_converted,
+                "lists_renumbered": summary.lists_renumbered,
+                "footnotes_rewritten": summary.footnotes_rewritten,
+            })
+        }
+        Err(ref error) => json!({ "path": path, "error": error }),
+    }
+}
+
+/// Formats the `--stats` line for the file at `path`.
+pub(crate) fn stats_line(path: &Path, changes: &[Change]) -> String {
+    let summary = ChangeSummary::from_changes(changes);
+    let counts = [
+        (summary.tables_reflowed, "table reflowed", "tables reflowed"),
+        (
+            summary.html_tables_converted,
+            "HTML table converted",
+            "HTML tables converted",
+        ),
+        (
+            summary.paragraphs_wrapped,
+            "paragraph wrapped",
+            "paragraphs wrapped",
+        ),
+        (
+            summary.lists_renumbered,
+            "list renumbered",
+            "lists renumbered",
+        ),
+        (
+            summary.footnotes_rewritten,
+            "footnote rewritten",
+            "footnotes rewritten",
+        ),
+    ]
+    .map(|(count, one, many)| format!("{count} {}", if count == 1 { one } else { many }));
+    format!("{}: {}", path.display(), counts.join(", "))
+}
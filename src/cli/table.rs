@@ -0,0 +1,188 @@
+//! `table` subcommand: reflow a single table read from standard input.
+//!
+//! Editors that filter a selection through a command can run
+//! `mdtablefix table -` on one table without the rest of the pipeline. The
+//! input holds exactly one Markdown table, or CSV with `--from csv`, and any
+//! blank lines around it are kept.
+
+use std::io::{self, Read};
+
+use anyhow::{Context, anyhow, bail};
+use clap::{Args, ValueEnum};
+use mdtablefix::{
+    block_ids::{Construct, number_blocks},
+    document::Document,
+    table::{Alignment, align_columns, reflow_table_with_max_width, reflow_table_with_style},
+};
+
+use crate::{
+    exit_status::Status,
+    format_values::{TableStyleArg, parse_alignment},
+    layout::render,
+};
+
+/// Arguments of `mdtablefix table`.
+#[derive(Args)]
+pub(crate) struct TableArgs {
+    /// `-`, to read the table from standard input
+    #[arg(value_name = "-", value_parser = ["-"])]
+    input: String,
+    /// Format of the input table
+    #[arg(
+        long = "from",
+        value_enum,
+        value_name = "FORMAT",
+        default_value = "markdown"
+    )]
+    from: TableFormat,
+    /// How to lay out the reflowed table
+    #[arg(
+        long = "table-style",
+        value_enum,
+        value_name = "STYLE",
+        default_value = "padded"
+    )]
+    table_style: TableStyleArg,
+    /// Keep padded tables within N columns where their cells allow
+    #[arg(
+        long = "max-table-width",
+        value_name = "N",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=u64::from(u16::MAX))
+    )]
+    max_table_width: Option<usize>,
+    /// Set column alignments from SPEC, such as `l,c,r`
+    #[arg(
+        long = "align",
+        value_name = "SPEC",
+        value_delimiter = ',',
+        allow_hyphen_values = true,
+        value_parser = parse_alignment
+    )]
+    align: Vec<Option<Alignment>>,
+}
+
+/// Table formats accepted by `table --from`.
+#[derive(ValueEnum, Clone, Copy)]
+enum TableFormat {
+    /// A Markdown pipe table
+    Markdown,
+    /// Comma-separated values, the first record being the header
+    Csv,
+}
+
+/// Converts CSV `text` into Markdown table lines, escaping pipes and folding
+/// line breaks inside quoted fields into spaces.
+fn csv_to_markdown(text: &str) -> anyhow::Result<Vec<String>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let records = reader
+        .records()
+        .collect::<Result<Vec<_>, _>>()
+        .context("parsing CSV")?;
+    let columns = records
+        .iter()
+        .map(csv::StringRecord::len)
+        .max()
+        .unwrap_or(0);
+    let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+    let mut lines: Vec<String> = records
+        .iter()
+        .map(|record| {
+            let mut cells: Vec<String> = record
+                .iter()
+                .map(|field| field.split_whitespace().collect::<Vec<_>>().join(" "))
+                .map(|field| field.replace('|', r"\|"))
+                .collect();
+            cells.resize(columns, String::new());
+            row(cells)
+        })
+        .collect();
+    if !lines.is_empty() {
+        lines.insert(1, row(vec!["---".to_string(); columns]));
+    }
+    Ok(lines)
+}
+
+/// Fails unless `lines` are exactly one Markdown table.
+fn ensure_single_table(lines: &[String]) -> anyhow::Result<()> {
+    match number_blocks(lines).as_slice() {
+        [block] if block.id.kind == Construct::Table && block.start == 1 => {
+            if block.end < lines.len() {
+                bail!(
+                    "expected one table, found more input after line {}",
+                    block.end
+                );
+            }
+            Ok(())
+        }
+        _ => bail!("expected one Markdown table"),
+    }
+}
+
+/// Reflows the table in `input`, keeping the blank lines around it.
+fn reflow(input: &str, args: &TableArgs) -> anyhow::Result<String> {
+    let source = Document::parse(input);
+    let lines = &source.lines;
+    let start = lines
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .ok_or_else(|| anyhow!("no table on standard input"))?;
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(start, |last| last + 1);
+    let table = match args.from {
+        TableFormat::Markdown => {
+            ensure_single_table(&lines[start..end])?;
+            lines[start..end].to_vec()
+        }
+        TableFormat::Csv => csv_to_markdown(&lines[start..end].join("\n"))?,
+    };
+    let table = align_columns(&table, &args.align);
+    let style = args.table_style.into();
+    let reflowed = match args.max_table_width {
+        Some(width) => reflow_table_with_max_width(&table, style, width),
+        None => reflow_table_with_style(&table, style),
+    };
+    let mut out = lines[..start].to_vec();
+    out.extend(reflowed);
+    out.extend_from_slice(&lines[end..]);
+    Ok(render(&source, &out))
+}
+
+/// Reads one table from standard input and prints it reflowed.
+///
+/// # Errors
+/// Returns an error when standard input cannot be read or does not hold
+/// exactly one table.
+pub(crate) fn run(args: &TableArgs) -> anyhow::Result<Status> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    print!("{}", reflow(&input, args)?);
+    Ok(Status::Clean)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for single-table input handling.
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[test]
+    fn converts_csv_with_quotes_and_pipes() {
+        assert_eq!(
+            csv_to_markdown("name,note\nada,\"a|b, c\"\nbob").unwrap(),
+            lines("| name | note |\n| --- | --- |\n| ada | a\\|b, c |\n| bob |  |")
+        );
+    }
+
+    #[test]
+    fn rejects_text_after_the_table() {
+        assert!(ensure_single_table(&lines("| a |\n| - |")).is_ok());
+        assert!(ensure_single_table(&lines("| a |\n| - |\n\nprose")).is_err());
+        assert!(ensure_single_table(&lines("prose")).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! `--check-titles` mode: compare each page's first heading with its title.
+//!
+//! Reports a first `#` heading that differs from the front matter `title`, or
+//! from the file name when there is none, as `PATH:LINE: MESSAGE` on stderr,
+//! and exits with status 1 when any file has one. Files are left unchanged;
+//! `--fix-titles` rewrites headings to the title.
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use mdtablefix::titles::check_title;
+
+use crate::exit_status::Status;
+
+/// Checks every file in `files`, or stdin when `read_stdin` is set. Stdin is
+/// named by `stdin_path`, and only compared with a file name when one is
+/// given.
+///
+/// # Errors
+/// Returns an error when a file or stdin cannot be read.
+pub(crate) fn run(
+    files: &[PathBuf],
+    read_stdin: bool,
+    stdin_path: Option<&Path>,
+) -> anyhow::Result<Status> {
+    let mut mismatches = 0;
+    if read_stdin {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        let label = stdin_path.unwrap_or(Path::new("<stdin>"));
+        mismatches += usize::from(report(label, stdin_path, &input));
+    }
+    for path in files {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        mismatches += usize::from(report(path, Some(path), &content));
+    }
+    if mismatches > 0 {
+        eprintln!("{mismatches} file(s) have a first heading that differs from their title");
+    }
+    Ok(Status::findings_if(mismatches > 0))
+}
+
+/// Prints the mismatch in `content`, if any, and returns whether there was
+/// one.
+fn report(label: &Path, path: Option<&Path>, content: &str) -> bool {
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let Some(mismatch) = check_title(&lines, path) else {
+        return false;
+    };
+    eprintln!("{}:{}: {mismatch}", label.display(), mismatch.line);
+    true
+}
@@ -10,11 +10,13 @@
 //! transformation should run before wrapping and footnote conversion so marker
 //! adjacency is evaluated on the raw input.
 
-use std::{iter::Peekable, vec::IntoIter};
+use std::iter::Peekable;
 
 use crate::{
+    fences::FenceState,
+    table::map_cells,
     textproc::process_text,
-    wrap::{Token, tokenize_markdown},
+    wrap::{MarkdownTokens, Token, leading_indent, markdown_tokens},
 };
 
 /// Split emphasis markers at both ends of `s`.
@@ -101,7 +103,7 @@ fn handle_text_token<'a>(
 }
 
 fn try_fold_matching_emphasis<'a>(
-    tokens: &mut Peekable<IntoIter<Token<'a>>>,
+    tokens: &mut Peekable<MarkdownTokens<'a>>,
     pending: &mut &'a str,
     raw: &'a str,
     code: &str,
@@ -123,7 +125,7 @@ fn try_fold_matching_emphasis<'a>(
 }
 
 fn consume_code_affixes<'a>(
-    tokens: &mut Peekable<IntoIter<Token<'a>>>,
+    tokens: &mut Peekable<MarkdownTokens<'a>>,
     pending: &mut &'a str,
 ) -> (&'a str, &'a str, bool) {
     let mut prefix = std::mem::take(pending);
@@ -152,7 +154,7 @@ fn consume_code_affixes<'a>(
 }
 
 fn handle_code_token<'a>(
-    tokens: &mut Peekable<IntoIter<Token<'a>>>,
+    tokens: &mut Peekable<MarkdownTokens<'a>>,
     code_token: (&'a str, &'a str),
     out: &mut String,
     pending: &mut &'a str,
@@ -176,7 +178,8 @@ fn handle_code_token<'a>(
 ///
 /// Groups of emphasis markers and inline code with no separating spaces are
 /// normalized so that emphasis markers wrap the entire group or are removed
-/// when they solely surround code.
+/// when they solely surround code. Table rows are fixed one cell at a time,
+/// so markers never move across a pipe.
 ///
 /// # Examples
 ///
@@ -190,6 +193,31 @@ fn handle_code_token<'a>(
 /// ```
 #[must_use]
 pub fn fix_code_emphasis(lines: &[String]) -> Vec<String> {
+    let mut fence_tracker = FenceState::default();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut start = 0;
+    for (index, line) in lines.iter().enumerate() {
+        let fence = fence_tracker.observe_source_line(line);
+        if fence.is_code() || !is_table_row(line) {
+            continue;
+        }
+        out.extend(fix_block(&lines[start..index]));
+        out.extend(map_cells(std::slice::from_ref(line), |cell| {
+            fix_block(&[cell.to_string()]).concat()
+        }));
+        start = index + 1;
+    }
+    out.extend(fix_block(&lines[start..]));
+    out
+}
+
+/// Returns `true` for pipe-led lines that table reflow would buffer.
+fn is_table_row(line: &str) -> bool {
+    leading_indent(line).0 < 4 && line.trim_start().starts_with('|')
+}
+
+/// Fixes a run of non-table lines as one token stream.
+fn fix_block(lines: &[String]) -> Vec<String> {
     if lines.is_empty() {
         return Vec::new();
     }
@@ -201,7 +229,7 @@ pub fn fix_code_emphasis(lines: &[String]) -> Vec<String> {
     if !has_code_emphasis_adjacent(&source) {
         return lines.to_vec();
     }
-    let mut tokens = tokenize_markdown(&source).into_iter().peekable();
+    let mut tokens = markdown_tokens(&source).peekable();
     let mut out = String::new();
     let mut pending = "";
     while let Some(token) = tokens.next() {
@@ -223,6 +251,19 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn fixes_table_rows_cell_by_cell() {
+        let input = vec![
+            "| `a`**b** | **`c`** |".to_string(),
+            "| --- | --- |".to_string(),
+        ];
+        let expected = vec![
+            "| **`a`b** | **`c`** |".to_string(),
+            "| --- | --- |".to_string(),
+        ];
+        assert_eq!(fix_code_emphasis(&input), expected);
+    }
+
     #[test]
     fn merges_emphasis_and_code() {
         let input = vec![
@@ -266,7 +307,7 @@ mod tests {
 
     #[test]
     fn consume_code_affixes_clears_mixed_pending_prefix() {
-        let mut tokens = vec![Token::Text("*lead*tail")].into_iter().peekable();
+        let mut tokens = markdown_tokens("*lead*tail").peekable();
         let mut pending = "**";
 
         let (prefix, suffix, modified) = consume_code_affixes(&mut tokens, &mut pending);
@@ -0,0 +1,198 @@
+//! Normalize inline code span spacing and delimiters.
+//!
+//! markdownlint's MD038 flags inline code whose content starts or ends with
+//! spaces, such as `` ` code ` ``. This pass trims those incidental spaces and
+//! re-serialises each span with the shortest backtick run that cannot be
+//! confused with a run inside the code. A single padding space is kept on each
+//! side when the code itself starts or ends with a backtick, because Markdown
+//! needs it to tell the delimiter apart from the content.
+//!
+//! Spans consisting only of spaces are left alone, as are fenced code blocks.
+//! Every rewritten line is tokenized again and kept only when it yields the
+//! same text and the intended code, so a shorter delimiter can never pair with
+//! a stray backtick elsewhere on the line.
+
+use crate::{
+    fences::FenceState,
+    wrap::{Token, markdown_tokens},
+};
+
+/// Trim incidental spaces inside inline code and minimize backtick delimiters.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::code_spans::normalize_code_spans;
+///
+/// let lines = vec!["Run ` cargo test ` or ``` ls ```.".to_string()];
+/// assert_eq!(
+///     normalize_code_spans(&lines),
+///     vec!["Run `cargo test` or `ls`.".to_string()]
+/// );
+/// ```
+#[must_use]
+pub fn normalize_code_spans(lines: &[String]) -> Vec<String> {
+    let mut fences = FenceState::default();
+    lines
+        .iter()
+        .map(|line| {
+            let fence = fences.observe_source_line(line);
+            if fence.is_code() || !line.contains('`') {
+                return line.clone();
+            }
+            normalize_line(line).unwrap_or_else(|| line.clone())
+        })
+        .collect()
+}
+
+/// Token shape used to confirm that a rewritten line parses as intended.
+#[derive(Debug, PartialEq)]
+enum Expected {
+    Text(String),
+    Code(String),
+}
+
+/// Returns the rewritten line, or `None` when nothing changes or the rewrite
+/// would not tokenize back into the intended spans.
+fn normalize_line(line: &str) -> Option<String> {
+    let mut out = String::with_capacity(line.len());
+    let mut expected = Vec::new();
+    for token in markdown_tokens(line) {
+        match token {
+            Token::Code { raw, fence, code } => {
+                let (span, inner) = normalize_span(fence, code);
+                let suffix = code_suffix(raw, fence, code);
+                out.push_str(&span);
+                out.push_str(suffix);
+                expected.push(Expected::Code(inner));
+                push_text(&mut expected, suffix);
+            }
+            Token::Text(text) => {
+                out.push_str(text);
+                push_text(&mut expected, text);
+            }
+            Token::Fence(_) | Token::Newline => return None,
+        }
+    }
+    (out != line && reparse(&out) == expected).then_some(out)
+}
+
+/// Rebuild one span, returning it together with the code text the tokenizer
+/// should report for it (including any padding spaces).
+fn normalize_span(fence: &str, code: &str) -> (String, String) {
+    let trimmed = code.trim_matches(' ');
+    if trimmed.is_empty() {
+        return (format!("{fence}{code}{fence}"), code.to_string());
+    }
+    let pad = if trimmed.starts_with('`') || trimmed.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    let delimiter = "`".repeat(minimal_fence_len(trimmed));
+    let inner = format!("{pad}{trimmed}{pad}");
+    (format!("{delimiter}{inner}{delimiter}"), inner)
+}
+
+/// Returns any inflectional suffix the tokenizer absorbed after the closing
+/// delimiter, such as the `s` in `` `VarGuard`s ``.
+fn code_suffix<'a>(raw: &'a str, fence: &str, code: &str) -> &'a str {
+    raw.get(fence.len() * 2 + code.len()..).unwrap_or("")
+}
+
+/// Smallest delimiter length that does not occur as a backtick run in `code`.
+fn minimal_fence_len(code: &str) -> usize {
+    let mut runs = Vec::new();
+    let mut run = 0;
+    for ch in code.chars().chain(std::iter::once(' ')) {
+        if ch == '`' {
+            run += 1;
+        } else if run > 0 {
+            runs.push(run);
+            run = 0;
+        }
+    }
+    (1..=runs.len() + 1)
+        .find(|len| !runs.contains(len))
+        .unwrap_or(1)
+}
+
+fn push_text(expected: &mut Vec<Expected>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(Expected::Text(previous)) = expected.last_mut() {
+        previous.push_str(text);
+    } else {
+        expected.push(Expected::Text(text.to_string()));
+    }
+}
+
+fn reparse(line: &str) -> Vec<Expected> {
+    let mut parsed = Vec::new();
+    for token in markdown_tokens(line) {
+        match token {
+            Token::Code { raw, fence, code } => {
+                parsed.push(Expected::Code(code.to_string()));
+                push_text(&mut parsed, code_suffix(raw, fence, code));
+            }
+            Token::Text(text) | Token::Fence(text) => push_text(&mut parsed, text),
+            Token::Newline => {}
+        }
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for inline code normalization.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("` code `", "`code`")]
+    #[case("`  padded  `", "`padded`")]
+    #[case("`trailing `", "`trailing`")]
+    #[case("``plain``", "`plain`")]
+    #[case("see ``` a`b ```", "see ``a`b``")]
+    #[case("see ```a``b```", "see `a``b`")]
+    #[case("see ``` `tick` ```", "see `` `tick` ``")]
+    #[case("``  `x  ``", "`` `x ``")]
+    #[case("*` emph `*s", "*`emph`*s")]
+    #[case("` VarGuard `s", "`VarGuard`s")]
+    fn normalizes_spans(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(normalize_code_spans(&[input.to_string()]), vec![expected]);
+    }
+
+    #[rstest]
+    #[case("`code`")]
+    #[case("`` `tick` ``")]
+    #[case("`   `")]
+    #[case("no code here")]
+    #[case("an unmatched ` backtick")]
+    fn leaves_normalized_or_unsafe_spans(#[case] input: &str) {
+        assert_eq!(normalize_code_spans(&[input.to_string()]), vec![input]);
+    }
+
+    #[test]
+    fn skips_fenced_blocks() {
+        let lines: Vec<String> = ["```", "` code `", "```", "` code `"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        assert_eq!(
+            normalize_code_spans(&lines),
+            vec!["```", "` code `", "```", "`code`"]
+        );
+    }
+
+    #[test]
+    fn minimal_fence_skips_used_run_lengths() {
+        assert_eq!(minimal_fence_len("plain"), 1);
+        assert_eq!(minimal_fence_len("a`b"), 2);
+        assert_eq!(minimal_fence_len("a`b``c"), 3);
+        assert_eq!(minimal_fence_len("a``b"), 1);
+    }
+}
@@ -0,0 +1,184 @@
+//! Markdown text together with the layout details lost by splitting it.
+//!
+//! The processing functions work on lines without terminators, so splitting
+//! a file with [`str::lines`] forgets whether it used `\r\n` line endings and
+//! whether it ended with a newline. [`Document::parse`] records both, and
+//! [`Document::render`] restores them, so callers no longer have to guess
+//! how to write the processed lines back.
+
+/// The line terminator used by a document.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Unix-style `\n`.
+    #[default]
+    Lf,
+    /// Windows-style `\r\n`.
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the terminator's characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::document::LineEnding;
+    ///
+    /// assert_eq!(LineEnding::CrLf.as_str(), "\r\n");
+    /// ```
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+
+    /// Returns the terminator ending most of the lines of `text`.
+    ///
+    /// A tie goes to the ending of the first line break, and text without
+    /// one uses [`LineEnding::Lf`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::document::LineEnding;
+    ///
+    /// assert_eq!(LineEnding::detect("a\nb\r\nc\r\n"), LineEnding::CrLf);
+    /// assert_eq!(LineEnding::detect("a\r\nb\n"), LineEnding::CrLf);
+    /// assert_eq!(LineEnding::detect("a"), LineEnding::Lf);
+    /// ```
+    #[must_use]
+    pub fn detect(text: &str) -> Self {
+        let breaks = text.matches('\n').count();
+        let crlf = text.matches("\r\n").count();
+        match crlf.cmp(&(breaks - crlf)) {
+            std::cmp::Ordering::Greater => Self::CrLf,
+            std::cmp::Ordering::Less => Self::Lf,
+            std::cmp::Ordering::Equal => match text.find('\n') {
+                Some(end) if text[..end].ends_with('\r') => Self::CrLf,
+                _ => Self::Lf,
+            },
+        }
+    }
+}
+
+/// A Markdown document split into lines, remembering its line ending and
+/// whether its last line was terminated.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Document {
+    /// The document's lines, without terminators.
+    pub lines: Vec<String>,
+    /// The terminator written between lines.
+    pub line_ending: LineEnding,
+    /// Whether the last line ends with a terminator.
+    pub trailing_newline: bool,
+}
+
+impl Document {
+    /// Splits `text` into lines, recording its layout.
+    ///
+    /// The line ending is the one [`LineEnding::detect`] finds most often;
+    /// `\r\n` and `\n` both end a line, so a document mixing the two
+    /// renders with its dominant ending throughout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::document::{Document, LineEnding};
+    ///
+    /// let doc = Document::parse("a\r\nb");
+    /// assert_eq!(doc.lines, ["a", "b"]);
+    /// assert_eq!(doc.line_ending, LineEnding::CrLf);
+    /// assert!(!doc.trailing_newline);
+    /// ```
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        Self {
+            lines: text.lines().map(str::to_string).collect(),
+            line_ending: LineEnding::detect(text),
+            trailing_newline: text.ends_with('\n'),
+        }
+    }
+
+    /// Returns a document with this one's layout and `lines` as its content.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::{document::Document, process::process_stream_no_wrap};
+    ///
+    /// let doc = Document::parse("|a|b|\r\n");
+    /// let fixed = doc.with_lines(process_stream_no_wrap(&doc.lines));
+    /// assert_eq!(fixed.render(), "| a | b |\r\n");
+    /// ```
+    #[must_use]
+    pub fn with_lines(&self, lines: Vec<String>) -> Self {
+        Self {
+            lines,
+            line_ending: self.line_ending,
+            trailing_newline: self.trailing_newline,
+        }
+    }
+
+    /// Joins the lines with the recorded line ending, ending the text with a
+    /// terminator when the parsed text had one.
+    ///
+    /// A document without lines renders as the empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::document::Document;
+    ///
+    /// for text in ["a\nb\n", "a\r\nb", "", "\n"] {
+    ///     assert_eq!(Document::parse(text).render(), text);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn render(&self) -> String {
+        let ending = self.line_ending.as_str();
+        let mut out = self.lines.join(ending);
+        if self.trailing_newline && !self.lines.is_empty() {
+            out.push_str(ending);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for document parsing and rendering.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::lf("a\nb\n", LineEnding::Lf, true)]
+    #[case::crlf("a\r\nb\r\n", LineEnding::CrLf, true)]
+    #[case::unterminated("a\nb", LineEnding::Lf, false)]
+    #[case::dominant_ending_wins("a\r\nb\nc\n", LineEnding::Lf, true)]
+    #[case::tie_goes_to_the_first("a\r\nb\nc", LineEnding::CrLf, false)]
+    #[case::single_line("a", LineEnding::Lf, false)]
+    fn records_layout(#[case] text: &str, #[case] ending: LineEnding, #[case] trailing: bool) {
+        let doc = Document::parse(text);
+        assert_eq!(doc.line_ending, ending);
+        assert_eq!(doc.trailing_newline, trailing);
+    }
+
+    #[rstest]
+    #[case::lf("| a |\n\ntext\n")]
+    #[case::crlf("| a |\r\n\r\ntext\r\n")]
+    #[case::blank_last_line("text\n\n")]
+    #[case::unterminated_crlf("a\r\nb")]
+    fn round_trips(#[case] text: &str) {
+        assert_eq!(Document::parse(text).render(), text);
+    }
+
+    #[test]
+    fn normalizes_mixed_endings_to_the_dominant_one() {
+        assert_eq!(Document::parse("a\nb\r\nc").render(), "a\nb\nc");
+        assert_eq!(Document::parse("a\nb\r\nc\r\n").render(), "a\r\nb\r\nc\r\n");
+    }
+}
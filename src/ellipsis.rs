@@ -11,10 +11,10 @@ use regex::Regex;
 use tracing::trace;
 
 use crate::{
-    textproc::{Token, push_original_token, tokenize_markdown},
+    fences::FenceState,
+    textproc::{Token, is_escaped, markdown_tokens, push_original_token},
     wrap::{
         BlockKind,
-        FenceTracker,
         LinkReferenceMatcher,
         LinkTitleWindow,
         LinkTitleWindowOutcome,
@@ -85,13 +85,14 @@ fn completes_leaf_block(block_kind: Option<BlockKind>) -> bool {
             BlockKind::Heading
                 | BlockKind::LinkReferenceDefinition
                 | BlockKind::MarkdownlintDirective
+                | BlockKind::MdtablefixDirective
         )
     )
 }
 
 fn replace_ellipsis_in_prose(line: &str) -> String {
     let mut out = String::with_capacity(line.len());
-    for token in tokenize_markdown(line) {
+    for token in markdown_tokens(line) {
         match token {
             Token::Text(text) => replace_text_ellipsis(text, &mut out),
             _ => push_original_token(&token, &mut out),
@@ -116,19 +117,23 @@ fn replace_dot_runs(text: &str, out: &mut String) {
         return;
     }
 
-    let replaced = DOT_RE.replace_all(text, |caps: &regex::Captures<'_>| {
-        let len = caps[0].len();
-        let ellipses = "…".repeat(len / 3);
-        let leftover = ".".repeat(len % 3);
-        format!("{ellipses}{leftover}")
-    });
-    out.push_str(&replaced);
+    let mut copied = 0;
+    for run in DOT_RE.find_iter(text) {
+        // An escaped first dot stays literal, as in `\...`.
+        let start = run.start() + usize::from(is_escaped(text, run.start()));
+        out.push_str(&text[copied..start]);
+        let len = run.end() - start;
+        out.push_str(&"…".repeat(len / 3));
+        out.push_str(&".".repeat(len % 3));
+        copied = run.end();
+    }
+    out.push_str(&text[copied..]);
 }
 
 /// Replace `...` with `…` outside code spans and code blocks.
 #[must_use]
 pub fn replace_ellipsis(lines: &[String]) -> Vec<String> {
-    let mut fence_tracker = FenceTracker::default();
+    let mut fence_tracker = FenceState::default();
     let mut indented_code_tracker = IndentedCodeTracker::default();
     let link_matcher = LinkReferenceMatcher::production();
     let mut link_title_window = LinkTitleWindow::default();
@@ -137,7 +142,7 @@ pub fn replace_ellipsis(lines: &[String]) -> Vec<String> {
         .iter()
         .map(|line| {
             let fence = fence_tracker.observe_source_line(line);
-            if fence.is_fence_marker || fence.is_in_fence {
+            if fence.is_code() {
                 indented_code_tracker.observe_completed_block();
                 link_title_window.observe_fence_context();
                 return line.clone();
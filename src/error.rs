@@ -0,0 +1,104 @@
+//! Errors reported by the fallible `try_*` functions.
+//!
+//! The infallible functions leave input they cannot process unchanged, which
+//! suits a formatter but hides why nothing happened. [`Error`] names the
+//! problem and the line it was found on, so library callers can show a
+//! diagnostic instead of a silent no-op.
+
+use std::fmt;
+
+/// Why a `try_*` function could not process its input.
+///
+/// Line numbers are one-based and count the lines passed to the function
+/// that reported the error.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::Error;
+///
+/// let err = Error::MismatchedColumns {
+///     line: 3,
+///     expected: 2,
+///     found: 1,
+/// };
+/// assert_eq!(err.line(), 3);
+/// assert_eq!(
+///     err.to_string(),
+///     "line 3: table row has 1 cell where the first row has 2"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A table row has a different number of cells from the table's first
+    /// row.
+    MismatchedColumns {
+        /// Line of the row.
+        line: usize,
+        /// Cells in the first row.
+        expected: usize,
+        /// Cells in this row.
+        found: usize,
+    },
+    /// A table's separator row has a different number of cells from the
+    /// table's columns.
+    SeparatorColumns {
+        /// Line of the separator row.
+        line: usize,
+        /// Columns in the table.
+        expected: usize,
+        /// Cells in the separator row.
+        found: usize,
+    },
+}
+
+impl Error {
+    /// Returns the line the error was found on.
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        match self {
+            Self::MismatchedColumns { line, .. } | Self::SeparatorColumns { line, .. } => *line,
+        }
+    }
+
+    /// Returns the error with its line moved down by `lines`, for an error
+    /// found in a slice starting that many lines into the caller's input.
+    pub(crate) const fn offset(mut self, lines: usize) -> Self {
+        match &mut self {
+            Self::MismatchedColumns { line, .. } | Self::SeparatorColumns { line, .. } => {
+                *line += lines;
+            }
+        }
+        self
+    }
+}
+
+fn cells(count: usize) -> &'static str { if count == 1 { "cell" } else { "cells" } }
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::MismatchedColumns {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: table row has {found} {} where the first row has {expected}",
+                cells(found)
+            ),
+            Self::SeparatorColumns {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: table separator has {found} {} for {expected} columns",
+                cells(found)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
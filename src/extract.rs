@@ -0,0 +1,277 @@
+//! Plain-text extraction for search indexing.
+//!
+//! [`extract_text`] walks a Markdown document with the same fence tracking
+//! and block prefixes used by the formatter and returns one [`TextBlock`] per
+//! heading, paragraph, list item, or table row. Each block carries the path of
+//! headings that encloses it and its text with Markdown syntax removed: inline
+//! code keeps its content, links and images keep their visible text, and
+//! emphasis markers, footnote references, and HTML tags are dropped.
+//!
+//! Fenced and indented code blocks, YAML frontmatter, thematic breaks, link
+//! reference definitions, and HTML comments produce no blocks.
+
+mod inline;
+
+use std::sync::LazyLock;
+
+pub use inline::plain_text;
+use regex::Regex;
+
+use crate::{
+    breaks::THEMATIC_BREAK_RE,
+    fences::FenceState,
+    frontmatter::split_leading_yaml_frontmatter,
+    table::{is_separator_line, split_cells},
+    wrap::{BlockKind, BlockquotePrefix, LinkReferenceMatcher, classify_block},
+};
+
+static ATX_HEADING_RE: LazyLock<Regex> = lazy_regex!(
+    r"^ {0,3}(#{1,6})(?:[ \t]+(.*?))?(?:[ \t]+#+)?[ \t]*$",
+    "ATX heading pattern should compile",
+);
+
+static SETEXT_UNDERLINE_RE: LazyLock<Regex> = lazy_regex!(
+    r"^ {0,3}(={3,}|-{3,})[ \t]*$",
+    "Setext underline pattern should compile",
+);
+
+static LIST_ITEM_RE: LazyLock<Regex> = lazy_regex!(
+    r"^\s*(?:[-*+]|\d+[.)])\s+(?:\[[ xX]\]\s+)?(.*)$",
+    "list item pattern should compile",
+);
+
+static FOOTNOTE_DEFINITION_RE: LazyLock<Regex> = lazy_regex!(
+    r"^\s*\[\^[^\]]+\]:\s*(.*)$",
+    "footnote definition pattern should compile",
+);
+
+/// The kind of Markdown block a [`TextBlock`] was extracted from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextBlockKind {
+    /// An ATX or Setext heading of the given level (1–6).
+    Heading(usize),
+    /// A paragraph, including quoted paragraphs and footnote definitions.
+    Paragraph,
+    /// A single bullet, ordered, or task list item with its continuation lines.
+    ListItem,
+    /// A table row, with cells separated by single spaces.
+    TableRow,
+}
+
+/// Plain text extracted from one Markdown block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextBlock {
+    /// The kind of block the text came from.
+    pub kind: TextBlockKind,
+    /// Plain text of the enclosing headings, outermost first. A heading's own
+    /// path ends with the heading itself.
+    pub heading_path: Vec<String>,
+    /// The block's text with Markdown syntax removed and whitespace collapsed.
+    pub text: String,
+    /// One-based line number where the block starts in the input.
+    pub line: usize,
+}
+
+/// Extract headings, paragraphs, list items, and table rows as plain text.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::extract::{TextBlockKind, extract_text};
+///
+/// let lines: Vec<String> = [
+///     "# Guide",
+///     "",
+///     "## Install",
+///     "",
+///     "Run `cargo install` from [crates.io](https://crates.io).",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// let blocks = extract_text(&lines);
+/// assert_eq!(blocks[2].kind, TextBlockKind::Paragraph);
+/// assert_eq!(blocks[2].heading_path, ["Guide", "Install"]);
+/// assert_eq!(blocks[2].text, "Run cargo install from crates.io.");
+/// assert_eq!(blocks[2].line, 5);
+/// ```
+#[must_use]
+pub fn extract_text(lines: &[String]) -> Vec<TextBlock> {
+    let (frontmatter, body) = split_leading_yaml_frontmatter(lines);
+    let mut extractor = Extractor::default();
+    for (index, line) in body.iter().enumerate() {
+        extractor.observe(line, frontmatter.len() + index + 1);
+    }
+    extractor.finish()
+}
+
+/// A block whose lines are still being collected.
+struct OpenBlock {
+    kind: TextBlockKind,
+    line: usize,
+    quoted: bool,
+    parts: Vec<String>,
+}
+
+#[derive(Default)]
+struct Extractor {
+    fences: FenceState,
+    headings: Vec<(usize, String)>,
+    open: Option<OpenBlock>,
+    blocks: Vec<TextBlock>,
+}
+
+impl Extractor {
+    fn observe(&mut self, line: &str, number: usize) {
+        let fence = self.fences.observe_source_line(line);
+        if fence.is_code() {
+            self.close();
+            return;
+        }
+        let (content, quoted) =
+            BlockquotePrefix::parse(line).map_or((line, false), |prefix| (prefix.inner(), true));
+        if content.trim().is_empty() || self.open.as_ref().is_some_and(|open| open.quoted != quoted)
+        {
+            self.close();
+            if content.trim().is_empty() {
+                return;
+            }
+        }
+        if !quoted && self.try_setext(content) {
+            return;
+        }
+        if THEMATIC_BREAK_RE.is_match(content) {
+            self.close();
+            return;
+        }
+        if is_table_line(content) {
+            self.close();
+            if !is_separator_line(content.trim()) {
+                let cells: Vec<String> =
+                    split_cells(content).iter().map(|c| plain_text(c)).collect();
+                self.emit(TextBlockKind::TableRow, number, &cells.join(" "));
+            }
+            return;
+        }
+        self.observe_block_line(content, quoted, number);
+    }
+
+    fn observe_block_line(&mut self, content: &str, quoted: bool, number: usize) {
+        match classify_block(content, LinkReferenceMatcher::production()) {
+            Some(BlockKind::Heading) => {
+                if let Some(caps) = ATX_HEADING_RE.captures(content) {
+                    self.close();
+                    let level = caps[1].len();
+                    let text = plain_text(caps.get(2).map_or("", |m| m.as_str()));
+                    // Quoted headings are excerpts, not part of the outline.
+                    if quoted {
+                        self.emit(TextBlockKind::Paragraph, number, &text);
+                    } else {
+                        self.push_heading(level, &text, number);
+                    }
+                    return;
+                }
+            }
+            Some(BlockKind::Bullet) => {
+                if let Some(caps) = LIST_ITEM_RE.captures(content) {
+                    self.close();
+                    self.open_block(TextBlockKind::ListItem, quoted, number, &caps[1]);
+                    return;
+                }
+            }
+            Some(BlockKind::FootnoteDefinition) => {
+                if let Some(caps) = FOOTNOTE_DEFINITION_RE.captures(content) {
+                    self.close();
+                    self.open_block(TextBlockKind::Paragraph, quoted, number, &caps[1]);
+                    return;
+                }
+            }
+            Some(
+                BlockKind::LinkReferenceDefinition
+                | BlockKind::MarkdownlintDirective
+                | BlockKind::MdtablefixDirective,
+            ) => {
+                self.close();
+                return;
+            }
+            _ => {}
+        }
+        if let Some(open) = self.open.as_mut() {
+            open.parts.push(content.to_string());
+        } else if is_indented_code(content) || is_html_comment(content) {
+            // Code and comments carry no indexable prose.
+        } else {
+            self.open_block(TextBlockKind::Paragraph, quoted, number, content);
+        }
+    }
+
+    /// Turns an open paragraph into a Setext heading when `content` underlines it.
+    fn try_setext(&mut self, content: &str) -> bool {
+        let Some(caps) = SETEXT_UNDERLINE_RE.captures(content) else {
+            return false;
+        };
+        let Some(open) = self
+            .open
+            .take_if(|open| open.kind == TextBlockKind::Paragraph && !open.quoted)
+        else {
+            return false;
+        };
+        let level = if caps[1].starts_with('=') { 1 } else { 2 };
+        let text = plain_text(&open.parts.join(" "));
+        self.push_heading(level, &text, open.line);
+        true
+    }
+
+    fn push_heading(&mut self, level: usize, text: &str, line: usize) {
+        self.headings.retain(|(outer, _)| *outer < level);
+        self.headings.push((level, text.to_string()));
+        self.emit(TextBlockKind::Heading(level), line, text);
+    }
+
+    fn open_block(&mut self, kind: TextBlockKind, quoted: bool, line: usize, first: &str) {
+        self.open = Some(OpenBlock {
+            kind,
+            line,
+            quoted,
+            parts: vec![first.to_string()],
+        });
+    }
+
+    fn close(&mut self) {
+        if let Some(open) = self.open.take() {
+            let text = plain_text(&open.parts.join(" "));
+            self.emit(open.kind, open.line, &text);
+        }
+    }
+
+    fn emit(&mut self, kind: TextBlockKind, line: usize, text: &str) {
+        if text.is_empty() && !matches!(kind, TextBlockKind::Heading(_)) {
+            return;
+        }
+        self.blocks.push(TextBlock {
+            kind,
+            heading_path: self.headings.iter().map(|(_, text)| text.clone()).collect(),
+            text: text.to_string(),
+            line,
+        });
+    }
+
+    fn finish(mut self) -> Vec<TextBlock> {
+        self.close();
+        self.blocks
+    }
+}
+
+fn is_table_line(content: &str) -> bool { content.trim_start().starts_with('|') }
+
+fn is_indented_code(content: &str) -> bool {
+    content.starts_with('\t') || content.starts_with("    ")
+}
+
+fn is_html_comment(content: &str) -> bool {
+    let trimmed = content.trim();
+    trimmed.starts_with("<!--") && trimmed.ends_with("-->")
+}
+
+#[cfg(test)]
+mod tests;
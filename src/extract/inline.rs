@@ -0,0 +1,130 @@
+//! Inline Markdown to plain-text conversion used by [`super::extract_text`].
+//!
+//! Code spans are swapped for private-use placeholders before any rewriting,
+//! so link, emphasis, and HTML patterns never look inside code, and the code
+//! content is restored verbatim afterwards.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::wrap::{Token, markdown_tokens};
+
+const CODE_OPEN: char = '\u{e000}';
+const CODE_CLOSE: char = '\u{e001}';
+
+static IMAGE_OR_LINK_RE: LazyLock<Regex> = lazy_regex!(
+    r"!?\[([^\]]*)\](?:\([^)]*\)|\[[^\]]*\])",
+    "inline link pattern should compile",
+);
+
+static FOOTNOTE_REF_RE: LazyLock<Regex> =
+    lazy_regex!(r"\[\^[^\]]+\]", "footnote reference pattern should compile",);
+
+static AUTOLINK_RE: LazyLock<Regex> = lazy_regex!(
+    r"<((?:https?|mailto|ftp):[^>\s]+)>",
+    "autolink pattern should compile",
+);
+
+static HTML_TAG_RE: LazyLock<Regex> = lazy_regex!(
+    r"<!--.*?-->|</?[A-Za-z][A-Za-z0-9-]*(?:\s[^>]*)?/?>",
+    "HTML tag pattern should compile",
+);
+
+static PLACEHOLDER_RE: LazyLock<Regex> = lazy_regex!(
+    "\u{e000}(\\d+)\u{e001}",
+    "code placeholder pattern should compile",
+);
+
+/// Convert one line or paragraph of inline Markdown to plain text.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::extract::plain_text;
+///
+/// assert_eq!(
+///     plain_text("See **[the guide](docs/guide.md)** for `--wrap`.[^1]"),
+///     "See the guide for --wrap."
+/// );
+/// ```
+#[must_use]
+pub fn plain_text(markdown: &str) -> String {
+    let mut code = Vec::new();
+    let mut masked = String::with_capacity(markdown.len());
+    for token in markdown_tokens(markdown) {
+        match token {
+            Token::Code {
+                raw,
+                fence,
+                code: inner,
+            } => {
+                masked.push(CODE_OPEN);
+                masked.push_str(&code.len().to_string());
+                masked.push(CODE_CLOSE);
+                code.push(strip_code_padding(inner).to_string());
+                masked.push_str(raw.get(fence.len() * 2 + inner.len()..).unwrap_or(""));
+            }
+            Token::Text(text) | Token::Fence(text) => masked.push_str(text),
+            Token::Newline => masked.push(' '),
+        }
+    }
+    let without_refs = FOOTNOTE_REF_RE.replace_all(&masked, "");
+    let without_links = IMAGE_OR_LINK_RE.replace_all(&without_refs, "$1");
+    let without_autolinks = AUTOLINK_RE.replace_all(&without_links, "$1");
+    let without_html = HTML_TAG_RE.replace_all(&without_autolinks, "");
+    let text = strip_emphasis(&unescape(&without_html));
+    let restored = PLACEHOLDER_RE.replace_all(&text, |caps: &regex::Captures<'_>| {
+        caps[1]
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| code.get(index))
+            .cloned()
+            .unwrap_or_default()
+    });
+    restored.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Strips the single padding space Markdown removes from both ends of code.
+fn strip_code_padding(code: &str) -> &str {
+    if code.len() >= 2 && code.starts_with(' ') && code.ends_with(' ') && !code.trim().is_empty() {
+        &code[1..code.len() - 1]
+    } else {
+        code
+    }
+}
+
+/// Removes backslashes that escape ASCII punctuation.
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' && chars.peek().is_some_and(char::is_ascii_punctuation) {
+            continue;
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Drops emphasis and strikethrough markers while keeping intraword
+/// underscores (`snake_case`) and free-standing operators (`2 * 3`).
+fn strip_emphasis(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    for (index, &ch) in chars.iter().enumerate() {
+        let before = index.checked_sub(1).and_then(|i| chars.get(i)).copied();
+        let after = chars.get(index + 1).copied();
+        let is_spaced = |side: Option<char>| side.is_none_or(char::is_whitespace);
+        let is_word = |side: Option<char>| side.is_some_and(char::is_alphanumeric);
+        let keep = match ch {
+            '*' | '~' => is_spaced(before) && is_spaced(after),
+            '_' => is_word(before) && is_word(after),
+            _ => true,
+        };
+        if keep {
+            out.push(ch);
+        }
+    }
+    out
+}
@@ -0,0 +1,131 @@
+//! Unit tests for plain-text extraction.
+
+use rstest::rstest;
+
+use super::*;
+use crate::test_support::lines;
+
+fn summary(blocks: &[TextBlock]) -> Vec<(TextBlockKind, String, String, usize)> {
+    blocks
+        .iter()
+        .map(|block| {
+            (
+                block.kind,
+                block.heading_path.join(" > "),
+                block.text.clone(),
+                block.line,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn tracks_heading_paths_across_levels() {
+    let input = lines("# A\n\nIntro.\n\n## B\n\nIn B.\n\n### C\n\nIn C.\n\n## D\n\nIn D.");
+    assert_eq!(
+        summary(&extract_text(&input)),
+        vec![
+            (TextBlockKind::Heading(1), "A".into(), "A".into(), 1),
+            (TextBlockKind::Paragraph, "A".into(), "Intro.".into(), 3),
+            (TextBlockKind::Heading(2), "A > B".into(), "B".into(), 5),
+            (TextBlockKind::Paragraph, "A > B".into(), "In B.".into(), 7),
+            (TextBlockKind::Heading(3), "A > B > C".into(), "C".into(), 9),
+            (
+                TextBlockKind::Paragraph,
+                "A > B > C".into(),
+                "In C.".into(),
+                11
+            ),
+            (TextBlockKind::Heading(2), "A > D".into(), "D".into(), 13),
+            (TextBlockKind::Paragraph, "A > D".into(), "In D.".into(), 15),
+        ]
+    );
+}
+
+#[test]
+fn recognizes_setext_headings_and_joins_paragraph_lines() {
+    let input = lines("Title\n=====\nfirst line\nsecond *line*\n\nPart\n---\n\n---\n");
+    assert_eq!(
+        summary(&extract_text(&input)),
+        vec![
+            (TextBlockKind::Heading(1), "Title".into(), "Title".into(), 1),
+            (
+                TextBlockKind::Paragraph,
+                "Title".into(),
+                "first line second line".into(),
+                3
+            ),
+            (
+                TextBlockKind::Heading(2),
+                "Title > Part".into(),
+                "Part".into(),
+                6
+            ),
+        ]
+    );
+}
+
+#[test]
+fn skips_code_frontmatter_and_definitions() {
+    let input = lines(
+        "---\ntitle: x\n---\n# Doc\n\n```rust\n# not a heading\n```\n\n    indented code\n\n\
+         [ref]: https://example.com\n<!-- comment -->\n\nText.",
+    );
+    assert_eq!(
+        summary(&extract_text(&input)),
+        vec![
+            (TextBlockKind::Heading(1), "Doc".into(), "Doc".into(), 4),
+            (TextBlockKind::Paragraph, "Doc".into(), "Text.".into(), 15),
+        ]
+    );
+}
+
+#[test]
+fn splits_list_items_and_table_rows() {
+    let input =
+        lines("- [x] done `item`\n  continued\n1. second\n\n| a | [b](u) |\n| - | - |\n| 1 | 2 |");
+    assert_eq!(
+        summary(&extract_text(&input)),
+        vec![
+            (
+                TextBlockKind::ListItem,
+                String::new(),
+                "done item continued".into(),
+                1
+            ),
+            (TextBlockKind::ListItem, String::new(), "second".into(), 3),
+            (TextBlockKind::TableRow, String::new(), "a b".into(), 5),
+            (TextBlockKind::TableRow, String::new(), "1 2".into(), 7),
+        ]
+    );
+}
+
+#[test]
+fn quoted_headings_do_not_change_the_path() {
+    let input = lines("# Top\n> # Quoted\n> text\n\n[^1]: A *note*.");
+    assert_eq!(
+        summary(&extract_text(&input)),
+        vec![
+            (TextBlockKind::Heading(1), "Top".into(), "Top".into(), 1),
+            (TextBlockKind::Paragraph, "Top".into(), "Quoted".into(), 2),
+            (TextBlockKind::Paragraph, "Top".into(), "text".into(), 3),
+            (TextBlockKind::Paragraph, "Top".into(), "A note.".into(), 5),
+        ]
+    );
+}
+
+#[rstest]
+#[case("![alt text](img.png) and [link][ref]", "alt text and link")]
+#[case(
+    "<https://example.com> and <b>bold</b>",
+    "https://example.com and bold"
+)]
+#[case(
+    "keep snake_case and 2 * 3, drop __strong__ ~~gone~~",
+    "keep snake_case and 2 * 3, drop strong gone"
+)]
+#[case("`` `[not](a link)` `` stays", "`[not](a link)` stays")]
+#[case(r"escaped \*stars\* and \[brackets\]", "escaped stars and [brackets]")]
+fn converts_inline_markdown(#[case] input: &str, #[case] expected: &str) {
+    assert_eq!(plain_text(input), expected);
+}
@@ -3,9 +3,10 @@
 //! `compress_fences` reduces safe outer delimiters to three backticks while
 //! preserving nested fence-like content whose marker runs are literal text.
 //! The local `FENCE_RE` defines which delimiter lines this module can
-//! normalize, while `FenceTracker` provides the depth-aware structural Markdown
-//! fence semantics shared with wrapping; its `observe_source_fence` supplies the
-//! structural marker parse so this module never re-runs `wrap::is_fence`.
+//! normalize, while [`FenceState`] provides the depth-aware structural Markdown
+//! fence semantics shared by every transform; its `observe_source_fence`
+//! supplies the structural marker parse so this module never re-runs
+//! [`is_fence`].
 //! `attach_orphan_specifiers` then finds orphaned fence specifier lines and
 //! attaches them to the following fence, preserving the retained indentation
 //! and normalized language specifier.
@@ -13,11 +14,12 @@ use std::sync::LazyLock;
 
 use regex::Regex;
 
-use crate::wrap::{FenceObservation, FenceTracker, ObservedFence};
-
 mod attachment;
+mod state;
 
 use attachment::attach_to_next_fence;
+pub(crate) use state::ObservedFence;
+pub use state::{FenceObservation, FenceState, is_fence};
 
 static FENCE_RE: LazyLock<Regex> = lazy_regex!(
     r"^(\s*)(`{3,}|~{3,})([A-Za-z0-9_+.,-]*)\s*$",
@@ -226,10 +228,10 @@ impl<'a> ParsedLine<'a> {
     /// Observe `line` against `tracker` and compute its compressed rewrite once.
     ///
     /// The blockquote depth and structural fence marker come from the tracker's
-    /// single parse via [`FenceTracker::observe_source_fence`]; only the local
+    /// single parse via [`FenceState::observe_source_fence`]; only the local
     /// normalization regex runs in addition, so the raw line is never handed to
     /// `is_fence` again.
-    fn observe(tracker: &mut FenceTracker, line: &'a str) -> Self {
+    fn observe(tracker: &mut FenceState, line: &'a str) -> Self {
         let observed: ObservedFence<'a> = tracker.observe_source_fence(line);
         Self {
             line,
@@ -329,7 +331,7 @@ fn advance_fence_block(
 /// ```
 #[must_use]
 pub fn compress_fences(lines: &[String]) -> Vec<String> {
-    let mut tracker = FenceTracker::new();
+    let mut tracker = FenceState::new();
     let mut pending_block = None;
     let mut out = Vec::with_capacity(lines.len());
 
@@ -381,7 +383,7 @@ pub fn compress_fences(lines: &[String]) -> Vec<String> {
 #[must_use]
 pub fn attach_orphan_specifiers(lines: &[String]) -> Vec<String> {
     let mut out = Vec::with_capacity(lines.len());
-    let mut tracker = FenceTracker::new();
+    let mut tracker = FenceState::new();
     let mut lines = lines.iter().peekable();
 
     while let Some(line) = lines.next() {
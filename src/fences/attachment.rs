@@ -1,7 +1,6 @@
 //! Orphan fence specifier attachment helpers.
 
-use super::{FENCE_RE, is_null_lang};
-use crate::wrap::FenceTracker;
+use super::{FENCE_RE, FenceState, is_null_lang};
 
 /// Result of an orphan fence specifier attachment operation.
 ///
@@ -87,7 +86,7 @@ pub(super) fn attach_to_next_fence<'a, I>(
     indent: &str,
     out: &mut Vec<String>,
     specifier_line: &str,
-    tracker: &mut FenceTracker,
+    tracker: &mut FenceState,
 ) -> AttachmentOutcome
 where
     I: Iterator<Item = &'a String> + Clone,
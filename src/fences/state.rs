@@ -0,0 +1,355 @@
+//! Shared fenced code block semantics.
+//!
+//! [`FenceState`] is the single authority on where fenced code blocks open
+//! and close; every transform that must leave code untouched observes lines
+//! through it. [`is_fence`] parses one line into its fence components.
+//!
+//! The rules follow the Markdown specification where the line-oriented
+//! pipeline can:
+//!
+//! - a fence is a run of three or more backticks or tildes, optionally inside blockquote markers;
+//! - a backtick fence's info string may not contain a backtick, so a line such as `` ```code``` ``
+//!   is an inline code span rather than an opener;
+//! - a fence closes only with the same marker character, a run at least as long as the opener's, no
+//!   info string, and at most three more columns of indentation than the opener;
+//! - dropping below the opener's blockquote depth closes the fence.
+//!
+//! Openers may be indented by any amount because list containers are not
+//! tracked here, so a fence nested in a deep list item is still recognised.
+
+use regex::Regex;
+use tracing::{debug, trace};
+
+use crate::wrap::{BlockquotePrefix, leading_indent};
+
+static FENCE_RE: std::sync::LazyLock<Regex> =
+    // Capture: indent, fence run of 3+ backticks/tilde, and the full info string (incl. leading
+    // spaces)
+    lazy_regex!(
+        r"^(\s*)(`{3,}|~{3,})([^\r\n]*)$",
+        "wrapping fence delimiter and info string pattern should compile",
+    );
+
+/// Return fence components if the line is a fence delimiter.
+///
+/// The function captures:
+/// - the leading indentation,
+/// - the fence marker itself (three or more backticks or tildes),
+/// - the full trailing “info string” (including any leading spaces and attributes).
+///
+/// # Examples
+///
+/// ```rust
+/// use mdtablefix::fences::is_fence;
+/// assert_eq!(is_fence("```rust"), Some(("", "```", "rust")));
+/// assert_eq!(is_fence("``` rust"), Some(("", "```", " rust")));
+/// assert_eq!(is_fence("``` rust linenums"), Some(("", "```", " rust linenums")));
+/// assert_eq!(is_fence("> > ```rust"), Some(("> > ", "```", "rust")));
+/// assert!(is_fence("```inline code```").is_none());
+/// assert!(is_fence("not a fence").is_none());
+/// ```
+#[must_use]
+#[rustfmt::skip]
+pub fn is_fence(line: &str) -> Option<(&str, &str, &str)> {
+    let context = FenceLine::parse(line);
+    is_inner_fence(context.inner).map(|(inner_indent, fence, info)| {
+        let indent = &line[..context.prefix_len + inner_indent.len()];
+        (indent, fence, info)
+    })
+}
+
+#[rustfmt::skip]
+fn is_inner_fence(line: &str) -> Option<(&str, &str, &str)> {
+    FENCE_RE.captures(line).and_then(|cap| {
+        let inner_indent = cap.get(1).map_or("", |m| m.as_str());
+        let fence  = cap.get(2).map_or("", |m| m.as_str());
+        let info   = cap.get(3).map_or("", |m| m.as_str());
+        (!(fence.starts_with('`') && info.contains('`'))).then_some((inner_indent, fence, info))
+    })
+}
+
+struct FenceLine<'a> {
+    inner: &'a str,
+    depth: usize,
+    prefix_len: usize,
+}
+
+impl<'a> FenceLine<'a> {
+    fn parse(line: &'a str) -> Self {
+        BlockquotePrefix::parse(line).map_or(
+            Self {
+                inner: line,
+                depth: 0,
+                prefix_len: 0,
+            },
+            |prefix| Self {
+                inner: prefix.inner(),
+                depth: prefix.depth(),
+                prefix_len: prefix.raw_prefix().len(),
+            },
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct OpenFence {
+    marker: char,
+    marker_len: usize,
+    indent: usize,
+    open_depth: usize,
+}
+
+impl OpenFence {
+    /// Returns `true` when a delimiter line closes this fence.
+    fn closes_with(self, depth: usize, marker: char, marker_len: usize, indent: usize) -> bool {
+        depth == self.open_depth
+            && marker == self.marker
+            && marker_len >= self.marker_len
+            && indent <= self.indent + 3
+    }
+}
+
+/// How one source line changed the fence state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FenceObservation {
+    /// The line was inside a fence before it was observed.
+    pub was_in_fence: bool,
+    /// The line is a fence delimiter, whether or not it changed the state.
+    pub is_fence_marker: bool,
+    /// The line leaves the state inside a fence.
+    pub is_in_fence: bool,
+}
+
+impl FenceObservation {
+    /// Returns `true` when the line is a fence delimiter or fenced content,
+    /// and so must be left untouched.
+    #[must_use]
+    pub const fn is_code(self) -> bool { self.is_fence_marker || self.is_in_fence }
+}
+
+/// A source-line fence observation paired with the structural fence parse of
+/// that same line.
+///
+/// Callers that need the marker components (indentation, marker run, info
+/// string) obtain them here rather than re-running [`is_fence`], keeping
+/// [`FenceState`] the single authority for the line's fence classification.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ObservedFence<'a> {
+    pub(crate) observation: FenceObservation,
+    /// The `(indent, marker, info)` components when the line is a fence marker,
+    /// with `indent` spanning any blockquote prefix, as [`is_fence`] returns.
+    pub(crate) fence: Option<(&'a str, &'a str, &'a str)>,
+}
+
+/// Tracks Markdown fenced code block state across lines.
+///
+/// The state centralises fence matching logic so that callers share the
+/// same semantics for opening and closing blocks.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::fences::FenceState;
+///
+/// let mut fences = FenceState::new();
+/// assert!(!fences.in_fence(0));
+/// assert!(fences.observe("```rust", 0));
+/// assert!(fences.in_fence(0));
+/// assert!(fences.observe("```", 0));
+/// assert!(!fences.in_fence(0));
+/// ```
+#[derive(Default, Debug)]
+pub struct FenceState {
+    open: Option<OpenFence>,
+}
+
+impl FenceState {
+    /// Create a new state with no active fence.
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Update the state with a potential fence line whose blockquote prefix
+    /// sits at `depth`.
+    ///
+    /// Returns `true` when the line is treated as a fence marker and updates
+    /// the internal state accordingly.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the fence regular expression yields an empty marker, which
+    /// would indicate the regex is inconsistent with Markdown fence rules.
+    #[must_use]
+    pub fn observe(&mut self, line: &str, depth: usize) -> bool {
+        self.observe_inner(FenceLine::parse(line).inner, depth)
+    }
+
+    pub(crate) fn observe_inner(&mut self, line: &str, depth: usize) -> bool {
+        self.observe_parsed(depth, is_inner_fence(line))
+    }
+
+    /// Update the state from an already-parsed inner fence, avoiding a second
+    /// regex match when the caller has parsed the line itself.
+    fn observe_parsed(&mut self, depth: usize, parsed: Option<(&str, &str, &str)>) -> bool {
+        if let Some(open) = self.open
+            && depth < open.open_depth
+        {
+            debug!(
+                transition = "implicit_close",
+                reason = "blockquote_depth_decreased",
+                depth,
+                open_depth = open.open_depth,
+                open_marker_len = open.marker_len,
+                "fence state changed"
+            );
+            self.open = None;
+        }
+
+        let Some((indent, fence, info)) = parsed else {
+            return false;
+        };
+
+        let mut chars = fence.chars();
+        let marker_ch = chars.next().expect("FENCE_RE guarantees a non-empty fence");
+        let marker_len = chars.count() + 1;
+        let (indent, _) = leading_indent(indent);
+
+        match self.open {
+            Some(open)
+                if info.trim().is_empty()
+                    && open.closes_with(depth, marker_ch, marker_len, indent) =>
+            {
+                debug!(
+                    transition = "matching_close",
+                    depth,
+                    open_depth = open.open_depth,
+                    marker_len,
+                    open_marker_len = open.marker_len,
+                    "fence state changed"
+                );
+                self.open = None;
+            }
+            Some(open) => {
+                trace!(
+                    transition = "unchanged",
+                    reason = "incompatible_active_opener",
+                    depth,
+                    open_depth = open.open_depth,
+                    marker_len,
+                    open_marker_len = open.marker_len,
+                    "fence marker did not change state"
+                );
+            }
+            None => {
+                debug!(
+                    transition = "open",
+                    depth,
+                    open_depth = depth,
+                    marker_len,
+                    open_marker_len = marker_len,
+                    "fence state changed"
+                );
+                self.open = Some(OpenFence {
+                    marker: marker_ch,
+                    marker_len,
+                    indent,
+                    open_depth: depth,
+                });
+            }
+        }
+
+        true
+    }
+
+    /// Update the state from a source line, including any blockquote prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::fences::FenceState;
+    ///
+    /// let mut fences = FenceState::new();
+    /// assert!(fences.observe_line("> > ```rust"));
+    /// assert!(fences.in_fence(2));
+    /// ```
+    #[must_use]
+    pub fn observe_line(&mut self, line: &str) -> bool {
+        self.observe_source_line(line).is_fence_marker
+    }
+
+    /// Observe a source line, returning the state before and after it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::fences::FenceState;
+    ///
+    /// let mut fences = FenceState::new();
+    /// assert!(fences.observe_source_line("~~~").is_code());
+    /// assert!(fences.observe_source_line("```").is_code());
+    /// assert!(fences.observe_source_line("~~~ not a closer").is_code());
+    /// assert!(fences.observe_source_line("~~~").is_fence_marker);
+    /// assert!(!fences.observe_source_line("prose").is_code());
+    /// ```
+    pub fn observe_source_line(&mut self, line: &str) -> FenceObservation {
+        self.observe_source_fence(line).observation
+    }
+
+    /// Observe a source line, returning the fence-state transition together with
+    /// the structural fence parse of the line.
+    ///
+    /// The line's blockquote prefix and fence marker are parsed exactly once and
+    /// reused for both the state update and the returned `fence` components, so
+    /// callers need not run [`is_fence`] again.
+    pub(crate) fn observe_source_fence<'a>(&mut self, line: &'a str) -> ObservedFence<'a> {
+        let context = FenceLine::parse(line);
+        let parsed_inner = is_inner_fence(context.inner);
+        let was_in_fence = self.in_fence(context.depth);
+        let is_fence_marker = self.observe_parsed(context.depth, parsed_inner);
+        let is_in_fence = self.in_fence(context.depth);
+        let fence = parsed_inner.map(|(inner_indent, marker, info)| {
+            let indent = &line[..context.prefix_len + inner_indent.len()];
+            (indent, marker, info)
+        });
+        ObservedFence {
+            observation: FenceObservation {
+                was_in_fence,
+                is_fence_marker,
+                is_in_fence,
+            },
+            fence,
+        }
+    }
+
+    /// Check whether a line at blockquote depth `current_depth` is inside a
+    /// fenced block.
+    #[must_use]
+    pub fn in_fence(&self, current_depth: usize) -> bool {
+        self.open
+            .is_some_and(|open| current_depth >= open.open_depth)
+    }
+
+    /// Check fence state at the blockquote depth represented by a source line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::fences::FenceState;
+    ///
+    /// let mut fences = FenceState::new();
+    /// assert!(fences.observe_line("> ```"));
+    /// assert!(fences.in_fence_for_line("> code"));
+    /// assert!(!fences.in_fence_for_line("outside the quote"));
+    /// ```
+    #[must_use]
+    pub fn in_fence_for_line(&self, line: &str) -> bool {
+        self.in_fence(FenceLine::parse(line).depth)
+    }
+}
+
+#[cfg(test)]
+#[path = "state_tests.rs"]
+mod tests;
+
+#[cfg(test)]
+#[path = "state_property_tests.rs"]
+mod property_tests;
@@ -0,0 +1,165 @@
+//! Compaction of repeated footnote references.
+//!
+//! Machine conversion often cites one footnote twice in a row, as in
+//! `text[^1][^1]`. [`compact_footnote_refs`] drops every reference that
+//! repeats the one directly before it. Under
+//! [`FootnoteRefCompaction::Punctuated`] references separated only by commas,
+//! semicolons, and spaces are first joined, so `text[^1], [^2]` becomes
+//! `text[^1][^2]` and `text[^1]; [^1]` becomes `text[^1]`. Inline code and
+//! fenced code blocks are left alone.
+
+use regex::{Captures, Regex};
+
+use crate::{
+    fences::FenceState,
+    textproc::{Token, is_escaped, markdown_tokens, push_original_token},
+};
+
+static REF_RE: std::sync::LazyLock<Regex> = lazy_regex!(
+    r"\[\^[^\]\s]+\]",
+    "footnote reference pattern should compile",
+);
+
+static ADJACENT_RUN_RE: std::sync::LazyLock<Regex> = lazy_regex!(
+    r"(?:\[\^[^\]\s]+\]){2,}",
+    "adjacent footnote reference pattern should compile",
+);
+
+static PUNCTUATED_RUN_RE: std::sync::LazyLock<Regex> = lazy_regex!(
+    r"\[\^[^\]\s]+\](?:[ ,;]*\[\^[^\]\s]+\])+",
+    "punctuated footnote reference pattern should compile",
+);
+
+/// Which runs of footnote references [`compact_footnote_refs`] merges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum FootnoteRefCompaction {
+    /// Drop a reference written directly after an identical one.
+    #[default]
+    Repeated,
+    /// Also join references separated only by commas, semicolons, and
+    /// spaces, then drop the repeats.
+    Punctuated,
+}
+
+impl FootnoteRefCompaction {
+    fn run_pattern(self) -> &'static Regex {
+        match self {
+            Self::Repeated => &ADJACENT_RUN_RE,
+            Self::Punctuated => &PUNCTUATED_RUN_RE,
+        }
+    }
+}
+
+/// Removes immediately repeated footnote references outside code.
+///
+/// Only consecutive repeats are removed, so `[^1][^2][^1]` keeps all three
+/// references. A run that starts with an escaped bracket is not a reference
+/// and is left as written.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::footnotes::{FootnoteRefCompaction, compact_footnote_refs};
+///
+/// let lines = vec!["See this[^1][^1], and `[^2][^2]`.".to_string()];
+/// assert_eq!(
+///     compact_footnote_refs(&lines, FootnoteRefCompaction::Repeated),
+///     vec!["See this[^1], and `[^2][^2]`."]
+/// );
+///
+/// let lines = vec!["Both[^1], [^2]; [^2].".to_string()];
+/// assert_eq!(
+///     compact_footnote_refs(&lines, FootnoteRefCompaction::Punctuated),
+///     vec!["Both[^1][^2]."]
+/// );
+/// ```
+#[must_use]
+pub fn compact_footnote_refs(lines: &[String], policy: FootnoteRefCompaction) -> Vec<String> {
+    let mut fences = FenceState::default();
+    lines
+        .iter()
+        .map(|line| {
+            let fence = fences.observe_source_line(line);
+            if fence.is_code() || !line.contains("[^") {
+                return line.clone();
+            }
+            compact_line(line, policy)
+        })
+        .collect()
+}
+
+fn compact_line(line: &str, policy: FootnoteRefCompaction) -> String {
+    let mut out = String::with_capacity(line.len());
+    for token in markdown_tokens(line) {
+        match token {
+            Token::Text(text) => out.push_str(&compact_text(text, policy)),
+            other => push_original_token(&other, &mut out),
+        }
+    }
+    out
+}
+
+fn compact_text(text: &str, policy: FootnoteRefCompaction) -> String {
+    policy
+        .run_pattern()
+        .replace_all(text, |caps: &Captures<'_>| {
+            let run = &caps[0];
+            let start = caps.get(0).map_or(0, |m| m.start());
+            if is_escaped(text, start) {
+                return run.to_string();
+            }
+            let mut refs: Vec<&str> = REF_RE.find_iter(run).map(|m| m.as_str()).collect();
+            refs.dedup();
+            refs.concat()
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for footnote reference compaction.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::repeated("a[^1][^1]", FootnoteRefCompaction::Repeated, "a[^1]")]
+    #[case::tripled("a[^n][^n][^n] b", FootnoteRefCompaction::Repeated, "a[^n] b")]
+    #[case::distinct("a[^1][^2][^1]", FootnoteRefCompaction::Repeated, "a[^1][^2][^1]")]
+    #[case::punctuation_kept("a[^1], [^1]", FootnoteRefCompaction::Repeated, "a[^1], [^1]")]
+    #[case::comma("a[^1], [^1]", FootnoteRefCompaction::Punctuated, "a[^1]")]
+    #[case::merged(
+        "a[^1];[^2] , [^3]",
+        FootnoteRefCompaction::Punctuated,
+        "a[^1][^2][^3]"
+    )]
+    #[case::full_stop("a[^1]. [^1]", FootnoteRefCompaction::Punctuated, "a[^1]. [^1]")]
+    #[case::escaped(r"a\[^1][^1]", FootnoteRefCompaction::Repeated, r"a\[^1][^1]")]
+    #[case::code_span(
+        "`[^1][^1]` b[^2][^2]",
+        FootnoteRefCompaction::Repeated,
+        "`[^1][^1]` b[^2]"
+    )]
+    #[case::fenced(
+        "```\n[^1][^1]\n```",
+        FootnoteRefCompaction::Repeated,
+        "```\n[^1][^1]\n```"
+    )]
+    fn compacts_references(
+        #[case] input: &str,
+        #[case] policy: FootnoteRefCompaction,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            compact_footnote_refs(&lines(input), policy),
+            lines(expected)
+        );
+    }
+}
@@ -1,33 +1,14 @@
 //! Inline footnote helpers.
 //!
 //! Handles inline reference detection and heading detection so the
-//! top-level converter can focus on orchestration.
+//! top-level converter can focus on orchestration. References are found by
+//! hand-written scanners rather than regular expressions because this check
+//! runs on every text token of every line; see the performance budget in
+//! `docs/developers-guide.md`.
 
-use std::sync::LazyLock;
-
-use regex::{Captures, Regex};
-
-static INLINE_FN_RE: LazyLock<Regex> = lazy_regex!(
-    r"(?P<pre>^|[^0-9])(?P<punc>[.!?);:])(?P<style>[*_]*)(?P<num>\d+)(?P<boundary>\s|$)",
-    "inline footnote reference pattern should compile",
-);
-
-static COLON_FN_RE: LazyLock<Regex> = lazy_regex!(
-    r"(?P<pre>^|[^0-9])\s+(?P<style>[*_]*)(?P<num>\d+)\s*:(?P<colons>:*)(?P<boundary>\s|[[:punct:]]|$)",
-    "space-colon footnote reference pattern should compile",
-);
-
-static ATX_HEADING_RE: LazyLock<Regex> = lazy_regex!(
-    r"(?x)
-        ^\s*
-        (?:>+\s*)*
-        (?:[-*+]\s+|\d+[.)]\s+)*
-        \#{1,6}
-        (?:\s|$)
-    ",
-    "atx heading prefix",
-);
+use std::borrow::Cow;
 
+/// A bare reference such as `.2` or `!*3*` found by [`match_inline_at`].
 #[derive(Clone, Copy)]
 struct InlineFootnote<'a> {
     pre: &'a str,
@@ -37,53 +18,288 @@ struct InlineFootnote<'a> {
     boundary: &'a str,
 }
 
-#[inline]
-fn capture_parts<'a>(caps: &'a Captures<'a>) -> InlineFootnote<'a> {
-    InlineFootnote {
-        pre: &caps["pre"],
-        punc: &caps["punc"],
-        style: &caps["style"],
-        num: &caps["num"],
-        boundary: &caps["boundary"],
+/// A bare reference such as ` 2:` found by [`match_colon_at`].
+#[derive(Clone, Copy)]
+struct ColonFootnote<'a> {
+    pre: &'a str,
+    gap: &'a str,
+    style: &'a str,
+    num: &'a str,
+    colons: &'a str,
+    boundary: &'a str,
+}
+
+/// Returns the end of the run of characters matching `pred` from `start`.
+fn skip_while(text: &str, start: usize, pred: impl Fn(char) -> bool) -> usize {
+    text[start..]
+        .find(|c| !pred(c))
+        .map_or(text.len(), |offset| start + offset)
+}
+
+/// Returns the byte offsets where the text after the optional prefix
+/// character can begin for a match starting at `start`.
+///
+/// The prefix is either empty, which is only allowed at the start of the
+/// text, or one character that is not an ASCII digit, so `12.3` never
+/// matches inside a number.
+fn body_starts(text: &str, start: usize) -> impl Iterator<Item = usize> {
+    let empty = (start == 0).then_some(start);
+    let single = text[start..]
+        .chars()
+        .next()
+        .filter(|c| !c.is_ascii_digit())
+        .map(|c| start + c.len_utf8());
+    empty.into_iter().chain(single)
+}
+
+/// Returns the trailing boundary at `at`: a single whitespace character, or
+/// the empty string at the end of the text.
+fn whitespace_boundary(text: &str, at: usize) -> Option<usize> {
+    match text[at..].chars().next() {
+        None => Some(at),
+        Some(c) if c.is_whitespace() => Some(at + c.len_utf8()),
+        Some(_) => None,
     }
 }
 
-#[inline]
-fn build_footnote(parts: InlineFootnote<'_>) -> String {
-    format!(
-        "{}{}{}[^{}]{}",
-        parts.pre, parts.punc, parts.style, parts.num, parts.boundary
-    )
+/// Matches a reference such as `word.2 ` starting at byte `start`, returning
+/// its end and parts.
+fn match_inline_at(text: &str, start: usize) -> Option<(usize, InlineFootnote<'_>)> {
+    body_starts(text, start).find_map(|body| {
+        let punc_end = body
+            + text[body..]
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '.' | '!' | '?' | ')' | ';' | ':'))?
+                .len_utf8();
+        let style_end = skip_while(text, punc_end, |c| matches!(c, '*' | '_'));
+        let num_end = skip_while(text, style_end, |c| c.is_ascii_digit());
+        if num_end == style_end {
+            return None;
+        }
+        let end = whitespace_boundary(text, num_end)?;
+        Some((
+            end,
+            InlineFootnote {
+                pre: &text[start..body],
+                punc: &text[body..punc_end],
+                style: &text[punc_end..style_end],
+                num: &text[style_end..num_end],
+                boundary: &text[num_end..end],
+            },
+        ))
+    })
 }
 
-/// Convert inline numeric references into Markdown footnote syntax.
-pub(super) fn convert_inline(text: &str) -> String {
-    let out = INLINE_FN_RE.replace_all(text, |caps: &Captures| build_footnote(capture_parts(caps)));
-    COLON_FN_RE
-        .replace_all(&out, |caps: &Captures| {
-            let pre = &caps["pre"];
-            let style = &caps["style"];
-            let num = &caps["num"];
-            let colons = &caps["colons"];
-            let boundary = &caps["boundary"];
-            let mat = caps.get(0).expect("regex matched without capture");
-            let match_str = mat.as_str();
-            let num_match = caps.name("num").expect("regex matched without num capture");
-            let style_start = caps
-                .name("style")
-                .map_or(num_match.start() - mat.start(), |m| m.start() - mat.start());
-            let captured_gap = &match_str[pre.len()..style_start];
-            let gap = if pre.is_empty() {
-                captured_gap
-            } else if pre.chars().last().is_some_and(char::is_alphanumeric) {
-                ""
-            } else {
-                captured_gap
-            };
-            format!("{pre}{gap}{style}[^{num}]:{colons}{boundary}")
+/// Matches a reference such as `word 2:` starting at byte `start`, returning
+/// its end and parts.
+///
+/// The colon must be followed by whitespace, ASCII punctuation, or the end of
+/// the text. Extra colons are kept, except that the last one serves as the
+/// boundary when nothing else can.
+fn match_colon_at(text: &str, start: usize) -> Option<(usize, ColonFootnote<'_>)> {
+    body_starts(text, start).find_map(|body| {
+        let gap_end = skip_while(text, body, char::is_whitespace);
+        let style_end = skip_while(text, gap_end, |c| matches!(c, '*' | '_'));
+        let num_end = skip_while(text, style_end, |c| c.is_ascii_digit());
+        if gap_end == body || num_end == style_end {
+            return None;
+        }
+        let colon = skip_while(text, num_end, char::is_whitespace);
+        if !text[colon..].starts_with(':') {
+            return None;
+        }
+        let colons_end = skip_while(text, colon + 1, |c| c == ':');
+        let (colons_end, end) = match text[colons_end..].chars().next() {
+            None => (colons_end, colons_end),
+            Some(c) if c.is_whitespace() || c.is_ascii_punctuation() => {
+                (colons_end, colons_end + c.len_utf8())
+            }
+            Some(_) if colons_end > colon + 1 => (colons_end - 1, colons_end),
+            Some(_) => return None,
+        };
+        Some((
+            end,
+            ColonFootnote {
+                pre: &text[start..body],
+                gap: &text[body..gap_end],
+                style: &text[gap_end..style_end],
+                num: &text[style_end..num_end],
+                colons: &text[colon + 1..colons_end],
+                boundary: &text[colons_end..end],
+            },
+        ))
+    })
+}
+
+/// Returns the earliest byte offset at or after `from` where an inline
+/// reference could start: the character before the next sentence
+/// punctuation mark.
+fn next_inline_candidate(text: &str, from: usize) -> Option<usize> {
+    let punc = from + text[from..].find(['.', '!', '?', ')', ';', ':'])?;
+    Some(from.max(punc - previous_char_len(text, punc)))
+}
+
+/// Returns the earliest byte offset at or after `from` where a colon
+/// reference could start: the character before the run of whitespace,
+/// emphasis markers, and digits that precedes the next colon.
+fn next_colon_candidate(text: &str, from: usize) -> Option<usize> {
+    let colon = from + text[from..].find(':')?;
+    let run = text[..colon]
+        .trim_end_matches(|c: char| {
+            c.is_whitespace() || c.is_ascii_digit() || matches!(c, '*' | '_')
         })
-        .into_owned()
+        .len();
+    Some(from.max(run - previous_char_len(text, run)))
+}
+
+/// Returns the length of the character ending at byte `at`, or zero at the
+/// start of the text.
+fn previous_char_len(text: &str, at: usize) -> usize {
+    text[..at].chars().next_back().map_or(0, char::len_utf8)
+}
+
+/// Replaces every non-overlapping match found by `matcher`, scanning left to
+/// right and resuming after each match.
+///
+/// `candidate` skips ahead to the next offset where a match could start, so
+/// lines without the relevant punctuation are scanned once and returned
+/// without copying.
+fn replace_matches<'a, T>(
+    text: &'a str,
+    candidate: impl Fn(&str, usize) -> Option<usize>,
+    matcher: impl Fn(&'a str, usize) -> Option<(usize, T)>,
+    build: impl Fn(&mut String, T),
+) -> Cow<'a, str> {
+    let mut out = String::new();
+    let mut copied = 0;
+    let mut start = 0;
+    while let Some(at) = candidate(text, start) {
+        if let Some((end, parts)) = matcher(text, at) {
+            out.push_str(&text[copied..at]);
+            build(&mut out, parts);
+            copied = end;
+            start = end;
+        } else {
+            start = at + text[at..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    if copied == 0 {
+        return Cow::Borrowed(text);
+    }
+    out.push_str(&text[copied..]);
+    Cow::Owned(out)
+}
+
+/// Convert inline numeric references into Markdown footnote syntax.
+pub(super) fn convert_inline(text: &str) -> Cow<'_, str> {
+    let out = replace_matches(
+        text,
+        next_inline_candidate,
+        match_inline_at,
+        |out, parts| {
+            out.extend([
+                parts.pre,
+                parts.punc,
+                parts.style,
+                "[^",
+                parts.num,
+                "]",
+                parts.boundary,
+            ]);
+        },
+    );
+    let rewritten = replace_matches(&out, next_colon_candidate, match_colon_at, |out, parts| {
+        let gap = if parts.pre.chars().last().is_some_and(char::is_alphanumeric) {
+            ""
+        } else {
+            parts.gap
+        };
+        out.extend([
+            parts.pre,
+            gap,
+            parts.style,
+            "[^",
+            parts.num,
+            "]:",
+            parts.colons,
+            parts.boundary,
+        ]);
+    });
+    match rewritten {
+        Cow::Owned(rewritten) => Cow::Owned(rewritten),
+        Cow::Borrowed(_) => out,
+    }
 }
 
 /// Determine whether a string is the prefix of an ATX heading.
-pub(super) fn is_atx_heading_prefix(s: &str) -> bool { ATX_HEADING_RE.is_match(s) }
+///
+/// Blockquote markers and list markers may precede the `#` run, which must
+/// be one to six characters long and followed by whitespace or the end of
+/// the line.
+pub(super) fn is_atx_heading_prefix(s: &str) -> bool {
+    let mut rest = s.trim_start_matches(|c: char| c == '>' || c.is_whitespace());
+    while let Some(after) = strip_list_marker(rest) {
+        rest = after;
+    }
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    (1..=6).contains(&hashes)
+        && rest[hashes..]
+            .chars()
+            .next()
+            .is_none_or(char::is_whitespace)
+}
+
+/// Strips a bullet or ordered list marker and the whitespace after it.
+fn strip_list_marker(text: &str) -> Option<&str> {
+    let after = text.strip_prefix(['-', '*', '+']).or_else(|| {
+        let digits = text.trim_start_matches(|c: char| c.is_ascii_digit());
+        (digits.len() < text.len())
+            .then(|| digits.strip_prefix(['.', ')']))
+            .flatten()
+    })?;
+    let trimmed = after.trim_start();
+    (trimmed.len() < after.len()).then_some(trimmed)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for the inline reference scanners.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::sentence_end("Done.1 Next", "Done.[^1] Next")]
+    #[case::line_end("Done!2", "Done![^2]")]
+    #[case::emphasis("Done.*3*", "Done.*3*")]
+    #[case::emphasis_before_number("Done.*3 x", "Done.*[^3] x")]
+    #[case::decimal("Pi is 3.14 today", "Pi is 3.14 today")]
+    #[case::line_start(".4 starts", ".[^4] starts")]
+    #[case::colon("See 5: here", "See[^5]: here")]
+    #[case::colon_after_space("( 6:", "( [^6]:")]
+    #[case::extra_colons("word 7:: x", "word[^7]:: x")]
+    #[case::colon_as_boundary("word 8::x", "word[^8]::x")]
+    #[case::colon_then_letter("word 9:x", "word 9:x")]
+    #[case::time("at 10:30", "at 10:30")]
+    #[case::non_ascii("café.5 ok", "café.[^5] ok")]
+    fn converts_references(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(convert_inline(input), expected);
+    }
+
+    #[test]
+    fn borrows_text_without_references() {
+        assert!(matches!(convert_inline("Plain text."), Cow::Borrowed(_)));
+    }
+
+    #[rstest]
+    #[case("# Title", true)]
+    #[case("> - 1. ###### Deep", true)]
+    #[case("####### Seven", false)]
+    #[case("#hashtag", false)]
+    #[case("-# not a list", false)]
+    fn detects_heading_prefixes(#[case] input: &str, #[case] expected: bool) {
+        assert_eq!(is_atx_heading_prefix(input), expected);
+    }
+}
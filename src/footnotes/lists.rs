@@ -6,7 +6,7 @@
 use regex::Captures;
 
 use super::parsing::{FOOTNOTE_LINE_RE, is_definition_continuation};
-use crate::wrap::FenceTracker;
+use crate::fences::FenceState;
 
 /// Find the trailing block of lines that satisfy a predicate.
 pub(super) fn trimmed_range<F>(lines: &[String], predicate: F) -> (usize, usize)
@@ -51,10 +51,10 @@ pub(super) fn has_h2_heading_before(lines: &[String], start: usize) -> bool {
 
 /// Check for existing footnote definitions before the block.
 pub(super) fn has_existing_footnote_block(lines: &[String], start: usize) -> bool {
-    let mut fences = FenceTracker::default();
+    let mut fences = FenceState::default();
     for l in &lines[..start] {
         let fence = fences.observe_source_line(l);
-        if fence.is_fence_marker || fence.is_in_fence {
+        if fence.is_code() {
             continue;
         }
         let mut t = l.trim_start();
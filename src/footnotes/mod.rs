@@ -2,30 +2,56 @@
 //!
 //! Converts bare numeric references in text to GitHub-flavoured Markdown
 //! footnote links and normalizes footnote numbering and ordering by
-//! orchestrating specialised submodules.
+//! orchestrating specialised submodules. [`FootnotePlacement`] chooses whether
+//! numbering and definitions span the document or each section, and
+//! [`footnote_tooltips`] summarises the resulting definitions for hover text.
+//! [`compact_footnote_refs`] removes references repeated back to back.
 
+mod compact;
 mod inline;
 mod lists;
 mod parsing;
+mod placement;
 mod renumber;
+mod tooltips;
 
+pub use compact::{FootnoteRefCompaction, compact_footnote_refs};
 use inline::{convert_inline, is_atx_heading_prefix};
 use lists::convert_block;
+pub use placement::FootnotePlacement;
+use placement::place_per_section;
 use renumber::renumber_footnotes;
+pub use tooltips::footnote_tooltips;
 
-use crate::textproc::{Token, push_original_token, tokenize_markdown};
+use crate::{
+    fences::FenceState,
+    textproc::{Token, markdown_tokens, push_original_token},
+};
+
+/// Returns `true` for a line holding one HTML comment, such as the
+/// placeholders that stand for masked blocks, whose numbers are not
+/// references.
+fn is_comment_line(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with("<!--") && line.ends_with("-->")
+}
 
 /// Convert bare numeric footnote references to Markdown footnote syntax.
 #[must_use]
-pub fn convert_footnotes(lines: &[String]) -> Vec<String> {
+pub fn convert_footnotes(lines: &[String]) -> Vec<String> { convert(lines, false) }
+
+fn convert(lines: &[String], comments: bool) -> Vec<String> {
     let mut out = Vec::with_capacity(lines.len());
 
+    let mut fences = FenceState::default();
+
     for line in lines {
-        if is_atx_heading_prefix(line) {
+        let fence = fences.observe_source_line(line);
+        if fence.is_code() || is_atx_heading_prefix(line) || is_comment_line(line) {
             out.push(line.clone());
         } else {
             let mut converted = String::with_capacity(line.len());
-            for token in tokenize_markdown(line) {
+            for token in markdown_tokens(line) {
                 match token {
                     Token::Text(t) => converted.push_str(&convert_inline(t)),
                     other => push_original_token(&other, &mut converted),
@@ -36,10 +62,86 @@ pub fn convert_footnotes(lines: &[String]) -> Vec<String> {
     }
 
     convert_block(&mut out);
-    renumber_footnotes(&mut out);
+    renumber_footnotes(&mut out, comments);
     out
 }
 
+/// Convert bare numeric footnote references like [`convert_footnotes`], then
+/// place the definitions as `placement` requires.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::footnotes::{FootnotePlacement, convert_footnotes_with};
+///
+/// let lines: Vec<String> = [
+///     "## A",
+///     "",
+///     "One.[^1]",
+///     "",
+///     "## B",
+///     "",
+///     "Two.[^2]",
+///     "",
+///     "[^1]: First",
+///     "[^2]: Second",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// let out = convert_footnotes_with(&lines, FootnotePlacement::Section);
+/// assert_eq!(
+///     out,
+///     [
+///         "## A",
+///         "",
+///         "One.[^1]",
+///         "",
+///         "[^1]: First",
+///         "",
+///         "## B",
+///         "",
+///         "Two.[^1]",
+///         "",
+///         "[^1]: Second"
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn convert_footnotes_with(lines: &[String], placement: FootnotePlacement) -> Vec<String> {
+    place(convert(lines, false), placement, false)
+}
+
+/// Convert and place footnotes like [`convert_footnotes_with`], appending
+/// `<!-- was N -->` to each definition whose number changes.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::footnotes::{FootnotePlacement, convert_footnotes_with_comments};
+///
+/// let lines: Vec<String> = ["Text.[^7]", "", "[^7]: Note"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let out = convert_footnotes_with_comments(&lines, FootnotePlacement::Document);
+/// assert_eq!(out, ["Text.[^1]", "", "[^1]: Note <!-- was 7 -->"]);
+/// ```
+#[must_use]
+pub fn convert_footnotes_with_comments(
+    lines: &[String],
+    placement: FootnotePlacement,
+) -> Vec<String> {
+    place(convert(lines, true), placement, true)
+}
+
+fn place(converted: Vec<String>, placement: FootnotePlacement, comments: bool) -> Vec<String> {
+    match placement {
+        FootnotePlacement::Document => converted,
+        FootnotePlacement::Section => place_per_section(&converted, comments),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     //! Unit tests for footnote conversion.
@@ -53,6 +155,12 @@ mod tests {
         assert_eq!(convert_footnotes(&input), expected);
     }
 
+    #[test]
+    fn leaves_comment_lines_alone() {
+        let input = vec!["<!-- mdtablefix-html-block:0 -->".to_string()];
+        assert_eq!(convert_footnotes(&input), input);
+    }
+
     #[test]
     fn converts_final_list() {
         let input = vec![
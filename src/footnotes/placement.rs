@@ -0,0 +1,247 @@
+//! Per-section placement of footnote definitions.
+//!
+//! Under [`FootnotePlacement::Section`] footnotes are scoped to the sections
+//! opened by level-one and level-two headings. Each section's references are
+//! renumbered from 1 in order of first use, and the definitions they cite are
+//! moved to the end of the section. A definition cited from several sections
+//! is copied into each, while definitions nothing cites stay where they are.
+
+use std::{collections::HashMap, ops::Range};
+
+use super::{
+    parsing::{is_definition_continuation, parse_definition},
+    renumber::{apply_mapping_to_lines, collect_reference_mapping},
+};
+use crate::{
+    extract::{TextBlockKind, extract_text},
+    fences::FenceState,
+    renumber_comments::note_old_number,
+};
+
+/// Where footnote definitions are placed once references are converted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum FootnotePlacement {
+    /// Number footnotes across the whole document, leaving definitions where
+    /// they are written.
+    #[default]
+    Document,
+    /// Number footnotes separately in each level-one or level-two section and
+    /// move each definition to the end of the section that cites it.
+    Section,
+}
+
+/// A footnote definition outside any blockquote and the lines it spans.
+struct Definition {
+    number: usize,
+    section: usize,
+    lines: Range<usize>,
+}
+
+/// Returns the index of each section's first line. The first section holds
+/// any text before the first level-one or level-two heading.
+fn section_starts(lines: &[String]) -> Vec<usize> {
+    let mut starts = vec![0];
+    starts.extend(
+        extract_text(lines)
+            .into_iter()
+            .filter_map(|block| match block.kind {
+                TextBlockKind::Heading(level) if level <= 2 => Some(block.line - 1),
+                _ => None,
+            })
+            .filter(|&index| index > 0),
+    );
+    starts
+}
+
+fn is_continuation_text(line: Option<&String>) -> bool {
+    line.is_some_and(|line| is_definition_continuation(line) && !line.trim().is_empty())
+}
+
+/// Returns the end of the definition starting at `start`, taking in indented
+/// continuation lines and the blank lines between its paragraphs.
+fn definition_end(lines: &[String], start: usize) -> usize {
+    let mut end = start + 1;
+    loop {
+        if is_continuation_text(lines.get(end)) {
+            end += 1;
+        } else if lines.get(end).is_some_and(|line| line.trim().is_empty())
+            && is_continuation_text(lines.get(end + 1))
+        {
+            end += 2;
+        } else {
+            return end;
+        }
+    }
+}
+
+fn find_definitions(lines: &[String], starts: &[usize]) -> Vec<Definition> {
+    let mut fences = FenceState::default();
+    let mut definitions = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let in_code = fences.observe_source_line(&lines[index]).is_code();
+        let number = parse_definition(&lines[index])
+            .filter(|parts| !in_code && parts.prefix.trim().is_empty())
+            .map(|parts| parts.number);
+        let Some(number) = number else {
+            index += 1;
+            continue;
+        };
+        let end = definition_end(lines, index);
+        for line in &lines[index + 1..end] {
+            fences.observe_source_line(line);
+        }
+        definitions.push(Definition {
+            number,
+            section: starts.partition_point(|&start| start <= index) - 1,
+            lines: index..end,
+        });
+        index = end;
+    }
+    definitions
+}
+
+/// Returns the lines of `range` outside the `skipped` ranges, dropping the
+/// blank line that would otherwise double up where a range was removed.
+fn kept_lines(lines: &[String], range: Range<usize>, skipped: &[&Range<usize>]) -> Vec<String> {
+    let mut kept: Vec<String> = Vec::with_capacity(range.len());
+    let mut removed = false;
+    for index in range {
+        if skipped.iter().any(|skip| skip.contains(&index)) {
+            removed = true;
+            continue;
+        }
+        let line = &lines[index];
+        let doubled = line.trim().is_empty() && kept.last().is_some_and(|l| l.trim().is_empty());
+        if !(removed && doubled) {
+            kept.push(line.clone());
+        }
+        removed = false;
+    }
+    kept
+}
+
+/// Renumbers footnotes per section and moves their definitions to the end of
+/// the sections that cite them, noting changed numbers in comments when
+/// `comments` is set.
+pub(super) fn place_per_section(lines: &[String], comments: bool) -> Vec<String> {
+    let mut starts = section_starts(lines);
+    let definitions = find_definitions(lines, &starts);
+    starts.push(lines.len());
+    let sections: Vec<Range<usize>> = starts.windows(2).map(|pair| pair[0]..pair[1]).collect();
+    let all_ranges: Vec<&Range<usize>> = definitions.iter().map(|def| &def.lines).collect();
+    let mappings: Vec<HashMap<usize, usize>> = sections
+        .iter()
+        .map(|section| collect_reference_mapping(&kept_lines(lines, section.clone(), &all_ranges)))
+        .collect();
+    if mappings.iter().all(HashMap::is_empty) {
+        return lines.to_vec();
+    }
+    let cited: Vec<&Range<usize>> = definitions
+        .iter()
+        .filter(|def| {
+            mappings
+                .iter()
+                .any(|mapping| mapping.contains_key(&def.number))
+        })
+        .map(|def| &def.lines)
+        .collect();
+
+    let mut out = Vec::with_capacity(lines.len());
+    for (section_index, (section, mapping)) in sections.iter().zip(&mappings).enumerate() {
+        let trailing_blanks = lines[section.clone()]
+            .iter()
+            .rev()
+            .take_while(|line| line.trim().is_empty())
+            .count();
+        let mut kept = kept_lines(lines, section.clone(), &cited);
+        apply_mapping_to_lines(&mut kept, mapping, &[]);
+        while kept.last().is_some_and(|line| line.trim().is_empty()) {
+            kept.pop();
+        }
+        out.extend(kept);
+        let mut order: Vec<(usize, usize)> =
+            mapping.iter().map(|(&old, &new)| (new, old)).collect();
+        order.sort_unstable();
+        let mut separated = false;
+        for (new, old) in order {
+            let Some(def) = definitions
+                .iter()
+                .filter(|def| def.number == old)
+                .min_by_key(|def| def.section != section_index)
+            else {
+                continue;
+            };
+            if !separated && !out.is_empty() {
+                out.push(String::new());
+            }
+            separated = true;
+            let header = &lines[def.lines.start];
+            if let Some(parts) = parse_definition(header) {
+                let line = format!("{}[^{new}]:{}", parts.prefix, parts.rest);
+                out.push(if comments {
+                    note_old_number(&line, old, new)
+                } else {
+                    line
+                });
+            }
+            out.extend(lines[def.lines.start + 1..def.lines.end].iter().cloned());
+        }
+        out.extend(std::iter::repeat_n(String::new(), trailing_blanks));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for per-section footnote placement.
+
+    use rstest::rstest;
+
+    use super::place_per_section;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::moves_to_citing_sections(
+        "## A\n\nOne.[^1]\n\n## B\n\nTwo.[^2] Three.[^3]\n\n[^1]: First\n[^2]: Second\n[^3]: Third",
+        "## A\n\nOne.[^1]\n\n[^1]: First\n\n## B\n\nTwo.[^1] Three.[^2]\n\n[^1]: Second\n[^2]: \
+         Third"
+    )]
+    #[case::copies_shared_definitions(
+        "## A\n\nX.[^1]\n\n## B\n\nY.[^1]\n\n[^1]: Shared",
+        "## A\n\nX.[^1]\n\n[^1]: Shared\n\n## B\n\nY.[^1]\n\n[^1]: Shared"
+    )]
+    #[case::keeps_subsections_in_scope(
+        "## A\n\nX.[^1]\n\n### A.1\n\nY.[^2]\n\n[^1]: One\n[^2]: Two",
+        "## A\n\nX.[^1]\n\n### A.1\n\nY.[^2]\n\n[^1]: One\n[^2]: Two"
+    )]
+    #[case::carries_continuation_lines(
+        "Intro.[^1]\n\n## B\n\nMore.\n\n[^1]: Long\n    note\n\n    second paragraph",
+        "Intro.[^1]\n\n[^1]: Long\n    note\n\n    second paragraph\n\n## B\n\nMore."
+    )]
+    #[case::leaves_uncited_definitions(
+        "## A\n\nText.\n\n[^4]: Orphan",
+        "## A\n\nText.\n\n[^4]: Orphan"
+    )]
+    #[case::ignores_fenced_references(
+        "## A\n\n```\nX.[^1]\n```\n\n[^1]: Note",
+        "## A\n\n```\nX.[^1]\n```\n\n[^1]: Note"
+    )]
+    fn places_definitions(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(place_per_section(&lines(input), false), lines(expected));
+    }
+
+    #[test]
+    fn is_idempotent() {
+        let once = place_per_section(
+            &lines("## A\n\nOne.[^2]\n\n## B\n\nTwo.[^1]\n\n[^1]: First\n[^2]: Second\n"),
+            false,
+        );
+        assert_eq!(place_per_section(&once, false), once);
+    }
+}
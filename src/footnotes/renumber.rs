@@ -30,7 +30,11 @@ use super::{
     lists::{footnote_block_range, has_existing_footnote_block, trimmed_range},
     parsing::{FOOTNOTE_LINE_RE, is_definition_continuation, parse_definition},
 };
-use crate::textproc::{Token, push_original_token, tokenize_markdown};
+use crate::{
+    fences::FenceState,
+    renumber_comments::note_old_number,
+    textproc::{Token, is_escaped, markdown_tokens, push_original_token},
+};
 
 static FOOTNOTE_REF_RE: LazyLock<Regex> = lazy_regex!(
     r"\[\^(?P<num>\d+)\]",
@@ -67,12 +71,11 @@ fn is_definition_like(text: &str, mat: &Match) -> bool {
     parse_definition(text.trim_end()).is_some()
 }
 
-fn is_fence_line(line: &str) -> bool {
-    let mut trimmed = line.trim_start();
-    while let Some(rest) = trimmed.strip_prefix('>') {
-        trimmed = rest.trim_start();
-    }
-    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+/// Report whether `line` is a fence delimiter or fenced content, advancing
+/// `fences` so nested and mismatched markers follow shared fence semantics.
+fn is_fenced(fences: &mut FenceState, line: &str) -> bool {
+    let fence = fences.observe_source_line(line);
+    fence.is_code()
 }
 
 fn rewrite_refs_in_segment(text: &str, mapping: &HashMap<usize, usize>) -> String {
@@ -81,7 +84,7 @@ fn rewrite_refs_in_segment(text: &str, mapping: &HashMap<usize, usize>) -> Strin
             let Some(mat) = caps.get(0) else {
                 return String::new();
             };
-            if is_definition_like(text, &mat) {
+            if is_definition_like(text, &mat) || is_escaped(text, mat.start()) {
                 return caps[0].to_string();
             }
             caps["num"]
@@ -98,7 +101,7 @@ fn rewrite_refs_in_segment(text: &str, mapping: &HashMap<usize, usize>) -> Strin
 
 fn rewrite_tokens(text: &str, mapping: &HashMap<usize, usize>) -> String {
     let mut rewritten = String::with_capacity(text.len());
-    for token in tokenize_markdown(text) {
+    for token in markdown_tokens(text) {
         match token {
             Token::Text(segment) => {
                 rewritten.push_str(&rewrite_refs_in_segment(segment, mapping));
@@ -109,19 +112,15 @@ fn rewrite_tokens(text: &str, mapping: &HashMap<usize, usize>) -> String {
     rewritten
 }
 
-fn collect_reference_mapping(lines: &[String]) -> HashMap<usize, usize> {
+pub(super) fn collect_reference_mapping(lines: &[String]) -> HashMap<usize, usize> {
     let mut mapping = HashMap::new();
     let mut next = 1;
-    let mut in_fence = false;
+    let mut fences = FenceState::default();
     for line in lines {
-        if is_fence_line(line) {
-            in_fence = !in_fence;
+        if is_fenced(&mut fences, line) || !line.contains("[^") {
             continue;
         }
-        if in_fence {
-            continue;
-        }
-        for token in tokenize_markdown(line) {
+        for token in markdown_tokens(line) {
             if let Token::Text(text) = token {
                 collect_reference_mapping_from_text(text, &mut mapping, &mut next);
             }
@@ -139,7 +138,7 @@ fn collect_reference_mapping_from_text(
         let Some(mat) = caps.get(0) else {
             continue;
         };
-        if is_definition_like(text, &mat) {
+        if is_definition_like(text, &mat) || is_escaped(text, mat.start()) {
             continue;
         }
         let Ok(number) = caps["num"].parse::<usize>() else {
@@ -176,18 +175,17 @@ fn footnote_definition_block_range(lines: &[String]) -> Option<(usize, usize)> {
     }
 }
 
-fn apply_mapping_to_lines(
+pub(super) fn apply_mapping_to_lines(
     lines: &mut [String],
     mapping: &HashMap<usize, usize>,
     is_definition_line: &[bool],
 ) {
-    let mut in_fence = false;
+    let mut fences = FenceState::default();
     for (idx, line) in lines.iter_mut().enumerate() {
-        if is_fence_line(line) {
-            in_fence = !in_fence;
-            continue;
-        }
-        if in_fence || is_definition_line.get(idx).copied().unwrap_or(false) {
+        if is_fenced(&mut fences, line)
+            || is_definition_line.get(idx).copied().unwrap_or(false)
+            || !line.contains("[^")
+        {
             continue;
         }
         *line = rewrite_tokens(line, mapping);
@@ -212,10 +210,13 @@ fn apply_mapping_to_lines(
 /// reordered so definitions appear sorted by their new sequential numbers,
 /// with continuation lines kept attached to their definition. Lines inside
 /// fenced code blocks are never rewritten.
-pub(super) fn renumber_footnotes(lines: &mut [String]) {
+///
+/// With `comments` set, each definition whose number changes gains a
+/// `<!-- was N -->` comment recording the number it had.
+pub(super) fn renumber_footnotes(lines: &mut [String], comments: bool) {
     let mut mapping = collect_reference_mapping(lines);
     let DefinitionUpdates {
-        definitions,
+        mut definitions,
         is_definition_line,
     } = collect_definition_updates(lines, &mut mapping);
 
@@ -229,6 +230,15 @@ pub(super) fn renumber_footnotes(lines: &mut [String]) {
 
     apply_mapping_to_lines(lines, &mapping, &is_definition_line);
 
+    if comments {
+        for definition in &mut definitions {
+            definition.line = note_old_number(
+                &definition.line,
+                definition.old_number,
+                definition.new_number,
+            );
+        }
+    }
     rewrite_definition_headers(lines, &definitions);
 
     if let Some((start, end)) = footnote_definition_block_range(lines) {
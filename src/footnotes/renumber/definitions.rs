@@ -14,10 +14,11 @@ use super::{
     footnote_block_range,
     has_existing_footnote_block,
     is_definition_continuation,
-    is_fence_line,
+    is_fenced,
     parse_definition,
     rewrite_tokens,
 };
+use crate::fences::FenceState;
 
 /// Rewrite plan for a single footnote-definition line.
 ///
@@ -28,6 +29,8 @@ use super::{
 pub(super) struct DefinitionLine {
     /// Zero-based row of the definition within the original `lines` slice.
     pub(super) index: usize,
+    /// Footnote number the definition carried before renumbering.
+    pub(super) old_number: usize,
     /// New sequential footnote number assigned to this definition.
     pub(super) new_number: usize,
     /// Fully rewritten line, including any leading indent and prefix, ready
@@ -154,6 +157,7 @@ fn definition_line_from_parts(
     line.push_str(&rewritten_rest);
     DefinitionLine {
         index,
+        old_number: parts.number,
         new_number,
         line,
     }
@@ -185,14 +189,10 @@ pub(super) fn numeric_candidate_from_line(line: &str, index: usize) -> Option<Nu
 }
 
 fn collect_scan_updates(lines: &[String], state: &mut DefinitionScanState<'_>) {
-    let mut in_fence = false;
+    let mut fences = FenceState::default();
 
     for (index, line) in lines.iter().enumerate() {
-        if is_fence_line(line) {
-            in_fence = !in_fence;
-            continue;
-        }
-        if in_fence {
+        if is_fenced(&mut fences, line) {
             continue;
         }
 
@@ -250,6 +250,7 @@ fn finalize_numeric_candidates(state: &mut DefinitionScanState<'_>) {
         line.push_str(&rewritten_rest);
         state.definitions.push(DefinitionLine {
             index: candidate.index,
+            old_number: candidate.number,
             new_number,
             line,
         });
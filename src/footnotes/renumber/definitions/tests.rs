@@ -95,6 +95,7 @@ fn rewrite_definition_headers_updates_only_known_definition_lines() {
     let mut lines = strings(&["[^7]: Old", "text"]);
     let definitions = vec![DefinitionLine {
         index: 0,
+        old_number: 7,
         new_number: 1,
         line: "[^1]: New".to_string(),
     }];
@@ -20,11 +20,13 @@ fn reorder_definition_block_sorts_segments_by_new_number() {
     let definitions = vec![
         DefinitionLine {
             index: 2,
+            old_number: 7,
             new_number: 2,
             line: "[^2]: Second".to_string(),
         },
         DefinitionLine {
             index: 5,
+            old_number: 3,
             new_number: 1,
             line: "[^1]: First".to_string(),
         },
@@ -57,6 +59,7 @@ proptest! {
             lines.push(format!("[^{number}]: Body {offset}"));
             definitions.push(DefinitionLine {
                 index,
+                old_number: *number,
                 new_number: *number,
                 line: format!("[^{number}]: Body {offset}"),
             });
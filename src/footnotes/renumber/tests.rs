@@ -33,7 +33,7 @@ fn renumber_footnotes_rewrites_definitions(
     #[case] mut input: Vec<String>,
     #[case] expected: Vec<String>,
 ) {
-    renumber_footnotes(&mut input);
+    renumber_footnotes(&mut input, false);
     assert_eq!(input, expected);
 }
 
@@ -86,7 +86,7 @@ mod proptest_tests {
                 input.push(format!("[^{n}]: Body for {n}"));
             }
 
-            renumber_footnotes(&mut input);
+            renumber_footnotes(&mut input, false);
 
             // 1. References in non-fenced text map to their definition's new number.
             let mapping: std::collections::HashMap<usize, usize> = unique
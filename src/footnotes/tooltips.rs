@@ -0,0 +1,140 @@
+//! Plain-text footnote summaries for hover tooltips.
+//!
+//! Static site generators can show a footnote's text when a reader hovers
+//! over its reference. [`footnote_tooltips`] reads the numbered definitions
+//! left by [`convert_footnotes`](super::convert_footnotes), joins each
+//! definition's first paragraph with its indented continuation lines, strips
+//! the Markdown syntax, and keeps the first sentence.
+
+use std::collections::BTreeMap;
+
+use super::parsing::{is_definition_continuation, parse_definition};
+use crate::{extract::plain_text, fences::FenceState};
+
+/// Map each numbered footnote definition to the first sentence of its text.
+///
+/// Run this on the output of [`convert_footnotes`](super::convert_footnotes)
+/// (or `process_stream` with footnotes enabled) so the numbers match the
+/// renumbered references. Definitions inside code fences are ignored, and when
+/// a number is defined twice the first definition wins.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{convert_footnotes, footnote_tooltips};
+///
+/// let lines: Vec<String> = [
+///     "Claim.[^7]",
+///     "",
+///     "[^7]: See **the report**, which covers",
+///     "    every case. Later detail.",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// let tooltips = footnote_tooltips(&convert_footnotes(&lines));
+/// assert_eq!(tooltips[&1], "See the report, which covers every case.");
+/// ```
+#[must_use]
+pub fn footnote_tooltips(lines: &[String]) -> BTreeMap<usize, String> {
+    let mut tooltips = BTreeMap::new();
+    let mut fences = FenceState::default();
+    let mut current: Option<(usize, &str, String)> = None;
+    for line in lines {
+        let fence = fences.observe_source_line(line);
+        if !fence.is_fence_marker && !fence.is_in_fence {
+            if let Some(def) = parse_definition(line) {
+                flush(&mut tooltips, current.take());
+                current = Some((def.number, def.prefix, def.rest.trim().to_string()));
+                continue;
+            }
+            if let Some((_, prefix, body)) = current.as_mut()
+                && let Some(rest) = line.strip_prefix(*prefix)
+                && is_definition_continuation(rest)
+                && !rest.trim().is_empty()
+            {
+                body.push(' ');
+                body.push_str(rest.trim());
+                continue;
+            }
+        }
+        flush(&mut tooltips, current.take());
+    }
+    flush(&mut tooltips, current);
+    tooltips
+}
+
+fn flush(tooltips: &mut BTreeMap<usize, String>, definition: Option<(usize, &str, String)>) {
+    if let Some((number, _, body)) = definition {
+        let text = plain_text(&body)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        tooltips
+            .entry(number)
+            .or_insert_with(|| first_sentence(&text).to_string());
+    }
+}
+
+/// Returns `text` up to and including the first `.`, `!`, or `?` that is
+/// followed by whitespace and a character other than a lowercase letter, so
+/// abbreviations such as "e.g. this" do not end the sentence.
+fn first_sentence(text: &str) -> &str {
+    for (idx, ch) in text.char_indices() {
+        if !matches!(ch, '.' | '!' | '?') {
+            continue;
+        }
+        let end = idx + ch.len_utf8();
+        let rest = &text[end..];
+        let next = rest.trim_start().chars().next();
+        if next.is_none()
+            || (rest.starts_with(char::is_whitespace) && !next.is_some_and(char::is_lowercase))
+        {
+            return &text[..end];
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for footnote tooltip extraction.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case("One. Two.", "One.")]
+    #[case("Use e.g. this one! Then more.", "Use e.g. this one!")]
+    #[case("Version 1.2 is out", "Version 1.2 is out")]
+    #[case("Why? Because.", "Why?")]
+    #[case("", "")]
+    fn keeps_the_first_sentence(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(first_sentence(text), expected);
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_first_paragraph() {
+        let input =
+            lines("[^1]: Short note\n\n    Second paragraph.\n[^2]: `code` and [a link](x)");
+        let tooltips = footnote_tooltips(&input);
+        assert_eq!(tooltips[&1], "Short note");
+        assert_eq!(tooltips[&2], "code and a link");
+    }
+
+    #[test]
+    fn reads_quoted_definitions_and_skips_fences() {
+        let input = lines("> [^1]: Quoted\n>     continued.\n```\n[^2]: not a note\n```");
+        let tooltips = footnote_tooltips(&input);
+        assert_eq!(tooltips.len(), 1);
+        assert_eq!(tooltips[&1], "Quoted continued.");
+    }
+
+    #[test]
+    fn first_definition_wins() {
+        let tooltips = footnote_tooltips(&lines("[^1]: First.\n[^1]: Second."));
+        assert_eq!(tooltips[&1], "First.");
+    }
+}
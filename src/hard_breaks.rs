@@ -0,0 +1,200 @@
+//! Normalise Markdown hard line breaks to a single style.
+//!
+//! A hard break can be written as two or more trailing spaces, a trailing
+//! backslash, or an HTML `<br>` tag. [`normalize_hard_breaks`] rewrites every
+//! break inside a paragraph to the chosen [`HardBreakStyle`]. Breaks only
+//! count when the paragraph continues on the next line, so markers at the end
+//! of a paragraph are left alone, as are code blocks, tables, and headings.
+//! [`crate::wrap::wrap_text`] keeps each line's marker when it reflows, so the
+//! chosen style survives wrapping.
+
+use crate::{
+    fences::FenceState,
+    wrap::{BlockKind, BlockquotePrefix, LinkReferenceMatcher, classify_block, leading_indent},
+};
+
+/// How a Markdown hard line break is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum HardBreakStyle {
+    /// Two trailing spaces, invisible in the source.
+    Spaces,
+    /// A trailing backslash.
+    Backslash,
+    /// An HTML `<br>` tag.
+    Html,
+}
+
+impl HardBreakStyle {
+    /// The text appended to a line to end it with this break.
+    #[must_use]
+    pub const fn marker(self) -> &'static str {
+        match self {
+            Self::Spaces => "  ",
+            Self::Backslash => "\\",
+            Self::Html => "<br>",
+        }
+    }
+}
+
+/// Splits a trailing hard-break marker from `line`, returning the text before
+/// it and the marker's style.
+///
+/// `<br>`, `<br/>`, and `<br />` are recognised, as is a backslash that is not
+/// itself escaped. Trailing whitespace after a visible marker is ignored.
+pub(crate) fn split_hard_break(line: &str) -> Option<(&str, HardBreakStyle)> {
+    let trimmed = line.trim_end();
+    for tag in ["<br>", "<br/>", "<br />"] {
+        if let Some(body) = trimmed.strip_suffix(tag) {
+            return Some((body.trim_end(), HardBreakStyle::Html));
+        }
+    }
+    let backslashes = trimmed.len() - trimmed.trim_end_matches('\\').len();
+    if backslashes % 2 == 1 {
+        return Some((&trimmed[..trimmed.len() - 1], HardBreakStyle::Backslash));
+    }
+    (line.ends_with("  ") && !trimmed.is_empty()).then_some((trimmed, HardBreakStyle::Spaces))
+}
+
+/// Returns `true` when `line` ends with a backslash or `<br>` break, which
+/// needs no trailing spaces to take effect.
+pub(crate) fn has_visible_hard_break(line: &str) -> bool {
+    split_hard_break(line).is_some_and(|(_, style)| style != HardBreakStyle::Spaces)
+}
+
+/// Rewrite every hard line break outside code to `style`.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::hard_breaks::{HardBreakStyle, normalize_hard_breaks};
+///
+/// let lines: Vec<String> = ["one  ", "two<br>", "three\\", "four"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     normalize_hard_breaks(&lines, HardBreakStyle::Backslash),
+///     vec!["one\\", "two\\", "three\\", "four"]
+/// );
+/// ```
+#[must_use]
+pub fn normalize_hard_breaks(lines: &[String], style: HardBreakStyle) -> Vec<String> {
+    let mut fences = FenceState::default();
+    let matcher = LinkReferenceMatcher::production();
+    let mut out = Vec::with_capacity(lines.len());
+    for (index, line) in lines.iter().enumerate() {
+        let fence = fences.observe_source_line(line);
+        let rewritten = (!fence.is_fence_marker && !fence.is_in_fence)
+            .then(|| split_hard_break(line))
+            .flatten()
+            .filter(|_| is_prose(line, matcher))
+            .filter(|_| {
+                lines
+                    .get(index + 1)
+                    .is_some_and(|next| continues_paragraph(line, next, matcher))
+            });
+        out.push(match rewritten {
+            Some((body, _)) => format!("{body}{}", style.marker()),
+            None => line.clone(),
+        });
+    }
+    out
+}
+
+/// Strips any blockquote markers from `line`.
+fn quoted_content(line: &str) -> &str { BlockquotePrefix::parse(line).map_or(line, |q| q.inner()) }
+
+/// Returns `true` for paragraph and list text, excluding indented code,
+/// tables, headings, and directive comments.
+pub(crate) fn is_prose(line: &str, matcher: LinkReferenceMatcher) -> bool {
+    let content = quoted_content(line);
+    let (indent, _) = leading_indent(content);
+    let trimmed = content.trim_start();
+    indent < 4
+        && !trimmed.starts_with('|')
+        && !trimmed.starts_with('<')
+        && matches!(
+            classify_block(content, matcher),
+            None | Some(BlockKind::Bullet | BlockKind::FootnoteDefinition | BlockKind::DigitPrefix)
+        )
+}
+
+/// Returns `true` when `next` carries on the paragraph that `line` belongs to.
+pub(crate) fn continues_paragraph(line: &str, next: &str, matcher: LinkReferenceMatcher) -> bool {
+    let quoted = BlockquotePrefix::parse(line).is_some();
+    if quoted != BlockquotePrefix::parse(next).is_some() {
+        return false;
+    }
+    let content = quoted_content(next);
+    let trimmed = content.trim_start();
+    !trimmed.is_empty()
+        && !trimmed.starts_with('|')
+        && !trimmed.starts_with("```")
+        && !trimmed.starts_with("~~~")
+        && !crate::breaks::THEMATIC_BREAK_RE.is_match(trimmed)
+        && matches!(
+            classify_block(content, matcher),
+            None | Some(BlockKind::DigitPrefix)
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for hard-break detection and normalisation.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case("a  ", Some(("a", HardBreakStyle::Spaces)))]
+    #[case("a\\", Some(("a", HardBreakStyle::Backslash)))]
+    #[case("a\\\\", None)]
+    #[case("a <br />", Some(("a", HardBreakStyle::Html)))]
+    #[case("a<br> ", Some(("a", HardBreakStyle::Html)))]
+    #[case("a ", None)]
+    #[case("   ", None)]
+    fn splits_markers(#[case] line: &str, #[case] expected: Option<(&str, HardBreakStyle)>) {
+        assert_eq!(split_hard_break(line), expected);
+    }
+
+    #[rstest]
+    #[case::spaces(HardBreakStyle::Spaces, "a  \nb  \nc  \nd")]
+    #[case::html(HardBreakStyle::Html, "a<br>\nb<br>\nc<br>\nd")]
+    fn converts_every_style(#[case] style: HardBreakStyle, #[case] expected: &str) {
+        let input = lines("a  \nb\\\nc <br/>\nd");
+        assert_eq!(normalize_hard_breaks(&input, style), lines(expected));
+    }
+
+    #[rstest]
+    #[case::paragraph_end("a<br>\n\nb")]
+    #[case::last_line("a<br>")]
+    #[case::fenced("```\na<br>\nb\n```")]
+    #[case::indented_code("    a<br>\n    b")]
+    #[case::table("| a<br> |\n| b |")]
+    #[case::heading("# a<br>\nb")]
+    #[case::before_list("a<br>\n- b")]
+    #[case::before_heading("a<br>\n# b")]
+    fn leaves_non_breaks_alone(#[case] input: &str) {
+        let input = lines(input);
+        assert_eq!(
+            normalize_hard_breaks(&input, HardBreakStyle::Backslash),
+            input
+        );
+    }
+
+    #[test]
+    fn handles_lists_and_quotes() {
+        let input = lines("- a<br>\n  b\n\n> c<br>\n> d");
+        assert_eq!(
+            normalize_hard_breaks(&input, HardBreakStyle::Spaces),
+            lines("- a  \n  b\n\n> c  \n> d")
+        );
+    }
+}
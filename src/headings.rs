@@ -3,9 +3,10 @@
 //! This module converts Setext-style headings (underlined with sequences of three or
 //! more `=` or `-` characters) into ATX headings that use leading hash markers.
 //! Normalising the heading style allows downstream processing such as wrapping to
-//! treat the headings consistently.
+//! treat the headings consistently. It can also promote bold-only pseudo-headings
+//! to ATX headings, which resolves markdownlint MD036 warnings.
 
-use crate::wrap::FenceTracker;
+use crate::{fences::FenceState, textproc::is_escaped};
 
 /// Convert Setext-style headings into ATX (`#`) headings.
 ///
@@ -14,14 +15,14 @@ use crate::wrap::FenceTracker;
 #[must_use]
 pub fn convert_setext_headings(lines: &[String]) -> Vec<String> {
     let mut out = Vec::with_capacity(lines.len());
-    let mut fence_tracker = FenceTracker::default();
+    let mut fence_tracker = FenceState::default();
     let mut idx = 0;
 
     while idx < lines.len() {
         let line = &lines[idx];
 
         let fence = fence_tracker.observe_source_line(line);
-        if fence.is_fence_marker || fence.is_in_fence {
+        if fence.is_code() {
             out.push(line.clone());
             idx += 1;
             continue;
@@ -43,6 +44,71 @@ pub fn convert_setext_headings(lines: &[String]) -> Vec<String> {
     out
 }
 
+/// Convert paragraphs consisting solely of `**bold**` or `__bold__` text into
+/// ATX headings of the given `level` (clamped to `1..=6`).
+///
+/// A line qualifies only when it starts at column zero, stands alone between
+/// blank lines (or the document edges), and its text does not end with
+/// punctuation, matching markdownlint MD036. Indented lines are skipped, so
+/// bold-only paragraphs nested inside list items stay as written, and fenced
+/// code is never touched.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::headings::convert_emphasis_headings;
+///
+/// let lines: Vec<String> = ["**Installation**", "", "Run the installer."]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     convert_emphasis_headings(&lines, 2),
+///     vec!["## Installation", "", "Run the installer."]
+/// );
+/// ```
+#[must_use]
+pub fn convert_emphasis_headings(lines: &[String], level: u8) -> Vec<String> {
+    let level = usize::from(level.clamp(1, 6));
+    let mut fence_tracker = FenceState::default();
+    let is_blank = |idx: Option<usize>| {
+        idx.and_then(|i| lines.get(i))
+            .is_none_or(|l| l.trim().is_empty())
+    };
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            let fence = fence_tracker.observe_source_line(line);
+            if fence.is_code() {
+                return line.clone();
+            }
+            let standalone = is_blank(idx.checked_sub(1)) && is_blank(Some(idx + 1));
+            match emphasis_heading_text(line) {
+                Some(text) if standalone => build_heading_line("", level, text),
+                _ => line.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the inner text when `line` is a whole-line `**strong**` span that
+/// MD036 would report.
+fn emphasis_heading_text(line: &str) -> Option<&str> {
+    let line = line.trim_end();
+    let text = ["**", "__"].iter().find_map(|marker| {
+        line.strip_prefix(marker)?
+            .strip_suffix(marker)
+            .filter(|inner| !inner.contains(marker))
+            .filter(|_| !is_escaped(line, line.len() - marker.len()))
+    })?;
+    let ends_with_punctuation =
+        text.ends_with(['.', ',', ';', ':', '!', '?', '。', '，', '；', '：', '！']);
+    let padded = text.starts_with(char::is_whitespace) || text.ends_with(char::is_whitespace);
+    (!text.is_empty() && !padded && !ends_with_punctuation).then_some(text)
+}
+
 fn detect_setext_heading(line: &str, underline: Option<&str>) -> Option<(usize, usize, String)> {
     let underline = underline?;
     if line.trim().is_empty() {
@@ -180,4 +246,30 @@ mod tests {
     fn leaves_non_headings_untouched(#[case] lines: Vec<String>) {
         assert_eq!(convert_setext_headings(&lines), lines);
     }
+
+    #[rstest]
+    #[case::strong(&["**Usage**"], 2, &["## Usage"])]
+    #[case::underscores(&["Intro.", "", "__Next steps__", ""], 3, &["Intro.", "", "### Next steps", ""])]
+    #[case::clamped(&["**Deep**"], 9, &["###### Deep"])]
+    fn converts_emphasis_headings(
+        #[case] input: &[&str],
+        #[case] level: u8,
+        #[case] expected: &[&str],
+    ) {
+        let input: Vec<String> = input.iter().map(|line| (*line).to_string()).collect();
+        assert_eq!(convert_emphasis_headings(&input, level), expected);
+    }
+
+    #[rstest]
+    #[case::trailing_punctuation(&["**Note:**", "", "Text"])]
+    #[case::part_of_paragraph(&["**Bold** start", "continues here"])]
+    #[case::followed_by_text(&["**Label**", "Value"])]
+    #[case::inside_list(&["- Item", "", "  **Details**", ""])]
+    #[case::mixed_emphasis(&["**a** and **b**"])]
+    #[case::fenced(&["```", "", "**Code**", "", "```"])]
+    #[case::single_emphasis(&["*Aside*"])]
+    fn leaves_other_bold_lines_untouched(#[case] input: &[&str]) {
+        let input: Vec<String> = input.iter().map(|line| (*line).to_string()).collect();
+        assert_eq!(convert_emphasis_headings(&input, 2), input);
+    }
 }
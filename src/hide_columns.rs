@@ -0,0 +1,176 @@
+//! Drop selected columns from generated tables.
+//!
+//! Tables generated from spreadsheets or issue trackers often carry columns
+//! that should not be published, such as internal notes. A
+//! `<!-- mdtablefix: hide-columns Internal Notes, Cost -->` pragma above a
+//! table names the header cells to remove. [`hide_columns`] drops those
+//! columns from every row and keeps them in a
+//! `<!-- mdtablefix: hidden-columns ... -->` comment after the table, so the
+//! data stays in the source while the rendered page omits it. A table that no
+//! longer has any of the named columns is left alone, so repeated runs change
+//! nothing.
+
+use crate::{fences::FenceState, table::split_cells};
+
+static PRAGMA_RE: std::sync::LazyLock<regex::Regex> = lazy_regex!(
+    r"^\s*<!--\s*mdtablefix:\s*hide-columns\s+(.*?)\s*-->\s*$",
+    "hide-columns pragma regex should compile",
+);
+
+/// Opening line of the comment holding the hidden columns.
+const HIDDEN_COMMENT: &str = "<!-- mdtablefix: hidden-columns";
+
+/// Returns the column names listed by a `hide-columns` pragma on `line`.
+fn pragma_columns(line: &str) -> Option<Vec<&str>> {
+    let names = PRAGMA_RE.captures(line)?.get(1)?.as_str();
+    Some(
+        names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect(),
+    )
+}
+
+fn is_table_line(line: &str) -> bool { line.trim_start().starts_with('|') }
+
+/// Removes the columns named by `hide-columns` pragmas from the tables that
+/// follow them, appending the removed cells as a comment after each table.
+///
+/// Names are separated by commas and match whole, trimmed header cells
+/// exactly. Blank lines may separate the pragma from its table. Tables inside
+/// fenced code, tables without a named column, and tables whose every column
+/// is named are returned unchanged. The kept rows are not aligned; the table
+/// stage reflows them afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::hide_columns::hide_columns;
+///
+/// let lines: Vec<String> = [
+///     "<!-- mdtablefix: hide-columns Notes -->",
+///     "| Name | Notes |",
+///     "| --- | --- |",
+///     "| a | secret |",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// assert_eq!(
+///     hide_columns(&lines),
+///     vec![
+///         "<!-- mdtablefix: hide-columns Notes -->",
+///         "| Name |",
+///         "| --- |",
+///         "| a |",
+///         "",
+///         "<!-- mdtablefix: hidden-columns",
+///         "| Notes |",
+///         "| --- |",
+///         "| secret |",
+///         "-->",
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn hide_columns(lines: &[String]) -> Vec<String> {
+    let mut fences = FenceState::default();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut pending: Option<Vec<&str>> = None;
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        let fence = fences.observe_source_line(line);
+        if fence.is_code() {
+            pending = None;
+        } else if let Some(names) = pragma_columns(line) {
+            pending = Some(names);
+        } else if let Some(names) = pending.take_if(|_| is_table_line(line)) {
+            let end = lines[index..]
+                .iter()
+                .position(|line| !is_table_line(line))
+                .map_or(lines.len(), |offset| index + offset);
+            out.extend(split_table(&lines[index..end], &names));
+            index = end;
+            continue;
+        } else if !line.trim().is_empty() {
+            pending = None;
+        }
+        out.push(line.clone());
+        index += 1;
+    }
+    out
+}
+
+/// Splits `table` into its kept rows, a blank line, and the comment holding
+/// the columns named in `names`, or returns it unchanged when no column or
+/// every column is named.
+fn split_table(table: &[String], names: &[&str]) -> Vec<String> {
+    let header = split_cells(&table[0]);
+    let hidden: Vec<bool> = header
+        .iter()
+        .map(|cell| names.contains(&cell.as_str()))
+        .collect();
+    if hidden.iter().all(|&hide| !hide) || hidden.iter().all(|&hide| hide) {
+        return table.to_vec();
+    }
+    let indent = &table[0][..table[0].len() - table[0].trim_start().len()];
+    let mut kept = Vec::with_capacity(table.len());
+    let mut removed = Vec::with_capacity(table.len());
+    for row in table {
+        let cells = split_cells(row);
+        let (mut shown, mut gone) = (Vec::new(), Vec::new());
+        for (column, &hide) in hidden.iter().enumerate() {
+            let cell = cells.get(column).map_or("", String::as_str);
+            if hide { &mut gone } else { &mut shown }.push(cell);
+        }
+        kept.push(format!("{indent}| {} |", shown.join(" | ")));
+        removed.push(format!("{indent}| {} |", gone.join(" | ")));
+    }
+    let mut out = kept;
+    out.push(String::new());
+    out.push(format!("{indent}{HIDDEN_COMMENT}"));
+    out.extend(removed);
+    out.push(format!("{indent}-->"));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for hiding table columns.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::several("<!-- mdtablefix: hide-columns A, B C -->", Some(vec!["A", "B C"]))]
+    #[case::other_pragma("<!-- mdtablefix: other A -->", None)]
+    #[case::plain_comment("<!-- hide-columns A -->", None)]
+    fn parses_pragma(#[case] line: &str, #[case] expected: Option<Vec<&str>>) {
+        assert_eq!(pragma_columns(line), expected);
+    }
+
+    #[rstest]
+    #[case::no_named_column("<!-- mdtablefix: hide-columns Z -->\n| A | B |\n| - | - |")]
+    #[case::every_column("<!-- mdtablefix: hide-columns A, B -->\n| A | B |\n| - | - |")]
+    #[case::paragraph_between("<!-- mdtablefix: hide-columns A -->\n\ntext\n\n| A | B |")]
+    #[case::fenced("```\n<!-- mdtablefix: hide-columns A -->\n| A | B |\n```")]
+    fn leaves_table_alone(#[case] text: &str) {
+        assert_eq!(hide_columns(&lines(text)), lines(text));
+    }
+
+    #[test]
+    fn keeps_indent_and_fills_short_rows() {
+        let text = "<!-- mdtablefix: hide-columns B -->\n\n  | A | B |\n  | - | - |\n  | x |";
+        assert_eq!(
+            hide_columns(&lines(text)),
+            lines(
+                "<!-- mdtablefix: hide-columns B -->\n\n  | A |\n  | - |\n  | x |\n\n  <!-- \
+                 mdtablefix: hidden-columns\n  | B |\n  | - |\n  |  |\n  -->"
+            )
+        );
+    }
+}
@@ -1,19 +1,26 @@
 //! Utilities for converting HTML tables embedded in Markdown into
 //! Markdown table syntax.
 //!
-//! The conversion is intentionally simple: only `<table>`, `<tr>`,
-//! `<th>`, and `<td>` tags are recognized. Attributes and tag casing
-//! are ignored. The resulting Markdown lines are passed to
-//! `reflow_table` to ensure consistent column widths.
+//! Blocks are found line by line, counting `<table>` and `</table>` tags
+//! outside HTML comments, then parsed with `html5ever`. Each table is reduced
+//! to a [`Table`] of [`Cell`]s and rendered as a padded Markdown table. Tag
+//! casing is ignored. [`parse_html_tables`] and [`render_markdown`] expose the
+//! two halves, so callers can inspect or edit a table, for example dropping a
+//! column, before it is rendered.
 
 use std::sync::LazyLock;
 
 use html5ever::{driver::ParseOpts, parse_document, tendril::TendrilSink};
-use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use markup5ever_rcdom::{Handle, RcDom};
 use regex::Regex;
 use tracing::debug;
 
-use crate::wrap::FenceTracker;
+use self::dom::is_element;
+pub use self::table::{Cell, ColumnWidth, Table};
+use crate::fences::FenceState;
+
+mod dom;
+mod table;
 
 /// Matches an HTML `<table>` tag at the start of a Markdown block, ignoring case.
 static TABLE_START_RE: LazyLock<Regex> = lazy_regex!(
@@ -29,163 +36,61 @@ static TABLE_TAG_RE: LazyLock<Regex> = lazy_regex!(
 static TABLE_END_RE: LazyLock<Regex> =
     lazy_regex!(r"(?i)</table>", "HTML table end pattern should compile");
 
-/// Extracts the text content of a DOM node, collapsing consecutive
-/// whitespace to single spaces.
-fn node_text(handle: &Handle) -> String {
-    let mut out = String::new();
-    let mut last_space = false;
-    collect_text(handle, &mut out, &mut last_space);
-    if last_space {
-        out.push(' ');
-    }
-    out.trim_start().to_string()
-}
-
-fn is_ignored_tag(tag: &str) -> bool {
-    matches!(
-        tag,
-        t if t.eq_ignore_ascii_case("script")
-            || t.eq_ignore_ascii_case("style")
-            || t.eq_ignore_ascii_case("noscript")
-            || t.eq_ignore_ascii_case("template")
-            || t.eq_ignore_ascii_case("head")
-    )
-}
-
-/// Recursively appends text nodes from `handle` to `out`, tracking whether the
-/// previous output was whitespace.
-fn collect_text(handle: &Handle, out: &mut String, last_space: &mut bool) {
-    match &handle.data {
-        NodeData::Text { contents } => {
-            for ch in contents.borrow().chars() {
-                push_collapsed_text_char(ch, out, last_space);
-            }
-        }
-        NodeData::Element { name, .. } => {
-            if is_ignored_tag(name.local.as_ref()) {
-                return;
-            }
-            for child in handle.children.borrow().iter() {
-                collect_text(child, out, last_space);
-            }
-        }
-        NodeData::Document => {
-            for child in handle.children.borrow().iter() {
-                collect_text(child, out, last_space);
-            }
-        }
-        _ => {}
-    }
-}
-
-fn push_collapsed_text_char(ch: char, out: &mut String, last_space: &mut bool) {
-    if ch.is_whitespace() {
-        *last_space = true;
-        return;
-    }
-    if *last_space && !out.is_empty() {
-        out.push(' ');
-    }
-    out.push(ch);
-    *last_space = false;
-}
-
-/// Returns `true` if `handle` is an HTML element with the given tag name.
-fn is_element(handle: &Handle, tag: &str) -> bool {
-    if let NodeData::Element { name, .. } = &handle.data {
-        name.local.as_ref().eq_ignore_ascii_case(tag)
-    } else {
-        false
-    }
-}
-
-/// Returns `true` if `handle` represents a `<td>` or `<th>` element.
-fn is_table_cell(handle: &Handle) -> bool { is_element(handle, "td") || is_element(handle, "th") }
-
-/// Walks the DOM tree collecting `<table>` nodes under `handle`.
+/// Walks the DOM tree collecting the outermost `<table>` nodes under
+/// `handle`. Nested tables are converted as part of their outer table's cells.
 fn collect_tables(handle: &Handle, tables: &mut Vec<Handle>) {
     if is_element(handle, "table") {
         tables.push(handle.clone());
+        return;
     }
     for child in handle.children.borrow().iter() {
         collect_tables(child, tables);
     }
 }
 
-/// Collects all `<tr>` nodes beneath `handle`.
-fn collect_rows(handle: &Handle, rows: &mut Vec<Handle>) {
-    if is_element(handle, "tr") {
-        rows.push(handle.clone());
-    }
-    for child in handle.children.borrow().iter() {
-        collect_rows(child, rows);
-    }
-}
-
-fn is_bold_tag(tag: &str) -> bool {
-    matches!(
-        tag,
-        t if t.eq_ignore_ascii_case("strong") || t.eq_ignore_ascii_case("b")
-    )
-}
-
-/// Returns `true` if `handle` contains a `<b>` or `<strong>` descendant.
-fn contains_strong(handle: &Handle) -> bool {
-    if let NodeData::Element { name, .. } = &handle.data
-        && is_bold_tag(name.local.as_ref())
-    {
-        return true;
-    }
-    let children = handle.children.borrow();
-    children.iter().any(contains_strong)
-}
-
-/// Extracts cell text from a row and reports whether all cells are header cells.
-fn parse_row(row: &Handle) -> (Vec<String>, bool) {
-    let mut cells = Vec::new();
-    let mut all_header = true;
-    for child in row.children.borrow().iter() {
-        if is_table_cell(child) {
-            let is_header = if is_element(child, "th") {
-                true
-            } else {
-                contains_strong(child)
-            };
-            all_header &= is_header;
-            cells.push(node_text(child));
-        }
-    }
-    (cells, all_header)
+/// Parses `html` and returns a [`Table`] for each outermost `<table>`
+/// element, in document order.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::html::parse_html_tables;
+///
+/// let tables = parse_html_tables("<table><tr><th>Name</th></tr><tr><td>Ada</td></tr></table>");
+/// assert_eq!(tables.len(), 1);
+/// assert!(tables[0].rows[0][0].heading);
+/// assert_eq!(tables[0].rows[1][0].text, "Ada");
+/// ```
+#[must_use]
+pub fn parse_html_tables(html: &str) -> Vec<Table> {
+    let dom: RcDom = parse_document(RcDom::default(), ParseOpts::default()).one(html);
+    let mut tables = Vec::new();
+    collect_tables(&dom.document, &mut tables);
+    tables.iter().map(Table::from_node).collect()
 }
 
-/// Converts a `<table>` DOM node into Markdown table lines and calls
-/// `reflow_table` so the columns are uniformly padded.
-fn table_node_to_markdown(table: &Handle) -> Vec<String> {
-    let mut row_handles = Vec::new();
-    collect_rows(table, &mut row_handles);
-    if row_handles.is_empty() {
-        return Vec::new();
-    }
-
-    let (first_cells, explicit_header) = parse_row(&row_handles[0]);
-    let col_count = first_cells.len();
-    let fallback_header = !explicit_header && row_handles.len() > 1;
-    let has_header = explicit_header || fallback_header;
-
-    let mut out = Vec::new();
-    out.push(format!("| {} |", first_cells.join(" | ")));
-    for row in row_handles.iter().skip(1) {
-        let (cells, _) = parse_row(row);
-        out.push(format!("| {} |", cells.join(" | ")));
-    }
-
-    if has_header {
-        let sep: Vec<String> = (0..col_count).map(|_| "---".to_string()).collect();
-        out.insert(1, format!("| {} |", sep.join(" | ")));
-    }
-
-    crate::reflow_table(&out)
-}
+/// Renders `table` as the padded Markdown table [`convert_html_tables`]
+/// would produce for it.
+///
+/// The first `<thead>` row is the header. Without one, the first row is the
+/// header when all its cells are header-like or when more rows follow, and
+/// heading cells in any other row are rendered in bold.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::html::{Cell, parse_html_tables, render_markdown};
+///
+/// let mut tables = parse_html_tables(
+///     "<table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>",
+/// );
+/// let table = &mut tables[0];
+/// table.remove_column(1);
+/// table.rows[1][0] = Cell::new("one");
+/// assert_eq!(render_markdown(table), ["| A   |", "| --- |", "| one |"]);
+/// ```
+#[must_use]
+pub fn render_markdown(table: &Table) -> Vec<String> { table.to_markdown() }
 
 /// Parses HTML table markup and returns the equivalent Markdown lines.
 ///
@@ -195,29 +100,48 @@ fn table_lines_to_markdown(lines: &[String]) -> Vec<String> {
         .first()
         .map(|l| l.chars().take_while(|c| c.is_whitespace()).collect())
         .unwrap_or_default();
-    let html: String = lines.join("\n");
-    let opts = ParseOpts::default();
-    let dom: RcDom = parse_document(RcDom::default(), opts).one(html);
-
-    let mut tables = Vec::new();
-    collect_tables(&dom.document, &mut tables);
+    let tables = parse_html_tables(&lines.join("\n"));
     if tables.is_empty() {
         return lines.to_vec();
     }
 
     let mut out = Vec::new();
-    for table in tables {
-        for line in table_node_to_markdown(&table) {
+    for table in &tables {
+        for line in render_markdown(table) {
             out.push(format!("{indent}{line}"));
         }
     }
     out
 }
 
+/// Returns the parts of `line` outside HTML comments, given whether a comment
+/// is open at its start, and records whether one is still open at its end.
+fn outside_comments(line: &str, in_comment: &mut bool) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    loop {
+        if *in_comment {
+            let Some(end) = rest.find("-->") else {
+                return out;
+            };
+            rest = &rest[end + 3..];
+            *in_comment = false;
+        }
+        let Some(start) = rest.find("<!--") else {
+            out.push_str(rest);
+            return out;
+        };
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 4..];
+        *in_comment = true;
+    }
+}
+
 #[derive(Default)]
 struct HtmlTableState {
     buf: Vec<String>,
     depth: usize,
+    in_comment: bool,
 }
 
 impl HtmlTableState {
@@ -233,13 +157,14 @@ impl HtmlTableState {
             out.append(&mut self.buf);
         }
         self.depth = 0;
+        self.in_comment = false;
     }
 
     fn push_html_line(&mut self, line: &str, out: &mut Vec<String>) {
-        let trimmed = line.trim_start();
+        let tags = outside_comments(line.trim_start(), &mut self.in_comment);
         let previous_depth = self.depth;
-        let start_count = TABLE_TAG_RE.find_iter(trimmed).count();
-        let end_count = TABLE_END_RE.find_iter(trimmed).count();
+        let start_count = TABLE_TAG_RE.find_iter(&tags).count();
+        let end_count = TABLE_END_RE.find_iter(&tags).count();
         self.buf.push(line.to_string());
         self.depth += start_count;
         if end_count > 0 {
@@ -260,6 +185,7 @@ impl HtmlTableState {
             );
             out.extend(table_lines_to_markdown(&self.buf));
             self.buf.clear();
+            self.in_comment = false;
         }
     }
 }
@@ -329,7 +255,7 @@ pub(crate) fn html_table_to_markdown(lines: &[String]) -> Vec<String> {
 pub fn convert_html_tables(lines: &[String]) -> Vec<String> {
     let mut out = Vec::new();
     let mut html_state = HtmlTableState::default();
-    let mut fences = FenceTracker::new();
+    let mut fences = FenceState::new();
 
     for line in lines {
         let fence = fences.observe_source_line(line);
@@ -0,0 +1,96 @@
+//! DOM queries shared by the HTML table model.
+
+use markup5ever_rcdom::{Handle, NodeData};
+
+/// Extracts the text content of a DOM node, collapsing consecutive
+/// whitespace to single spaces.
+pub(super) fn node_text(handle: &Handle) -> String {
+    let mut out = String::new();
+    let mut last_space = false;
+    collect_text(handle, &mut out, &mut last_space);
+    if last_space {
+        out.push(' ');
+    }
+    out.trim_start().to_string()
+}
+
+fn is_ignored_tag(tag: &str) -> bool {
+    matches!(
+        tag,
+        t if t.eq_ignore_ascii_case("script")
+            || t.eq_ignore_ascii_case("style")
+            || t.eq_ignore_ascii_case("noscript")
+            || t.eq_ignore_ascii_case("template")
+            || t.eq_ignore_ascii_case("head")
+    )
+}
+
+/// Recursively appends text nodes from `handle` to `out`, tracking whether the
+/// previous output was whitespace.
+fn collect_text(handle: &Handle, out: &mut String, last_space: &mut bool) {
+    match &handle.data {
+        NodeData::Text { contents } => {
+            for ch in contents.borrow().chars() {
+                push_collapsed_text_char(ch, out, last_space);
+            }
+        }
+        NodeData::Element { name, .. } => {
+            if is_ignored_tag(name.local.as_ref()) {
+                return;
+            }
+            for child in handle.children.borrow().iter() {
+                collect_text(child, out, last_space);
+            }
+        }
+        NodeData::Document => {
+            for child in handle.children.borrow().iter() {
+                collect_text(child, out, last_space);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_collapsed_text_char(ch: char, out: &mut String, last_space: &mut bool) {
+    if ch.is_whitespace() {
+        *last_space = true;
+        return;
+    }
+    if *last_space && !out.is_empty() {
+        out.push(' ');
+    }
+    out.push(ch);
+    *last_space = false;
+}
+
+/// Returns `true` if `handle` is an HTML element with the given tag name.
+pub(super) fn is_element(handle: &Handle, tag: &str) -> bool {
+    if let NodeData::Element { name, .. } = &handle.data {
+        name.local.as_ref().eq_ignore_ascii_case(tag)
+    } else {
+        false
+    }
+}
+
+/// Returns `true` if `handle` represents a `<td>` or `<th>` element.
+pub(super) fn is_table_cell(handle: &Handle) -> bool {
+    is_element(handle, "td") || is_element(handle, "th")
+}
+
+fn is_bold_tag(tag: &str) -> bool {
+    matches!(
+        tag,
+        t if t.eq_ignore_ascii_case("strong") || t.eq_ignore_ascii_case("b")
+    )
+}
+
+/// Returns `true` if `handle` contains a `<b>` or `<strong>` descendant.
+pub(super) fn contains_strong(handle: &Handle) -> bool {
+    if let NodeData::Element { name, .. } = &handle.data
+        && is_bold_tag(name.local.as_ref())
+    {
+        return true;
+    }
+    let children = handle.children.borrow();
+    children.iter().any(contains_strong)
+}
@@ -0,0 +1,355 @@
+//! The table model HTML conversion builds before rendering Markdown.
+//!
+//! [`Table::from_node`] is the extension point for HTML handling. It walks
+//! the children of one `<table>` element and maps each element it knows onto
+//! header rows, body rows, footer rows, or column width hints, skipping the
+//! rest along with comments. Supporting another element means adding a
+//! branch to `Sections::visit`; [`Table::to_markdown`] renders whatever the
+//! walk collected. Tables nested inside a cell are not walked: their text
+//! becomes part of the cell.
+
+use html5ever::Attribute;
+use markup5ever_rcdom::{Handle, NodeData};
+use unicode_width::UnicodeWidthStr;
+
+use super::dom::{contains_strong, is_element, is_table_cell, node_text};
+
+/// A relative column width read from a `<col>` or `<colgroup>` element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnWidth {
+    /// A percentage of the table width, such as `width="30%"`.
+    Percent(usize),
+    /// A share of the width the percentages leave, such as `width="2*"`.
+    Relative(usize),
+}
+
+/// One cell of an HTML table row.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cell {
+    /// The cell's text content, with runs of whitespace collapsed to one
+    /// space.
+    pub text: String,
+    /// The cell is a `<th>`.
+    pub heading: bool,
+    /// The cell is a `<th>`, or a `<td>` containing `<b>` or `<strong>`.
+    pub header_like: bool,
+}
+
+impl Cell {
+    /// Returns a `<td>` cell holding `text`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::html::Cell;
+    ///
+    /// let cell = Cell::new("total");
+    /// assert_eq!(cell.text, "total");
+    /// assert!(!cell.heading);
+    /// ```
+    #[must_use]
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// An HTML table reduced to the parts a Markdown table can express.
+///
+/// `rows` lists the `<thead>` rows first, then the `<tbody>` and bare `<tr>`
+/// rows, then the `<tfoot>` rows, wherever each section appears in the
+/// source.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    /// The table's rows, each a list of cells.
+    pub rows: Vec<Vec<Cell>>,
+    /// How many of the leading rows came from `<thead>`.
+    pub head_rows: usize,
+    /// Width hints for each column, from `<colgroup>` and `<col>`.
+    pub widths: Vec<Option<ColumnWidth>>,
+}
+
+/// The rows of each table section, collected while walking a `<table>`.
+#[derive(Default)]
+struct Sections {
+    head: Vec<Vec<Cell>>,
+    body: Vec<Vec<Cell>>,
+    foot: Vec<Vec<Cell>>,
+    widths: Vec<Option<ColumnWidth>>,
+}
+
+/// The section of a table a row belongs to.
+#[derive(Clone, Copy)]
+enum Section {
+    Head,
+    Body,
+    Foot,
+}
+
+impl Table {
+    /// Collects the rows and column widths of the `<table>` element `table`.
+    pub(super) fn from_node(table: &Handle) -> Self {
+        let mut sections = Sections::default();
+        for child in table.children.borrow().iter() {
+            sections.visit(child, Section::Body);
+        }
+        let Sections {
+            mut head,
+            body,
+            foot,
+            widths,
+        } = sections;
+        let head_rows = head.len();
+        head.extend(body);
+        head.extend(foot);
+        Self {
+            rows: head,
+            head_rows,
+            widths,
+        }
+    }
+
+    /// Removes the column at `index` from every row and from the width
+    /// hints. Rows too short to have the column are left as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::html::parse_html_tables;
+    ///
+    /// let mut tables = parse_html_tables("<table><tr><td>a</td><td>b</td></tr></table>");
+    /// tables[0].remove_column(0);
+    /// assert_eq!(tables[0].rows[0][0].text, "b");
+    /// ```
+    pub fn remove_column(&mut self, index: usize) {
+        for row in &mut self.rows {
+            if index < row.len() {
+                row.remove(index);
+            }
+        }
+        if index < self.widths.len() {
+            self.widths.remove(index);
+        }
+    }
+
+    /// Renders the table as Markdown lines, with columns widened to their
+    /// `<colgroup>` hints.
+    ///
+    /// The first `<thead>` row is the header. Without a `<thead>`, the first
+    /// row is the header when its cells are all header-like or when more rows
+    /// follow. `<th>` cells in any other row are rendered in bold.
+    pub(super) fn to_markdown(&self) -> Vec<String> {
+        let explicit_head = self.head_rows > 0;
+        let rows = &self.rows;
+        let Some(first) = rows.first() else {
+            return Vec::new();
+        };
+        let has_header =
+            explicit_head || first.iter().all(|cell| cell.header_like) || rows.len() > 1;
+        let mut texts: Vec<Vec<String>> = Vec::with_capacity(rows.len());
+        for (index, row) in rows.iter().enumerate() {
+            let is_header = index == 0 && has_header;
+            texts.push(
+                row.iter()
+                    .map(|cell| {
+                        if cell.heading && !is_header && !cell.text.is_empty() {
+                            format!("**{}**", cell.text)
+                        } else {
+                            cell.text.clone()
+                        }
+                    })
+                    .collect(),
+            );
+        }
+
+        let mut out: Vec<String> = texts
+            .iter()
+            .map(|cells| format!("| {} |", cells.join(" | ")))
+            .collect();
+        if has_header {
+            let sep = vec!["---"; first.len()];
+            out.insert(1, format!("| {} |", sep.join(" | ")));
+        }
+        crate::table::reflow_table_with_min_widths(&out, &self.min_widths(&texts))
+    }
+
+    /// Returns the width each hinted column needs to take its share of the
+    /// table's natural width, the sum of its widest cells.
+    fn min_widths(&self, rows: &[Vec<String>]) -> Vec<usize> {
+        if self.widths.iter().all(Option::is_none) {
+            return Vec::new();
+        }
+        let mut natural = vec![3; self.widths.len()];
+        for row in rows {
+            for (width, cell) in natural.iter_mut().zip(row) {
+                *width = (*width).max(UnicodeWidthStr::width(cell.as_str()));
+            }
+        }
+        let total: usize = natural.iter().sum();
+        let percent: usize = self
+            .widths
+            .iter()
+            .map(|width| match width {
+                Some(ColumnWidth::Percent(share)) => *share,
+                _ => 0,
+            })
+            .sum();
+        let stars: usize = self
+            .widths
+            .iter()
+            .map(|width| match width {
+                Some(ColumnWidth::Relative(share)) => *share,
+                _ => 0,
+            })
+            .sum();
+        let left = 100usize.saturating_sub(percent);
+        self.widths
+            .iter()
+            .map(|width| match width {
+                Some(ColumnWidth::Percent(share)) => (total * share).div_ceil(100),
+                Some(ColumnWidth::Relative(share)) => (total * left * share).div_ceil(100 * stars),
+                None => 0,
+            })
+            .collect()
+    }
+}
+
+impl Sections {
+    /// Maps `node`, found inside the table's `section`, onto the model.
+    fn visit(&mut self, node: &Handle, section: Section) {
+        let NodeData::Element { attrs, .. } = &node.data else {
+            return;
+        };
+        if is_element(node, "thead") {
+            self.visit_children(node, Section::Head);
+        } else if is_element(node, "tbody") {
+            self.visit_children(node, Section::Body);
+        } else if is_element(node, "tfoot") {
+            self.visit_children(node, Section::Foot);
+        } else if is_element(node, "tr") {
+            let row = parse_row(node);
+            match section {
+                Section::Head => self.head.push(row),
+                Section::Body => self.body.push(row),
+                Section::Foot => self.foot.push(row),
+            }
+        } else if is_element(node, "colgroup") {
+            let before = self.widths.len();
+            self.visit_children(node, section);
+            if self.widths.len() == before {
+                self.push_widths(&attrs.borrow());
+            }
+        } else if is_element(node, "col") {
+            self.push_widths(&attrs.borrow());
+        }
+    }
+
+    fn visit_children(&mut self, node: &Handle, section: Section) {
+        for child in node.children.borrow().iter() {
+            self.visit(child, section);
+        }
+    }
+
+    /// Records the width of a `<col>` or `<colgroup>` for each column it
+    /// spans.
+    fn push_widths(&mut self, attrs: &[Attribute]) {
+        let span = attribute(attrs, "span")
+            .and_then(|span| span.trim().parse::<usize>().ok())
+            .filter(|&span| (1..=1000).contains(&span))
+            .unwrap_or(1);
+        let width = attribute(attrs, "width")
+            .or_else(|| attribute(attrs, "style").and_then(style_width))
+            .and_then(parse_width);
+        self.widths.extend(std::iter::repeat_n(width, span));
+    }
+}
+
+/// Extracts the cells of a `<tr>` element.
+fn parse_row(row: &Handle) -> Vec<Cell> {
+    row.children
+        .borrow()
+        .iter()
+        .filter(|child| is_table_cell(child))
+        .map(|child| {
+            let heading = is_element(child, "th");
+            Cell {
+                text: node_text(child),
+                heading,
+                header_like: heading || contains_strong(child),
+            }
+        })
+        .collect()
+}
+
+fn attribute<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|attr| attr.name.local.as_ref().eq_ignore_ascii_case(name))
+        .map(|attr| attr.value.as_ref())
+}
+
+/// Returns the value of the `width` property in a `style` attribute.
+fn style_width(style: &str) -> Option<&str> {
+    style.split(';').find_map(|declaration| {
+        let (property, value) = declaration.split_once(':')?;
+        property
+            .trim()
+            .eq_ignore_ascii_case("width")
+            .then_some(value)
+    })
+}
+
+/// Parses a relative width such as `30%`, `2*`, or `*`. Absolute widths,
+/// such as pixels, give no hint.
+fn parse_width(value: &str) -> Option<ColumnWidth> {
+    let value = value.trim();
+    if let Some(share) = value.strip_suffix('%') {
+        let share = share.trim().parse::<f64>().ok()?;
+        return (0.0..=100.0).contains(&share).then(|| {
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "the share is checked to lie between 0 and 100"
+            )]
+            let share = share.round() as usize;
+            ColumnWidth::Percent(share)
+        });
+    }
+    let share = value.strip_suffix('*')?.trim();
+    if share.is_empty() {
+        return Some(ColumnWidth::Relative(1));
+    }
+    share
+        .parse::<usize>()
+        .ok()
+        .filter(|&share| (1..=100).contains(&share))
+        .map(ColumnWidth::Relative)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for column width hints.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::percent("30%", Some(ColumnWidth::Percent(30)))]
+    #[case::fractional_percent(" 12.5% ", Some(ColumnWidth::Percent(13)))]
+    #[case::relative("2*", Some(ColumnWidth::Relative(2)))]
+    #[case::bare_star("*", Some(ColumnWidth::Relative(1)))]
+    #[case::pixels("120", None)]
+    #[case::too_wide("150%", None)]
+    fn parses_relative_widths(#[case] value: &str, #[case] expected: Option<ColumnWidth>) {
+        assert_eq!(parse_width(value), expected);
+    }
+
+    #[test]
+    fn reads_width_from_style() {
+        assert_eq!(style_width("color: red; width: 40%"), Some(" 40%"));
+        assert_eq!(style_width("min-width: 40%"), None);
+    }
+}
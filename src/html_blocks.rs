@@ -0,0 +1,377 @@
+//! Detection and masking of block-level HTML.
+//!
+//! `CommonMark` passes HTML blocks through to the output untouched, so wrapping,
+//! joining, or rewriting their lines corrupts the rendered page.
+//! [`html_block_ranges`] finds them with the `CommonMark` HTML block start and
+//! end conditions, and [`HtmlMask`] swaps each block for a placeholder comment
+//! while a transform runs, putting the original lines back afterwards.
+//!
+//! Some HTML is deliberately left visible. HTML table conversion runs on the
+//! unmasked document, so `<table>` markup can still become a Markdown table.
+//! `<details>` and `<summary>` lines keep their existing handling, so Markdown
+//! in collapsible summaries is still formatted. Single-line `mdtablefix` and
+//! markdownlint directive comments stay in place because transforms read
+//! them, and a comment on the line after a paragraph still wraps with it.
+
+use std::ops::Range;
+
+use crate::{fences::FenceState, wrap::leading_indent};
+
+/// Tags whose blocks run until their closing tag, blank lines included.
+const RAW_TAGS: [(&str, &str); 4] = [
+    ("pre", "</pre>"),
+    ("script", "</script>"),
+    ("style", "</style>"),
+    ("textarea", "</textarea>"),
+];
+
+/// Tags that start a block ending at the next blank line, even in the middle
+/// of a paragraph. `details` and `summary` are omitted on purpose.
+const BLOCK_TAGS: [&str; 60] = [
+    "address",
+    "article",
+    "aside",
+    "base",
+    "basefont",
+    "blockquote",
+    "body",
+    "caption",
+    "center",
+    "col",
+    "colgroup",
+    "dd",
+    "dialog",
+    "dir",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "frame",
+    "frameset",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "iframe",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "menu",
+    "menuitem",
+    "nav",
+    "noframes",
+    "ol",
+    "optgroup",
+    "option",
+    "p",
+    "param",
+    "search",
+    "section",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "track",
+    "ul",
+];
+
+/// Matches a line holding only one complete opening or closing tag.
+static LONE_TAG_RE: std::sync::LazyLock<regex::Regex> = lazy_regex!(
+    r#"^(?:<[A-Za-z][A-Za-z0-9-]*(?:\s+[A-Za-z_:][A-Za-z0-9_.:-]*(?:\s*=\s*(?:[^\s"'=<>`]+|'[^']*'|"[^"]*"))?)*\s*/?>|</[A-Za-z][A-Za-z0-9-]*\s*>)\s*$"#,
+    "lone tag regex should compile",
+);
+
+const PLACEHOLDER_PREFIX: &str = "<!-- mdtablefix-html-block:";
+
+/// How an HTML block ends.
+#[derive(Clone, Copy)]
+enum BlockEnd {
+    /// On the first line, the start line included, containing this text,
+    /// compared case-insensitively.
+    Contains(&'static str),
+    /// Before the next blank line.
+    Blank,
+}
+
+fn tag_name(text: &str) -> Option<(&str, &str)> {
+    let rest = text.strip_prefix('<')?;
+    let rest = rest.strip_prefix('/').unwrap_or(rest);
+    let len = rest
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(rest.len());
+    (len > 0).then(|| rest.split_at(len))
+}
+
+fn is_directive(text: &str) -> bool {
+    text.strip_prefix("<!--")
+        .map(str::trim_start)
+        .is_some_and(|body| {
+            ["mdtablefix-", "mdtablefix:", "markdownlint-"]
+                .iter()
+                .any(|prefix| body.starts_with(prefix))
+                && body.contains("-->")
+        })
+}
+
+fn start_condition(line: &str, after_paragraph: bool) -> Option<BlockEnd> {
+    let (width, bytes) = leading_indent(line);
+    let text = &line[bytes..];
+    if width >= 4 || !text.starts_with('<') || is_directive(text) {
+        return None;
+    }
+    let lower = text.to_ascii_lowercase();
+    if let Some((name, after)) = tag_name(&lower) {
+        let bounded = after.is_empty() || after.starts_with(['>', ' ', '\t']);
+        let raw = RAW_TAGS.iter().find(|(tag, _)| *tag == name);
+        if let Some((_, close)) = raw
+            && bounded
+            && !lower.starts_with("</")
+        {
+            return Some(BlockEnd::Contains(close));
+        }
+        if BLOCK_TAGS.contains(&name) && (bounded || after.starts_with("/>")) {
+            return Some(BlockEnd::Blank);
+        }
+    }
+    // Comments written on the line after a paragraph have always wrapped with
+    // it, so only a comment that opens its own block is held back.
+    if lower.starts_with("<!--") {
+        return (!after_paragraph).then_some(BlockEnd::Contains("-->"));
+    }
+    if lower.starts_with("<?") {
+        return Some(BlockEnd::Contains("?>"));
+    }
+    if lower.starts_with("<![cdata[") {
+        return Some(BlockEnd::Contains("]]>"));
+    }
+    if lower
+        .strip_prefix("<!")
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_alphabetic()))
+    {
+        return Some(BlockEnd::Contains(">"));
+    }
+    let other_tag = tag_name(&lower).is_some_and(|(name, _)| {
+        !RAW_TAGS.iter().any(|(tag, _)| *tag == name) && name != "details" && name != "summary"
+    });
+    (other_tag && !after_paragraph && LONE_TAG_RE.is_match(text)).then_some(BlockEnd::Blank)
+}
+
+fn block_end(lines: &[String], start: usize, end: BlockEnd) -> usize {
+    match end {
+        BlockEnd::Contains(marker) => lines[start..]
+            .iter()
+            .position(|line| line.to_ascii_lowercase().contains(marker))
+            .map_or(lines.len(), |offset| start + offset + 1),
+        BlockEnd::Blank => lines[start..]
+            .iter()
+            .position(|line| line.trim().is_empty())
+            .map_or(lines.len(), |offset| start + offset),
+    }
+}
+
+/// Returns the line ranges of the HTML blocks in `lines`.
+///
+/// Blocks follow the `CommonMark` start and end conditions: `<pre>`,
+/// `<script>`, `<style>`, and `<textarea>` run to their closing tag, comments,
+/// processing instructions, declarations, and CDATA sections run to their
+/// terminator, and other block-level tags run to the next blank line. A line
+/// holding a single other tag starts a block only when it does not continue a
+/// paragraph. Fenced code is never searched.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::html_blocks::html_block_ranges;
+///
+/// let lines: Vec<String> = [
+///     "Text",
+///     "",
+///     "<div class=\"grid\">",
+///     "  cell",
+///     "</div>",
+///     "",
+///     "More",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// assert_eq!(html_block_ranges(&lines), [2..5]);
+/// ```
+#[must_use]
+pub fn html_block_ranges(lines: &[String]) -> Vec<Range<usize>> {
+    let mut fences = FenceState::default();
+    let mut ranges = Vec::new();
+    let mut after_paragraph = false;
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        if fences.observe_source_line(line).is_code() {
+            after_paragraph = false;
+            index += 1;
+            continue;
+        }
+        let Some(end) = start_condition(line, after_paragraph) else {
+            after_paragraph = !line.trim().is_empty();
+            index += 1;
+            continue;
+        };
+        let last = block_end(lines, index, end);
+        ranges.push(index..last);
+        after_paragraph = false;
+        index = last;
+    }
+    ranges
+}
+
+/// A document with its HTML blocks replaced by placeholder comments.
+///
+/// Transforms run over [`HtmlMask::lines`], which they treat as ordinary
+/// directive comments, and [`HtmlMask::unmask`] restores the original blocks
+/// in their output.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{html_blocks::HtmlMask, replace_ellipsis};
+///
+/// let lines: Vec<String> = ["Wait...", "", "<div>", "Loading...", "</div>"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let mask = HtmlMask::new(&lines);
+/// let fixed = mask.unmask(replace_ellipsis(mask.lines())).unwrap();
+/// assert_eq!(fixed, ["Wait…", "", "<div>", "Loading...", "</div>"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HtmlMask {
+    masked: Vec<String>,
+    blocks: Vec<Vec<String>>,
+    prefix: &'static str,
+}
+
+impl HtmlMask {
+    /// Masks the HTML blocks of `lines`.
+    #[must_use]
+    pub fn new(lines: &[String]) -> Self {
+        Self::with_prefix(lines, html_block_ranges(lines), PLACEHOLDER_PREFIX)
+    }
+
+    /// Masks the `ranges` of `lines`, which must be sorted and disjoint, with
+    /// placeholder comments starting with `prefix`. The prefix must open an
+    /// `mdtablefix-` directive comment, so transforms leave the placeholders
+    /// alone and an [`HtmlMask`] of the masked lines passes over them.
+    pub(crate) fn with_prefix(
+        lines: &[String],
+        ranges: Vec<Range<usize>>,
+        prefix: &'static str,
+    ) -> Self {
+        let mut masked = Vec::with_capacity(lines.len());
+        let mut blocks = Vec::new();
+        let mut next = 0;
+        for range in ranges {
+            masked.extend_from_slice(&lines[next..range.start]);
+            let first = &lines[range.start];
+            let indent = &first[..leading_indent(first).1];
+            masked.push(format!("{indent}{prefix}{} -->", blocks.len()));
+            blocks.push(lines[range.clone()].to_vec());
+            next = range.end;
+        }
+        masked.extend_from_slice(&lines[next..]);
+        Self {
+            masked,
+            blocks,
+            prefix,
+        }
+    }
+
+    /// Returns `true` when the document has no HTML blocks.
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.blocks.is_empty() }
+
+    /// Returns the document with each HTML block replaced by a placeholder.
+    #[must_use]
+    pub fn lines(&self) -> &[String] { &self.masked }
+
+    /// Replaces the placeholders in `lines` with the blocks they stand for.
+    ///
+    /// Returns `None` when a transform dropped, repeated, or reordered a
+    /// placeholder, as the blocks can then no longer be put back safely.
+    #[must_use]
+    pub fn unmask(&self, lines: Vec<String>) -> Option<Vec<String>> {
+        let mut out = Vec::with_capacity(lines.len() + self.blocks.len());
+        let mut restored = 0;
+        for line in lines {
+            match placeholder_index(&line, self.prefix) {
+                Some(index) if index == restored && index < self.blocks.len() => {
+                    out.extend(self.blocks[index].iter().cloned());
+                    restored += 1;
+                }
+                Some(_) => return None,
+                None => out.push(line),
+            }
+        }
+        (restored == self.blocks.len()).then_some(out)
+    }
+}
+
+fn placeholder_index(line: &str, prefix: &str) -> Option<usize> {
+    line.trim()
+        .strip_prefix(prefix)?
+        .strip_suffix(" -->")?
+        .parse()
+        .ok()
+}
+
+/// Applies `transform` to `lines` with their HTML blocks masked, so the
+/// transform cannot alter them.
+///
+/// When the transform loses track of a block, it is run again over the
+/// unmasked lines instead.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{format_breaks, html_blocks::outside_html_blocks};
+///
+/// let lines: Vec<String> = ["***", "", "<div>", "***", "</div>"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let out = outside_html_blocks(&lines, |doc| {
+///     format_breaks(doc)
+///         .into_iter()
+///         .map(|line| line.into_owned())
+///         .collect()
+/// });
+/// assert_eq!(out[0], "_".repeat(70));
+/// assert_eq!(out[3], "***");
+/// ```
+pub fn outside_html_blocks<F>(lines: &[String], transform: F) -> Vec<String>
+where
+    F: Fn(&[String]) -> Vec<String>,
+{
+    let mask = HtmlMask::new(lines);
+    if mask.is_empty() {
+        return transform(lines);
+    }
+    mask.unmask(transform(mask.lines()))
+        .unwrap_or_else(|| transform(lines))
+}
+
+#[cfg(test)]
+mod tests;
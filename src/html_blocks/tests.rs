@@ -0,0 +1,38 @@
+//! Unit tests for HTML block detection.
+
+use rstest::rstest;
+
+use super::*;
+use crate::test_support::lines;
+
+#[rstest]
+#[case::div_to_blank("<div>\n*a*\n\ntext", vec![0..2])]
+#[case::pre_spans_blanks("<pre>\n\ncode\n</pre>\ntext", vec![0..4])]
+#[case::one_line_script("<script>x()</script>\ntext", vec![0..1])]
+#[case::comment("<!--\nnote\n-->\ntext", vec![0..3])]
+#[case::declaration("<!DOCTYPE html>\ntext", vec![0..1])]
+#[case::closing_tag("</section>\ntext", vec![0..2])]
+#[case::lone_tag_after_blank("text\n\n<span class=\"x\">\nmore", vec![2..4])]
+#[case::lone_tag_in_paragraph("text\n<span>\nmore", vec![])]
+#[case::inline_html("<span>a</span> text", vec![])]
+#[case::autolink("<https://example.com>", vec![])]
+#[case::indented_code("    <div>", vec![])]
+#[case::fenced("```\n<div>\n```", vec![])]
+#[case::comment_after_paragraph("text\n<!-- note -->", vec![])]
+#[case::directive("<!-- mdtablefix-headers: | A | -->\n| A |", vec![])]
+#[case::pragma("<!-- mdtablefix: hide-columns A -->\n| A |", vec![])]
+#[case::details("<details>\n<summary>Title</summary>", vec![])]
+#[case::unclosed_pre("<pre>\ncode", vec![0..2])]
+fn finds_html_blocks(#[case] input: &str, #[case] expected: Vec<Range<usize>>) {
+    assert_eq!(html_block_ranges(&lines(input)), expected);
+}
+
+#[test]
+fn unmask_rejects_lost_placeholders() {
+    let mask = HtmlMask::new(&lines("<div>\n</div>"));
+    assert_eq!(mask.unmask(Vec::new()), None);
+    assert_eq!(
+        mask.unmask(mask.lines().to_vec()),
+        Some(lines("<div>\n</div>"))
+    );
+}
@@ -7,7 +7,7 @@
 use html5ever::{driver::ParseOpts, parse_document, tendril::TendrilSink};
 use markup5ever_rcdom::RcDom;
 
-use super::*;
+use super::{dom::is_table_cell, *};
 
 #[test]
 fn element_detection() {
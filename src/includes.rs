@@ -0,0 +1,383 @@
+//! Expansion of `<!-- include: path -->` pragmas.
+//!
+//! A document stitched together from fragments names each one on a line of
+//! its own, such as `<!-- include: setup.md -->`. [`expand_includes`] reads
+//! the named file, relative to the directory of the file that names it, and
+//! puts its lines in place of the pragma, expanding the fragment's own
+//! pragmas first. Under [`IncludeMode::Refresh`] the pragma stays and the
+//! fragment is written between it and an `<!-- end include -->` marker, which
+//! later runs replace with the fragment's current text, so expanding again is
+//! a no-op until a fragment changes.
+
+use std::{
+    fmt,
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+
+use crate::fences::FenceState;
+
+/// The deepest chain of nested includes expanded before giving up.
+pub const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// The marker closing a region written by [`IncludeMode::Refresh`].
+pub const END_INCLUDE: &str = "<!-- end include -->";
+
+static INCLUDE_RE: std::sync::LazyLock<Regex> = lazy_regex!(
+    r"^<!--\s*include:\s*(\S(?:.*\S)?)\s*-->$",
+    "include pragma pattern should compile",
+);
+
+static END_RE: std::sync::LazyLock<Regex> = lazy_regex!(
+    r"^<!--\s*end include\s*-->$",
+    "end include pattern should compile",
+);
+
+/// How [`expand_includes`] writes an expanded fragment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IncludeMode {
+    /// Replace the pragma with the fragment, leaving no trace of it.
+    #[default]
+    Inline,
+    /// Keep the pragma and write the fragment before an [`END_INCLUDE`]
+    /// marker, replacing whatever an earlier run wrote there.
+    Refresh,
+}
+
+/// Errors raised while expanding includes.
+#[derive(Debug)]
+pub enum IncludeError {
+    /// The included file could not be read.
+    Read {
+        /// The path as resolved against the including file.
+        path: PathBuf,
+        /// The underlying error.
+        source: io::Error,
+    },
+    /// The file includes itself, directly or through other fragments.
+    Cycle(PathBuf),
+    /// The chain of nested includes is deeper than [`MAX_INCLUDE_DEPTH`].
+    TooDeep(PathBuf),
+}
+
+impl fmt::Display for IncludeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read { path, source } => write!(f, "including {}: {source}", path.display()),
+            Self::Cycle(path) => write!(f, "{} includes itself", path.display()),
+            Self::TooDeep(path) => write!(
+                f,
+                "including {}: includes nested more than {MAX_INCLUDE_DEPTH} deep",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IncludeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read { source, .. } => Some(source),
+            Self::Cycle(_) | Self::TooDeep(_) => None,
+        }
+    }
+}
+
+/// Expands the include pragmas in `lines`, the text of the file at `origin`.
+///
+/// Paths in pragmas are resolved against the directory holding `origin`,
+/// and paths in a fragment against the fragment's directory. `origin` need
+/// not exist, so standard input can pass a placeholder such as `-` to
+/// resolve paths against the working directory. Pragmas inside fenced code
+/// are left alone, and an indented pragma indents the fragment to match.
+///
+/// # Errors
+/// Returns an [`IncludeError`] when a fragment cannot be read, when a file
+/// includes itself, or when includes nest more than [`MAX_INCLUDE_DEPTH`]
+/// deep.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::includes::{IncludeMode, expand_includes};
+///
+/// let dir = tempfile::tempdir()?;
+/// std::fs::write(dir.path().join("part.md"), "Included.\n")?;
+/// let lines = vec![
+///     "# Guide".to_string(),
+///     "<!-- include: part.md -->".to_string(),
+/// ];
+/// let origin = dir.path().join("guide.md");
+/// assert_eq!(
+///     expand_includes(&lines, &origin, IncludeMode::Inline)?,
+///     ["# Guide", "Included."]
+/// );
+/// assert_eq!(
+///     expand_includes(&lines, &origin, IncludeMode::Refresh)?,
+///     [
+///         "# Guide",
+///         "<!-- include: part.md -->",
+///         "Included.",
+///         "<!-- end include -->"
+///     ]
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn expand_includes(
+    lines: &[String],
+    origin: &Path,
+    mode: IncludeMode,
+) -> Result<Vec<String>, IncludeError> {
+    let mut stack: Vec<PathBuf> = fs::canonicalize(origin).into_iter().collect();
+    let dir = origin.parent().unwrap_or(Path::new(""));
+    expand(lines, dir, mode, &mut stack, 0)
+}
+
+/// Returns the path named by an include pragma on `line`.
+fn include_target(line: &str) -> Option<&str> {
+    INCLUDE_RE
+        .captures(line.trim())
+        .and_then(|caps| caps.get(1))
+        .map(|target| target.as_str())
+}
+
+/// Returns the index of the [`END_INCLUDE`] marker closing the region that
+/// starts at `start`, skipping the regions of nested pragmas, or `None` when
+/// the pragma before `start` has not been expanded yet.
+fn region_end(lines: &[String], start: usize) -> Option<usize> {
+    let mut fences = FenceState::default();
+    let mut depth = 0usize;
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        if fences.observe_source_line(line).is_code() {
+            continue;
+        }
+        if include_target(line).is_some() {
+            depth += 1;
+        } else if END_RE.is_match(line.trim()) {
+            if depth == 0 {
+                return Some(index);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Expands the pragmas in `lines`, a file nested `depth` includes deep whose
+/// paths resolve against `dir`. `stack` holds the files being expanded.
+fn expand(
+    lines: &[String],
+    dir: &Path,
+    mode: IncludeMode,
+    stack: &mut Vec<PathBuf>,
+    depth: usize,
+) -> Result<Vec<String>, IncludeError> {
+    let mut out = Vec::with_capacity(lines.len());
+    let mut fences = FenceState::default();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        index += 1;
+        let fence = fences.observe_source_line(line);
+        let Some(target) = include_target(line).filter(|_| !fence.is_code()) else {
+            out.push(line.clone());
+            continue;
+        };
+        let fragment = read_fragment(&dir.join(target), mode, stack, depth + 1)?;
+        let indent = &line[..line.len() - line.trim_start().len()];
+        if mode == IncludeMode::Refresh {
+            out.push(line.clone());
+        }
+        out.extend(fragment.into_iter().map(|text| {
+            if text.is_empty() {
+                text
+            } else {
+                format!("{indent}{text}")
+            }
+        }));
+        if mode == IncludeMode::Refresh {
+            out.push(format!("{indent}{END_INCLUDE}"));
+        }
+        if let Some(end) = region_end(lines, index) {
+            index = end + 1;
+        }
+    }
+    Ok(out)
+}
+
+/// Reads the fragment at `path`, included `depth` deep, and expands its own
+/// includes.
+fn read_fragment(
+    path: &Path,
+    mode: IncludeMode,
+    stack: &mut Vec<PathBuf>,
+    depth: usize,
+) -> Result<Vec<String>, IncludeError> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(IncludeError::TooDeep(path.to_path_buf()));
+    }
+    let read_error = |source| IncludeError::Read {
+        path: path.to_path_buf(),
+        source,
+    };
+    let canonical = fs::canonicalize(path).map_err(read_error)?;
+    if stack.contains(&canonical) {
+        return Err(IncludeError::Cycle(path.to_path_buf()));
+    }
+    let text = fs::read_to_string(&canonical).map_err(read_error)?;
+    let lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let dir = canonical.parent().unwrap_or(Path::new("")).to_path_buf();
+    stack.push(canonical);
+    let expanded = expand(&lines, &dir, mode, stack, depth);
+    stack.pop();
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for include expansion.
+
+    use rstest::rstest;
+    use tempfile::{TempDir, tempdir};
+
+    use super::*;
+    use crate::test_support::lines;
+
+    /// Writes `files` into a new directory, returning it with the path of a
+    /// document inside it.
+    fn fixture(files: &[(&str, &str)]) -> (TempDir, PathBuf) {
+        let dir = tempdir().expect("create temp dir");
+        for (name, text) in files {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().expect("file has a parent")).expect("create dir");
+            fs::write(path, text).expect("write fragment");
+        }
+        let origin = dir.path().join("doc.md");
+        (dir, origin)
+    }
+
+    #[rstest]
+    #[case::inline(
+        IncludeMode::Inline,
+        "a\n<!-- include: part.md -->\nb",
+        "a\none\ntwo\nb"
+    )]
+    #[case::refresh(
+        IncludeMode::Refresh,
+        "a\n<!-- include: part.md -->\nb",
+        "a\n<!-- include: part.md -->\none\ntwo\n<!-- end include -->\nb"
+    )]
+    #[case::refresh_replaces_region(
+        IncludeMode::Refresh,
+        "<!-- include: part.md -->\nstale\n<!-- end include -->\nb",
+        "<!-- include: part.md -->\none\ntwo\n<!-- end include -->\nb"
+    )]
+    #[case::inline_drops_region(
+        IncludeMode::Inline,
+        "<!-- include: part.md -->\nstale\n<!-- end include -->",
+        "one\ntwo"
+    )]
+    #[case::indented(
+        IncludeMode::Inline,
+        "- item\n\n  <!-- include: part.md -->",
+        "- item\n\n  one\n  two"
+    )]
+    #[case::fenced(
+        IncludeMode::Inline,
+        "```\n<!-- include: part.md -->\n```",
+        "```\n<!-- include: part.md -->\n```"
+    )]
+    fn expands_pragmas(#[case] mode: IncludeMode, #[case] input: &str, #[case] expected: &str) {
+        let (_dir, origin) = fixture(&[("part.md", "one\ntwo\n")]);
+        let out = expand_includes(&lines(input), &origin, mode).expect("expand");
+        assert_eq!(out, lines(expected));
+    }
+
+    #[rstest]
+    #[case::inline(IncludeMode::Inline, "x\ninner\ny")]
+    #[case::refresh(
+        IncludeMode::Refresh,
+        "<!-- include: sub/outer.md -->\nx\n<!-- include: inner.md -->\ninner\n<!-- end include \
+         -->\ny\n<!-- end include -->"
+    )]
+    fn nested_paths_resolve_against_the_fragment(
+        #[case] mode: IncludeMode,
+        #[case] expected: &str,
+    ) {
+        let (_dir, origin) = fixture(&[
+            ("sub/outer.md", "x\n<!-- include: inner.md -->\ny\n"),
+            ("sub/inner.md", "inner\n"),
+        ]);
+        let input = lines("<!-- include: sub/outer.md -->");
+        let once = expand_includes(&input, &origin, mode).expect("expand");
+        assert_eq!(once, lines(expected));
+        let twice = expand_includes(&once, &origin, mode).expect("expand again");
+        assert_eq!(twice, once);
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let (_dir, origin) = fixture(&[
+            ("a.md", "<!-- include: b.md -->\n"),
+            ("b.md", "<!-- include: a.md -->\n"),
+        ]);
+        let err = expand_includes(
+            &lines("<!-- include: a.md -->"),
+            &origin,
+            IncludeMode::Inline,
+        )
+        .expect_err("cycle");
+        assert!(matches!(err, IncludeError::Cycle(path) if path.ends_with("a.md")));
+    }
+
+    #[test]
+    fn self_inclusion_is_a_cycle() {
+        let (_dir, origin) = fixture(&[("doc.md", "<!-- include: doc.md -->\n")]);
+        let err = expand_includes(
+            &lines("<!-- include: doc.md -->"),
+            &origin,
+            IncludeMode::Inline,
+        )
+        .expect_err("cycle");
+        assert!(matches!(err, IncludeError::Cycle(_)));
+    }
+
+    #[test]
+    fn deep_nesting_is_reported() {
+        let names: Vec<String> = (0..=MAX_INCLUDE_DEPTH + 1)
+            .map(|n| format!("{n}.md"))
+            .collect();
+        let files: Vec<(String, String)> = names
+            .windows(2)
+            .map(|pair| (pair[0].clone(), format!("<!-- include: {} -->\n", pair[1])))
+            .collect();
+        let files: Vec<(&str, &str)> = files
+            .iter()
+            .map(|(a, b)| (a.as_str(), b.as_str()))
+            .collect();
+        let (_dir, origin) = fixture(&files);
+        let err = expand_includes(
+            &lines("<!-- include: 0.md -->"),
+            &origin,
+            IncludeMode::Inline,
+        )
+        .expect_err("too deep");
+        assert!(matches!(err, IncludeError::TooDeep(_)));
+    }
+
+    #[test]
+    fn missing_fragments_are_read_errors() {
+        let (_dir, origin) = fixture(&[]);
+        let err = expand_includes(
+            &lines("<!-- include: nope.md -->"),
+            &origin,
+            IncludeMode::Inline,
+        )
+        .expect_err("missing");
+        assert!(err.to_string().starts_with("including "));
+        assert!(matches!(err, IncludeError::Read { .. }));
+    }
+}
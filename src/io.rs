@@ -1,13 +1,33 @@
 //! File helpers for rewriting Markdown documents.
+//!
+//! Every rewrite goes through [`write_atomic`], so an interrupted run never
+//! leaves a truncated document. [`process_reader`] formats documents too
+//! large to read into memory at once, and [`read_text`] reads files that are
+//! not valid UTF-8.
 
-use std::{fs, path::Path};
+mod encoding;
+mod stream;
 
-use crate::process::{process_stream, process_stream_no_wrap};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+pub use encoding::{DecodePolicy, Decoded, Encoding, decode, read_text};
+pub use stream::process_reader;
+use tempfile::NamedTempFile;
+
+use crate::{
+    document::Document,
+    process::{process_stream, process_stream_no_wrap},
+};
 
 /// Read `path`, process the contents with `f`, and write the result back.
 ///
 /// This helper encapsulates the common pattern used by [`rewrite`] and
-/// [`rewrite_no_wrap`].
+/// [`rewrite_no_wrap`]. The file keeps its line endings and ends with one
+/// exactly when it did before.
 ///
 /// # Errors
 /// Returns an error if reading or writing the file fails.
@@ -15,15 +35,71 @@ fn rewrite_with<F>(path: &Path, f: F) -> std::io::Result<()>
 where
     F: Fn(&[String]) -> Vec<String>,
 {
-    let text = fs::read_to_string(path)?;
-    let lines: Vec<String> = text.lines().map(str::to_string).collect();
-    let fixed = f(&lines);
-    let output = if fixed.is_empty() {
-        String::new()
-    } else {
-        fixed.join("\n") + "\n"
+    let doc = Document::parse(&fs::read_to_string(path)?);
+    write_atomic(path, doc.with_lines(f(&doc.lines)).render())
+}
+
+/// Replace the contents of `path` with `contents` without ever leaving a
+/// partly written file behind.
+///
+/// The text goes to a temporary file in the same directory, which is then
+/// renamed over `path`, so a crash or a failed write keeps the original
+/// intact. The new file takes the original's permissions and, on Unix, its
+/// owner and group where the caller may set them, so a rewrite does not
+/// change who can read or run the file. A symbolic link at `path` is followed
+/// so the link survives, and a file the caller may not write is refused just
+/// as [`fs::write`] would refuse it.
+///
+/// # Errors
+/// Returns an error if `path` is not writable or writing or renaming the
+/// temporary file fails.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let target = resolve_link(path)?;
+    let original = match fs::metadata(&target) {
+        Ok(meta) => {
+            OpenOptions::new().write(true).open(&target)?;
+            Some(meta)
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
     };
-    fs::write(path, output)
+    let dir = target
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut temp = NamedTempFile::new_in(dir)?;
+    temp.write_all(contents.as_ref())?;
+    if let Some(meta) = original {
+        copy_metadata(&meta, temp.as_file())?;
+    }
+    temp.as_file().sync_all()?;
+    temp.persist(&target).map_err(|err| err.error)?;
+    Ok(())
+}
+
+/// Gives `file` the permissions, and on Unix the ownership, recorded in
+/// `meta`. Only a privileged caller may hand a file to another user, so a
+/// refused ownership change leaves the caller as owner rather than failing.
+fn copy_metadata(meta: &fs::Metadata, file: &fs::File) -> std::io::Result<()> {
+    file.set_permissions(meta.permissions())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, fchown};
+
+        match fchown(file, Some(meta.uid()), Some(meta.gid())) {
+            Err(err) if err.kind() != std::io::ErrorKind::PermissionDenied => return Err(err),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Returns the file a symbolic link at `path` points to, or `path` itself.
+fn resolve_link(path: &Path) -> std::io::Result<PathBuf> {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => fs::canonicalize(path),
+        _ => Ok(path.to_path_buf()),
+    }
 }
 
 /// Rewrite a file in place with wrapped tables.
@@ -65,6 +141,16 @@ mod tests {
         assert!(out.contains("| A | B |"));
     }
 
+    #[test]
+    fn rewrite_keeps_crlf_line_endings() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("sample.md");
+        fs::write(&file, "|A|B|\r\n|1|2|\r\n").unwrap();
+        rewrite_no_wrap(&file).unwrap();
+        let out = fs::read_to_string(&file).unwrap();
+        assert_eq!(out, "| A | B |\r\n| 1 | 2 |\r\n");
+    }
+
     #[test]
     fn rewrite_no_wrap_roundtrip() {
         let dir = tempdir().unwrap();
@@ -72,7 +158,7 @@ mod tests {
         fs::write(&file, "|A|B|\n|1|2|").unwrap();
         rewrite_no_wrap(&file).unwrap();
         let out = fs::read_to_string(&file).unwrap();
-        assert_eq!(out, "| A | B |\n| 1 | 2 |\n");
+        assert_eq!(out, "| A | B |\n| 1 | 2 |");
     }
 
     #[cfg(unix)]
@@ -119,6 +205,49 @@ mod tests {
         assert_permission_error_or_root_success(result);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_keeps_permissions_and_links() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("script.md");
+        let link = dir.path().join("link.md");
+        fs::write(&file, "old").unwrap();
+        fs::set_permissions(&file, Permissions::from_mode(0o755)).unwrap();
+        std::os::unix::fs::symlink(&file, &link).unwrap();
+        write_atomic(&link, "new").unwrap();
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+        let mode = fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+
+    #[test]
+    fn write_atomic_leaves_no_temporary_files() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("doc.md");
+        fs::write(&file, "old").unwrap();
+        write_atomic(&file, "new").unwrap();
+        let names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["doc.md"]);
+    }
+
+    #[test]
+    fn rewrite_keeps_a_missing_final_newline() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("doc.md");
+        fs::write(&file, "|a|b|").unwrap();
+        rewrite(&file).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "| a | b |");
+    }
+
     #[test]
     fn rewrite_empty_file_no_extra_newline() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,265 @@
+//! Decoding of Markdown files that are not valid UTF-8.
+//!
+//! [`fs::read_to_string`] refuses a file with a single stray Latin-1 byte.
+//! [`decode`] applies a [`DecodePolicy`] instead: refuse as before, replace
+//! the invalid bytes, or detect UTF-16 with a byte order mark and Latin-1
+//! and remember the [`Encoding`], so [`Encoding::encode`] can write the
+//! formatted text back in the encoding it was read in.
+
+use std::{fs, io, path::Path};
+
+/// How [`decode`] treats bytes that are not valid UTF-8.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DecodePolicy {
+    /// Fail with [`io::ErrorKind::InvalidData`].
+    #[default]
+    Strict,
+    /// Replace each invalid sequence with `U+FFFD`; the text is written back
+    /// as UTF-8.
+    Lossy,
+    /// Read files starting with a UTF-16 byte order mark as UTF-16 and other
+    /// files that are not valid UTF-8 as Latin-1, so they can be written back
+    /// in the same encoding.
+    Detect,
+}
+
+/// The encoding a file was decoded from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8. A byte order mark stays in the text as `U+FEFF`.
+    #[default]
+    Utf8,
+    /// ISO 8859-1, where each byte is the code point of the same value.
+    Latin1,
+    /// Little-endian UTF-16 with a byte order mark.
+    Utf16Le,
+    /// Big-endian UTF-16 with a byte order mark.
+    Utf16Be,
+}
+
+impl Encoding {
+    /// Returns the encoding's name, as used in log messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::io::Encoding;
+    ///
+    /// assert_eq!(Encoding::Utf16Le.name(), "UTF-16LE");
+    /// ```
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Latin1 => "Latin-1",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf16Be => "UTF-16BE",
+        }
+    }
+
+    /// Encodes `text` in this encoding, starting UTF-16 with its byte order
+    /// mark.
+    ///
+    /// # Errors
+    /// Returns [`io::ErrorKind::InvalidData`] when `text` holds a character
+    /// Latin-1 cannot represent, such as an `…` added by ellipsis
+    /// replacement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::io::Encoding;
+    ///
+    /// assert_eq!(Encoding::Latin1.encode("café").unwrap(), b"caf\xe9");
+    /// assert!(Encoding::Latin1.encode("wait…").is_err());
+    /// ```
+    pub fn encode(self, text: &str) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Utf8 => Ok(text.as_bytes().to_vec()),
+            Self::Latin1 => text
+                .chars()
+                .map(|ch| {
+                    u8::try_from(ch).map_err(|_| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("`{ch}` cannot be written as Latin-1"),
+                        )
+                    })
+                })
+                .collect(),
+            Self::Utf16Le => Ok(std::iter::once('\u{feff}' as u16)
+                .chain(text.encode_utf16())
+                .flat_map(u16::to_le_bytes)
+                .collect()),
+            Self::Utf16Be => Ok(std::iter::once('\u{feff}' as u16)
+                .chain(text.encode_utf16())
+                .flat_map(u16::to_be_bytes)
+                .collect()),
+        }
+    }
+}
+
+/// Text decoded by [`decode`] and the encoding it was read in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decoded {
+    /// The decoded text, without a UTF-16 byte order mark.
+    pub text: String,
+    /// The encoding to write the text back in.
+    pub encoding: Encoding,
+    /// Whether [`DecodePolicy::Lossy`] replaced invalid bytes.
+    pub replaced: bool,
+}
+
+/// Decodes `bytes` according to `policy`.
+///
+/// Valid UTF-8 is always read as UTF-8, so [`DecodePolicy::Detect`] only
+/// treats a file as Latin-1 when it is not valid UTF-8 and has no UTF-16
+/// byte order mark.
+///
+/// # Errors
+/// Returns [`io::ErrorKind::InvalidData`] for invalid UTF-8 under
+/// [`DecodePolicy::Strict`], and for UTF-16 with an odd byte count or an
+/// unpaired surrogate.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::io::{DecodePolicy, Encoding, decode};
+///
+/// let bytes = b"caf\xe9".to_vec();
+/// assert!(decode(bytes.clone(), DecodePolicy::Strict).is_err());
+/// assert_eq!(
+///     decode(bytes.clone(), DecodePolicy::Lossy).unwrap().text,
+///     "caf\u{fffd}"
+/// );
+/// let detected = decode(bytes, DecodePolicy::Detect).unwrap();
+/// assert_eq!(
+///     (detected.text.as_str(), detected.encoding),
+///     ("café", Encoding::Latin1)
+/// );
+/// ```
+pub fn decode(bytes: Vec<u8>, policy: DecodePolicy) -> io::Result<Decoded> {
+    if policy == DecodePolicy::Detect {
+        match bytes.as_slice() {
+            [0xff, 0xfe, rest @ ..] => return decode_utf16(rest, Encoding::Utf16Le),
+            [0xfe, 0xff, rest @ ..] => return decode_utf16(rest, Encoding::Utf16Be),
+            _ => {}
+        }
+    }
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(Decoded {
+            text,
+            encoding: Encoding::Utf8,
+            replaced: false,
+        }),
+        Err(err) => match policy {
+            DecodePolicy::Strict => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid UTF-8 at byte {}", err.utf8_error().valid_up_to()),
+            )),
+            DecodePolicy::Lossy => Ok(Decoded {
+                text: String::from_utf8_lossy(err.as_bytes()).into_owned(),
+                encoding: Encoding::Utf8,
+                replaced: true,
+            }),
+            DecodePolicy::Detect => Ok(Decoded {
+                text: err.as_bytes().iter().copied().map(char::from).collect(),
+                encoding: Encoding::Latin1,
+                replaced: false,
+            }),
+        },
+    }
+}
+
+/// Decodes UTF-16 `bytes` that follow a byte order mark.
+fn decode_utf16(bytes: &[u8], encoding: Encoding) -> io::Result<Decoded> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let (units, rest) = bytes.as_chunks::<2>();
+    if !rest.is_empty() {
+        return Err(invalid("UTF-16 text has an odd number of bytes"));
+    }
+    let units: Vec<u16> = units
+        .iter()
+        .map(|&pair| match encoding {
+            Encoding::Utf16Be => u16::from_be_bytes(pair),
+            _ => u16::from_le_bytes(pair),
+        })
+        .collect();
+    let text =
+        String::from_utf16(&units).map_err(|_| invalid("UTF-16 text has an unpaired surrogate"))?;
+    Ok(Decoded {
+        text,
+        encoding,
+        replaced: false,
+    })
+}
+
+/// Reads `path` and decodes it according to `policy`.
+///
+/// # Errors
+/// Returns an error if reading the file fails or [`decode`] rejects it.
+pub fn read_text(path: &Path, policy: DecodePolicy) -> io::Result<Decoded> {
+    decode(fs::read(path)?, policy)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for decoding and re-encoding file contents.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::utf8("| a | ü |\n".as_bytes().to_vec(), Encoding::Utf8)]
+    #[case::utf8_bom(b"\xef\xbb\xbf# Title\n".to_vec(), Encoding::Utf8)]
+    #[case::latin1(b"| caf\xe9 | na\xefve |\n".to_vec(), Encoding::Latin1)]
+    #[case::utf16le(b"\xff\xfe#\0 \0\xe9\0\n\0".to_vec(), Encoding::Utf16Le)]
+    #[case::utf16be(b"\xfe\xff\0#\0 \0\xe9\0\n".to_vec(), Encoding::Utf16Be)]
+    fn detected_encodings_round_trip(#[case] bytes: Vec<u8>, #[case] encoding: Encoding) {
+        let decoded = decode(bytes.clone(), DecodePolicy::Detect).unwrap();
+        assert_eq!(decoded.encoding, encoding);
+        assert_eq!(decoded.encoding.encode(&decoded.text).unwrap(), bytes);
+    }
+
+    #[test]
+    fn strict_reports_the_first_invalid_byte() {
+        let err = decode(b"ab\xffc".to_vec(), DecodePolicy::Strict).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "invalid UTF-8 at byte 2");
+    }
+
+    #[test]
+    fn lossy_replaces_only_invalid_bytes() {
+        let decoded = decode(b"a\xffb".to_vec(), DecodePolicy::Lossy).unwrap();
+        assert_eq!(decoded.text, "a\u{fffd}b");
+        assert_eq!(decoded.encoding, Encoding::Utf8);
+        assert!(decoded.replaced);
+        assert!(
+            !decode(b"ab".to_vec(), DecodePolicy::Lossy)
+                .unwrap()
+                .replaced
+        );
+    }
+
+    #[rstest]
+    #[case::odd_length(b"\xff\xfea\0b".to_vec())]
+    #[case::unpaired_surrogate(b"\xff\xfe\x00\xd8".to_vec())]
+    fn rejects_malformed_utf16(#[case] bytes: Vec<u8>) {
+        assert_eq!(
+            decode(bytes, DecodePolicy::Detect).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn utf16_byte_order_marks_are_only_read_when_detecting() {
+        assert!(decode(b"\xff\xfea\0".to_vec(), DecodePolicy::Strict).is_err());
+        assert_eq!(
+            decode(b"\xff\xfea\0".to_vec(), DecodePolicy::Lossy)
+                .unwrap()
+                .text,
+            "\u{fffd}\u{fffd}a\0"
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Streaming processing of documents too large to hold as one line vector.
+//!
+//! [`process_reader`] reads a document a line at a time and formats it in
+//! chunks, writing each chunk before reading the next. A chunk ends only at a
+//! blank line that no construct read as a whole spans: fenced code, HTML
+//! blocks and `<table>` elements, YAML frontmatter, or a block an
+//! `mdtablefix:` pragma applies to. Every built-in stage other than footnote
+//! conversion looks no further than such a block, so the output matches
+//! [`process_stream_opts`] on the whole document.
+
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    document::LineEnding,
+    fences::FenceState,
+    frontmatter::split_leading_yaml_frontmatter,
+    html_blocks::html_block_ranges,
+    process::{Options, process_stream_inner, process_stream_opts},
+};
+
+/// The fewest lines a chunk holds before a blank line may end it, so the
+/// per-chunk cost of starting the pipeline stays small.
+const CHUNK_LINES: usize = 1024;
+
+/// Formats the Markdown read from `reader` with `opts`, writing the result to
+/// `writer` block by block instead of reading the whole document first.
+///
+/// The output uses the line ending of the input's first line and, like
+/// [`rewrite`](super::rewrite), ends with a line ending when it is not
+/// empty. Footnote conversion numbers references across the whole document,
+/// and a [`BlockProtection`](crate::protect::BlockProtection) predicate or a
+/// registered [`LineTransform`](crate::process::LineTransform) may look at
+/// any block, so with [`Options::footnotes`], [`Options::protect`], or
+/// [`Options::transforms`] set the document is read in full before it is
+/// formatted.
+///
+/// # Errors
+/// Returns an error if reading from `reader` fails, including on input that
+/// is not UTF-8, or if writing to `writer` fails.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{Options, io::process_reader};
+///
+/// let input = "|a|b|\r\n|-|-|\r\n\r\nWait...\r\n";
+/// let opts = Options {
+///     ellipsis: true,
+///     ..Options::default()
+/// };
+/// let mut output = Vec::new();
+/// process_reader(input.as_bytes(), &mut output, &opts)?;
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "| a   | b   |\r\n| --- | --- |\r\n\r\nWait…\r\n"
+/// );
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn process_reader<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    opts: &Options,
+) -> io::Result<()> {
+    process_in_chunks(reader, writer, opts, CHUNK_LINES)
+}
+
+/// Streams `reader` to `writer` in chunks of at least `chunk_lines` lines.
+fn process_in_chunks<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    opts: &Options,
+    chunk_lines: usize,
+) -> io::Result<()> {
+    let whole_document = opts.footnotes || !opts.protect.is_empty() || !opts.transforms.is_empty();
+    let mut chunker = Chunker::default();
+    let mut ending = None;
+    let mut buf = String::new();
+    while reader.read_line(&mut buf)? > 0 {
+        let line = buf.strip_suffix('\n').map_or(buf.as_str(), |line| {
+            ending.get_or_insert(if line.ends_with('\r') {
+                LineEnding::CrLf
+            } else {
+                LineEnding::Lf
+            });
+            line.strip_suffix('\r').unwrap_or(line)
+        });
+        if !whole_document && chunker.lines.len() >= chunk_lines && chunker.can_split_before(line) {
+            chunker.flush(&mut writer, opts, ending.unwrap_or_default())?;
+        }
+        chunker.push(line.to_string());
+        buf.clear();
+    }
+    chunker.flush(&mut writer, opts, ending.unwrap_or_default())?;
+    writer.flush()
+}
+
+/// The lines read since the last chunk was written, and the state needed to
+/// tell whether the chunk may end.
+#[derive(Default)]
+struct Chunker {
+    lines: Vec<String>,
+    fences: FenceState,
+    /// `<table>` elements opened and not yet closed.
+    open_tables: usize,
+    /// Whether a chunk has already been written.
+    started: bool,
+}
+
+impl Chunker {
+    fn push(&mut self, line: String) {
+        if !self.fences.observe_source_line(&line).is_code() {
+            let lower = line.to_ascii_lowercase();
+            self.open_tables += lower.matches("<table").count();
+            self.open_tables = self
+                .open_tables
+                .saturating_sub(lower.matches("</table").count());
+        }
+        self.lines.push(line);
+    }
+
+    /// Returns `true` when the lines read so far form a chunk that `next`
+    /// may start after: they end in a blank line outside every construct
+    /// that spans blank lines, and `next` starts a new top-level block.
+    fn can_split_before(&self, next: &str) -> bool {
+        let Some(last) = self.lines.last() else {
+            return false;
+        };
+        if !last.trim().is_empty()
+            || next.trim().is_empty()
+            || next.starts_with(char::is_whitespace)
+            || self.open_tables > 0
+            || self.fences.in_fence_for_line(next)
+        {
+            return false;
+        }
+        let pragma = self
+            .lines
+            .iter()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .is_some_and(|line| line.trim_start().starts_with("<!-- mdtablefix:"));
+        let open_frontmatter = !self.started
+            && self.lines[0] == "---"
+            && split_leading_yaml_frontmatter(&self.lines).0.is_empty();
+        let blank = self.lines.len() - 1;
+        let open_html = html_block_ranges(&self.lines)
+            .last()
+            .is_some_and(|range| range.end > blank);
+        !pragma && !open_frontmatter && !open_html
+    }
+
+    /// Formats and writes the buffered lines, then starts a new chunk.
+    fn flush<W: Write>(
+        &mut self,
+        writer: &mut W,
+        opts: &Options,
+        ending: LineEnding,
+    ) -> io::Result<()> {
+        let lines = std::mem::take(&mut self.lines);
+        let out = if self.started {
+            process_stream_inner(&lines, opts.clone())
+        } else {
+            process_stream_opts(&lines, opts.clone())
+        };
+        for line in out {
+            writer.write_all(line.as_bytes())?;
+            writer.write_all(ending.as_str().as_bytes())?;
+        }
+        self.fences = FenceState::default();
+        self.open_tables = 0;
+        self.started = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for streaming processing.
+
+    use std::fs;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    fn stream(input: &str, opts: &Options, chunk_lines: usize) -> String {
+        let mut out = Vec::new();
+        process_in_chunks(input.as_bytes(), &mut out, opts, chunk_lines).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn whole(input: &str, opts: &Options) -> String {
+        let lines: Vec<String> = input.lines().map(str::to_string).collect();
+        process_stream_opts(&lines, opts.clone())
+            .into_iter()
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    #[rstest]
+    #[case::frontmatter("---\ntitle: x\n\nmore: y\n---\n\n|a|b|\n|-|-|\n")]
+    #[case::fence("```\n|a|\n\n|b|\n```\n\ntext...\n")]
+    #[case::html_block("<pre>\n|a|\n\n|b|\n</pre>\n\n|c|d|\n")]
+    #[case::html_table("<table>\n<tr><td>a</td></tr>\n\n<tr><td>b</td></tr>\n</table>\n\nx\n")]
+    #[case::pragma("<!-- mdtablefix: hide-columns B -->\n\n| A | B |\n| - | - |\n| 1 | 2 |\n")]
+    #[case::thematic_break_chunk("text\n\n---\n\ntext\n")]
+    fn matches_whole_document_processing(#[case] input: &str) {
+        let opts = Options {
+            wrap: true,
+            ellipsis: true,
+            ..Options::default()
+        };
+        assert_eq!(stream(input, &opts, 1), whole(input, &opts));
+    }
+
+    #[test]
+    fn matches_whole_document_processing_on_fixtures() {
+        let opts = Options {
+            wrap: true,
+            code_spans: true,
+            headings: true,
+            ..Options::default()
+        };
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data");
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.to_string_lossy().ends_with("_input.txt") {
+                continue;
+            }
+            let input = fs::read_to_string(&path).unwrap();
+            assert_eq!(
+                stream(&input, &opts, 1),
+                whole(&input, &opts),
+                "{}",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn empty_input_writes_nothing() {
+        assert_eq!(stream("", &Options::default(), 1), "");
+    }
+}
@@ -1,18 +1,44 @@
 //! Library for normalizing Markdown tables and wrapping text.
 //!
 //! Modules:
-//! - `html` for converting HTML tables.
+//! - `html` for converting or parsing HTML tables (`html` feature, on by default).
+//! - `html_blocks` for keeping block-level HTML away from Markdown transforms.
+//! - `wikimarkup` for converting Jira and Confluence wiki tables.
+//! - `rst` for converting reStructuredText grid and simple tables.
 //! - `table` for standardizing Markdown table alignment.
+//! - `abbreviations` for shortening the headers of over-wide tables.
+//! - `hide_columns` for dropping columns named by a pragma from tables.
 //! - `wrap` for paragraph wrapping.
+//! - `rejoin` for merging lines hard-wrapped by other tools.
+//! - `list_markers` for normalizing the spaces after list markers.
 //! - `lists` for renumbering ordered lists.
+//! - `renumber_comments` for recording the numbers renumbering replaced.
 //! - `breaks` for thematizing horizontal rules.
+//! - `blank_lines` for limiting runs of blank lines.
+//! - `anchors` for listing the link targets a document defines.
+//! - `block_ids` for numbering tables, fences, and headings in document order.
+//! - `blocks` for excluding block types from individual transforms.
+//! - `changes` for recording which transform rewrote which lines.
 //! - `ellipsis` for replacing textual ellipses.
+//! - `opaque` for keeping template placeholders whole.
+//! - `protect` for blocks embedders mark as untouchable.
+//! - `sentence_spacing` for normalizing the spaces between sentences.
+//! - `extract` for plain-text extraction used by search indexers.
 //! - `fences` for issues with code block fences
+//! - `sections` for resolving the lines under a heading.
+//! - `rust_fences` for compiling fenced Rust examples (`check-rust-fences` feature).
 //! - `footnotes` for converting bare footnote links.
-//! - `headings` for standardizing Setext headings.
+//! - `plugins` for running WASM plugins as pipeline stages (`wasm-plugins` feature).
+//! - `hard_breaks` for normalizing hard line breaks to one style.
+//! - `headings` for standardizing Setext headings and bold pseudo-headings.
 //! - `code_emphasis` for fixing emphasis adjoining inline code.
+//! - `code_spans` for trimming and re-delimiting inline code.
 //! - `textproc` for token-based transformations.
-//! - `process` for stream processing.
+//! - `process` for stream processing and the `Pipeline` builder.
+//! - `error` for the `Error` returned by the fallible `try_*` functions.
+//! - `document` for keeping line endings and the final newline across processing.
+//! - `includes` for expanding `<!-- include: path -->` pragmas.
+//! - `titles` for checking first headings against page titles.
 //! - `io` for file helpers.
 
 #[macro_export]
@@ -22,37 +48,104 @@ macro_rules! lazy_regex {
     };
 }
 
+pub mod abbreviations;
+pub mod anchors;
+pub mod blank_lines;
+pub mod block_ids;
+pub mod blocks;
 pub mod breaks;
+pub mod changes;
 pub mod code_emphasis;
+pub mod code_spans;
+pub mod document;
 pub mod ellipsis;
+pub mod error;
+pub mod extract;
 pub mod fences;
 pub mod footnotes;
 pub(crate) mod frontmatter;
+pub mod hard_breaks;
 pub mod headings;
-mod html;
+pub mod hide_columns;
+#[cfg(feature = "html")]
+pub mod html;
+pub mod html_blocks;
+pub mod includes;
 pub mod io;
+pub mod list_markers;
 pub mod lists;
+pub mod opaque;
+#[cfg(feature = "wasm-plugins")]
+pub mod plugins;
 pub mod process;
+pub mod protect;
 mod reflow;
+pub mod rejoin;
+pub mod renumber_comments;
+pub mod rst;
+#[cfg(feature = "check-rust-fences")]
+pub mod rust_fences;
+pub mod sections;
+pub mod sentence_spacing;
 pub mod table;
+#[cfg(test)]
+mod test_support;
 pub mod textproc;
+pub mod titles;
+pub mod wikimarkup;
 pub mod wrap;
 
+#[cfg(feature = "html")]
 #[deprecated(note = "this function is legacy; use `convert_html_tables` instead")]
 #[must_use]
 pub fn html_table_to_markdown(lines: &[String]) -> Vec<String> {
     html::html_table_to_markdown(lines)
 }
 
+pub use abbreviations::abbreviate_headers;
 pub use breaks::{THEMATIC_BREAK_LEN, format_breaks};
 pub use code_emphasis::fix_code_emphasis;
+pub use code_spans::normalize_code_spans;
+pub use document::{Document, LineEnding};
 pub use ellipsis::replace_ellipsis;
-pub use fences::{attach_orphan_specifiers, compress_fences};
-pub use footnotes::convert_footnotes;
-pub use headings::convert_setext_headings;
+pub use error::Error;
+pub use extract::{TextBlock, TextBlockKind, extract_text};
+pub use fences::{FenceState, attach_orphan_specifiers, compress_fences, is_fence};
+pub use footnotes::{compact_footnote_refs, convert_footnotes, footnote_tooltips};
+pub use hard_breaks::{HardBreakStyle, normalize_hard_breaks};
+pub use headings::{convert_emphasis_headings, convert_setext_headings};
+#[cfg(feature = "html")]
 pub use html::convert_html_tables;
 pub use io::{rewrite, rewrite_no_wrap};
 pub use lists::renumber_lists;
-pub use process::{Options, process_stream, process_stream_no_wrap, process_stream_opts};
-pub use table::{reflow_table, split_cells};
-pub use wrap::{Token, is_fence, tokenize_markdown, wrap_text};
+pub use process::{
+    LineTransform,
+    Options,
+    OptionsWarning,
+    Pipeline,
+    TransformRegistry,
+    process_str,
+    process_str_to,
+    process_stream,
+    process_stream_cow,
+    process_stream_no_wrap,
+    process_stream_opts,
+    process_stream_opts_cow,
+    process_stream_opts_with_edits,
+    reprocess_range,
+    try_process_stream,
+    try_process_stream_opts,
+};
+pub use rst::convert_rst_tables;
+pub use sentence_spacing::{SentenceSpacing, normalize_sentence_spacing};
+pub use table::{TableStyle, reflow_table, reflow_table_with_style, split_cells, try_reflow_table};
+pub use wikimarkup::convert_wiki_tables;
+pub use wrap::{
+    CjkWrap,
+    MarkdownTokens,
+    Token,
+    markdown_tokens,
+    tokenize_markdown,
+    wrap_text,
+    wrap_text_cjk,
+};
@@ -0,0 +1,237 @@
+//! Spacing between list markers and item text.
+//!
+//! markdownlint's MD030 rule expects a fixed number of spaces after each
+//! bullet (`-`, `*`, `+`) and ordered (`1.`, `1)`) marker. [`space_list_markers`]
+//! rewrites the gap after each marker to that width, adds the space missing
+//! from items such as `1.item`, and moves the item's continuation lines and
+//! nested blocks by the same amount so they stay inside the item. Gaps of
+//! five or more spaces start indented code and are left alone.
+
+use regex::Regex;
+
+use crate::{breaks::THEMATIC_BREAK_RE, fences::FenceState};
+
+static ITEM_RE: std::sync::LazyLock<Regex> = lazy_regex!(
+    r"^( *)([-*+]|[0-9]{1,9}[.)])( *)(.*)$",
+    "list marker pattern should compile",
+);
+
+/// The parts of a line that may be a list item.
+struct ItemLine<'a> {
+    indent: usize,
+    marker: &'a str,
+    gap: usize,
+    rest: &'a str,
+}
+
+impl<'a> ItemLine<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        if THEMATIC_BREAK_RE.is_match(line.trim_end()) {
+            return None;
+        }
+        let cap = ITEM_RE.captures(line)?;
+        Some(Self {
+            indent: cap.get(1)?.len(),
+            marker: cap.get(2)?.as_str(),
+            gap: cap.get(3)?.len(),
+            rest: cap.get(4)?.as_str(),
+        })
+    }
+
+    /// Returns `true` for an item written without the space after its
+    /// marker, such as `1.item` or `-item`. Only letters may follow, and
+    /// only `-` among the bullets, so `*emphasis*`, `+1`, and `1.5` stay
+    /// prose.
+    fn lacks_space(&self) -> bool {
+        self.gap == 0
+            && !self.marker.starts_with(['*', '+'])
+            && self.rest.chars().next().is_some_and(char::is_alphabetic)
+    }
+
+    /// Returns the gap this item's text should start after, or `None` when
+    /// the line is not an item this module rewrites.
+    fn new_gap(&self, spacing: usize, may_start_item: bool) -> Option<usize> {
+        match self.gap {
+            0 if may_start_item && self.lacks_space() => Some(spacing),
+            _ if self.rest.is_empty() => Some(self.gap),
+            1..=4 => Some(spacing),
+            0 => None,
+            _ => Some(self.gap),
+        }
+    }
+
+    /// Returns how far past the marker's indent the item's text starts with
+    /// `gap`: one column past the marker when the gap opens indented code or
+    /// the item is empty.
+    fn text_offset(&self, gap: usize) -> usize {
+        let gap = if gap > 4 || self.rest.is_empty() {
+            1
+        } else {
+            gap
+        };
+        self.marker.len() + gap
+    }
+}
+
+/// An open list item: where its text started and how far it moves.
+struct OpenItem {
+    old_col: usize,
+    shift: isize,
+}
+
+/// Rewrites the gap after every list marker to `spacing` spaces, moving the
+/// lines inside each item along with it.
+///
+/// A marker missing its space only starts an item at the start of the
+/// document, after a blank line, or straight after another item. Items
+/// inside fenced code are left alone, and markers indented with tabs are not
+/// recognised.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::list_markers::space_list_markers;
+///
+/// let lines: Vec<String> = ["1.   First", "     more", "", "-item"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     space_list_markers(&lines, 1),
+///     ["1. First", "   more", "", "- item"]
+/// );
+/// ```
+#[must_use]
+pub fn space_list_markers(lines: &[String], spacing: usize) -> Vec<String> {
+    space_renumbered_list_markers(lines, lines, spacing)
+}
+
+/// Rewrites list marker gaps like [`space_list_markers`] in `renumbered`,
+/// the output of [`renumber_lists`](crate::renumber_lists) for `original`.
+///
+/// Renumbering can change how wide a marker is, as when `10.` becomes `1.`,
+/// without moving the item's other lines. Reading where each item's text
+/// started from `original` lets those lines follow the new marker width as
+/// well as the new gap. Both slices must have the same number of lines.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{list_markers::space_renumbered_list_markers, renumber_lists};
+///
+/// let original: Vec<String> = ["10. a", "    more"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let renumbered = renumber_lists(&original);
+/// assert_eq!(renumbered, ["1. a", "    more"]);
+/// assert_eq!(
+///     space_renumbered_list_markers(&original, &renumbered, 1),
+///     ["1. a", "   more"]
+/// );
+/// ```
+#[must_use]
+pub fn space_renumbered_list_markers(
+    original: &[String],
+    renumbered: &[String],
+    spacing: usize,
+) -> Vec<String> {
+    debug_assert_eq!(original.len(), renumbered.len());
+    let mut fences = FenceState::default();
+    let mut stack: Vec<OpenItem> = Vec::new();
+    let mut out = Vec::with_capacity(renumbered.len());
+    let mut prev_blank = true;
+    let mut prev_item = false;
+    for (before, line) in original.iter().zip(renumbered) {
+        let fence = fences.observe_source_line(line);
+        if line.trim().is_empty() {
+            out.push(line.clone());
+            prev_blank = true;
+            prev_item = false;
+            continue;
+        }
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        let item = ItemLine::parse(line).filter(|_| !fence.is_code());
+        let new_gap = item
+            .as_ref()
+            .and_then(|item| item.new_gap(spacing, prev_blank || prev_item));
+        // Only paragraph text may continue an item lazily, below its indent.
+        if prev_blank || new_gap.is_some() || fence.is_code() {
+            while stack.last().is_some_and(|item| item.old_col > indent) {
+                stack.pop();
+            }
+        }
+        let shift = stack
+            .iter()
+            .rev()
+            .find(|item| item.old_col <= indent)
+            .map_or(0, |item| item.shift);
+        let moved = indent.saturating_add_signed(shift);
+        if let (Some(item), Some(gap)) = (item, new_gap) {
+            let old_col = ItemLine::parse(before)
+                .map_or(item.indent + item.text_offset(item.gap), |old| {
+                    old.indent + old.text_offset(old.gap)
+                });
+            let new_col = moved + item.text_offset(gap);
+            out.push(format!(
+                "{}{}{}{}",
+                " ".repeat(moved),
+                item.marker,
+                " ".repeat(gap),
+                item.rest
+            ));
+            stack.push(OpenItem {
+                old_col,
+                shift: new_col.cast_signed() - old_col.cast_signed(),
+            });
+            prev_item = true;
+        } else {
+            out.push(format!("{}{}", " ".repeat(moved), &line[indent..]));
+            prev_item = false;
+        }
+        prev_blank = false;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for list marker spacing.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::wide_gap("-   a\n*  b\n+ c", 1, "- a\n* b\n+ c")]
+    #[case::missing_space("1.item\n2)item\n-item", 1, "1. item\n2) item\n- item")]
+    #[case::wider_spacing("1. a\n   more", 2, "1.  a\n    more")]
+    #[case::nested("1.  a\n\n    -   b\n\n        deep", 1, "1. a\n\n   - b\n\n     deep")]
+    #[case::indented_code("1.      code\n\n        more", 1, "1.      code\n\n        more")]
+    #[case::empty_item("-\n  text", 1, "-\n  text")]
+    #[case::after_list("1.  a\n\ntext", 1, "1. a\n\ntext")]
+    #[case::fence_after_item("-  a\n```\n   code\n```", 1, "- a\n```\n   code\n```")]
+    fn spaces_items(#[case] input: &str, #[case] spacing: usize, #[case] expected: &str) {
+        assert_eq!(space_list_markers(&lines(input), spacing), lines(expected));
+    }
+
+    #[rstest]
+    #[case::emphasis("*emphasis* here")]
+    #[case::decimal("1.5 litres")]
+    #[case::mid_paragraph("Some text\n-item")]
+    #[case::thematic_break("* * *")]
+    #[case::fenced("```\n1.   a\n```")]
+    fn leaves_prose_alone(#[case] input: &str) {
+        assert_eq!(space_list_markers(&lines(input), 1), lines(input));
+    }
+
+    #[test]
+    fn moves_fenced_code_inside_items() {
+        let input = lines("-   a\n\n    ```\n    code\n    ```");
+        assert_eq!(
+            space_list_markers(&input, 1),
+            lines("- a\n\n  ```\n  code\n  ```")
+        );
+    }
+}
@@ -1,14 +1,32 @@
 //! Ordered list renumbering utilities.
+//!
+//! A list ends, and the next ordered list starts again at 1, when a blank
+//! line is followed by a paragraph, bullet item, or blockquote indented no
+//! deeper than the list marker, or when a bullet item or blockquote at that
+//! indent interrupts it directly. Lines indented past the marker continue the
+//! current item, and a line straight after an item is a lazy continuation of
+//! it. Tables and fenced code blocks sit inside the list's flow and leave its
+//! count alone. [`ListContinuation`]
+//! lets callers keep counting across such separating blocks instead.
 
 use std::collections::HashMap;
 
 use regex::Regex;
 use tracing::debug;
 
-use crate::{breaks::THEMATIC_BREAK_RE, wrap::FenceTracker};
-
-/// Characters that mark formatted text at the start of a line.
-const FORMATTING_CHARS: [char; 3] = ['*', '_', '`'];
+use crate::{breaks::THEMATIC_BREAK_RE, fences::FenceState, renumber_comments::note_old_number};
+
+/// How ordered lists separated by other blocks are numbered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListContinuation {
+    /// Each list starts again at 1.
+    #[default]
+    Restart,
+    /// A list separated from the previous one at the same indent by
+    /// paragraphs or other blocks continues its count. Headings and thematic
+    /// breaks still restart numbering.
+    Continue,
+}
 
 // Lines starting with optional indentation followed by '#' characters denote
 // Markdown ATX headings. A space or end of line must follow the hashes.
@@ -17,17 +35,19 @@ static HEADING_RE: std::sync::LazyLock<Regex> = lazy_regex!(
     "ATX heading prefix pattern should compile",
 );
 
-fn parse_numbered(line: &str) -> Option<(usize, &str, &str, &str)> {
+fn parse_numbered(line: &str) -> Option<(usize, &str, usize, &str, &str)> {
     static NUMBERED_RE: std::sync::LazyLock<Regex> = lazy_regex!(
-        r"^(\s*)(?:[1-9][0-9]*)\.(\s+)(.*)",
+        r"^(\s*)([1-9][0-9]*)\.(\s+)(.*)",
         "numbered list item pattern should compile",
     );
     let cap = NUMBERED_RE.captures(line)?;
     let indent_str = cap.get(1)?.as_str();
     let indent = indent_len(indent_str);
-    let sep = cap.get(2)?.as_str();
-    let rest = cap.get(3)?.as_str();
-    Some((indent, indent_str, sep, rest))
+    // Numbers too long for `usize` are not worth recording; treat them as 0.
+    let number = cap.get(2)?.as_str().parse().unwrap_or(0);
+    let sep = cap.get(3)?.as_str();
+    let rest = cap.get(4)?.as_str();
+    Some((indent, indent_str, number, sep, rest))
 }
 
 /// Remove counters for indents deeper than the given level.
@@ -54,14 +74,15 @@ fn indent_len(indent: &str) -> usize {
         .fold(0, |acc, ch| acc + if ch == '\t' { 4 } else { 1 })
 }
 
-fn is_plain_paragraph_line(line: &str) -> bool {
-    matches!(
-        line.trim_start()
-            .trim_start_matches(|c: char| FORMATTING_CHARS.contains(&c))
-            .chars()
-            .next(),
-        Some(c) if c.is_alphanumeric()
-    )
+/// Returns `true` for lines that interrupt a list item without a blank line:
+/// bullet items and blockquotes.
+fn interrupts_item(line: &str) -> bool {
+    let rest = line.trim_start();
+    if rest.starts_with('>') {
+        return true;
+    }
+    let mut chars = rest.chars();
+    matches!(chars.next(), Some('-' | '*' | '+')) && chars.next().is_none_or(char::is_whitespace)
 }
 
 #[derive(Default)]
@@ -101,30 +122,95 @@ impl ListState {
         current
     }
 
-    fn handle_paragraph_restart(&mut self, indent: usize, line: &str, prev_blank: bool) -> bool {
-        let inclusive = prev_blank
+    /// Ends the lists that a non-list line at `indent` closes, returning
+    /// `true` when a list at that indent ended.
+    fn handle_block_boundary(&mut self, indent: usize, line: &str, prev_blank: bool) -> bool {
+        let separates = prev_blank && !line.trim_start().starts_with('|');
+        let ends_list = (separates || interrupts_item(line))
             && self
                 .indent_stack
                 .last()
-                .is_some_and(|&depth| indent <= depth && is_plain_paragraph_line(line));
-        if inclusive {
+                .is_some_and(|&depth| indent <= depth);
+        if ends_list {
             self.prune_deeper(indent, true);
         }
-        inclusive
+        ends_list
     }
 }
 
 /// Renumber ordered Markdown list items across the given lines.
 /// - Preserve code fences; do not renumber inside them.
 /// - Reset numbering on headings and thematic breaks.
-/// - Restart numbering after a blank line followed by a plain paragraph at the same or a shallower
-///   indent.
+/// - Restart numbering after a blank line followed by a paragraph, bullet item, or blockquote at
+///   the same or a shallower indent, or directly after a bullet item or blockquote at that indent.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::renumber_lists;
+///
+/// let lines: Vec<String> = ["1. a", "3. b", "", "> note", "", "5. c"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     renumber_lists(&lines),
+///     ["1. a", "2. b", "", "> note", "", "1. c"]
+/// );
+/// ```
 #[must_use]
 pub fn renumber_lists(lines: &[String]) -> Vec<String> {
+    renumber_lists_with(lines, ListContinuation::Restart)
+}
+
+/// Renumber ordered list items like [`renumber_lists`], choosing how lists
+/// separated by other blocks are numbered.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::lists::{ListContinuation, renumber_lists_with};
+///
+/// let lines: Vec<String> = ["1. a", "", "Aside.", "", "1. b"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let out = renumber_lists_with(&lines, ListContinuation::Continue);
+/// assert_eq!(out, ["1. a", "", "Aside.", "", "2. b"]);
+/// ```
+#[must_use]
+pub fn renumber_lists_with(lines: &[String], continuation: ListContinuation) -> Vec<String> {
+    renumber(lines, continuation, false)
+}
+
+/// Renumber ordered list items like [`renumber_lists_with`], appending
+/// `<!-- was N -->` to each item whose number changes.
+///
+/// Remove the comments later with
+/// [`strip_renumber_comments`](crate::renumber_comments::strip_renumber_comments).
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::lists::{ListContinuation, renumber_lists_with_comments};
+///
+/// let lines: Vec<String> = ["1. a", "7. b"].into_iter().map(str::to_string).collect();
+/// let out = renumber_lists_with_comments(&lines, ListContinuation::Restart);
+/// assert_eq!(out, ["1. a", "2. b <!-- was 7 -->"]);
+/// ```
+#[must_use]
+pub fn renumber_lists_with_comments(
+    lines: &[String],
+    continuation: ListContinuation,
+) -> Vec<String> {
+    renumber(lines, continuation, true)
+}
+
+fn renumber(lines: &[String], continuation: ListContinuation, comments: bool) -> Vec<String> {
     let mut out = Vec::with_capacity(lines.len());
     let mut state = ListState::default();
     // Track fenced code blocks consistently across list processing.
-    let mut fences = FenceTracker::default();
+    let mut fences = FenceState::default();
     #[allow(clippy::unnecessary_map_or)]
     let mut prev_blank = lines.first().map_or(true, |l| l.trim().is_empty());
 
@@ -145,9 +231,14 @@ pub fn renumber_lists(lines: &[String]) -> Vec<String> {
             prev_blank = true;
             continue;
         }
-        if let Some((indent, indent_str, sep, rest)) = parse_numbered(line) {
+        if let Some((indent, indent_str, old, sep, rest)) = parse_numbered(line) {
             let current = state.next_number(indent);
-            out.push(format!("{indent_str}{current}.{sep}{rest}"));
+            let item = format!("{indent_str}{current}.{sep}{rest}");
+            out.push(if comments {
+                note_old_number(&item, old, current)
+            } else {
+                item
+            });
             prev_blank = false;
             continue;
         }
@@ -163,8 +254,9 @@ pub fn renumber_lists(lines: &[String]) -> Vec<String> {
             prev_blank = false;
             continue;
         }
-        let did_inclusive = state.handle_paragraph_restart(indent, line, prev_blank);
-        if !did_inclusive {
+        if continuation == ListContinuation::Continue
+            || !state.handle_block_boundary(indent, line, prev_blank)
+        {
             state.prune_deeper(indent, false);
         }
         out.push(line.clone());
@@ -185,13 +277,13 @@ mod tests {
     #[test]
     fn parse_numbered_parts() {
         let line = "  12. item";
-        assert_eq!(parse_numbered(line), Some((2, "  ", " ", "item")));
+        assert_eq!(parse_numbered(line), Some((2, "  ", 12, " ", "item")));
     }
 
     #[test]
     fn parse_numbered_with_tab() {
         let line = "	1.	foo";
-        assert_eq!(parse_numbered(line), Some((4, "	", "	", "foo")));
+        assert_eq!(parse_numbered(line), Some((4, "	", 1, "	", "foo")));
     }
 
     #[test]
@@ -5,148 +5,104 @@
 //! rewritten in place. Without paths the tool reads from standard input and
 //! prints results to stdout while preserving the input order.
 
+#[path = "cli/anchor_map.rs"]
+mod anchor_map;
+#[path = "cli/args.rs"]
+mod args;
+#[path = "cli/batch.rs"]
+mod batch;
+#[path = "cli/batch_output.rs"]
+mod batch_output;
+#[path = "cli/block_list.rs"]
+mod block_list;
+#[path = "cli/bulk.rs"]
+mod bulk;
+#[path = "cli/config.rs"]
+mod config;
+#[path = "cli/daemon.rs"]
+mod daemon;
+#[path = "cli/diff.rs"]
+mod diff;
+#[path = "cli/discover.rs"]
+mod discover;
+#[path = "cli/exit_status.rs"]
+mod exit_status;
+#[path = "cli/format_opts.rs"]
+mod format_opts;
+#[path = "cli/format_values.rs"]
+mod format_values;
 /// Detects and splits leading YAML frontmatter for CLI processing so command
 /// handlers can preserve the prefix while applying transforms to the Markdown
 /// body.
 #[path = "frontmatter.rs"]
 mod frontmatter;
-
-use std::{
-    borrow::Cow,
-    fs,
-    io::{self, Read},
-    path::{Path, PathBuf},
+#[path = "cli/git.rs"]
+mod git;
+#[path = "cli/help_markdown.rs"]
+mod help_markdown;
+#[path = "cli/jobs.rs"]
+mod jobs;
+#[path = "cli/layout.rs"]
+mod layout;
+#[path = "cli/lint.rs"]
+mod lint;
+#[path = "cli/logging.rs"]
+mod logging;
+#[path = "cli/output_dir.rs"]
+mod output_dir;
+#[path = "cli/panic_isolation.rs"]
+mod panic_isolation;
+#[path = "cli/pipeline.rs"]
+mod pipeline;
+#[path = "cli/plugins.rs"]
+mod plugins;
+#[path = "cli/presets.rs"]
+mod presets;
+#[path = "cli/progress.rs"]
+mod progress;
+#[path = "cli/report.rs"]
+mod report;
+#[cfg(feature = "check-rust-fences")]
+#[path = "cli/rust_fences.rs"]
+mod rust_fences;
+#[path = "cli/stdin.rs"]
+mod stdin;
+#[path = "cli/suggest_width.rs"]
+mod suggest_width;
+#[path = "cli/summary.rs"]
+mod summary;
+#[path = "cli/table.rs"]
+mod table;
+#[cfg(test)]
+#[path = "test_support.rs"]
+mod test_support;
+#[path = "cli/titles.rs"]
+mod titles;
+
+use std::{path::Path, process::ExitCode};
+
+use clap::{CommandFactory, Parser};
+
+use crate::{
+    args::{Command, FmtArgs},
+    config::ConfigResolver,
+    exit_status::Status,
 };
 
-use anyhow::Context;
-use clap::Parser;
-use mdtablefix::{Options, format_breaks, process::process_stream_inner, renumber_lists};
-use rayon::prelude::*;
-
-use crate::frontmatter::split_leading_yaml_frontmatter;
-
 #[derive(Parser)]
-#[command(version, about = "Reflow broken markdown tables")]
+#[command(
+    version,
+    about = "Reflow broken markdown tables",
+    args_conflicts_with_subcommands = true
+)]
 struct Cli {
-    /// Rewrite files in place
-    #[arg(long = "in-place", requires = "files")]
-    in_place: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
     #[command(flatten)]
-    opts: FormatOpts,
-    /// Markdown files to fix
-    files: Vec<PathBuf>,
-}
-
-#[derive(clap::Args, Clone, Copy)]
-#[expect(
-    clippy::struct_excessive_bools,
-    reason = "CLI exposes independent flags via separate switches"
-)]
-struct FormatOpts {
-    /// Wrap paragraphs and list items to 80 columns
-    #[arg(long = "wrap")]
-    wrap: bool,
-    /// Renumber ordered list items
-    #[arg(long = "renumber")]
-    renumber: bool,
-    /// Reformat thematic breaks as underscores
-    #[arg(long = "breaks")]
-    breaks: bool,
-    /// Replace "..." with the ellipsis character
-    #[arg(long = "ellipsis")]
-    ellipsis: bool,
-    /// Normalise fence delimiters to three backticks
-    #[arg(long = "fences")]
-    fences: bool,
-    /// Convert bare numeric references and the final numbered list to
-    /// Markdown footnote links
-    #[arg(long = "footnotes")]
-    footnotes: bool,
-    /// Fix emphasis markers adjacent to inline code
-    #[arg(long = "code-emphasis")]
-    code_emphasis: bool,
-    /// Convert Setext-style headings to hash-prefixed headings
-    #[arg(long = "headings")]
-    headings: bool,
-}
-
-impl From<FormatOpts> for Options {
-    fn from(opts: FormatOpts) -> Self {
-        Self {
-            wrap: opts.wrap,
-            ellipsis: opts.ellipsis,
-            fences: opts.fences,
-            footnotes: opts.footnotes,
-            code_emphasis: opts.code_emphasis,
-            headings: opts.headings,
-        }
-    }
-}
-
-fn process_lines(lines: &[String], opts: FormatOpts) -> Vec<String> {
-    // Split off leading YAML frontmatter to preserve it from all transforms
-    let (frontmatter_prefix, body) = split_leading_yaml_frontmatter(lines);
-
-    // Use process_stream_inner directly since we've already split frontmatter
-    let mut out = process_stream_inner(body, opts.into());
-    if opts.renumber {
-        out = renumber_lists(&out);
-    }
-    if opts.breaks {
-        out = format_breaks(&out)
-            .into_iter()
-            .map(Cow::into_owned)
-            .collect();
-    }
-
-    // Prepend the preserved frontmatter prefix
-    let mut result = frontmatter_prefix.to_vec();
-    result.extend(out);
-    result
-}
-
-fn handle_file(path: &Path, in_place: bool, opts: FormatOpts) -> anyhow::Result<Option<String>> {
-    let content =
-        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
-    let lines: Vec<String> = content.lines().map(str::to_string).collect();
-    let fixed = process_lines(&lines, opts);
-    if in_place {
-        // Preserve compatibility with the `rewrite` helper by always ending files with a
-        // trailing newline when content exists. This mirrors typical Unix tool behaviour
-        // and avoids spurious diffs when rewriting in place.
-        let output = if fixed.is_empty() {
-            String::new()
-        } else {
-            fixed.join("\n") + "\n"
-        };
-        fs::write(path, output).with_context(|| format!("writing {}", path.display()))?;
-        Ok(None)
-    } else {
-        Ok(Some(fixed.join("\n")))
-    }
-}
-
-fn report_results<T, F>(results: Vec<anyhow::Result<T>>, mut on_ok: F) -> anyhow::Result<()>
-where
-    F: FnMut(T),
-{
-    let mut first_err: Option<anyhow::Error> = None;
-    for res in results {
-        match res {
-            Ok(val) => on_ok(val),
-            Err(e) => {
-                eprintln!("{e}");
-                if first_err.is_none() {
-                    first_err = Some(e);
-                }
-            }
-        }
-    }
-    if let Some(err) = first_err {
-        Err(err)
-    } else {
-        Ok(())
-    }
+    args: FmtArgs,
+    /// Print the full command-line and config file reference as Markdown
+    #[arg(long = "help-markdown", exclusive = true)]
+    help_markdown: bool,
 }
 
 /// Entry point for the command-line tool that reflows broken markdown tables.
@@ -157,8 +113,9 @@ where
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` if all operations complete successfully; otherwise, returns an error if
-/// argument validation or file processing fails.
+/// Returns exit status 0 if all operations complete successfully, 1 when `--check` finds files
+/// that would be reformatted or `--in-place` rewrites them, 2 for invalid arguments, and 3 when
+/// reading, writing, or parsing an input fails. See [`exit_status`] for the full contract.
 ///
 /// # Examples
 ///
@@ -169,40 +126,104 @@ where
 /// # Fix tables in place
 /// mdtablefix --in-place myfile.md
 ///
+/// # Fix every Markdown file below a directory
+/// mdtablefix --in-place docs/
+///
+/// # Write fixed copies below build/, leaving docs/ untouched
+/// mdtablefix --output-dir build docs/
+///
+/// # List files that need fixing without touching them
+/// mdtablefix --check docs/*.md
+///
+/// # Print the full reference as Markdown
+/// mdtablefix --help-markdown > docs/reference.md
+///
+/// # Print a diagnostic for each change formatting would make
+/// mdtablefix lint docs/
+///
+/// # Pass the files that need fixing to another tool
+/// mdtablefix --list-changed docs/ | xargs markdownlint
+///
+/// # Review the intended changes as a patch
+/// mdtablefix --diff docs/*.md
+///
+/// # Suggest wrap and table widths that fit existing documents
+/// mdtablefix suggest-width docs/
+///
+/// # Reflow one table from an editor selection
+/// pbpaste | mdtablefix table -
+///
 /// # Fix tables from standard input
 /// cat myfile.md | mdtablefix
 /// ```
-fn main() -> anyhow::Result<()> {
+fn main() -> ExitCode {
     let cli = Cli::parse();
+    let args = match &cli.command {
+        Some(Command::Fmt(args) | Command::Check(args) | Command::Lint(args)) => args,
+        _ => &cli.args,
+    };
+    logging::init(args.verbose, args.quiet);
+    let status = run(cli).unwrap_or_else(|err| {
+        eprintln!("Error: {err:?}");
+        Status::of_error(&err)
+    });
+    status.into()
+}
 
-    if cli.files.is_empty() {
-        let mut input = String::new();
-        io::stdin().read_to_string(&mut input)?;
-        let lines: Vec<String> = input.lines().map(str::to_string).collect();
-        let fixed = process_lines(&lines, cli.opts);
-        println!("{}", fixed.join("\n"));
-        return Ok(());
+/// Runs the command described by `cli`.
+fn run(cli: Cli) -> anyhow::Result<Status> {
+    if cli.help_markdown {
+        print!("{}", help_markdown::render(&Cli::command()));
+        return Ok(Status::Clean);
+    }
+    let args = match cli.command {
+        None => cli.args,
+        Some(Command::Fmt(args)) => *args,
+        Some(Command::Check(args)) => args.into_check()?,
+        Some(Command::Lint(args)) => args.into_lint()?,
+        Some(Command::SuggestWidth(args)) => return suggest_width::run(&args),
+        Some(Command::Table(args)) => return table::run(&args),
+    };
+    format(&args)
+}
+
+/// Formats, checks, or lints the inputs named by `cli`.
+fn format(cli: &FmtArgs) -> anyhow::Result<Status> {
+    let configs = ConfigResolver::new(&cli.opts, cli.preset.as_deref(), cli.config.as_deref())?;
+
+    if let Some(socket) = &cli.daemon {
+        daemon::run(socket, &configs)?;
+        return Ok(Status::Clean);
+    }
+
+    let files = cli.input_files()?;
+    let read_stdin =
+        cli.files.is_empty() && cli.files_from.is_none() && cli.git_selection().is_none();
+    #[cfg(feature = "check-rust-fences")]
+    if cli.check_rust_fences {
+        return rust_fences::run(&files, read_stdin);
     }
 
-    if cli.in_place {
-        let results: Vec<anyhow::Result<()>> = cli
-            .files
-            .par_iter()
-            .map(|p| handle_file(p, true, cli.opts).map(|_| ()))
-            .collect();
-        report_results(results, |()| {})?;
-    } else {
-        let results: Vec<anyhow::Result<Option<String>>> = cli
-            .files
-            .par_iter()
-            .map(|p| handle_file(p, false, cli.opts))
-            .collect();
-        report_results(results, |maybe_out| {
-            if let Some(out) = maybe_out {
-                println!("{out}");
-            }
-        })?;
+    if cli.list_blocks {
+        let label = cli
+            .stdin_filepath
+            .as_deref()
+            .unwrap_or(Path::new("<stdin>"));
+        block_list::run(&files, read_stdin.then_some(label))?;
+        return Ok(Status::Clean);
+    }
+
+    if cli.check_titles {
+        return titles::run(&files, read_stdin, cli.stdin_filepath.as_deref());
+    }
+
+    if read_stdin {
+        let opts = match &cli.stdin_filepath {
+            Some(path) => configs.for_file(path)?,
+            None => configs.for_working_dir()?,
+        };
+        return stdin::run(cli, &opts);
     }
 
-    Ok(())
+    batch::run(cli, &configs, &files)
 }
@@ -0,0 +1,224 @@
+//! Text that wrapping and table parsing must keep whole.
+//!
+//! Template engines read placeholders such as `{{ user.name }}`,
+//! `<%= total %>`, or `${HOME}` from the rendered page, so a line break or a
+//! cell split inside one breaks the template. [`OpaqueTokens`] holds the
+//! patterns that match such placeholders. While the wrap and table stages
+//! run, every match is swapped for a placeholder of the same display width
+//! made of private-use characters, which contains no space or pipe to break
+//! at, and the original text is put back afterwards.
+
+use std::sync::Arc;
+
+use regex::Regex;
+use unicode_width::UnicodeWidthStr;
+
+/// The first character of each placeholder, offset by the token's index,
+/// comes from Supplementary Private Use Area-A.
+const INDEX_BASE: u32 = 0xf_0000;
+
+/// The number of tokens a placeholder can number.
+const MAX_TOKENS: usize = 0xfffe;
+
+/// Pads a placeholder to the token's display width.
+const FILLER: char = '\u{E000}';
+
+/// Patterns matching text that must not be split by wrapping or treated as
+/// table cell boundaries.
+///
+/// Each pattern is matched against one line at a time, so only placeholders
+/// written on a single line are protected.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{Options, opaque::OpaqueTokens, process_stream_opts};
+///
+/// let opts = Options {
+///     wrap: true,
+///     wrap_width: 20,
+///     opaque_tokens: OpaqueTokens::new([r"\{\{.*?\}\}"]).unwrap(),
+///     ..Options::default()
+/// };
+/// let lines = vec!["Hello there {{ user.first_name }}, welcome.".to_string()];
+/// assert_eq!(
+///     process_stream_opts(&lines, opts),
+///     ["Hello there", "{{ user.first_name }},", "welcome."]
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OpaqueTokens(Arc<[Regex]>);
+
+impl OpaqueTokens {
+    /// Compiles `patterns`, each a regular expression in the syntax of the
+    /// `regex` crate.
+    ///
+    /// # Errors
+    /// Returns the first pattern that fails to compile.
+    pub fn new<I, S>(patterns: I) -> Result<Self, regex::Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        patterns
+            .into_iter()
+            .map(|pattern| Regex::new(pattern.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|patterns| Self(patterns.into()))
+    }
+
+    /// Returns `true` when there are no patterns.
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+    /// Replaces every match in `lines` with a placeholder, or returns `None`
+    /// when nothing matches.
+    pub(crate) fn mask(&self, lines: &[String]) -> Option<OpaqueMask> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut tokens: Vec<String> = Vec::new();
+        let masked = lines
+            .iter()
+            .map(|line| {
+                let mut out = String::with_capacity(line.len());
+                let mut last = 0;
+                for found in self.matches(line) {
+                    if tokens.len() == MAX_TOKENS {
+                        break;
+                    }
+                    out.push_str(&line[last..found.start]);
+                    out.push_str(&placeholder(tokens.len(), &line[found.clone()]));
+                    tokens.push(line[found.clone()].to_string());
+                    last = found.end;
+                }
+                out.push_str(&line[last..]);
+                out
+            })
+            .collect();
+        (!tokens.is_empty()).then_some(OpaqueMask { masked, tokens })
+    }
+
+    /// Returns the non-overlapping matches of every pattern in `line`, in
+    /// order, preferring the earliest and then the longest match.
+    fn matches(&self, line: &str) -> Vec<std::ops::Range<usize>> {
+        let mut found: Vec<_> = self
+            .0
+            .iter()
+            .flat_map(|pattern| pattern.find_iter(line).map(|m| m.range()))
+            .filter(|range| UnicodeWidthStr::width(&line[range.clone()]) > 0)
+            .collect();
+        found.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+        let mut end = 0;
+        found.retain(|range| {
+            let keep = range.start >= end;
+            if keep {
+                end = range.end;
+            }
+            keep
+        });
+        found
+    }
+}
+
+/// Serializes as the list of patterns, in the order they were compiled.
+#[cfg(feature = "serde")]
+impl serde::Serialize for OpaqueTokens {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(Regex::as_str))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OpaqueTokens {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let patterns = Vec::<String>::deserialize(deserializer)?;
+        Self::new(patterns).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Builds the placeholder for the token at `index`, as wide as `token`.
+fn placeholder(index: usize, token: &str) -> String {
+    let offset = u32::try_from(index).expect("token index is below MAX_TOKENS");
+    let first = char::from_u32(INDEX_BASE + offset).expect("index maps to a private-use character");
+    let width = UnicodeWidthStr::width(token);
+    std::iter::once(first)
+        .chain(std::iter::repeat_n(FILLER, width - 1))
+        .collect()
+}
+
+/// Lines with their opaque tokens swapped for placeholders.
+pub(crate) struct OpaqueMask {
+    masked: Vec<String>,
+    tokens: Vec<String>,
+}
+
+impl OpaqueMask {
+    /// Returns the masked lines.
+    pub(crate) fn lines(&self) -> &[String] { &self.masked }
+
+    /// Puts the tokens back into `lines`, the output of a transform run on
+    /// [`Self::lines`]. Returns `None` when a placeholder was lost, split, or
+    /// duplicated, so the caller can run the transform unmasked instead.
+    pub(crate) fn unmask(&self, mut lines: Vec<String>) -> Option<Vec<String>> {
+        for (index, token) in self.tokens.iter().enumerate() {
+            let placeholder = placeholder(index, token);
+            let mut seen = 0;
+            for line in &mut lines {
+                let count = line.matches(placeholder.as_str()).count();
+                if count > 0 {
+                    *line = line.replace(placeholder.as_str(), token);
+                    seen += count;
+                }
+            }
+            if seen != 1 {
+                return None;
+            }
+        }
+        Some(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for opaque token masking.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::jinja("a {{ b | c }} d", "a {{ b | c }} d")]
+    #[case::erb_and_shell("<%= x %> and ${HOME}", "<%= x %> and ${HOME}")]
+    fn masks_and_restores(#[case] input: &str, #[case] expected: &str) {
+        let tokens = OpaqueTokens::new([r"\{\{.*?\}\}", r"<%=?.*?%>", r"\$\{[^}]*\}"]).unwrap();
+        let mask = tokens.mask(&lines(input)).expect("tokens match");
+        let masked = &mask.lines()[0];
+        assert!(!masked.contains(['{', '|', '%']));
+        assert_eq!(
+            UnicodeWidthStr::width(masked.as_str()),
+            UnicodeWidthStr::width(input)
+        );
+        assert_eq!(mask.unmask(mask.lines().to_vec()), Some(lines(expected)));
+    }
+
+    #[test]
+    fn keeps_the_longest_of_overlapping_matches() {
+        let tokens = OpaqueTokens::new([r"\{\{", r"\{\{.*?\}\}"]).unwrap();
+        let mask = tokens.mask(&lines("{{ a }}")).unwrap();
+        assert_eq!(mask.tokens, ["{{ a }}"]);
+    }
+
+    #[test]
+    fn unmask_rejects_lost_placeholders() {
+        let tokens = OpaqueTokens::new([r"\$\{[^}]*\}"]).unwrap();
+        let mask = tokens.mask(&lines("${A}")).unwrap();
+        assert_eq!(mask.unmask(lines("gone")), None);
+    }
+
+    #[test]
+    fn no_patterns_mask_nothing() {
+        assert!(OpaqueTokens::default().mask(&lines("{{ a }}")).is_none());
+    }
+}
@@ -0,0 +1,182 @@
+//! Run user-supplied WebAssembly modules as pipeline stages.
+//!
+//! A plugin is a core WASM module that rewrites a whole document. It
+//! exports its linear memory as `memory` and two functions:
+//!
+//! - `alloc(len: i32) -> i32` returns a buffer of `len` bytes for the input.
+//! - `transform(ptr: i32, len: i32) -> i64` rewrites the document at `ptr` and returns its output
+//!   as `(out_ptr << 32) | out_len`.
+//!
+//! Documents cross the boundary as UTF-8 text with every line terminated by
+//! `\n`. Plugins run in an interpreter with no host functions, so a module
+//! that imports anything, including WASI, is rejected when it is loaded.
+//! Each call gets a fresh instance whose memory and instruction count are
+//! capped, so a plugin cannot read files, keep state between documents, or
+//! hang the formatter.
+//!
+//! This module is only available with the `wasm-plugins` feature.
+
+use std::{fmt, fs, io, path::Path, sync::Arc};
+
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Largest linear memory a plugin may grow to, in bytes.
+pub const MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// Instruction budget for one call to `transform`.
+pub const FUEL_LIMIT: u64 = 1_000_000_000;
+
+/// Errors raised while loading or running a [`WasmPlugin`].
+#[derive(Debug)]
+pub enum PluginError {
+    /// The module file could not be read.
+    Io(io::Error),
+    /// The module failed to compile, instantiate, or run.
+    Wasm(wasmi::Error),
+    /// The module does not follow the plugin interface.
+    Interface(String),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "reading plugin: {err}"),
+            Self::Wasm(err) => write!(f, "running plugin: {err}"),
+            Self::Interface(msg) => write!(f, "invalid plugin: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for PluginError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Wasm(err) => Some(err),
+            Self::Interface(_) => None,
+        }
+    }
+}
+
+impl From<wasmi::Error> for PluginError {
+    fn from(err: wasmi::Error) -> Self { Self::Wasm(err) }
+}
+
+/// A compiled plugin module.
+///
+/// Loading validates the module once; [`WasmPlugin::transform`] may then be
+/// called from several threads at once.
+///
+/// # Examples
+///
+/// ```no_run
+/// use mdtablefix::plugins::WasmPlugin;
+///
+/// let plugin = WasmPlugin::load("canonical-links.wasm".as_ref())?;
+/// let out = plugin.transform(&["See <http://wiki/a>".to_string()])?;
+/// # Ok::<(), mdtablefix::plugins::PluginError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct WasmPlugin {
+    engine: Engine,
+    module: Arc<Module>,
+}
+
+impl WasmPlugin {
+    /// Reads and compiles the module at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file cannot be read or does not hold a
+    /// valid plugin module.
+    pub fn load(path: &Path) -> Result<Self, PluginError> {
+        Self::from_bytes(&fs::read(path).map_err(PluginError::Io)?)
+    }
+
+    /// Compiles a plugin from WASM binary `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `bytes` is not a valid module, imports host
+    /// functions, or lacks the exports listed in the [module
+    /// documentation](self).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PluginError> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, bytes)?;
+        if let Some(import) = module.imports().next() {
+            return Err(PluginError::Interface(format!(
+                "imports `{}::{}`, but plugins may not import host functions",
+                import.module(),
+                import.name()
+            )));
+        }
+        for export in ["memory", "alloc", "transform"] {
+            if module.get_export(export).is_none() {
+                return Err(PluginError::Interface(format!("missing `{export}` export")));
+            }
+        }
+        Ok(Self {
+            engine,
+            module: Arc::new(module),
+        })
+    }
+
+    /// Passes `lines` through the plugin's `transform` export.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the plugin traps, exceeds its memory or
+    /// instruction budget, or returns a buffer that is out of bounds or not
+    /// UTF-8.
+    pub fn transform(&self, lines: &[String]) -> Result<Vec<String>, PluginError> {
+        let mut store = Store::new(
+            &self.engine,
+            StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build(),
+        );
+        store.limiter(|limits: &mut StoreLimits| limits);
+        store.set_fuel(FUEL_LIMIT).map_err(wasmi::Error::from)?;
+        let instance = Linker::new(&self.engine)
+            .instantiate(&mut store, &self.module)?
+            .start(&mut store)?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| PluginError::Interface("`memory` is not a memory".to_string()))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&store, "alloc")?;
+        let transform = instance.get_typed_func::<(i32, i32), i64>(&store, "transform")?;
+
+        let input: String = lines
+            .iter()
+            .flat_map(|line| [line.as_str(), "\n"])
+            .collect();
+        let len = wasm_len(input.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory
+            .write(&mut store, wasm_offset(ptr), input.as_bytes())
+            .map_err(|err| PluginError::Interface(format!("`alloc` returned {err}")))?;
+        let packed = transform.call(&mut store, (ptr, len))?;
+
+        #[expect(
+            clippy::cast_sign_loss,
+            clippy::cast_possible_truncation,
+            reason = "the result packs two unsigned 32-bit halves"
+        )]
+        let (out_ptr, out_len) = ((packed as u64 >> 32) as usize, packed as u32 as usize);
+        let output = memory
+            .data(&store)
+            .get(out_ptr..out_ptr.saturating_add(out_len))
+            .ok_or_else(|| PluginError::Interface("output is out of bounds".to_string()))?;
+        let output = std::str::from_utf8(output)
+            .map_err(|err| PluginError::Interface(format!("output is not UTF-8: {err}")))?;
+        Ok(output.split_terminator('\n').map(str::to_string).collect())
+    }
+}
+
+/// Converts a document length into a WASM `i32`.
+fn wasm_len(len: usize) -> Result<i32, PluginError> {
+    i32::try_from(len).map_err(|_| PluginError::Interface("document exceeds 2 GiB".to_string()))
+}
+
+/// Reinterprets a WASM pointer as an unsigned memory offset.
+#[expect(clippy::cast_sign_loss, reason = "WASM pointers are unsigned")]
+fn wasm_offset(ptr: i32) -> usize { ptr as u32 as usize }
@@ -1,60 +1,40 @@
 //! High-level Markdown stream processing.
 
+mod borrowed;
 mod buffer;
+mod checked;
+mod edits;
+mod incremental;
+mod options;
+mod pipeline;
+mod registry;
+mod stages;
+mod text;
+mod validate;
 
-use buffer::ProcessBuffer;
+use std::{convert::Infallible, time::Instant};
+
+pub use borrowed::{process_stream_cow, process_stream_opts_cow};
+pub use checked::{try_process_stream, try_process_stream_opts};
+pub use edits::process_stream_opts_with_edits;
+pub use incremental::{Reprocessed, block_boundaries, reprocess_range};
+pub use options::Options;
+pub use pipeline::Pipeline;
+use registry::run_pass;
+pub use registry::{BuiltinStage, LineTransform, TransformRegistry, UnsupportedStage};
+use stages::{STAGES, run_stage};
+pub use text::{process_str, process_str_to};
+use tracing::{Level, debug};
+pub use validate::OptionsWarning;
 
 use crate::{
-    ellipsis::replace_ellipsis,
-    fences::{attach_orphan_specifiers, compress_fences},
-    footnotes::convert_footnotes,
+    changes::{ChangeLog, Transform},
     frontmatter::split_leading_yaml_frontmatter,
-    html::convert_html_tables,
-    wrap::{FenceTracker, wrap_text},
 };
 
-/// Column width used when wrapping text.
+/// Default column width used when wrapping text.
 pub const WRAP_COLS: usize = 80;
 
-/// Processing options controlling the behaviour of [`process_stream_inner`].
-///
-/// # Examples
-///
-/// ```
-/// use mdtablefix::process::{Options, process_stream_opts};
-///
-/// let lines = vec!["example".to_string()];
-/// let opts = Options {
-///     wrap: false,
-///     ellipsis: false,
-///     fences: false,
-///     footnotes: false,
-///     code_emphasis: false,
-///     headings: false,
-/// };
-/// let out = process_stream_opts(&lines, opts);
-/// assert_eq!(out, vec!["example"]);
-/// ```
-#[expect(
-    clippy::struct_excessive_bools,
-    reason = "Options map directly to CLI flags"
-)]
-#[derive(Clone, Copy, Default)]
-pub struct Options {
-    /// Enable paragraph wrapping.
-    pub wrap: bool,
-    /// Replace `...` with `…`.
-    pub ellipsis: bool,
-    /// Normalise code block fences.
-    pub fences: bool,
-    /// Convert bare numeric references into GitHub-flavoured footnote links (default: `false`).
-    pub footnotes: bool,
-    /// Fix emphasis markers adjacent to inline code.
-    pub code_emphasis: bool,
-    /// Convert Setext-style headings into ATX (`#`) headings.
-    pub headings: bool,
-}
-
 /// Processes a stream of Markdown lines using the provided [`Options`].
 ///
 /// The function normalizes code fences, converts HTML tables, detects
@@ -64,7 +44,15 @@ pub struct Options {
 /// # Examples
 ///
 /// ```
-/// use mdtablefix::process::{Options, process_stream_inner};
+/// use mdtablefix::{
+///     blocks::BlockExclusions,
+///     footnotes::FootnotePlacement,
+///     opaque::OpaqueTokens,
+///     process::{Options, TransformRegistry, process_stream_inner},
+///     protect::BlockProtection,
+///     table::{TableOverflow, TableStyle},
+///     wrap::CjkWrap,
+/// };
 ///
 /// let lines = vec![
 ///     "| a | b |".to_string(),
@@ -75,11 +63,32 @@ pub struct Options {
 ///     &lines,
 ///     Options {
 ///         wrap: false,
+///         wrap_width: 80,
+///         rejoin: false,
 ///         ellipsis: false,
 ///         fences: false,
 ///         footnotes: false,
+///         footnote_placement: FootnotePlacement::Document,
+///         footnote_refs: None,
+///         renumber_comments: false,
 ///         code_emphasis: false,
+///         code_spans: false,
 ///         headings: false,
+///         emphasis_headings: None,
+///         wiki_tables: false,
+///         rst_tables: false,
+///         keep_clean_tables: false,
+///         table_style: TableStyle::Padded,
+///         max_table_width: None,
+///         table_overflow: TableOverflow::Keep,
+///         table_alignment: Vec::new(),
+///         cjk_wrap: CjkWrap::Cells,
+///         hard_breaks: None,
+///         sentence_spacing: None,
+///         block_exclusions: BlockExclusions::default(),
+///         opaque_tokens: OpaqueTokens::default(),
+///         protect: BlockProtection::default(),
+///         transforms: TransformRegistry::default(),
 ///     },
 /// );
 /// assert_eq!(
@@ -92,62 +101,161 @@ pub struct Options {
 /// );
 /// ```
 #[must_use]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "the public API takes `Options` by value, as callers build it inline"
+)]
 pub fn process_stream_inner(lines: &[String], opts: Options) -> Vec<String> {
-    let lines = if opts.fences {
-        let tmp = compress_fences(lines);
-        attach_orphan_specifiers(&tmp)
-    } else {
-        lines.to_vec()
-    };
-
-    let pre = convert_html_tables(&lines);
-
-    let mut state = ProcessBuffer::new(opts.ellipsis);
-    // Track fences so subsequent logic respects shared semantics.
-    let mut fence_tracker = FenceTracker::default();
+    run_pipeline(lines, &STAGES, &opts, None, no_hook).unwrap_or_else(|never| match never {})
+}
 
-    for line in pre {
-        let fence = fence_tracker.observe_source_line(&line);
-        if state.handle_fence_line(&line, fence.is_fence_marker) {
-            continue;
-        }
+/// Runs [`process_stream_inner`] while recording each stage's edits.
+///
+/// Every transform that rewrites at least one line appends its hunks to
+/// `changes`, which lets callers audit what the pipeline did.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{
+///     changes::{ChangeLog, Transform},
+///     process::{Options, process_stream_inner_with_changes},
+/// };
+///
+/// let lines = vec!["|a|b|".to_string(), "|1|2|".to_string()];
+/// let mut changes = ChangeLog::default();
+/// let out = process_stream_inner_with_changes(&lines, Options::default(), &mut changes);
+/// assert_eq!(out, vec!["| a | b |", "| 1 | 2 |"]);
+/// assert_eq!(changes.changes()[0].transform, Transform::Tables);
+/// ```
+#[must_use]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "the public API takes `Options` by value, as callers build it inline"
+)]
+pub fn process_stream_inner_with_changes(
+    lines: &[String],
+    opts: Options,
+    changes: &mut ChangeLog,
+) -> Vec<String> {
+    run_pipeline(lines, &STAGES, &opts, Some(changes), no_hook)
+        .unwrap_or_else(|never| match never {})
+}
 
-        if fence.is_in_fence {
-            state.push_out(line);
-            continue;
-        }
+/// Runs [`process_stream_inner`], offering each stage's output to `hook`.
+///
+/// `hook` is called after every built-in stage, including stages that `opts`
+/// disables, with the stage's [`Transform`] and the current document. It
+/// returns `Ok(Some(lines))` to replace the document or `Ok(None)` to leave
+/// it alone, so callers can splice their own rewrites into a fixed position
+/// in the pipeline. Replacements are recorded in `changes` as
+/// [`Transform::Plugin`], and the first error aborts the pipeline.
+///
+/// # Errors
+///
+/// Returns the first error produced by `hook`.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::Infallible;
+///
+/// use mdtablefix::{
+///     changes::Transform,
+///     process::{Options, process_stream_inner_with_hook},
+/// };
+///
+/// let lines = vec!["|a|b|".to_string(), "|1|2|".to_string()];
+/// let out = process_stream_inner_with_hook(&lines, Options::default(), None, |stage, doc| {
+///     Ok::<_, Infallible>(
+///         (stage == Transform::Fences)
+///             .then(|| doc.iter().map(|l| l.replace('1', "one")).collect()),
+///     )
+/// })
+/// .unwrap();
+/// assert_eq!(out, vec!["| a   | b |", "| one | 2 |"]);
+/// ```
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "the public API takes `Options` by value, as callers build it inline"
+)]
+pub fn process_stream_inner_with_hook<E, F>(
+    lines: &[String],
+    opts: Options,
+    changes: Option<&mut ChangeLog>,
+    hook: F,
+) -> Result<Vec<String>, E>
+where
+    F: FnMut(Transform, &[String]) -> Result<Option<Vec<String>>, E>,
+{
+    run_pipeline(lines, &STAGES, &opts, changes, hook)
+}
 
-        let Some(line) = state.handle_table_line(line) else {
-            continue;
-        };
+/// Hook for pipelines without caller-supplied stages.
+#[expect(
+    clippy::unnecessary_wraps,
+    reason = "matches the hook signature expected by `run_pipeline`"
+)]
+fn no_hook(_: Transform, _: &[String]) -> Result<Option<Vec<String>>, Infallible> { Ok(None) }
 
-        state.flush();
-        state.push_out(line);
+/// Records the edits made by one stage when a log is present.
+fn record(
+    changes: &mut Option<&mut ChangeLog>,
+    transform: Transform,
+    before: &[String],
+    after: &[String],
+) {
+    if let Some(log) = changes.as_deref_mut() {
+        log.record(transform, before, after);
     }
+}
 
-    state.flush();
-
-    let mut out = state.into_out();
-    if opts.headings {
-        out = crate::headings::convert_setext_headings(&out);
-    }
-    if opts.code_emphasis {
-        out = crate::code_emphasis::fix_code_emphasis(&out);
-    }
+/// Emits the `-vv` event describing one stage of the pipeline.
+fn log_stage(transform: Transform, changed: bool, started: Instant) {
+    debug!(
+        transform = transform.name(),
+        changed,
+        elapsed_us = started.elapsed().as_micros(),
+        "stage applied"
+    );
+}
 
-    let mut out = if opts.wrap {
-        wrap_text(&out, WRAP_COLS)
-    } else {
-        out
-    };
-    if opts.ellipsis {
-        out = replace_ellipsis(&out);
-    }
-    if opts.footnotes {
-        out = convert_footnotes(&out);
+/// Applies `stages` to `lines` in order, offering each stage's output to
+/// `hook` and then to the passes [`Options::transforms`] registers after it.
+fn run_pipeline<E, F>(
+    lines: &[String],
+    stages: &[Transform],
+    opts: &Options,
+    mut changes: Option<&mut ChangeLog>,
+    mut hook: F,
+) -> Result<Vec<String>, E>
+where
+    F: FnMut(Transform, &[String]) -> Result<Option<Vec<String>>, E>,
+{
+    let mut out = lines.to_vec();
+    let timed = tracing::enabled!(Level::DEBUG);
+    for &transform in stages {
+        let started = timed.then(Instant::now);
+        let next = run_stage(transform, &out, opts);
+        if let Some(started) = started {
+            log_stage(transform, next.as_ref().is_some_and(|n| *n != out), started);
+        }
+        if let Some(next) = next {
+            record(&mut changes, transform, &out, &next);
+            out = next;
+        }
+        if let Some(next) = hook(transform, &out)? {
+            record(&mut changes, Transform::Plugin, &out, &next);
+            out = next;
+        }
+        for pass in opts.transforms.passes_after(transform) {
+            let next = run_pass(pass, &out, opts);
+            debug!(pass = pass.name(), after = transform.name(), "pass applied");
+            record(&mut changes, pass.transform(), &out, &next);
+            out = next;
+        }
     }
-
-    out
+    Ok(out)
 }
 
 /// Processes a Markdown stream with all default options enabled.
@@ -224,15 +332,44 @@ pub fn process_stream_no_wrap(lines: &[String]) -> Vec<String> {
 /// # Examples
 ///
 /// ```
-/// use mdtablefix::process::{Options, process_stream_opts};
+/// use mdtablefix::{
+///     blocks::BlockExclusions,
+///     footnotes::FootnotePlacement,
+///     opaque::OpaqueTokens,
+///     process::{Options, TransformRegistry, process_stream_opts},
+///     protect::BlockProtection,
+///     table::{TableOverflow, TableStyle},
+///     wrap::CjkWrap,
+/// };
 /// let lines = vec!["text".to_string()];
 /// let opts = Options {
 ///     wrap: false,
+///     wrap_width: 80,
+///     rejoin: false,
 ///     ellipsis: false,
 ///     fences: false,
 ///     footnotes: false,
+///     footnote_placement: FootnotePlacement::Document,
+///     footnote_refs: None,
+///     renumber_comments: false,
 ///     code_emphasis: false,
+///     code_spans: false,
 ///     headings: false,
+///     emphasis_headings: None,
+///     wiki_tables: false,
+///     rst_tables: false,
+///     keep_clean_tables: false,
+///     table_style: TableStyle::Padded,
+///     max_table_width: None,
+///     table_overflow: TableOverflow::Keep,
+///     table_alignment: Vec::new(),
+///     cjk_wrap: CjkWrap::Cells,
+///     hard_breaks: None,
+///     sentence_spacing: None,
+///     block_exclusions: BlockExclusions::default(),
+///     opaque_tokens: OpaqueTokens::default(),
+///     protect: BlockProtection::default(),
+///     transforms: TransformRegistry::default(),
 /// };
 /// let out = process_stream_opts(&lines, opts);
 /// assert_eq!(out, vec!["text"]);
@@ -252,93 +389,4 @@ fn process_with_frontmatter(lines: &[String], opts: Options) -> Vec<String> {
 }
 
 #[cfg(test)]
-mod tests {
-    //! Unit tests for Markdown processing.
-
-    use super::*;
-
-    #[test]
-    fn processes_html_and_tables() {
-        let input = vec![
-            "<table><tr><td>A</td><td>B</td></tr></table>".to_string(),
-            "| X | Y |".to_string(),
-            "|---|---|".to_string(),
-            "| 1 | 2 |".to_string(),
-        ];
-        let output = process_stream(&input);
-        assert!(output.iter().any(|l| l.contains("| A   | B   |")));
-        assert!(output.iter().any(|l| l.contains("| X   | Y   |")));
-    }
-
-    #[test]
-    fn no_wrap_option() {
-        let input = vec!["| a | b |".to_string(), "| 1 | 2 |".to_string()];
-        let out = process_stream_no_wrap(&input);
-        assert_eq!(out, vec!["| a | b |", "| 1 | 2 |"]);
-    }
-
-    #[test]
-    fn integrates_code_emphasis_flag() {
-        let input = vec!["`X`** Y (in **`Z`**)**".to_string()];
-        let out = process_stream_inner(
-            &input,
-            Options {
-                code_emphasis: true,
-                ..Default::default()
-            },
-        );
-        assert_eq!(out, vec!["**`X` Y (in `Z`)**"]);
-    }
-
-    #[test]
-    fn converts_headings_when_enabled() {
-        let input = vec![
-            "Heading".to_string(),
-            "====".to_string(),
-            "Paragraph".to_string(),
-        ];
-        let disabled = process_stream_inner(
-            &input,
-            Options {
-                headings: false,
-                ..Default::default()
-            },
-        );
-        assert_eq!(disabled, input);
-
-        let enabled = process_stream_inner(
-            &input,
-            Options {
-                headings: true,
-                ..Default::default()
-            },
-        );
-        assert_eq!(
-            enabled,
-            vec!["# Heading".to_string(), "Paragraph".to_string()]
-        );
-    }
-
-    #[test]
-    fn process_stream_inner_applies_table_ellipsis_before_reflow() {
-        let input = vec![
-            "| example | value |".to_string(),
-            "| ------- | ----- |".to_string(),
-            "| ... | tail |".to_string(),
-        ];
-
-        let with_ellipsis = process_stream_inner(
-            &input,
-            Options {
-                ellipsis: true,
-                ..Default::default()
-            },
-        );
-        let without_ellipsis = process_stream_inner(&input, Options::default());
-
-        assert!(with_ellipsis.iter().any(|line| line.contains('…')));
-        assert!(!with_ellipsis.iter().any(|line| line.contains("...")));
-        assert!(without_ellipsis.iter().any(|line| line.contains("...")));
-        assert!(!without_ellipsis.iter().any(|line| line.contains('…')));
-    }
-}
+mod tests;
@@ -0,0 +1,257 @@
+//! Processing that borrows the lines it leaves unchanged.
+//!
+//! Most documents a formatter sees are already clean, yet the pipeline copies
+//! every line before the first stage and each stage allocates its output.
+//! [`process_stream_opts_cow`] instead runs the stages over the blocks that
+//! [`block_boundaries`] finds, one block at a time, holding each block as a
+//! borrow of the input. A stage only runs on a block containing something it
+//! looks for, such as a pipe for table reflow or `...` for ellipsis
+//! replacement, and a block keeps its borrow until a stage's output differs
+//! from it. The lines of blocks no stage rewrites reach the caller as
+//! [`Cow::Borrowed`] slices of the input, and blocks no stage looks at are
+//! never copied at all.
+
+use std::{borrow::Cow, ops::Range};
+
+use super::{
+    Options,
+    incremental::{block_boundaries, reads_whole_document},
+    registry::run_pass,
+    stages::{STAGES, run_stage},
+};
+use crate::{changes::Transform, frontmatter::split_leading_yaml_frontmatter};
+
+/// Processes `lines` like [`process_stream`](super::process_stream),
+/// borrowing the lines of every block no stage rewrites.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use mdtablefix::process::process_stream_cow;
+///
+/// let lines: Vec<String> = ["# Title", "", "|a|b|", "|-|-|"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let out = process_stream_cow(&lines);
+/// assert!(matches!(out[0], Cow::Borrowed("# Title")));
+/// assert!(matches!(out[2], Cow::Owned(_)));
+/// assert_eq!(out[2], "| a   | b   |");
+/// ```
+#[must_use]
+pub fn process_stream_cow(lines: &[String]) -> Vec<Cow<'_, str>> {
+    process_stream_opts_cow(
+        lines,
+        Options {
+            wrap: true,
+            ..Options::default()
+        },
+    )
+}
+
+/// Processes `lines` like [`process_stream_opts`](super::process_stream_opts),
+/// borrowing the lines of every block no stage rewrites.
+///
+/// Frontmatter is always borrowed. Every line of a block a stage rewrites is
+/// owned, even one whose text survives. When `opts` enables a transform that
+/// reads the whole document, such as footnote conversion, the body is treated
+/// as a single block.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use mdtablefix::process::{Options, process_stream_opts_cow};
+///
+/// let lines: Vec<String> = ["Clean.", "", "Wait..."]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let opts = Options {
+///     ellipsis: true,
+///     ..Options::default()
+/// };
+/// let out = process_stream_opts_cow(&lines, opts);
+/// assert_eq!(
+///     out,
+///     [
+///         Cow::Borrowed("Clean."),
+///         Cow::Borrowed(""),
+///         Cow::Owned("Wait…".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "the public API takes `Options` by value, as callers build it inline"
+)]
+pub fn process_stream_opts_cow(lines: &[String], opts: Options) -> Vec<Cow<'_, str>> {
+    let (frontmatter, body) = split_leading_yaml_frontmatter(lines);
+    let mut out: Vec<Cow<'_, str>> = frontmatter
+        .iter()
+        .map(|line| Cow::Borrowed(line.as_str()))
+        .collect();
+    for segment in segments(body, &opts) {
+        match run_segment(&body[segment], &opts) {
+            Cow::Borrowed(lines) => {
+                out.extend(lines.iter().map(|line| Cow::Borrowed(line.as_str())));
+            }
+            Cow::Owned(lines) => out.extend(lines.into_iter().map(Cow::Owned)),
+        }
+    }
+    out
+}
+
+/// Splits `body` into the runs of lines the stages can format apart: each
+/// block with the blank lines that follow it, or the whole body when `opts`
+/// reads the whole document.
+fn segments(body: &[String], opts: &Options) -> Vec<Range<usize>> {
+    if body.is_empty() {
+        return Vec::new();
+    }
+    let starts = if reads_whole_document(opts) {
+        Vec::new()
+    } else {
+        block_boundaries(body)
+            .into_iter()
+            .skip(1)
+            .map(|block| block.start)
+            .collect()
+    };
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for next in starts {
+        segments.push(start..next);
+        start = next;
+    }
+    segments.push(start..body.len());
+    segments
+}
+
+/// Runs the built-in stages and registered passes over `lines`, keeping the
+/// borrow until one of them changes a line.
+fn run_segment<'a>(lines: &'a [String], opts: &Options) -> Cow<'a, [String]> {
+    let mut block = Cow::Borrowed(lines);
+    for &transform in &STAGES {
+        if may_change(transform, &block, opts)
+            && let Some(next) = run_stage(transform, &block, opts)
+        {
+            replace_if_changed(&mut block, next);
+        }
+        for pass in opts.transforms.passes_after(transform) {
+            let next = run_pass(pass, &block, opts);
+            replace_if_changed(&mut block, next);
+        }
+    }
+    block
+}
+
+fn replace_if_changed(block: &mut Cow<'_, [String]>, next: Vec<String>) {
+    if *next != **block {
+        *block = Cow::Owned(next);
+    }
+}
+
+/// Returns `false` when `lines` hold nothing `transform` rewrites.
+///
+/// The test is conservative: it may pass a block the stage leaves alone,
+/// and stages without a cheap test always run.
+fn may_change(transform: Transform, lines: &[String], opts: &Options) -> bool {
+    let contains = |needle: &str| lines.iter().any(|line| line.contains(needle));
+    match transform {
+        Transform::Fences => contains("```") || contains("~~~"),
+        Transform::HtmlTables => lines
+            .iter()
+            .any(|line| line.to_ascii_lowercase().contains("<table")),
+        Transform::WikiTables => contains("{|"),
+        Transform::CodeSpans | Transform::CodeEmphasis => contains("`"),
+        Transform::Tables => contains("|"),
+        Transform::Headings => lines
+            .iter()
+            .any(|line| line.trim_start().starts_with(['=', '-'])),
+        Transform::EmphasisHeadings => contains("**") || contains("__"),
+        Transform::Wrap => may_wrap(lines, opts.wrap_width),
+        Transform::Ellipsis => contains("..."),
+        Transform::FootnoteRefs => contains("[^"),
+        _ => true,
+    }
+}
+
+/// Returns `true` when `lines` hold a paragraph of more than one line, a
+/// line wider than `width`, trailing whitespace, or a tab, the only things
+/// the wrap stage changes.
+fn may_wrap(lines: &[String], width: usize) -> bool {
+    let filled = |line: &String| !line.trim_start_matches([' ', '\t']).is_empty();
+    lines
+        .iter()
+        .any(|line| line.len() > width || line.contains('\t') || line.ends_with(' '))
+        || lines.windows(2).any(|pair| pair.iter().all(filled))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for borrowing unchanged lines.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{process::process_stream_opts, test_support::lines};
+
+    fn owned(out: &[Cow<'_, str>]) -> Vec<bool> {
+        out.iter()
+            .map(|line| matches!(line, Cow::Owned(_)))
+            .collect()
+    }
+
+    #[test]
+    fn clean_documents_are_fully_borrowed() {
+        let input = lines("---\ntitle: x\n---\n# Title\n\nText.\n\n| abc |\n| --- |");
+        let out = process_stream_cow(&input);
+        assert_eq!(out, input);
+        assert!(owned(&out).iter().all(|owned| !owned));
+    }
+
+    #[test]
+    fn only_rewritten_blocks_are_owned() {
+        let input = lines("Intro.\n\n|a|b|\n|-|-|\n\n- one\n- two");
+        let out = process_stream_cow(&input);
+        assert_eq!(
+            out,
+            process_stream_opts(
+                &input,
+                Options {
+                    wrap: true,
+                    ..Options::default()
+                }
+            )
+        );
+        assert_eq!(owned(&out), [false, false, true, true, true, false, false]);
+    }
+
+    #[test]
+    fn whole_document_transforms_own_the_body() {
+        let input = lines("Wait...\n\nClean.");
+        let opts = Options {
+            ellipsis: true,
+            footnotes: true,
+            ..Options::default()
+        };
+        let out = process_stream_opts_cow(&input, opts);
+        assert_eq!(out, ["Wait…", "", "Clean."]);
+        assert_eq!(owned(&out), [true, true, true]);
+    }
+
+    #[rstest]
+    #[case::paragraph("One line\nand another", true)]
+    #[case::short_lines("One line\n\nanother", false)]
+    #[case::trailing_space("One line ", true)]
+    #[case::tab("One\tline", true)]
+    #[case::wide("A line well past twenty columns", true)]
+    fn detects_lines_wrapping_may_change(#[case] text: &str, #[case] expected: bool) {
+        assert_eq!(may_wrap(&lines(text), 20), expected);
+    }
+}
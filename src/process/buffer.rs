@@ -6,10 +6,24 @@
 //! parent [`process`](super) module stays within the repository size limit.
 
 use tracing::debug;
+use unicode_width::UnicodeWidthStr;
 
+use super::Options;
 use crate::{
+    Error,
+    code_emphasis::fix_code_emphasis,
     ellipsis::replace_ellipsis,
-    table::reflow_table,
+    table::{
+        SCROLL_MARKER,
+        TableOverflow,
+        align_columns,
+        contain_table,
+        is_clean_table,
+        map_cells,
+        reflow_table_with_max_width,
+        reflow_table_with_style,
+        try_reflow_table,
+    },
     wrap::{LinkReferenceMatcher, classify_block, leading_indent},
 };
 
@@ -22,7 +36,7 @@ fn is_indented_content_line(line: &str) -> bool {
 }
 
 // Note: `warn` is intentionally not imported. `flush` only calls
-// `reflow_table` after its `buf.is_empty()` guard, and `reflow_table` returns
+// `reflow_table_with_style` after its `buf.is_empty()` guard, and it returns
 // an empty vector solely for empty input; for any non-empty input it yields
 // either the reflowed table or the original lines verbatim. An empty result
 // from a non-empty buffer is therefore unreachable, so no `warn!` is needed.
@@ -39,21 +53,42 @@ pub(super) struct ProcessBuffer {
     out: Vec<String>,
     buf: Vec<String>,
     in_table: bool,
-    ellipsis: bool,
+    opts: Options,
+    /// Whether the buffer only checks tables instead of reflowing them.
+    checking: bool,
+    /// The first table a checking buffer could not reflow.
+    error: Option<Error>,
 }
 
 impl ProcessBuffer {
-    /// Creates an empty buffer. `ellipsis` selects whether buffered table
-    /// cells have `...` replaced with `…` during [`flush`](Self::flush).
-    pub(super) fn new(ellipsis: bool) -> Self {
+    /// Creates an empty buffer. `opts.ellipsis` and `opts.code_emphasis`
+    /// select the inline fixes applied to each buffered table cell during
+    /// [`flush`](Self::flush), and `opts.keep_clean_tables` leaves already
+    /// aligned tables unchanged.
+    pub(super) fn new(opts: Options) -> Self {
         Self {
             out: Vec::new(),
             buf: Vec::new(),
             in_table: false,
-            ellipsis,
+            opts,
+            checking: false,
+            error: None,
         }
     }
 
+    /// Creates a buffer that copies its input unchanged and records the
+    /// first table [`try_reflow_table`] rejects, by its line in the input.
+    pub(super) fn checking() -> Self {
+        Self {
+            checking: true,
+            ..Self::new(Options::default())
+        }
+    }
+
+    /// Consumes a [`checking`](Self::checking) buffer and returns the first
+    /// table error it found.
+    pub(super) fn into_error(self) -> Option<Error> { self.error }
+
     /// Appends a finished line directly to the output, without touching the
     /// pending table buffer. Callers that must preserve table/verbatim
     /// ordering call [`flush`](Self::flush) first.
@@ -75,19 +110,88 @@ impl ProcessBuffer {
             return;
         }
         let buffered = std::mem::take(&mut self.buf);
-        if self.in_table {
-            let table_lines = if self.ellipsis {
-                replace_ellipsis(&buffered)
+        if self.checking {
+            if self.in_table
+                && self.error.is_none()
+                && let Err(err) = try_reflow_table(&buffered)
+            {
+                self.error = Some(err.offset(self.out.len()));
+            }
+            self.out.extend(buffered);
+        } else if self.in_table {
+            let table_lines = self.fix_cells(buffered);
+            if self.opts.keep_clean_tables
+                && is_clean_table(&table_lines)
+                && self.fits(&table_lines)
+            {
+                self.out.extend(table_lines);
+            } else if let Some(max_width) = self.opts.max_table_width {
+                let table =
+                    reflow_table_with_max_width(&table_lines, self.opts.table_style, max_width);
+                let overflow = self.opts.table_overflow;
+                if overflow == TableOverflow::Keep || (self.fits(&table) && !self.is_contained()) {
+                    self.out.extend(table);
+                } else {
+                    // A contained table scrolls, so it keeps its natural widths.
+                    let natural = reflow_table_with_style(&table_lines, self.opts.table_style);
+                    if self.is_contained() {
+                        self.out.extend(natural);
+                    } else {
+                        self.out.extend(contain_table(&natural, overflow));
+                    }
+                }
             } else {
-                buffered
-            };
-            self.out.extend(reflow_table(&table_lines));
+                self.out
+                    .extend(reflow_table_with_style(&table_lines, self.opts.table_style));
+            }
         } else {
             self.out.extend(buffered);
         }
         self.in_table = false;
     }
 
+    /// Returns `true` when no line of a kept table exceeds
+    /// [`Options::max_table_width`].
+    fn fits(&self, table: &[String]) -> bool {
+        self.opts.max_table_width.is_none_or(|max_width| {
+            table
+                .iter()
+                .all(|line| UnicodeWidthStr::width(line.as_str()) <= max_width)
+        })
+    }
+
+    /// Returns `true` when the table being flushed follows the marker of a
+    /// scrolling container written by an earlier run.
+    fn is_contained(&self) -> bool {
+        self.out
+            .last()
+            .is_some_and(|line| line.trim() == SCROLL_MARKER)
+    }
+
+    /// Runs the enabled inline fixes on each cell of a buffered table, in
+    /// pipeline order, so reflow pads the cells' final content, and writes
+    /// [`Options::table_alignment`] into its separator.
+    fn fix_cells(&self, table: Vec<String>) -> Vec<String> {
+        let table = if self.opts.table_alignment.is_empty() {
+            table
+        } else {
+            align_columns(&table, &self.opts.table_alignment)
+        };
+        if !self.opts.ellipsis && !self.opts.code_emphasis {
+            return table;
+        }
+        map_cells(&table, |cell| {
+            let mut text = vec![cell.to_string()];
+            if self.opts.code_emphasis {
+                text = fix_code_emphasis(&text);
+            }
+            if self.opts.ellipsis {
+                text = replace_ellipsis(&text);
+            }
+            text.concat()
+        })
+    }
+
     pub(super) fn push_verbatim(&mut self, line: &str) {
         self.flush();
         self.out.push(line.to_string());
@@ -144,7 +248,7 @@ impl ProcessBuffer {
             self.flush();
             return Some(line);
         }
-        if self.in_table && (line.contains('|') || crate::table::SEP_RE.is_match(line.trim())) {
+        if self.in_table && (line.contains('|') || crate::table::is_separator_line(line.trim())) {
             self.buf.push(line);
             return None;
         }
@@ -4,15 +4,18 @@
 use rstest::rstest;
 
 use super::*;
+use crate::table::reflow_table;
 
-/// Builds a fresh, empty buffer with table reflow enabled and ellipsis
-/// replacement disabled (the default for these tests).
+/// Builds a fresh, empty buffer with table reflow enabled and cell fixes
+/// disabled (the default for these tests).
 fn new_buffer() -> ProcessBuffer {
     ProcessBuffer {
         out: Vec::new(),
         buf: Vec::new(),
         in_table: false,
-        ellipsis: false,
+        opts: Options::default(),
+        checking: false,
+        error: None,
     }
 }
 
@@ -181,3 +184,32 @@ fn table_followed_by_indented_pipe_line_flushes_table() {
         owned(&["| a   | b   |", "| --- | --- |", "| 1   | 2   |"]),
     );
 }
+
+#[test]
+fn checking_records_the_first_bad_table() {
+    let mut buffer = ProcessBuffer::checking();
+    for line in [
+        "text",
+        "",
+        "| a | b |",
+        "| --- | --- |",
+        "| 1 |",
+        "",
+        "| x |",
+        "| - | - |",
+    ] {
+        if let Some(line) = handle_line(&mut buffer, line) {
+            buffer.flush();
+            buffer.push_out(line);
+        }
+    }
+    buffer.flush();
+    assert_eq!(
+        buffer.into_error(),
+        Some(Error::MismatchedColumns {
+            line: 5,
+            expected: 2,
+            found: 1,
+        })
+    );
+}
@@ -0,0 +1,124 @@
+//! Processing that reports tables it cannot reflow.
+//!
+//! The table stage leaves a table it cannot parse as it was written, so a
+//! document with a broken table still formats without complaint.
+//! [`try_process_stream`] and [`try_process_stream_opts`] first find the
+//! tables the stage would see, check each with
+//! [`try_reflow_table`](crate::table::try_reflow_table), and return the
+//! first [`Error`] instead.
+
+use super::{Options, buffer::ProcessBuffer, process_with_frontmatter, stages::buffer_tables};
+use crate::{Error, frontmatter::split_leading_yaml_frontmatter, html_blocks::html_block_ranges};
+
+/// Processes `lines` like [`process_stream`](super::process_stream), or
+/// returns an [`Error`] for the first table that cannot be reflowed.
+///
+/// # Errors
+/// Returns the [`Error`] for the first table that cannot be parsed, with
+/// its line counted from the start of `lines`.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{Error, try_process_stream};
+///
+/// let lines: Vec<String> = ["# Title", "", "| a | b |", "| --- | --- |", "| 1 |"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let err = try_process_stream(&lines).unwrap_err();
+/// assert_eq!(err.line(), 5);
+/// assert!(matches!(err, Error::MismatchedColumns { .. }));
+/// ```
+pub fn try_process_stream(lines: &[String]) -> Result<Vec<String>, Error> {
+    try_process_stream_opts(
+        lines,
+        Options {
+            wrap: true,
+            ..Options::default()
+        },
+    )
+}
+
+/// Processes `lines` like [`process_stream_opts`](super::process_stream_opts),
+/// or returns an [`Error`] for the first table that cannot be reflowed.
+///
+/// Tables are grouped exactly as the table stage groups them. Those inside
+/// fenced code, HTML blocks, and blocks [`Options::protect`] accepts are
+/// skipped, and [`Options::opaque_tokens`] are masked first, as the stage
+/// never parses them.
+///
+/// # Errors
+/// Returns the [`Error`] for the first table that cannot be parsed, with
+/// its line counted from the start of `lines`.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::process::{Options, try_process_stream_opts};
+///
+/// let lines: Vec<String> = ["|a|b|", "|-|-|"].into_iter().map(str::to_string).collect();
+/// assert_eq!(
+///     try_process_stream_opts(&lines, Options::default()),
+///     Ok(vec![
+///         "| a   | b   |".to_string(),
+///         "| --- | --- |".to_string()
+///     ])
+/// );
+/// ```
+pub fn try_process_stream_opts(lines: &[String], opts: Options) -> Result<Vec<String>, Error> {
+    let (frontmatter, body) = split_leading_yaml_frontmatter(lines);
+    let opaque = opts.opaque_tokens.mask(body);
+    let mut body = opaque.map_or_else(|| body.to_vec(), |mask| mask.lines().to_vec());
+    // Blank lines end a table run without starting one, as the placeholders
+    // the stage sees for these blocks do.
+    let skipped = html_block_ranges(&body)
+        .into_iter()
+        .chain(opts.protect.protected_ranges(&body));
+    for range in skipped.collect::<Vec<_>>() {
+        body[range].fill(String::new());
+    }
+    if let Some(err) = buffer_tables(&body, ProcessBuffer::checking()).into_error() {
+        return Err(err.offset(frontmatter.len()));
+    }
+    Ok(process_with_frontmatter(lines, opts))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for fallible processing.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[test]
+    fn counts_lines_from_the_start_of_the_document() {
+        let input = lines("---\ntitle: x\n---\n\n| a |\n| --- | --- |\n| 1 |");
+        assert_eq!(
+            try_process_stream(&input),
+            Err(Error::SeparatorColumns {
+                line: 6,
+                expected: 1,
+                found: 2,
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::fence("```\n| a | b |\n| 1 |\n```")]
+    #[case::html_block("<div>\n| a | b |\n| 1 |\n</div>")]
+    #[case::opaque_pipe("| a | b |\n|---|---|\n| {{ x|y }} | 2 |")]
+    fn skips_what_the_table_stage_skips(#[case] input: &str) {
+        let opts = Options {
+            opaque_tokens: crate::opaque::OpaqueTokens::new([r"\{\{.*?\}\}"]).unwrap(),
+            ..Options::default()
+        };
+        let input = lines(input);
+        assert_eq!(
+            try_process_stream_opts(&input, opts.clone()),
+            Ok(crate::process_stream_opts(&input, opts))
+        );
+    }
+}
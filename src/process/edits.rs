@@ -0,0 +1,81 @@
+//! Processing that reports its edits against the input.
+//!
+//! Editor integrations apply formatting as a list of edits to the buffer
+//! they hold rather than replacing it wholesale, which keeps the cursor,
+//! folds, and undo history of untouched text. [`process_stream_opts_with_edits`]
+//! returns those edits alongside the formatted lines.
+
+use super::{Options, process_stream_inner_with_changes};
+use crate::{
+    changes::{ChangeLog, Edit},
+    frontmatter::split_leading_yaml_frontmatter,
+};
+
+/// Processes `lines` like [`process_stream_opts`](super::process_stream_opts)
+/// and returns the [`Edit`]s that turn `lines` into the result.
+///
+/// Edit ranges index `lines`, including any leading YAML frontmatter, which
+/// is never edited. Each edit names the transforms that rewrote its lines,
+/// and applying the edits in order with
+/// [`apply_edits`](crate::changes::apply_edits) yields the returned lines.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{
+///     changes::Transform,
+///     process::{Options, process_stream_opts_with_edits},
+/// };
+///
+/// let lines: Vec<String> = ["---", "title: x", "---", "|a|b|", "|-|-|"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let (out, edits) = process_stream_opts_with_edits(&lines, Options::default());
+/// assert_eq!(out[3], "| a   | b   |");
+/// assert_eq!(edits.len(), 1);
+/// assert_eq!(edits[0].lines, 3..5);
+/// assert_eq!(edits[0].transforms, [Transform::Tables]);
+/// ```
+#[must_use]
+pub fn process_stream_opts_with_edits(lines: &[String], opts: Options) -> (Vec<String>, Vec<Edit>) {
+    let (frontmatter_prefix, body) = split_leading_yaml_frontmatter(lines);
+    let mut log = ChangeLog::with_line_offset(frontmatter_prefix.len());
+    let out = process_stream_inner_with_changes(body, opts, &mut log);
+    let edits = log.edits(body, &out);
+    let mut result = frontmatter_prefix.to_vec();
+    result.extend(out);
+    (result, edits)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for processing with edits.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{changes::apply_edits, test_support::lines};
+
+    #[rstest]
+    #[case::clean("# Title\n\nText.")]
+    #[case::wrapped_and_reflowed(
+        "|a|b|\n|-|-|\n\nWait... this paragraph goes on for long enough that it has to wrap at \
+         eighty columns."
+    )]
+    #[case::headings("Title\n=====\n\nText...")]
+    #[case::footnotes("Text.1\n\n1. Note.")]
+    fn edits_reproduce_the_output(#[case] input: &str) {
+        let input = lines(input);
+        let opts = Options {
+            wrap: true,
+            ellipsis: true,
+            headings: true,
+            footnotes: true,
+            ..Options::default()
+        };
+        let (out, edits) = process_stream_opts_with_edits(&input, opts);
+        assert_eq!(apply_edits(&input, &edits), out);
+        assert!(edits.iter().all(|edit| !edit.transforms.is_empty()));
+    }
+}
@@ -0,0 +1,273 @@
+//! Reprocessing only the blocks an edit touched.
+//!
+//! Editors format the buffer after every change, and running the whole
+//! pipeline over a long document for a one-word edit is wasted work.
+//! [`reprocess_range`] takes the previous output, the edited text, and the
+//! lines the edit changed, widens the change to the whole blocks that
+//! [`block_boundaries`] finds around it, and runs the pipeline over those
+//! lines alone. Transforms that read the whole document, such as footnote
+//! conversion, and edits that move a block boundary outside the changed
+//! lines fall back to processing everything.
+
+use std::ops::Range;
+
+use super::{Options, process_stream_inner, process_with_frontmatter};
+use crate::{
+    blocks::{BlockType, line_blocks},
+    frontmatter::split_leading_yaml_frontmatter,
+    html_blocks::html_block_ranges,
+    protect::block_ranges,
+};
+
+/// A document formatted by [`reprocess_range`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reprocessed {
+    /// The formatted document.
+    pub lines: Vec<String>,
+    /// The lines of the edited text that ran through the pipeline. Every
+    /// other line is copied unchanged.
+    pub region: Range<usize>,
+    /// The lines of [`Self::lines`] that replaced [`Self::region`].
+    pub output: Range<usize>,
+}
+
+/// Returns the line ranges of the blocks the pipeline formats independently.
+///
+/// Blocks are runs of non-blank lines, with fenced code kept whole. Blank
+/// lines do not end a block inside an HTML block or an HTML `<table>`, nor
+/// between the items of a loose list or its indented continuations, nor after
+/// a `<!-- mdtablefix: … -->` pragma, which applies to the block below it,
+/// since the transforms read those as one unit.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::process::block_boundaries;
+///
+/// let lines: Vec<String> = ["# Title", "", "- one", "", "- two", "", "Text"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(block_boundaries(&lines), [0..1, 2..5, 6..7]);
+/// ```
+#[must_use]
+pub fn block_boundaries(lines: &[String]) -> Vec<Range<usize>> {
+    let types = line_blocks(lines);
+    let html = html_block_ranges(lines);
+    let mut table_depth = 0usize;
+    let mut blocks: Vec<Range<usize>> = Vec::new();
+    for range in block_ranges(lines) {
+        let joins = blocks.last().is_some_and(|last| {
+            table_depth > 0
+                || is_pragma(&lines[last.end - 1])
+                || continues_list(&types, last, &range)
+                || html
+                    .iter()
+                    .any(|block| block.start < last.end && block.end > range.start)
+        });
+        for line in &lines[range.clone()] {
+            let line = line.to_ascii_lowercase();
+            table_depth = (table_depth + line.matches("<table").count())
+                .saturating_sub(line.matches("</table>").count());
+        }
+        match blocks.last_mut() {
+            Some(last) if joins => last.end = range.end,
+            _ => blocks.push(range),
+        }
+    }
+    blocks
+}
+
+/// Returns `true` when `line` is a directive such as
+/// `<!-- mdtablefix: hide-columns Notes -->`.
+fn is_pragma(line: &str) -> bool { line.trim_start().starts_with("<!-- mdtablefix:") }
+
+/// Returns `true` when `next` carries on the list item or footnote
+/// definition that ends `last`.
+fn continues_list(types: &[Option<BlockType>], last: &Range<usize>, next: &Range<usize>) -> bool {
+    let kind = types[next.start];
+    matches!(
+        kind,
+        Some(BlockType::ListItem | BlockType::FootnoteDefinition)
+    ) && types[last.end - 1] == kind
+}
+
+/// Formats `text`, an edit of the formatted `previous`, by reprocessing only
+/// the blocks around the lines `changed` covers.
+///
+/// `changed` indexes `text`: every line before it must match the same line of
+/// `previous`, and every line after it the matching line at the end of
+/// `previous`. An empty range marks a deletion before that line. When those
+/// lines do not match, when `opts` enables a transform that reads the whole
+/// document, or when the edit moves a block boundary outside the changed
+/// blocks, the whole document is processed as
+/// [`process_stream_opts`](super::process_stream_opts) would.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::process::{Options, process_stream_opts, reprocess_range};
+///
+/// let lines = |text: &str| text.lines().map(str::to_string).collect::<Vec<_>>();
+/// let previous = process_stream_opts(&lines("|a|b|\n\n|1|2|"), Options::default());
+/// let text = lines("| a | b |\n\n|one|2|");
+/// let result = reprocess_range(&previous, &text, 2..3, Options::default());
+/// assert_eq!(result.lines, ["| a | b |", "", "| one | 2 |"]);
+/// assert_eq!(result.region, 2..3);
+/// ```
+#[must_use]
+pub fn reprocess_range(
+    previous: &[String],
+    text: &[String],
+    changed: Range<usize>,
+    opts: Options,
+) -> Reprocessed {
+    let Some(region) = affected_region(previous, text, &changed, &opts) else {
+        let lines = process_with_frontmatter(text, opts);
+        return Reprocessed {
+            output: 0..lines.len(),
+            region: 0..text.len(),
+            lines,
+        };
+    };
+    let mut lines = text[..region.start].to_vec();
+    lines.extend(process_stream_inner(&text[region.clone()], opts));
+    let output = region.start..lines.len();
+    lines.extend_from_slice(&text[region.end..]);
+    Reprocessed {
+        lines,
+        region,
+        output,
+    }
+}
+
+/// Returns `true` when `opts` enables a transform whose result on one block
+/// depends on the rest of the document.
+pub(super) fn reads_whole_document(opts: &Options) -> bool {
+    opts.footnotes
+        || opts.footnote_refs.is_some()
+        || opts.renumber_comments
+        || !opts.transforms.is_empty()
+        || !opts.protect.is_empty()
+}
+
+/// Returns the lines of `text` to reprocess, or `None` when only processing
+/// the whole document is safe.
+fn affected_region(
+    previous: &[String],
+    text: &[String],
+    changed: &Range<usize>,
+    opts: &Options,
+) -> Option<Range<usize>> {
+    if reads_whole_document(opts) || changed.start > changed.end || changed.end > text.len() {
+        return None;
+    }
+    let kept = text.len() - changed.end;
+    if changed.start + kept > previous.len()
+        || previous[..changed.start] != text[..changed.start]
+        || previous[previous.len() - kept..] != text[changed.end..]
+    {
+        return None;
+    }
+    let offset = split_leading_yaml_frontmatter(text).0.len();
+    if changed.start < offset || split_leading_yaml_frontmatter(previous).0.len() != offset {
+        return None;
+    }
+    let body = &text[offset..];
+    let (start, end) = (changed.start - offset, changed.end - offset);
+    let region = block_boundaries(body)
+        .into_iter()
+        .filter(|block| block.start <= end && block.end >= start)
+        .fold(start..end, |region, block| {
+            region.start.min(block.start)..region.end.max(block.end)
+        });
+    let previous_body = &previous[offset..];
+    let previous_end = previous_body.len() - (body.len() - region.end);
+    let previous_blocks = block_boundaries(previous_body);
+    let splits = |at: usize| {
+        !previous_blocks
+            .iter()
+            .any(|block| block.start < at && at < block.end)
+    };
+    (splits(region.start) && splits(previous_end))
+        .then(|| region.start + offset..region.end + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for incremental reprocessing.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{process::process_stream_opts, test_support::lines};
+
+    fn opts() -> Options {
+        Options {
+            wrap: true,
+            wrap_width: 30,
+            headings: true,
+            ellipsis: true,
+            ..Options::default()
+        }
+    }
+
+    #[rstest]
+    #[case::table_cell(
+        "Intro text.\n\n|a|b|\n|-|-|\n|1|2|\n\nOutro.",
+        4..5,
+        "|1000|2|",
+        Some(2..5)
+    )]
+    #[case::paragraph(
+        "# Title\n\nShort.\n\nLast.",
+        2..3,
+        "Now this paragraph runs well past thirty columns...",
+        Some(2..3)
+    )]
+    #[case::loose_list_item("Text.\n\n- one\n\n- two\n\nEnd.", 4..5, "- two\n\n- three", Some(2..7))]
+    #[case::joined_paragraphs("First part\n\nsecond part.\n\nEnd.", 1..2, "", Some(0..2))]
+    #[case::setext_heading("Text.\n\nTitle\n\nEnd.", 3..3, "=====", Some(2..4))]
+    #[case::unclosed_fence("A.\n\nB...\n\nC...", 2..2, "```", Some(2..6))]
+    #[case::closed_fence("A.\n\n```\nB...\n\nC...", 4..4, "```", None)]
+    #[case::frontmatter("---\ntitle: x\n---\nText...", 1..2, "title: y", None)]
+    #[case::pragma(
+        "Intro.\n\n<!-- mdtablefix: hide-columns B -->\n\n|A|B|\n|-|-|\n|1|2|",
+        4..7,
+        "|A|B|\n|-|-|\n|3|4|",
+        Some(2..7)
+    )]
+    fn matches_processing_the_whole_document(
+        #[case] original: &str,
+        #[case] changed: Range<usize>,
+        #[case] replacement: &str,
+        #[case] region: Option<Range<usize>>,
+    ) {
+        let previous = process_stream_opts(&lines(original), opts());
+        let mut text = previous.clone();
+        text.splice(changed.clone(), lines(replacement));
+        let changed = changed.start..changed.start + lines(replacement).len();
+
+        let result = reprocess_range(&previous, &text, changed, opts());
+
+        assert_eq!(result.lines, process_stream_opts(&text, opts()));
+        assert_eq!(result.region, region.unwrap_or(0..text.len()));
+    }
+
+    #[test]
+    fn falls_back_when_unchanged_lines_differ() {
+        let previous = lines("A.\n\nB.");
+        let text = lines("A...\n\nB.");
+        let result = reprocess_range(&previous, &text, 2..3, opts());
+        assert_eq!(result.lines, ["A…", "", "B."]);
+        assert_eq!(result.region, 0..3);
+    }
+
+    #[test]
+    fn keeps_html_tables_whole_across_blank_lines() {
+        assert_eq!(
+            block_boundaries(&lines("<table>\n<tr><td>a</td></tr>\n\n</table>\n\nText")),
+            [0..4, 5..6]
+        );
+    }
+}
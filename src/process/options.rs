@@ -0,0 +1,229 @@
+//! The [`Options`] that select and configure pipeline stages.
+
+use super::{TransformRegistry, WRAP_COLS};
+use crate::{
+    blocks::BlockExclusions,
+    changes::Transform,
+    footnotes::{FootnotePlacement, FootnoteRefCompaction},
+    hard_breaks::HardBreakStyle,
+    opaque::OpaqueTokens,
+    protect::BlockProtection,
+    sentence_spacing::SentenceSpacing,
+    table::{Alignment, TableOverflow, TableStyle},
+    wrap::CjkWrap,
+};
+
+/// Processing options controlling the behaviour of [`process_stream_inner`].
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{
+///     blocks::BlockExclusions,
+///     footnotes::FootnotePlacement,
+///     opaque::OpaqueTokens,
+///     process::{Options, TransformRegistry, process_stream_opts},
+///     protect::BlockProtection,
+///     table::{TableOverflow, TableStyle},
+///     wrap::CjkWrap,
+/// };
+///
+/// let lines = vec!["example".to_string()];
+/// let opts = Options {
+///     wrap: false,
+///     wrap_width: 80,
+///     rejoin: false,
+///     ellipsis: false,
+///     fences: false,
+///     footnotes: false,
+///     footnote_placement: FootnotePlacement::Document,
+///     footnote_refs: None,
+///     renumber_comments: false,
+///     code_emphasis: false,
+///     code_spans: false,
+///     headings: false,
+///     emphasis_headings: None,
+///     wiki_tables: false,
+///     rst_tables: false,
+///     keep_clean_tables: false,
+///     table_style: TableStyle::Padded,
+///     max_table_width: None,
+///     table_overflow: TableOverflow::Keep,
+///     table_alignment: Vec::new(),
+///     cjk_wrap: CjkWrap::Cells,
+///     hard_breaks: None,
+///     sentence_spacing: None,
+///     block_exclusions: BlockExclusions::default(),
+///     opaque_tokens: OpaqueTokens::default(),
+///     protect: BlockProtection::default(),
+///     transforms: TransformRegistry::default(),
+/// };
+/// let out = process_stream_opts(&lines, opts);
+/// assert_eq!(out, vec!["example"]);
+/// ```
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "Options map directly to CLI flags"
+)]
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct Options {
+    /// Enable paragraph wrapping.
+    pub wrap: bool,
+    /// Column width used by [`Options::wrap`] (default: [`WRAP_COLS`]).
+    pub wrap_width: usize,
+    /// Merge paragraph lines broken mid-sentence before wrapping.
+    pub rejoin: bool,
+    /// Replace `...` with `…`.
+    pub ellipsis: bool,
+    /// Normalise code block fences.
+    pub fences: bool,
+    /// Convert bare numeric references into GitHub-flavoured footnote links (default: `false`).
+    pub footnotes: bool,
+    /// Whether [`Options::footnotes`] numbers and places definitions per
+    /// document or per section.
+    pub footnote_placement: FootnotePlacement,
+    /// Remove footnote references that repeat the one before them, merging
+    /// the runs this policy chooses.
+    pub footnote_refs: Option<FootnoteRefCompaction>,
+    /// Append `<!-- was N -->` to footnote definitions whose number
+    /// [`Options::footnotes`] changes.
+    pub renumber_comments: bool,
+    /// Fix emphasis markers adjacent to inline code.
+    pub code_emphasis: bool,
+    /// Trim spaces inside inline code and minimize its backtick delimiters.
+    pub code_spans: bool,
+    /// Convert Setext-style headings into ATX (`#`) headings.
+    pub headings: bool,
+    /// Convert bold-only paragraphs into ATX headings of this level.
+    pub emphasis_headings: Option<u8>,
+    /// Convert Jira and Confluence wiki-markup tables into Markdown tables.
+    pub wiki_tables: bool,
+    /// Convert reStructuredText grid and simple tables into Markdown tables.
+    pub rst_tables: bool,
+    /// Leave tables whose columns are already aligned exactly as written.
+    pub keep_clean_tables: bool,
+    /// Layout used when reflowing tables.
+    pub table_style: TableStyle,
+    /// Narrow padded table columns, never below their longest cell, so rows
+    /// fit within this many columns where possible.
+    pub max_table_width: Option<usize>,
+    /// How a table still wider than [`Options::max_table_width`] after its
+    /// columns are narrowed is contained.
+    pub table_overflow: TableOverflow,
+    /// Alignment written into the separator of every reflowed table, by
+    /// column. `None`, and columns past the end, keep the table's own.
+    pub table_alignment: Vec<Option<Alignment>>,
+    /// How paragraphs written mostly in Chinese, Japanese, or Korean wrap.
+    pub cjk_wrap: CjkWrap,
+    /// Rewrite hard line breaks inside paragraphs to this style.
+    pub hard_breaks: Option<HardBreakStyle>,
+    /// Rewrite the spaces after sentence-ending punctuation to this width.
+    pub sentence_spacing: Option<SentenceSpacing>,
+    /// Block types that individual transforms leave untouched.
+    pub block_exclusions: BlockExclusions,
+    /// Text that wrapping and table parsing keep whole, such as template
+    /// placeholders.
+    pub opaque_tokens: OpaqueTokens,
+    /// Blocks that no transform may change, chosen by a caller-supplied
+    /// predicate. Not serialized, since the predicate is code.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub protect: BlockProtection,
+    /// Caller-supplied passes run after built-in stages. Not serialized,
+    /// since the passes are code.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub transforms: TransformRegistry,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            wrap: false,
+            wrap_width: WRAP_COLS,
+            rejoin: false,
+            ellipsis: false,
+            fences: false,
+            footnotes: false,
+            footnote_placement: FootnotePlacement::Document,
+            footnote_refs: None,
+            renumber_comments: false,
+            code_emphasis: false,
+            code_spans: false,
+            headings: false,
+            emphasis_headings: None,
+            wiki_tables: false,
+            rst_tables: false,
+            keep_clean_tables: false,
+            table_style: TableStyle::default(),
+            max_table_width: None,
+            table_overflow: TableOverflow::default(),
+            table_alignment: Vec::new(),
+            cjk_wrap: CjkWrap::default(),
+            hard_breaks: None,
+            sentence_spacing: None,
+            block_exclusions: BlockExclusions::default(),
+            opaque_tokens: OpaqueTokens::default(),
+            protect: BlockProtection::default(),
+            transforms: TransformRegistry::default(),
+        }
+    }
+}
+
+impl Options {
+    /// Switches the stage named by `transform` on or off.
+    ///
+    /// Stages with a plain switch, such as [`Transform::Wrap`], go either way.
+    /// Stages configured by a value, such as [`Transform::HardBreaks`], can
+    /// only be switched off, since switching them on needs the value. Returns
+    /// `false` and leaves `self` unchanged for any other combination,
+    /// including table reflow, which always runs, and the stages only the
+    /// command-line tool provides.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::{Options, changes::Transform};
+    ///
+    /// let mut opts = Options::default();
+    /// assert!(opts.set_enabled(Transform::Footnotes, true));
+    /// assert!(opts.footnotes);
+    /// assert!(!opts.set_enabled(Transform::Tables, false));
+    /// ```
+    pub fn set_enabled(&mut self, transform: Transform, enabled: bool) -> bool {
+        let switch = match transform {
+            Transform::Fences => &mut self.fences,
+            Transform::WikiTables => &mut self.wiki_tables,
+            Transform::RstTables => &mut self.rst_tables,
+            Transform::CodeSpans => &mut self.code_spans,
+            Transform::Headings => &mut self.headings,
+            Transform::CodeEmphasis => &mut self.code_emphasis,
+            Transform::Rejoin => &mut self.rejoin,
+            Transform::Wrap => &mut self.wrap,
+            Transform::Ellipsis => &mut self.ellipsis,
+            Transform::Footnotes => &mut self.footnotes,
+            Transform::EmphasisHeadings if !enabled => {
+                self.emphasis_headings = None;
+                return true;
+            }
+            Transform::HardBreaks if !enabled => {
+                self.hard_breaks = None;
+                return true;
+            }
+            Transform::FootnoteRefs if !enabled => {
+                self.footnote_refs = None;
+                return true;
+            }
+            Transform::SentenceSpacing if !enabled => {
+                self.sentence_spacing = None;
+                return true;
+            }
+            _ => return false,
+        };
+        *switch = enabled;
+        true
+    }
+}
@@ -0,0 +1,234 @@
+//! A builder that composes pipeline stages explicitly.
+//!
+//! [`super::process_stream_opts`] always runs the built-in stages in a fixed
+//! order and reads which of them to apply from [`Options`] switches.
+//! [`Pipeline`] instead runs exactly the stages its `with_*` methods add, in
+//! the order they were added, so a caller can, for example, wrap before
+//! reflowing tables or run one transform on its own.
+
+use std::convert::Infallible;
+
+use super::{Options, no_hook, run_pipeline};
+use crate::{
+    changes::Transform,
+    footnotes::FootnoteRefCompaction,
+    frontmatter::split_leading_yaml_frontmatter,
+    hard_breaks::HardBreakStyle,
+    sentence_spacing::SentenceSpacing,
+};
+
+/// An ordered list of transforms to apply to a Markdown document.
+///
+/// Each `with_*` method appends one stage. Stages see YAML frontmatter
+/// removed, as with [`super::process_stream_opts`], and HTML blocks, opaque
+/// tokens, and protected blocks stay masked exactly as in the built-in
+/// pipeline.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::Pipeline;
+///
+/// let lines: Vec<String> = ["|a|b|", "|-|-|", "", "Wait..."]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let out = Pipeline::new().with_tables().with_ellipsis().run(&lines);
+/// assert_eq!(out, ["| a   | b   |", "| --- | --- |", "", "Wait…"]);
+/// ```
+#[derive(Clone, Default)]
+pub struct Pipeline {
+    opts: Options,
+    stages: Vec<Transform>,
+}
+
+impl Pipeline {
+    /// Starts an empty pipeline with the default settings.
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Starts an empty pipeline whose stages read their settings, such as
+    /// [`Options::table_style`] or [`Options::protect`], from `opts`.
+    ///
+    /// Every on/off switch in `opts` is turned off, because the stages
+    /// added afterwards decide what runs.
+    #[must_use]
+    pub fn with_settings(mut opts: Options) -> Self {
+        for transform in Transform::ALL {
+            opts.set_enabled(transform, false);
+        }
+        Self {
+            opts,
+            stages: Vec::new(),
+        }
+    }
+
+    fn with(mut self, transform: Transform) -> Self {
+        self.opts.set_enabled(transform, true);
+        self.stages.push(transform);
+        self
+    }
+
+    /// Compresses code fences and attaches orphaned language specifiers.
+    #[must_use]
+    pub fn with_fences(self) -> Self { self.with(Transform::Fences) }
+
+    /// Converts HTML `<table>` elements into Markdown tables.
+    #[must_use]
+    pub fn with_html_tables(self) -> Self { self.with(Transform::HtmlTables) }
+
+    /// Converts Jira and Confluence wiki-markup tables.
+    #[must_use]
+    pub fn with_wiki_tables(self) -> Self { self.with(Transform::WikiTables) }
+
+    /// Converts reStructuredText grid and simple tables.
+    #[must_use]
+    pub fn with_rst_tables(self) -> Self { self.with(Transform::RstTables) }
+
+    /// Normalizes the spacing and delimiters of inline code.
+    #[must_use]
+    pub fn with_code_spans(self) -> Self { self.with(Transform::CodeSpans) }
+
+    /// Reflows Markdown tables.
+    #[must_use]
+    pub fn with_tables(self) -> Self { self.with(Transform::Tables) }
+
+    /// Converts Setext headings into ATX headings.
+    #[must_use]
+    pub fn with_headings(self) -> Self { self.with(Transform::Headings) }
+
+    /// Converts bold-only paragraphs into ATX headings of `level`.
+    #[must_use]
+    pub fn with_emphasis_headings(mut self, level: u8) -> Self {
+        self.opts.emphasis_headings = Some(level);
+        self.with(Transform::EmphasisHeadings)
+    }
+
+    /// Repairs emphasis markers adjoining inline code.
+    #[must_use]
+    pub fn with_code_emphasis(self) -> Self { self.with(Transform::CodeEmphasis) }
+
+    /// Rewrites hard line breaks in paragraphs to `style`.
+    #[must_use]
+    pub fn with_hard_breaks(mut self, style: HardBreakStyle) -> Self {
+        self.opts.hard_breaks = Some(style);
+        self.with(Transform::HardBreaks)
+    }
+
+    /// Rewrites the gaps between sentences to `spacing`.
+    #[must_use]
+    pub fn with_sentence_spacing(mut self, spacing: SentenceSpacing) -> Self {
+        self.opts.sentence_spacing = Some(spacing);
+        self.with(Transform::SentenceSpacing)
+    }
+
+    /// Merges paragraph lines broken mid-sentence.
+    #[must_use]
+    pub fn with_rejoin(self) -> Self { self.with(Transform::Rejoin) }
+
+    /// Wraps paragraphs and list items to `width` columns.
+    #[must_use]
+    pub fn with_wrap(mut self, width: usize) -> Self {
+        self.opts.wrap_width = width;
+        self.with(Transform::Wrap)
+    }
+
+    /// Replaces textual ellipses with the ellipsis character.
+    #[must_use]
+    pub fn with_ellipsis(self) -> Self { self.with(Transform::Ellipsis) }
+
+    /// Converts bare numeric references into footnote links.
+    #[must_use]
+    pub fn with_footnotes(self) -> Self { self.with(Transform::Footnotes) }
+
+    /// Removes repeated footnote references, merging the runs `policy`
+    /// chooses.
+    #[must_use]
+    pub fn with_footnote_refs(mut self, policy: FootnoteRefCompaction) -> Self {
+        self.opts.footnote_refs = Some(policy);
+        self.with(Transform::FootnoteRefs)
+    }
+
+    /// Renumbers ordered lists, as [`renumber_lists`](crate::renumber_lists)
+    /// does, honouring `<!-- mdtablefix: renumber … -->` comments when
+    /// [`Options::renumber_comments`] is set.
+    #[must_use]
+    pub fn with_renumber(self) -> Self { self.with(Transform::Renumber) }
+
+    /// Normalizes thematic breaks, as [`format_breaks`](crate::format_breaks)
+    /// does.
+    #[must_use]
+    pub fn with_breaks(self) -> Self { self.with(Transform::Breaks) }
+
+    /// Returns the stages in the order [`Pipeline::run`] applies them.
+    #[must_use]
+    pub fn stages(&self) -> &[Transform] { &self.stages }
+
+    /// Applies the stages to `lines`, keeping any leading YAML frontmatter
+    /// unchanged.
+    #[must_use]
+    pub fn run(&self, lines: &[String]) -> Vec<String> {
+        let (frontmatter, body) = split_leading_yaml_frontmatter(lines);
+        let out = run_pipeline(body, &self.stages, &self.opts, None, no_hook)
+            .unwrap_or_else(|never: Infallible| match never {});
+        [frontmatter, out.as_slice()].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for the pipeline builder.
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[test]
+    fn runs_only_the_stages_added() {
+        let input = lines("|a|b|\n\nWait...");
+        assert_eq!(
+            Pipeline::new().with_ellipsis().run(&input),
+            lines("|a|b|\n\nWait…")
+        );
+        assert_eq!(Pipeline::new().run(&input), input);
+    }
+
+    #[test]
+    fn settings_switches_are_ignored() {
+        let opts = Options {
+            ellipsis: true,
+            wrap: true,
+            ..Options::default()
+        };
+        let input = lines("Wait...");
+        assert_eq!(Pipeline::with_settings(opts).run(&input), input);
+    }
+
+    #[test]
+    fn renumbers_lists_and_normalizes_breaks() {
+        let input = lines("1. a\n3. b\n\n***\n\n<div>\n1. a\n3. b\n</div>");
+        let out = Pipeline::new().with_renumber().with_breaks().run(&input);
+        let thematic_break = "_".repeat(crate::breaks::THEMATIC_BREAK_LEN);
+        assert_eq!(
+            out,
+            [
+                "1. a",
+                "2. b",
+                "",
+                thematic_break.as_str(),
+                "",
+                "<div>",
+                "1. a",
+                "3. b",
+                "</div>",
+            ]
+        );
+    }
+
+    #[test]
+    fn stages_run_in_the_order_added() {
+        let pipeline = Pipeline::new().with_wrap(20).with_rejoin();
+        assert_eq!(pipeline.stages(), [Transform::Wrap, Transform::Rejoin]);
+        let input = lines("A sentence long enough to wrap at twenty columns.");
+        assert_eq!(pipeline.run(&input), input);
+    }
+}
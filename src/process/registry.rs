@@ -0,0 +1,320 @@
+//! Caller-supplied passes spliced into the pipeline.
+//!
+//! A [`LineTransform`] rewrites a whole document. Downstream crates implement
+//! it for their own passes and add them to a [`TransformRegistry`] after one
+//! of the built-in stages; [`Options::transforms`] carries the registry into
+//! [`process_stream_opts`](super::process_stream_opts) and [`Pipeline`](super::Pipeline),
+//! which run each pass straight after its stage. [`BuiltinStage`] implements
+//! the trait for the built-in stages, so they can be registered too, for
+//! example to run ellipsis replacement a second time after a custom pass.
+
+use std::{fmt, sync::Arc};
+
+use super::{
+    Options,
+    stages::{is_library_stage, run_stage},
+};
+use crate::{changes::Transform, html_blocks::HtmlMask};
+
+/// A pass that rewrites the lines of a Markdown document.
+///
+/// Passes see the document without YAML frontmatter, with HTML blocks and
+/// the blocks [`Options::protect`] accepts masked as for built-in stages.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{
+///     Options,
+///     changes::Transform,
+///     process::{LineTransform, TransformRegistry},
+///     process_stream_opts,
+/// };
+///
+/// struct Shout;
+///
+/// impl LineTransform for Shout {
+///     fn name(&self) -> &str { "shout" }
+///
+///     fn apply(&self, lines: &[String]) -> Vec<String> {
+///         lines.iter().map(|line| line.replace("!", "!!")).collect()
+///     }
+/// }
+///
+/// let opts = Options {
+///     transforms: TransformRegistry::new().after(Transform::Tables, Shout),
+///     ..Options::default()
+/// };
+/// let lines = vec!["Hello!".to_string()];
+/// assert_eq!(process_stream_opts(&lines, opts), ["Hello!!"]);
+/// ```
+pub trait LineTransform: Send + Sync {
+    /// A short name for the pass, such as `"smart-quotes"`, used in logs.
+    fn name(&self) -> &str;
+
+    /// The transform a [`ChangeLog`](crate::changes::ChangeLog) records the
+    /// pass's edits under. Passes other than built-in stages are recorded as
+    /// [`Transform::Plugin`].
+    fn transform(&self) -> Transform { Transform::Plugin }
+
+    /// Returns the rewritten document.
+    fn apply(&self, lines: &[String]) -> Vec<String>;
+}
+
+/// One built-in stage, configured by its own [`Options`], as a
+/// [`LineTransform`].
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{
+///     Options,
+///     changes::Transform,
+///     process::{BuiltinStage, LineTransform},
+/// };
+///
+/// let ellipsis = BuiltinStage::new(Transform::Ellipsis, Options::default())?;
+/// assert_eq!(ellipsis.name(), "ellipsis");
+/// assert_eq!(ellipsis.apply(&["Wait...".to_string()]), ["Wait…"]);
+/// assert!(BuiltinStage::new(Transform::Includes, Options::default()).is_err());
+/// # Ok::<(), mdtablefix::process::UnsupportedStage>(())
+/// ```
+#[derive(Clone)]
+pub struct BuiltinStage {
+    transform: Transform,
+    opts: Options,
+}
+
+impl BuiltinStage {
+    /// Wraps the stage `transform`, switched on in `opts`.
+    ///
+    /// Stages configured by a value, such as [`Transform::HardBreaks`], read
+    /// it from `opts` and leave the document alone when it is unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnsupportedStage`] for the stages only the command-line tool
+    /// provides, such as [`Transform::Includes`], and for
+    /// [`Transform::HtmlTables`] without the `html` feature.
+    pub fn new(transform: Transform, mut opts: Options) -> Result<Self, UnsupportedStage> {
+        if !is_library_stage(transform) {
+            return Err(UnsupportedStage(transform));
+        }
+        opts.set_enabled(transform, true);
+        Ok(Self { transform, opts })
+    }
+}
+
+/// The error [`BuiltinStage::new`] returns for a stage the library does not
+/// implement.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{changes::Transform, process::UnsupportedStage};
+///
+/// assert_eq!(
+///     UnsupportedStage(Transform::Titles).to_string(),
+///     "the titles stage is only available in the command-line tool"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnsupportedStage(pub Transform);
+
+impl fmt::Display for UnsupportedStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the {} stage is only available in the command-line tool",
+            self.0.name()
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedStage {}
+
+impl LineTransform for BuiltinStage {
+    fn name(&self) -> &str { self.transform.name() }
+
+    fn transform(&self) -> Transform { self.transform }
+
+    fn apply(&self, lines: &[String]) -> Vec<String> {
+        // `new` only accepts library stages, so `None` means the stage's value
+        // is unset in `opts`.
+        run_stage(self.transform, lines, &self.opts).unwrap_or_else(|| lines.to_vec())
+    }
+}
+
+/// The caller-supplied passes to run after each built-in stage.
+///
+/// Passes registered after the same stage run in the order they were added.
+/// Passes follow a stage whose [`Options`] switch is off as well, but not a
+/// stage a [`Pipeline`](super::Pipeline) leaves out or one the library
+/// pipeline never runs, such as [`Transform::Includes`].
+#[derive(Clone, Default)]
+pub struct TransformRegistry(Vec<(Transform, Arc<dyn LineTransform>)>);
+
+impl TransformRegistry {
+    /// Returns an empty registry.
+    #[must_use]
+    pub fn new() -> Self { Self::default() }
+
+    /// Adds `pass` to run straight after the stage `stage`.
+    #[must_use]
+    pub fn after(mut self, stage: Transform, pass: impl LineTransform + 'static) -> Self {
+        self.0.push((stage, Arc::new(pass)));
+        self
+    }
+
+    /// Returns `true` when no pass is registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+    /// Returns the passes registered after `stage`, in the order added.
+    pub fn passes_after(&self, stage: Transform) -> impl Iterator<Item = &dyn LineTransform> {
+        self.0
+            .iter()
+            .filter(move |(after, _)| *after == stage)
+            .map(|(_, pass)| pass.as_ref())
+    }
+}
+
+impl fmt::Debug for TransformRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.0
+                    .iter()
+                    .map(|(stage, pass)| (stage.name(), pass.name())),
+            )
+            .finish()
+    }
+}
+
+/// Applies `pass` to `lines` with protected and HTML blocks masked, falling
+/// back to the unmasked lines when the pass loses a placeholder.
+pub(super) fn run_pass(pass: &dyn LineTransform, lines: &[String], opts: &Options) -> Vec<String> {
+    let Some(mask) = opts.protect.mask(lines) else {
+        return run_outside_html(pass, lines);
+    };
+    let out = run_outside_html(pass, mask.lines());
+    mask.unmask(out).unwrap_or_else(|| lines.to_vec())
+}
+
+fn run_outside_html(pass: &dyn LineTransform, lines: &[String]) -> Vec<String> {
+    let mask = HtmlMask::new(lines);
+    if mask.is_empty() {
+        return pass.apply(lines);
+    }
+    mask.unmask(pass.apply(mask.lines()))
+        .unwrap_or_else(|| pass.apply(lines))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for registered passes.
+
+    use super::*;
+    use crate::{
+        changes::ChangeLog,
+        process::{Pipeline, process_stream_inner_with_changes},
+        test_support::lines,
+    };
+
+    struct Append(&'static str);
+
+    impl LineTransform for Append {
+        fn name(&self) -> &str { self.0 }
+
+        fn apply(&self, lines: &[String]) -> Vec<String> {
+            lines
+                .iter()
+                .map(|line| format!("{line}{}", self.0))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn passes_run_after_their_stage_in_order() {
+        let registry = TransformRegistry::new()
+            .after(Transform::Ellipsis, Append("b"))
+            .after(Transform::Wrap, Append("a"))
+            .after(Transform::Ellipsis, Append("c"));
+        let opts = Options {
+            transforms: registry,
+            ..Options::default()
+        };
+        let mut log = ChangeLog::default();
+        let out = process_stream_inner_with_changes(&lines("x..."), opts, &mut log);
+        assert_eq!(out, lines("x...abc"));
+        assert!(
+            log.changes()
+                .iter()
+                .all(|change| change.transform == Transform::Plugin)
+        );
+    }
+
+    #[test]
+    fn builtin_stages_record_their_own_transform() {
+        let opts = Options {
+            transforms: TransformRegistry::new().after(
+                Transform::Wrap,
+                BuiltinStage::new(Transform::Ellipsis, Options::default())
+                    .expect("ellipsis replacement is a library stage"),
+            ),
+            ..Options::default()
+        };
+        let mut log = ChangeLog::default();
+        let out = process_stream_inner_with_changes(&lines("x..."), opts, &mut log);
+        assert_eq!(out, lines("x…"));
+        assert_eq!(log.changes()[0].transform, Transform::Ellipsis);
+    }
+
+    #[test]
+    fn builtin_stages_reject_command_line_stages() {
+        for transform in [
+            Transform::Includes,
+            Transform::Titles,
+            Transform::AbbreviateHeaders,
+            Transform::ListMarkers,
+            Transform::HeadingSpacing,
+            Transform::BlankLines,
+            Transform::Plugin,
+        ] {
+            assert_eq!(
+                BuiltinStage::new(transform, Options::default()).err(),
+                Some(UnsupportedStage(transform))
+            );
+        }
+    }
+
+    struct Capitalize;
+
+    impl LineTransform for Capitalize {
+        fn name(&self) -> &'static str { "capitalize" }
+
+        fn apply(&self, lines: &[String]) -> Vec<String> {
+            lines
+                .iter()
+                .map(|line| {
+                    if line == "b" {
+                        "B".to_string()
+                    } else {
+                        line.clone()
+                    }
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn passes_skip_html_blocks() {
+        let opts = Options {
+            transforms: TransformRegistry::new().after(Transform::Fences, Capitalize),
+            ..Options::default()
+        };
+        let pipeline = Pipeline::with_settings(opts).with_fences();
+        let out = pipeline.run(&lines("b\n\n<div>\nb\n</div>"));
+        assert_eq!(out, lines("B\n\n<div>\nb\n</div>"));
+    }
+}
@@ -0,0 +1,193 @@
+//! Built-in pipeline stages and the options that enable them.
+//!
+//! [`STAGES`] fixes the order in which [`super::run_pipeline`] applies the
+//! transforms, and [`run_stage`] maps each [`Transform`] to its function.
+
+use std::borrow::Cow;
+
+use super::{Options, buffer::ProcessBuffer};
+use crate::{
+    changes::Transform,
+    ellipsis::replace_ellipsis,
+    fences::{FenceState, attach_orphan_specifiers, compress_fences},
+    footnotes::{convert_footnotes_with, convert_footnotes_with_comments},
+    html_blocks::HtmlMask,
+    lists::ListContinuation,
+    wrap::wrap_text_cjk,
+};
+
+/// Built-in stages in the order [`super::run_pipeline`] applies them.
+pub(super) const STAGES: [Transform; 16] = [
+    Transform::Fences,
+    Transform::HtmlTables,
+    Transform::WikiTables,
+    Transform::RstTables,
+    Transform::CodeSpans,
+    Transform::Tables,
+    Transform::Headings,
+    Transform::EmphasisHeadings,
+    Transform::CodeEmphasis,
+    Transform::HardBreaks,
+    Transform::SentenceSpacing,
+    Transform::Rejoin,
+    Transform::Wrap,
+    Transform::Ellipsis,
+    Transform::Footnotes,
+    Transform::FootnoteRefs,
+];
+
+/// Returns `true` when [`run_stage`] implements `transform`, rather than it
+/// being one of the stages only the command-line tool provides.
+pub(super) fn is_library_stage(transform: Transform) -> bool {
+    match transform {
+        Transform::Renumber | Transform::Breaks => true,
+        Transform::HtmlTables => cfg!(feature = "html"),
+        _ => STAGES.contains(&transform),
+    }
+}
+
+/// Applies `transform` to `lines`, or returns `None` when `opts` disables it.
+///
+/// List renumbering and thematic break normalization have no switch in
+/// [`Options`]: [`STAGES`] leaves them out, and a
+/// [`Pipeline`](super::Pipeline) that adds them always runs them.
+///
+/// The blocks [`Options::protect`] accepts are masked first, and the stage's
+/// input is kept whole if the transform loses one of their placeholders.
+pub(super) fn run_stage(
+    transform: Transform,
+    lines: &[String],
+    opts: &Options,
+) -> Option<Vec<String>> {
+    let Some(mask) = opts.protect.mask(lines) else {
+        return run_unprotected(transform, lines, opts);
+    };
+    let out = run_unprotected(transform, mask.lines(), opts)?;
+    Some(mask.unmask(out).unwrap_or_else(|| lines.to_vec()))
+}
+
+/// Applies `transform` to `lines` outside protected blocks.
+///
+/// Lines of the blocks that [`Options::block_exclusions`] protects from
+/// `transform` keep their original text, as do HTML blocks, which every stage
+/// but HTML table conversion sees through an [`HtmlMask`]. The wrap and table
+/// stages also see each of [`Options::opaque_tokens`] as one unbreakable word.
+fn run_unprotected(transform: Transform, lines: &[String], opts: &Options) -> Option<Vec<String>> {
+    let mask = (transform != Transform::HtmlTables)
+        .then(|| HtmlMask::new(lines))
+        .filter(|mask| !mask.is_empty());
+    let out = if let Some(mask) = mask {
+        let masked = apply_opaque(transform, mask.lines(), opts)?;
+        match mask.unmask(masked) {
+            Some(out) => out,
+            None => apply_opaque(transform, lines, opts)?,
+        }
+    } else {
+        apply_opaque(transform, lines, opts)?
+    };
+    Some(opts.block_exclusions.restore(transform, lines, out))
+}
+
+/// Applies `transform` with the opaque tokens of `opts` masked when it is the
+/// wrap or table stage, falling back to the unmasked lines when the transform
+/// loses a placeholder.
+fn apply_opaque(transform: Transform, lines: &[String], opts: &Options) -> Option<Vec<String>> {
+    let mask = matches!(transform, Transform::Wrap | Transform::Tables)
+        .then(|| opts.opaque_tokens.mask(lines))
+        .flatten();
+    let Some(mask) = mask else {
+        return apply_transform(transform, lines, opts);
+    };
+    let masked = apply_transform(transform, mask.lines(), opts)?;
+    mask.unmask(masked)
+        .or_else(|| apply_transform(transform, lines, opts))
+}
+
+fn apply_transform(transform: Transform, lines: &[String], opts: &Options) -> Option<Vec<String>> {
+    Some(match transform {
+        Transform::Fences if opts.fences => attach_orphan_specifiers(&compress_fences(lines)),
+        #[cfg(feature = "html")]
+        Transform::HtmlTables => crate::html::convert_html_tables(lines),
+        Transform::WikiTables if opts.wiki_tables => crate::wikimarkup::convert_wiki_tables(lines),
+        Transform::RstTables if opts.rst_tables => crate::rst::convert_rst_tables(lines),
+        Transform::CodeSpans if opts.code_spans => crate::code_spans::normalize_code_spans(lines),
+        Transform::Tables => reflow_tables(&crate::hide_columns::hide_columns(lines), opts),
+        Transform::Headings if opts.headings => crate::headings::convert_setext_headings(lines),
+        Transform::EmphasisHeadings => {
+            crate::headings::convert_emphasis_headings(lines, opts.emphasis_headings?)
+        }
+        Transform::CodeEmphasis if opts.code_emphasis => {
+            crate::code_emphasis::fix_code_emphasis(lines)
+        }
+        Transform::HardBreaks => {
+            crate::hard_breaks::normalize_hard_breaks(lines, opts.hard_breaks?)
+        }
+        Transform::SentenceSpacing => {
+            crate::sentence_spacing::normalize_sentence_spacing(lines, opts.sentence_spacing?)
+        }
+        Transform::Rejoin if opts.rejoin => crate::rejoin::rejoin_lines(lines),
+        Transform::Wrap if opts.wrap => wrap_text_cjk(lines, opts.wrap_width, opts.cjk_wrap),
+        Transform::Ellipsis if opts.ellipsis => replace_ellipsis(lines),
+        Transform::Footnotes if opts.footnotes && opts.renumber_comments => {
+            convert_footnotes_with_comments(lines, opts.footnote_placement)
+        }
+        Transform::Footnotes if opts.footnotes => {
+            convert_footnotes_with(lines, opts.footnote_placement)
+        }
+        Transform::FootnoteRefs => {
+            crate::footnotes::compact_footnote_refs(lines, opts.footnote_refs?)
+        }
+        Transform::Renumber if opts.renumber_comments => {
+            crate::lists::renumber_lists_with_comments(lines, ListContinuation::Restart)
+        }
+        Transform::Renumber => crate::lists::renumber_lists(lines),
+        Transform::Breaks => crate::breaks::format_breaks(lines)
+            .into_iter()
+            .map(Cow::into_owned)
+            .collect(),
+        _ => return None,
+    })
+}
+
+fn reflow_tables(lines: &[String], opts: &Options) -> Vec<String> {
+    buffer_tables(lines, ProcessBuffer::new(opts.clone())).into_out()
+}
+
+/// Feeds `lines` through `state`, which flushes each table run it detects.
+pub(super) fn buffer_tables(lines: &[String], mut state: ProcessBuffer) -> ProcessBuffer {
+    // Track fences so subsequent logic respects shared semantics.
+    let mut fence_tracker = FenceState::default();
+
+    let mut grid_end = 0;
+    for (index, line) in lines.iter().enumerate() {
+        let fence = fence_tracker.observe_source_line(line);
+        if state.handle_fence_line(line, fence.is_fence_marker) {
+            continue;
+        }
+
+        if fence.is_in_fence {
+            state.push_out(line.clone());
+            continue;
+        }
+
+        // RST grid tables are not pipe tables; keep their rows intact.
+        if index >= grid_end {
+            grid_end = crate::rst::grid_table_end(lines, index).unwrap_or(0);
+        }
+        if index < grid_end {
+            state.flush();
+            state.push_out(line.clone());
+            continue;
+        }
+
+        let Some(line) = state.handle_table_line(line.clone()) else {
+            continue;
+        };
+
+        state.flush();
+        state.push_out(line);
+    }
+
+    state.flush();
+    state
+}
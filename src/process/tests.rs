@@ -0,0 +1,120 @@
+//! Unit tests for Markdown processing.
+
+use super::*;
+
+#[cfg(feature = "html")]
+#[test]
+fn processes_html_and_tables() {
+    let input = vec![
+        "<table><tr><td>A</td><td>B</td></tr></table>".to_string(),
+        "| X | Y |".to_string(),
+        "|---|---|".to_string(),
+        "| 1 | 2 |".to_string(),
+    ];
+    let output = process_stream(&input);
+    assert!(output.iter().any(|l| l.contains("| A   | B   |")));
+    assert!(output.iter().any(|l| l.contains("| X   | Y   |")));
+}
+
+#[test]
+fn no_wrap_option() {
+    let input = vec!["| a | b |".to_string(), "| 1 | 2 |".to_string()];
+    let out = process_stream_no_wrap(&input);
+    assert_eq!(out, vec!["| a | b |", "| 1 | 2 |"]);
+}
+
+#[test]
+fn integrates_code_emphasis_flag() {
+    let input = vec!["`X`** Y (in **`Z`**)**".to_string()];
+    let out = process_stream_inner(
+        &input,
+        Options {
+            code_emphasis: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(out, vec!["**`X` Y (in `Z`)**"]);
+}
+
+#[test]
+fn converts_headings_when_enabled() {
+    let input = vec![
+        "Heading".to_string(),
+        "====".to_string(),
+        "Paragraph".to_string(),
+    ];
+    let disabled = process_stream_inner(
+        &input,
+        Options {
+            headings: false,
+            ..Default::default()
+        },
+    );
+    assert_eq!(disabled, input);
+
+    let enabled = process_stream_inner(
+        &input,
+        Options {
+            headings: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        enabled,
+        vec!["# Heading".to_string(), "Paragraph".to_string()]
+    );
+}
+
+#[test]
+fn process_stream_inner_applies_table_ellipsis_before_reflow() {
+    let input = vec![
+        "| example | value |".to_string(),
+        "| ------- | ----- |".to_string(),
+        "| ... | tail |".to_string(),
+    ];
+
+    let with_ellipsis = process_stream_inner(
+        &input,
+        Options {
+            ellipsis: true,
+            ..Default::default()
+        },
+    );
+    let without_ellipsis = process_stream_inner(&input, Options::default());
+
+    assert!(with_ellipsis.iter().any(|line| line.contains('…')));
+    assert!(!with_ellipsis.iter().any(|line| line.contains("...")));
+    assert!(without_ellipsis.iter().any(|line| line.contains("...")));
+    assert!(!without_ellipsis.iter().any(|line| line.contains('…')));
+}
+
+#[test]
+fn records_changes_per_stage() {
+    let input = vec![
+        "Heading".to_string(),
+        "=======".to_string(),
+        "|a|b|".to_string(),
+        "|1|2|".to_string(),
+    ];
+    let mut changes = ChangeLog::default();
+    let out = process_stream_inner_with_changes(
+        &input,
+        Options {
+            headings: true,
+            ..Default::default()
+        },
+        &mut changes,
+    );
+    assert_eq!(
+        out,
+        process_stream_inner(
+            &input,
+            Options {
+                headings: true,
+                ..Default::default()
+            }
+        )
+    );
+    let transforms: Vec<_> = changes.changes().iter().map(|c| c.transform).collect();
+    assert_eq!(transforms, vec![Transform::Tables, Transform::Headings]);
+}
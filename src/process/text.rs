@@ -0,0 +1,90 @@
+//! Processing of Markdown held as one string.
+//!
+//! The line-based functions leave splitting and joining to the caller, which
+//! is easy to get subtly wrong: `\r\n` endings and a missing final newline
+//! are lost by [`str::lines`]. [`process_str`] and [`process_str_to`] parse
+//! the text into a [`Document`], run the pipeline, and restore its layout.
+
+use std::fmt;
+
+use super::{Options, process_stream_opts};
+use crate::document::Document;
+
+/// Formats the Markdown `text` with `opts`, keeping its line ending and
+/// whether it ends with a newline.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{Options, process_str};
+///
+/// let opts = Options {
+///     ellipsis: true,
+///     ..Options::default()
+/// };
+/// assert_eq!(
+///     process_str("|a|b|\r\nWait...\r\n", opts),
+///     "| a | b |\r\nWait…\r\n"
+/// );
+/// ```
+#[must_use]
+pub fn process_str(text: &str, opts: Options) -> String {
+    let doc = Document::parse(text);
+    doc.with_lines(process_stream_opts(&doc.lines, opts))
+        .render()
+}
+
+/// Formats the Markdown `text` with `opts` like [`process_str`], writing the
+/// result to `out` line by line instead of building it as one string.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{Options, process_str_to};
+///
+/// let mut out = String::from("<!-- formatted -->\n");
+/// process_str_to("|a|b|", Options::default(), &mut out)?;
+/// assert_eq!(out, "<!-- formatted -->\n| a | b |");
+/// # Ok::<(), std::fmt::Error>(())
+/// ```
+pub fn process_str_to<W: fmt::Write>(text: &str, opts: Options, out: &mut W) -> fmt::Result {
+    let doc = Document::parse(text);
+    let lines = process_stream_opts(&doc.lines, opts);
+    let ending = doc.line_ending.as_str();
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            out.write_str(ending)?;
+        }
+        out.write_str(line)?;
+    }
+    if doc.trailing_newline && !lines.is_empty() {
+        out.write_str(ending)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for string processing.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::blank_line("\n", "\n")]
+    #[case::unterminated("|a|b|", "| a | b |")]
+    #[case::crlf("|a|b|\r\n|1|2|\r\n", "| a | b |\r\n| 1 | 2 |\r\n")]
+    #[case::frontmatter("---\ntitle: x\n---\n|a|b|\n", "---\ntitle: x\n---\n| a | b |\n")]
+    fn keeps_the_layout_of_the_text(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(process_str(input, Options::default()), expected);
+        let mut out = String::new();
+        process_str_to(input, Options::default(), &mut out).expect("writing to a string");
+        assert_eq!(out, expected);
+    }
+}
@@ -0,0 +1,212 @@
+//! [`Options::validate`] and the [`OptionsWarning`]s it reports.
+
+use std::fmt;
+
+use super::Options;
+use crate::{
+    footnotes::FootnotePlacement,
+    table::{TableOverflow, TableStyle},
+    wrap::CjkWrap,
+};
+
+impl Options {
+    /// Returns a warning for each setting that has no effect, or whose
+    /// result depends on the order the pipeline runs its stages in.
+    ///
+    /// The options are still valid: processing applies them as documented.
+    /// The warnings explain output that might otherwise look like a bug.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::{Options, OptionsWarning};
+    ///
+    /// let opts = Options {
+    ///     rejoin: true,
+    ///     ..Options::default()
+    /// };
+    /// assert_eq!(opts.validate(), [OptionsWarning::RejoinWithoutWrap]);
+    /// assert!(Options::default().validate().is_empty());
+    /// ```
+    #[must_use]
+    pub fn validate(&self) -> Vec<OptionsWarning> {
+        let mut warnings = Vec::new();
+        if self.footnotes && self.wrap {
+            warnings.push(OptionsWarning::FootnotesAfterWrap);
+        }
+        if self.rejoin && !self.wrap {
+            warnings.push(OptionsWarning::RejoinWithoutWrap);
+        }
+        if self.cjk_wrap != CjkWrap::default() && !self.wrap {
+            warnings.push(OptionsWarning::CjkWrapWithoutWrap);
+        }
+        if !self.footnotes && self.footnote_placement != FootnotePlacement::Document {
+            warnings.push(OptionsWarning::PlacementWithoutFootnotes);
+        }
+        if !self.footnotes && self.renumber_comments {
+            warnings.push(OptionsWarning::RenumberCommentsWithoutFootnotes);
+        }
+        if self.max_table_width.is_some() && self.table_style == TableStyle::EdgesOnly {
+            warnings.push(OptionsWarning::EdgesOnlyMaxWidth);
+        }
+        if self.max_table_width.is_some() && self.table_style == TableStyle::Compact {
+            warnings.push(OptionsWarning::CompactMaxWidth);
+        }
+        if self.max_table_width.is_none() && self.table_overflow != TableOverflow::Keep {
+            warnings.push(OptionsWarning::OverflowWithoutMaxWidth);
+        }
+        if let Some(level) = self
+            .emphasis_headings
+            .filter(|level| !(1..=6).contains(level))
+        {
+            warnings.push(OptionsWarning::EmphasisHeadingLevel(level));
+        }
+        warnings
+    }
+}
+
+/// A combination of [`Options`] that [`Options::validate`] reports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OptionsWarning {
+    /// Footnote conversion runs after wrapping, so a line whose references
+    /// it converts can end up wider than [`Options::wrap_width`].
+    FootnotesAfterWrap,
+    /// [`Options::rejoin`] merges broken lines without
+    /// [`Options::wrap`], which leaves each merged paragraph on one line.
+    RejoinWithoutWrap,
+    /// [`Options::cjk_wrap`] is set without [`Options::wrap`], so it has no
+    /// effect.
+    CjkWrapWithoutWrap,
+    /// [`Options::footnote_placement`] is set without
+    /// [`Options::footnotes`], so it has no effect.
+    PlacementWithoutFootnotes,
+    /// [`Options::renumber_comments`] is set without
+    /// [`Options::footnotes`], so no definition is renumbered.
+    RenumberCommentsWithoutFootnotes,
+    /// [`TableStyle::EdgesOnly`] does not pad columns, so
+    /// [`Options::max_table_width`] cannot narrow them and only
+    /// [`Options::table_overflow`] applies.
+    EdgesOnlyMaxWidth,
+    /// [`TableStyle::Compact`] does not pad columns, so
+    /// [`Options::max_table_width`] cannot narrow them and only
+    /// [`Options::table_overflow`] applies.
+    CompactMaxWidth,
+    /// [`Options::table_overflow`] is set without
+    /// [`Options::max_table_width`], so no table counts as over-wide.
+    OverflowWithoutMaxWidth,
+    /// [`Options::emphasis_headings`] names a level outside 1 to 6, which is
+    /// clamped into that range.
+    EmphasisHeadingLevel(u8),
+}
+
+impl OptionsWarning {
+    /// Returns `true` for warnings that describe how two settings combine,
+    /// rather than a setting that is ignored or corrected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mdtablefix::OptionsWarning;
+    ///
+    /// assert!(OptionsWarning::FootnotesAfterWrap.is_note());
+    /// assert!(!OptionsWarning::OverflowWithoutMaxWidth.is_note());
+    /// ```
+    #[must_use]
+    pub const fn is_note(self) -> bool {
+        matches!(self, Self::FootnotesAfterWrap | Self::RejoinWithoutWrap)
+    }
+}
+
+impl fmt::Display for OptionsWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FootnotesAfterWrap => f.write_str(
+                "footnote conversion runs after wrapping, so lines whose references it converts \
+                 can exceed the wrap width",
+            ),
+            Self::RejoinWithoutWrap => f.write_str(
+                "rejoining without wrapping leaves each rejoined paragraph on a single line",
+            ),
+            Self::CjkWrapWithoutWrap => {
+                f.write_str("the CJK wrapping mode has no effect without wrapping")
+            }
+            Self::PlacementWithoutFootnotes => {
+                f.write_str("footnote placement has no effect without footnote conversion")
+            }
+            Self::RenumberCommentsWithoutFootnotes => {
+                f.write_str("renumber comments have no effect without footnote conversion")
+            }
+            Self::EdgesOnlyMaxWidth => f.write_str(
+                "edges-only tables are not padded, so the maximum table width cannot narrow their \
+                 columns",
+            ),
+            Self::CompactMaxWidth => f.write_str(
+                "compact tables are not padded, so the maximum table width cannot narrow their \
+                 columns",
+            ),
+            Self::OverflowWithoutMaxWidth => {
+                f.write_str("table overflow handling has no effect without a maximum table width")
+            }
+            Self::EmphasisHeadingLevel(level) => write!(
+                f,
+                "emphasis heading level {level} is outside 1 to 6 and is clamped"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for option validation.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::footnotes_and_wrap(
+        Options { footnotes: true, wrap: true, ..Options::default() },
+        vec![OptionsWarning::FootnotesAfterWrap],
+    )]
+    #[case::rejoin_and_wrap(Options { rejoin: true, wrap: true, ..Options::default() }, vec![])]
+    #[case::overflow(
+        Options { table_overflow: TableOverflow::Scroll, ..Options::default() },
+        vec![OptionsWarning::OverflowWithoutMaxWidth],
+    )]
+    #[case::edges_only(
+        Options {
+            table_style: TableStyle::EdgesOnly,
+            max_table_width: Some(60),
+            table_overflow: TableOverflow::Fence,
+            ..Options::default()
+        },
+        vec![OptionsWarning::EdgesOnlyMaxWidth],
+    )]
+    #[case::compact(
+        Options {
+            table_style: TableStyle::Compact,
+            max_table_width: Some(60),
+            ..Options::default()
+        },
+        vec![OptionsWarning::CompactMaxWidth],
+    )]
+    #[case::placement_and_comments(
+        Options {
+            footnote_placement: FootnotePlacement::Section,
+            renumber_comments: true,
+            ..Options::default()
+        },
+        vec![
+            OptionsWarning::PlacementWithoutFootnotes,
+            OptionsWarning::RenumberCommentsWithoutFootnotes,
+        ],
+    )]
+    #[case::heading_level(
+        Options { emphasis_headings: Some(9), ..Options::default() },
+        vec![OptionsWarning::EmphasisHeadingLevel(9)],
+    )]
+    fn reports_interacting_options(#[case] opts: Options, #[case] expected: Vec<OptionsWarning>) {
+        assert_eq!(opts.validate(), expected);
+    }
+}
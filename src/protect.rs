@@ -0,0 +1,167 @@
+//! Blocks that embedders mark as untouchable.
+//!
+//! Some documents hold blocks that only their own tooling may edit, such as
+//! the block after a proprietary `<!-- vendor:keep -->` pragma.
+//! [`BlockProtection`] wraps a predicate over [`BlockContext`]s. Before each
+//! pipeline stage runs, the document is split into blocks, runs of non-blank
+//! lines with fenced code kept whole, and every block the predicate accepts
+//! is swapped for a placeholder comment in the same way as HTML blocks. The
+//! stage, whether wrapping, table reflow, or footnote renumbering, never sees
+//! the block's lines, which are put back unchanged afterwards.
+
+use std::{fmt, ops::Range, sync::Arc};
+
+use crate::{
+    blocks::{BlockType, line_blocks},
+    fences::FenceState,
+    html_blocks::HtmlMask,
+};
+
+const PLACEHOLDER_PREFIX: &str = "<!-- mdtablefix-protected-block:";
+
+/// A block offered to a [`BlockProtection`] predicate.
+#[derive(Clone, Copy, Debug)]
+pub struct BlockContext<'a> {
+    /// The lines of the block.
+    pub lines: &'a [String],
+    /// Zero-based index of the block's first line among the lines the stage
+    /// receives, which exclude YAML frontmatter.
+    pub start: usize,
+    /// The lines of the preceding block, or `None` for the first block.
+    pub previous: Option<&'a [String]>,
+    /// The outermost block containing the first line, as
+    /// [`line_blocks`] reports it.
+    pub block_type: Option<BlockType>,
+}
+
+type Predicate = dyn Fn(&BlockContext<'_>) -> bool + Send + Sync;
+
+/// A predicate choosing the blocks every transform leaves untouched.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{Options, process_stream_opts, protect::BlockProtection};
+///
+/// let opts = Options {
+///     ellipsis: true,
+///     protect: BlockProtection::new(|block| {
+///         block
+///             .previous
+///             .is_some_and(|previous| previous == ["<!-- vendor:keep -->"])
+///     }),
+///     ..Options::default()
+/// };
+/// let lines: Vec<String> = ["Wait...", "", "<!-- vendor:keep -->", "", "Hold..."]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     process_stream_opts(&lines, opts),
+///     ["Wait…", "", "<!-- vendor:keep -->", "", "Hold..."]
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct BlockProtection(Option<Arc<Predicate>>);
+
+impl BlockProtection {
+    /// Protects the blocks for which `predicate` returns `true`.
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&BlockContext<'_>) -> bool + Send + Sync + 'static,
+    {
+        Self(Some(Arc::new(predicate)))
+    }
+
+    /// Returns `true` when no predicate is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.0.is_none() }
+
+    /// Returns the line ranges of the protected blocks in `lines`.
+    #[must_use]
+    pub fn protected_ranges(&self, lines: &[String]) -> Vec<Range<usize>> {
+        let Some(predicate) = &self.0 else {
+            return Vec::new();
+        };
+        let types = line_blocks(lines);
+        let mut previous = None;
+        let mut ranges = Vec::new();
+        for range in block_ranges(lines) {
+            let block = &lines[range.clone()];
+            let context = BlockContext {
+                lines: block,
+                start: range.start,
+                previous,
+                block_type: types[range.start],
+            };
+            if predicate(&context) {
+                ranges.push(range);
+            }
+            previous = Some(block);
+        }
+        ranges
+    }
+
+    /// Swaps the protected blocks of `lines` for placeholders, or returns
+    /// `None` when no block is protected.
+    pub(crate) fn mask(&self, lines: &[String]) -> Option<HtmlMask> {
+        let ranges = self.protected_ranges(lines);
+        (!ranges.is_empty()).then(|| HtmlMask::with_prefix(lines, ranges, PLACEHOLDER_PREFIX))
+    }
+}
+
+impl fmt::Debug for BlockProtection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BlockProtection")
+            .field(&self.0.as_ref().map(|_| "<predicate>"))
+            .finish()
+    }
+}
+
+/// Splits `lines` into runs of non-blank lines. Blank lines inside fenced
+/// code do not end a block.
+pub(crate) fn block_ranges(lines: &[String]) -> Vec<Range<usize>> {
+    let mut fences = FenceState::default();
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (index, line) in lines.iter().enumerate() {
+        let in_code = fences.observe_source_line(line).is_code();
+        if in_code || !line.trim().is_empty() {
+            start.get_or_insert(index);
+        } else if let Some(first) = start.take() {
+            ranges.push(first..index);
+        }
+    }
+    if let Some(first) = start {
+        ranges.push(first..lines.len());
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for block protection.
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[test]
+    fn splits_blocks_at_blank_lines_outside_fences() {
+        assert_eq!(
+            block_ranges(&lines("a\nb\n\n```\n\n```\n\n\nc")),
+            [0..2, 3..6, 8..9]
+        );
+    }
+
+    #[test]
+    fn offers_each_block_with_its_predecessor() {
+        let protection = BlockProtection::new(|block| {
+            block.block_type == Some(BlockType::Heading)
+                || block.previous.is_some_and(|prev| prev[0] == "keep:")
+        });
+        assert_eq!(
+            protection.protected_ranges(&lines("# A\n\nkeep:\n\nx\n\ny")),
+            [0..1, 4..5]
+        );
+    }
+}
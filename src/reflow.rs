@@ -5,10 +5,12 @@
 
 use unicode_width::UnicodeWidthStr;
 
-use crate::table::{SEP_RE, format_separator_cells, split_cells};
+use crate::table::{format_separator_cells, is_separator_line, split_cells};
 
+mod edges;
 mod row_parsing;
 
+pub(crate) use edges::{format_compact, format_edges_only};
 use row_parsing::{cell_is_semantically_empty, split_physical_rows};
 
 const LEADING_EMPTY_CELL_MARKER: &str = "\u{1d}";
@@ -303,7 +305,7 @@ fn should_use_second_row_as_separator(sep_invalid: bool, rows: &[Vec<String>]) -
 }
 
 fn second_row_is_separator(rows: &[Vec<String>]) -> bool {
-    rows.len() > 1 && rows[1].iter().all(|c| SEP_RE.is_match(c))
+    rows.len() > 1 && rows[1].iter().all(|c| is_separator_line(c))
 }
 
 /// Replaces leading empty cells with a marker so continuation rows survive the
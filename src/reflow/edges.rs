@@ -0,0 +1,101 @@
+//! The unpadded table renderers.
+//!
+//! Only the leading and trailing pipes of an `edges-only` table line up;
+//! inner pipes follow the cell content. A `compact` table lines up nothing.
+
+use unicode_width::UnicodeWidthStr;
+
+use super::escape_literal_pipes;
+use crate::table::format_separator_cells;
+
+/// Formats rows with only their outer pipes aligned.
+///
+/// Cells are separated by single spaces and keep their own widths; the last
+/// cell of each row is padded so every closing pipe lands in the same column.
+/// The separator uses three-dash cells, with its final cell lengthened to
+/// the same edge, and keeps any alignment colons.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let rows = vec![
+///     vec!["a".to_string(), "bb".to_string()],
+///     vec!["ccc".to_string(), "d".to_string()],
+/// ];
+/// let sep = vec!["---".to_string(), ":-".to_string()];
+/// let formatted = mdtablefix::reflow::format_edges_only(&rows, Some(&sep), "");
+///
+/// assert_eq!(formatted, vec!["| a | bb    |", "| --- | :-- |", "| ccc | d   |"]);
+/// ```
+pub(crate) fn format_edges_only(
+    rows: &[Vec<String>],
+    sep_cells: Option<&[String]>,
+    indent: &str,
+) -> Vec<String> {
+    let bodies: Vec<String> = rows.iter().map(|row| row_body(row)).collect();
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let sep_width = (columns * 6).saturating_sub(3);
+    let edge = bodies
+        .iter()
+        .map(|body| UnicodeWidthStr::width(body.as_str()))
+        .chain(sep_cells.map(|_| sep_width))
+        .max()
+        .unwrap_or(0);
+    let mut out: Vec<String> = bodies
+        .iter()
+        .map(|body| {
+            let padding = edge - UnicodeWidthStr::width(body.as_str());
+            format!("{indent}| {body}{} |", " ".repeat(padding))
+        })
+        .collect();
+    if let Some(cells) = sep_cells {
+        let mut widths = vec![3; columns];
+        if let Some(last) = widths.last_mut() {
+            *last += edge - sep_width;
+        }
+        let sep = format_separator_cells(&widths, cells).join(" | ");
+        out.insert(out.len().min(1), format!("{indent}| {sep} |"));
+    }
+    out
+}
+
+/// Formats rows with single spaces around every pipe and no padding.
+///
+/// The separator uses three-dash cells and keeps any alignment colons.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let rows = vec![
+///     vec!["a".to_string(), "bb".to_string()],
+///     vec!["ccc".to_string(), "d".to_string()],
+/// ];
+/// let sep = vec!["---".to_string(), ":-".to_string()];
+/// let formatted = mdtablefix::reflow::format_compact(&rows, Some(&sep), "");
+///
+/// assert_eq!(formatted, vec!["| a | bb |", "| --- | :-- |", "| ccc | d |"]);
+/// ```
+pub(crate) fn format_compact(
+    rows: &[Vec<String>],
+    sep_cells: Option<&[String]>,
+    indent: &str,
+) -> Vec<String> {
+    let mut out: Vec<String> = rows
+        .iter()
+        .map(|row| format!("{indent}| {} |", row_body(row)))
+        .collect();
+    if let Some(cells) = sep_cells {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let sep = format_separator_cells(&vec![3; columns], cells).join(" | ");
+        out.insert(out.len().min(1), format!("{indent}| {sep} |"));
+    }
+    out
+}
+
+/// Joins the cells of `row` with single-spaced pipes, escaping literal pipes.
+fn row_body(row: &[String]) -> String {
+    row.iter()
+        .map(|cell| escape_literal_pipes(cell))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
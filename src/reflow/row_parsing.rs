@@ -1,6 +1,6 @@
 //! Provenance-aware recovery of logical table rows from physical source lines.
 
-use super::{LEADING_EMPTY_CELL_MARKER, SEP_RE};
+use super::{LEADING_EMPTY_CELL_MARKER, is_separator_line};
 
 pub(super) fn cell_is_semantically_empty(cell: &str) -> bool {
     cell.is_empty() || cell == LEADING_EMPTY_CELL_MARKER
@@ -66,7 +66,7 @@ fn has_embedded_separator_row(row: &[String], width: usize) -> bool {
         let start = index * (width + 1);
         row[start..start + width]
             .iter()
-            .all(|cell| cell.contains('-') && SEP_RE.is_match(cell))
+            .all(|cell| cell.contains('-') && is_separator_line(cell))
     })
 }
 
@@ -21,7 +21,14 @@ fn arbitrary_non_empty_cell_strategy() -> BoxedStrategy<String> {
             .prop_map(|characters| {
                 let content = characters.into_iter().collect::<String>();
                 format!("x{content}x")
-            }),
+            })
+            .prop_filter(
+                "split cells only hold pipes preceded by an even backslash run",
+                |cell| {
+                    cell.match_indices('|')
+                        .all(|(index, _)| !crate::textproc::is_escaped(cell, index))
+                },
+            ),
     ]
     .boxed()
 }
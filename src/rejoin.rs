@@ -0,0 +1,214 @@
+//! Rejoin paragraph lines hard-wrapped by older tools.
+//!
+//! Text wrapped at a fixed width by another program often breaks mid-sentence
+//! and sometimes mid-word, hyphenating the fragments ("exam-" / "ple").
+//! [`rejoin_lines`] merges such lines back together so later passes, such as
+//! [`crate::wrap::wrap_text`], can reflow the paragraph cleanly.
+//!
+//! The heuristics are conservative. A line is only merged into the one before
+//! it when both are paragraph or list text at the same blockquote depth, the
+//! earlier line does not end a sentence or carry a hard break, and the later
+//! line does not start a new block. Code, tables, headings, HTML, and
+//! directive comments are never touched.
+
+use std::{collections::HashSet, sync::LazyLock};
+
+use regex::Regex;
+
+use crate::{
+    breaks::THEMATIC_BREAK_RE,
+    fences::FenceState,
+    hard_breaks::{continues_paragraph, is_prose, split_hard_break},
+    wrap::{BlockquotePrefix, LinkReferenceMatcher, has_unclosed_code_span},
+};
+
+/// A word fragment ending in a hyphen at the end of a line.
+static TRAILING_FRAGMENT_RE: LazyLock<Regex> = lazy_regex!(
+    r"(?:^|[^\p{L}\p{N}-])(\p{L}+)-$",
+    "trailing fragment regex should compile"
+);
+
+/// A lowercase word fragment at the start of a line.
+static LEADING_FRAGMENT_RE: LazyLock<Regex> =
+    lazy_regex!(r"^\p{Ll}\p{L}*", "leading fragment regex should compile");
+
+/// A whole word, including any hyphenated parts, so "well-known" never
+/// counts as "well" and "known".
+static WORD_RE: LazyLock<Regex> = lazy_regex!(r"\p{L}+(?:-\p{L}+)*", "word regex should compile");
+
+/// Sentence-ending punctuation followed by any closing quotes, brackets, or
+/// emphasis markers.
+static SENTENCE_END_RE: LazyLock<Regex> = lazy_regex!(
+    r#"[.!?:]["'’”)\]*_]*$"#,
+    "sentence end regex should compile"
+);
+
+/// A Setext heading underline.
+static SETEXT_UNDERLINE_RE: LazyLock<Regex> = lazy_regex!(
+    r"^ {0,3}(?:=+|-+)\s*$",
+    "setext underline regex should compile"
+);
+
+/// Merge paragraph lines that were broken mid-sentence.
+///
+/// A line that does not end with `.`, `!`, `?`, or `:` absorbs the next line
+/// of the same paragraph, separated by a space. When the line ends in a word
+/// fragment and a hyphen, and the next line starts with a lowercase letter,
+/// the fragments are joined into one word. The hyphen is kept, as in
+/// "well-known", unless the document spells the joined word without one
+/// elsewhere. Lines starting with `: ` are definition-list descriptions and
+/// are never joined.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::rejoin::rejoin_lines;
+///
+/// let lines: Vec<String> = [
+///     "This is an exam-",
+///     "ple of text wrapped",
+///     "by an older tool.",
+///     "A new example stays put.",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// assert_eq!(
+///     rejoin_lines(&lines),
+///     [
+///         "This is an example of text wrapped by an older tool.",
+///         "A new example stays put.",
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn rejoin_lines(lines: &[String]) -> Vec<String> {
+    let words = plain_words(lines);
+    let matcher = LinkReferenceMatcher::production();
+    let mut fences = FenceState::default();
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut open = false;
+    for line in lines {
+        if fences.observe_source_line(line).is_code() {
+            out.push(line.clone());
+            open = false;
+            continue;
+        }
+        if open
+            && let Some(last) = out.last_mut()
+            && can_absorb(last, line, matcher)
+        {
+            let next = BlockquotePrefix::parse(line).map_or(line.as_str(), |q| q.inner());
+            *last = join(last, next.trim_start(), &words);
+            continue;
+        }
+        out.push(line.clone());
+        open = is_joinable(line, matcher);
+    }
+    out
+}
+
+/// Returns `true` when `line` is paragraph text that may continue on the
+/// next line.
+fn is_joinable(line: &str, matcher: LinkReferenceMatcher) -> bool {
+    !line.trim().is_empty()
+        && is_prose(line, matcher)
+        && !line.contains('|')
+        && !THEMATIC_BREAK_RE.is_match(line)
+        && !SETEXT_UNDERLINE_RE.is_match(line)
+}
+
+/// Returns `true` when `next` may be appended to `last`.
+fn can_absorb(last: &str, next: &str, matcher: LinkReferenceMatcher) -> bool {
+    let depth = |line| BlockquotePrefix::parse(line).map(|q| q.depth());
+    split_hard_break(last).is_none()
+        && !SENTENCE_END_RE.is_match(last.trim_end())
+        && depth(last) == depth(next)
+        && continues_paragraph(last, next, matcher)
+        && !next.contains('|')
+        && !next.trim_start().starts_with('<')
+        && !next.trim_start().starts_with(": ")
+        && !SETEXT_UNDERLINE_RE.is_match(next)
+}
+
+/// Appends `next` to `last`, closing up a word split by a hyphen.
+///
+/// `next` keeps its trailing whitespace, so a hard break at the end of the
+/// joined line still stops the next join.
+fn join(last: &str, next: &str, words: &HashSet<String>) -> String {
+    let last = last.trim_end();
+    let split_word = TRAILING_FRAGMENT_RE
+        .captures(last)
+        .and_then(|caps| caps.get(1))
+        .zip(LEADING_FRAGMENT_RE.find(next))
+        .filter(|_| !has_unclosed_code_span(last));
+    match split_word {
+        Some((head, tail)) => {
+            let word = format!("{}{}", head.as_str(), tail.as_str()).to_lowercase();
+            if words.contains(&word) {
+                format!("{}{next}", &last[..last.len() - 1])
+            } else {
+                format!("{last}{next}")
+            }
+        }
+        None => format!("{last} {next}"),
+    }
+}
+
+/// Collects the words written without a hyphen anywhere in the document,
+/// lowercased.
+fn plain_words(lines: &[String]) -> HashSet<String> {
+    lines
+        .iter()
+        .flat_map(|line| WORD_RE.find_iter(line))
+        .map(|m| m.as_str().to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for line rejoining.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::mid_sentence("one two\nthree four.", "one two three four.")]
+    #[case::known_word("an exam-\nple here\n\nAn example.", "an example here\n\nAn example.")]
+    #[case::compound("It is a well-\nknown fact", "It is a well-known fact")]
+    #[case::unknown_word("a self-\ncontained tool", "a self-contained tool")]
+    #[case::hard_break_after_join("Line one\nLine two  \nnext", "Line one Line two  \nnext")]
+    #[case::capitalised_tail("see Jean-\nPaul", "see Jean- Paul")]
+    #[case::list_item("- one two\n  three", "- one two three")]
+    #[case::footnote("[^1]: one\n  two", "[^1]: one two")]
+    #[case::blockquote("> one\n> two", "> one two")]
+    #[case::code_span("a `b\nc` d", "a `b c` d")]
+    fn rejoins(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(rejoin_lines(&lines(input)), lines(expected));
+    }
+
+    #[rstest]
+    #[case::sentence_end("One.\nTwo")]
+    #[case::question("Why?\nBecause")]
+    #[case::quoted_end("He said \"no.\"\nThen")]
+    #[case::colon("As follows:\nitems")]
+    #[case::definition("Term\n: def")]
+    #[case::hard_break("one  \ntwo")]
+    #[case::backslash_break("one\\\ntwo")]
+    #[case::blank("one\n\ntwo")]
+    #[case::list("one\n- two")]
+    #[case::heading("one\n# two")]
+    #[case::after_heading("# one\ntwo")]
+    #[case::setext("Title\n=====")]
+    #[case::thematic_break("---\ntext")]
+    #[case::table("| a |\n| b |")]
+    #[case::fence("one\n```\ntwo\n```")]
+    #[case::quote_depth("> one\n> > two")]
+    #[case::html("<div>\ntext\n</div>")]
+    fn leaves_alone(#[case] input: &str) {
+        let input = lines(input);
+        assert_eq!(rejoin_lines(&input), input);
+    }
+}
@@ -0,0 +1,98 @@
+//! Comments recording the number a renumbered item had before.
+//!
+//! During a migration other documents may still cite list items or footnotes
+//! by their old numbers. Renumbering can append `<!-- was 7 -->` to each item
+//! whose number changes, and [`strip_renumber_comments`] removes the comments
+//! once those references are updated. An item keeps the first comment it was
+//! given, so the comment names its number in the source document however
+//! often it is renumbered again.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::fences::FenceState;
+
+/// Matches a renumber comment and the whitespace before it.
+static COMMENT_RE: LazyLock<Regex> = lazy_regex!(
+    r"\s*<!-- was \d+ -->",
+    "renumber comment pattern should compile"
+);
+
+/// Returns `line` with a comment recording `old` appended, unless `old`
+/// equals `new` or the line already carries a comment.
+pub(crate) fn note_old_number(line: &str, old: usize, new: usize) -> String {
+    if old == new || COMMENT_RE.is_match(line) {
+        return line.to_string();
+    }
+    format!("{} <!-- was {old} -->", line.trim_end())
+}
+
+/// Removes the `<!-- was N -->` comments renumbering added, dropping lines
+/// left holding nothing else, such as a comment wrapping moved onto its own
+/// line. Fenced code is left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::renumber_comments::strip_renumber_comments;
+///
+/// let lines = vec!["2. Item <!-- was 7 -->".to_string()];
+/// assert_eq!(strip_renumber_comments(&lines), ["2. Item"]);
+/// ```
+#[must_use]
+pub fn strip_renumber_comments(lines: &[String]) -> Vec<String> {
+    let mut fences = FenceState::default();
+    let mut out = Vec::with_capacity(lines.len());
+    for line in lines {
+        if fences.observe_source_line(line).is_code() || !COMMENT_RE.is_match(line) {
+            out.push(line.clone());
+            continue;
+        }
+        let stripped = COMMENT_RE.replace_all(line, "");
+        if !stripped.trim().is_empty() {
+            out.push(stripped.into_owned());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for renumber comments.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::changed("2. Item", 7, 2, "2. Item <!-- was 7 -->")]
+    #[case::unchanged("2. Item", 2, 2, "2. Item")]
+    #[case::keeps_first_comment("1. Item <!-- was 7 -->", 2, 1, "1. Item <!-- was 7 -->")]
+    fn notes_old_numbers(
+        #[case] line: &str,
+        #[case] old: usize,
+        #[case] new: usize,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(note_old_number(line, old, new), expected);
+    }
+
+    #[test]
+    fn strips_comments_outside_fences() {
+        let lines: Vec<String> = [
+            "1. A <!-- was 3 -->",
+            "   <!-- was 4 -->",
+            "```",
+            "x <!-- was 5 -->",
+            "```",
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        assert_eq!(
+            strip_renumber_comments(&lines),
+            ["1. A", "```", "x <!-- was 5 -->", "```"]
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! Convert reStructuredText grid and simple tables into Markdown tables.
+//!
+//! Documentation migrated from Sphinx keeps RST tables such as
+//!
+//! ```text
+//! +--------+-------+
+//! | Option | Use   |
+//! +========+=======+
+//! | wrap   | prose |
+//! +--------+-------+
+//! ```
+//!
+//! and the `=====  =====` simple table form. [`convert_rst_tables`] parses
+//! both, joins the lines of multi-line cells with spaces, and emits a pipe
+//! table aligned by [`reflow_table`]. Rows above the `+===+` border of a grid
+//! table, or between the first two borders of a simple table, become the
+//! header; several header rows are merged column by column. Tables without a
+//! header promote their first row, because Markdown tables need one.
+//!
+//! Pipe tables cannot express cells that span rows or columns, so such tables
+//! are left unchanged. [`unconvertible_rst_tables`] lists them so callers can
+//! report what still needs converting by hand.
+
+mod grid;
+mod simple;
+
+use std::fmt;
+
+use crate::{
+    fences::FenceState,
+    table::{escape_cell_pipes, reflow_table},
+};
+
+/// A span that prevents an RST table from becoming a pipe table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RstSpan {
+    /// A cell spans more than one row.
+    Row,
+    /// A cell spans more than one column.
+    Column,
+}
+
+impl fmt::Display for RstSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Row => "row spans",
+            Self::Column => "column spans",
+        })
+    }
+}
+
+/// An RST table that [`convert_rst_tables`] left unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnconvertedRstTable {
+    /// One-based line number of the table's top border.
+    pub line: usize,
+    /// The first span found in the table.
+    pub span: RstSpan,
+}
+
+/// Cells parsed from an RST table, before rendering.
+struct ParsedTable {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Outcome of parsing the table that starts at a given line.
+struct TableBlock {
+    /// Exclusive index of the line after the table.
+    end: usize,
+    table: Result<ParsedTable, RstSpan>,
+}
+
+/// Convert RST grid and simple tables outside fenced code into Markdown tables.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::rst::convert_rst_tables;
+///
+/// let lines: Vec<String> = [
+///     "+------+-------+",
+///     "| Name | Value |",
+///     "+======+=======+",
+///     "| a    | 1     |",
+///     "+------+-------+",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// assert_eq!(
+///     convert_rst_tables(&lines),
+///     vec!["| Name | Value |", "| ---- | ----- |", "| a    | 1     |"]
+/// );
+/// ```
+#[must_use]
+pub fn convert_rst_tables(lines: &[String]) -> Vec<String> { scan(lines).0 }
+
+/// List the RST tables that [`convert_rst_tables`] cannot express as pipe
+/// tables because they contain row or column spans.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::rst::{RstSpan, unconvertible_rst_tables};
+///
+/// let lines: Vec<String> = [
+///     "Intro",
+///     "+---+---+",
+///     "| a | b |",
+///     "+---+   +",
+///     "| c |   |",
+///     "+---+---+",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// let unconverted = unconvertible_rst_tables(&lines);
+/// assert_eq!(unconverted[0].line, 2);
+/// assert_eq!(unconverted[0].span, RstSpan::Row);
+/// ```
+#[must_use]
+pub fn unconvertible_rst_tables(lines: &[String]) -> Vec<UnconvertedRstTable> { scan(lines).1 }
+
+/// Returns the index after the RST grid table starting at `lines[start]`.
+///
+/// Table reflow uses this to pass grid tables through verbatim, because their
+/// `| cell |` lines would otherwise be reflowed as one-row pipe tables.
+pub(crate) fn grid_table_end(lines: &[String], start: usize) -> Option<usize> {
+    grid::parse(lines, start).map(|block| block.end)
+}
+
+fn scan(lines: &[String]) -> (Vec<String>, Vec<UnconvertedRstTable>) {
+    let mut fences = FenceState::default();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut unconverted = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        let fence = fences.observe_source_line(line);
+        let block = if fence.is_code() {
+            None
+        } else {
+            grid::parse(lines, index).or_else(|| simple::parse(lines, index))
+        };
+        let Some(block) = block else {
+            out.push(line.clone());
+            index += 1;
+            continue;
+        };
+        for consumed in &lines[index + 1..block.end] {
+            fences.observe_source_line(consumed);
+        }
+        match block.table {
+            Ok(table) => out.extend(render(&table, leading_whitespace(line))),
+            Err(span) => {
+                unconverted.push(UnconvertedRstTable {
+                    line: index + 1,
+                    span,
+                });
+                out.extend_from_slice(&lines[index..block.end]);
+            }
+        }
+        index = block.end;
+    }
+    (out, unconverted)
+}
+
+fn render(table: &ParsedTable, indent: &str) -> Vec<String> {
+    let row = |cells: &[String]| {
+        let escaped: Vec<String> = cells.iter().map(|cell| escape_cell_pipes(cell)).collect();
+        format!("{indent}| {} |", escaped.join(" | "))
+    };
+    let mut lines = vec![
+        row(&table.header),
+        format!("{indent}|{}|", vec![" --- "; table.header.len()].join("|")),
+    ];
+    lines.extend(table.rows.iter().map(|cells| row(cells)));
+    reflow_table(&lines)
+}
+
+/// Promotes the first row to the header when the table has none and merges
+/// several header rows into one.
+fn build_table(header_rows: Vec<Vec<String>>, mut rows: Vec<Vec<String>>) -> Option<ParsedTable> {
+    let header = if header_rows.is_empty() {
+        if rows.is_empty() {
+            return None;
+        }
+        rows.remove(0)
+    } else {
+        merge_rows(header_rows)
+    };
+    Some(ParsedTable { header, rows })
+}
+
+/// Joins rows column by column, separating non-empty fragments with spaces.
+fn merge_rows(rows: Vec<Vec<String>>) -> Vec<String> {
+    let mut merged: Vec<String> = Vec::new();
+    for row in rows {
+        if merged.is_empty() {
+            merged = row;
+            continue;
+        }
+        for (cell, fragment) in merged.iter_mut().zip(row) {
+            append_fragment(cell, &fragment);
+        }
+    }
+    merged
+}
+
+fn append_fragment(cell: &mut String, fragment: &str) {
+    let fragment = fragment.trim();
+    if fragment.is_empty() {
+        return;
+    }
+    if !cell.is_empty() {
+        cell.push(' ');
+    }
+    cell.push_str(fragment);
+}
+
+fn leading_whitespace(line: &str) -> &str { &line[..line.len() - line.trim_start().len()] }
+
+#[cfg(test)]
+mod tests;
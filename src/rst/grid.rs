@@ -0,0 +1,120 @@
+//! Parser for RST grid tables drawn with `+`, `-`, `=`, and `|`.
+
+use super::{ParsedTable, RstSpan, TableBlock, append_fragment, build_table};
+
+/// Parses the grid table whose top border is `lines[start]`.
+///
+/// Returns `None` when `lines[start]` is not a top border or the block is not
+/// a well-formed grid, so the lines pass through untouched.
+pub(super) fn parse(lines: &[String], start: usize) -> Option<TableBlock> {
+    let top = lines[start].trim_start();
+    let indent = lines[start].len() - top.len();
+    if !is_border(top, '-') {
+        return None;
+    }
+    let top: Vec<char> = top.trim_end().chars().collect();
+    let boundaries: Vec<usize> = (0..top.len()).filter(|&i| top[i] == '+').collect();
+    let end = block_end(lines, start, indent);
+    let body: Vec<Vec<char>> = lines[start + 1..end]
+        .iter()
+        .map(|line| line[indent..].trim_end().chars().collect())
+        .collect();
+    if body.last().is_none_or(|last| last.first() != Some(&'+')) {
+        return None;
+    }
+    let table = parse_rows(&body, &boundaries)?;
+    Some(TableBlock { end, table })
+}
+
+/// Returns `true` for `+---+---+` (or `=`) borders.
+fn is_border(line: &str, fill: char) -> bool {
+    let line = line.trim_end();
+    line.len() > 2
+        && line.starts_with('+')
+        && line.ends_with('+')
+        && line.split('+').all(|seg| seg.chars().all(|c| c == fill))
+        && !line.contains("++")
+}
+
+/// Index after the last line that starts with `+` or `|` at the table indent.
+fn block_end(lines: &[String], start: usize, indent: usize) -> usize {
+    let mut end = start + 1;
+    while let Some(line) = lines.get(end) {
+        let belongs = line.get(..indent).is_some_and(|pre| pre.trim().is_empty())
+            && line[indent..].starts_with(['+', '|']);
+        if !belongs {
+            break;
+        }
+        end += 1;
+    }
+    end
+}
+
+/// How a line between the top and bottom borders relates to the grid.
+enum GridLine {
+    /// A full `-` border closing the current row.
+    RowBorder,
+    /// A full `=` border closing the header rows.
+    HeaderBorder,
+    /// Cell text, one fragment per column.
+    Content(Vec<String>),
+}
+
+fn parse_rows(body: &[Vec<char>], boundaries: &[usize]) -> Option<Result<ParsedTable, RstSpan>> {
+    let mut header_rows = None;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut current: Option<Vec<String>> = None;
+    for line in body {
+        match classify(line, boundaries)? {
+            Ok(GridLine::Content(fragments)) => {
+                let cells = current.get_or_insert_with(|| vec![String::new(); fragments.len()]);
+                for (cell, fragment) in cells.iter_mut().zip(&fragments) {
+                    append_fragment(cell, fragment);
+                }
+            }
+            Ok(border) => {
+                rows.extend(current.take());
+                if matches!(border, GridLine::HeaderBorder) && header_rows.is_none() {
+                    header_rows = Some(std::mem::take(&mut rows));
+                }
+            }
+            Err(span) => return Some(Err(span)),
+        }
+    }
+    build_table(header_rows.unwrap_or_default(), rows).map(Ok)
+}
+
+/// Classifies one line, returning `None` when it does not fit the grid at all.
+fn classify(line: &[char], boundaries: &[usize]) -> Option<Result<GridLine, RstSpan>> {
+    let last = *boundaries.last()?;
+    if line.len() != last + 1 {
+        return None;
+    }
+    let segments = || {
+        boundaries
+            .windows(2)
+            .map(|pair| &line[pair[0] + 1..pair[1]])
+    };
+    if line[0] == '+' {
+        let text: String = line.iter().collect();
+        if is_border(&text, '-') && boundaries.iter().all(|&b| line[b] == '+') {
+            return Some(Ok(GridLine::RowBorder));
+        }
+        if is_border(&text, '=') && boundaries.iter().all(|&b| line[b] == '+') {
+            return Some(Ok(GridLine::HeaderBorder));
+        }
+        // A border interrupted by cell text means a cell continues below it.
+        let spans_rows = segments().any(|seg| !seg.iter().all(|&c| c == '-'));
+        return Some(Err(if spans_rows {
+            RstSpan::Row
+        } else {
+            RstSpan::Column
+        }));
+    }
+    if boundaries.iter().any(|&b| !matches!(line[b], '|' | '+')) {
+        return Some(Err(RstSpan::Column));
+    }
+    Some(Ok(GridLine::Content(
+        segments().map(|seg| seg.iter().collect()).collect(),
+    )))
+}
@@ -0,0 +1,108 @@
+//! Parser for RST simple tables delimited by `=====  =====` borders.
+
+use super::{RstSpan, TableBlock, append_fragment, build_table};
+
+/// Parses the simple table whose top border is `lines[start]`.
+///
+/// A table with a header has three borders (top, below the header, bottom);
+/// one without has two. The second border ends the table when it is followed
+/// by a blank line or the end of input.
+pub(super) fn parse(lines: &[String], start: usize) -> Option<TableBlock> {
+    let top = lines[start].trim_start();
+    let indent = lines[start].len() - top.len();
+    let columns = border_columns(top)?;
+    if columns.len() < 2 {
+        return None;
+    }
+    let is_border = |index: usize| {
+        lines
+            .get(index)
+            .and_then(|line| line.get(indent..))
+            .is_some_and(|line| border_columns(line).as_ref() == Some(&columns))
+    };
+    let first = (start + 1..lines.len()).find(|&i| is_border(i))?;
+    let ends_after_first = lines
+        .get(first + 1)
+        .is_none_or(|line| line.trim().is_empty());
+    let second = (first + 1..lines.len()).find(|&i| is_border(i));
+    let (header, body, end) = match second {
+        Some(second) if !ends_after_first => (start + 1..first, first + 1..second, second + 1),
+        _ => (start + 1..start + 1, start + 1..first, first + 1),
+    };
+    let header = parse_rows(&lines[header], indent, &columns);
+    let body = parse_rows(&lines[body], indent, &columns);
+    let table = match (header, body) {
+        (Ok(header_rows), Ok(rows)) => Ok(build_table(header_rows, rows)?),
+        (Err(span), _) | (_, Err(span)) => Err(span),
+    };
+    Some(TableBlock { end, table })
+}
+
+/// Returns the `[start, end)` character ranges of a `===  ===` border.
+fn border_columns(line: &str) -> Option<Vec<(usize, usize)>> {
+    let line = line.trim_end();
+    if line.is_empty() || !line.chars().all(|c| c == '=' || c == ' ') || !line.starts_with('=') {
+        return None;
+    }
+    let mut columns = Vec::new();
+    let mut start = None;
+    for (i, ch) in line.chars().chain(std::iter::once(' ')).enumerate() {
+        match (ch, start) {
+            ('=', None) => start = Some(i),
+            (' ', Some(begin)) => {
+                columns.push((begin, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    Some(columns)
+}
+
+/// Splits the lines of one section into rows of cells.
+///
+/// A line whose first column is blank continues the previous row. Text in
+/// the gap between two columns, or a `---` underline, means a cell spans
+/// columns.
+fn parse_rows(
+    lines: &[String],
+    indent: usize,
+    columns: &[(usize, usize)],
+) -> Result<Vec<Vec<String>>, RstSpan> {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for line in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let chars: Vec<char> = line.get(indent..).unwrap_or_default().chars().collect();
+        let text = |from: usize, to: usize| -> String {
+            chars
+                .get(from..to.min(chars.len()))
+                .map_or_else(String::new, |s| s.iter().collect())
+        };
+        if line.trim().chars().all(|c| c == '-' || c == ' ') {
+            return Err(RstSpan::Column);
+        }
+        let mut cells = Vec::with_capacity(columns.len());
+        for (i, &(begin, end)) in columns.iter().enumerate() {
+            // The last column may run past its border; the others may not.
+            let Some(&(next, _)) = columns.get(i + 1) else {
+                cells.push(text(begin, chars.len()).trim().to_string());
+                break;
+            };
+            if !text(end, next).trim().is_empty() {
+                return Err(RstSpan::Column);
+            }
+            cells.push(text(begin, end).trim().to_string());
+        }
+        match rows.last_mut() {
+            Some(previous) if cells[0].is_empty() => {
+                for (cell, fragment) in previous.iter_mut().zip(&cells) {
+                    append_fragment(cell, fragment);
+                }
+            }
+            _ => rows.push(cells),
+        }
+    }
+    Ok(rows)
+}
@@ -0,0 +1,71 @@
+//! Unit tests for RST table conversion.
+
+use rstest::rstest;
+
+use super::*;
+use crate::test_support::lines;
+
+#[test]
+fn converts_grid_table_with_multi_line_cells() {
+    let input = lines(
+        "Before\n\n  +-------+-----------+\n  | Flag  | Effect    |\n  +=======+===========+\n  | \
+         wrap  | Reflows   |\n  |       | a | b     |\n  +-------+-----------+\n  | fence | \
+         Tidies    |\n  +-------+-----------+\n\nAfter",
+    );
+    assert_eq!(
+        convert_rst_tables(&input),
+        lines(
+            "Before\n\n  | Flag  | Effect         |\n  | ----- | -------------- |\n  | wrap  | \
+             Reflows a \\| b |\n  | fence | Tidies         |\n\nAfter"
+        )
+    );
+}
+
+#[test]
+fn converts_simple_tables_with_and_without_headers() {
+    let input = lines(
+        "=====  ======\nInput  Output\n=====  ======\nA      first\n       line\nB      \
+         second\n=====  ======\n\n===  ===\nx    1\ny    2\n===  ===\n",
+    );
+    assert_eq!(
+        convert_rst_tables(&input),
+        lines(
+            "| Input | Output     |\n| ----- | ---------- |\n| A     | first line |\n| B     | \
+             second     |\n\n| x   | 1   |\n| --- | --- |\n| y   | 2   |"
+        )
+    );
+}
+
+#[rstest]
+#[case::grid_row_span(
+    "+---+-----+\n| a | b   |\n+---+ c   +\n| d | e   |\n+---+-----+",
+    RstSpan::Row
+)]
+#[case::grid_column_span(
+    "+---+---+\n| a | b |\n+---+---+\n| spans |\n+---+---+",
+    RstSpan::Column
+)]
+#[case::simple_column_span(
+    "=====  =====\n  Both cols\n------------\nA      B\n=====  =====\n1      2\n=====  =====",
+    RstSpan::Column
+)]
+fn reports_and_keeps_spanning_tables(#[case] input: &str, #[case] span: RstSpan) {
+    let mut input = lines(input);
+    input.insert(0, "Intro".to_string());
+    assert_eq!(convert_rst_tables(&input), input);
+    assert_eq!(
+        unconvertible_rst_tables(&input),
+        vec![UnconvertedRstTable { line: 2, span }]
+    );
+}
+
+#[rstest]
+#[case("```\n+---+\n| a |\n+---+\n```")]
+#[case("+---+\n| a |")]
+#[case("Heading\n=======")]
+#[case("+---+---+\n| a | b |\n+---+---+ trailing")]
+fn leaves_other_content_unchanged(#[case] input: &str) {
+    let input = lines(input);
+    assert_eq!(convert_rst_tables(&input), input);
+    assert!(unconvertible_rst_tables(&input).is_empty());
+}
@@ -0,0 +1,251 @@
+//! Extract fenced Rust examples and check that they still compile.
+//!
+//! Code samples in READMEs and guides are not built by `cargo test`, so they
+//! drift out of date as APIs change. [`rust_fences`] collects every fenced
+//! block whose info string names `rust`, and [`check_rust_fence`] compiles one
+//! with `rustc --emit=metadata`, mapping each error back to its line in the
+//! Markdown source.
+//!
+//! Fences follow rustdoc conventions: `ignore`, `compile_fail`, and `text`
+//! attributes skip the block, `editionNNNN` selects the edition (2021 by
+//! default), `# ` hides a line from readers but still compiles it, and code
+//! without `fn main` is wrapped in one. Each block is compiled on its own,
+//! without access to other crates.
+//!
+//! This module is only available with the `check-rust-fences` feature.
+
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+use serde_json::Value;
+
+use crate::{fences::FenceState, wrap::BlockquotePrefix};
+
+/// A fenced Rust example extracted from Markdown.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RustFence {
+    /// One-based line number of the first line inside the fence.
+    pub line: usize,
+    /// Rust edition requested by an `editionNNNN` attribute, or `"2021"`.
+    pub edition: String,
+    /// Source lines with blockquote markers, fence indentation, and rustdoc
+    /// `# ` prefixes removed.
+    pub code: Vec<String>,
+}
+
+/// A compiler error reported for a [`RustFence`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RustFenceError {
+    /// One-based line number in the Markdown source.
+    pub line: usize,
+    /// The compiler's error message.
+    pub message: String,
+}
+
+/// Collect the fenced Rust examples in `lines` that rustdoc would compile.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::rust_fences::rust_fences;
+///
+/// let lines: Vec<String> = [
+///     "Intro",
+///     "```rust",
+///     "let x = 1;",
+///     "```",
+///     "```rust,ignore",
+///     "x",
+///     "```",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// let fences = rust_fences(&lines);
+/// assert_eq!(fences.len(), 1);
+/// assert_eq!(fences[0].line, 3);
+/// assert_eq!(fences[0].code, vec!["let x = 1;"]);
+/// ```
+#[must_use]
+pub fn rust_fences(lines: &[String]) -> Vec<RustFence> {
+    let mut tracker = FenceState::default();
+    let mut fences = Vec::new();
+    let mut current: Option<(RustFence, usize)> = None;
+    for (index, line) in lines.iter().enumerate() {
+        let observed = tracker.observe_source_fence(line);
+        let observation = observed.observation;
+        if observation.is_fence_marker && !observation.was_in_fence {
+            current = observed.fence.and_then(|(indent, _, info)| {
+                let edition = rust_edition(info)?;
+                let fence = RustFence {
+                    line: index + 2,
+                    edition,
+                    code: Vec::new(),
+                };
+                Some((fence, inner_indent(indent)))
+            });
+        } else if observation.is_fence_marker {
+            fences.extend(current.take().map(|(fence, _)| fence));
+        } else if let Some((fence, indent)) = current.as_mut() {
+            fence.code.push(code_line(line, *indent));
+        }
+    }
+    fences
+}
+
+/// Compile `fence` with `rustc` and return its errors.
+///
+/// The compiler is taken from the `RUSTC` environment variable, falling back
+/// to `rustc` on the `PATH`. Warnings are ignored.
+///
+/// # Errors
+///
+/// Returns an error when the compiler cannot be started or its output cannot
+/// be read.
+pub fn check_rust_fence(fence: &RustFence) -> io::Result<Vec<RustFenceError>> {
+    let body = fence.code.join("\n");
+    let wrapped = !body.contains("fn main");
+    let (source, offset) = if wrapped {
+        (format!("#![allow(unused)]\nfn main() {{\n{body}\n}}\n"), 2)
+    } else {
+        (format!("{body}\n"), 0)
+    };
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let mut child = Command::new(rustc)
+        .args(["--edition", &fence.edition])
+        .args(["--crate-type", "bin", "--crate-name", "rust_fence"])
+        .args(["--emit=metadata=-", "--error-format=json", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(source.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok(stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|diagnostic| fence_error(&diagnostic, fence, offset))
+        .collect())
+}
+
+/// Returns the edition for a compiled Rust fence, or `None` when the info
+/// string names another language or opts out of compilation.
+fn rust_edition(info: &str) -> Option<String> {
+    let mut attrs = info
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|attr| !attr.is_empty());
+    if attrs.next() != Some("rust") {
+        return None;
+    }
+    let mut edition = "2021".to_string();
+    for attr in attrs {
+        match attr {
+            "ignore" | "compile_fail" | "text" => return None,
+            _ => {
+                if let Some(year) = attr.strip_prefix("edition") {
+                    edition = year.to_string();
+                }
+            }
+        }
+    }
+    Some(edition)
+}
+
+/// Width of the fence indentation after any blockquote markers.
+fn inner_indent(indent: &str) -> usize {
+    let inner = BlockquotePrefix::parse(indent).map_or(indent, |prefix| prefix.inner());
+    inner.len() - inner.trim_start().len()
+}
+
+/// Strips blockquote markers, up to `indent` spaces, and a rustdoc `# ` hide
+/// marker from one line of fence content.
+fn code_line(line: &str, indent: usize) -> String {
+    let inner = BlockquotePrefix::parse(line).map_or(line, |prefix| prefix.inner());
+    let strip = inner.len() - inner.trim_start_matches(' ').len();
+    let code = &inner[strip.min(indent)..];
+    match code.trim_start() {
+        "#" => String::new(),
+        trimmed => trimmed
+            .strip_prefix("# ")
+            .map_or_else(|| code.to_string(), str::to_string),
+    }
+}
+
+fn fence_error(diagnostic: &Value, fence: &RustFence, offset: usize) -> Option<RustFenceError> {
+    if diagnostic.get("level").and_then(Value::as_str) != Some("error") {
+        return None;
+    }
+    let message = diagnostic.get("message").and_then(Value::as_str)?;
+    if message.starts_with("aborting due to") {
+        return None;
+    }
+    let source_line = diagnostic
+        .get("spans")
+        .and_then(Value::as_array)
+        .and_then(|spans| {
+            spans
+                .iter()
+                .find(|span| span.get("is_primary").and_then(Value::as_bool) == Some(true))
+        })
+        .and_then(|span| span.get("line_start").and_then(Value::as_u64))
+        .and_then(|line| usize::try_from(line).ok())
+        .unwrap_or(1);
+    let code_index = source_line
+        .saturating_sub(1 + offset)
+        .min(fence.code.len().saturating_sub(1));
+    Some(RustFenceError {
+        line: fence.line + code_index,
+        message: message.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for Rust fence extraction.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case("rust", Some("2021"))]
+    #[case("rust,no_run", Some("2021"))]
+    #[case("rust edition2018", Some("2018"))]
+    #[case("rust,ignore", None)]
+    #[case("rust,compile_fail", None)]
+    #[case("rusty", None)]
+    #[case("toml", None)]
+    #[case("", None)]
+    fn reads_rustdoc_attributes(#[case] info: &str, #[case] expected: Option<&str>) {
+        assert_eq!(rust_edition(info).as_deref(), expected);
+    }
+
+    #[test]
+    fn strips_quotes_indent_and_hidden_lines() {
+        let input = lines("> ```rust\n> # use std::fmt;\n>     let x = 1;\n> #\n> ```");
+        assert_eq!(
+            rust_fences(&input),
+            vec![RustFence {
+                line: 2,
+                edition: "2021".to_string(),
+                code: vec![
+                    "use std::fmt;".to_string(),
+                    "let x = 1;".to_string(),
+                    String::new(),
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_other_languages_and_unclosed_fences() {
+        let input = lines("```toml\n[x]\n```\n\n```rust\nfn f() {}");
+        assert!(rust_fences(&input).is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+//! Resolution of heading-scoped sections.
+//!
+//! [`section_range`] finds the lines under a named heading using the block
+//! model from [`extract_text`], so headings inside code blocks, blockquotes,
+//! and frontmatter are never mistaken for section boundaries. A section runs
+//! from the line after its heading up to the next heading of the same or a
+//! higher level, or the end of the document.
+
+use std::ops::Range;
+
+use crate::extract::{TextBlockKind, extract_text, plain_text};
+
+/// Returns the zero-based range of lines under `heading`.
+///
+/// `heading` is the heading text, optionally preceded by ATX markers to fix
+/// its level: `"## API Reference"` only matches a level-two heading, while
+/// `"API Reference"` matches the first heading of any level with that text.
+/// Headings are compared by their plain text, so inline code, emphasis, and
+/// link syntax in the document need not be repeated. The range excludes the
+/// heading itself, and `None` is returned when no heading matches.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::sections::section_range;
+///
+/// let lines: Vec<String> = [
+///     "# Guide",
+///     "## API Reference",
+///     "text",
+///     "### Details",
+///     "more",
+///     "## Changelog",
+/// ]
+/// .into_iter()
+/// .map(str::to_string)
+/// .collect();
+/// assert_eq!(section_range(&lines, "## API Reference"), Some(2..5));
+/// assert_eq!(section_range(&lines, "Details"), Some(4..5));
+/// assert_eq!(section_range(&lines, "### API Reference"), None);
+/// ```
+#[must_use]
+pub fn section_range(lines: &[String], heading: &str) -> Option<Range<usize>> {
+    let query = heading.trim();
+    let body = query.trim_start_matches('#');
+    let wanted_level = (body.len() < query.len()).then_some(query.len() - body.len());
+    let wanted_text = plain_text(body.trim());
+
+    let headings: Vec<(usize, usize, String)> = extract_text(lines)
+        .into_iter()
+        .filter_map(|block| match block.kind {
+            TextBlockKind::Heading(level) => {
+                let text = block.heading_path.last().cloned().unwrap_or_default();
+                Some((level, block.line - 1, text))
+            }
+            _ => None,
+        })
+        .collect();
+    let position = headings.iter().position(|(level, _, text)| {
+        *text == wanted_text && wanted_level.is_none_or(|wanted| wanted == *level)
+    })?;
+    let (level, index, _) = &headings[position];
+    let is_atx = lines[*index].trim_start().starts_with('#');
+    let start = if is_atx { index + 1 } else { index + 2 };
+    let end = headings[position + 1..]
+        .iter()
+        .find(|(next, ..)| next <= level)
+        .map_or(lines.len(), |(_, next_index, _)| *next_index);
+    Some(start.min(end)..end)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for section resolution.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::ends_at_same_level("## A\nx\n## B\ny", "## A", Some(1..2))]
+    #[case::ends_at_higher_level("## A\nx\n# B\ny", "A", Some(1..2))]
+    #[case::keeps_subsections("## A\nx\n### B\ny", "## A", Some(1..4))]
+    #[case::setext("A\n---\nx\n\nB\n---", "## A", Some(2..4))]
+    #[case::inline_markup("## The `api` *module*\nx", "The api module", Some(1..2))]
+    #[case::ignores_fenced("```\n## A\n```\n## A\nx", "## A", Some(4..5))]
+    #[case::missing("## A\nx", "## B", None)]
+    #[case::wrong_level("## A\nx", "# A", None)]
+    fn resolves_sections(
+        #[case] input: &str,
+        #[case] heading: &str,
+        #[case] expected: Option<Range<usize>>,
+    ) {
+        assert_eq!(section_range(&lines(input), heading), expected);
+    }
+}
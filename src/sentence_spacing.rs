@@ -0,0 +1,211 @@
+//! Normalise the spacing between sentences.
+//!
+//! Some authors type two spaces after a full stop, others one. Markdown
+//! renders both the same, but mixed source produces noisy diffs.
+//! [`normalize_sentence_spacing`] rewrites the gap after `.`, `!`, and `?` to
+//! the chosen [`SentenceSpacing`] in paragraph and list text. Code spans, code
+//! blocks, tables, headings, list markers, and trailing hard-break spaces are
+//! never touched.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::{
+    fences::FenceState,
+    hard_breaks::is_prose,
+    textproc::{Token, markdown_tokens, push_original_token},
+    wrap::LinkReferenceMatcher,
+};
+
+/// Sentence-ending punctuation, any closing quotes or brackets, and the run
+/// of spaces after them.
+static SENTENCE_GAP_RE: LazyLock<Regex> = lazy_regex!(
+    r#"[.!?]["'’”)\]]*( +)"#,
+    "sentence gap regex should compile"
+);
+
+/// Blockquote markers and a list marker at the start of a line.
+static LINE_PREFIX_RE: LazyLock<Regex> = lazy_regex!(
+    r"^(?:\s*>)*\s*(?:(?:[-*+]|\d{1,9}[.)])\s+)?",
+    "line prefix regex should compile"
+);
+
+/// Words that end in a full stop without ending a sentence.
+const ABBREVIATIONS: [&str; 14] = [
+    "cf", "dr", "e.g", "etc", "fig", "i.e", "jr", "mr", "mrs", "ms", "no", "prof", "sr", "vs",
+];
+
+/// How many spaces separate two sentences.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum SentenceSpacing {
+    /// One space, collapsing longer gaps.
+    Single,
+    /// Two spaces, widening single spaces before a capitalised sentence.
+    Double,
+}
+
+/// Rewrite the spaces after sentence-ending punctuation to `spacing`.
+///
+/// With [`SentenceSpacing::Single`] every run of two or more spaces after
+/// `.`, `!`, or `?` becomes one space. With [`SentenceSpacing::Double`] such
+/// runs become two spaces, and a single space is widened only when the next
+/// word starts with a capital letter and the previous word is not an initial
+/// or a common abbreviation such as "Dr." or "e.g.".
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::sentence_spacing::{SentenceSpacing, normalize_sentence_spacing};
+///
+/// let lines = vec!["One.  Two!   Three `a.  b`.".to_string()];
+/// assert_eq!(
+///     normalize_sentence_spacing(&lines, SentenceSpacing::Single),
+///     vec!["One. Two! Three `a.  b`."]
+/// );
+/// ```
+#[must_use]
+pub fn normalize_sentence_spacing(lines: &[String], spacing: SentenceSpacing) -> Vec<String> {
+    let mut fences = FenceState::default();
+    let matcher = LinkReferenceMatcher::production();
+    lines
+        .iter()
+        .map(|line| {
+            let fence = fences.observe_source_line(line);
+            if fence.is_code() || !is_prose(line, matcher) {
+                return line.clone();
+            }
+            respace_line(line, spacing)
+        })
+        .collect()
+}
+
+/// Rewrites the sentence gaps of one prose line, keeping its block prefix and
+/// any trailing whitespace as written.
+fn respace_line(line: &str, spacing: SentenceSpacing) -> String {
+    let body = line.trim_end();
+    let prefix_len = LINE_PREFIX_RE.find(body).map_or(0, |m| m.end());
+    let (prefix, content) = body.split_at(prefix_len);
+    let mut out = String::with_capacity(line.len() + 8);
+    out.push_str(prefix);
+    let mut tokens = markdown_tokens(content).peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => respace_text(text, tokens.peek().is_some(), spacing, &mut out),
+            _ => push_original_token(&token, &mut out),
+        }
+    }
+    out.push_str(&line[body.len()..]);
+    out
+}
+
+/// Rewrites the sentence gaps in `text`. `followed` is `true` when another
+/// token, such as a code span, comes after it on the same line.
+fn respace_text(text: &str, followed: bool, spacing: SentenceSpacing, out: &mut String) {
+    let mut cursor = 0;
+    for caps in SENTENCE_GAP_RE.captures_iter(text) {
+        let (Some(whole), Some(gap)) = (caps.get(0), caps.get(1)) else {
+            continue;
+        };
+        let next = &text[gap.end()..];
+        if (next.is_empty() && !followed) || next.starts_with('|') {
+            continue;
+        }
+        let width = match spacing {
+            SentenceSpacing::Single if gap.len() > 1 => 1,
+            SentenceSpacing::Double
+                if gap.len() > 2
+                    || (gap.len() == 1
+                        && starts_sentence(next)
+                        && !ends_with_abbreviation(&text[..whole.end()])) =>
+            {
+                2
+            }
+            _ => continue,
+        };
+        out.push_str(&text[cursor..gap.start()]);
+        out.push_str(&" ".repeat(width));
+        cursor = gap.end();
+    }
+    out.push_str(&text[cursor..]);
+}
+
+/// Returns `true` when `text` opens with a capital letter, optionally after
+/// opening quotes or brackets.
+fn starts_sentence(text: &str) -> bool {
+    text.trim_start_matches(['"', '\'', '‘', '“', '(', '['])
+        .chars()
+        .next()
+        .is_some_and(char::is_uppercase)
+}
+
+/// Returns `true` when the word ending `text` is an initial or a known
+/// abbreviation followed by a full stop.
+fn ends_with_abbreviation(text: &str) -> bool {
+    let Some(word) = text.trim_end().strip_suffix('.') else {
+        return false;
+    };
+    let word = word
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches(['"', '\'', '(', '[']);
+    word.chars().count() == 1 || ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for sentence spacing normalisation.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::collapses("One.  Two!   Three?  Four", "One. Two! Three? Four")]
+    #[case::after_quotes("\"Yes.\"  Then (maybe.)  Done", "\"Yes.\" Then (maybe.) Done")]
+    #[case::before_code("See this.  `code`", "See this. `code`")]
+    #[case::inside_code("`a.  b` and c", "`a.  b` and c")]
+    #[case::list_marker("1.  First.  Second", "1.  First. Second")]
+    #[case::quoted_list("> -   A.  B", "> -   A. B")]
+    #[case::hard_break("End.  ", "End.  ")]
+    fn single_spacing(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            normalize_sentence_spacing(&lines(input), SentenceSpacing::Single),
+            lines(expected)
+        );
+    }
+
+    #[rstest]
+    #[case::widens("One. Two! Three? Four", "One.  Two!  Three?  Four")]
+    #[case::narrows("One.    Two", "One.  Two")]
+    #[case::lowercase("One. two", "One. two")]
+    #[case::abbreviations("Ask Dr. Smith, e.g. Mr. Jones", "Ask Dr. Smith, e.g. Mr. Jones")]
+    #[case::initials("J. R. R. Tolkien wrote. It sold", "J. R. R. Tolkien wrote.  It sold")]
+    #[case::numbers("Version 1.2 is out. Get it", "Version 1.2 is out.  Get it")]
+    fn double_spacing(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            normalize_sentence_spacing(&lines(input), SentenceSpacing::Double),
+            lines(expected)
+        );
+    }
+
+    #[rstest]
+    #[case::fenced("```\nOne.  Two\n```")]
+    #[case::indented_code("    One.  Two")]
+    #[case::table("| One.  Two |")]
+    #[case::heading("# One.  Two")]
+    fn leaves_non_prose_alone(#[case] input: &str) {
+        let input = lines(input);
+        assert_eq!(
+            normalize_sentence_spacing(&input, SentenceSpacing::Single),
+            input
+        );
+    }
+}
@@ -4,15 +4,34 @@
 //! [`docs/architecture.md`](../../docs/architecture.md).
 //! Provides helpers used by the `reflow` module and `reflow_table` itself.
 
-use regex::Regex;
+mod align;
+mod balance;
+mod cells;
+mod checked;
+mod clean;
+mod overflow;
+mod style;
 
-static ESCAPED_PIPE_RE: std::sync::LazyLock<Regex> =
-    lazy_regex!(r"\\\|", "escaped table pipe pattern should compile");
+pub use align::{Alignment, align_columns};
+pub use balance::{WidthAllocation, allocate_widths, table_width};
+pub use cells::map_cells;
+use checked::RowMismatch;
+pub use checked::try_reflow_table;
+pub use clean::is_clean_table;
+pub(crate) use overflow::SCROLL_MARKER;
+pub use overflow::{TableOverflow, contain_table};
+#[cfg(feature = "html")]
+pub(crate) use style::reflow_table_with_min_widths;
+pub use style::{TableStyle, reflow_table_with_max_width, reflow_table_with_style};
+use unicode_width::UnicodeWidthStr;
+
+use crate::textproc::is_escaped;
 
 /// Split a Markdown table row into individual cell strings.
 ///
 /// Escaped pipe characters (`\|`) are treated as literals and whitespace
-/// inside each cell is trimmed.
+/// inside each cell is trimmed. A pipe after an escaped backslash, as in
+/// `a\\|b`, still separates cells.
 ///
 /// # Examples
 ///
@@ -29,13 +48,42 @@ static ESCAPED_PIPE_RE: std::sync::LazyLock<Regex> =
 /// ```
 #[must_use]
 pub fn split_cells(line: &str) -> Vec<String> {
-    let trimmed = line.trim().trim_start_matches('|').trim_end_matches('|');
-    let placeholder = '\u{1f}';
-    let replaced = ESCAPED_PIPE_RE.replace_all(trimmed, &placeholder.to_string());
-    replaced
-        .split('|')
-        .map(|cell| cell.trim().replace(placeholder, "|"))
-        .collect()
+    let mut row = line.trim().trim_start_matches('|');
+    while row.ends_with('|') && !is_escaped(row, row.len() - 1) {
+        row = &row[..row.len() - 1];
+    }
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    for (index, ch) in row.char_indices() {
+        match ch {
+            '|' if is_escaped(row, index) => {
+                cell.pop();
+                cell.push('|');
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(ch),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+/// Escapes literal pipes in imported cell text so they do not split the row.
+///
+/// Pipes already preceded by an odd run of backslashes are left alone. GFM
+/// needs the escape even inside code spans, so callers apply it to whole
+/// cells.
+pub(crate) fn escape_cell_pipes(cell: &str) -> String {
+    let mut out = String::with_capacity(cell.len());
+    let mut escaped = false;
+    for ch in cell.chars() {
+        if ch == '|' && !escaped {
+            out.push('\\');
+        }
+        escaped = ch == '\\' && !escaped;
+        out.push(ch);
+    }
+    out
 }
 
 /// Formats separator cells so they match the computed table widths.
@@ -95,28 +143,36 @@ fn sep_index_within(idx: Option<usize>, len: usize) -> Option<usize> {
     }
 }
 
-fn rows_mismatched(rows: &[Vec<String>], split_within_line: bool) -> bool {
+fn mismatched_row(rows: &[Vec<String>], split_within_line: bool) -> Option<RowMismatch> {
     if split_within_line {
-        return false;
+        return None;
     }
-    let Some(first_len) = rows.first().map(Vec::len) else {
-        return false;
-    };
+    let expected = rows.first()?.len();
     rows.iter()
+        .enumerate()
         .skip(1)
-        .any(|row| row.len() != first_len && !row.iter().all(|c| SEP_RE.is_match(c)))
+        .find(|(_, row)| row.len() != expected && !row.iter().all(|c| is_separator_line(c)))
+        .map(|(row, cells)| RowMismatch {
+            row,
+            expected,
+            found: cells.len(),
+        })
 }
 
-/// Matches Markdown table separator lines made only of pipes, colons, dashes,
-/// and whitespace so parsing can detect and extract the alignment row.
+/// Returns `true` for Markdown table separator lines made only of pipes,
+/// colons, dashes, and whitespace, so parsing can detect and extract the
+/// alignment row.
 ///
-/// The pattern `^[\s|:-]+$` accepts common separator forms such as
-/// `| --- | :--: | --: |` while rejecting content rows that contain other
-/// characters.
-pub(crate) static SEP_RE: std::sync::LazyLock<Regex> = lazy_regex!(
-    r"^[\s|:-]+$",
-    "Markdown table separator row pattern should compile",
-);
+/// Common separator forms such as `| --- | :--: | --: |` are accepted while
+/// content rows that contain other characters are rejected. The check runs
+/// on every table line, so it scans characters directly instead of using a
+/// regular expression.
+pub(crate) fn is_separator_line(line: &str) -> bool {
+    !line.is_empty()
+        && line
+            .chars()
+            .all(|c| c.is_whitespace() || matches!(c, '|' | ':' | '-'))
+}
 
 /// Holds the parsed and validated table data.
 ///
@@ -148,24 +204,27 @@ fn extract_indent_and_trim(lines: &[String]) -> (String, Vec<String>) {
 
 /// Removes and returns the first separator line detected in `lines`.
 fn extract_separator_line(lines: &mut Vec<String>) -> Option<String> {
-    let sep_idx = lines.iter().position(|l| SEP_RE.is_match(l));
+    let sep_idx = lines.iter().position(|l| is_separator_line(l));
     sep_idx.map(|idx| lines.remove(idx))
 }
 
 /// Parses table rows and validates column consistency.
-fn parse_and_validate(trimmed: &[String], sep_line: Option<&String>) -> Option<ParsedTable> {
+fn parse_and_validate(
+    trimmed: &[String],
+    sep_line: Option<&String>,
+) -> Result<ParsedTable, RowMismatch> {
     let (rows, split_within_line) = crate::reflow::parse_rows(trimmed);
     let max_cols = rows.iter().map(Vec::len).max().unwrap_or(0);
     let (sep_cells, sep_row_idx) = crate::reflow::detect_separator(sep_line, &rows, max_cols);
     let cleaned = crate::reflow::clean_rows(rows);
-    if rows_mismatched(&cleaned, split_within_line) {
-        return None;
+    if let Some(mismatch) = mismatched_row(&cleaned, split_within_line) {
+        return Err(mismatch);
     }
     let mut output_rows = cleaned.clone();
     if let Some(idx) = sep_index_within(sep_row_idx, output_rows.len()) {
         output_rows.remove(idx);
     }
-    Some(ParsedTable {
+    Ok(ParsedTable {
         output_rows,
         sep_cells,
         max_cols,
@@ -173,13 +232,34 @@ fn parse_and_validate(trimmed: &[String], sep_line: Option<&String>) -> Option<P
 }
 
 /// Calculates column widths and formats the final table output.
-fn calculate_and_format(parsed: &ParsedTable, indent: &str) -> Option<Vec<String>> {
+fn calculate_and_format(
+    parsed: &ParsedTable,
+    indent: &str,
+    style: TableStyle,
+    max_width: Option<usize>,
+    min_widths: &[usize],
+) -> Option<Vec<String>> {
     let mut widths = crate::reflow::calculate_widths(&parsed.output_rows, parsed.max_cols);
     if parsed.sep_cells.is_some() {
         for width in &mut widths {
             *width = (*width).max(3);
         }
     }
+    // Narrowing a column below its longest cell would leave ragged rows.
+    let floors = widths.clone();
+    for (width, &min) in widths.iter_mut().zip(min_widths) {
+        *width = (*width).max(min);
+    }
+    if let Some(max_width) = max_width {
+        let indent_width = UnicodeWidthStr::width(indent);
+        widths = allocate_widths(
+            &widths,
+            &floors,
+            indent_width,
+            max_width,
+            WidthAllocation::default(),
+        );
+    }
     if parsed
         .sep_cells
         .as_ref()
@@ -187,6 +267,23 @@ fn calculate_and_format(parsed: &ParsedTable, indent: &str) -> Option<Vec<String
     {
         return None;
     }
+    match style {
+        TableStyle::Padded => {}
+        TableStyle::EdgesOnly => {
+            return Some(crate::reflow::format_edges_only(
+                &parsed.output_rows,
+                parsed.sep_cells.as_deref(),
+                indent,
+            ));
+        }
+        TableStyle::Compact => {
+            return Some(crate::reflow::format_compact(
+                &parsed.output_rows,
+                parsed.sep_cells.as_deref(),
+                indent,
+            ));
+        }
+    }
     let out = crate::reflow::format_rows(&parsed.output_rows, &widths, indent);
     Some(crate::reflow::insert_separator(
         out,
@@ -217,87 +314,39 @@ fn calculate_and_format(parsed: &ParsedTable, indent: &str) -> Option<Vec<String
 /// ```
 #[must_use]
 pub fn reflow_table(lines: &[String]) -> Vec<String> {
+    reflow_table_with_style(lines, TableStyle::Padded)
+}
+
+fn reflow_table_within(
+    lines: &[String],
+    style: TableStyle,
+    max_width: Option<usize>,
+    min_widths: &[usize],
+) -> Vec<String> {
+    try_reflow_table_within(lines, style, max_width, min_widths).unwrap_or_else(|_| lines.to_vec())
+}
+
+fn try_reflow_table_within(
+    lines: &[String],
+    style: TableStyle,
+    max_width: Option<usize>,
+    min_widths: &[usize],
+) -> Result<Vec<String>, crate::Error> {
     if lines.is_empty() {
-        return Vec::new();
+        return Ok(Vec::new());
     }
 
     let (indent, mut trimmed) = extract_indent_and_trim(lines);
     let sep_line = extract_separator_line(&mut trimmed);
 
-    let Some(parsed) = parse_and_validate(&trimmed, sep_line.as_ref()) else {
-        return lines.to_vec();
-    };
+    let parsed = parse_and_validate(&trimmed, sep_line.as_ref())
+        .map_err(|mismatch| mismatch.into_error(lines))?;
 
-    calculate_and_format(&parsed, &indent).unwrap_or_else(|| lines.to_vec())
+    calculate_and_format(&parsed, &indent, style, max_width, min_widths).ok_or_else(|| {
+        let found = parsed.sep_cells.as_ref().map_or(0, Vec::len);
+        checked::separator_columns(lines, parsed.max_cols, found)
+    })
 }
 
 #[cfg(test)]
-mod tests {
-    //! Unit tests for table parsing and formatting.
-
-    use rstest::rstest;
-
-    use super::*;
-
-    #[test]
-    fn sep_index_within_bounds() {
-        assert_eq!(sep_index_within(Some(1), 3), Some(1));
-        assert_eq!(sep_index_within(Some(3), 3), None);
-        assert_eq!(sep_index_within(None, 3), None);
-    }
-
-    #[test]
-    fn detect_row_mismatch() {
-        let rows = vec![
-            vec!["a".to_string(), "b".to_string()],
-            vec!["1".to_string(), "2".to_string()],
-        ];
-        assert!(!rows_mismatched(&rows, false));
-
-        let mismatch = vec![
-            vec!["a".to_string(), "b".to_string()],
-            vec!["1".to_string()],
-        ];
-        assert!(rows_mismatched(&mismatch, false));
-
-        let with_sep = vec![
-            vec!["a".to_string(), "b".to_string()],
-            vec!["---".to_string(), "---".to_string()],
-            vec!["1".to_string(), "2".to_string()],
-        ];
-        assert!(!rows_mismatched(&with_sep, false));
-
-        assert!(!rows_mismatched(&mismatch, true));
-    }
-
-    #[rstest]
-    #[case(vec![2], vec!["---".to_string()], vec!["---".to_string()])]
-    #[case(vec![5], vec![":---".to_string()], vec![":----".to_string()])]
-    #[case(vec![5], vec!["---:".to_string()], vec!["----:".to_string()])]
-    #[case(vec![5], vec![":--:".to_string()], vec![":---:".to_string()])]
-    fn format_separator_cells_preserves_alignment_markers(
-        #[case] widths: Vec<usize>,
-        #[case] cells: Vec<String>,
-        #[case] expected: Vec<String>,
-    ) {
-        assert_eq!(format_separator_cells(&widths, &cells), expected);
-    }
-
-    #[test]
-    fn format_separator_cells_returns_empty_when_counts_mismatch() {
-        let sep_cells = vec!["---".to_string()];
-
-        assert!(format_separator_cells(&[3, 4], &sep_cells).is_empty());
-    }
-
-    #[test]
-    fn reflow_table_returns_original_lines_for_mismatched_separator_columns() {
-        let lines = vec![
-            "| head |".to_string(),
-            "| --- | --- |".to_string(),
-            "| body |".to_string(),
-        ];
-
-        assert_eq!(reflow_table(&lines), lines);
-    }
-}
+mod tests;
@@ -0,0 +1,164 @@
+//! Column alignments set by the caller rather than the source separator.
+//!
+//! Reflow keeps the colons of a table's own separator row. [`align_columns`]
+//! rewrites that row first, so the alignments a caller chooses, such as those
+//! given to `--align`, are the ones reflow preserves.
+
+use std::{fmt, str::FromStr};
+
+use super::{is_separator_line, split_cells};
+
+/// How a column's content is aligned, as written in the separator row.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Alignment {
+    /// No colons, `---`, leaving alignment to the renderer.
+    #[default]
+    Default,
+    /// `:--`
+    Left,
+    /// `:-:`
+    Center,
+    /// `--:`
+    Right,
+}
+
+impl Alignment {
+    /// The shortest separator cell with this alignment. Reflow widens it to
+    /// the column.
+    #[must_use]
+    pub const fn marker(self) -> &'static str {
+        match self {
+            Self::Default => "---",
+            Self::Left => ":--",
+            Self::Center => ":-:",
+            Self::Right => "--:",
+        }
+    }
+}
+
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Default => "default",
+            Self::Left => "left",
+            Self::Center => "center",
+            Self::Right => "right",
+        })
+    }
+}
+
+impl FromStr for Alignment {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "-" | "d" | "default" => Ok(Self::Default),
+            "l" | "left" => Ok(Self::Left),
+            "c" | "center" | "centre" => Ok(Self::Center),
+            "r" | "right" => Ok(Self::Right),
+            _ => Err(format!(
+                "unknown alignment `{name}`; expected `l`, `c`, `r`, or `-`"
+            )),
+        }
+    }
+}
+
+/// Rewrites the separator row of the table `lines` so column `i` has
+/// `alignments[i]`.
+///
+/// `None` entries, and columns past the end of `alignments`, keep the
+/// alignment the table already has. A table without a separator row is
+/// returned unchanged. Call [`reflow_table`](super::reflow_table) afterwards
+/// to widen the rewritten cells to their columns.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::table::{Alignment, align_columns, reflow_table};
+///
+/// let lines: Vec<String> = ["|a|b|c|", "|-|:-|-|"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let aligned = align_columns(
+///     &lines,
+///     &[Some(Alignment::Right), None, Some(Alignment::Center)],
+/// );
+/// assert_eq!(
+///     reflow_table(&aligned),
+///     ["| a   | b   | c   |", "| --: | :-- | :-: |"]
+/// );
+/// ```
+#[must_use]
+pub fn align_columns(lines: &[String], alignments: &[Option<Alignment>]) -> Vec<String> {
+    let mut out = lines.to_vec();
+    let Some(sep) = out
+        .iter_mut()
+        .find(|line| is_separator_line(line) && line.contains('-'))
+    else {
+        return out;
+    };
+    let indent = sep[..sep.len() - sep.trim_start().len()].to_string();
+    let cells: Vec<String> = split_cells(sep)
+        .into_iter()
+        .enumerate()
+        .map(|(i, cell)| match alignments.get(i) {
+            Some(Some(alignment)) => alignment.marker().to_string(),
+            _ => cell,
+        })
+        .collect();
+    *sep = format!("{indent}| {} |", cells.join(" | "));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for caller-chosen column alignments.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    const LCR: &[Option<Alignment>] = &[
+        Some(Alignment::Left),
+        Some(Alignment::Center),
+        Some(Alignment::Right),
+    ];
+
+    #[rstest]
+    #[case::all_columns("|a|b|c|\n|-|-|-|", LCR, "|a|b|c|\n| :-- | :-: | --: |")]
+    #[case::keeps_unset_columns(
+        "|a|b|\n|:-|-|",
+        &[None, Some(Alignment::Right)],
+        "|a|b|\n| :- | --: |"
+    )]
+    #[case::keeps_indent("  |a|b|\n  |-|-|", LCR, "  |a|b|\n  | :-- | :-: |")]
+    #[case::no_separator("|a|b|\n|1|2|", LCR, "|a|b|\n|1|2|")]
+    fn rewrites_the_separator_row(
+        #[case] input: &str,
+        #[case] alignments: &[Option<Alignment>],
+        #[case] expected: &str,
+    ) {
+        assert_eq!(align_columns(&lines(input), alignments), lines(expected));
+    }
+
+    #[rstest]
+    #[case("l", Alignment::Left)]
+    #[case("Centre", Alignment::Center)]
+    #[case(" r ", Alignment::Right)]
+    #[case("-", Alignment::Default)]
+    fn parses_alignment_names(#[case] name: &str, #[case] expected: Alignment) {
+        assert_eq!(name.parse::<Alignment>(), Ok(expected));
+    }
+
+    #[test]
+    fn rejects_unknown_alignments() {
+        assert!("middle".parse::<Alignment>().is_err());
+    }
+}
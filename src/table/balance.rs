@@ -0,0 +1,180 @@
+//! Column width allocation for tables with a maximum width.
+//!
+//! A padded table is as wide as the sum of its column widths plus three
+//! columns of pipes and spaces per cell. When that exceeds the limit,
+//! [`allocate_widths`] narrows the columns according to a
+//! [`WidthAllocation`], but never below their floors, the width of their
+//! longest cell, so every row keeps the same width. Only padding beyond the
+//! longest cell is given up, and a table that still does not fit is left to
+//! the [`TableOverflow`](super::TableOverflow) policy.
+
+/// How [`allocate_widths`] fits columns into a width budget.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WidthAllocation {
+    /// Keep every column at its natural width, letting the table overflow.
+    Overflow,
+    /// Take the reduction from the columns wider than an even share of the
+    /// budget, in proportion to how far each exceeds that share.
+    #[default]
+    Proportional,
+}
+
+/// Returns the display width of a padded table row with these column widths
+/// and `indent_width` columns of indentation.
+#[must_use]
+pub fn table_width(widths: &[usize], indent_width: usize) -> usize {
+    indent_width + widths.iter().sum::<usize>() + 3 * widths.len() + 1
+}
+
+/// Narrows `widths` so a padded table fits within `max_width` columns.
+///
+/// `widths` are the column widths, `floors` the narrowest each column may
+/// become, normally the width of its longest cell, and `indent_width` the
+/// table's indentation. Tables that already fit are returned unchanged.
+/// Otherwise the columns are reduced according to `strategy`, but never below
+/// their floors, so a table whose cells are too wide for the limit still
+/// exceeds it.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::table::{WidthAllocation, allocate_widths, table_width};
+///
+/// let widths = [4, 30, 50];
+/// assert_eq!(table_width(&widths, 0), 94);
+/// let fitted = allocate_widths(&widths, &[3, 3, 3], 0, 80, WidthAllocation::Proportional);
+/// assert_eq!(fitted, [4, 27, 39]);
+/// assert_eq!(table_width(&fitted, 0), 80);
+/// let floored = allocate_widths(&widths, &[4, 30, 45], 0, 80, WidthAllocation::Proportional);
+/// assert_eq!(floored, [4, 30, 45]);
+/// assert_eq!(
+///     allocate_widths(&widths, &[3, 3, 3], 0, 80, WidthAllocation::Overflow),
+///     widths
+/// );
+/// ```
+#[must_use]
+pub fn allocate_widths(
+    widths: &[usize],
+    floors: &[usize],
+    indent_width: usize,
+    max_width: usize,
+    strategy: WidthAllocation,
+) -> Vec<usize> {
+    let natural = table_width(widths, indent_width);
+    if strategy == WidthAllocation::Overflow || natural <= max_width || widths.is_empty() {
+        return widths.to_vec();
+    }
+    let overhead = natural - widths.iter().sum::<usize>();
+    shrink_proportionally(widths, floors, max_width.saturating_sub(overhead))
+}
+
+/// Reduces the columns wider than `budget / widths.len()` until their sum is
+/// `budget`, sharing the cut by each column's excess over that share.
+///
+/// A column is never cut below its entry in `floors`. When a floor stops a
+/// cut, the rest is shared again among the columns that can still give up
+/// width, first those above the even share and then the others, until the
+/// budget is met or every column is at its floor.
+fn shrink_proportionally(widths: &[usize], floors: &[usize], budget: usize) -> Vec<usize> {
+    let mut out = widths.to_vec();
+    let total: usize = widths.iter().sum();
+    let mut excess = total - budget.min(total);
+    let share = budget / widths.len();
+    while excess > 0 {
+        let slack: Vec<usize> = out
+            .iter()
+            .enumerate()
+            .map(|(idx, &width)| width.saturating_sub(floors.get(idx).copied().unwrap_or(0)))
+            .collect();
+        let over: Vec<usize> = out
+            .iter()
+            .zip(&slack)
+            .map(|(&width, &slack)| {
+                if slack == 0 {
+                    0
+                } else {
+                    width.saturating_sub(share)
+                }
+            })
+            .collect();
+        let weights = if over.iter().any(|&o| o > 0) {
+            over
+        } else {
+            slack.clone()
+        };
+        if weights.iter().all(|&weight| weight == 0) {
+            break;
+        }
+        for ((width, cut), slack) in out.iter_mut().zip(share_out(&weights, excess)).zip(slack) {
+            let cut = cut.min(slack);
+            *width -= cut;
+            excess -= cut;
+        }
+    }
+    out
+}
+
+/// Splits `total` in proportion to `weights`, using largest-remainder
+/// rounding so the parts add up to exactly `total`.
+fn share_out(weights: &[usize], total: usize) -> Vec<usize> {
+    let weight_total: usize = weights.iter().sum();
+    let mut parts: Vec<(usize, usize)> = weights
+        .iter()
+        .map(|&weight| {
+            let scaled = weight * total;
+            (scaled / weight_total, scaled % weight_total)
+        })
+        .collect();
+    let assigned: usize = parts.iter().map(|(part, _)| part).sum();
+    let mut order: Vec<usize> = (0..parts.len()).collect();
+    order.sort_by(|&a, &b| parts[b].1.cmp(&parts[a].1).then(a.cmp(&b)));
+    for &idx in order.iter().take(total - assigned) {
+        parts[idx].0 += 1;
+    }
+    parts.into_iter().map(|(part, _)| part).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for column width allocation.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::fits(&[3, 5], &[3, 3], 20, &[3, 5])]
+    #[case::widest_takes_most(&[4, 30, 50], &[3, 3, 3], 80, &[4, 27, 39])]
+    #[case::equal_columns(&[40, 40], &[3, 3], 71, &[32, 32])]
+    #[case::narrow_columns_kept(&[3, 3, 60], &[3, 3, 3], 40, &[3, 3, 24])]
+    #[case::only_the_widest_shrinks(&[5, 10, 40], &[3, 3, 3], 60, &[5, 10, 35])]
+    #[case::minimum_width(&[10, 10], &[3, 3], 7, &[3, 3])]
+    #[case::floor_passes_the_cut_on(&[10, 30, 30], &[10, 28, 10], 60, &[10, 28, 12])]
+    #[case::below_the_share(&[10, 30], &[5, 30], 38, &[5, 30])]
+    #[case::floors_exceed_the_limit(&[12, 40], &[10, 40], 20, &[10, 40])]
+    fn proportional_allocation(
+        #[case] widths: &[usize],
+        #[case] floors: &[usize],
+        #[case] max_width: usize,
+        #[case] expected: &[usize],
+    ) {
+        assert_eq!(
+            allocate_widths(widths, floors, 0, max_width, WidthAllocation::Proportional),
+            expected
+        );
+    }
+
+    #[test]
+    fn indentation_counts_towards_the_limit() {
+        let fitted = allocate_widths(&[20, 20], &[3, 3], 4, 40, WidthAllocation::Proportional);
+        assert_eq!(table_width(&fitted, 4), 40);
+    }
+
+    #[test]
+    fn overflow_keeps_natural_widths() {
+        assert_eq!(
+            allocate_widths(&[40, 40], &[3, 3], 0, 20, WidthAllocation::Overflow),
+            [40, 40]
+        );
+    }
+}
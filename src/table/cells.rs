@@ -0,0 +1,61 @@
+//! Cell-level access to Markdown table rows.
+//!
+//! Rows are split on unescaped pipes only, matching how GFM delimits cells,
+//! so escaped `\|` sequences stay inside their cell.
+
+/// Byte offsets of the pipes in `row` that are not escaped with `\`.
+pub(super) fn unescaped_pipes(row: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut escaped = false;
+    row.char_indices().filter_map(move |(offset, ch)| {
+        let is_pipe = ch == '|' && !escaped;
+        escaped = ch == '\\' && !escaped;
+        is_pipe.then_some(offset)
+    })
+}
+
+/// Apply `f` to the text of every cell in the table `lines`.
+///
+/// Rows are split on unescaped pipes, and the whitespace around each cell is
+/// kept, so a row whose cells `f` leaves alone comes back unchanged. Inline
+/// transforms run through this helper before [`reflow_table`] so that column
+/// widths are measured on the rewritten content, and so that a code span or
+/// emphasis run can never reach across a cell boundary.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::table::map_cells;
+///
+/// let lines = vec!["| a... | b \\| c |".to_string()];
+/// let out = map_cells(&lines, |cell| cell.to_uppercase());
+/// assert_eq!(out, vec!["| A... | B \\| C |"]);
+/// ```
+pub fn map_cells<F>(lines: &[String], mut f: F) -> Vec<String>
+where
+    F: FnMut(&str) -> String,
+{
+    lines
+        .iter()
+        .map(|line| {
+            let mut out = String::with_capacity(line.len());
+            let mut start = 0;
+            for end in unescaped_pipes(line).chain(std::iter::once(line.len())) {
+                let cell = &line[start..end];
+                let text = cell.trim();
+                if text.is_empty() {
+                    out.push_str(cell);
+                } else {
+                    let lead = cell.len() - cell.trim_start().len();
+                    out.push_str(&cell[..lead]);
+                    out.push_str(&f(text));
+                    out.push_str(&cell[lead + text.len()..]);
+                }
+                if end < line.len() {
+                    out.push('|');
+                }
+                start = end + 1;
+            }
+            out
+        })
+        .collect()
+}
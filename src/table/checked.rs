@@ -0,0 +1,134 @@
+//! Table reflow that reports why a table was left unchanged.
+//!
+//! [`reflow_table`](super::reflow_table) returns a table it cannot parse as
+//! it was written. [`try_reflow_table`] runs the same parser but returns an
+//! [`Error`] naming the row that stopped it.
+
+use super::{TableStyle, is_separator_line, split_cells, try_reflow_table_within};
+use crate::Error;
+
+/// A row whose cell count differs from the first row's, by its index among
+/// the table's parsed rows.
+pub(super) struct RowMismatch {
+    pub(super) row: usize,
+    pub(super) expected: usize,
+    pub(super) found: usize,
+}
+
+impl RowMismatch {
+    /// Converts the mismatch into an [`Error`] on the line of `lines` that
+    /// holds the row. Separator rows, escaped `\-` lines, and rows of empty
+    /// cells are skipped when counting, as the parser drops them.
+    pub(super) fn into_error(self, lines: &[String]) -> Error {
+        let line = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| {
+                let trimmed = line.trim();
+                !is_separator_line(trimmed)
+                    && !trimmed.starts_with("\\-")
+                    && split_cells(trimmed).iter().any(|cell| !cell.is_empty())
+            })
+            .nth(self.row)
+            .map_or(lines.len(), |(index, _)| index + 1);
+        Error::MismatchedColumns {
+            line,
+            expected: self.expected,
+            found: self.found,
+        }
+    }
+}
+
+/// Returns an [`Error`] for a separator row of `found` cells in a table of
+/// `expected` columns, on the first separator line of `lines`.
+pub(super) fn separator_columns(lines: &[String], expected: usize, found: usize) -> Error {
+    let line = lines
+        .iter()
+        .position(|line| is_separator_line(line.trim()))
+        .map_or(1, |index| index + 1);
+    Error::SeparatorColumns {
+        line,
+        expected,
+        found,
+    }
+}
+
+/// Reflows a Markdown table like [`reflow_table`](super::reflow_table),
+/// returning an [`Error`] instead of the original lines when the table
+/// cannot be parsed.
+///
+/// # Errors
+/// Returns [`Error::MismatchedColumns`] when a row has a different number of
+/// cells from the first row, and [`Error::SeparatorColumns`] when the
+/// separator row does not match the table's columns.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::{Error, table::try_reflow_table};
+///
+/// let lines: Vec<String> = ["| a | b |", "| --- | --- |", "| 1 |"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     try_reflow_table(&lines),
+///     Err(Error::MismatchedColumns {
+///         line: 3,
+///         expected: 2,
+///         found: 1,
+///     })
+/// );
+/// ```
+pub fn try_reflow_table(lines: &[String]) -> Result<Vec<String>, Error> {
+    try_reflow_table_within(lines, TableStyle::Padded, None, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for fallible table reflow.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::short_row("| a | b |\n|---|---|\n| 1 | 2 |\n| 3 |", 4, 2, 1)]
+    #[case::skips_empty_rows("| a | b |\n|   |   |\n| 1 | 2 | 3 |", 3, 2, 3)]
+    fn reports_the_mismatched_row(
+        #[case] input: &str,
+        #[case] line: usize,
+        #[case] expected: usize,
+        #[case] found: usize,
+    ) {
+        assert_eq!(
+            try_reflow_table(&lines(input)),
+            Err(Error::MismatchedColumns {
+                line,
+                expected,
+                found,
+            })
+        );
+    }
+
+    #[test]
+    fn reports_the_separator_row() {
+        assert_eq!(
+            try_reflow_table(&lines("| head |\n| --- | --- |\n| body |")),
+            Err(Error::SeparatorColumns {
+                line: 2,
+                expected: 1,
+                found: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn reflows_valid_tables() {
+        assert_eq!(
+            try_reflow_table(&lines("|a|b|\n|-|-|")),
+            Ok(lines("| a   | b   |\n| --- | --- |"))
+        );
+    }
+}
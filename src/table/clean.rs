@@ -0,0 +1,67 @@
+//! Detection of tables that are already aligned.
+
+use unicode_width::UnicodeWidthStr;
+
+/// Report whether a Markdown table is already aligned and can be left as is.
+///
+/// A table is clean when every line shares the same indent, starts and ends
+/// with a pipe, and places its unescaped pipes in the same display columns,
+/// and when its second line, and only that line, is a valid separator row.
+/// Padding that differs from [`reflow_table`]'s output is accepted.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::table::is_clean_table;
+///
+/// let lines: Vec<String> = ["|  A  |  B  |", "|:---:|-----|", "|  1  |  2  |"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert!(is_clean_table(&lines));
+/// assert!(!is_clean_table(&[
+///     "| A | Bee |".to_string(),
+///     "|---|---|".to_string()
+/// ]));
+/// ```
+#[must_use]
+pub fn is_clean_table(lines: &[String]) -> bool {
+    if lines.len() < 2 {
+        return false;
+    }
+    let indent = &lines[0][..lines[0].len() - lines[0].trim_start().len()];
+    let mut columns = None;
+    for (index, line) in lines.iter().enumerate() {
+        let Some(row) = line.strip_prefix(indent).map(str::trim_end) else {
+            return false;
+        };
+        if !row.starts_with('|') || !row.ends_with('|') || row.len() < 2 {
+            return false;
+        }
+        if (index == 1) != is_separator_row(row) {
+            return false;
+        }
+        let pipes = pipe_columns(row);
+        if columns.get_or_insert_with(|| pipes.clone()) != &pipes {
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns `true` when every cell of `row` is `---` with optional colons.
+fn is_separator_row(row: &str) -> bool {
+    row[1..row.len() - 1].split('|').all(|cell| {
+        let cell = cell.trim();
+        let dashes = cell.strip_prefix(':').unwrap_or(cell);
+        let dashes = dashes.strip_suffix(':').unwrap_or(dashes);
+        !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+    })
+}
+
+/// Display columns of the unescaped pipes in `row`.
+fn pipe_columns(row: &str) -> Vec<usize> {
+    super::cells::unescaped_pipes(row)
+        .map(|offset| UnicodeWidthStr::width(&row[..offset]))
+        .collect()
+}
@@ -0,0 +1,131 @@
+//! Containment of tables still too wide after balancing.
+//!
+//! [`allocate_widths`](super::allocate_widths) never narrows a column below
+//! its longest cell, so a table whose cells are too wide stays wider than the
+//! maximum whatever the balancing. A site
+//! whose layout breaks on such a table can choose a [`TableOverflow`] policy
+//! that moves it into a container the page can scroll sideways instead.
+
+use tracing::warn;
+
+/// Marker written above a table inside a scrolling `<div>`, so later runs
+/// know it is already contained. HTML blocks are masked while tables are
+/// reflowed, but `mdtablefix:` directive comments stay visible.
+pub(crate) const SCROLL_MARKER: &str = "<!-- mdtablefix: overflow-scroll -->";
+
+/// Opening tag of the scrolling container.
+const SCROLL_OPEN: &str = "<div style=\"overflow-x: auto\">";
+
+/// What to do with a table still wider than
+/// [`Options::max_table_width`](crate::Options::max_table_width) after its
+/// columns are narrowed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum TableOverflow {
+    /// Emit the over-wide table as it is.
+    #[default]
+    Keep,
+    /// Move the table into a fenced `text` code block, which keeps its
+    /// alignment and scrolls on most sites, but no longer renders as a table.
+    Fence,
+    /// Wrap the table in a `<div style="overflow-x: auto">` element so it
+    /// still renders as a table and scrolls sideways.
+    Scroll,
+}
+
+/// Returns `table` placed in the container `overflow` asks for, logging a
+/// warning naming the table's first line.
+///
+/// Callers pass the table at its natural column widths, since narrowing only
+/// helps a table that must fit the page.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::table::{TableOverflow, contain_table};
+///
+/// let table: Vec<String> = ["| a | b |", "| --- | --- |"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     contain_table(&table, TableOverflow::Fence),
+///     ["```text", "| a | b |", "| --- | --- |", "```"]
+/// );
+/// assert_eq!(contain_table(&table, TableOverflow::Keep), table);
+/// ```
+#[must_use]
+pub fn contain_table(table: &[String], overflow: TableOverflow) -> Vec<String> {
+    let Some(first) = table.first() else {
+        return Vec::new();
+    };
+    let indent = &first[..first.len() - first.trim_start().len()];
+    let mut out = Vec::with_capacity(table.len() + 5);
+    match overflow {
+        TableOverflow::Keep => return table.to_vec(),
+        TableOverflow::Fence => {
+            let fence = "`".repeat(longest_backtick_run(table).max(2) + 1);
+            out.push(format!("{indent}{fence}text"));
+            out.extend_from_slice(table);
+            out.push(format!("{indent}{fence}"));
+        }
+        TableOverflow::Scroll => {
+            out.push(format!("{indent}{SCROLL_OPEN}"));
+            out.push(String::new());
+            out.push(format!("{indent}{SCROLL_MARKER}"));
+            out.extend_from_slice(table);
+            out.push(String::new());
+            out.push(format!("{indent}</div>"));
+        }
+    }
+    warn!(
+        table = first.trim(),
+        policy = ?overflow,
+        "table exceeds the maximum table width after balancing; contained it"
+    );
+    out
+}
+
+/// Returns the length of the longest run of backticks in `lines`, so a
+/// fence can be chosen that no cell closes early.
+fn longest_backtick_run(lines: &[String]) -> usize {
+    lines
+        .iter()
+        .flat_map(|line| line.split(|c| c != '`'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for containing over-wide tables.
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[test]
+    fn fence_outlasts_backticks_in_cells() {
+        let table = lines("  | ```x``` | b |\n  | --- | --- |");
+        assert_eq!(
+            contain_table(&table, TableOverflow::Fence),
+            lines("  ````text\n  | ```x``` | b |\n  | --- | --- |\n  ````")
+        );
+    }
+
+    #[test]
+    fn scroll_marks_the_table() {
+        let table = lines("| a | b |\n| --- | --- |");
+        assert_eq!(
+            contain_table(&table, TableOverflow::Scroll),
+            lines(
+                "<div style=\"overflow-x: auto\">\n\n<!-- mdtablefix: overflow-scroll -->\n| a | \
+                 b |\n| --- | --- |\n\n</div>"
+            )
+        );
+    }
+}
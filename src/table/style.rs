@@ -0,0 +1,87 @@
+//! Table layout styles and the reflow entry points that take them.
+
+use super::reflow_table_within;
+
+/// How [`reflow_table_with_style`] lays out a table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum TableStyle {
+    /// Pad every cell to its column width so all pipes line up.
+    #[default]
+    Padded,
+    /// Leave cells at their own width and line up only the outer pipes.
+    EdgesOnly,
+    /// Leave cells at their own width with one space either side of every
+    /// pipe, and write three-dash separator cells.
+    Compact,
+}
+
+/// Reflow a Markdown table using the given [`TableStyle`].
+///
+/// Invalid tables are returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::table::{TableStyle, reflow_table_with_style};
+///
+/// let lines: Vec<String> = ["|a|bb|", "|-|-|", "|ccc|d|"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     reflow_table_with_style(&lines, TableStyle::EdgesOnly),
+///     vec!["| a | bb    |", "| --- | --- |", "| ccc | d   |"]
+/// );
+/// ```
+#[must_use]
+pub fn reflow_table_with_style(lines: &[String], style: TableStyle) -> Vec<String> {
+    reflow_table_within(lines, style, None, &[])
+}
+
+/// Reflow a Markdown table, narrowing its columns with
+/// [`WidthAllocation::Proportional`](super::WidthAllocation::Proportional) so padded rows fit
+/// within `max_width` display columns where possible.
+///
+/// Columns only give up padding beyond their longest cell, so every row keeps
+/// the same width. A table whose cells are too wide for the limit is returned
+/// at its natural widths, for the caller's
+/// [`TableOverflow`](super::TableOverflow) policy to contain.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::table::{TableStyle, reflow_table_with_max_width};
+///
+/// let lines: Vec<String> = ["| a | b |", "|---|---|", "| short | a much longer cell |"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     reflow_table_with_max_width(&lines, TableStyle::Padded, 26),
+///     vec![
+///         "| a     | b                  |",
+///         "| ----- | ------------------ |",
+///         "| short | a much longer cell |",
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn reflow_table_with_max_width(
+    lines: &[String],
+    style: TableStyle,
+    max_width: usize,
+) -> Vec<String> {
+    reflow_table_within(lines, style, Some(max_width), &[])
+}
+
+/// Reflow a padded Markdown table, keeping each column at least as wide as
+/// the matching entry of `min_widths`.
+#[cfg(feature = "html")]
+pub(crate) fn reflow_table_with_min_widths(lines: &[String], min_widths: &[usize]) -> Vec<String> {
+    reflow_table_within(lines, TableStyle::Padded, None, min_widths)
+}
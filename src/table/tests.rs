@@ -0,0 +1,160 @@
+//! Unit tests for table parsing and formatting.
+
+use rstest::rstest;
+
+use super::*;
+
+#[test]
+fn sep_index_within_bounds() {
+    assert_eq!(sep_index_within(Some(1), 3), Some(1));
+    assert_eq!(sep_index_within(Some(3), 3), None);
+    assert_eq!(sep_index_within(None, 3), None);
+}
+
+#[test]
+fn detect_row_mismatch() {
+    let rows = vec![
+        vec!["a".to_string(), "b".to_string()],
+        vec!["1".to_string(), "2".to_string()],
+    ];
+    assert!(mismatched_row(&rows, false).is_none());
+
+    let mismatch = vec![
+        vec!["a".to_string(), "b".to_string()],
+        vec!["1".to_string()],
+    ];
+    let found = mismatched_row(&mismatch, false).expect("row 1 is short");
+    assert_eq!((found.row, found.expected, found.found), (1, 2, 1));
+
+    let with_sep = vec![
+        vec!["a".to_string(), "b".to_string()],
+        vec!["---".to_string(), "---".to_string()],
+        vec!["1".to_string(), "2".to_string()],
+    ];
+    assert!(mismatched_row(&with_sep, false).is_none());
+
+    assert!(mismatched_row(&mismatch, true).is_none());
+}
+
+#[rstest]
+#[case(vec![2], vec!["---".to_string()], vec!["---".to_string()])]
+#[case(vec![5], vec![":---".to_string()], vec![":----".to_string()])]
+#[case(vec![5], vec!["---:".to_string()], vec!["----:".to_string()])]
+#[case(vec![5], vec![":--:".to_string()], vec![":---:".to_string()])]
+fn format_separator_cells_preserves_alignment_markers(
+    #[case] widths: Vec<usize>,
+    #[case] cells: Vec<String>,
+    #[case] expected: Vec<String>,
+) {
+    assert_eq!(format_separator_cells(&widths, &cells), expected);
+}
+
+#[rstest]
+#[case::padded(None, &["| left | centre | right |", "| :--- | :----: | ----: |", "| x    | y      | z     |"])]
+#[case::over_the_limit(Some(20), &["| left | centre | right |", "| :--- | :----: | ----: |", "| x    | y      | z     |"])]
+fn reflow_keeps_alignment_markers(#[case] max_width: Option<usize>, #[case] expected: &[&str]) {
+    let lines: Vec<String> = ["|left|centre|right|", "|:-|:-:|-:|", "|x|y|z|"]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let reflowed = match max_width {
+        Some(width) => reflow_table_with_max_width(&lines, TableStyle::Padded, width),
+        None => reflow_table(&lines),
+    };
+    assert_eq!(reflowed, expected);
+    assert!(reflowed.iter().all(|row| row.len() == reflowed[0].len()));
+}
+
+/// Rows must all be as wide as each other, and within `max_width` whenever
+/// the longest cells leave room; otherwise the table keeps its natural widths.
+#[rstest]
+#[case::fits(60)]
+#[case::exact(59)]
+#[case::narrowed(40)]
+#[case::tight(30)]
+fn reflow_with_max_width_renders_rows_within_it(#[case] max_width: usize) {
+    let lines: Vec<String> = [
+        "| id | name | description |",
+        "|---|---|---|",
+        "| 1 | widget | a part used to hold the frame together |",
+        "| 2 | sprocket | short |",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect();
+    let natural = reflow_table(&lines);
+    let reflowed = reflow_table_with_max_width(&lines, TableStyle::Padded, max_width);
+    let widths: Vec<usize> = reflowed
+        .iter()
+        .map(|row| UnicodeWidthStr::width(row.as_str()))
+        .collect();
+    assert!(
+        widths.iter().all(|&width| width == widths[0]),
+        "{reflowed:#?}"
+    );
+    if UnicodeWidthStr::width(natural[0].as_str()) <= max_width {
+        assert!(widths[0] <= max_width, "{reflowed:#?}");
+    } else {
+        assert_eq!(reflowed, natural);
+    }
+}
+
+#[test]
+fn reflow_pads_cells_by_display_width() {
+    let lines: Vec<String> = ["|名前|b|", "|-|-|", "|ab|🙂x|", "|e\u{301}|ｆｕｌｌ|"]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    assert_eq!(
+        reflow_table(&lines),
+        [
+            "| 名前 | b        |",
+            "| ---- | -------- |",
+            "| ab   | 🙂x      |",
+            "| e\u{301}    | ｆｕｌｌ |",
+        ]
+    );
+}
+
+#[test]
+fn format_separator_cells_returns_empty_when_counts_mismatch() {
+    let sep_cells = vec!["---".to_string()];
+
+    assert!(format_separator_cells(&[3, 4], &sep_cells).is_empty());
+}
+
+#[rstest]
+#[case::compact_padding(&["|a|bb|", "|-|--|", "|1|22|"], true)]
+#[case::wide_padding(&["  |  A  | B |", "  |:---:|--:|", "  | `x` |\\| |"], true)]
+#[case::cjk_width(&["| 名前 | B |", "| ---- | - |", "| ab   | 1 |"], true)]
+#[case::ragged(&["| a | bb |", "|---|---|", "| 1 | 2 |"], false)]
+#[case::no_separator(&["| a | b |", "| 1 | 2 |"], false)]
+#[case::second_separator(&["| a |", "| - |", "| - |"], false)]
+#[case::missing_edge_pipe(&["| a | b", "| - | -", "| 1 | 2"], false)]
+#[case::mixed_indent(&["| a |", " | - |"], false)]
+#[case::header_only(&["| a |"], false)]
+fn detects_clean_tables(#[case] lines: &[&str], #[case] expected: bool) {
+    let lines: Vec<String> = lines.iter().map(|line| (*line).to_string()).collect();
+    assert_eq!(is_clean_table(&lines), expected);
+}
+
+#[test]
+fn reflow_table_returns_original_lines_for_mismatched_separator_columns() {
+    let lines = vec![
+        "| head |".to_string(),
+        "| --- | --- |".to_string(),
+        "| body |".to_string(),
+    ];
+
+    assert_eq!(reflow_table(&lines), lines);
+}
+
+#[rstest]
+#[case("| a | b |", "| A | B |")]
+#[case("  a |  b  ", "  A |  B  ")]
+#[case("| x \\| y |   |", "| X \\| Y |   |")]
+#[case("| --- | :-: |", "| --- | :-: |")]
+fn map_cells_rewrites_cell_text_only(#[case] row: &str, #[case] expected: &str) {
+    let out = map_cells(&[row.to_string()], str::to_uppercase);
+    assert_eq!(out, vec![expected.to_string()]);
+}
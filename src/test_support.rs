@@ -0,0 +1,4 @@
+//! Helpers shared by the unit tests of the library and the binary.
+
+/// Splits `text` into the owned lines the formatting passes take.
+pub(crate) fn lines(text: &str) -> Vec<String> { text.lines().map(str::to_string).collect() }
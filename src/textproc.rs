@@ -2,11 +2,35 @@
 //!
 //! This module reuses the tokenizer from the [`crate::wrap`] module and offers
 //! a streaming API for rewriting Markdown. Each helper tokenizes lines
-//! on the fly, feeds the resulting tokens to caller-provided logic, and
+//! on the fly with [`markdown_tokens`], feeds the resulting tokens to caller-provided logic, and
 //! then reconstructs the lines. Trailing blank lines roundtrip
-//! correctly.
+//! correctly. [`is_escaped`] gives every transform the tokenizer's rule for
+//! backslash escapes, so text tokens matched by regular expressions skip the
+//! punctuation an author escaped.
 
-pub use crate::wrap::{Token, tokenize_markdown};
+use crate::wrap::has_odd_backslash_escape_bytes;
+pub use crate::wrap::{MarkdownTokens, Token, markdown_tokens, tokenize_markdown};
+
+/// Returns `true` when the character starting at byte `index` of `text` is
+/// escaped by a backslash.
+///
+/// A character is escaped when an odd number of backslashes directly
+/// precedes it, so in `\\*` the backslashes escape each other and the `*`
+/// stays Markdown syntax.
+///
+/// # Examples
+///
+/// ```rust
+/// use mdtablefix::textproc::is_escaped;
+///
+/// assert!(is_escaped(r"a\*b", 2));
+/// assert!(!is_escaped(r"a\\*b", 3));
+/// assert!(!is_escaped("*b", 0));
+/// ```
+#[must_use]
+pub fn is_escaped(text: &str, index: usize) -> bool {
+    has_odd_backslash_escape_bytes(text.as_bytes(), index)
+}
 
 /// Append a [`Token`] to an output buffer without modification.
 ///
@@ -77,7 +101,7 @@ where
 
     let source = lines.join("\n");
     let mut out = String::with_capacity(source.len());
-    for token in tokenize_markdown(&source) {
+    for token in markdown_tokens(&source) {
         f(token, &mut out);
     }
 
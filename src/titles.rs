@@ -0,0 +1,327 @@
+//! Consistency between a page's first heading and its title.
+//!
+//! Documentation site generators take a page's navigation title from its
+//! front matter `title`, or from its file name when there is none, while
+//! readers see the first `#` heading. [`check_title`] reports a
+//! first level-one heading that says something else, and [`fix_title`]
+//! rewrites that heading to the front matter title, or to a title built from
+//! the file name.
+
+use std::{fmt, path::Path, sync::LazyLock};
+
+use regex::Regex;
+
+use crate::{
+    anchors::heading_slug,
+    extract::{TextBlockKind, extract_text, plain_text},
+    frontmatter::split_leading_yaml_frontmatter,
+};
+
+static ATX_H1_RE: LazyLock<Regex> = lazy_regex!(
+    r"^(?P<open> {0,3}#[ \t]+)(?P<text>.*?)(?P<rest>(?:[ \t]+\{#[^\s{}]+\})?(?:[ \t]+#+)?[ \t]*)$",
+    "level-one heading pattern should compile",
+);
+
+static SETEXT_H1_RE: LazyLock<Regex> = lazy_regex!(
+    r"^ {0,3}=+[ \t]*$",
+    "setext underline pattern should compile"
+);
+
+static HEADING_ID_RE: LazyLock<Regex> = lazy_regex!(
+    r"\s*\{#[^\s{}]+\}$",
+    "heading id attribute pattern should compile",
+);
+
+/// File stems whose page takes its name from the enclosing directory.
+const INDEX_STEMS: [&str; 3] = ["index", "_index", "readme"];
+
+/// Where the title a heading was compared against came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TitleSource {
+    /// The `title` key of the YAML front matter.
+    FrontMatter,
+    /// The file name, compared as a slug.
+    FileName,
+}
+
+/// A first level-one heading that does not match the page title.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TitleMismatch {
+    /// One-based line number of the heading.
+    pub line: usize,
+    /// Plain text of the heading.
+    pub heading: String,
+    /// The title the heading should match: the front matter title, or the
+    /// slug of the file name.
+    pub expected: String,
+    /// Where `expected` came from.
+    pub source: TitleSource,
+}
+
+impl fmt::Display for TitleMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match self.source {
+            TitleSource::FrontMatter => "front matter title",
+            TitleSource::FileName => "file name",
+        };
+        write!(
+            f,
+            "heading \"{}\" does not match the {source} \"{}\"",
+            self.heading, self.expected
+        )
+    }
+}
+
+/// Compares the first level-one heading of `lines` with the page title.
+///
+/// The heading is compared by its plain text with the front matter `title`
+/// when there is one. Otherwise its slug is compared with the stem of
+/// `path`, ignoring a leading number prefix such as `01-` and treating `_` as
+/// `-`; `index` and `README` pages, and documents without a path, are not
+/// checked against their name. A document without a level-one heading has
+/// nothing to compare.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use mdtablefix::titles::{TitleSource, check_title};
+///
+/// let lines: Vec<String> = ["---", "title: Setup", "---", "", "# Install"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// let mismatch = check_title(&lines, None).unwrap();
+/// assert_eq!(
+///     (mismatch.line, mismatch.source),
+///     (5, TitleSource::FrontMatter)
+/// );
+/// assert_eq!(mismatch.expected, "Setup");
+///
+/// let lines = vec!["# Getting Started".to_string()];
+/// assert_eq!(
+///     check_title(&lines, Some(Path::new("docs/02-getting_started.md"))),
+///     None
+/// );
+/// ```
+#[must_use]
+pub fn check_title(lines: &[String], path: Option<&Path>) -> Option<TitleMismatch> {
+    let (line, heading) = first_h1(lines)?;
+    let (frontmatter, _) = split_leading_yaml_frontmatter(lines);
+    if let Some(title) = frontmatter_title(frontmatter) {
+        return (plain_text(&title) != heading).then_some(TitleMismatch {
+            line,
+            heading,
+            expected: title,
+            source: TitleSource::FrontMatter,
+        });
+    }
+    let expected = file_slug(path?)?;
+    (heading_slug(&heading).replace('_', "-") != expected).then_some(TitleMismatch {
+        line,
+        heading,
+        expected,
+        source: TitleSource::FileName,
+    })
+}
+
+/// Rewrites the first level-one heading of `lines` to the page title when
+/// [`check_title`] reports that it differs.
+///
+/// The title is the front matter `title` when there is one. Otherwise it is
+/// built from the slug of `path`, with hyphens turned into spaces and the
+/// first letter capitalized, so `02-getting_started.md` gives `Getting
+/// started`. ATX headings keep their `{#id}` attribute and closing hashes, and
+/// Setext headings keep their underline. The number of lines never changes.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use mdtablefix::titles::fix_title;
+///
+/// let lines: Vec<String> = ["---", "title: Setup", "---", "# Install {#top}"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(fix_title(&lines, None)[3], "# Setup {#top}");
+///
+/// let lines = vec!["# Install".to_string()];
+/// assert_eq!(
+///     fix_title(&lines, Some(Path::new("docs/02-getting_started.md"))),
+///     ["# Getting started"]
+/// );
+/// ```
+#[must_use]
+pub fn fix_title(lines: &[String], path: Option<&Path>) -> Vec<String> {
+    let mut out = lines.to_vec();
+    let Some(mismatch) = check_title(lines, path) else {
+        return out;
+    };
+    let title = match mismatch.source {
+        TitleSource::FrontMatter => mismatch.expected,
+        TitleSource::FileName => slug_title(&mismatch.expected),
+    };
+    let index = mismatch.line - 1;
+    let line = &lines[index];
+    if let Some(caps) = ATX_H1_RE.captures(line) {
+        out[index] = format!("{}{title}{}", &caps["open"], &caps["rest"]);
+    } else if lines
+        .get(index + 1)
+        .is_some_and(|next| SETEXT_H1_RE.is_match(next))
+    {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        out[index] = format!("{indent}{title}");
+    }
+    out
+}
+
+/// Builds a sentence-case title from a file name slug.
+fn slug_title(slug: &str) -> String {
+    let words = slug.replace('-', " ");
+    let mut chars = words.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Returns the line number and plain text of the first level-one heading.
+fn first_h1(lines: &[String]) -> Option<(usize, String)> {
+    extract_text(lines)
+        .into_iter()
+        .find(|block| block.kind == TextBlockKind::Heading(1))
+        .map(|block| {
+            let text = HEADING_ID_RE.replace(&block.text, "").into_owned();
+            (block.line, text)
+        })
+}
+
+/// Reads a top-level `title` key from front matter lines, unquoting it.
+fn frontmatter_title(frontmatter: &[String]) -> Option<String> {
+    let value = frontmatter
+        .iter()
+        .find_map(|line| line.strip_prefix("title:"))?
+        .trim();
+    let title = if let Some(inner) = value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        inner.replace("\\\"", "\"")
+    } else if let Some(inner) = value
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+    {
+        inner.replace("''", "'")
+    } else if value.starts_with(['|', '>']) {
+        return None;
+    } else {
+        value.to_string()
+    };
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Returns the slug a page at `path` is named by, or `None` for index pages.
+fn file_slug(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    if INDEX_STEMS.contains(&stem.to_lowercase().as_str()) {
+        return None;
+    }
+    let unnumbered = stem.trim_start_matches(|ch: char| ch.is_ascii_digit());
+    let stem = match unnumbered.strip_prefix(['-', '_', '.']) {
+        Some(rest) if unnumbered.len() < stem.len() && !rest.is_empty() => rest,
+        _ => stem,
+    };
+    Some(heading_slug(stem).replace('_', "-"))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for title consistency checks.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case::matching("---\ntitle: Setup\n---\n# Setup", None)]
+    #[case::markup("---\ntitle: \"The `cli`\"\n---\n# The *cli*", None)]
+    #[case::quoted("---\ntitle: 'It''s here'\n---\n# It's here", None)]
+    #[case::id_attribute("---\ntitle: Setup\n---\n# Setup {#top}", None)]
+    #[case::no_heading("---\ntitle: Setup\n---\n## Install", None)]
+    #[case::fenced("---\ntitle: Setup\n---\n```\n# Install\n```", None)]
+    #[case::differs("---\ntitle: Setup\n---\n\n# Install", Some(5))]
+    #[case::setext("---\ntitle: Setup\n---\nInstall\n=======", Some(4))]
+    fn compares_with_front_matter(#[case] input: &str, #[case] line: Option<usize>) {
+        let mismatch = check_title(&lines(input), Some(Path::new("setup.md")));
+        assert_eq!(mismatch.as_ref().map(|m| m.line), line);
+        assert!(
+            mismatch.is_none_or(|m| m.source == TitleSource::FrontMatter && m.expected == "Setup")
+        );
+    }
+
+    #[rstest]
+    #[case::slug("getting-started.md", "# Getting Started", true)]
+    #[case::underscores("getting_started.md", "# Getting started", true)]
+    #[case::numbered("01-intro.md", "# Intro", true)]
+    #[case::digits_only("2024.md", "# 2024", true)]
+    #[case::index("docs/index.md", "# Anything", true)]
+    #[case::readme("README.md", "# Anything", true)]
+    #[case::differs("install.md", "# Setup", false)]
+    fn compares_with_file_name(#[case] path: &str, #[case] input: &str, #[case] matches: bool) {
+        let mismatch = check_title(&lines(input), Some(Path::new(path)));
+        assert_eq!(mismatch.is_none(), matches);
+    }
+
+    #[test]
+    fn reports_the_file_name_slug() {
+        let mismatch = check_title(&lines("# Setup"), Some(Path::new("docs/install.md")));
+        assert_eq!(
+            mismatch.unwrap().to_string(),
+            "heading \"Setup\" does not match the file name \"install\""
+        );
+    }
+
+    #[rstest]
+    #[case::atx("# Install ##", "# Setup ##")]
+    #[case::setext("Install\n=======", "Setup\n=======")]
+    #[case::matching("# Setup", "# Setup")]
+    #[case::second_heading("# Setup\n\n# Install", "# Setup\n\n# Install")]
+    fn fixes_the_first_heading(#[case] body: &str, #[case] expected: &str) {
+        let input = lines(&format!("---\ntitle: Setup\n---\n{body}"));
+        assert_eq!(
+            fix_title(&input, Some(Path::new("install.md")))[3..],
+            lines(expected)
+        );
+    }
+
+    #[rstest]
+    #[case::slug("getting-started.md", "# Install", "# Getting started")]
+    #[case::numbered(
+        "docs/02-getting_started.md",
+        "# Install {#top}",
+        "# Getting started {#top}"
+    )]
+    #[case::setext("install.md", "Setup\n=====", "Install\n=====")]
+    #[case::matching_slug("getting-started.md", "# Getting Started", "# Getting Started")]
+    #[case::index("docs/index.md", "# Anything", "# Anything")]
+    fn fixes_the_heading_from_the_file_name(
+        #[case] path: &str,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            fix_title(&lines(input), Some(Path::new(path))),
+            lines(expected)
+        );
+    }
+
+    #[test]
+    fn leaves_unnamed_pages_without_a_front_matter_title() {
+        let input = lines("# Install");
+        assert_eq!(fix_title(&input, None), input);
+    }
+}
@@ -0,0 +1,197 @@
+//! Convert Jira and Confluence wiki-markup tables into Markdown tables.
+//!
+//! Wiki markup writes header rows as `||Heading||Heading||` and body rows as
+//! `|cell|cell|`. Migration scripts often paste these verbatim into Markdown,
+//! where they render as a single paragraph. [`convert_wiki_tables`] rewrites
+//! each such block as a pipe table with a separator row and passes it to
+//! [`reflow_table`] for alignment.
+//!
+//! Only blocks that start with a header row are converted, and a header row
+//! followed by a Markdown separator row is left alone because it is already a
+//! pipe table with an empty first column. Pipes inside `[text|url]` links and
+//! `{macro|param}` markup do not split cells. Links become Markdown links,
+//! `{{monospace}}` becomes inline code, and every remaining literal pipe is
+//! escaped, as GFM requires even inside code spans.
+
+use crate::{
+    fences::FenceState,
+    table::{escape_cell_pipes, is_separator_line, reflow_table},
+};
+
+/// Convert wiki-markup tables outside fenced code into Markdown tables.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::wikimarkup::convert_wiki_tables;
+///
+/// let lines: Vec<String> = ["||Key||Summary||", "|ABC-1|See [docs|https://example.com]|"]
+///     .into_iter()
+///     .map(str::to_string)
+///     .collect();
+/// assert_eq!(
+///     convert_wiki_tables(&lines),
+///     vec![
+///         "| Key   | Summary                         |",
+///         "| ----- | ------------------------------- |",
+///         "| ABC-1 | See [docs](https://example.com) |",
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn convert_wiki_tables(lines: &[String]) -> Vec<String> {
+    let mut fences = FenceState::default();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut iter = lines.iter().peekable();
+    while let Some(line) = iter.next() {
+        let fence = fences.observe_source_line(line);
+        let next_is_separator = iter
+            .peek()
+            .is_some_and(|next| is_separator_line(next.trim()));
+        if fence.is_code() || next_is_separator || !is_header_row(line) {
+            out.push(line.clone());
+            continue;
+        }
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let header = split_wiki_cells(line);
+        let mut table = vec![
+            format!("{indent}{}", markdown_row(&header)),
+            format!("{indent}|{}|", vec![" --- "; header.len()].join("|")),
+        ];
+        while let Some(row) = iter.next_if(|next| next.trim_start().starts_with('|')) {
+            fences.observe_source_line(row);
+            let mut cells = split_wiki_cells(row);
+            // Wiki tables tolerate short rows; Markdown needs every column.
+            if cells.len() < header.len() {
+                cells.resize(header.len(), String::new());
+            }
+            table.push(format!("{indent}{}", markdown_row(&cells)));
+        }
+        out.extend(reflow_table(&table));
+    }
+    out
+}
+
+/// Returns `true` for a `||Heading||Heading||` row.
+fn is_header_row(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() > 4
+        && trimmed.starts_with("||")
+        && trimmed.ends_with("||")
+        && !split_wiki_cells(trimmed).is_empty()
+}
+
+/// Splits a wiki row on `|` and `||` delimiters outside links and macros.
+fn split_wiki_cells(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0_usize;
+    let mut chars = trimmed.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth = depth.saturating_sub(1),
+            '\\' if chars.peek() == Some(&'|') => {
+                current.push(ch);
+                current.extend(chars.next());
+                continue;
+            }
+            '|' if depth == 0 => {
+                chars.next_if_eq(&'|');
+                cells.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    cells.push(current);
+    // Leading and trailing delimiters produce empty edge cells.
+    if cells.first().is_some_and(|cell| cell.trim().is_empty()) {
+        cells.remove(0);
+    }
+    if cells.last().is_some_and(|cell| cell.trim().is_empty()) {
+        cells.pop();
+    }
+    cells.iter().map(|cell| convert_cell(cell.trim())).collect()
+}
+
+/// Rewrites wiki inline markup that would otherwise break the Markdown row.
+fn convert_cell(cell: &str) -> String {
+    let mut out = String::with_capacity(cell.len());
+    let mut rest = cell;
+    while let Some(start) = rest.find(['[', '{']) {
+        out.push_str(&escape_cell_pipes(&rest[..start]));
+        let tail = &rest[start..];
+        if let Some(code) = tail
+            .strip_prefix("{{")
+            .and_then(|inner| inner.split_once("}}"))
+        {
+            out.push('`');
+            out.push_str(&escape_cell_pipes(code.0));
+            out.push('`');
+            rest = code.1;
+        } else if let Some((link, after)) = tail
+            .strip_prefix('[')
+            .and_then(|inner| inner.split_once(']'))
+        {
+            out.push_str(&convert_link(link));
+            rest = after;
+        } else {
+            out.push_str(&escape_cell_pipes(&tail[..1]));
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(&escape_cell_pipes(rest));
+    out
+}
+
+fn convert_link(link: &str) -> String {
+    match link.split_once('|') {
+        Some((text, target)) => format!("[{}]({})", text.trim(), target.trim()),
+        None if link.contains("://") => format!("<{}>", link.trim()),
+        None => format!("[{link}]"),
+    }
+}
+
+fn markdown_row(cells: &[String]) -> String { format!("| {} |", cells.join(" | ")) }
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for wiki-markup table conversion.
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::test_support::lines;
+
+    #[rstest]
+    #[case("||a||b||", vec!["a", "b"])]
+    #[case("|1|[x|http://y]|", vec!["1", "[x](http://y)"])]
+    #[case("||row||{{a|b}}|", vec!["row", r"`a\|b`"])]
+    #[case("|[http://example.com]| {color:red}x{color} |", vec!["<http://example.com>", "{color:red}x{color}"])]
+    #[case(r"|a \| b|c", vec![r"a \| b", "c"])]
+    fn splits_cells_outside_markup(#[case] row: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(split_wiki_cells(row), expected);
+    }
+
+    #[test]
+    fn converts_table_and_keeps_surrounding_lines() {
+        let input = lines("Intro\n||H1||H2||\n|a|b|\n|c|\n\nAfter");
+        assert_eq!(
+            convert_wiki_tables(&input),
+            lines("Intro\n| H1  | H2  |\n| --- | --- |\n| a   | b   |\n| c   |     |\n\nAfter")
+        );
+    }
+
+    #[rstest]
+    #[case("|| a | b |\n| - | - | - |\n| 1 | 2 | 3 |")]
+    #[case("```\n||H1||H2||\n|a|b|\n```")]
+    #[case("|a|b|\n|c|d|")]
+    #[case("||||")]
+    fn leaves_markdown_and_fenced_content(#[case] input: &str) {
+        let input = lines(input);
+        assert_eq!(convert_wiki_tables(&input), input);
+    }
+}
@@ -5,33 +5,38 @@
 //! `UnicodeWidthStr::width` from the `unicode-width` crate as described in
 //! `docs/architecture.md#unicode-width-handling`.
 //!
-//! The [`Token`] enum and [`tokenize_markdown`] function are public so callers
-//! can perform custom token-based processing.
+//! The [`Token`] enum, the [`tokenize_markdown`] function, and its lazy
+//! counterpart [`markdown_tokens`] are public so callers can perform custom
+//! token-based processing.
 
 use std::borrow::Cow;
 
 use tracing::trace;
 
+use crate::hard_breaks::split_hard_break;
+
 mod block;
 mod blockquote;
+mod cjk;
 mod continuation;
 mod fence;
 mod inline;
 mod link_reference;
 mod paragraph;
 mod tokenize;
-use block::{BULLET_RE, FOOTNOTE_RE};
+use block::{BULLET_RE, FOOTNOTE_RE, is_summary_line};
 pub(crate) use block::{BlockKind, classify_block, leading_indent};
 pub use blockquote::BlockquotePrefix;
+pub use cjk::CjkWrap;
 use continuation::apply_continuation_chunk;
-pub(crate) use fence::{FenceObservation, ObservedFence};
-/// Fence-detection utilities re-exported for downstream callers.
-///
-/// [`FenceTracker`] maintains fenced code-block state across lines, which is
-/// useful for callers that process Markdown incrementally. [`is_fence`]
-/// inspects one line and returns the fence components (indentation, marker,
-/// info string) when the line opens a fenced code block, or `None` otherwise.
-pub use fence::{FenceTracker, is_fence};
+
+use crate::fences::FenceState;
+/// Fence detection, re-exported from [`crate::fences`] for existing callers.
+#[doc(hidden)]
+pub use crate::fences::is_fence;
+/// Former name of [`FenceState`], which now lives in [`crate::fences`].
+#[deprecated(note = "use `fences::FenceState` instead")]
+pub type FenceTracker = FenceState;
 pub(crate) use link_reference::{LinkReferenceMatcher, LinkTitleWindow, LinkTitleWindowOutcome};
 use paragraph::{ParagraphState, ParagraphWriter, PrefixLine};
 /// Token emitted by the `tokenize::segment_inline` parser and used by
@@ -44,12 +49,16 @@ use paragraph::{ParagraphState, ParagraphWriter, PrefixLine};
 /// Re-export these so callers of [`crate::textproc`] can implement custom
 /// transformations without depending on internal modules.
 pub use tokenize::Token;
-#[doc(inline)]
-pub use tokenize::tokenize_markdown;
 // Re-exported for unit tests; not used in production code.
 #[cfg(test)]
-pub(crate) use tokenize::{continuation_begins_with_closing_fence, has_unclosed_code_span};
-pub(crate) use tokenize::{has_odd_backslash_escape_bytes, link_or_image_span};
+pub(crate) use tokenize::continuation_begins_with_closing_fence;
+#[doc(inline)]
+pub use tokenize::{MarkdownTokens, markdown_tokens, tokenize_markdown};
+pub(crate) use tokenize::{
+    has_odd_backslash_escape_bytes,
+    has_unclosed_code_span,
+    link_or_image_span,
+};
 
 // Permit GFM task list markers with flexible spacing and missing post-marker
 // spaces in Markdown.
@@ -63,7 +72,7 @@ fn is_indented_code_line(line: &str) -> bool {
 }
 
 fn is_table_or_separator(line: &str) -> bool {
-    line.trim_start().starts_with('|') || crate::table::SEP_RE.is_match(line.trim())
+    line.trim_start().starts_with('|') || crate::table::is_separator_line(line.trim())
 }
 
 fn is_passthrough_block(block_kind: Option<BlockKind>, line: &str) -> bool {
@@ -73,6 +82,7 @@ fn is_passthrough_block(block_kind: Option<BlockKind>, line: &str) -> bool {
             Some(
                 BlockKind::Heading
                     | BlockKind::MarkdownlintDirective
+                    | BlockKind::MdtablefixDirective
                     | BlockKind::LinkReferenceDefinition,
             )
         )
@@ -145,22 +155,14 @@ struct PreambleLine<'a> {
     inner: &'a str,
     depth: usize,
 }
+/// Splits `line` into its paragraph text and whether it ends in a hard break.
+///
+/// Backslash and `<br>` markers stay in the text so they survive reflow in the
+/// author's chosen style; trailing-space breaks are re-applied by the
+/// [`ParagraphWriter`].
 fn line_break_parts(line: &str) -> (String, bool) {
-    let trimmed_end = line.trim_end();
-    let text_without_html_breaks = trimmed_end
-        .trim_end_matches("<br>")
-        .trim_end_matches("<br/>")
-        .trim_end_matches("<br />");
-
-    let is_trailing_spaces = line.ends_with("  ");
-    let is_html_br = trimmed_end != text_without_html_breaks;
-    let backslash_count = trimmed_end.chars().rev().take_while(|&c| c == '\\').count();
-    let is_backslash_escape = backslash_count % 2 == 1;
-    let hard_break = is_trailing_spaces || is_html_br || is_backslash_escape;
-    let text = text_without_html_breaks
-        .trim_start()
-        .trim_end_matches(' ')
-        .to_string();
+    let hard_break = split_hard_break(line).is_some();
+    let text = line.trim_start().trim_end_matches(' ').to_string();
     (text, hard_break)
 }
 
@@ -230,7 +232,7 @@ fn handle_line_preamble(
     line: PreambleLine<'_>,
     writer: &mut ParagraphWriter<'_>,
     state: &mut ParagraphState,
-    fence_tracker: &mut FenceTracker,
+    fence_tracker: &mut FenceState,
     link_matcher: LinkReferenceMatcher,
     link_title_window: &mut link_reference::LinkTitleWindow,
 ) -> bool {
@@ -259,6 +261,11 @@ fn handle_line_preamble(
         return true;
     }
 
+    if is_summary_line(line.inner) {
+        writer.push_verbatim(state, line.original);
+        return true;
+    }
+
     false
 }
 
@@ -294,11 +301,30 @@ fn dispatch_continuation(
 /// Wrap text lines to the given width.
 #[must_use]
 pub fn wrap_text(lines: &[String], width: usize) -> Vec<String> {
+    wrap_text_cjk(lines, width, CjkWrap::default())
+}
+
+/// Wrap text lines to the given width, treating paragraphs written mostly in
+/// Chinese, Japanese, or Korean according to `cjk_wrap`.
+///
+/// # Examples
+///
+/// ```
+/// use mdtablefix::wrap::{CjkWrap, wrap_text_cjk};
+///
+/// let lines = vec![
+///     "これは日本語の文です。".to_string(),
+///     "次の行です。".to_string(),
+/// ];
+/// assert_eq!(wrap_text_cjk(&lines, 80, CjkWrap::Never), lines);
+/// ```
+#[must_use]
+pub fn wrap_text_cjk(lines: &[String], width: usize, cjk_wrap: CjkWrap) -> Vec<String> {
     let mut out = Vec::new();
     let mut state = ParagraphState::default();
-    let mut writer = ParagraphWriter::new(&mut out, width);
+    let mut writer = ParagraphWriter::new(&mut out, width).with_cjk_wrap(cjk_wrap);
     // Track fenced code blocks so wrapping honours shared fence semantics.
-    let mut fence_tracker = FenceTracker::default();
+    let mut fence_tracker = FenceState::default();
     let link_matcher = link_reference::LinkReferenceMatcher::production();
     let mut link_title_window = link_reference::LinkTitleWindow::default();
 
@@ -340,7 +366,7 @@ pub fn wrap_text(lines: &[String], width: usize) -> Vec<String> {
 
         state.note_indent(inner_content);
         let (text, hard_break) = line_break_parts(inner_content);
-        state.push(text, hard_break);
+        state.push(text, hard_break, line);
     }
 
     writer.flush_paragraph(&mut state);
@@ -59,6 +59,21 @@ pub(super) static MARKDOWNLINT_DIRECTIVE_RE: std::sync::LazyLock<Regex> = lazy_r
     "markdownlint directive regex should compile",
 );
 
+/// Matches single-line `mdtablefix` metadata comments and pragmas such as
+/// `<!-- mdtablefix-headers: | Description | -->` and
+/// `<!-- mdtablefix: hide-columns Notes -->`.
+pub(super) static MDTABLEFIX_DIRECTIVE_RE: std::sync::LazyLock<Regex> = lazy_regex!(
+    r"^\s*<!--\s*mdtablefix(?:-[a-z]+(?:-[a-z]+)*\b|:).*-->\s*$",
+    "mdtablefix directive regex should compile",
+);
+
+/// Matches a complete `<summary>…</summary>` element on one line, optionally
+/// preceded by the opening `<details>` tag.
+static SUMMARY_LINE_RE: std::sync::LazyLock<Regex> = lazy_regex!(
+    r"(?i)^\s*(?:<details(?:\s[^>]*)?>\s*)?<summary(?:\s[^>]*)?>.*</summary>\s*$",
+    "summary line regex should compile",
+);
+
 /// Describes the Markdown block prefix detected by [`classify_block`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum BlockKind {
@@ -74,6 +89,9 @@ pub(crate) enum BlockKind {
     LinkReferenceDefinition,
     /// HTML-style markdownlint directives recognised by [`is_markdownlint_directive`].
     MarkdownlintDirective,
+    /// HTML comments carrying `mdtablefix` metadata, recognised by
+    /// [`MDTABLEFIX_DIRECTIVE_RE`].
+    MdtablefixDirective,
     /// Lines whose first non-whitespace character is an ASCII digit.
     DigitPrefix,
 }
@@ -82,8 +100,8 @@ pub(crate) enum BlockKind {
 ///
 /// Detection order determines precedence when a line could match multiple prefixes.
 /// The current precedence is: heading, bullet, blockquote, footnote definition,
-/// link reference definition, markdownlint directive, digit prefix. Headings
-/// outrank bullets and blockquotes,
+/// link reference definition, markdownlint directive, mdtablefix directive,
+/// digit prefix. Headings outrank bullets and blockquotes,
 /// so inputs such as "# 1" remain headings rather than list items. Headings ignore
 /// indentation of four or more spaces so indented code remains untouched.
 /// For example, passing "> quote" returns `Some(BlockKind::Blockquote)` while
@@ -115,12 +133,22 @@ pub(crate) fn classify_block(
     if indent_width < 4 && is_markdownlint_directive(line) {
         return Some(BlockKind::MarkdownlintDirective);
     }
+    if indent_width < 4 && MDTABLEFIX_DIRECTIVE_RE.is_match(line) {
+        return Some(BlockKind::MdtablefixDirective);
+    }
     if indent_width < 4 && trimmed.chars().next().is_some_and(|c| c.is_ascii_digit()) {
         return Some(BlockKind::DigitPrefix);
     }
     None
 }
 
+/// Returns `true` when `line` holds a whole `<summary>` element.
+///
+/// Wrapping emits such lines verbatim: breaking them would split the element
+/// across lines, and joining them with neighbours would merge the summary into
+/// the surrounding prose. Inline transforms still rewrite the Markdown inside.
+pub(super) fn is_summary_line(line: &str) -> bool { SUMMARY_LINE_RE.is_match(line) }
+
 pub(super) fn is_markdownlint_directive(line: &str) -> bool {
     MARKDOWNLINT_DIRECTIVE_RE.is_match(line)
 }
@@ -165,6 +193,15 @@ mod tests {
             "<!-- markdownlint-disable -->",
             Some(BlockKind::MarkdownlintDirective)
         ),
+        case(
+            "<!-- mdtablefix-headers: | Description | -->",
+            Some(BlockKind::MdtablefixDirective)
+        ),
+        case(
+            "<!-- mdtablefix: hide-columns Notes -->",
+            Some(BlockKind::MdtablefixDirective)
+        ),
+        case("<!-- mdtablefix is great -->", None),
         case("2024 revenue", Some(BlockKind::DigitPrefix)),
         case("plain paragraph", None),
         case("| a | b |", None),
@@ -187,4 +224,14 @@ mod tests {
     fn detects_markdownlint_directives(#[case] line: &str, #[case] expected: bool) {
         assert_eq!(is_markdownlint_directive(line), expected);
     }
+
+    #[rstest]
+    #[case("<summary>Details</summary>", true)]
+    #[case("  <SUMMARY class=\"x\">**Bold** `code`</SUMMARY>  ", true)]
+    #[case("<details open><summary>Details</summary>", true)]
+    #[case("<summary>Details", false)]
+    #[case("Text <summary>Details</summary>", false)]
+    fn detects_summary_lines(#[case] line: &str, #[case] expected: bool) {
+        assert_eq!(is_summary_line(line), expected);
+    }
 }
@@ -0,0 +1,94 @@
+//! Script detection for paragraphs written mostly in Chinese, Japanese, or
+//! Korean.
+//!
+//! CJK prose has no spaces between words, so renderers turn every soft line
+//! break inside it into a visible space. [`CjkWrap`] lets callers keep such
+//! paragraphs as written, or measure them in characters rather than display
+//! cells, while other paragraphs wrap as usual.
+
+/// How paragraphs dominated by CJK script are wrapped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum CjkWrap {
+    /// Leave CJK paragraphs exactly as written.
+    Never,
+    /// Wrap CJK paragraphs by display width, where most CJK characters take
+    /// two cells.
+    #[default]
+    Cells,
+    /// Wrap CJK paragraphs by character count, so a line holds as many CJK
+    /// characters as the width allows Latin ones.
+    Chars,
+}
+
+use super::inline::{wrap_preserving_code, wrap_preserving_code_by_chars};
+
+/// Wraps inline `text` to `width` according to `policy`.
+///
+/// Returns `None` when `policy` is [`CjkWrap::Never`] and `text` is
+/// CJK-dominant, telling the caller to emit its source lines unchanged.
+pub(super) fn wrap_for_policy(text: &str, width: usize, policy: CjkWrap) -> Option<Vec<String>> {
+    match policy {
+        CjkWrap::Never if is_cjk_dominant(text) => None,
+        CjkWrap::Chars if is_cjk_dominant(text) => Some(wrap_preserving_code_by_chars(text, width)),
+        _ => Some(wrap_preserving_code(text, width)),
+    }
+}
+
+/// Returns `true` for Han, kana, Hangul, and CJK punctuation characters.
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{3000}'..='\u{30FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{31F0}'..='\u{31FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+            | '\u{20000}'..='\u{3FFFF}'
+    )
+}
+
+/// Returns `true` when more than half of the letters in `text` are CJK.
+///
+/// Whitespace, digits, and ASCII punctuation are ignored so Markdown markup
+/// and inline numbers do not tip the balance.
+pub(super) fn is_cjk_dominant(text: &str) -> bool {
+    let (cjk, other) = text
+        .chars()
+        .filter(|ch| is_cjk(*ch) || ch.is_alphabetic())
+        .fold((0_usize, 0_usize), |(cjk, other), ch| {
+            if is_cjk(ch) {
+                (cjk + 1, other)
+            } else {
+                (cjk, other + 1)
+            }
+        });
+    cjk > other
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for CJK script detection.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("これは日本語の段落です。", true)]
+    #[case("中文段落，包含 `code` 和一些说明。", true)]
+    #[case("한국어 문단입니다.", true)]
+    #[case("An English paragraph mentioning 東京.", false)]
+    #[case("1234 -- ...", false)]
+    fn detects_cjk_dominant_text(#[case] text: &str, #[case] expected: bool) {
+        assert_eq!(is_cjk_dominant(text), expected);
+    }
+}
@@ -36,10 +36,11 @@ pub(in crate::wrap::inline) use predicates::{
     ends_with_footnote_ref,
     ends_with_hyphen_prefix,
     fragment_is_link,
+    is_closing_cluster_token,
     is_inline_code_token,
+    is_opening_cluster_token,
     is_opening_punct,
     is_trailing_punct,
-    is_trailing_punctuation_token,
     is_whitespace_token,
     looks_like_footnote_ref,
 };
@@ -65,9 +66,8 @@ fn initial_token_span(tokens: &[String], start: usize) -> (usize, usize, SpanKin
     let mut width = UnicodeWidthStr::width(tokens[start].as_str());
     let mut kind = SpanKind::General;
 
-    // Forward-couple opening punctuation to the next atomic span so wrapping
-    // never leaves a lone `(` at the end of a line before inline code or a link.
-    if tokens[start].chars().all(is_opening_punct)
+    // Forward-couple openers such as `(` or `(*` to a following code span or link.
+    if is_opening_cluster_token(&tokens[start])
         && let Some(next) = tokens.get(start + 1)
     {
         if is_code_token(next) {
@@ -148,7 +148,7 @@ pub(super) fn determine_token_span(tokens: &[String], start: usize) -> (usize, u
             break;
         }
 
-        if is_trailing_punctuation_token(token) {
+        if is_closing_cluster_token(token) {
             if matches!(
                 kind,
                 SpanKind::Code | SpanKind::Link | SpanKind::FootnoteRef
@@ -292,8 +292,9 @@ fn should_flush_boundary_link(
 
 /// Renders one wrapped fragment line back into Markdown text.
 ///
-/// `line` supplies the fragments to render. `is_final_output_line` determines
-/// whether a single trailing space may be trimmed. When
+/// `line` supplies the fragments to render. Trailing spaces are trimmed from
+/// every line except the final one, so a wide gap such as two spaces between
+/// sentences never turns into a hard break at a wrap point. When
 /// `strip_leading_carry_whitespace` is set, carry whitespace from the wrap
 /// pipeline is removed from continuation lines only. The return value is the
 /// emitted text for that line, and this helper preserves the invariant that
@@ -308,8 +309,8 @@ fn render_line(
         .map(|fragment| fragment.text.as_str())
         .collect::<String>();
 
-    if !is_final_output_line && text.ends_with(' ') && !text.ends_with("  ") {
-        text.pop();
+    if !is_final_output_line {
+        text.truncate(text.trim_end_matches(' ').len());
     }
 
     if strip_leading_carry_whitespace {
@@ -328,13 +329,25 @@ fn render_line(
 /// Unicode display columns and must be at least one effective column after any
 /// caller prefix handling. This helper never panics for valid input.
 pub(super) fn wrap_preserving_code(text: &str, width: usize) -> Vec<String> {
+    wrap_measured(text, width, false)
+}
+
+/// Wraps like [`wrap_preserving_code`] but counts `width` in characters.
+pub(super) fn wrap_preserving_code_by_chars(text: &str, width: usize) -> Vec<String> {
+    wrap_measured(text, width, true)
+}
+
+fn wrap_measured(text: &str, width: usize, by_chars: bool) -> Vec<String> {
     let tokens = tokenize::segment_inline(text);
     if tokens.is_empty() {
         return Vec::new();
     }
 
     let tokens = normalize_footnote_ref_spacing(&tokens);
-    let fragments = build_fragments(&tokens);
+    let mut fragments = build_fragments(&tokens);
+    for fragment in fragments.iter_mut().filter(|_| by_chars) {
+        fragment.width = fragment.text.chars().count();
+    }
     let mut lines = Vec::new();
     let mut buffer: Vec<InlineFragment> = Vec::new();
 
@@ -380,7 +393,7 @@ pub(super) fn wrap_preserving_code(text: &str, width: usize) -> Vec<String> {
         lines.push(render_line(&buffer, true, !lines.is_empty()));
     }
 
-    lines
+    postprocess::keep_heading_markers_inline(lines)
 }
 
 #[cfg(test)]
@@ -225,6 +225,35 @@ pub(super) fn rebalance_atomic_tails(lines: &mut [Vec<InlineFragment>], width: u
     }
 }
 
+/// Moves heading markers that wrapping left at the start of a continuation
+/// line back onto the line before.
+///
+/// A wrapped line starting with `#` to `######` and a space would render as
+/// an ATX heading straight after paragraph text, and heading spacing would
+/// then split it from the paragraph. The marker overflows the previous line
+/// instead. This helper never panics.
+pub(super) fn keep_heading_markers_inline(mut lines: Vec<String>) -> Vec<String> {
+    let mut index = 1;
+    while index < lines.len() {
+        let line = &lines[index];
+        let (marker, rest) = line.split_once(' ').unwrap_or((line, ""));
+        if marker.is_empty() || marker.len() > 6 || marker.bytes().any(|byte| byte != b'#') {
+            index += 1;
+            continue;
+        }
+        trace!(index, marker, "keeping heading marker on the previous line");
+        let (marker, rest) = (marker.to_string(), rest.trim_start().to_string());
+        lines[index - 1].push(' ');
+        lines[index - 1].push_str(&marker);
+        if rest.is_empty() {
+            lines.remove(index);
+        } else {
+            lines[index] = rest;
+        }
+    }
+    lines
+}
+
 #[cfg(test)]
 #[path = "postprocess_tests.rs"]
 mod tests;
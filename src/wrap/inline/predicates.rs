@@ -31,6 +31,30 @@ pub(in crate::wrap::inline) fn is_trailing_punctuation_token(token: &str) -> boo
     !token.is_empty() && token.chars().all(is_trailing_punct)
 }
 
+fn is_emphasis_marker(c: char) -> bool { matches!(c, '*' | '_' | '~') }
+
+/// Returns whether `token` closes an atomic span: trailing punctuation,
+/// optionally mixed with the emphasis markers that close a span around a
+/// link or code, as in `*).` after `(*[link](url)`.
+///
+/// Such a cluster is glued to the preceding link, image, or code span so it
+/// never starts a wrapped line on its own.
+pub(in crate::wrap::inline) fn is_closing_cluster_token(token: &str) -> bool {
+    !token.is_empty()
+        && token
+            .chars()
+            .all(|c| is_trailing_punct(c) || is_emphasis_marker(c))
+}
+
+/// Returns whether `token` opens an atomic span: opening punctuation,
+/// optionally mixed with emphasis markers, as in `(*` before a link.
+pub(in crate::wrap::inline) fn is_opening_cluster_token(token: &str) -> bool {
+    !token.is_empty()
+        && token
+            .chars()
+            .all(|c| is_opening_punct(c) || is_emphasis_marker(c))
+}
+
 /// Returns whether `token` is a full or abbreviated English month name.
 ///
 /// The `#[tracing::instrument]` attribute records the return value while
@@ -116,8 +140,8 @@ fn is_day(day: u8) -> bool { (1..=31).contains(&day) }
 /// Returns whether `token` already looks like a complete Markdown link.
 pub(in crate::wrap::inline) fn looks_like_link(token: &str) -> bool {
     (token.starts_with('[') || token.starts_with("!["))
-        && token.contains("](")
-        && token.ends_with(')')
+        && ((token.contains("](") && token.ends_with(')'))
+            || (token.contains("][") && token.ends_with(']')))
 }
 
 /// Returns whether `token` looks like a complete GFM footnote reference.
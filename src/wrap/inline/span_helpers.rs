@@ -12,6 +12,7 @@ use tracing::debug;
 use unicode_width::UnicodeWidthStr;
 
 use super::predicates::{
+    is_closing_cluster_token,
     is_inline_code_token,
     is_month_name,
     is_numeric_day,
@@ -44,7 +45,7 @@ pub(in crate::wrap::inline) fn extend_punctuation(
     mut j: usize,
     width: &mut usize,
 ) -> usize {
-    while j < tokens.len() && is_trailing_punctuation_token(&tokens[j]) {
+    while j < tokens.len() && is_closing_cluster_token(&tokens[j]) {
         *width += UnicodeWidthStr::width(tokens[j].as_str());
         j += 1;
     }
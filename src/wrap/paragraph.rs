@@ -9,9 +9,16 @@ use code_span_trim::trim_code_span_edge_spaces;
 use tracing::trace;
 use unicode_width::UnicodeWidthStr;
 
-use super::{inline::wrap_preserving_code, tokenize::parse_open_code_span};
+use super::{
+    cjk::{CjkWrap, is_cjk_dominant, wrap_for_policy},
+    tokenize::parse_open_code_span,
+};
+use crate::hard_breaks::has_visible_hard_break;
 
 mod code_span_trim;
+mod state;
+
+pub(super) use state::ParagraphState;
 
 #[cfg(test)]
 #[path = "paragraph_tests.rs"]
@@ -66,83 +73,14 @@ pub(super) enum ContinuationMode {
     VerbatimFlush,
 }
 
-/// Tracks buffered paragraph content and its shared indentation.
-#[derive(Default)]
-pub(super) struct ParagraphState {
-    /// Stores buffered paragraph segments and whether each ends with a hard break.
-    buf: Vec<(String, bool)>,
-    /// Stores the leading indentation reused for wrapped continuation lines.
-    indent: String,
-    /// Stores the list continuation indent after a deferred prefix flush.
-    continuation_indent: Option<String>,
-    /// Stores a prefixed line waiting for a cross-line code span to close.
-    pub(super) pending_prefix: Option<PendingPrefix>,
-}
-
-impl ParagraphState {
-    /// Clears the buffered paragraph state.
-    ///
-    /// This resets both the accumulated segments and the remembered indent.
-    /// It returns no value and preserves the invariant that an empty state has
-    /// no buffered text. This method never panics.
-    pub(super) fn clear(&mut self) {
-        self.buf.clear();
-        self.indent.clear();
-        self.continuation_indent = None;
-        self.pending_prefix = None;
-    }
-
-    /// Records the paragraph indent from `line` when the buffer is still empty.
-    ///
-    /// The `line` parameter is the original input line whose leading
-    /// whitespace may become the continuation prefix. This method returns no
-    /// value, updates `indent` only for the first buffered segment, and never
-    /// panics.
-    pub(super) fn note_indent(&mut self, line: &str) {
-        if self.buf.is_empty() {
-            let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
-            if indent.is_empty() {
-                self.continuation_indent = None;
-                self.indent.clear();
-            } else {
-                self.indent = self.continuation_indent.take().unwrap_or(indent);
-            }
-        }
-    }
-
-    /// Records the continuation indent for the next indented paragraph.
-    pub(super) fn remember_continuation_indent(&mut self, indent: String) {
-        self.continuation_indent = Some(indent);
-    }
-
-    /// Appends one paragraph segment and its hard-break marker.
-    ///
-    /// `text` is stored verbatim and `hard_break` records whether the source
-    /// line ended with Markdown hard-break spacing. This method returns no
-    /// value and keeps buffered segments in input order without panicking.
-    pub(super) fn push(&mut self, text: String, hard_break: bool) {
-        self.buf.push((text, hard_break));
-    }
-
-    /// Takes the deferred prefixed segment and resets plain paragraph buffers.
-    ///
-    /// This keeps the pending-prefix state transition separate from output
-    /// emission. It returns the pending prefix when one exists, clears the
-    /// regular paragraph buffer and indent, and leaves `pending_prefix` empty.
-    pub(super) fn drain_pending_prefix(&mut self) -> Option<PendingPrefix> {
-        let pending = self.pending_prefix.take()?;
-        self.buf.clear();
-        self.indent.clear();
-        Some(pending)
-    }
-}
-
 /// Emits wrapped paragraph lines into the caller-provided output buffer.
 pub(super) struct ParagraphWriter<'a> {
     /// Borrows the caller-owned output buffer that receives emitted lines.
     out: &'a mut Vec<String>,
     /// Stores the target wrap width in Unicode display columns.
     width: usize,
+    /// Controls how CJK-dominant paragraphs are wrapped.
+    cjk_wrap: CjkWrap,
 }
 
 impl<'a> ParagraphWriter<'a> {
@@ -151,7 +89,25 @@ impl<'a> ParagraphWriter<'a> {
     /// The returned writer borrows `out` for subsequent emission. `width` is
     /// interpreted in Unicode display columns, and the constructor never
     /// panics.
-    pub(super) fn new(out: &'a mut Vec<String>, width: usize) -> Self { Self { out, width } }
+    pub(super) fn new(out: &'a mut Vec<String>, width: usize) -> Self {
+        Self {
+            out,
+            width,
+            cjk_wrap: CjkWrap::default(),
+        }
+    }
+
+    /// Sets the policy applied to CJK-dominant paragraphs.
+    pub(super) fn with_cjk_wrap(mut self, cjk_wrap: CjkWrap) -> Self {
+        self.cjk_wrap = cjk_wrap;
+        self
+    }
+
+    /// Wraps `text` to `width` under the writer's CJK policy, keeping it on
+    /// one line when the policy leaves it unwrapped.
+    fn wrap_inline(&self, text: &str, width: usize) -> Vec<String> {
+        wrap_for_policy(text, width, self.cjk_wrap).unwrap_or_else(|| vec![text.to_string()])
+    }
 
     /// Wraps `text` with `prefix` on the first line and `continuation_prefix`
     /// on later lines.
@@ -163,7 +119,7 @@ impl<'a> ParagraphWriter<'a> {
     fn wrap_with_prefix(&mut self, prefix: &str, continuation_prefix: &str, text: &str) {
         let prefix_width = UnicodeWidthStr::width(prefix);
         let available = self.width.saturating_sub(prefix_width).max(1);
-        let lines = wrap_preserving_code(text, available);
+        let lines = self.wrap_inline(text, available);
         if lines.is_empty() {
             self.out.push(prefix.to_string());
             return;
@@ -200,7 +156,7 @@ impl<'a> ParagraphWriter<'a> {
         let continuation_prefix =
             continuation_prefix_for(prefix, line.repeat_prefix, line.outer_prefix.as_deref());
 
-        let lines = wrap_preserving_code(line.rest, available);
+        let lines = self.wrap_inline(line.rest, available);
         if lines.is_empty() {
             self.out.push(prefix.to_string());
             return;
@@ -217,10 +173,8 @@ impl<'a> ParagraphWriter<'a> {
     }
 
     pub(super) fn ensure_trailing_hard_break_on_last_line(&mut self) {
-        if let Some(last) = self.out.last_mut()
-            && !last.ends_with("  ")
-        {
-            last.push_str("  ");
+        if let Some(last) = self.out.last_mut() {
+            push_hard_break_spaces(last);
         }
     }
 
@@ -239,14 +193,14 @@ impl<'a> ParagraphWriter<'a> {
     ) {
         let prefix = pending.prefix;
         let mut first_line = format!("{prefix}{rest}", rest = pending.rest);
-        if pending.hard_break && !first_line.ends_with("  ") {
-            first_line.push_str("  ");
+        if pending.hard_break {
+            push_hard_break_spaces(&mut first_line);
         }
         self.out.push(first_line);
 
         let mut continuation_line = continuation.to_string();
-        if hard_break && !continuation_line.ends_with("  ") {
-            continuation_line.push_str("  ");
+        if hard_break {
+            push_hard_break_spaces(&mut continuation_line);
         }
         self.out.push(continuation_line);
     }
@@ -298,6 +252,13 @@ impl<'a> ParagraphWriter<'a> {
             return;
         }
 
+        let text: String = state.buf.iter().map(|(text, _)| text.as_str()).collect();
+        if self.cjk_wrap == CjkWrap::Never && is_cjk_dominant(&text) {
+            self.out.append(&mut state.source);
+            state.clear();
+            return;
+        }
+
         let mut segment = String::new();
         for (text, hard_break) in &state.buf {
             if !segment.is_empty() {
@@ -306,6 +267,7 @@ impl<'a> ParagraphWriter<'a> {
             segment.push_str(text);
             if *hard_break {
                 self.push_wrapped_segment(&state.indent, &segment);
+                self.ensure_trailing_hard_break_on_last_line();
                 segment.clear();
             }
         }
@@ -421,3 +383,11 @@ fn continuation_prefix_for(
     let indent_width = UnicodeWidthStr::width(indent_str.as_str());
     format!("{}{}", indent_str, " ".repeat(prefix_width - indent_width))
 }
+
+/// Ends `line` with a trailing-space hard break unless it already ends with
+/// one, or with a backslash or `<br>` break carried over from the source.
+fn push_hard_break_spaces(line: &mut String) {
+    if !line.ends_with("  ") && !has_visible_hard_break(line) {
+        line.push_str("  ");
+    }
+}
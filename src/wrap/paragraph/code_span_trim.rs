@@ -8,6 +8,8 @@ use std::{borrow::Cow, collections::HashSet};
 
 use tracing::trace;
 
+use crate::textproc::is_escaped;
+
 pub(super) fn trim_code_span_edge_spaces<'a>(
     text: &'a str,
     synthetic_spaces: &[usize],
@@ -58,7 +60,7 @@ fn next_backtick_run(text: &str, start: usize) -> Option<(usize, usize)> {
     let mut index = start;
     while index < text.len() {
         let ch = text[index..].chars().next()?;
-        if ch == '`' && !has_odd_backslash_escape(text.as_bytes(), index) {
+        if ch == '`' && !is_escaped(text, index) {
             return Some((index, backtick_run_end(text, index)));
         }
         index += ch.len_utf8();
@@ -96,18 +98,6 @@ fn backtick_run_end(text: &str, start: usize) -> usize {
     end
 }
 
-fn has_odd_backslash_escape(bytes: &[u8], mut index: usize) -> bool {
-    let mut count = 0;
-    while index > 0 {
-        index -= 1;
-        if bytes[index] != b'\\' {
-            break;
-        }
-        count += 1;
-    }
-    count % 2 == 1
-}
-
 #[cfg(test)]
 mod tests {
     //! Unit tests for code-span edge-space trimming.
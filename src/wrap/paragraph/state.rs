@@ -0,0 +1,81 @@
+//! Buffered paragraph state consumed by `ParagraphWriter`.
+
+use super::PendingPrefix;
+
+/// Tracks buffered paragraph content and its shared indentation.
+#[derive(Default)]
+pub(in crate::wrap) struct ParagraphState {
+    /// Stores buffered paragraph segments and whether each ends with a hard break.
+    pub(super) buf: Vec<(String, bool)>,
+    /// Stores the source lines behind `buf` for paragraphs left unwrapped.
+    pub(super) source: Vec<String>,
+    /// Stores the leading indentation reused for wrapped continuation lines.
+    pub(super) indent: String,
+    /// Stores the list continuation indent after a deferred prefix flush.
+    pub(super) continuation_indent: Option<String>,
+    /// Stores a prefixed line waiting for a cross-line code span to close.
+    pub(in crate::wrap) pending_prefix: Option<PendingPrefix>,
+}
+
+impl ParagraphState {
+    /// Clears the buffered paragraph state.
+    ///
+    /// This resets both the accumulated segments and the remembered indent.
+    /// It returns no value and preserves the invariant that an empty state has
+    /// no buffered text. This method never panics.
+    pub(in crate::wrap) fn clear(&mut self) {
+        self.buf.clear();
+        self.source.clear();
+        self.indent.clear();
+        self.continuation_indent = None;
+        self.pending_prefix = None;
+    }
+
+    /// Records the paragraph indent from `line` when the buffer is still empty.
+    ///
+    /// The `line` parameter is the original input line whose leading
+    /// whitespace may become the continuation prefix. This method returns no
+    /// value, updates `indent` only for the first buffered segment, and never
+    /// panics.
+    pub(in crate::wrap) fn note_indent(&mut self, line: &str) {
+        if self.buf.is_empty() {
+            let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+            if indent.is_empty() {
+                self.continuation_indent = None;
+                self.indent.clear();
+            } else {
+                self.indent = self.continuation_indent.take().unwrap_or(indent);
+            }
+        }
+    }
+
+    /// Records the continuation indent for the next indented paragraph.
+    pub(in crate::wrap) fn remember_continuation_indent(&mut self, indent: String) {
+        self.continuation_indent = Some(indent);
+    }
+
+    /// Appends one paragraph segment and its hard-break marker.
+    ///
+    /// `text` is stored verbatim and `hard_break` records whether the source
+    /// line ended with Markdown hard-break spacing. `source` is the full input
+    /// line, kept in case the paragraph is emitted unwrapped. This method
+    /// returns no value and keeps buffered segments in input order without
+    /// panicking.
+    pub(in crate::wrap) fn push(&mut self, text: String, hard_break: bool, source: &str) {
+        self.buf.push((text, hard_break));
+        self.source.push(source.to_string());
+    }
+
+    /// Takes the deferred prefixed segment and resets plain paragraph buffers.
+    ///
+    /// This keeps the pending-prefix state transition separate from output
+    /// emission. It returns the pending prefix when one exists, clears the
+    /// regular paragraph buffer and indent, and leaves `pending_prefix` empty.
+    pub(in crate::wrap) fn drain_pending_prefix(&mut self) -> Option<PendingPrefix> {
+        let pending = self.pending_prefix.take()?;
+        self.buf.clear();
+        self.source.clear();
+        self.indent.clear();
+        Some(pending)
+    }
+}
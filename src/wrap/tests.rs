@@ -5,7 +5,6 @@
 
 mod blockquote;
 mod classify_block;
-mod fence_tracker;
 mod inline_wrapping;
 mod link_ref_regex;
 mod link_reference_definitions;
@@ -122,11 +122,7 @@ fn wrap_preserving_code_splits_after_consecutive_whitespace() {
     let lines = wrap_preserving_code("alpha  beta   gamma", 8);
     assert_eq!(
         lines,
-        vec![
-            "alpha  ".to_string(),
-            "beta   ".to_string(),
-            "gamma".to_string()
-        ]
+        vec!["alpha".to_string(), "beta".to_string(), "gamma".to_string()]
     );
 }
 
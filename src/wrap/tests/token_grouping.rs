@@ -40,6 +40,11 @@ use super::super::{inline::determine_token_span, tokenize::segment_inline};
 #[case("word([link](url))", "word([link](url))")]
 #[case("word([1](url))([2](url2))", "word([1](url))([2](url2))")]
 #[case("[[link](url)]", "[[link](url)]")]
+#[case("[docs][ref]).", "[docs][ref]).")]
+#[case("![logo](img.png)),", "![logo](img.png)),")]
+#[case("[link](url)*).", "[link](url)*).")]
+#[case("(*[link](url)*).", "(*[link](url)*).")]
+#[case("**[link](url)**,", "**[link](url)**,")]
 fn determine_token_span_groups_related_tokens(#[case] input: &str, #[case] expected_group: &str) {
     let tokens = segment_inline(input);
     let (end, width) = determine_token_span(&tokens, 0);
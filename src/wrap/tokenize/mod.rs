@@ -3,10 +3,9 @@
 //! This module contains utilities for breaking lines into tokens so that
 //! inline code spans and Markdown links are preserved during wrapping.
 //! Full-line fenced code blocks are tracked by [`tokenize_markdown`] with the
-//! shared [`FenceTracker`] from `super::fence`; `fence.rs` owns that tracker
-//! and its opening, closing, marker-length, and nested-literal semantics. When
-//! [`FenceTracker::observe`] reports a fence boundary, that line is emitted as
-//! [`Token::Fence`]. Subsequent lines inside the open fence are also emitted as
+//! shared [`FenceState`] from [`crate::fences`], which owns the opening, closing, marker-length,
+//! and nested-literal semantics. When [`FenceState::observe`] reports a fence boundary, that line
+//! is emitted as [`Token::Fence`]. Subsequent lines inside the open fence are also emitted as
 //! [`Token::Fence`], preserving their byte content verbatim until the matching
 //! closing fence marker is seen. This prevents `--ellipsis`, `--wrap`, and
 //! similar post-processors from mutating fenced code block contents, fixing
@@ -25,17 +24,18 @@ use parsing::{
 };
 #[cfg(test)]
 pub(crate) use scanning::continuation_begins_with_closing_fence;
-#[cfg(test)]
-pub(crate) use scanning::has_unclosed_code_span;
 use scanning::{bracket_follows_escaped_bang, collect_range, scan_code_suffix_end, scan_while};
 pub(crate) use scanning::{
     has_odd_backslash_escape_bytes,
+    has_unclosed_code_span,
     opening_fence_run_len,
     parse_open_code_span,
     position_after_close,
     scan_continuation_span_state,
 };
 
+use crate::fences::FenceState;
+
 /// Markdown token emitted by the `segment_inline` tokenizer.
 #[derive(Debug, PartialEq)]
 pub enum Token<'a> {
@@ -273,54 +273,83 @@ fn next_token(line: &str, offset: usize) -> Option<(Token<'_>, usize)> {
     Some((Token::Text(fence), delim_len))
 }
 
-/// Emit [`Token`]s for inline segments within a single line.
-///
-/// The function scans for backtick sequences and yields `Token::Code` for
-/// matched spans. Text outside code spans is emitted as `Token::Text` via the
-/// provided callback.
-///
-/// # Examples
-///
-/// ```rust,ignore
-/// // Prints:
-/// // Token::Text("run ")
-/// // Token::Code { raw: "`cmd`", fence: "`", code: "cmd" }
-/// tokenize_inline("run `cmd`", &mut |t| println!("{:?}", t));
-/// ```
+/// A lazy iterator over the [`Token`]s of a Markdown document.
 ///
-/// The callback receives each token as a [`Token<'a>`], such as
-/// `Token::Text(&str)` or `Token::Code { raw: &str, fence: &str, code: &str }`.
-fn tokenize_inline<'a, F>(line: &'a str, mut emit: F)
-where
-    F: FnMut(Token<'a>),
-{
-    let mut offset = 0;
-    while offset < line.len() {
-        if let Some((tok, used)) = next_token(line, offset) {
-            emit(tok);
-            if used == 0 {
-                break;
+/// Created by [`markdown_tokens`]. Each line is tokenized only when the
+/// tokens before it have been consumed, so a transform that streams tokens
+/// into its output never holds the whole token list.
+#[derive(Debug)]
+pub struct MarkdownTokens<'a> {
+    lines: std::iter::Peekable<std::str::Lines<'a>>,
+    trailing_newline: bool,
+    fences: FenceState,
+    /// The rest of the line being tokenized, if any.
+    line: Option<&'a str>,
+    offset: usize,
+}
+
+impl<'a> Iterator for MarkdownTokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        loop {
+            if let Some(line) = self.line {
+                if self.offset < line.len()
+                    && let Some((token, used)) = next_token(line, self.offset)
+                {
+                    self.offset = if used == 0 {
+                        line.len()
+                    } else {
+                        self.offset + used
+                    };
+                    return Some(token);
+                }
+                self.line = None;
+                // A newline separates lines, and follows the last one only
+                // when the source ended with it.
+                if self.lines.peek().is_some() || self.trailing_newline {
+                    return Some(Token::Newline);
+                }
+                continue;
             }
-            offset += used;
-        } else {
-            break;
+            let line = self.lines.next()?;
+            self.offset = 0;
+            if self.fences.observe_source_line(line).is_code() {
+                // The empty remainder makes the next call emit the newline.
+                self.line = Some(&line[line.len()..]);
+                return Some(Token::Fence(line));
+            }
+            self.line = Some(line);
         }
     }
 }
 
-fn push_newline_if_needed<I>(
-    tokens: &mut Vec<Token<'_>>,
-    lines: &mut std::iter::Peekable<I>,
-    had_trailing_newline: bool,
-) where
-    I: Iterator,
-{
-    // Emit a newline token if another line follows or when the
-    // original input ended with a trailing newline. The peek avoids
-    // prematurely allocating for the final newline when it isn't
-    // necessary.
-    if lines.peek().is_some() || (had_trailing_newline && lines.peek().is_none()) {
-        tokens.push(Token::Newline);
+impl std::iter::FusedIterator for MarkdownTokens<'_> {}
+
+/// Returns an iterator over the [`Token`]s of Markdown `source`, yielding
+/// them lazily in the order [`tokenize_markdown`] would return them.
+///
+/// ```rust
+/// use mdtablefix::wrap::{Token, markdown_tokens};
+///
+/// let mut tokens = markdown_tokens("one `two`\nthree");
+/// assert_eq!(tokens.next(), Some(Token::Text("one ")));
+/// assert!(matches!(
+///     tokens.next(),
+///     Some(Token::Code { code: "two", .. })
+/// ));
+/// assert_eq!(tokens.next(), Some(Token::Newline));
+/// assert_eq!(tokens.next(), Some(Token::Text("three")));
+/// assert_eq!(tokens.next(), None);
+/// ```
+#[must_use]
+pub fn markdown_tokens(source: &str) -> MarkdownTokens<'_> {
+    MarkdownTokens {
+        lines: source.lines().peekable(),
+        trailing_newline: source.ends_with('\n'),
+        fences: FenceState::default(),
+        line: None,
+        offset: 0,
     }
 }
 
@@ -331,7 +360,8 @@ fn push_newline_if_needed<I>(
 /// value is `Vec<Token<'_>>`, preserving the token order found in `source`.
 /// Inline code spans, links, and whitespace runs are emitted as distinct token
 /// variants or text slices so callers can perform width-aware or
-/// format-aware processing.
+/// format-aware processing. [`markdown_tokens`] yields the same tokens
+/// without collecting them.
 ///
 /// ```rust
 /// use mdtablefix::wrap::{Token, tokenize_markdown};
@@ -349,33 +379,7 @@ fn push_newline_if_needed<I>(
 ///     ]
 /// );
 /// ```
-pub fn tokenize_markdown(source: &str) -> Vec<Token<'_>> {
-    if source.is_empty() {
-        return Vec::new();
-    }
-
-    let mut tokens = Vec::new();
-    let had_trailing_newline = source.ends_with('\n');
-    let mut lines = source.lines().peekable();
-    let mut fence_tracker = super::FenceTracker::default();
-
-    // Iterate lazily so we can safely use `peek()` to decide on trailing
-    // newline emission without borrowing issues from a `for` loop over
-    // `&str` references.
-    while let Some(line) = lines.next() {
-        let fence = fence_tracker.observe_source_line(line);
-        if fence.is_fence_marker || fence.is_in_fence {
-            tokens.push(Token::Fence(line));
-            push_newline_if_needed(&mut tokens, &mut lines, had_trailing_newline);
-            continue;
-        }
-
-        tokenize_inline(line, &mut |tok| tokens.push(tok));
-        push_newline_if_needed(&mut tokens, &mut lines, had_trailing_newline);
-    }
-
-    tokens
-}
+pub fn tokenize_markdown(source: &str) -> Vec<Token<'_>> { markdown_tokens(source).collect() }
 
 #[cfg(test)]
 #[path = "mod_tests.rs"]
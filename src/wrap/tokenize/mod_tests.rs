@@ -135,3 +135,32 @@ fn tokenize_markdown_treats_escaped_backtick_adjacent_to_multibyte_as_text(#[cas
     let tokens = tokenize_markdown(input);
     assert_eq!(tokens, vec![Token::Text(input)]);
 }
+
+#[rstest]
+#[case::empty("", vec![])]
+#[case::blank_lines("a\n\nb\n", vec![
+    Token::Text("a"),
+    Token::Newline,
+    Token::Newline,
+    Token::Text("b"),
+    Token::Newline,
+])]
+#[case::fence("```\n`x`\n```", vec![
+    Token::Fence("```"),
+    Token::Newline,
+    Token::Fence("`x`"),
+    Token::Newline,
+    Token::Fence("```"),
+])]
+fn markdown_tokens_yields_lines_in_order(#[case] input: &str, #[case] expected: Vec<Token<'_>>) {
+    assert_eq!(markdown_tokens(input).collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn markdown_tokens_stops_when_the_caller_does() {
+    let source = "first line\n".repeat(10_000);
+    let mut tokens = markdown_tokens(&source);
+    assert_eq!(tokens.next(), Some(Token::Text("first line")));
+    assert_eq!(tokens.next(), Some(Token::Newline));
+    assert_eq!(tokens.lines.count(), 9_999);
+}
@@ -0,0 +1,94 @@
+//! Integration tests for the `--abbreviate-header` option.
+//!
+//! Verifies that header labels are only shortened for tables wider than the
+//! width budget, that the original header row is recorded in a comment which
+//! survives wrapping, and that repeated runs leave the result unchanged.
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+use rstest::rstest;
+
+const WIDE: &str = concat!(
+    "| Identifier | Description | Notes |\n",
+    "| --- | --- | --- |\n",
+    "| 1 | Explains the first entry in the table at considerable length | none |\n",
+);
+
+const ABBREVIATED: &str = concat!(
+    "<!-- mdtablefix-headers: | Identifier | Description | Notes | -->\n",
+    "| ID  | Desc.                                                        | Notes |\n",
+    "| --- | ------------------------------------------------------------ | ----- |\n",
+    "| 1   | Explains the first entry in the table at considerable length | none  |\n",
+);
+
+fn abbreviation_args(extra: &[&'static str]) -> Vec<&'static str> {
+    let mut args = vec![
+        "--abbreviate-header",
+        "Identifier=ID",
+        "--abbreviate-header",
+        "Description=Desc.",
+    ];
+    args.extend_from_slice(extra);
+    args
+}
+
+#[test]
+fn cli_abbreviates_wide_tables() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&abbreviation_args(&[]), WIDE)?
+        .success()
+        .stdout(ABBREVIATED);
+    Ok(())
+}
+
+#[test]
+fn cli_abbreviation_is_stable_under_wrap() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&abbreviation_args(&["--wrap"]), ABBREVIATED)?
+        .success()
+        .stdout(ABBREVIATED);
+    Ok(())
+}
+
+#[test]
+fn cli_leaves_narrow_tables_alone() -> Result<(), Box<dyn std::error::Error>> {
+    let input =
+        "| Identifier | Description |\n| ---------- | ----------- |\n| 1          | a           \
+         |\n";
+    run_cli_with_stdin(&abbreviation_args(&[]), input)?
+        .success()
+        .stdout(input);
+    Ok(())
+}
+
+#[rstest]
+#[case::max_table_width(&["--max-table-width", "120"])]
+#[case::wrap_width(&["--wrap-width", "120"])]
+#[case::max_table_width_over_wrap_width(&["--max-table-width", "120", "--wrap-width", "40"])]
+fn cli_measures_against_the_configured_width(
+    #[case] extra: &[&'static str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let expected = run_cli_with_stdin(&[], WIDE)?.get_output().stdout.clone();
+    run_cli_with_stdin(&abbreviation_args(extra), WIDE)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn cli_abbreviates_tables_wider_than_wrap_width() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "| Identifier | Description |\n| --- | --- |\n| 1 | a |\n";
+    run_cli_with_stdin(&abbreviation_args(&["--wrap-width", "20"]), input)?
+        .success()
+        .stdout(predicates::str::starts_with(
+            "<!-- mdtablefix-headers: | Identifier | Description | -->\n| ID  | Desc. |\n",
+        ));
+    Ok(())
+}
+
+#[test]
+fn cli_rejects_malformed_abbreviation() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--abbreviate-header", "Description"], "")?
+        .failure()
+        .stderr(predicates::str::contains("FULL=SHORT"));
+    Ok(())
+}
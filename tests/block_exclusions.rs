@@ -0,0 +1,70 @@
+//! CLI tests for per-transform block exclusions set in the config file.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+use tempfile::tempdir;
+
+const INPUT: &str = "Wait...\n\n> Quoted...\n\n[^1]: Smith, *Title...*\n  p. 4...\n";
+
+#[test]
+fn excluded_blocks_keep_their_text() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join(".mdtablefix.toml"),
+        "[ellipsis]\nexclude = [\"footnote-definition\"]\n",
+    )?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .arg("--ellipsis")
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout("Wait…\n\n> Quoted…\n\n[^1]: Smith, *Title...*\n  p. 4...\n");
+    Ok(())
+}
+
+#[test]
+fn explicit_config_applies_without_a_preset() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let config = dir.path().join("style.toml");
+    fs::write(
+        &config,
+        "[ellipsis]\nexclude = [\"blockquote\", \"footnote-definition\"]\n",
+    )?;
+    Command::cargo_bin("mdtablefix")?
+        .args(["--ellipsis", "--config"])
+        .arg(&config)
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout("Wait…\n\n> Quoted...\n\n[^1]: Smith, *Title...*\n  p. 4...\n");
+    Ok(())
+}
+
+#[rstest]
+#[case::unknown_transform(
+    "[elipsis]\nexclude = [\"heading\"]\n",
+    "unknown config table `[elipsis]`"
+)]
+#[case::unknown_block("[ellipsis]\nexclude = [\"table\"]\n", "unknown block type `table`")]
+#[case::unsupported_transform(
+    "[wrap]\nexclude = [\"heading\"]\n",
+    "`[wrap]` cannot exclude blocks"
+)]
+fn invalid_exclusions_are_rejected(
+    #[case] config: &str,
+    #[case] message: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::write(dir.path().join(".mdtablefix.toml"), config)?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .write_stdin("x\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(message));
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Tests that the borrowing pipeline matches the owning one.
+//!
+//! Every `*_input.txt` fixture in `tests/data` is formatted by
+//! `process_stream_opts_cow` under several option sets and compared with
+//! `process_stream_opts`, so a stage that would rewrite a block its trigger
+//! test skipped shows up here. Formatting output the pipeline leaves alone
+//! must borrow every line.
+
+use std::{borrow::Cow, fs, path::Path};
+
+use mdtablefix::{Options, process_stream_opts, process_stream_opts_cow};
+use rstest::rstest;
+
+#[rstest]
+#[case::defaults(Options::default())]
+#[case::wrapped(Options { wrap: true, ..Options::default() })]
+#[case::narrow(Options { wrap: true, wrap_width: 30, ..Options::default() })]
+#[case::everything(Options {
+    wrap: true,
+    ellipsis: true,
+    fences: true,
+    code_spans: true,
+    code_emphasis: true,
+    headings: true,
+    wiki_tables: true,
+    ..Options::default()
+})]
+#[case::footnotes(Options { wrap: true, footnotes: true, ..Options::default() })]
+fn matches_the_owning_pipeline(#[case] opts: Options) -> Result<(), Box<dyn std::error::Error>> {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let mut cases = 0;
+    for entry in fs::read_dir(&corpus)? {
+        let input = entry?.path();
+        if !input.to_string_lossy().ends_with("_input.txt") {
+            continue;
+        }
+        let source: Vec<String> = fs::read_to_string(&input)?
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let borrowed = process_stream_opts_cow(&source, opts.clone());
+        assert_eq!(
+            borrowed,
+            process_stream_opts(&source, opts.clone()),
+            "{}",
+            input.display()
+        );
+        let formatted = process_stream_opts(&source, opts.clone());
+        let stable = process_stream_opts(&formatted, opts.clone()) == formatted;
+        assert!(
+            !stable
+                || process_stream_opts_cow(&formatted, opts.clone())
+                    .iter()
+                    .all(|line| matches!(line, Cow::Borrowed(_))),
+            "{} is not borrowed once formatted",
+            input.display()
+        );
+        cases += 1;
+    }
+    assert!(cases > 0, "no fixtures found in {}", corpus.display());
+    Ok(())
+}
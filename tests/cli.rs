@@ -261,7 +261,7 @@ fn run_in_place(flags: &[&str], input: &str, expected: &str) {
         .args(flags)
         .arg(&file_path)
         .assert()
-        .success()
+        .code(1)
         .stdout("")
         .stderr("");
 
@@ -299,6 +299,7 @@ fn run_in_place(flags: &[&str], input: &str, expected: &str) {
 #[case(&["--wrap", "--footnotes"], include_str!("data/footnotes_input.txt"), include_str!("data/footnotes_wrap_expected.txt"))]
 #[case(&["--wrap", "--ellipsis"], include_str!("data/ellipsis_wrap_input.txt"), include_str!("data/ellipsis_wrap_expected.txt"))]
 #[case(&["--headings"], "Title\n=====\n", "# Title\n")]
+#[case(&["--footnotes-per-section"], "## A\n\nOne.[^1]\n\n## B\n\nTwo.[^2]\n\n[^1]: First\n[^2]: Second\n", "## A\n\nOne.[^1]\n\n[^1]: First\n\n## B\n\nTwo.[^1]\n\n[^1]: Second\n")]
 fn test_cli_in_place_variants(#[case] flags: &[&str], #[case] input: &str, #[case] expected: &str) {
     run_in_place(flags, input, expected);
 }
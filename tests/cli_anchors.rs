@@ -0,0 +1,73 @@
+//! CLI tests for the `--emit-anchors` JSON anchor map.
+
+use std::fs;
+
+use assert_cmd::Command;
+use serde_json::{Value, json};
+use tempfile::tempdir;
+
+fn read_map(path: &std::path::Path) -> Result<Value, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[test]
+fn test_cli_emit_anchors_lists_headings_and_html_ids() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    let missing = dir.path().join("missing.md");
+    let anchors = dir.path().join("anchors.json");
+    fs::write(
+        &doc,
+        "Setup Guide\n===========\n\n<a id=\"top\"></a>\n\n## Install {#install}\n\n## \
+         Usage\n\n## Usage\n\n```\n# not a heading\n```\n",
+    )?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--headings", "--emit-anchors"])
+        .arg(&anchors)
+        .arg(&doc)
+        .arg(&missing)
+        .assert()
+        .failure();
+
+    let map = read_map(&anchors)?;
+    assert_eq!(map["version"], 1);
+    assert_eq!(
+        map["files"][0]["anchors"],
+        json!([
+            { "id": "setup-guide", "kind": "heading", "line": 1 },
+            { "id": "top", "kind": "html", "line": 3 },
+            { "id": "install", "kind": "heading", "line": 5 },
+            { "id": "usage", "kind": "heading", "line": 7 },
+            { "id": "usage-1", "kind": "heading", "line": 9 },
+        ])
+    );
+    assert!(
+        map["files"][1]["error"]
+            .as_str()
+            .is_some_and(|e| e.contains("missing.md"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cli_emit_anchors_from_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let anchors = dir.path().join("anchors.json");
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--emit-anchors")
+        .arg(&anchors)
+        .write_stdin("# Title\n\n|a|b|\n|1|2|\n")
+        .assert()
+        .success()
+        .stdout("# Title\n\n| a | b |\n| 1 | 2 |\n");
+
+    let map = read_map(&anchors)?;
+    assert_eq!(map["files"][0]["path"], "-");
+    assert_eq!(
+        map["files"][0]["anchors"],
+        json!([{ "id": "title", "kind": "heading", "line": 1 }])
+    );
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! CLI tests for `--backup`, which keeps a copy of each file rewritten by
+//! `--in-place`.
+
+use std::fs;
+
+use assert_cmd::Command;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[rstest]
+#[case::default_suffix("--backup", "doc.md.bak")]
+#[case::custom_suffix("--backup=.orig", "doc.md.orig")]
+fn copies_the_original_before_rewriting(
+    #[case] flag: &str,
+    #[case] backup: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "|a|b|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", flag])
+        .arg(&doc)
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::read_to_string(&doc)?, "| a | b |\n");
+    assert_eq!(fs::read_to_string(dir.path().join(backup))?, "|a|b|\n");
+    Ok(())
+}
+
+#[test]
+fn skips_files_that_do_not_change() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "| a | b |\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--backup"])
+        .arg(&doc)
+        .assert()
+        .success();
+
+    assert!(!dir.path().join("doc.md.bak").exists());
+    Ok(())
+}
+
+#[rstest]
+#[case::without_in_place(&["--backup"])]
+#[case::empty_suffix(&["--in-place", "--backup="])]
+fn rejects_invalid_usage(#[case] args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "|a|b|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .arg(&doc)
+        .assert()
+        .code(2);
+    assert_eq!(fs::read_to_string(&doc)?, "|a|b|\n");
+    Ok(())
+}
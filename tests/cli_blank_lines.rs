@@ -0,0 +1,56 @@
+//! CLI tests for limiting blank lines with `--max-consecutive-blanks`.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+
+#[rstest]
+#[case::collapses_and_trims(
+    &["--max-consecutive-blanks", "1"],
+    "\n\n# Title\n\n\n\nText.\n\n\n",
+    "# Title\n\nText.\n"
+)]
+#[case::keeps_fenced_blanks(
+    &["--max-consecutive-blanks", "1"],
+    "```\na\n\n\nb\n```\n",
+    "```\na\n\n\nb\n```\n"
+)]
+#[case::keeps_one_blank_after_frontmatter(
+    &["--max-consecutive-blanks", "1"],
+    "---\ntitle: x\n---\n\n\n# Title\n",
+    "---\ntitle: x\n---\n\n# Title\n"
+)]
+#[case::leaves_section_ends(
+    &["--max-consecutive-blanks", "1", "--only-section", "## B"],
+    "## A\n\n\n\na\n\n## B\n\n\n\nb\n\n",
+    "## A\n\n\n\na\n\n## B\n\nb\n\n"
+)]
+#[case::disabled_by_name(
+    &["--max-consecutive-blanks", "1", "--disable", "blank-lines"],
+    "a\n\n\n\nb\n",
+    "a\n\n\n\nb\n"
+)]
+fn limits_blank_lines(
+    #[case] args: &[&str],
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn enabling_by_name_needs_the_flag() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--enable", "blank-lines"])
+        .write_stdin("a\n")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot enable `blank-lines`"));
+    Ok(())
+}
@@ -0,0 +1,92 @@
+//! CLI tests for `--bulk`, which skips files a pre-scan shows are already
+//! formatted.
+
+use std::{
+    fs::{self, File},
+    time::{Duration, SystemTime},
+};
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[test]
+fn clean_files_are_not_rewritten() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let clean = dir.path().join("clean.md");
+    let table = dir.path().join("table.md");
+    fs::write(&clean, "# Notes\n\nNothing to fix here.\n")?;
+    fs::write(&table, "|a|b|\n")?;
+    let earlier = SystemTime::now() - Duration::from_secs(3600);
+    File::options()
+        .write(true)
+        .open(&clean)?
+        .set_modified(earlier)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--bulk"])
+        .args([&clean, &table])
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::metadata(&clean)?.modified()?, earlier);
+    assert_eq!(fs::read_to_string(&table)?, "| a | b |\n");
+    Ok(())
+}
+
+#[test]
+fn output_matches_the_normal_path() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let files: Vec<_> = [
+        ("a.md", "Plain text.\r\n"),
+        ("b.md", "|a|b|\n"),
+        ("c.md", "Wait...\n"),
+        ("d.md", "no newline"),
+    ]
+    .into_iter()
+    .map(|(name, text)| {
+        let path = dir.path().join(name);
+        fs::write(&path, text).map(|()| path)
+    })
+    .collect::<Result<_, _>>()?;
+
+    let run = |bulk: bool| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("mdtablefix")?;
+        cmd.args(["--ellipsis", "--jobs", "1"]).args(&files);
+        if bulk {
+            cmd.arg("--bulk");
+        }
+        Ok(cmd.assert().success().get_output().stdout.clone())
+    };
+
+    assert_eq!(run(true)?, run(false)?);
+    Ok(())
+}
+
+#[test]
+fn check_still_reports_files_that_need_work() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let clean = dir.path().join("clean.md");
+    let messy = dir.path().join("messy.md");
+    fs::write(&clean, "Done.\n")?;
+    fs::write(&messy, "Title\n=====\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--list-changed", "--headings", "--bulk"])
+        .args([&clean, &messy])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", messy.display()));
+    Ok(())
+}
+
+#[test]
+fn requires_input_files() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .arg("--bulk")
+        .write_stdin("text\n")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--bulk"));
+    Ok(())
+}
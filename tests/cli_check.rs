@@ -0,0 +1,100 @@
+//! CLI tests for `--check`, which reports unformatted files without
+//! rewriting them.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[test]
+fn check_reports_files_that_would_change() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let messy = dir.path().join("messy.md");
+    let clean = dir.path().join("clean.md");
+    fs::write(&messy, "|a|b|\n|1|2|\n")?;
+    fs::write(&clean, "| a | b |\n| 1 | 2 |\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check")
+        .arg(&messy)
+        .arg(&clean)
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(format!("{}: would be reformatted\n", messy.display()));
+
+    assert_eq!(fs::read_to_string(&messy)?, "|a|b|\n|1|2|\n");
+    Ok(())
+}
+
+#[test]
+fn check_passes_when_in_place_would_be_a_no_op() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "|a|b|\n|1|2|")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--check", "--wrap"])
+        .arg(&doc)
+        .assert()
+        .code(1);
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--wrap"])
+        .arg(&doc)
+        .assert()
+        .code(1);
+    Command::cargo_bin("mdtablefix")?
+        .args(["--check", "--wrap"])
+        .arg(&doc)
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+    Ok(())
+}
+
+#[test]
+fn check_reads_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check")
+        .write_stdin("|a|b|\n")
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("<stdin>: would be reformatted\n");
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check")
+        .write_stdin("| a | b |\n")
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+    Ok(())
+}
+
+#[test]
+fn check_on_stdin_prints_the_diff_when_asked() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--check", "--diff", "--stdin-filepath", "doc.md"])
+        .write_stdin("|a|b|\n")
+        .assert()
+        .code(1)
+        .stdout("--- doc.md\n+++ doc.md\n@@ -1 +1 @@\n-|a|b|\n+| a | b |\n")
+        .stderr("doc.md: would be reformatted\n");
+    Ok(())
+}
+
+#[test]
+fn check_conflicts_with_in_place() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "text\n")?;
+    Command::cargo_bin("mdtablefix")?
+        .args(["--check", "--in-place"])
+        .arg(&doc)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
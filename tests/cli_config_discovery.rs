@@ -0,0 +1,95 @@
+//! CLI tests for resolving the nearest `.mdtablefix.toml` to each input.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+const INPUT: &str = "Wait...\n\n> Quoted...\n";
+const KEEP_QUOTES: &str = "[ellipsis]\nexclude = [\"blockquote\"]\n";
+
+#[test]
+fn each_file_uses_its_nearest_config() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::create_dir_all(dir.path().join("api/guide"))?;
+    fs::create_dir_all(dir.path().join("blog"))?;
+    fs::write(dir.path().join(".mdtablefix.toml"), KEEP_QUOTES)?;
+    fs::write(dir.path().join("blog/.mdtablefix.toml"), "")?;
+    for file in ["api/guide/intro.md", "blog/post.md"] {
+        fs::write(dir.path().join(file), INPUT)?;
+    }
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--ellipsis", "--in-place", "api", "blog"])
+        .assert()
+        .code(1);
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("api/guide/intro.md"))?,
+        "Wait…\n\n> Quoted...\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("blog/post.md"))?,
+        "Wait…\n\n> Quoted…\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn explicit_config_applies_to_every_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::create_dir_all(dir.path().join("blog"))?;
+    fs::write(dir.path().join("blog/.mdtablefix.toml"), "")?;
+    fs::write(dir.path().join("style.toml"), KEEP_QUOTES)?;
+    fs::write(dir.path().join("blog/post.md"), INPUT)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--ellipsis", "--config", "style.toml", "blog/post.md"])
+        .assert()
+        .success()
+        .stdout("Wait…\n\n> Quoted...\n");
+    Ok(())
+}
+
+#[test]
+fn stdin_filepath_selects_the_config() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::create_dir_all(dir.path().join("api"))?;
+    fs::write(dir.path().join("api/.mdtablefix.toml"), KEEP_QUOTES)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--ellipsis", "--stdin-filepath", "api/new.md"])
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout("Wait…\n\n> Quoted...\n");
+    Ok(())
+}
+
+#[test]
+fn invalid_config_fails_only_its_tree() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::create_dir_all(dir.path().join("bad"))?;
+    fs::create_dir_all(dir.path().join("good"))?;
+    fs::write(dir.path().join("bad/.mdtablefix.toml"), "not = [valid\n")?;
+    fs::write(dir.path().join("bad/doc.md"), INPUT)?;
+    fs::write(dir.path().join("good/doc.md"), INPUT)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--ellipsis", "--in-place", "bad/doc.md", "good/doc.md"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(".mdtablefix.toml"));
+
+    assert_eq!(fs::read_to_string(dir.path().join("bad/doc.md"))?, INPUT);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("good/doc.md"))?,
+        "Wait…\n\n> Quoted…\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! CLI tests for `--diff`, which prints unified diffs instead of fixed text.
+
+use std::fs;
+
+use assert_cmd::Command;
+use tempfile::tempdir;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+#[test]
+fn diff_prints_a_patch_per_changed_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let messy = dir.path().join("messy.md");
+    let clean = dir.path().join("clean.md");
+    fs::write(&messy, "Intro\n\n|a|b|\n")?;
+    fs::write(&clean, "Nothing to fix.\n")?;
+    let label = messy.display();
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--diff")
+        .arg(&messy)
+        .arg(&clean)
+        .assert()
+        .success()
+        .stdout(format!(
+            "--- {label}\n+++ {label}\n@@ -1,3 +1,3 @@\n Intro\n \n-|a|b|\n+| a | b |\n"
+        ));
+    assert_eq!(fs::read_to_string(&messy)?, "Intro\n\n|a|b|\n");
+    Ok(())
+}
+
+#[test]
+fn diff_with_check_fails_on_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "|a|b|\n")?;
+    Command::cargo_bin("mdtablefix")?
+        .args(["--diff", "--check"])
+        .arg(&doc)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("+| a | b |"));
+    Ok(())
+}
+
+#[test]
+fn diff_reads_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--diff"], "|a|b|\n")?
+        .success()
+        .stdout("--- <stdin>\n+++ <stdin>\n@@ -1 +1 @@\n-|a|b|\n+| a | b |\n");
+    run_cli_with_stdin(&["--diff"], "unchanged\n")?
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+#[test]
+fn diff_colours_when_asked() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--diff", "--colour", "always"], "|a|b|\n")?
+        .success()
+        .stdout(concat!(
+            "\x1b[1m--- <stdin>\x1b[0m\n",
+            "\x1b[1m+++ <stdin>\x1b[0m\n",
+            "\x1b[36m@@ -1 +1 @@\x1b[0m\n",
+            "\x1b[31m-|a|b|\x1b[0m\n",
+            "\x1b[32m+| a | b |\x1b[0m\n",
+        ));
+    Ok(())
+}
+
+#[test]
+fn diff_is_plain_when_piped() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("doc.md");
+    fs::write(&path, "|a|b|\n")?;
+    let output = Command::cargo_bin("mdtablefix")?
+        .args(["--diff", "--color", "auto"])
+        .arg(&path)
+        .output()?;
+    assert!(!String::from_utf8(output.stdout)?.contains('\x1b'));
+    Ok(())
+}
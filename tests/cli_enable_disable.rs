@@ -0,0 +1,59 @@
+//! CLI tests for switching transforms by name with `--enable` and
+//! `--disable`.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+
+#[rstest]
+#[case::enables_like_flags(&["--enable", "ellipsis,headings"], "Title\n=====\n\nWait...\n", "# Title\n\nWait…\n")]
+#[case::repeatable(&["--enable", "ellipsis", "--enable", "headings"], "Title\n=====\n\nWait...\n", "# Title\n\nWait…\n")]
+#[case::disable_overrides_flag(&["--ellipsis", "--disable", "ellipsis"], "Wait...\n", "Wait...\n")]
+#[case::disable_overrides_enable(&["--enable", "ellipsis", "--disable", "ellipsis"], "Wait...\n", "Wait...\n")]
+#[case::disable_overrides_preset(&["--preset", "readme", "--disable", "wrap"], "Short\nline.\n", "Short\nline.\n")]
+#[case::binary_stages(&["--enable", "renumber"], "1. a\n1. b\n", "1. a\n2. b\n")]
+#[case::tables_always_run(&["--enable", "tables"], "|a|b|\n", "| a | b |\n")]
+fn switches_transforms_by_name(
+    #[case] args: &[&str],
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[rstest]
+#[case::unknown_name(&["--enable", "no-such-transform"], "unknown transform")]
+#[case::always_runs(&["--disable", "tables"], "cannot disable `tables`")]
+#[case::needs_a_value(&["--enable", "hard-breaks"], "cannot enable `hard-breaks`")]
+fn rejects_transforms_that_cannot_be_switched(
+    #[case] args: &[&str],
+    #[case] message: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .write_stdin("text\n")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(message));
+    Ok(())
+}
+
+#[test]
+fn all_enables_every_switch_but_those_disabled() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--all", "--disable", "wrap,footnotes"])
+        .write_stdin("Title\n=====\n\nWait...\n\n1. a\n1. b\n\n***\n")
+        .assert()
+        .success()
+        .stdout(format!(
+            "# Title\n\nWait…\n\n1. a\n2. b\n\n{}\n",
+            "_".repeat(70)
+        ));
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! CLI tests for reading files that are not valid UTF-8 with `--encoding`.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[test]
+fn strict_reports_the_file_and_formats_the_rest() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let latin1 = dir.path().join("latin1.md");
+    let utf8 = dir.path().join("utf8.md");
+    fs::write(&latin1, b"|caf\xe9|b|\n")?;
+    fs::write(&utf8, "|a|b|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--in-place")
+        .args([&latin1, &utf8])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("invalid UTF-8 at byte 4"));
+
+    assert_eq!(fs::read(&latin1)?, b"|caf\xe9|b|\n");
+    assert_eq!(fs::read_to_string(&utf8)?, "| a | b |\n");
+    Ok(())
+}
+
+#[rstest]
+#[case::latin1(b"|caf\xe9|b|\n".to_vec(), b"| caf\xe9 | b |\n".to_vec())]
+#[case::utf16le(
+    utf16le("\u{feff}|\u{e9}|b|\r\n"),
+    utf16le("\u{feff}| \u{e9} | b |\r\n")
+)]
+fn detect_writes_files_back_in_their_encoding(
+    #[case] input: Vec<u8>,
+    #[case] expected: Vec<u8>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, input)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--encoding", "detect"])
+        .arg(&doc)
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::read(&doc)?, expected);
+    Ok(())
+}
+
+#[test]
+fn detect_refuses_characters_latin1_cannot_hold() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, b"caf\xe9...\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--ellipsis", "--encoding", "detect"])
+        .arg(&doc)
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("as Latin-1"));
+
+    assert_eq!(fs::read(&doc)?, b"caf\xe9...\n");
+    Ok(())
+}
+
+#[test]
+fn lossy_replaces_invalid_bytes() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, b"|caf\xe9|b|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--encoding", "lossy"])
+        .arg(&doc)
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::read_to_string(&doc)?, "| caf\u{fffd} | b |\n");
+    Ok(())
+}
+
+#[test]
+fn standard_input_is_decoded_and_printed_as_utf8() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--encoding", "detect"])
+        .write_stdin(b"|caf\xe9|b|\n".to_vec())
+        .assert()
+        .success()
+        .stdout("| café | b |\n");
+    Ok(())
+}
+
+fn utf16le(text: &str) -> Vec<u8> { text.encode_utf16().flat_map(u16::to_le_bytes).collect() }
@@ -0,0 +1,93 @@
+//! CLI tests for the exit status contract: 0 when clean, 1 when a check finds
+//! work or `--in-place` rewrites a file, 2 for usage errors, and 3 for I/O or
+//! parse failures.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[rstest]
+#[case::clean("| a | b |\n", 0)]
+#[case::would_reformat("|a|b|\n", 1)]
+fn check_reports_formatting_drift(
+    #[case] content: &str,
+    #[case] status: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, content)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check")
+        .arg(&doc)
+        .assert()
+        .code(status);
+    Ok(())
+}
+
+#[rstest]
+#[case::clean("| a | b |\n", 0)]
+#[case::rewritten("|a|b|\n", 1)]
+fn in_place_reports_rewritten_files(
+    #[case] content: &str,
+    #[case] status: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, content)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--in-place")
+        .arg(&doc)
+        .assert()
+        .code(status);
+    assert_eq!(fs::read_to_string(&doc)?, "| a | b |\n");
+    Ok(())
+}
+
+#[rstest]
+#[case::unknown_flag(&["--no-such-flag"])]
+#[case::unknown_preset(&["--preset", "no-such-preset"])]
+fn usage_errors_exit_with_two(#[case] args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .write_stdin("text\n")
+        .assert()
+        .code(2)
+        .stdout("");
+    Ok(())
+}
+
+#[test]
+fn missing_file_exits_with_three() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let messy = dir.path().join("messy.md");
+    fs::write(&messy, "|a|b|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check")
+        .arg(&messy)
+        .arg(dir.path().join("missing.md"))
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("missing.md"));
+    Ok(())
+}
+
+#[test]
+fn invalid_config_exits_with_three() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let config = dir.path().join("config.toml");
+    fs::write(&config, "not = [valid\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--config")
+        .arg(&config)
+        .write_stdin("text\n")
+        .assert()
+        .code(3);
+    Ok(())
+}
@@ -0,0 +1,72 @@
+//! CLI tests for keeping, adding, or removing a document's final newline.
+
+use std::fs;
+
+use assert_cmd::Command;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+#[rstest]
+#[case::unterminated(&[], "|a|b|", "| a | b |")]
+#[case::terminated(&[], "|a|b|\n", "| a | b |\n")]
+#[case::trailing_blank_lines(&[], "|a|b|\n\n\n", "| a | b |\n\n\n")]
+#[case::empty(&[], "", "")]
+#[case::always(&["--final-newline", "always"], "|a|b|", "| a | b |\n")]
+#[case::never(&["--final-newline", "never"], "|a|b|\r\n", "| a | b |")]
+fn stdout_follows_the_final_newline_policy(
+    #[case] args: &[&str],
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(args, input)?
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[rstest]
+#[case::keep("keep", "|a|b|", "| a | b |")]
+#[case::always("always", "|a|b|", "| a | b |\n")]
+#[case::never("never", "|a|b|\n", "| a | b |")]
+fn in_place_follows_the_final_newline_policy(
+    #[case] policy: &str,
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let file = dir.path().join("doc.md");
+    fs::write(&file, input)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--final-newline", policy])
+        .arg(&file)
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::read_to_string(&file)?, expected);
+    Ok(())
+}
+
+#[test]
+fn check_accepts_a_missing_final_newline_unless_required() -> Result<(), Box<dyn std::error::Error>>
+{
+    let dir = tempdir()?;
+    let file = dir.path().join("doc.md");
+    fs::write(&file, "Plain text.")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check")
+        .arg(&file)
+        .assert()
+        .success();
+    Command::cargo_bin("mdtablefix")?
+        .args(["--check", "--final-newline", "always"])
+        .arg(&file)
+        .assert()
+        .code(1);
+    Ok(())
+}
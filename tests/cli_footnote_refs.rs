@@ -0,0 +1,56 @@
+//! CLI tests for removing repeated footnote references with
+//! `--compact-footnote-refs`.
+
+use assert_cmd::Command;
+use rstest::rstest;
+
+#[rstest]
+#[case::defaults_to_repeated(
+    &["--compact-footnote-refs"],
+    "Text[^1][^1], more[^2], [^2].\n",
+    "Text[^1], more[^2], [^2].\n"
+)]
+#[case::punctuated(
+    &["--compact-footnote-refs=punctuated"],
+    "Text[^1][^1], more[^2], [^2]; [^3].\n",
+    "Text[^1], more[^2][^3].\n"
+)]
+#[case::after_conversion(
+    &["--footnotes", "--compact-footnote-refs"],
+    "Text.[^1][^1]\n\n[^1]: Note.\n",
+    "Text.[^1]\n\n[^1]: Note.\n"
+)]
+#[case::leaves_code(&["--compact-footnote-refs"], "`a[^1][^1]`\n", "`a[^1][^1]`\n")]
+#[case::enabled_by_name(&["--enable", "footnote-refs"], "a[^x][^x]\n", "a[^x]\n")]
+#[case::disabled_by_name(
+    &["--compact-footnote-refs", "--disable", "footnote-refs"],
+    "a[^x][^x]\n",
+    "a[^x][^x]\n"
+)]
+fn compacts_footnote_references(
+    #[case] args: &[&str],
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn bare_flag_leaves_file_arguments_alone() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let doc = dir.path().join("doc.md");
+    std::fs::write(&doc, "a[^1][^1]\n")?;
+    Command::cargo_bin("mdtablefix")?
+        .arg("--compact-footnote-refs")
+        .arg(&doc)
+        .assert()
+        .success()
+        .stdout("a[^1]\n");
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! CLI tests for the `--format json` run summary.
+
+use std::fs;
+
+use assert_cmd::Command;
+use serde_json::{Value, json};
+use tempfile::tempdir;
+
+fn parse(stdout: &[u8]) -> Result<Value, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_slice(stdout)?)
+}
+
+#[test]
+fn test_cli_format_json_summarizes_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    let clean = dir.path().join("clean.md");
+    let missing = dir.path().join("missing.md");
+    let original = "|a|b|\n|1|2|\n\nalpha beta gamma delta\n";
+    fs::write(&doc, original)?;
+    fs::write(&clean, "Fine.\n")?;
+
+    let output = Command::cargo_bin("mdtablefix")?
+        .args(["--format", "json", "--wrap", "--wrap-width", "12"])
+        .arg(&doc)
+        .arg(&clean)
+        .arg(&missing)
+        .output()?;
+    assert!(!output.status.success());
+
+    let summary = parse(&output.stdout)?;
+    assert_eq!(summary["version"], 1);
+    assert_eq!(
+        summary["files"][0],
+        json!({
+            "path": doc.display().to_string(),
+            "changed": true,
+            "changes": 2,
+            "tables_reflowed": 1,
+            "lines_wrapped": 1,
+            "paragraphs_wrapped": 1,
+            "html_tables_converted": 0,
+            "lists_renumbered": 0,
+            "footnotes_rewritten": 0,
+        })
+    );
+    assert_eq!(summary["files"][1]["changed"], false);
+    assert_eq!(summary["files"][1]["changes"], 0);
+    assert!(
+        summary["files"][2]["error"]
+            .as_str()
+            .is_some_and(|e| e.contains("missing.md"))
+    );
+    assert_eq!(fs::read_to_string(&doc)?, original);
+    Ok(())
+}
+
+#[test]
+fn test_cli_format_json_with_in_place_rewrites() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "|a|b|\n|1|2|\n")?;
+
+    let output = Command::cargo_bin("mdtablefix")?
+        .args(["--format", "json", "--in-place"])
+        .arg(&doc)
+        .output()?;
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(parse(&output.stdout)?["files"][0]["tables_reflowed"], 1);
+    assert_eq!(fs::read_to_string(&doc)?, "| a | b |\n| 1 | 2 |\n");
+    Ok(())
+}
+
+#[test]
+fn test_cli_format_json_from_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin("mdtablefix")?
+        .args(["--format", "json"])
+        .write_stdin("|a|b|\n|1|2|\n")
+        .output()?;
+    assert!(output.status.success());
+    let summary = parse(&output.stdout)?;
+    assert_eq!(summary["files"][0]["path"], "-");
+    assert_eq!(summary["files"][0]["changed"], true);
+    Ok(())
+}
+
+#[test]
+fn test_cli_format_json_conflicts_with_diff() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--format", "json", "--diff"])
+        .write_stdin("x\n")
+        .assert()
+        .failure();
+    Ok(())
+}
@@ -12,7 +12,7 @@ fn in_place_runner() -> impl Fn(&[&str], &str, &str) {
 
         let mut cmd = Command::cargo_bin("mdtablefix").expect("find binary");
         cmd.arg("--in-place").args(args).arg(temp.path());
-        cmd.assert().success();
+        cmd.assert().code(i32::from(input != expected));
 
         let actual = std::fs::read_to_string(temp.path()).expect("read temp file");
         assert_eq!(actual, expected, "in-place content mismatch");
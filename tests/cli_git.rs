@@ -0,0 +1,115 @@
+//! CLI tests for `--changed` and `--staged`.
+
+use std::{fs, path::Path, process::Command as StdCommand};
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+const TABLE: &str = "|a|b|\n|1|2|\n";
+const FIXED: &str = "| a | b |\n| 1 | 2 |\n";
+
+fn git(dir: &Path, args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    let status = StdCommand::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .status()?;
+    assert!(status.success(), "git {args:?} failed");
+    Ok(())
+}
+
+/// Creates a repository with a committed, a staged, a modified, and an
+/// untracked Markdown file, all holding an unformatted table.
+fn repo() -> Result<tempfile::TempDir, Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let root = dir.path();
+    git(root, &["init", "-q"])?;
+    for name in ["clean.md", "staged.md", "modified.md", "notes.txt"] {
+        fs::write(root.join(name), TABLE)?;
+    }
+    git(root, &["add", "."])?;
+    git(root, &["commit", "-q", "-m", "initial"])?;
+    fs::write(root.join("staged.md"), format!("{TABLE}\nMore.\n"))?;
+    git(root, &["add", "staged.md"])?;
+    fs::write(root.join("modified.md"), format!("{TABLE}\nEdited.\n"))?;
+    fs::write(root.join("new.md"), TABLE)?;
+    fs::write(root.join("notes.txt"), "changed, but not Markdown\n")?;
+    Ok(dir)
+}
+
+#[test]
+fn test_cli_changed_processes_modified_and_untracked_files()
+-> Result<(), Box<dyn std::error::Error>> {
+    let dir = repo()?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--changed", "--in-place"])
+        .assert()
+        .code(1);
+    let read = |name: &str| fs::read_to_string(dir.path().join(name));
+    assert_eq!(read("clean.md")?, TABLE);
+    assert_eq!(read("staged.md")?, format!("{FIXED}\nMore.\n"));
+    assert_eq!(read("modified.md")?, format!("{FIXED}\nEdited.\n"));
+    assert_eq!(read("new.md")?, FIXED);
+    Ok(())
+}
+
+#[test]
+fn test_cli_staged_checks_only_the_index() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repo()?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--staged", "--check"])
+        .assert()
+        .failure()
+        .stderr("staged.md: would be reformatted\n");
+    Ok(())
+}
+
+#[test]
+fn test_cli_changed_limits_to_pathspecs_and_excludes() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repo()?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args([
+            "--changed",
+            "--check",
+            "--exclude",
+            "new.md",
+            "new.md",
+            "modified.md",
+        ])
+        .assert()
+        .failure()
+        .stderr("modified.md: would be reformatted\n");
+    Ok(())
+}
+
+#[test]
+fn test_cli_changed_without_changes_succeeds() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repo()?;
+    git(dir.path(), &["add", "."])?;
+    git(dir.path(), &["commit", "-q", "-m", "more"])?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--changed", "--check"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+    Ok(())
+}
+
+#[test]
+fn test_cli_changed_fails_outside_a_repository() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .env("GIT_CEILING_DIRECTORIES", dir.path())
+        .arg("--changed")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("git diff failed"));
+    Ok(())
+}
@@ -0,0 +1,44 @@
+//! CLI tests for blank lines around headings with `--heading-spacing`.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+
+#[rstest]
+#[case::defaults_to_one(&["--heading-spacing"], "# A\ntext\n\n\n## B\ntext\n", "# A\n\ntext\n\n## B\n\ntext\n")]
+#[case::before_and_after(&["--heading-spacing", "2,0"], "text\n# A\n\ntext\n", "text\n\n\n# A\ntext\n")]
+#[case::keeps_frontmatter_gap(
+    &["--heading-spacing"],
+    "---\ntitle: x\n---\n# A\n",
+    "---\ntitle: x\n---\n# A\n"
+)]
+#[case::wrapped_marker_stays_prose(
+    &["--heading-spacing", "--wrap", "--wrap-width", "20"],
+    "aaaa bbbb cccc dddd # eeee ffff\n",
+    "aaaa bbbb cccc dddd #\neeee ffff\n"
+)]
+#[case::enabled_by_name(&["--enable", "heading-spacing"], "# A\ntext\n", "# A\n\ntext\n")]
+fn spaces_headings(
+    #[case] args: &[&str],
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn rejects_counts_that_are_not_numbers() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--heading-spacing", "1,x"])
+        .write_stdin("# A\n")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("not a number of blank lines"));
+    Ok(())
+}
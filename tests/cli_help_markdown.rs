@@ -0,0 +1,49 @@
+//! CLI tests for the `--help-markdown` reference generator.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn reference() -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin("mdtablefix")?
+        .arg("--help-markdown")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    Ok(String::from_utf8(output)?)
+}
+
+#[test]
+fn documents_options_transforms_and_config() -> Result<(), Box<dyn std::error::Error>> {
+    let text = reference()?;
+    assert!(text.starts_with("# mdtablefix\n"));
+    assert!(text.contains("- `--wrap-width N`: Column width for --wrap."));
+    assert!(text.contains("- `--list-marker-spacing[=N]`:"));
+    assert!(text.contains("### `mdtablefix suggest-width`"));
+    assert!(text.contains("| `wrap` "));
+    assert!(text.contains("`[presets.NAME]`"));
+    Ok(())
+}
+
+#[test]
+fn reference_is_already_formatted() -> Result<(), Box<dyn std::error::Error>> {
+    let text = reference()?;
+    Command::cargo_bin("mdtablefix")?
+        .arg("--wrap")
+        .write_stdin(text.clone())
+        .assert()
+        .success()
+        .stdout(text);
+    Ok(())
+}
+
+#[test]
+fn rejects_other_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--help-markdown", "--wrap"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--help-markdown"));
+    Ok(())
+}
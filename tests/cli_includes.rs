@@ -0,0 +1,79 @@
+//! CLI tests for expanding include pragmas with `--expand-includes`.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[rstest]
+#[case::inline(&["--expand-includes"], "# Guide\n\n| a   | b   |\n| --- | --- |\n")]
+#[case::enabled_by_name(&["--enable", "includes"], "# Guide\n\n| a   | b   |\n| --- | --- |\n")]
+#[case::refresh(
+    &["--expand-includes=refresh"],
+    "# Guide\n\n<!-- include: parts/table.md -->\n| a   | b   |\n| --- | --- |\n<!-- end include -->\n"
+)]
+fn expands_fragments_before_formatting(
+    #[case] args: &[&str],
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::create_dir(dir.path().join("parts"))?;
+    fs::write(dir.path().join("parts/table.md"), "|a|b|\n|-|-|\n")?;
+    let doc = dir.path().join("guide.md");
+    fs::write(&doc, "# Guide\n\n<!-- include: parts/table.md -->\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .arg(&doc)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn refreshing_is_idempotent() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let part = dir.path().join("part.md");
+    fs::write(&part, "Old text.\n")?;
+    let doc = dir.path().join("guide.md");
+    fs::write(&doc, "<!-- include: part.md -->\n")?;
+    let refresh = |status: i32| -> Result<String, Box<dyn std::error::Error>> {
+        Command::cargo_bin("mdtablefix")?
+            .args(["--expand-includes=refresh", "--in-place"])
+            .arg(&doc)
+            .assert()
+            .code(status);
+        Ok(fs::read_to_string(&doc)?)
+    };
+
+    let first = refresh(1)?;
+    assert_eq!(
+        first,
+        "<!-- include: part.md -->\nOld text.\n<!-- end include -->\n"
+    );
+    assert_eq!(refresh(0)?, first);
+    fs::write(&part, "New text.\n")?;
+    assert_eq!(
+        refresh(1)?,
+        "<!-- include: part.md -->\nNew text.\n<!-- end include -->\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn cycles_fail_the_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("guide.md");
+    fs::write(&doc, "<!-- include: guide.md -->\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--expand-includes")
+        .arg(&doc)
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("includes itself"));
+    Ok(())
+}
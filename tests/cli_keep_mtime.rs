@@ -0,0 +1,83 @@
+//! CLI tests for the file metadata `--in-place` keeps: permissions always,
+//! and the modification time of unchanged files with `--keep-mtime`.
+
+use std::{
+    fs::{self, File},
+    time::{Duration, SystemTime},
+};
+
+use assert_cmd::Command;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[rstest]
+#[case::kept_with_flag(&["--in-place", "--keep-mtime"], true)]
+#[case::touched_without_flag(&["--in-place"], false)]
+fn unchanged_files_keep_their_mtime_only_with_the_flag(
+    #[case] args: &[&str],
+    #[case] kept: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "| a | b |\n")?;
+    let earlier = SystemTime::now() - Duration::from_secs(3600);
+    File::options()
+        .write(true)
+        .open(&doc)?
+        .set_modified(earlier)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .arg(&doc)
+        .assert()
+        .success();
+
+    assert_eq!(fs::metadata(&doc)?.modified()? == earlier, kept);
+    Ok(())
+}
+
+#[test]
+fn changed_files_are_still_rewritten() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "|a|b|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--keep-mtime"])
+        .arg(&doc)
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::read_to_string(&doc)?, "| a | b |\n");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn rewritten_files_keep_their_mode() -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempdir()?;
+    let doc = dir.path().join("run.md");
+    fs::write(&doc, "|a|b|\n")?;
+    fs::set_permissions(&doc, fs::Permissions::from_mode(0o750))?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--in-place")
+        .arg(&doc)
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::metadata(&doc)?.permissions().mode() & 0o777, 0o750);
+    Ok(())
+}
+
+#[test]
+fn requires_in_place() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .arg("--keep-mtime")
+        .write_stdin("text\n")
+        .assert()
+        .code(2);
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! CLI tests for keeping a document's line endings.
+
+use std::fs;
+
+use assert_cmd::Command;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const CRLF_TABLE: &str = "|a|b|\r\n|1|2|\r\n";
+const CRLF_FIXED: &str = "| a | b |\r\n| 1 | 2 |\r\n";
+
+#[test]
+fn test_cli_keeps_crlf_on_stdout() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&[], CRLF_TABLE)?
+        .success()
+        .stdout(CRLF_FIXED);
+    Ok(())
+}
+
+#[test]
+fn test_cli_keeps_crlf_in_place_and_passes_check() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let file = dir.path().join("windows.md");
+    fs::write(&file, CRLF_TABLE)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--in-place")
+        .arg(&file)
+        .assert()
+        .code(1);
+    assert_eq!(fs::read_to_string(&file)?, CRLF_FIXED);
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check")
+        .arg(&file)
+        .assert()
+        .success()
+        .stderr("");
+    Ok(())
+}
+
+#[test]
+fn test_cli_keeps_the_dominant_ending_of_mixed_files() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&[], "text\n|a|b|\r\n|1|2|\r\n")?
+        .success()
+        .stdout("text\r\n| a | b |\r\n| 1 | 2 |\r\n");
+    Ok(())
+}
+
+#[rstest]
+#[case::to_lf("lf", CRLF_TABLE, "| a | b |\n| 1 | 2 |\n")]
+#[case::to_crlf("crlf", "|a|b|\n|1|2|\n", CRLF_FIXED)]
+fn test_cli_line_ending_flag_converts_files(
+    #[case] style: &str,
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let file = dir.path().join("doc.md");
+    fs::write(&file, input)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--line-ending", style])
+        .arg(&file)
+        .assert()
+        .code(1);
+    assert_eq!(fs::read_to_string(&file)?, expected);
+
+    run_cli_with_stdin(&["--line-ending", style], input)?
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn test_cli_check_reports_files_in_another_ending() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let file = dir.path().join("doc.md");
+    fs::write(&file, "Plain text.\r\n")?;
+
+    for bulk in [false, true] {
+        let mut cmd = Command::cargo_bin("mdtablefix")?;
+        cmd.args(["--check", "--line-ending", "lf"]).arg(&file);
+        if bulk {
+            cmd.arg("--bulk");
+        }
+        cmd.assert().code(1);
+    }
+    Ok(())
+}
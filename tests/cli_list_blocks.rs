@@ -0,0 +1,63 @@
+//! CLI tests for `--list-blocks`.
+
+use std::fs;
+
+use assert_cmd::Command;
+use tempfile::tempdir;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const DOC: &str = "# Title\n\n|a|b|\n|1|2|\n\n```\n|not|a table|\n```\n\n## Next\n\n|c|\n";
+
+#[test]
+fn test_cli_lists_blocks_of_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--list-blocks"], DOC)?
+        .success()
+        .stdout(
+            "<stdin>:1-1: heading #1\n<stdin>:3-4: table #1\n<stdin>:6-8: fence \
+             #1\n<stdin>:10-10: heading #2\n<stdin>:12-12: table #2\n",
+        );
+    Ok(())
+}
+
+#[test]
+fn test_cli_lists_blocks_per_file_without_rewriting() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let first = dir.path().join("first.md");
+    let second = dir.path().join("second.md");
+    fs::write(&first, "|x|\n")?;
+    fs::write(&second, "~~~\ncode\n~~~\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--list-blocks")
+        .arg(&first)
+        .arg(&second)
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}:1-1: table #1\n{}:1-3: fence #1\n",
+            first.display(),
+            second.display()
+        ));
+    assert_eq!(fs::read_to_string(&first)?, "|x|\n");
+    Ok(())
+}
+
+#[test]
+fn test_cli_list_blocks_uses_stdin_filepath() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--list-blocks", "--stdin-filepath", "doc.md"], "|x|\n")?
+        .success()
+        .stdout("doc.md:1-1: table #1\n");
+    Ok(())
+}
+
+#[test]
+fn test_cli_list_blocks_rejects_in_place() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--list-blocks", "--in-place", "x.md"])
+        .assert()
+        .failure();
+    Ok(())
+}
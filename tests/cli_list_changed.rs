@@ -0,0 +1,70 @@
+//! CLI tests for `--list-changed`, which prints the paths of files that would
+//! be reformatted.
+
+use std::fs;
+
+use assert_cmd::Command;
+use tempfile::tempdir;
+
+#[test]
+fn lists_only_files_that_would_change() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let messy = dir.path().join("messy.md");
+    let clean = dir.path().join("clean.md");
+    let other = dir.path().join("other.md");
+    fs::write(&messy, "|a|b|\n|1|2|\n")?;
+    fs::write(&clean, "| a | b |\n| 1 | 2 |\n")?;
+    fs::write(&other, "|x|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--list-changed")
+        .args([&messy, &clean, &other])
+        .assert()
+        .success()
+        .stdout(format!("{}\n{}\n", messy.display(), other.display()))
+        .stderr("");
+
+    assert_eq!(fs::read_to_string(&messy)?, "|a|b|\n|1|2|\n");
+    Ok(())
+}
+
+#[test]
+fn check_sets_the_exit_status() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let messy = dir.path().join("messy.md");
+    fs::write(&messy, "|a|b|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--list-changed", "--check"])
+        .arg(&messy)
+        .assert()
+        .code(1)
+        .stdout(format!("{}\n", messy.display()))
+        .stderr("");
+    Ok(())
+}
+
+#[test]
+fn conflicts_with_in_place() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "|a|b|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--list-changed", "--in-place"])
+        .arg(&doc)
+        .assert()
+        .failure();
+    assert_eq!(fs::read_to_string(&doc)?, "|a|b|\n");
+    Ok(())
+}
+
+#[test]
+fn requires_files() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .arg("--list-changed")
+        .write_stdin("|a|b|\n")
+        .assert()
+        .failure();
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! CLI tests for normalizing list marker spacing with `--list-marker-spacing`.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+
+#[rstest]
+#[case::defaults_to_one_space(
+    &["--list-marker-spacing"],
+    "1.   a\n     more\n\n-b\n",
+    "1. a\n   more\n\n- b\n"
+)]
+#[case::chosen_width(&["--list-marker-spacing", "2"], "- a\n  more\n", "-  a\n   more\n")]
+#[case::follows_renumbered_width(
+    &["--list-marker-spacing", "--renumber"],
+    "10.  a\n\n     more\n",
+    "1. a\n\n   more\n"
+)]
+#[case::leaves_html_blocks(
+    &["--list-marker-spacing"],
+    "<div>\n-   a\n</div>\n",
+    "<div>\n-   a\n</div>\n"
+)]
+#[case::enabled_by_name(&["--enable", "list-markers"], "*  a\n", "* a\n")]
+fn spaces_list_markers(
+    #[case] args: &[&str],
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn rejects_spacing_beyond_four() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--list-marker-spacing", "5"])
+        .write_stdin("- a\n")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("5"));
+    Ok(())
+}
@@ -128,6 +128,7 @@ fn matrix_cases_enable_and_disable_each_transform() {
 #[test]
 fn cli_matrix_snapshots() -> anyhow::Result<()> {
     for logical in logical_cases() {
+        let logical_fixture = logical.fixture;
         let stdout_case = PhysicalCase {
             logical: logical.clone(),
             mode: ExecutionMode::Stdout,
@@ -147,9 +148,12 @@ fn cli_matrix_snapshots() -> anyhow::Result<()> {
         assert_transform_invariants(&stdout_case.logical, &stdout_result.output.stdout)?;
 
         let in_place_result = run_physical_case(&in_place_case).expect("run physical case");
-        assert!(
-            in_place_result.output.status.success(),
-            "{} failed with stderr:\n{}",
+        let rewritten =
+            in_place_result.file_content != std::fs::read(fixture_path(logical_fixture))?;
+        assert_eq!(
+            in_place_result.output.status.code(),
+            Some(i32::from(rewritten)),
+            "{} exited unexpectedly with stderr:\n{}",
             in_place_case.snapshot_name(),
             String::from_utf8_lossy(&in_place_result.output.stderr),
         );
@@ -0,0 +1,77 @@
+//! CLI tests for warnings about interacting options.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const TEXT: &str = "Some text\nbroken across lines.\n";
+
+#[test]
+fn test_cli_warns_about_ignored_options() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--cjk-wrap", "never"], TEXT)?
+        .success()
+        .stdout(TEXT)
+        .stderr(predicate::str::contains(
+            "WARN the CJK wrapping mode has no effect without wrapping",
+        ));
+    run_cli_with_stdin(&["-q", "--cjk-wrap", "never"], TEXT)?
+        .success()
+        .stderr("");
+    Ok(())
+}
+
+#[test]
+fn test_cli_keeps_quiet_about_renumber_comments_on_lists() -> Result<(), Box<dyn std::error::Error>>
+{
+    run_cli_with_stdin(&["--renumber-comments"], TEXT)?
+        .success()
+        .stderr(predicate::str::contains(
+            "renumber comments have no effect without footnote conversion",
+        ));
+    run_cli_with_stdin(&["--renumber", "--renumber-comments"], "1. one\n3. two\n")?
+        .success()
+        .stdout("1. one\n2. two <!-- was 3 -->\n")
+        .stderr("");
+    Ok(())
+}
+
+#[test]
+fn test_cli_logs_notes_when_verbose() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--rejoin"], TEXT)?
+        .success()
+        .stdout("Some text broken across lines.\n")
+        .stderr("");
+    run_cli_with_stdin(&["-v", "--rejoin"], TEXT)?
+        .success()
+        .stderr(predicate::str::contains(
+            "INFO rejoining without wrapping leaves each rejoined paragraph on a single line",
+        ));
+    Ok(())
+}
+
+#[test]
+fn test_cli_names_the_config_behind_a_warning() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let config = dir.path().join(".mdtablefix.toml");
+    fs::write(&config, "[defaults]\nrejoin = true\n")?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, TEXT)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .env_remove("RUST_LOG")
+        .arg("-v")
+        .arg(&doc)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "rejoining without wrapping leaves each rejoined paragraph on a single line config={}",
+            config.display()
+        )));
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! CLI tests for writing formatted copies with `--output-dir`.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[test]
+fn mirrors_inputs_below_the_output_dir() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::create_dir_all(dir.path().join("docs/guide"))?;
+    fs::write(dir.path().join("docs/index.md"), "|a|b|\n")?;
+    fs::write(dir.path().join("docs/guide/intro.md"), "|c|d|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--output-dir", "build", "docs"])
+        .assert()
+        .success()
+        .stdout("");
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("build/docs/index.md"))?,
+        "| a | b |\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("build/docs/guide/intro.md"))?,
+        "| c | d |\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("docs/index.md"))?,
+        "|a|b|\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn refuses_to_overwrite_the_input() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::write(dir.path().join("doc.md"), "|a|b|\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--output-dir", ".", "doc.md"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("would overwrite the input"));
+
+    assert_eq!(fs::read_to_string(dir.path().join("doc.md"))?, "|a|b|\n");
+    Ok(())
+}
+
+#[test]
+fn conflicts_with_in_place() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--output-dir", "out", "--in-place", "doc.md"])
+        .assert()
+        .code(2);
+    Ok(())
+}
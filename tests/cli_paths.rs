@@ -0,0 +1,188 @@
+//! CLI tests for directory and glob arguments.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+const MESSY: &str = "|a|b|\n";
+const FIXED: &str = "| a | b |\n";
+
+#[test]
+fn in_place_walks_directories() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let docs = dir.path().join("docs");
+    fs::create_dir_all(docs.join("guide"))?;
+    fs::create_dir_all(docs.join(".cache"))?;
+    for name in [
+        "index.md",
+        "guide/setup.markdown",
+        "notes.txt",
+        ".cache/old.md",
+    ] {
+        fs::write(docs.join(name), MESSY)?;
+    }
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--in-place")
+        .arg(&docs)
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::read_to_string(docs.join("index.md"))?, FIXED);
+    assert_eq!(
+        fs::read_to_string(docs.join("guide/setup.markdown"))?,
+        FIXED
+    );
+    assert_eq!(fs::read_to_string(docs.join("notes.txt"))?, MESSY);
+    assert_eq!(fs::read_to_string(docs.join(".cache/old.md"))?, MESSY);
+    Ok(())
+}
+
+#[test]
+fn quoted_globs_are_expanded() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::create_dir(dir.path().join("sub"))?;
+    fs::write(dir.path().join("sub/a.md"), MESSY)?;
+    fs::write(dir.path().join("b.md"), MESSY)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--check", "**/*.md"])
+        .assert()
+        .code(1)
+        .stderr(
+            predicate::str::contains("b.md: would be reformatted")
+                .and(predicate::str::contains("a.md: would be reformatted")),
+        );
+    Ok(())
+}
+
+#[test]
+fn unmatched_glob_is_an_error() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .arg("*.md")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no files match `*.md`"));
+    Ok(())
+}
+
+#[test]
+fn exclude_skips_explicit_and_discovered_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::create_dir_all(dir.path().join("docs/generated"))?;
+    for name in ["CHANGELOG.md", "docs/index.md", "docs/generated/api.md"] {
+        fs::write(dir.path().join(name), MESSY)?;
+    }
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args([
+            "--in-place",
+            "--exclude",
+            "CHANGELOG.md",
+            "--exclude",
+            "docs/generated",
+            "CHANGELOG.md",
+            "docs",
+        ])
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::read_to_string(dir.path().join("docs/index.md"))?, FIXED);
+    assert_eq!(fs::read_to_string(dir.path().join("CHANGELOG.md"))?, MESSY);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("docs/generated/api.md"))?,
+        MESSY
+    );
+    Ok(())
+}
+
+#[test]
+fn files_from_stdin_reads_nul_separated_names() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    for name in ["a.md", "b c.md", "skipped.md"] {
+        fs::write(dir.path().join(name), MESSY)?;
+    }
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--files-from", "-", "--in-place"])
+        .write_stdin("a.md\0b c.md\0")
+        .assert()
+        .code(1);
+
+    assert_eq!(fs::read_to_string(dir.path().join("a.md"))?, FIXED);
+    assert_eq!(fs::read_to_string(dir.path().join("b c.md"))?, FIXED);
+    assert_eq!(fs::read_to_string(dir.path().join("skipped.md"))?, MESSY);
+    Ok(())
+}
+
+#[test]
+fn files_from_path_combines_with_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::write(dir.path().join("listed.md"), MESSY)?;
+    fs::write(dir.path().join("named.md"), MESSY)?;
+    fs::write(dir.path().join("list.txt"), "listed.md\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--check", "--files-from", "list.txt", "named.md"])
+        .assert()
+        .code(1)
+        .stderr(
+            predicate::str::contains("named.md: would be reformatted")
+                .and(predicate::str::contains("listed.md: would be reformatted")),
+        );
+    Ok(())
+}
+
+#[test]
+fn empty_file_list_does_nothing() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--files-from", "-", "--in-place"])
+        .write_stdin("")
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+#[test]
+fn ignore_files_skip_walked_paths_unless_disabled() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let root = dir.path();
+    fs::create_dir_all(root.join("target/doc"))?;
+    fs::write(root.join(".gitignore"), "target/\n")?;
+    fs::write(root.join(".mdtablefixignore"), "generated.md\n")?;
+    for name in ["index.md", "generated.md", "target/doc/out.md"] {
+        fs::write(root.join(name), MESSY)?;
+    }
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check")
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(format!(
+            "{}: would be reformatted\n",
+            root.join("index.md").display()
+        ));
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check")
+        .arg(root.join("generated.md"))
+        .assert()
+        .failure();
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--no-ignore"])
+        .arg(root)
+        .assert()
+        .code(1);
+    assert_eq!(fs::read_to_string(root.join("target/doc/out.md"))?, FIXED);
+    assert_eq!(fs::read_to_string(root.join("generated.md"))?, FIXED);
+    Ok(())
+}
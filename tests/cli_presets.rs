@@ -0,0 +1,134 @@
+//! CLI tests for `--preset` and preset overrides in the config file.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+use tempfile::tempdir;
+
+const LONG: &str =
+    "This paragraph is long enough that wrapping at eighty columns has to break it somewhere.";
+
+fn input() -> String { format!("{LONG}\n\n1. a\n3. b\n") }
+
+#[rstest]
+#[case::changelog_keeps_lines("changelog", &format!("{LONG}\n\n1. a\n2. b\n"))]
+#[case::readme_wraps(
+    "readme",
+    "This paragraph is long enough that wrapping at eighty columns has to break \
+     it\nsomewhere.\n\n1. a\n2. b\n"
+)]
+fn builtin_presets_enable_their_flags(
+    #[case] preset: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--preset", preset])
+        .write_stdin(input())
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn command_line_flags_extend_a_preset() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--preset", "changelog", "--ellipsis"])
+        .write_stdin("Wait...\n")
+        .assert()
+        .success()
+        .stdout("Wait…\n");
+    Ok(())
+}
+
+#[test]
+fn config_overrides_and_adds_presets() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join(".mdtablefix.toml"),
+        "[presets.changelog]\nrenumber = false\n\n[presets.dots]\nellipsis = true\n",
+    )?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--preset", "changelog"])
+        .write_stdin("1. a\n3. b\n")
+        .assert()
+        .success()
+        .stdout("1. a\n3. b\n");
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--preset", "dots"])
+        .write_stdin("Wait...\n")
+        .assert()
+        .success()
+        .stdout("Wait…\n");
+    Ok(())
+}
+
+#[test]
+fn explicit_config_path_is_read() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let config = dir.path().join("presets.toml");
+    fs::write(&config, "[presets.dots]\nellipsis = true\n")?;
+    Command::cargo_bin("mdtablefix")?
+        .args(["--preset", "dots", "--config"])
+        .arg(&config)
+        .write_stdin("Wait...\n")
+        .assert()
+        .success()
+        .stdout("Wait…\n");
+    Ok(())
+}
+
+const PROFILES: &str = concat!(
+    "[defaults]\nellipsis = true\nrenumber = true\n\n",
+    "[profile.blog]\nellipsis = false\n\n",
+    "[profile.api-docs]\nfences = true\n",
+);
+
+#[rstest]
+#[case::defaults_alone(&[], "Wait…\n\n1. a\n2. b\n")]
+#[case::profile_switches_a_default_off(&["--profile", "blog"], "Wait...\n\n1. a\n2. b\n")]
+#[case::flag_extends_a_profile(&["--profile", "blog", "--ellipsis"], "Wait…\n\n1. a\n2. b\n")]
+#[case::profile_keeps_other_defaults(&["--profile", "api-docs"], "Wait…\n\n1. a\n2. b\n")]
+#[case::disable_wins(&["--profile", "api-docs", "--disable", "renumber"], "Wait…\n\n1. a\n3. b\n")]
+fn profiles_merge_over_config_defaults(
+    #[case] args: &[&str],
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::write(dir.path().join(".mdtablefix.toml"), PROFILES)?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(args)
+        .write_stdin("Wait...\n\n1. a\n3. b\n")
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[rstest]
+#[case::unknown_preset("", "unknown preset `nope`")]
+#[case::unknown_flag("[presets.nope]\nwrapp = true\n", "unknown field `wrapp`")]
+fn bad_presets_are_rejected(
+    #[case] config: &str,
+    #[case] message: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::write(dir.path().join(".mdtablefix.toml"), config)?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--preset", "nope"])
+        .write_stdin("text\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(message));
+    Ok(())
+}
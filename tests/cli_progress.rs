@@ -0,0 +1,32 @@
+//! CLI tests for the batch progress bar.
+
+use std::fs;
+
+use assert_cmd::Command;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[rstest]
+#[case::default(&[])]
+#[case::quiet(&["--quiet"])]
+#[case::short_quiet(&["-q"])]
+fn progress_stays_off_when_not_a_terminal(
+    #[case] flags: &[&str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    for name in ["a.md", "b.md", "c.md"] {
+        fs::write(dir.path().join(name), "|a|b|\n")?;
+    }
+    Command::cargo_bin("mdtablefix")?
+        .args(flags)
+        .arg("--in-place")
+        .arg(dir.path())
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("");
+    for name in ["a.md", "b.md", "c.md"] {
+        assert_eq!(fs::read_to_string(dir.path().join(name))?, "| a | b |\n");
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! CLI tests for recording original numbers with `--renumber-comments` and
+//! removing them with `--strip-renumber-comments`.
+
+use assert_cmd::Command;
+use rstest::rstest;
+
+#[rstest]
+#[case::lists(
+    &["--renumber", "--renumber-comments"],
+    "1. a\n7. b\n3. c\n",
+    "1. a\n2. b <!-- was 7 -->\n3. c\n"
+)]
+#[case::footnotes(
+    &["--footnotes", "--renumber-comments"],
+    "Text.[^4] More.[^2]\n\n[^2]: Two\n[^4]: Four\n",
+    "Text.[^1] More.[^2]\n\n[^1]: Four <!-- was 4 -->\n[^2]: Two\n"
+)]
+#[case::keeps_the_first_comment(
+    &["--renumber", "--renumber-comments"],
+    "1. a <!-- was 5 -->\n1. b <!-- was 7 -->\n",
+    "1. a <!-- was 5 -->\n2. b <!-- was 7 -->\n"
+)]
+#[case::strips(
+    &["--strip-renumber-comments"],
+    "1. a\n2. b <!-- was 7 -->\n\n```\n3. c <!-- was 9 -->\n```\n",
+    "1. a\n2. b\n\n```\n3. c <!-- was 9 -->\n```\n"
+)]
+fn records_and_strips_original_numbers(
+    #[case] args: &[&str],
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn adding_and_stripping_conflict() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--renumber-comments", "--strip-renumber-comments"])
+        .write_stdin("1. a\n")
+        .assert()
+        .code(2);
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! CLI tests for the `--report-file` JSON audit artefact.
+
+use std::fs;
+
+use assert_cmd::Command;
+use serde_json::Value;
+use tempfile::tempdir;
+
+fn read_report(path: &std::path::Path) -> Result<Value, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[test]
+fn test_cli_report_file_records_in_place_transforms() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    let clean = dir.path().join("clean.md");
+    let report = dir.path().join("report.json");
+    fs::write(&doc, "Title\n=====\n\n|a|b|\n|1|2|\n")?;
+    fs::write(&clean, "Nothing to fix.\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--in-place", "--headings", "--report-file"])
+        .arg(&report)
+        .arg(&doc)
+        .arg(&clean)
+        .assert()
+        .code(1)
+        .stdout("");
+
+    let json = read_report(&report)?;
+    assert_eq!(json["version"], 1);
+    let files = json["files"].as_array().ok_or("files should be an array")?;
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0]["changed"], true);
+    assert_eq!(files[1]["changed"], false);
+
+    let changes = files[0]["changes"]
+        .as_array()
+        .ok_or("changes should be an array")?;
+    let transforms: Vec<&str> = changes
+        .iter()
+        .filter_map(|c| c["transform"].as_str())
+        .collect();
+    assert_eq!(transforms, vec!["tables", "headings"]);
+    assert_eq!(changes[0]["before"]["start"], 4);
+    assert_eq!(changes[0]["before"]["lines"][0], "|a|b|");
+    assert_eq!(changes[0]["after"]["lines"][0], "| a | b |");
+    Ok(())
+}
+
+#[test]
+fn test_cli_report_file_records_errors_and_frontmatter_offset()
+-> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    let missing = dir.path().join("missing.md");
+    let report = dir.path().join("report.json");
+    fs::write(&doc, "---\ntitle: x\n---\n1. a\n3. b\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--renumber", "--report-file"])
+        .arg(&report)
+        .arg(&doc)
+        .arg(&missing)
+        .assert()
+        .failure();
+
+    let json = read_report(&report)?;
+    let change = &json["files"][0]["changes"][0];
+    assert_eq!(change["transform"], "renumber");
+    assert_eq!(change["before"]["start"], 5);
+    assert_eq!(change["after"]["lines"][0], "2. b");
+    assert!(
+        json["files"][1]["error"]
+            .as_str()
+            .is_some_and(|e| e.contains("missing.md"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cli_report_file_bounds_snippets() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let report = dir.path().join("report.json");
+    let input = (0..20)
+        .map(|i| format!("|{i}|x|"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--report-file")
+        .arg(&report)
+        .write_stdin(input)
+        .assert()
+        .success();
+
+    let json = read_report(&report)?;
+    let before = &json["files"][0]["changes"][0]["before"];
+    assert_eq!(json["files"][0]["path"], "-");
+    assert_eq!(before["count"], 20);
+    assert_eq!(before["truncated"], true);
+    assert_eq!(before["lines"].as_array().map(Vec::len), Some(8));
+    Ok(())
+}
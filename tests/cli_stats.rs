@@ -0,0 +1,63 @@
+//! CLI tests for the `--stats` per-file statistics.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const DOC: &str = "<table><tr><td>a</td></tr></table>\n\n|x|y|\n|1|2|\n\nalpha beta gamma \
+                   delta\n\n1. one\n3. two\n";
+
+#[test]
+fn test_cli_stats_counts_each_transform() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    let clean = dir.path().join("clean.md");
+    fs::write(&doc, DOC)?;
+    fs::write(&clean, "Fine.\n")?;
+    // Without the `html` feature the HTML table is left as written.
+    let html = if cfg!(feature = "html") {
+        "1 HTML table"
+    } else {
+        "0 HTML tables"
+    };
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--stats", "--wrap", "--wrap-width", "12", "--renumber"])
+        .arg(&doc)
+        .arg(&clean)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("| x | y |"))
+        .stderr(predicate::str::contains(format!(
+            "{}: 1 table reflowed, {html} converted, 1 paragraph wrapped, 1 list renumbered, 0 \
+             footnotes rewritten",
+            doc.display()
+        )))
+        .stderr(predicate::str::contains(format!(
+            "{}: 0 tables reflowed, 0 HTML tables converted, 0 paragraphs wrapped, 0 lists \
+             renumbered, 0 footnotes rewritten",
+            clean.display()
+        )));
+    Ok(())
+}
+
+#[test]
+fn test_cli_stats_labels_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(
+        &["--stats", "--footnotes"],
+        "A useful tip.1\n\n## Footnotes\n\n1. Source\n",
+    )?
+    .success()
+    .stdout(predicate::str::contains("[^1]: Source"))
+    .stderr(
+        "<stdin>: 0 tables reflowed, 0 HTML tables converted, 0 paragraphs wrapped, 0 lists \
+         renumbered, 1 footnote rewritten\n",
+    );
+    Ok(())
+}
@@ -0,0 +1,53 @@
+//! CLI tests for `--stdin-filepath`.
+
+use assert_cmd::Command;
+use rstest::rstest;
+
+#[test]
+fn diff_is_labelled_with_the_path() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--diff", "--stdin-filepath", "docs/guide.md"])
+        .write_stdin("|a|b|\n")
+        .assert()
+        .success()
+        .stdout("--- docs/guide.md\n+++ docs/guide.md\n@@ -1 +1 @@\n-|a|b|\n+| a | b |\n");
+    Ok(())
+}
+
+#[test]
+fn summary_uses_the_path() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin("mdtablefix")?
+        .args(["--format", "json", "--stdin-filepath", "docs/guide.md"])
+        .write_stdin("|a|b|\n")
+        .output()?;
+    assert!(output.status.success());
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(summary["files"][0]["path"], "docs/guide.md");
+    Ok(())
+}
+
+#[rstest]
+#[case::excluded_directory("vendor", "|a|b|\n")]
+#[case::excluded_name("*.md", "|a|b|\n")]
+#[case::other_path("docs", "| a | b |\n")]
+fn excluded_paths_pass_through(
+    #[case] pattern: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--stdin-filepath", "vendor/x.md", "--exclude", pattern])
+        .write_stdin("|a|b|\n")
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn conflicts_with_file_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["--stdin-filepath", "x.md", "y.md"])
+        .assert()
+        .failure();
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! CLI tests for the `fmt`, `check`, and `lint` subcommands.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[rstest]
+#[case::bare(&[])]
+#[case::fmt(&["fmt"])]
+fn fmt_matches_the_bare_invocation(
+    #[case] command: &[&str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(command)
+        .arg("--ellipsis")
+        .write_stdin("|a|b|\n\nWait...\n")
+        .assert()
+        .success()
+        .stdout("| a | b |\n\nWait…\n");
+    Ok(())
+}
+
+#[test]
+fn check_subcommand_reports_without_rewriting() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "|a|b|\n")?;
+    Command::cargo_bin("mdtablefix")?
+        .arg("check")
+        .arg(&doc)
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(format!("{}: would be reformatted\n", doc.display()));
+    assert_eq!(fs::read_to_string(&doc)?, "|a|b|\n");
+    Ok(())
+}
+
+#[test]
+fn lint_prints_a_diagnostic_per_change() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "# Title\n\n|a|b|\n|-|-|\n\nWait...\n")?;
+    Command::cargo_bin("mdtablefix")?
+        .args(["lint", "--ellipsis"])
+        .arg(&doc)
+        .assert()
+        .code(1)
+        .stdout(format!(
+            "{0}:3: tables: would rewrite 2 lines\n{0}:6: ellipsis: would rewrite 1 line\n",
+            doc.display()
+        ));
+    Command::cargo_bin("mdtablefix")?
+        .arg("lint")
+        .write_stdin("| a | b |\n")
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+#[rstest]
+#[case::check_in_place(&["check", "--in-place", "doc.md"], "--in-place")]
+#[case::lint_diff(&["lint", "--diff", "doc.md"], "--diff")]
+fn rejects_conflicting_options(
+    #[case] args: &[&str],
+    #[case] message: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(message));
+    Ok(())
+}
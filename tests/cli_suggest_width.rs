@@ -0,0 +1,57 @@
+//! CLI tests for the `suggest-width` subcommand.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[test]
+fn suggests_wrap_and_table_widths() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    let prose = format!("{}\n", "word ".repeat(16).trim_end());
+    let table = format!("| {} | b |\n| --- | --- |\n", "x".repeat(90));
+    fs::write(&doc, format!("{}\n{table}", prose.repeat(3)))?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("suggest-width")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Prose lines: 3, widest 79 columns",
+        ))
+        .stdout(predicate::str::contains(
+            "100% of prose lines fit at 80 columns.",
+        ))
+        .stdout(predicate::str::contains(
+            "Suggested: --wrap-width 80 --max-table-width 100",
+        ));
+    assert!(fs::read_to_string(&doc)?.starts_with("word"));
+    Ok(())
+}
+
+#[test]
+fn reports_documents_without_prose_or_tables() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, "# Title\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["suggest-width"])
+        .arg(&doc)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No prose or tables to measure."));
+    Ok(())
+}
+
+#[test]
+fn rejects_formatting_flags() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["suggest-width", "--wrap", "doc.md"])
+        .assert()
+        .code(2);
+    Ok(())
+}
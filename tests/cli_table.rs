@@ -0,0 +1,54 @@
+//! CLI tests for `mdtablefix table -`, which reflows one table from standard
+//! input.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+
+#[rstest]
+#[case::markdown(&[], "|a|b|\n|-|-|\n|ccc|d|\n", "| a   | b   |\n| --- | --- |\n| ccc | d   |\n")]
+#[case::keeps_blank_lines(&[], "\n|a|\n|-|\n\n", "\n| a   |\n| --- |\n\n")]
+#[case::csv(&["--from", "csv"], "name,note\nada,\"x|y\"\n", "| name | note |\n| ---- | ---- |\n| ada  | x\\|y |\n")]
+#[case::edges_only(&["--table-style", "edges-only"], "|a|bb|\n|-|-|\n|ccc|d|\n", "| a | bb    |\n| --- | --- |\n| ccc | d   |\n")]
+#[case::align(&["--align", "r,c"], "|a|b|\n|-|-|\n|ccc|d|\n", "| a   | b   |\n| --: | :-: |\n| ccc | d   |\n")]
+fn reflows_one_table(
+    #[case] args: &[&str],
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .arg("table")
+        .args(args)
+        .arg("-")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[rstest]
+#[case::prose("Just prose.\n")]
+#[case::two_tables("|a|\n|-|\n\n|b|\n|-|\n")]
+#[case::empty("")]
+fn rejects_input_that_is_not_one_table(
+    #[case] input: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["table", "-"])
+        .write_stdin(input)
+        .assert()
+        .code(3)
+        .stdout("");
+    Ok(())
+}
+
+#[test]
+fn requires_the_stdin_marker() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(["table", "doc.md"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("invalid value"));
+    Ok(())
+}
@@ -0,0 +1,132 @@
+//! CLI tests for `--check-titles` and `--fix-titles`.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[test]
+fn reports_headings_that_differ_from_their_title() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let titled = dir.path().join("setup.md");
+    fs::write(&titled, "---\ntitle: Setup\n---\n\n# Install\n")?;
+    let named = dir.path().join("getting-started.md");
+    fs::write(&named, "# Getting Started\n")?;
+    let renamed = dir.path().join("usage.md");
+    fs::write(&renamed, "# Options\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check-titles")
+        .args([&titled, &named, &renamed])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(
+            predicate::str::contains(format!(
+                "{}:5: heading \"Install\" does not match the front matter title \"Setup\"",
+                titled.display()
+            ))
+            .and(predicate::str::contains(format!(
+                "{}:1: heading \"Options\" does not match the file name \"usage\"",
+                renamed.display()
+            )))
+            .and(predicate::str::contains("getting-started").not())
+            .and(predicate::str::contains("2 file(s)")),
+        );
+    assert_eq!(
+        fs::read_to_string(&titled)?,
+        "---\ntitle: Setup\n---\n\n# Install\n"
+    );
+    Ok(())
+}
+
+#[rstest]
+#[case::unnamed(&[], 0)]
+#[case::named(&["--stdin-filepath", "docs/usage.md"], 1)]
+fn stdin_is_named_by_its_filepath(
+    #[case] args: &[&str],
+    #[case] code: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .arg("--check-titles")
+        .args(args)
+        .write_stdin("# Options\n")
+        .assert()
+        .code(code);
+    Ok(())
+}
+
+#[rstest]
+#[case::flag(&["--fix-titles"])]
+#[case::enabled_by_name(&["--enable", "titles"])]
+fn rewrites_the_heading_to_the_front_matter_title(
+    #[case] args: &[&str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .args(args)
+        .write_stdin("---\ntitle: \"Setup\"\n---\n\nInstall\n=======\n")
+        .assert()
+        .success()
+        .stdout("---\ntitle: \"Setup\"\n---\n\nSetup\n=======\n");
+    Ok(())
+}
+
+#[test]
+fn rewrites_the_heading_to_the_file_name() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("02-getting_started.md");
+    fs::write(
+        &doc,
+        "# Install
+",
+    )?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["--fix-titles", "--in-place"])
+        .arg(&doc)
+        .assert()
+        .code(1);
+    assert_eq!(
+        fs::read_to_string(&doc)?,
+        "# Getting started
+"
+    );
+    Ok(())
+}
+
+#[rstest]
+#[case::unnamed(&[], "# Install\n")]
+#[case::named(&["--stdin-filepath", "docs/usage.md"], "# Usage\n")]
+fn titles_stdin_by_its_file_path(
+    #[case] args: &[&str],
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("mdtablefix")?
+        .arg("--fix-titles")
+        .args(args)
+        .write_stdin("# Install\n")
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn lint_reports_a_title_fix() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("setup.md");
+    fs::write(&doc, "---\ntitle: Setup\n---\n\n# Install\n")?;
+
+    Command::cargo_bin("mdtablefix")?
+        .args(["lint", "--fix-titles"])
+        .arg(&doc)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(format!(
+            "{}:5: titles",
+            doc.display()
+        )));
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! CLI tests for the `-v` and `-q` logging flags.
+
+use std::fs;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const TABLE: &str = "|a|b|\n|1|2|\n";
+
+#[test]
+fn test_cli_is_silent_by_default() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&[], TABLE)?.success().stderr("");
+    Ok(())
+}
+
+#[test]
+fn test_cli_verbose_logs_each_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let doc = dir.path().join("doc.md");
+    fs::write(&doc, TABLE)?;
+
+    Command::cargo_bin("mdtablefix")?
+        .env_remove("RUST_LOG")
+        .arg("-v")
+        .arg(&doc)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "processing file path={}",
+            doc.display()
+        )))
+        .stderr(predicate::str::is_match(
+            r"file processed path=\S+ changed=true elapsed_ms=\d+",
+        )?)
+        .stderr(predicate::str::contains("stage applied").not());
+    Ok(())
+}
+
+#[test]
+fn test_cli_very_verbose_logs_each_stage() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["-vv", "--renumber"], TABLE)?
+        .success()
+        .stderr(predicate::str::is_match(
+            r#"stage applied transform="tables" changed=true elapsed_us=\d+"#,
+        )?)
+        .stderr(predicate::str::contains(
+            r#"stage applied transform="renumber" changed=false"#,
+        ))
+        .stderr(predicate::str::contains("processing file path=<stdin>"));
+    Ok(())
+}
+
+#[test]
+fn test_cli_rejects_verbose_with_quiet() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["-v", "-q"], TABLE)?
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
@@ -23,6 +23,17 @@ fn cli_stdin_code_emphasis() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn cli_fixes_table_cells_before_reflow() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "| Item | Notes |\n| --- | --- |\n| `x`** y (in **`z`**)** | ok... |\n";
+    let expected = "| Item               | Notes |\n| ------------------ | ----- |\n| **`x` y (in \
+                    `z`)** | ok…   |\n";
+    run_cli_with_stdin(&["--code-emphasis", "--ellipsis"], input)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
 #[test]
 fn cli_without_flag_is_noop_for_code_emphasis_input() -> Result<(), Box<dyn std::error::Error>> {
     let input = "`StepContext`** Enhancement (in **`crates/rstest-bdd/src/context.rs`**)**\n";
@@ -55,7 +66,7 @@ fn cli_in_place_code_emphasis() {
         file_path.to_str().expect("path is not valid UTF-8"),
     ])
     .unwrap()
-    .success()
+    .code(1)
     .stdout("");
     let out = fs::read_to_string(&file_path).expect("failed to read output file");
     assert_eq!(out, expected);
@@ -83,7 +94,7 @@ fn cli_in_place_code_emphasis_whitespace_file() {
     let dir = tempdir().expect("failed to create temporary directory");
     let file_path = dir.path().join("whitespace.md");
     let input = "   \n\t  ";
-    let expected = "   \n\t  \n";
+    let expected = "   \n\t  ";
     fs::write(&file_path, input).expect("failed to write test file");
     run_cli_with_args(&[
         "--code-emphasis",
@@ -0,0 +1,50 @@
+//! Integration tests for the `--code-spans` flag.
+//!
+//! Verifies that inline code loses incidental padding, uses the fewest
+//! backticks needed, and that tables are aligned to the normalized spans.
+
+use rstest::rstest;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+#[rstest]
+#[case("Run ` cargo test ` now.\n", "Run `cargo test` now.\n")]
+#[case("A ```` a`b ```` span.\n", "A ``a`b`` span.\n")]
+#[case("Tick ``` `x` ``` here.\n", "Tick `` `x` `` here.\n")]
+fn cli_normalizes_code_spans(
+    #[case] input: &str,
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--code-spans"], input)?
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn cli_without_flag_keeps_code_span_padding() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "Run ` cargo test ` now.\n";
+    run_cli_with_stdin(&[], input)?.success().stdout(input);
+    Ok(())
+}
+
+#[test]
+fn cli_code_spans_realign_tables() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "| cmd | note |\n| --- | --- |\n| `  ls  ` | list |\n";
+    let expected = "| cmd  | note |\n| ---- | ---- |\n| `ls` | list |\n";
+    run_cli_with_stdin(&["--code-spans"], input)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn cli_code_spans_skip_fenced_code() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "```md\nKeep ` this ` as written.\n```\n";
+    run_cli_with_stdin(&["--code-spans"], input)?
+        .success()
+        .stdout(input);
+    Ok(())
+}
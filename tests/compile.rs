@@ -1,5 +1,6 @@
 //! Compile-time regression tests for dependency integration.
 
+#[cfg(feature = "html")]
 #[test]
 fn html5ever_rcdom_parser_stack_compiles() {
     let cases = trybuild::TestCases::new();
@@ -0,0 +1,109 @@
+//! End-to-end tests for `--daemon` socket mode.
+#![cfg(unix)]
+
+use std::{
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    path::Path,
+    process::{Child, Command},
+    thread,
+    time::{Duration, Instant},
+};
+
+use rstest::rstest;
+use serde_json::{Value, json};
+use tempfile::tempdir;
+
+/// Kills the daemon when a test finishes, even if an assertion fails.
+struct Daemon(Child);
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn spawn_daemon(socket: &Path, flags: &[&str]) -> Daemon {
+    let child = Command::new(assert_cmd::cargo::cargo_bin("mdtablefix"))
+        .args(flags)
+        .arg("--daemon")
+        .arg(socket)
+        .spawn()
+        .expect("failed to spawn daemon");
+    Daemon(child)
+}
+
+fn connect(socket: &Path) -> UnixStream {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        match UnixStream::connect(socket) {
+            Ok(stream) => return stream,
+            Err(err) if Instant::now() > deadline => panic!("daemon never came up: {err}"),
+            Err(_) => thread::sleep(Duration::from_millis(20)),
+        }
+    }
+}
+
+fn exchange(stream: &mut UnixStream, request: &Value) -> Value {
+    let payload = request.to_string();
+    let len = u32::try_from(payload.len()).expect("request fits in a frame");
+    stream
+        .write_all(&len.to_be_bytes())
+        .expect("failed to write length");
+    stream
+        .write_all(payload.as_bytes())
+        .expect("failed to write payload");
+    let mut len_bytes = [0_u8; 4];
+    stream
+        .read_exact(&mut len_bytes)
+        .expect("failed to read length");
+    let mut response = vec![0_u8; u32::from_be_bytes(len_bytes) as usize];
+    stream
+        .read_exact(&mut response)
+        .expect("failed to read payload");
+    serde_json::from_slice(&response).expect("response should be JSON")
+}
+
+#[rstest]
+fn test_daemon_formats_requests_over_socket() {
+    let dir = tempdir().expect("failed to create temporary directory");
+    let socket = dir.path().join("mdtablefix.sock");
+    let file = dir.path().join("doc.md");
+    std::fs::write(&file, "1. a\n4. b\n").expect("failed to write file");
+    let _daemon = spawn_daemon(&socket, &["--renumber"]);
+    let mut stream = connect(&socket);
+
+    let table = exchange(&mut stream, &json!({ "content": "|a|b|\n|1|2|\n" }));
+    assert_eq!(
+        table,
+        json!({ "ok": true, "output": "| a | b |\n| 1 | 2 |\n" })
+    );
+
+    let from_path = exchange(&mut stream, &json!({ "path": file }));
+    assert_eq!(from_path["output"], "1. a\n2. b\n");
+
+    let bad = exchange(&mut stream, &json!({ "content": "x", "args": ["--bogus"] }));
+    assert_eq!(bad["ok"], false);
+}
+
+#[rstest]
+fn test_daemon_replaces_stale_socket() {
+    let dir = tempdir().expect("failed to create temporary directory");
+    let socket = dir.path().join("stale.sock");
+    drop(std::os::unix::net::UnixListener::bind(&socket).expect("failed to bind"));
+    let _daemon = spawn_daemon(&socket, &[]);
+    let mut stream = connect(&socket);
+    let response = exchange(&mut stream, &json!({ "content": "|a|b|" }));
+    assert_eq!(response["output"], "| a | b |");
+}
+
+#[rstest]
+fn test_daemon_conflicts_with_files() {
+    assert_cmd::Command::cargo_bin("mdtablefix")
+        .expect("failed to create command")
+        .args(["--daemon", "sock", "file.md"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--daemon"));
+}
@@ -0,0 +1,28 @@
+//! Integration tests for the `--emphasis-headings` flag.
+//!
+//! Verifies that bold-only paragraphs become ATX headings at the requested
+//! level, while bold text inside lists and prose is left alone.
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const INPUT: &str = "**Installation**\n\nRun the installer.\n\n- Step one\n\n  \
+                     **Optional**\n\n**Note:** restart afterwards.\n";
+
+#[test]
+fn cli_converts_emphasis_headings() -> Result<(), Box<dyn std::error::Error>> {
+    let expected = INPUT.replacen("**Installation**", "### Installation", 1);
+    run_cli_with_stdin(&["--emphasis-headings", "3"], INPUT)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn cli_rejects_out_of_range_level() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--emphasis-headings", "7"], INPUT)?
+        .failure()
+        .stderr(predicates::str::contains("--emphasis-headings"));
+    Ok(())
+}
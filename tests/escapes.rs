@@ -0,0 +1,85 @@
+//! Regression tests for backslash escapes passing through every transform.
+//!
+//! Each case runs the library pipeline with the escape-sensitive transforms
+//! switched on, so an escape one of them drops or doubles shows up whichever
+//! module introduced it.
+
+use mdtablefix::{
+    Options,
+    footnotes::FootnoteRefCompaction,
+    process_stream_opts,
+    textproc::is_escaped,
+};
+use rstest::rstest;
+
+#[macro_use]
+mod common;
+
+fn all_transforms() -> Options {
+    Options {
+        wrap: true,
+        ellipsis: true,
+        footnotes: true,
+        footnote_refs: Some(FootnoteRefCompaction::default()),
+        code_emphasis: true,
+        headings: true,
+        emphasis_headings: Some(2),
+        ..Options::default()
+    }
+}
+
+fn run(text: &str) -> Vec<String> {
+    process_stream_opts(
+        &text.lines().map(str::to_string).collect::<Vec<_>>(),
+        all_transforms(),
+    )
+}
+
+#[rstest]
+#[case::emphasis(r"Use \*stars\* and \_underscores\_ literally.")]
+#[case::brackets(r"Write \[link\](url) without linking.")]
+#[case::escaped_dots(r"Wait\... for it")]
+#[case::escaped_footnote(r"Cite as \[^5] in text.")]
+#[case::escaped_footnote_run(r"Repeat \[^1][^1] once.")]
+#[case::escaped_emphasis_heading(r"**Not a heading\**")]
+#[case::backslash_pair(r"Paths like C:\\ stay.")]
+fn escapes_survive_unchanged(#[case] line: &str) {
+    assert_eq!(run(line), lines_vec![line]);
+}
+
+#[rstest]
+#[case::escaped_pipe(
+    &[r"|a|b\|c|", "|-|-|"],
+    &[r"| a   | b\|c |", "| --- | ---- |"],
+)]
+#[case::escaped_trailing_pipe(
+    &[r"|a|b\||", "|-|-|"],
+    &[r"| a   | b\| |", "| --- | --- |"],
+)]
+#[case::escaped_backslash_before_pipe(
+    &[r"|a\\|b|", "|-|-|"],
+    &[r"| a\\ | b   |", "| --- | --- |"],
+)]
+fn table_cells_keep_their_escapes(#[case] input: &[&str], #[case] expected: &[&str]) {
+    let input: Vec<String> = input.iter().map(ToString::to_string).collect();
+    assert_eq!(process_stream_opts(&input, all_transforms()), expected);
+}
+
+#[test]
+fn unescaped_syntax_after_a_backslash_pair_is_still_rewritten() {
+    assert_eq!(run(r"Wait\\... for it"), lines_vec![r"Wait\\… for it"]);
+    assert_eq!(run(r"**Heading\\**"), lines_vec![r"## Heading\\"]);
+}
+
+#[rstest]
+#[case::single(r"\*", 1, true)]
+#[case::pair(r"\\*", 2, false)]
+#[case::triple(r"\\\*", 3, true)]
+#[case::start("*", 0, false)]
+fn is_escaped_counts_the_backslash_run(
+    #[case] text: &str,
+    #[case] index: usize,
+    #[case] expected: bool,
+) {
+    assert_eq!(is_escaped(text, index), expected);
+}
@@ -0,0 +1,146 @@
+//! End-to-end tests for converting a trailing numeric list into footnote
+//! definitions.
+//!
+//! A list is rewritten only when it ends the document, follows an H2 heading,
+//! and no footnote definition block already exists; the cases cover each of
+//! those guards and the blank lines a list may contain.
+
+use mdtablefix::{convert_footnotes, process_stream};
+
+#[macro_use]
+#[path = "common/mod.rs"]
+mod common;
+
+#[test]
+fn test_convert_preserves_headers_with_blank_separators() {
+    let input: Vec<String> = include_lines!("data/footnotes_regression_input.txt");
+    let expected: Vec<String> = include_lines!("data/footnotes_regression_expected.txt");
+
+    let reflowed = process_stream(&input);
+    assert_eq!(
+        reflowed, expected,
+        "reflowed fixture must match expectation"
+    );
+
+    let output = convert_footnotes(&reflowed);
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_converts_list_with_blank_lines() {
+    let input = lines_vec!(
+        "Text.",
+        "",
+        "## Footnotes",
+        "",
+        " 1. First",
+        "  ",
+        " 2. Second",
+        "",
+        "10. Tenth",
+        "   ",
+        "",
+    );
+    let expected = lines_vec!(
+        "Text.",
+        "",
+        "## Footnotes",
+        "",
+        " [^1]: First",
+        "  ",
+        " [^2]: Second",
+        "",
+        "[^3]: Tenth",
+        "   ",
+        "",
+    );
+    let output = convert_footnotes(&input);
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_empty_input() {
+    let input: Vec<String> = Vec::new();
+    let output = convert_footnotes(&input);
+    assert!(output.is_empty());
+}
+
+#[test]
+fn test_whitespace_input() {
+    let input = lines_vec!("   ", "\t");
+    let output = convert_footnotes(&input);
+    assert_eq!(output, input);
+}
+
+#[test]
+fn test_requires_h2_heading() {
+    let input = lines_vec!("Text.", " 1. First footnote",);
+    assert_eq!(convert_footnotes(&input), input);
+}
+
+#[test]
+fn test_skips_when_existing_block_present() {
+    let input = lines_vec!("[^1]: Old", "## Footnotes", " 2. New",);
+    assert_eq!(convert_footnotes(&input), input);
+}
+
+#[test]
+fn test_skips_when_list_not_last() {
+    let input = lines_vec!("## Footnotes", " 1. Note", "", "Tail.",);
+    assert_eq!(convert_footnotes(&input), input);
+}
+
+#[test]
+fn test_skips_with_h3_heading() {
+    let input = lines_vec!("Text.", "### Notes", " 1. First");
+    assert_eq!(convert_footnotes(&input), input);
+}
+
+#[test]
+fn test_converts_with_non_footnotes_h2() {
+    let input = lines_vec!("## Notes", " 1. First");
+    let expected = lines_vec!("## Notes", " [^1]: First");
+    assert_eq!(convert_footnotes(&input), expected);
+}
+
+#[test]
+fn test_skips_when_existing_block_is_indented_or_quoted() {
+    let input1 = lines_vec!("  [^1]: Old", "## Footnotes", " 2. New");
+    let input2 = lines_vec!("> [^1]: Old", "## Footnotes", " 2. New");
+    let input3 = lines_vec!(">> [^1]: Old", "## Footnotes", " 2. New");
+    assert_eq!(convert_footnotes(&input1), input1);
+    assert_eq!(convert_footnotes(&input2), input2);
+    assert_eq!(convert_footnotes(&input3), input3);
+}
+
+#[test]
+fn test_converts_after_inline_reference_at_bol() {
+    let input = lines_vec!("[^1] see note", "## Footnotes", " 1. First");
+    let expected = lines_vec!("[^1] see note", "## Footnotes", " [^1]: First");
+    assert_eq!(convert_footnotes(&input), expected);
+}
+
+#[test]
+fn test_ignores_definition_inside_fence() {
+    let input = lines_vec!("```", "[^1]: Old", "```", "## Footnotes", " 1. First",);
+    let expected = lines_vec!("```", "[^1]: Old", "```", "## Footnotes", " [^1]: First",);
+    assert_eq!(convert_footnotes(&input), expected);
+}
+
+#[test]
+fn test_skips_numeric_list_not_last_without_heading() {
+    let input = lines_vec!(
+        "Reference.[^2]",
+        "1. First footnote",
+        "2. Second footnote",
+        "",
+        "Tail.",
+    );
+    assert_eq!(convert_footnotes(&input), input);
+}
+
+#[test]
+fn test_preserves_numeric_list_without_references() {
+    let input = lines_vec!("Ordinary list:", "1. Apples", "2. Bananas",);
+    assert_eq!(convert_footnotes(&input), input);
+}
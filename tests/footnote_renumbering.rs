@@ -0,0 +1,110 @@
+//! End-to-end tests for renumbering and placing converted footnotes.
+//!
+//! References are renumbered in order of first use, definitions are reordered
+//! to match, and `FootnotePlacement::Section` moves each definition to the end
+//! of the section citing it.
+
+use mdtablefix::{
+    convert_footnotes,
+    footnotes::{FootnotePlacement, convert_footnotes_with},
+};
+
+#[macro_use]
+#[path = "common/mod.rs"]
+mod common;
+
+#[test]
+fn test_renumbers_numeric_list_without_heading() {
+    let input = lines_vec!(
+        "First reference.[^7]",
+        "Second reference.[^3]",
+        "",
+        "1. Legacy footnote",
+        "3. Third footnote",
+        "7. Seventh footnote",
+    );
+    let expected = lines_vec!(
+        "First reference.[^1]",
+        "Second reference.[^2]",
+        "",
+        "[^1]: Seventh footnote",
+        "[^2]: Third footnote",
+        "[^3]: Legacy footnote",
+    );
+    assert_eq!(convert_footnotes(&input), expected);
+}
+
+#[test]
+fn test_renumbers_numeric_list_with_wrapped_items_and_duplicates_without_heading() {
+    let input = lines_vec!(
+        "First ref.[^7] and again [^7]",
+        "",
+        "1. Legacy footnote",
+        "3. Third footnote wraps",
+        "   over two lines.",
+        "7. Seventh footnote",
+    );
+    let expected = lines_vec!(
+        "First ref.[^1] and again [^1]",
+        "",
+        "[^1]: Seventh footnote",
+        "[^2]: Third footnote wraps",
+        "   over two lines.",
+        "[^3]: Legacy footnote",
+    );
+    assert_eq!(convert_footnotes(&input), expected);
+}
+
+#[test]
+fn test_renumbers_reference_followed_by_colons() {
+    let input = lines_vec!(
+        "Usage.[^7]:: extra context",
+        "",
+        "## Footnotes",
+        "7. Footnote text",
+    );
+    let expected = lines_vec!(
+        "Usage.[^1]:: extra context",
+        "",
+        "## Footnotes",
+        "[^1]: Footnote text",
+    );
+    assert_eq!(convert_footnotes(&input), expected);
+}
+
+#[test]
+fn test_places_converted_footnotes_per_section() {
+    let input = lines_vec!(
+        "## Intro",
+        "",
+        "See this.1",
+        "",
+        "## Usage",
+        "",
+        "And that.2",
+        "",
+        "## Footnotes",
+        "",
+        "1. First",
+        "2. Second",
+    );
+    let expected = lines_vec!(
+        "## Intro",
+        "",
+        "See this.[^1]",
+        "",
+        "[^1]: First",
+        "",
+        "## Usage",
+        "",
+        "And that.[^1]",
+        "",
+        "[^1]: Second",
+        "",
+        "## Footnotes",
+    );
+    assert_eq!(
+        convert_footnotes_with(&input, FootnotePlacement::Section),
+        expected
+    );
+}
@@ -0,0 +1,56 @@
+//! Integration tests for the `--normalize-hard-breaks` flag.
+//!
+//! Verifies that every break style converts to the chosen one outside code,
+//! and that `--wrap` keeps each break's marker when it reflows a paragraph.
+
+use rstest::rstest;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const MIXED: &str = "one  \ntwo\\\nthree <br/>\nfour\n\n```\ncode<br>\nmore\n```\n";
+
+const LONG: &str =
+    "This is a long line of prose that definitely needs wrapping once it passes eighty columns";
+
+#[rstest]
+#[case("spaces", "one  \ntwo  \nthree  \nfour\n")]
+#[case("backslash", "one\\\ntwo\\\nthree\\\nfour\n")]
+#[case("html", "one<br>\ntwo<br>\nthree<br>\nfour\n")]
+fn cli_converts_every_break(
+    #[case] style: &str,
+    #[case] prose: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let expected = format!("{prose}\n```\ncode<br>\nmore\n```\n");
+    run_cli_with_stdin(&["--normalize-hard-breaks", style], MIXED)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[rstest]
+#[case::kept(&["--wrap"], "<br>", "<br>")]
+#[case::kept_spaces(&["--wrap"], "  ", "  ")]
+#[case::converted(&["--wrap", "--normalize-hard-breaks", "backslash"], "<br>", "\\")]
+fn wrapping_keeps_the_break_style(
+    #[case] args: &[&str],
+    #[case] marker: &str,
+    #[case] expected_marker: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let input = format!("{LONG}{marker}\nnext line\n");
+    let expected = format!(
+        "This is a long line of prose that definitely needs wrapping once it passes\neighty \
+         columns{expected_marker}\nnext line\n"
+    );
+    run_cli_with_stdin(args, &input)?.success().stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn rejects_unknown_styles() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--normalize-hard-breaks", "tabs"], "text\n")?
+        .failure()
+        .code(2);
+    Ok(())
+}
@@ -0,0 +1,39 @@
+//! Integration tests for the `hide-columns` table pragma.
+
+use mdtablefix::{Options, process_stream_opts};
+use rstest::rstest;
+
+#[macro_use]
+#[path = "common/mod.rs"]
+mod common;
+
+#[rstest]
+#[case::default(Options::default())]
+#[case::wrap(Options { wrap: true, ..Options::default() })]
+fn hides_columns_once(#[case] opts: Options) {
+    let input = lines_vec![
+        "<!-- mdtablefix: hide-columns Internal Notes -->",
+        "|Name|Internal Notes|Status|",
+        "|---|---|---|",
+        "|a|secret|ok|",
+        "",
+        "After.",
+    ];
+    let expected = lines_vec![
+        "<!-- mdtablefix: hide-columns Internal Notes -->",
+        "| Name | Status |",
+        "| ---- | ------ |",
+        "| a    | ok     |",
+        "",
+        "<!-- mdtablefix: hidden-columns",
+        "| Internal Notes |",
+        "| -------------- |",
+        "| secret         |",
+        "-->",
+        "",
+        "After.",
+    ];
+    let output = process_stream_opts(&input, opts.clone());
+    assert_eq!(output, expected);
+    assert_eq!(process_stream_opts(&output, opts), expected);
+}
@@ -0,0 +1,98 @@
+//! Tests for keeping block-level HTML opaque to Markdown transforms.
+
+use mdtablefix::{
+    Options,
+    html_blocks::{html_block_ranges, outside_html_blocks},
+    process_stream_opts,
+};
+use rstest::rstest;
+
+#[macro_use]
+mod common;
+
+#[rstest]
+#[case::wrapping(Options { wrap: true, ..Default::default() })]
+#[case::ellipsis(Options { ellipsis: true, ..Default::default() })]
+#[case::footnotes(Options { footnotes: true, ..Default::default() })]
+fn html_blocks_pass_through_unchanged(#[case] opts: Options) {
+    let input = lines_vec![
+        "<div class=\"grid\">",
+        "  <p>A long paragraph of text inside a div that is written well past the wrap width... \
+         See 1.</p>",
+        "</div>",
+        "",
+        "Text after the block.",
+    ];
+    assert_eq!(process_stream_opts(&input, opts), input);
+}
+
+#[test]
+fn markdown_around_html_blocks_is_still_formatted() {
+    let input = lines_vec![
+        "Before...",
+        "",
+        "<section>",
+        "Inside...",
+        "</section>",
+        "",
+        "After...",
+    ];
+    let opts = Options {
+        ellipsis: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        process_stream_opts(&input, opts),
+        lines_vec![
+            "Before…",
+            "",
+            "<section>",
+            "Inside...",
+            "</section>",
+            "",
+            "After…",
+        ]
+    );
+}
+
+#[cfg(feature = "html")]
+#[test]
+fn html_tables_are_still_converted() {
+    let input = lines_vec![
+        "<table>",
+        "<tr><th>A</th></tr>",
+        "<tr><td>1</td></tr>",
+        "</table>"
+    ];
+    let output = process_stream_opts(&input, Options::default());
+    assert_eq!(output, lines_vec!["| A   |", "| --- |", "| 1   |"]);
+}
+
+#[cfg(not(feature = "html"))]
+#[test]
+fn html_tables_are_left_as_written_without_the_html_feature() {
+    let input = lines_vec![
+        "<table>",
+        "<tr><th>A</th></tr>",
+        "<tr><td>1</td></tr>",
+        "</table>"
+    ];
+    assert_eq!(process_stream_opts(&input, Options::default()), input);
+}
+
+#[test]
+fn fenced_html_is_not_a_block() {
+    let input = lines_vec!["```html", "<div>", "```"];
+    assert!(html_block_ranges(&input).is_empty());
+}
+
+#[test]
+fn outside_html_blocks_restores_block_lines() {
+    let input = lines_vec!["keep", "", "<pre>", "keep", "</pre>"];
+    let output = outside_html_blocks(&input, |doc| {
+        doc.iter()
+            .map(|line| line.replace("keep", "kept"))
+            .collect()
+    });
+    assert_eq!(output, lines_vec!["kept", "", "<pre>", "keep", "</pre>"]);
+}
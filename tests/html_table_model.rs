@@ -0,0 +1,84 @@
+//! Tests for the typed HTML table model in `mdtablefix::html`.
+//!
+//! Runs only with the `html` feature.
+#![cfg(feature = "html")]
+
+use mdtablefix::{
+    convert_html_tables,
+    html::{Cell, ColumnWidth, parse_html_tables, render_markdown},
+};
+
+const INVOICE: &str = "<table>
+<colgroup><col width=\"20%\"><col><col width=\"2*\"></colgroup>
+<tfoot><tr><td>Total</td><td></td><td>3</td></tr></tfoot>
+<thead><tr><th>Item</th><th>SKU</th><th>Qty</th></tr></thead>
+<tbody><tr><th>Pens</th><td>P-1</td><td><b>3</b></td></tr></tbody>
+</table>";
+
+#[test]
+fn parses_sections_cells_and_width_hints() {
+    let tables = parse_html_tables(INVOICE);
+    assert_eq!(tables.len(), 1);
+    let table = &tables[0];
+
+    assert_eq!(table.head_rows, 1);
+    let texts: Vec<Vec<&str>> = table
+        .rows
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.text.as_str()).collect())
+        .collect();
+    assert_eq!(
+        texts,
+        [
+            ["Item", "SKU", "Qty"],
+            ["Pens", "P-1", "3"],
+            ["Total", "", "3"],
+        ]
+    );
+    assert!(table.rows[1][0].heading);
+    assert!(table.rows[1][2].header_like && !table.rows[1][2].heading);
+    assert_eq!(
+        table.widths,
+        [
+            Some(ColumnWidth::Percent(20)),
+            None,
+            Some(ColumnWidth::Relative(2)),
+        ]
+    );
+}
+
+#[test]
+fn unedited_tables_render_as_conversion_does() {
+    let tables = parse_html_tables(INVOICE);
+    let lines: Vec<String> = INVOICE.lines().map(str::to_string).collect();
+    assert_eq!(render_markdown(&tables[0]), convert_html_tables(&lines));
+}
+
+#[test]
+fn edits_are_rendered() {
+    let mut tables = parse_html_tables(INVOICE);
+    let table = &mut tables[0];
+    table.remove_column(1);
+    table.rows[2][0] = Cell::new("Sum");
+
+    assert_eq!(
+        render_markdown(table),
+        [
+            "| Item     | Qty       |",
+            "| -------- | --------- |",
+            "| **Pens** | 3         |",
+            "| Sum      | 3         |",
+        ]
+    );
+}
+
+#[test]
+fn finds_each_outermost_table() {
+    let tables = parse_html_tables(
+        "<p>intro</p><table><tr><td>a<table><tr><td>inner</td></tr></table></td></tr></\
+         table><table><tr><td>b</td></tr></table>",
+    );
+    assert_eq!(tables.len(), 2);
+    assert_eq!(tables[1].rows[0][0].text, "b");
+    assert!(parse_html_tables("<p>no tables</p>").is_empty());
+}
@@ -0,0 +1,46 @@
+//! Golden tests for HTML table conversion.
+//!
+//! Every `NAME_input.html` file in `tests/data/html_tables` is converted with
+//! `convert_html_tables` and compared with `NAME_expected.txt`, so adding a
+//! fixture pair adds a case. Runs only with the `html` feature.
+#![cfg(feature = "html")]
+
+use std::{fs, path::Path};
+
+use mdtablefix::convert_html_tables;
+
+#[test]
+fn converts_the_html_table_corpus() -> Result<(), Box<dyn std::error::Error>> {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/html_tables");
+    let mut cases = 0;
+    for entry in fs::read_dir(&corpus)? {
+        let input = entry?.path();
+        let Some(name) = input
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_suffix("_input.html"))
+        else {
+            continue;
+        };
+        let source: Vec<String> = fs::read_to_string(&input)?
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let expected = fs::read_to_string(corpus.join(format!("{name}_expected.txt")))?;
+        let output = convert_html_tables(&source);
+        assert_eq!(
+            output,
+            expected.lines().collect::<Vec<_>>(),
+            "{}",
+            input.display()
+        );
+        assert_eq!(
+            convert_html_tables(&output),
+            output,
+            "{} is not stable",
+            input.display()
+        );
+        cases += 1;
+    }
+    assert!(cases > 0, "no fixtures found in {}", corpus.display());
+    Ok(())
+}
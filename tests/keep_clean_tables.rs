@@ -0,0 +1,41 @@
+//! Integration tests for the `--keep-clean-tables` flag.
+//!
+//! Verifies that tables whose columns already line up keep their padding,
+//! while misaligned tables in the same document are still reflowed.
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const INPUT: &str = "|  Name  |  Value  |\n|:------:|---------|\n|   a    |  1      |\n\n| x | yy \
+                     |\n|---|---|\n| 1 | 2 |\n";
+
+#[test]
+fn cli_keeps_clean_tables() -> Result<(), Box<dyn std::error::Error>> {
+    let expected = concat!(
+        "|  Name  |  Value  |\n",
+        "|:------:|---------|\n",
+        "|   a    |  1      |\n\n",
+        "| x   | yy  |\n",
+        "| --- | --- |\n",
+        "| 1   | 2   |\n",
+    );
+    run_cli_with_stdin(&["--keep-clean-tables"], INPUT)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn cli_reflows_clean_tables_without_flag() -> Result<(), Box<dyn std::error::Error>> {
+    let expected = concat!(
+        "| Name | Value |\n",
+        "| :--: | ----- |\n",
+        "| a    | 1     |\n\n",
+        "| x   | yy  |\n",
+        "| --- | --- |\n",
+        "| 1   | 2   |\n",
+    );
+    run_cli_with_stdin(&[], INPUT)?.success().stdout(expected);
+    Ok(())
+}
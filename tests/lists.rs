@@ -1,7 +1,10 @@
 //! Integration tests for list renumbering.
 
 use assert_cmd::Command;
-use mdtablefix::renumber_lists;
+use mdtablefix::{
+    lists::{ListContinuation, renumber_lists_with},
+    renumber_lists,
+};
 use rstest::rstest;
 
 #[macro_use]
@@ -63,6 +66,67 @@ fn reset_on_heading_and_thematic_break() {
     let expected = lines_vec!("1. a", "2. b", "# Heading", "1. c", "---", "1. d");
     assert_eq!(renumber_lists(&input), expected);
 }
+
+#[rstest]
+#[case::quoted_paragraph("\"Quoted\" aside.")]
+#[case::parenthesised_paragraph("(An aside.)")]
+#[case::bullet_list("- bullet")]
+#[case::blockquote("> quote")]
+fn restart_after_any_separating_block(#[case] separator: &str) {
+    let input = lines_vec!("1. a", "2. b", "", separator, "", "3. c");
+    let expected = lines_vec!("1. a", "2. b", "", separator, "", "1. c");
+    assert_eq!(renumber_lists(&input), expected);
+}
+
+#[rstest]
+#[case::bullet_list("- bullet")]
+#[case::blockquote("> quote")]
+fn restart_after_interrupting_block(#[case] separator: &str) {
+    let input = lines_vec!("1. a", separator, "3. b");
+    let expected = lines_vec!("1. a", separator, "1. b");
+    assert_eq!(renumber_lists(&input), expected);
+}
+
+#[test]
+fn nested_bullet_does_not_end_list() {
+    let input = lines_vec!("1. a", "   - nested", "3. b");
+    let expected = lines_vec!("1. a", "   - nested", "2. b");
+    assert_eq!(renumber_lists(&input), expected);
+}
+
+#[test]
+fn continue_numbering_across_paragraphs() {
+    let input = lines_vec!(
+        "1. a",
+        "",
+        "Aside.",
+        "",
+        "1. b",
+        "",
+        "> quote",
+        "",
+        "1. c",
+        "# Heading",
+        "4. d"
+    );
+    let expected = lines_vec!(
+        "1. a",
+        "",
+        "Aside.",
+        "",
+        "2. b",
+        "",
+        "> quote",
+        "",
+        "3. c",
+        "# Heading",
+        "1. d"
+    );
+    assert_eq!(
+        renumber_lists_with(&input, ListContinuation::Continue),
+        expected
+    );
+}
 /// Tests the CLI `--renumber` option.
 ///
 /// Ensures that list numbering is corrected when the flag is supplied.
@@ -77,6 +141,27 @@ fn test_cli_renumber_option() {
         .stdout("1. a\n2. b\n");
 }
 
+#[test]
+fn test_cli_continue_numbering_option() {
+    Command::cargo_bin("mdtablefix")
+        .expect("Failed to create cargo command for mdtablefix")
+        .args(["--renumber", "--continue-numbering"])
+        .write_stdin("1. a\n\nAside.\n\n1. b\n")
+        .assert()
+        .success()
+        .stdout("1. a\n\nAside.\n\n2. b\n");
+}
+
+#[test]
+fn test_cli_continue_numbering_requires_renumber() {
+    Command::cargo_bin("mdtablefix")
+        .expect("Failed to create cargo command for mdtablefix")
+        .arg("--continue-numbering")
+        .write_stdin("1. a\n")
+        .assert()
+        .failure();
+}
+
 #[test]
 fn nested_lists_respect_fence_tracker() {
     let input = lines_vec![
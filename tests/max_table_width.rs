@@ -0,0 +1,105 @@
+//! Integration tests for `--max-table-width` and `Options::max_table_width`.
+
+use mdtablefix::{Options, process_stream_opts, table::TableOverflow};
+use rstest::rstest;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const INPUT: &str = "|id|summary|details|\n|-|-|-|\n|1|short|a considerably longer \
+                     description|\n|2|a medium length summary|ok|\n";
+
+#[rstest]
+#[case::natural(
+    &[],
+    "| id  | summary                 | details                           |\n\
+     | --- | ----------------------- | --------------------------------- |\n\
+     | 1   | short                   | a considerably longer description |\n\
+     | 2   | a medium length summary | ok                                |\n"
+)]
+#[case::cells_too_wide(
+    &["--max-table-width", "60"],
+    "| id  | summary                 | details                           |\n\
+     | --- | ----------------------- | --------------------------------- |\n\
+     | 1   | short                   | a considerably longer description |\n\
+     | 2   | a medium length summary | ok                                |\n"
+)]
+#[case::edges_only_unaffected(
+    &["--max-table-width", "20", "--table-style", "edges-only"],
+    "| id | summary | details                        |\n\
+     | --- | --- | --------------------------------- |\n\
+     | 1 | short | a considerably longer description |\n\
+     | 2 | a medium length summary | ok              |\n"
+)]
+fn cli_balances_wide_tables(
+    #[case] args: &[&str],
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(args, INPUT)?
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn over_wide_rows_stay_aligned() {
+    let lines: Vec<String> = INPUT.lines().map(str::to_string).collect();
+    let opts = Options {
+        max_table_width: Some(60),
+        ..Options::default()
+    };
+    let out = process_stream_opts(&lines, opts);
+    assert!(out.iter().all(|row| row.len() == 69), "{out:#?}");
+}
+
+#[test]
+fn keep_clean_tables_reflows_tables_over_the_limit() -> Result<(), Box<dyn std::error::Error>> {
+    let clean = "| a   | bee                  |\n| --- | -------------------- |\n";
+    run_cli_with_stdin(&["--keep-clean-tables"], clean)?
+        .success()
+        .stdout(clean);
+    run_cli_with_stdin(&["--keep-clean-tables", "--max-table-width", "20"], clean)?
+        .success()
+        .stdout("| a   | bee |\n| --- | --- |\n");
+    Ok(())
+}
+
+const NATURAL: &str = "| id  | summary                 | details                           |\n| \
+                       --- | ----------------------- | --------------------------------- |\n| 1   \
+                       | short                   | a considerably longer description |\n| 2   | a \
+                       medium length summary | ok                                |\n";
+
+#[test]
+fn fence_policy_moves_over_wide_tables_into_a_code_block() -> Result<(), Box<dyn std::error::Error>>
+{
+    run_cli_with_stdin(
+        &["--max-table-width", "40", "--table-overflow", "fence"],
+        INPUT,
+    )?
+    .success()
+    .stdout(format!("```text\n{NATURAL}```\n"))
+    .stderr(predicates::str::contains("exceeds the maximum table width"));
+    Ok(())
+}
+
+#[rstest]
+#[case::fits(100, "| id  |")]
+#[case::overflows(40, "<div style=\"overflow-x: auto\">")]
+fn scroll_policy_wraps_only_over_wide_tables(#[case] max_width: usize, #[case] first: &str) {
+    let lines: Vec<String> = INPUT.lines().map(str::to_string).collect();
+    let opts = || Options {
+        max_table_width: Some(max_width),
+        table_overflow: TableOverflow::Scroll,
+        ..Options::default()
+    };
+    let out = process_stream_opts(&lines, opts());
+    assert!(out[0].starts_with(first), "unexpected output: {out:?}");
+    assert_eq!(process_stream_opts(&out, opts()), out);
+}
+
+#[test]
+fn table_overflow_requires_a_maximum_width() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--table-overflow", "fence"], INPUT)?.code(2);
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! CLI tests for `--only-section`.
+
+use assert_cmd::Command;
+use tempfile::tempdir;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const INPUT: &str = "# Guide\n\n|a|b|\n\n## API Reference\n\n|c|d|\n\n### Details\n\n|e|f|\n\n## \
+                     Changelog\n\n|g|h|\n";
+
+#[test]
+fn only_the_named_section_is_formatted() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--only-section", "## API Reference"], INPUT)?
+        .success()
+        .stdout(
+            "# Guide\n\n|a|b|\n\n## API Reference\n\n| c | d |\n\n### Details\n\n| e | f |\n\n## \
+             Changelog\n\n|g|h|\n",
+        );
+    Ok(())
+}
+
+#[test]
+fn missing_section_leaves_the_document_alone() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--only-section", "## Missing"], INPUT)?
+        .success()
+        .stdout(INPUT);
+    Ok(())
+}
+
+#[test]
+fn report_lines_point_into_the_whole_document() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let file = dir.path().join("doc.md");
+    let report = dir.path().join("report.json");
+    std::fs::write(&file, format!("---\ntitle: x\n---\n{INPUT}"))?;
+    Command::cargo_bin("mdtablefix")?
+        .args(["--only-section", "Details", "--report-file"])
+        .arg(&report)
+        .arg(&file)
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(report)?)?;
+    let changes = &json["files"][0]["changes"];
+    assert_eq!(changes.as_array().map(Vec::len), Some(1), "{json}");
+    assert_eq!(changes[0]["before"]["start"], 14, "{json}");
+    Ok(())
+}
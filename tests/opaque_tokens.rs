@@ -0,0 +1,82 @@
+//! Tests for template placeholders kept whole by wrapping and table parsing.
+
+use std::fs;
+
+use assert_cmd::Command;
+use mdtablefix::{Options, opaque::OpaqueTokens, process_stream_opts};
+use predicates::prelude::*;
+use rstest::rstest;
+use tempfile::tempdir;
+
+#[macro_use]
+mod common;
+
+fn template_opts(wrap: bool) -> Options {
+    Options {
+        wrap,
+        wrap_width: 30,
+        opaque_tokens: OpaqueTokens::new([r"\{\{.*?\}\}", r"<%=?.*?%>", r"\$\{[^}]*\}"])
+            .expect("patterns compile"),
+        ..Options::default()
+    }
+}
+
+#[rstest]
+#[case::jinja("Dear {{ customer.full_name | title }}, thanks.")]
+#[case::erb("Your total is <%= number_to_currency(total) %> today.")]
+#[case::shell("Install into ${INSTALL_PREFIX_DIRECTORY}/bin now.")]
+fn wrapping_never_breaks_inside_a_placeholder(#[case] line: &str) {
+    let input = lines_vec![line];
+    let output = process_stream_opts(&input, template_opts(true));
+    assert!(output.len() > 1, "line should wrap: {output:?}");
+    assert_eq!(output.join(" "), line);
+    let placeholder = regex::Regex::new(r"\{\{.*?\}\}|<%=?.*?%>|\$\{[^}]*\}").unwrap();
+    let token = placeholder.find(line).unwrap().as_str();
+    assert!(output.iter().any(|out| out.contains(token)), "{output:?}");
+}
+
+#[test]
+fn pipes_inside_placeholders_do_not_split_cells() {
+    let input = lines_vec!["|Name|Value|", "|-|-|", "|user|{{ name | upper }}|"];
+    assert_eq!(
+        process_stream_opts(&input, template_opts(false)),
+        lines_vec![
+            "| Name | Value              |",
+            "| ---- | ------------------ |",
+            "| user | {{ name | upper }} |",
+        ]
+    );
+}
+
+#[test]
+fn config_patterns_apply_to_the_cli() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join(".mdtablefix.toml"),
+        "opaque-tokens = ['\\{\\{.*?\\}\\}']\n",
+    )?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .args(["--wrap", "--wrap-width", "20"])
+        .write_stdin("Hello there {{ user.first_name }}, welcome.\n")
+        .assert()
+        .success()
+        .stdout("Hello there\n{{ user.first_name }},\nwelcome.\n");
+    Ok(())
+}
+
+#[test]
+fn invalid_patterns_are_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    fs::write(
+        dir.path().join(".mdtablefix.toml"),
+        "opaque-tokens = ['{{(']\n",
+    )?;
+    Command::cargo_bin("mdtablefix")?
+        .current_dir(dir.path())
+        .write_stdin("text\n")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("`opaque-tokens`"));
+    Ok(())
+}
@@ -19,7 +19,7 @@ use fixtures::broken_table;
 
 #[rstest]
 fn test_cli_parallel_empty_file_list() -> Result<(), Box<dyn std::error::Error>> {
-    run_cli_with_args(&[])?.success().stdout("\n");
+    run_cli_with_args(&[])?.success().stdout("");
     Ok(())
 }
 
@@ -101,3 +101,71 @@ fn test_cli_parallel_missing_file_in_place(
         .stderr(predicates::str::contains("missing.md"));
     Ok(())
 }
+
+#[rstest]
+#[case::flag(&["--jobs", "1"], None)]
+#[case::short_flag(&["-j", "3"], None)]
+#[case::env(&[], Some("2"))]
+fn test_cli_jobs_preserve_output_order(
+    #[case] flags: &[&str],
+    #[case] env_jobs: Option<&str>,
+    broken_table: Vec<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let mut cmd = Command::cargo_bin("mdtablefix")?;
+    cmd.args(flags);
+    if let Some(jobs) = env_jobs {
+        cmd.env("MDTABLEFIX_JOBS", jobs);
+    }
+    let mut expected = Vec::new();
+    for i in 0..5 {
+        let path = dir.path().join(format!("file{i}.md"));
+        std::fs::write(&path, format!("# File {i}\n{}\n", broken_table.join("\n")))?;
+        expected.push(format!("# File {i}\n| A | B |\n| 1 | 2 |\n| 3 | 4 |\n"));
+        cmd.arg(path);
+    }
+    cmd.assert().success().stdout(expected.concat());
+    Ok(())
+}
+
+#[test]
+fn test_cli_single_job_logs_in_order() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let mut cmd = Command::cargo_bin("mdtablefix")?;
+    cmd.args(["--jobs", "1", "-v"]);
+    let mut paths = Vec::new();
+    for i in 0..5 {
+        let path = dir.path().join(format!("file{i}.md"));
+        std::fs::write(&path, "|a|b|\n")?;
+        cmd.arg(&path);
+        paths.push(path.display().to_string());
+    }
+    let output = cmd.output()?;
+    let stderr = String::from_utf8(output.stderr)?;
+    let started: Vec<&str> = stderr
+        .lines()
+        .filter(|line| line.contains("processing file"))
+        .filter_map(|line| line.split("path=").nth(1))
+        .collect();
+    assert_eq!(started, paths);
+    Ok(())
+}
+
+#[rstest]
+#[case::flag(&["--jobs", "0"], None)]
+#[case::env(&[], Some("0"))]
+#[case::not_a_number(&["--jobs", "many"], None)]
+fn test_cli_jobs_rejects_invalid_counts(
+    #[case] flags: &[&str],
+    #[case] env_jobs: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("mdtablefix")?;
+    cmd.args(flags).arg("unused.md");
+    if let Some(jobs) = env_jobs {
+        cmd.env("MDTABLEFIX_JOBS", jobs);
+    }
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("--jobs"));
+    Ok(())
+}
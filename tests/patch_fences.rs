@@ -0,0 +1,130 @@
+//! Regression tests for `diff` and `patch` fences nested in lists and
+//! blockquotes.
+//!
+//! Patch lines begin with `+`, `-`, or a space, so they resemble list items,
+//! thematic breaks, and indented prose. Every transform that re-indents or
+//! rewrites list and blockquote content must leave fenced patch bodies
+//! byte-for-byte intact.
+
+use std::borrow::Cow;
+
+use mdtablefix::{Options, fences::FenceState, format_breaks, process_stream_opts, renumber_lists};
+use rstest::rstest;
+
+#[macro_use]
+#[path = "common/mod.rs"]
+mod common;
+
+#[path = "support/cli_args.rs"]
+mod cli_args;
+use cli_args::run_cli_with_args;
+
+/// Collect the content lines of every fenced block, excluding delimiters.
+fn fenced_bodies(lines: &[String]) -> Vec<Vec<String>> {
+    let mut tracker = FenceState::new();
+    let mut bodies: Vec<Vec<String>> = Vec::new();
+    let mut was_in_fence = false;
+    for line in lines {
+        if tracker.observe_line(line) {
+            if !was_in_fence {
+                bodies.push(Vec::new());
+            }
+            was_in_fence = tracker.in_fence_for_line(line);
+            continue;
+        }
+        was_in_fence = tracker.in_fence_for_line(line);
+        if was_in_fence && let Some(body) = bodies.last_mut() {
+            body.push(line.clone());
+        }
+    }
+    bodies
+}
+
+fn options_from_mask(mask: u8) -> Options {
+    Options {
+        wrap: mask & 1 != 0,
+        ellipsis: mask & 2 != 0,
+        fences: mask & 4 != 0,
+        footnotes: mask & 8 != 0,
+        code_emphasis: mask & 16 != 0,
+        headings: mask & 32 != 0,
+        code_spans: mask & 64 != 0,
+        // Both importers only rewrite foreign table syntax, so they share a bit.
+        wiki_tables: mask & 128 != 0,
+        rst_tables: mask & 128 != 0,
+        ..Options::default()
+    }
+}
+
+#[test]
+fn fixture_contains_nested_patches() {
+    let input: Vec<String> = include_lines!("data/patch_fences_input.txt");
+    assert_eq!(fenced_bodies(&input).len(), 5);
+}
+
+#[test]
+fn patch_bodies_survive_every_option_combination() {
+    let input: Vec<String> = include_lines!("data/patch_fences_input.txt");
+    let expected = fenced_bodies(&input);
+    for mask in 0..=u8::MAX {
+        let opts = options_from_mask(mask);
+        let processed = process_stream_opts(&input, opts);
+        let renumbered = renumber_lists(&processed);
+        let broken: Vec<String> = format_breaks(&renumbered)
+            .into_iter()
+            .map(Cow::into_owned)
+            .collect();
+        for (stage, output) in [
+            ("pipeline", &processed),
+            ("renumber", &renumbered),
+            ("breaks", &broken),
+        ] {
+            assert_eq!(
+                fenced_bodies(output),
+                expected,
+                "{stage} altered patch content with option mask {mask:#010b}"
+            );
+        }
+    }
+}
+
+#[rstest]
+#[case::footnotes_in_list("1. x\n\n   ```diff\n   -See the docs.1\n   ```\n")]
+#[case::footnotes_top_level("```patch\n+See the docs.1\n```\n")]
+#[case::footnotes_in_quote("> ```diff\n> -See the docs.1\n> ```\n")]
+fn footnotes_leave_patch_numbers_alone(#[case] input: &str) {
+    let lines: Vec<String> = input.lines().map(str::to_owned).collect();
+    let opts = Options {
+        footnotes: true,
+        ..Options::default()
+    };
+    assert_eq!(process_stream_opts(&lines, opts), lines);
+}
+
+#[test]
+fn cli_all_flags_preserve_patch_fixture() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/patch_fences_input.txt"
+    );
+    run_cli_with_args(&[
+        "--wrap",
+        "--renumber",
+        "--breaks",
+        "--ellipsis",
+        "--fences",
+        "--footnotes",
+        "--code-emphasis",
+        "--headings",
+        "--emphasis-headings",
+        "2",
+        "--code-spans",
+        "--wiki-tables",
+        "--import",
+        "rst-tables",
+        path,
+    ])
+    .expect("failed to run mdtablefix")
+    .success()
+    .stdout(include_str!("data/patch_fences_expected.txt"));
+}
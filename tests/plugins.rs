@@ -0,0 +1,163 @@
+//! Integration tests for WASM plugins and the `--plugin` flag.
+//!
+//! Runs only with the `wasm-plugins` feature. Plugins are written in the
+//! WebAssembly text format and compiled with `wat` at test time.
+#![cfg(feature = "wasm-plugins")]
+
+use std::{fs, path::PathBuf};
+
+use mdtablefix::plugins::{PluginError, WasmPlugin};
+use predicates::prelude::*;
+use rstest::rstest;
+use tempfile::TempDir;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+/// Upper-cases ASCII letters in place and returns the input buffer.
+const UPPERCASE: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 1024))
+  (func (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (local.get $ptr) (local.get $len)))
+    (drop (memory.grow (i32.add (i32.shr_u (local.get $len) (i32.const 16)) (i32.const 1))))
+    (local.get $ptr))
+  (func (export "transform") (param $ptr i32) (param $len i32) (result i64)
+    (local $i i32)
+    (local $c i32)
+    (block $done
+      (loop $scan
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+        (if (i32.and (i32.ge_u (local.get $c) (i32.const 97))
+                     (i32.le_u (local.get $c) (i32.const 122)))
+          (then (i32.store8 (i32.add (local.get $ptr) (local.get $i))
+                            (i32.sub (local.get $c) (i32.const 32)))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $scan)))
+    (i64.or (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+            (i64.extend_i32_u (local.get $len)))))
+"#;
+
+/// Asks the host to write to stdout through WASI.
+const IMPORTS_WASI: &str = r#"
+(module
+  (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) (i32.const 0))
+  (func (export "transform") (param i32 i32) (result i64) (i64.const 0)))
+"#;
+
+/// Never returns from `transform`.
+const SPINS: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) (i32.const 0))
+  (func (export "transform") (param i32 i32) (result i64) (loop $forever (br $forever)) (i64.const 0)))
+"#;
+
+/// Returns a buffer that runs past the end of memory.
+const OUT_OF_BOUNDS: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) (i32.const 0))
+  (func (export "transform") (param i32 i32) (result i64) (i64.const 0x0000fff000001000)))
+"#;
+
+fn plugin(source: &str) -> Result<WasmPlugin, PluginError> {
+    WasmPlugin::from_bytes(&wat::parse_str(source).expect("valid WAT"))
+}
+
+fn lines(items: &[&str]) -> Vec<String> { items.iter().map(ToString::to_string).collect() }
+
+/// Writes `source` as a compiled module in a temporary directory.
+fn plugin_file(source: &str) -> (TempDir, PathBuf) {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let path = dir.path().join("plugin.wasm");
+    fs::write(&path, wat::parse_str(source).expect("valid WAT")).expect("write plugin");
+    (dir, path)
+}
+
+#[rstest]
+#[case(&["hello", "", "world"], &["HELLO", "", "WORLD"])]
+#[case(&[""], &[""])]
+#[case(&[], &[])]
+fn transform_round_trips_lines(#[case] input: &[&str], #[case] expected: &[&str]) {
+    let out = plugin(UPPERCASE)
+        .expect("load plugin")
+        .transform(&lines(input))
+        .expect("run plugin");
+    assert_eq!(out, lines(expected));
+}
+
+#[test]
+fn rejects_modules_that_import_host_functions() {
+    let err = plugin(IMPORTS_WASI).expect_err("imports are refused");
+    assert!(
+        err.to_string()
+            .contains("imports `wasi_snapshot_preview1::fd_write`"),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejects_modules_without_transform_export() {
+    let err = plugin(r#"(module (memory (export "memory") 1))"#).expect_err("missing exports");
+    assert!(err.to_string().contains("missing `alloc` export"), "{err}");
+}
+
+#[rstest]
+#[case(SPINS)]
+#[case(OUT_OF_BOUNDS)]
+fn misbehaving_plugins_fail_cleanly(#[case] source: &str) {
+    let result = plugin(source)
+        .expect("load plugin")
+        .transform(&lines(&["text"]));
+    assert!(result.is_err());
+}
+
+#[test]
+fn cli_runs_plugin_after_named_stage() -> Result<(), Box<dyn std::error::Error>> {
+    let (_dir, path) = plugin_file(UPPERCASE);
+    let arg = format!("{}@tables", path.display());
+    run_cli_with_stdin(&["--plugin", &arg], "|a|b|\n|1|2|\n")?
+        .success()
+        .stdout("| A | B |\n| 1 | 2 |\n");
+    Ok(())
+}
+
+#[test]
+fn cli_reports_plugin_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let (dir, path) = plugin_file(UPPERCASE);
+    let report = dir.path().join("report.json");
+    let plugin_arg = path.display().to_string();
+    let report_arg = report.display().to_string();
+    run_cli_with_stdin(
+        &["--plugin", &plugin_arg, "--report-file", &report_arg],
+        "text\n",
+    )?
+    .success()
+    .stdout("TEXT\n");
+    assert!(fs::read_to_string(report)?.contains("\"plugin\""));
+    Ok(())
+}
+
+#[rstest]
+#[case(UPPERCASE, "@nowhere", "unknown stage `nowhere`")]
+#[case(IMPORTS_WASI, "", "plugins may not import host functions")]
+fn cli_rejects_bad_plugins(
+    #[case] source: &str,
+    #[case] suffix: &str,
+    #[case] message: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (_dir, path) = plugin_file(source);
+    let arg = format!("{}{suffix}", path.display());
+    run_cli_with_stdin(&["--plugin", &arg], "text\n")?
+        .failure()
+        .stderr(predicate::str::contains(message));
+    Ok(())
+}
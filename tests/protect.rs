@@ -0,0 +1,62 @@
+//! Integration tests for blocks protected by an `Options::protect` predicate.
+
+use mdtablefix::{Options, process_stream_opts, protect::BlockProtection};
+use rstest::rstest;
+
+#[macro_use]
+#[path = "common/mod.rs"]
+mod common;
+
+/// Protects the block after a `<!-- vendor:keep -->` pragma.
+fn after_pragma() -> BlockProtection {
+    BlockProtection::new(|block| {
+        block
+            .previous
+            .is_some_and(|previous| previous == ["<!-- vendor:keep -->"])
+    })
+}
+
+#[rstest]
+#[case::tables(
+    Options::default(),
+    lines_vec!["<!-- vendor:keep -->", "", "|a|b|", "|-|-|", "", "|c|d|", "|-|-|"],
+    lines_vec!["<!-- vendor:keep -->", "", "|a|b|", "|-|-|", "", "| c   | d   |", "| --- | --- |"]
+)]
+#[case::wrap(
+    Options { wrap: true, wrap_width: 20, ..Options::default() },
+    lines_vec![
+        "<!-- vendor:keep -->",
+        "",
+        "A line far too long to fit within twenty columns.",
+    ],
+    lines_vec![
+        "<!-- vendor:keep -->",
+        "",
+        "A line far too long to fit within twenty columns.",
+    ]
+)]
+#[case::footnotes(
+    Options { footnotes: true, ..Options::default() },
+    lines_vec!["See.[^2]", "", "<!-- vendor:keep -->", "", "Cited.[^2]", "", "[^2]: Note"],
+    lines_vec!["See.[^1]", "", "<!-- vendor:keep -->", "", "Cited.[^2]", "", "[^1]: Note"]
+)]
+fn leaves_protected_blocks_untouched(
+    #[case] opts: Options,
+    #[case] input: Vec<String>,
+    #[case] expected: Vec<String>,
+) {
+    let opts = Options {
+        protect: after_pragma(),
+        ..opts
+    };
+    assert_eq!(process_stream_opts(&input, opts), expected);
+}
+
+#[test]
+fn protects_nothing_by_default() {
+    let input = lines_vec!["<!-- vendor:keep -->", "", "|a|b|"];
+    assert_eq!(
+        process_stream_opts(&input, Options::default()),
+        lines_vec!["<!-- vendor:keep -->", "", "| a | b |"]
+    );
+}
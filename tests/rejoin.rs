@@ -0,0 +1,41 @@
+//! Integration tests for the `--rejoin` flag.
+//!
+//! Verifies that hard-wrapped paragraphs are merged through the CLI, that the
+//! merged text reflows under `--wrap`, and that other blocks are left alone.
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const HARD_WRAPPED: &str = "An old tool wrapped this para-\ngraph at a narrow\nwidth.\nIt kept \
+                            each paragraph apart.\n\n```\nkeep\nthese\n```\n";
+
+#[test]
+fn cli_rejoins_broken_lines() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--rejoin"], HARD_WRAPPED)?
+        .success()
+        .stdout(
+            "An old tool wrapped this paragraph at a narrow width.\nIt kept each paragraph \
+             apart.\n\n```\nkeep\nthese\n```\n",
+        );
+    Ok(())
+}
+
+#[test]
+fn cli_rejoins_before_wrapping() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--rejoin", "--wrap", "--wrap-width", "30"], HARD_WRAPPED)?
+        .success()
+        .stdout(
+            "An old tool wrapped this\nparagraph at a narrow width.\nIt kept each paragraph \
+             apart.\n\n```\nkeep\nthese\n```\n",
+        );
+    Ok(())
+}
+
+#[test]
+fn cli_leaves_lines_without_flag() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&[], HARD_WRAPPED)?
+        .success()
+        .stdout(HARD_WRAPPED);
+    Ok(())
+}
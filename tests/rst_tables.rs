@@ -0,0 +1,53 @@
+//! Integration tests for `--import rst-tables`.
+//!
+//! Verifies that RST grid and simple tables become Markdown tables and that
+//! tables with spans are kept and reported on stderr.
+
+use predicates::prelude::*;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+#[test]
+fn cli_converts_rst_tables() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "+------+-------+\n| Flag | Use   |\n+======+=======+\n| wrap | prose \
+                 |\n+------+-------+\n\n====  ====\nA     B\n====  ====\n1     2\n====  ====\n";
+    let expected = "| Flag | Use   |\n| ---- | ----- |\n| wrap | prose |\n\n| A   | B   |\n| --- \
+                    | --- |\n| 1   | 2   |\n";
+    run_cli_with_stdin(&["--import", "rst-tables"], input)?
+        .success()
+        .stdout(expected)
+        .stderr("");
+    Ok(())
+}
+
+#[test]
+fn cli_reports_spanning_rst_tables() -> Result<(), Box<dyn std::error::Error>> {
+    let input =
+        "---\ntitle: x\n---\nIntro\n\n+---+---+\n| a | b |\n+---+   +\n| c |   |\n+---+---+\n";
+    run_cli_with_stdin(&["--import", "rst-tables"], input)?
+        .success()
+        .stdout(predicate::str::contains("+---+   +"))
+        .stderr(predicate::str::contains(
+            "<stdin>:6: RST table left unchanged because pipe tables cannot express row spans",
+        ));
+    Ok(())
+}
+
+#[test]
+fn cli_rejects_unknown_import_format() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--import", "latex"], "")?
+        .failure()
+        .stderr(predicate::str::contains("rst-tables"));
+    Ok(())
+}
+
+#[test]
+fn cli_keeps_grid_tables_intact_without_import() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "+---+---+\n| a | b |\n+---+   +\n| c |   |\n+---+---+\n";
+    run_cli_with_stdin(&["--wrap"], input)?
+        .success()
+        .stdout(input);
+    Ok(())
+}
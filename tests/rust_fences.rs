@@ -0,0 +1,41 @@
+//! Integration tests for the `--check-rust-fences` flag.
+//!
+//! Runs only with the `check-rust-fences` feature, because the flag does not
+//! exist otherwise.
+#![cfg(feature = "check-rust-fences")]
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+use predicates::prelude::*;
+
+#[test]
+fn cli_reports_broken_examples_with_lines_and_ids() -> Result<(), Box<dyn std::error::Error>> {
+    let input =
+        "# Demo\n\n```rust\nlet total: u32 = \"1\";\n```\n\n> ```rust\n> fn main() {\n>     \
+         println!(\"{}\", missing);\n> }\n> ```\n\n```rust,ignore\nnot rust\n```\n";
+    run_cli_with_stdin(&["--check-rust-fences"], input)?
+        .failure()
+        .stdout("")
+        .stderr(
+            predicate::str::contains("<stdin>:4: fence #1: mismatched types")
+                .and(predicate::str::contains(
+                    "<stdin>:9: fence #2: cannot find value `missing`",
+                ))
+                .and(predicate::str::contains(
+                    "2 Rust example(s) failed to compile",
+                )),
+        );
+    Ok(())
+}
+
+#[test]
+fn cli_accepts_working_examples() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "```rust\n# use std::fmt::Write;\nlet mut s = String::new();\nwrite!(s, \
+                 \"ok\").expect(\"write\");\n```\n";
+    run_cli_with_stdin(&["--check-rust-fences"], input)?
+        .success()
+        .stdout("")
+        .stderr("");
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! Integration tests for the `--sentence-spacing` flag.
+//!
+//! Verifies both spacing styles through the CLI, that code and tables are left
+//! alone, and that wrapping never turns a sentence gap into a hard break.
+
+use rstest::rstest;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const MIXED: &str = "One.  Two. Three!   `a.  b`\n\n| x.  y |\n| ----- |\n";
+
+#[rstest]
+#[case("single", "One. Two. Three! `a.  b`\n\n| x.  y |\n| ----- |\n")]
+#[case("double", "One.  Two.  Three!  `a.  b`\n\n| x.  y |\n| ----- |\n")]
+fn cli_normalizes_sentence_gaps(
+    #[case] spacing: &str,
+    #[case] expected: &'static str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--sentence-spacing", spacing], MIXED)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn wrapped_double_spacing_adds_no_hard_breaks() -> Result<(), Box<dyn std::error::Error>> {
+    let input =
+        "This is the first sentence of a paragraph. It continues with a second one. And a third.\n";
+    run_cli_with_stdin(&["--wrap", "--sentence-spacing", "double"], input)?
+        .success()
+        .stdout(
+            "This is the first sentence of a paragraph.  It continues with a second one.\nAnd a \
+             third.\n",
+        );
+    Ok(())
+}
+
+#[test]
+fn keeps_hard_break_spaces() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--sentence-spacing", "single"], "End.  \nNext.\n")?
+        .success()
+        .stdout("End.  \nNext.\n");
+    Ok(())
+}
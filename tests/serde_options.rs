@@ -0,0 +1,115 @@
+//! Tests for building [`Options`] from serialized configuration.
+
+#![cfg(feature = "serde")]
+
+use mdtablefix::{
+    Options,
+    blocks::{BlockExclusions, BlockType},
+    changes::Transform,
+    footnotes::FootnoteRefCompaction,
+    hard_breaks::HardBreakStyle,
+    opaque::OpaqueTokens,
+    process_stream_opts,
+    table::TableStyle,
+};
+use serde_json::json;
+
+#[macro_use]
+mod common;
+
+#[test]
+fn missing_fields_take_their_defaults() {
+    let opts: Options = serde_json::from_value(json!({ "ellipsis": true })).expect("valid options");
+
+    assert!(opts.ellipsis);
+    assert_eq!(opts.wrap_width, Options::default().wrap_width);
+    assert_eq!(opts.table_style, TableStyle::Padded);
+    assert!(opts.block_exclusions.is_empty());
+}
+
+#[test]
+fn policies_use_their_command_line_names() {
+    let opts: Options = serde_json::from_value(json!({
+        "table_style": "edges-only",
+        "hard_breaks": "backslash",
+        "footnote_refs": "punctuated",
+        "block_exclusions": { "ellipsis": ["footnote-definition"] },
+        "opaque_tokens": [r"\{\{.*?\}\}"],
+    }))
+    .expect("valid options");
+
+    assert_eq!(opts.table_style, TableStyle::EdgesOnly);
+    assert_eq!(opts.hard_breaks, Some(HardBreakStyle::Backslash));
+    assert_eq!(opts.footnote_refs, Some(FootnoteRefCompaction::Punctuated));
+    assert_eq!(
+        opts.block_exclusions,
+        BlockExclusions::default().with(Transform::Ellipsis, [BlockType::FootnoteDefinition])
+    );
+    assert!(!opts.opaque_tokens.is_empty());
+}
+
+#[test]
+fn serialized_options_round_trip() {
+    let opts = Options {
+        wrap: true,
+        wrap_width: 60,
+        emphasis_headings: Some(2),
+        block_exclusions: BlockExclusions::default().with(
+            Transform::CodeSpans,
+            [BlockType::Heading, BlockType::ListItem],
+        ),
+        opaque_tokens: OpaqueTokens::new([r"<%=?.*?%>"]).expect("pattern compiles"),
+        ..Options::default()
+    };
+
+    let value = serde_json::to_value(&opts).expect("options serialize");
+    assert_eq!(
+        value["block_exclusions"],
+        json!({ "code-spans": ["heading", "list-item"] })
+    );
+    assert_eq!(value["opaque_tokens"], json!([r"<%=?.*?%>"]));
+    assert!(value.get("protect").is_none());
+
+    let restored: Options = serde_json::from_value(value.clone()).expect("options deserialize");
+    assert_eq!(
+        serde_json::to_value(&restored).expect("options serialize"),
+        value
+    );
+}
+
+#[test]
+fn deserialized_options_drive_the_pipeline() {
+    let opts: Options = serde_json::from_value(json!({
+        "ellipsis": true,
+        "block_exclusions": { "ellipsis": ["blockquote"] },
+    }))
+    .expect("valid options");
+
+    assert_eq!(
+        process_stream_opts(&["Wait...".to_string(), "> Quoted...".to_string()], opts),
+        lines_vec!["Wait…", "> Quoted..."]
+    );
+}
+
+#[test]
+fn rejects_unknown_fields_and_invalid_patterns() {
+    let unknown = serde_json::from_value::<Options>(json!({ "wrapp": true }))
+        .err()
+        .expect("unknown field is rejected");
+    assert!(unknown.to_string().contains("wrapp"), "{unknown}");
+
+    let pattern = serde_json::from_value::<Options>(json!({ "opaque_tokens": ["("] }))
+        .err()
+        .expect("invalid pattern is rejected");
+    assert!(
+        pattern.to_string().contains("regex parse error"),
+        "{pattern}"
+    );
+
+    assert!(
+        serde_json::from_value::<Options>(json!({
+            "block_exclusions": { "ellipsis": ["table"] },
+        }))
+        .is_err()
+    );
+}
@@ -4,12 +4,14 @@ use assert_cmd::{Command, assert::Assert};
 
 /// Run the `mdtablefix` binary with the provided arguments and standard input.
 ///
+/// `RUST_LOG` is cleared so the tool's log output depends only on `args`.
 /// Returns an [`Assert`] handle for chaining output and status checks.
 pub fn run_cli_with_stdin(
     args: &[&str],
     input: &str,
 ) -> Result<Assert, Box<dyn std::error::Error>> {
     Ok(Command::cargo_bin("mdtablefix")?
+        .env_remove("RUST_LOG")
         .args(args)
         .write_stdin(input)
         .assert())
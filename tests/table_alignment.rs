@@ -0,0 +1,60 @@
+//! Integration tests for the `--align` flag.
+//!
+//! Verifies that the chosen alignments replace each table's own separator
+//! colons, that empty entries keep them, and that the result is stable.
+
+use mdtablefix::{Options, process_stream_opts, table::Alignment};
+use rstest::rstest;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const INPUT: &str = "|a|b|c|\n|-|:-|-|\n|1|2|3|\n";
+
+#[rstest]
+#[case::all_columns(
+    "l,c,r",
+    "| a   | b   | c   |\n| :-- | :-: | --: |\n| 1   | 2   | 3   |\n"
+)]
+#[case::empty_entry_keeps(
+    "r,,c",
+    "| a   | b   | c   |\n| --: | :-- | :-: |\n| 1   | 2   | 3   |\n"
+)]
+#[case::clears_colons(
+    "-,-",
+    "| a   | b   | c   |\n| --- | --- | --- |\n| 1   | 2   | 3   |\n"
+)]
+fn cli_sets_column_alignments(
+    #[case] spec: &str,
+    #[case] expected: &'static str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--align", spec], INPUT)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn cli_rejects_unknown_alignments() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--align", "l,middle"], INPUT)?
+        .failure()
+        .stderr(predicates::str::contains("unknown alignment `middle`"));
+    Ok(())
+}
+
+#[test]
+fn aligns_clean_tables_too() {
+    let lines: Vec<String> = ["| a   | b   |", "| --- | --- |", "| 1   | 2   |"]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let opts = || Options {
+        keep_clean_tables: true,
+        table_alignment: vec![Some(Alignment::Right), Some(Alignment::Center)],
+        ..Options::default()
+    };
+    let out = process_stream_opts(&lines, opts());
+    assert_eq!(out, ["| a   | b   |", "| --: | :-: |", "| 1   | 2   |"]);
+    assert_eq!(process_stream_opts(&out, opts()), out);
+}
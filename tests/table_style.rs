@@ -0,0 +1,67 @@
+//! Integration tests for the `--table-style` flag.
+//!
+//! Verifies that `edges-only` lines up only the outer pipes, that `compact`
+//! pads nothing, that both keep alignment colons, and that both are stable
+//! when run twice.
+
+use mdtablefix::table::{TableStyle, reflow_table_with_style};
+use rstest::rstest;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const INPUT: &str = "|a|bb|\n|:-|-:|\n|ccc|d \\| e|\n";
+
+const EDGES_ONLY: &str = "| a | bb       |\n| :-- | -----: |\n| ccc | d \\| e |\n";
+
+const COMPACT: &str = "| a | bb |\n| :-- | --: |\n| ccc | d \\| e |\n";
+
+#[rstest]
+#[case(&[], "| a   | bb     |\n| :-- | -----: |\n| ccc | d \\| e |\n")]
+#[case(&["--table-style", "padded"], "| a   | bb     |\n| :-- | -----: |\n| ccc | d \\| e |\n")]
+#[case(&["--table-style", "edges-only"], EDGES_ONLY)]
+#[case(&["--table-style", "compact"], COMPACT)]
+fn cli_applies_table_style(
+    #[case] args: &[&str],
+    #[case] expected: &'static str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(args, INPUT)?.success().stdout(expected);
+    Ok(())
+}
+
+#[rstest]
+#[case(EDGES_ONLY, TableStyle::EdgesOnly)]
+#[case(COMPACT, TableStyle::Compact)]
+fn unpadded_styles_are_idempotent(#[case] output: &str, #[case] style: TableStyle) {
+    let once: Vec<String> = output.lines().map(str::to_string).collect();
+    assert_eq!(reflow_table_with_style(&once, style), once);
+}
+
+#[test]
+fn edges_only_widens_separator_for_short_rows() {
+    let lines: Vec<String> = ["  |a|b|", "  |---|---|"]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    assert_eq!(
+        reflow_table_with_style(&lines, TableStyle::EdgesOnly),
+        vec!["  | a | b     |", "  | --- | --- |"]
+    );
+}
+
+#[test]
+fn compact_keeps_indent_and_empty_cells() {
+    let lines: Vec<String> = ["  |a|b|c|", "  |---|---|---|", "  |long cell||x|"]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    assert_eq!(
+        reflow_table_with_style(&lines, TableStyle::Compact),
+        vec![
+            "  | a | b | c |",
+            "  | --- | --- | --- |",
+            "  | long cell |  | x |"
+        ]
+    );
+}
@@ -1,8 +1,11 @@
-//! Compile-pass fixture: the public `BlockquotePrefix`, `FenceTracker`, and
+//! Compile-pass fixture: the public `BlockquotePrefix`, `FenceState`, and
 //! `is_fence` API introduced for semantic blockquote handling stays usable by
 //! downstream callers, with its documented depth-aware semantics intact.
 
-use mdtablefix::wrap::{BlockquotePrefix, FenceTracker, is_fence};
+use mdtablefix::{
+    fences::{FenceState, is_fence},
+    wrap::BlockquotePrefix,
+};
 
 fn main() {
     // `BlockquotePrefix` borrows the source line, exposing the raw prefix
@@ -14,8 +17,8 @@ fn main() {
     assert_eq!(prefix.inner(), "quoted text");
     assert!(BlockquotePrefix::parse("no prefix here").is_none());
 
-    // `FenceTracker` is the depth-aware authority for fenced code-block state.
-    let mut tracker = FenceTracker::new();
+    // `FenceState` is the depth-aware authority for fenced code-block state.
+    let mut tracker = FenceState::new();
     assert!(!tracker.in_fence(2));
     assert!(tracker.observe_line("> > ```rust"));
     assert!(tracker.in_fence(2));
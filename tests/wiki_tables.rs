@@ -0,0 +1,31 @@
+//! Integration tests for the `--wiki-tables` flag.
+//!
+//! Verifies that Jira and Confluence tables pasted into Markdown become
+//! aligned pipe tables only when the flag is given.
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const WIKI: &str = "Release notes:\n\n||Key||Summary||Owner||\n|ABC-1|Fix [login|https://example.com/1]|{{ops|infra}}|\n|ABC-2|Tidy docs|\n";
+
+#[test]
+fn cli_converts_wiki_tables() -> Result<(), Box<dyn std::error::Error>> {
+    let expected = concat!(
+        "Release notes:\n\n",
+        "| Key   | Summary                            | Owner        |\n",
+        "| ----- | ---------------------------------- | ------------ |\n",
+        "| ABC-1 | Fix [login](https://example.com/1) | `ops\\|infra` |\n",
+        "| ABC-2 | Tidy docs                          |              |\n",
+    );
+    run_cli_with_stdin(&["--wiki-tables"], WIKI)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn cli_leaves_wiki_tables_without_flag() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&[], WIKI)?.success().stdout(WIKI);
+    Ok(())
+}
@@ -1,6 +1,9 @@
 //! Property tests for depth-aware blockquote wrapping and fenced code state.
 
-use mdtablefix::wrap::{BlockquotePrefix, FenceTracker, wrap_text};
+use mdtablefix::{
+    fences::FenceState,
+    wrap::{BlockquotePrefix, wrap_text},
+};
 use proptest::prelude::*;
 use unicode_width::UnicodeWidthStr;
 
@@ -22,7 +25,7 @@ proptest! {
     ) {
         let fence = marker.to_string().repeat(marker_len);
         let opening_fence = format!("{fence}rust");
-        let mut tracker = FenceTracker::new();
+        let mut tracker = FenceState::new();
 
         prop_assert!(tracker.observe(&opening_fence, depth));
         prop_assert!(!tracker.observe(&inner, depth + nested_depth_offset));
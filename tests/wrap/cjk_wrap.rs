@@ -0,0 +1,53 @@
+//! Tests for the `cjk_wrap` policy applied to CJK-dominant paragraphs.
+
+use mdtablefix::{CjkWrap, wrap_text_cjk};
+use rstest::rstest;
+
+use super::*;
+
+const WORDS: &str = "汉字 汉字 汉字 汉字 汉字 汉字 汉字 汉字";
+
+#[rstest]
+#[case::cells(CjkWrap::Cells, lines_vec!["汉字 汉字 汉字 汉字", "汉字 汉字 汉字 汉字"])]
+#[case::chars(CjkWrap::Chars, lines_vec!["汉字 汉字 汉字 汉字 汉字 汉字", "汉字 汉字"])]
+#[case::never(CjkWrap::Never, lines_vec![WORDS])]
+fn cjk_paragraphs_follow_policy(#[case] policy: CjkWrap, #[case] expected: Vec<String>) {
+    assert_eq!(wrap_text_cjk(&[WORDS.to_string()], 20, policy), expected);
+}
+
+#[test]
+fn never_keeps_cjk_line_breaks_but_wraps_other_paragraphs() {
+    let input = lines_vec![
+        "这是第一行。",
+        "这是第二行。",
+        "",
+        "An English paragraph that is long enough",
+        "to be joined.",
+        "",
+        "- 列表项目 汉字 汉字 汉字 汉字 汉字",
+    ];
+    assert_eq!(
+        wrap_text_cjk(&input, 40, CjkWrap::Never),
+        lines_vec![
+            "这是第一行。",
+            "这是第二行。",
+            "",
+            "An English paragraph that is long",
+            "enough to be joined.",
+            "",
+            "- 列表项目 汉字 汉字 汉字 汉字 汉字",
+        ]
+    );
+}
+
+#[test]
+fn cli_accepts_cjk_wrap_policy() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "日本語の段落です。\n次の行です。\n";
+    cli_stdin::run_cli_with_stdin(&["--wrap", "--cjk-wrap", "never"], input)?
+        .success()
+        .stdout(input);
+    cli_stdin::run_cli_with_stdin(&["--wrap"], input)?
+        .success()
+        .stdout("日本語の段落です。 次の行です。\n");
+    Ok(())
+}
@@ -6,7 +6,7 @@
 //! links instead of being orphaned during wrapping.
 
 use insta::assert_snapshot;
-use mdtablefix::process::WRAP_COLS;
+use mdtablefix::{process::WRAP_COLS, wrap::wrap_text};
 use rstest::rstest;
 
 use super::*;
@@ -252,6 +252,25 @@ fn test_wrap_link_at_exact_wrap_boundary_is_not_split() {
     );
 }
 
+/// Ensures that punctuation and emphasis markers after reference links and
+/// emphasised links never open a wrapped line, whatever the width.
+#[rstest]
+#[case("Read the [guide][docs]). Then continue with the rest.")]
+#[case("Read the ![logo][img]), then continue with the rest.")]
+#[case("Read the (*[guide](docs.md)*). Then continue with the rest.")]
+#[case("Read the **[guide](docs.md)**, then continue with the rest.")]
+fn test_wrap_keeps_punctuation_cluster_after_link(#[case] input_line: &str) {
+    for width in 10..=60 {
+        let output = wrap_text(&[input_line.to_string()], width);
+        assert!(
+            !output
+                .iter()
+                .any(|l| l.trim_start_matches('*').starts_with([')', '.', ','])),
+            "punctuation led a wrapped line at width {width}: {output:?}",
+        );
+    }
+}
+
 #[rstest]
 #[case(
     "long_link_trailing_period",
@@ -13,6 +13,7 @@ mod blockquote_properties;
 mod blockquote_snapshots;
 mod blockquotes;
 mod checklist_code_spans;
+mod cjk_wrap;
 mod cli;
 mod cli_files;
 mod cli_issue_329_property;
@@ -29,6 +30,7 @@ mod links;
 mod lists;
 mod paragraphs;
 mod spanning_code_spans;
+mod summary_lines;
 mod tokenize_markdown;
 #[path = "../support/wrap_assertions.rs"]
 mod wrap_assertions;
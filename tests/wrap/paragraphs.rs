@@ -3,6 +3,7 @@
 //! Validates text wrapping behaviour for paragraph content, including handling
 //! of long words that exceed the 80-column limit and cannot be broken.
 
+use mdtablefix::wrap::wrap_text;
 use rstest::rstest;
 
 use super::*;
@@ -220,3 +221,15 @@ fn test_wrap_keeps_inline_code_leading_hyphen_on_same_line(#[case] snippet: &str
         "expected compound {snippet:?} preserved on a single line: {output:?}"
     );
 }
+
+#[rstest]
+#[case::single("aaaa bbbb cccc dddd # eeee ffff", "aaaa bbbb cccc dddd #\neeee ffff")]
+#[case::repeated("aaaa bbbb cccc dddd ## #", "aaaa bbbb cccc dddd ## #")]
+#[case::hashtag("aaaa bbbb cccc dddd #eeee ffff", "aaaa bbbb cccc dddd\n#eeee ffff")]
+fn test_wrap_never_starts_a_line_with_a_heading_marker(
+    #[case] input: &str,
+    #[case] expected: &str,
+) {
+    let output = wrap_text(&[input.to_string()], 20);
+    assert_eq!(output, expected.lines().collect::<Vec<_>>());
+}
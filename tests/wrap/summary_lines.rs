@@ -0,0 +1,38 @@
+//! Tests for `<summary>` one-liners inside wrapped content.
+
+use rstest::rstest;
+
+use super::*;
+
+const LONG_SUMMARY: &str = "<summary>Install the **optional** `cli` extras and configure the \
+                            daemon before first use</summary>";
+
+#[rstest]
+#[case::paragraph(lines_vec!["<details>", LONG_SUMMARY, "", "Body.", "", "</details>"])]
+#[case::list_item(lines_vec!["- item", &format!("  {LONG_SUMMARY}")])]
+#[case::blockquote(lines_vec!["> Quoted text.", &format!("> {LONG_SUMMARY}")])]
+#[case::details_prefix(lines_vec![&format!("<details open>{LONG_SUMMARY}")])]
+fn summary_lines_are_never_wrapped_or_joined(#[case] input: Vec<String>) {
+    assert_eq!(process_stream(&input), input);
+}
+
+#[test]
+fn multi_line_summary_is_wrapped_as_prose() {
+    let input = lines_vec!["<summary>Install the optional cli extras", "now</summary>"];
+    assert_eq!(
+        process_stream(&input),
+        lines_vec!["<summary>Install the optional cli extras now</summary>"]
+    );
+}
+
+#[test]
+fn cli_processes_markdown_inside_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let input = "<details>\n<summary>Run ` cargo test ` and wait... then read the long report it \
+                 prints</summary>\n</details>\n";
+    let expected = "<details>\n<summary>Run `cargo test` and wait… then read the long report it \
+                    prints</summary>\n</details>\n";
+    cli_stdin::run_cli_with_stdin(&["--wrap", "--ellipsis", "--code-spans"], input)?
+        .success()
+        .stdout(expected);
+    Ok(())
+}
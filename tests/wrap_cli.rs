@@ -10,16 +10,16 @@ fn run_wrap_in_place_and_read_back(input: &str) -> Result<String, Box<dyn std::e
     let temp = NamedTempFile::new()?;
     fs::write(temp.path(), input)?;
 
-    let mut command = Command::cargo_bin("mdtablefix")?;
-    command
+    let assert = Command::cargo_bin("mdtablefix")?
         .args(["--wrap", "--in-place"])
         .arg(temp.path())
         .assert()
-        .success()
         .stdout("")
         .stderr("");
 
-    Ok(fs::read_to_string(temp.path())?)
+    let output = fs::read_to_string(temp.path())?;
+    assert.code(i32::from(output != input));
+    Ok(output)
 }
 
 /// Guards issue #261 by asserting `--wrap --in-place` leaves shell code blocks
@@ -82,7 +82,7 @@ fn cli_wrap_in_place_preserves_fenced_block_without_final_newline()
         "make lint 2>&1 | tee /tmp/lint.log\n",
         "make test 2>&1 | tee /tmp/test.log\n",
         "```\n",
-        "Trailing paragraph without final newline\n",
+        "Trailing paragraph without final newline",
     );
 
     let actual = run_wrap_in_place_and_read_back(input)?;
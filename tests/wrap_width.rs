@@ -0,0 +1,54 @@
+//! Integration tests for `--wrap-width` and `Options::wrap_width`.
+
+use mdtablefix::{Options, process::WRAP_COLS, process_stream_opts};
+use rstest::rstest;
+
+#[path = "support/cli_stdin.rs"]
+mod cli_stdin;
+use cli_stdin::run_cli_with_stdin;
+
+const PARAGRAPH: &str = "Projects that follow a one hundred column convention want their prose \
+                         wrapped at that width instead of the default.";
+
+#[rstest]
+#[case::default(
+    &["--wrap"],
+    "Projects that follow a one hundred column convention want their prose wrapped\nat that \
+     width instead of the default.\n"
+)]
+#[case::wider(
+    &["--wrap", "--wrap-width", "100"],
+    "Projects that follow a one hundred column convention want their prose wrapped at that width \
+     instead\nof the default.\n"
+)]
+#[case::without_wrap(&["--wrap-width", "20"], &format!("{PARAGRAPH}\n"))]
+fn cli_wraps_at_the_requested_width(
+    #[case] args: &[&str],
+    #[case] expected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(args, &format!("{PARAGRAPH}\n"))?
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn cli_rejects_zero_width() -> Result<(), Box<dyn std::error::Error>> {
+    run_cli_with_stdin(&["--wrap", "--wrap-width", "0"], PARAGRAPH)?
+        .failure()
+        .code(2);
+    Ok(())
+}
+
+#[test]
+fn options_default_to_eighty_columns() {
+    assert_eq!(Options::default().wrap_width, WRAP_COLS);
+    let opts = Options {
+        wrap: true,
+        wrap_width: 40,
+        ..Options::default()
+    };
+    let out = process_stream_opts(&[PARAGRAPH.to_string()], opts);
+    assert!(out.len() > 2);
+    assert!(out.iter().all(|line| line.len() <= 40), "{out:?}");
+}